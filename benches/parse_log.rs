@@ -0,0 +1,116 @@
+//! `svn log --xml` 解析的耗时和内存峰值：整个文档建立 DOM、读入全部输出后逐条解析，
+//! 以及从输出流中边读边解析（同步时读取 `svn log` 子进程输出的方式）
+//!
+//! 运行：`cargo bench --features fuzzing --bench parse_log`
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fs::File,
+    hint::black_box,
+    io::BufReader,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+use roxmltree::Document;
+use svn2git::{SvnLogReader, fuzzing::parse_svn_log_xml};
+
+/// 统计当前和峰值堆内存的分配器
+struct CountingAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// 生成 `entries` 条日志、每条 `paths` 个变更路径的详细日志
+fn verbose_log(entries: usize, paths: usize) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<log>\n");
+    for rev in 1..=entries {
+        xml.push_str(&format!(
+            "<logentry revision=\"{rev}\">\n<author>dev</author>\n<date>2024-01-01T00:00:00.000000Z</date>\n<paths>\n"
+        ));
+        for i in 0..paths {
+            xml.push_str(&format!(
+                "<path action=\"M\" prop-mods=\"false\" text-mods=\"true\" kind=\"file\">/trunk/src/module{i}/file{rev}.rs</path>\n"
+            ));
+        }
+        xml.push_str(&format!(
+            "</paths>\n<msg>修改 r{rev} &amp; 测试</msg>\n</logentry>\n"
+        ));
+    }
+    xml.push_str("</log>\n");
+    xml
+}
+
+/// 改动前的实现：整个输出建立一个 DOM
+fn parse_whole_document(xml: &[u8]) -> usize {
+    let doc = Document::parse(std::str::from_utf8(xml).unwrap()).unwrap();
+    doc.root_element()
+        .children()
+        .filter(|n| n.is_element() && n.tag_name().name() == "logentry")
+        .map(|entry| {
+            entry
+                .descendants()
+                .filter(|n| n.is_element() && n.tag_name().name() == "path")
+                .count()
+        })
+        .sum()
+}
+
+/// 测量解析的用时和解析期间新分配内存的峰值（不含已在内存中的输入）
+fn measure(name: &str, parse: impl FnOnce() -> usize) {
+    let base = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+    let started = Instant::now();
+    let count = black_box(parse());
+    let elapsed = started.elapsed();
+    let peak = PEAK.load(Ordering::Relaxed) - base;
+    println!(
+        "{name:<12} 路径 {count:>8}  用时 {:>8.1} ms  额外内存峰值 {:>8.1} MB",
+        elapsed.as_secs_f64() * 1000.0,
+        peak as f64 / 1024.0 / 1024.0
+    );
+}
+
+fn main() {
+    let xml = verbose_log(20_000, 50);
+    println!("日志大小 {:.1} MB", xml.len() as f64 / 1024.0 / 1024.0);
+    measure("整个文档", || parse_whole_document(xml.as_bytes()));
+    measure("逐条解析", || {
+        parse_svn_log_xml(xml.as_bytes())
+            .unwrap()
+            .iter()
+            .map(|log| log.paths.len())
+            .sum()
+    });
+
+    // 输出写入文件后从文件读取，相当于读取子进程的标准输出，输出本身不在内存中
+    let path = std::env::temp_dir().join(format!("svn2git-bench-{}.xml", std::process::id()));
+    std::fs::write(&path, &xml).unwrap();
+    drop(xml);
+    measure("边读边解析", || {
+        let reader = BufReader::new(File::open(&path).unwrap());
+        SvnLogReader::new(reader, None)
+            .map(|log| log.unwrap().paths.len())
+            .sum()
+    });
+    std::fs::remove_file(&path).unwrap();
+}
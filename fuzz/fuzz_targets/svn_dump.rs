@@ -0,0 +1,10 @@
+//! `svnadmin dump` 解析和重放：任意输入只能返回错误，不能 panic
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use svn2git::fuzzing::replay_svn_dump;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = replay_svn_dump(data);
+});
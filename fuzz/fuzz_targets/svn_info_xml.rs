@@ -0,0 +1,13 @@
+//! `svn info --xml` 和 `svn status --xml` 解析：任意输入只能返回错误，不能 panic
+
+#![no_main]
+
+use std::path::Path;
+
+use libfuzzer_sys::fuzz_target;
+use svn2git::fuzzing::{parse_svn_info_xml, parse_svn_status_xml};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_svn_info_xml(data);
+    let _ = parse_svn_status_xml(data, Path::new("/svn/wc"));
+});
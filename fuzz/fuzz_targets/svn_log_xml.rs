@@ -0,0 +1,10 @@
+//! `svn log --xml` 解析：任意输入只能返回错误，不能 panic
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use svn2git::fuzzing::parse_svn_log_xml;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_svn_log_xml(data);
+});
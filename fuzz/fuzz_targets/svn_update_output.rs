@@ -0,0 +1,10 @@
+//! `svn update` 输出中的冲突解析：任意文本都不能 panic
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use svn2git::fuzzing::parse_update_conflicts;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_update_conflicts(&String::from_utf8_lossy(data));
+});
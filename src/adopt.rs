@@ -0,0 +1,211 @@
+//! 接管已转换的 Git 仓库：从仓库中的镜像标记和 SVN 版本记录重建同步对的历史记录
+
+use std::path::Path;
+
+use crate::{
+    config::{FileStorage, HistoryManager, MirrorMarker, RepoIdentity, STATE_REF},
+    error::{Result, SyncError},
+    ops::{GitCommitEntry, GitOperations},
+    sync::{SVN_NOTES_REF, parse_git_svn_id},
+};
+
+/// 接管结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AdoptSummary {
+    /// 从提交中找到的 SVN 版本数
+    pub mapped: usize,
+    /// 恢复的同步进度
+    pub last_synced_rev: Option<String>,
+    /// 镜像标记，仓库中没有标记时为 `None`
+    pub marker: Option<MirrorMarker>,
+}
+
+/// 从已转换的 Git 仓库重建同步对的历史记录，以便在新机器上继续同步
+///
+/// 版本与提交的对应关系来自提交的 `SVN-Revision:` 记录（note 或提交说明）或 `git-svn-id` 记录，
+/// 同步进度和 SVN 仓库 UUID 来自 `refs/svn2git/state` 镜像标记；没有标记时同步进度取最后一个有记录的版本，
+/// 仓库 UUID 取最后一个 `git-svn-id` 中的 UUID。
+/// 同步对已有同步进度时拒绝接管，避免覆盖。
+///
+/// # 参数
+///
+/// * `history`: 历史记录
+/// * `svn_dir`: 继续同步使用的 SVN 工作副本目录
+/// * `git_dir`: 已转换的 Git 仓库目录
+/// * `git`: Git 操作
+///
+/// # 返回
+///
+/// * `Err(SyncError)` - 不是 Git 仓库、没有提交、同步对已有同步进度，或仓库中找不到任何 SVN 版本记录
+pub fn adopt_repository<S: FileStorage>(
+    history: &mut HistoryManager<S>,
+    svn_dir: &Path,
+    git_dir: &Path,
+    git: &dyn GitOperations,
+) -> Result<AdoptSummary> {
+    if !git.is_repository(git_dir)? {
+        return Err(SyncError::NotGitRepository(git_dir.to_path_buf()));
+    }
+    let (svn_path, git_path) = (svn_dir.to_path_buf(), git_dir.to_path_buf());
+    if let Some(record) = history.find_record(&svn_path, &git_path)
+        && (record.last_synced_rev().is_some() || !record.revision_map().is_empty())
+    {
+        return Err(SyncError::App(format!(
+            "同步对 {} 已有同步进度，无需接管",
+            record.label()
+        )));
+    }
+    let head = git
+        .rev_parse_head(git_dir)
+        .map_err(|_| SyncError::App(format!("Git 仓库 {} 还没有任何提交", git_dir.display())))?;
+
+    let marker = git
+        .read_blob_ref(git_dir, STATE_REF)?
+        .map(|text| MirrorMarker::parse(&text))
+        .transpose()?;
+    let history_entries = git.commit_history(git_dir, SVN_NOTES_REF)?;
+    let mapped: Vec<(String, String)> = history_entries
+        .iter()
+        .filter_map(|entry| svn_revision(entry).map(|rev| (rev, entry.hash.clone())))
+        .collect();
+    let last_synced_rev = marker
+        .as_ref()
+        .map(|marker| marker.last_synced_rev.clone())
+        .or_else(|| mapped.last().map(|(rev, _)| rev.clone()));
+    let Some(last_synced_rev) = last_synced_rev else {
+        return Err(SyncError::App(format!(
+            "Git 仓库 {} 中既没有 {STATE_REF} 镜像标记，也没有带 SVN-Revision 记录的提交，无法确定同步进度",
+            git_dir.display()
+        )));
+    };
+
+    history.add_record(svn_path.clone(), git_path.clone());
+    for (rev, hash) in &mapped {
+        history.update_sync_state(&svn_path, &git_path, rev, hash);
+    }
+    if mapped.last().map(|(rev, _)| rev) != Some(&last_synced_rev) {
+        history.update_sync_state(&svn_path, &git_path, &last_synced_rev, &head);
+    }
+    let svn_uuid = marker
+        .as_ref()
+        .and_then(|marker| marker.svn_uuid.clone())
+        .or_else(|| {
+            history_entries
+                .iter()
+                .rev()
+                .find_map(|entry| parse_git_svn_id(&entry.message))
+                .map(|(_, _, uuid)| uuid.to_string())
+        });
+    if let Some(svn_uuid) = svn_uuid {
+        let identity = RepoIdentity {
+            svn_uuid,
+            git_root: Some(git.root_commit(git_dir)?),
+        };
+        history.set_identity(&svn_path, &git_path, identity);
+    }
+    history.save()?;
+
+    Ok(AdoptSummary {
+        mapped: mapped.len(),
+        last_synced_rev: Some(last_synced_rev),
+        marker,
+    })
+}
+
+/// 提交对应的 SVN 版本，先查 note，再查提交说明中的记录和 git-svn-id
+fn svn_revision(entry: &GitCommitEntry) -> Option<String> {
+    [entry.note.as_deref(), Some(entry.message.as_str())]
+        .into_iter()
+        .flatten()
+        .find_map(|text| {
+            text.lines()
+                .find_map(|line| line.trim().strip_prefix("SVN-Revision:"))
+                .map(|rev| rev.trim().to_string())
+                .filter(|rev| !rev.is_empty())
+        })
+        .or_else(|| parse_git_svn_id(&entry.message).map(|(_, rev, _)| rev.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::{config::MemoryStorage, ops::MockGitOperations};
+
+    fn repo_with_commits(git: &MockGitOperations, git_dir: &Path) {
+        git.init(git_dir).unwrap();
+        for (rev, message) in [("1", "SVN: m1"), ("2", "SVN: m2")] {
+            git.add_file_to_mock(git_dir, &format!("r{rev}.txt"))
+                .unwrap();
+            git.add_all(git_dir).unwrap();
+            git.commit(git_dir, message).unwrap();
+            git.add_note(
+                git_dir,
+                SVN_NOTES_REF,
+                "HEAD",
+                &format!("SVN-Revision: {rev}\n"),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_adopt_repository_should_rebuild_revision_map_and_progress() {
+        let (svn_dir, git_dir) = (PathBuf::from("svn"), PathBuf::from("git"));
+        let git = MockGitOperations::new();
+        repo_with_commits(&git, &git_dir);
+        let marker = MirrorMarker::new(4, Some("uuid-a".into()), "3");
+        git.write_blob_ref(&git_dir, STATE_REF, &marker.render())
+            .unwrap();
+
+        let mut history = HistoryManager::new(MemoryStorage::new()).unwrap();
+        let summary = adopt_repository(&mut history, &svn_dir, &git_dir, &git).unwrap();
+        assert_eq!(summary.mapped, 2);
+        assert_eq!(summary.last_synced_rev.as_deref(), Some("3"));
+
+        let record = history.find_record(&svn_dir, &git_dir).unwrap();
+        assert_eq!(record.last_synced_rev(), Some("3"));
+        assert_eq!(record.revision_map().git_hash("1"), Some("commit1"));
+        assert_eq!(record.revision_map().git_hash("2"), Some("commit2"));
+        assert_eq!(record.identity().unwrap().svn_uuid, "uuid-a");
+
+        // 已有同步进度时拒绝再次接管
+        let err = adopt_repository(&mut history, &svn_dir, &git_dir, &git).unwrap_err();
+        assert!(err.to_string().contains("已有同步进度"), "{err}");
+    }
+
+    #[test]
+    fn test_adopt_repository_should_require_svn_records() {
+        let (svn_dir, git_dir) = (PathBuf::from("svn"), PathBuf::from("git"));
+        let git = MockGitOperations::new();
+        git.init(&git_dir).unwrap();
+        git.add_file_to_mock(&git_dir, "a.txt").unwrap();
+        git.add_all(&git_dir).unwrap();
+        git.commit(&git_dir, "unrelated").unwrap();
+
+        let mut history = HistoryManager::new(MemoryStorage::new()).unwrap();
+        assert!(adopt_repository(&mut history, &svn_dir, &git_dir, &git).is_err());
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn test_adopt_repository_should_read_git_svn_id() {
+        let (svn_dir, git_dir) = (PathBuf::from("svn"), PathBuf::from("git"));
+        let git = MockGitOperations::new();
+        git.init(&git_dir).unwrap();
+        git.add_file_to_mock(&git_dir, "a.txt").unwrap();
+        git.add_all(&git_dir).unwrap();
+        git.commit(
+            &git_dir,
+            "SVN: m7\n\ngit-svn-id: https://svn.example.com/repo/trunk@7 uuid-b",
+        )
+        .unwrap();
+
+        let mut history = HistoryManager::new(MemoryStorage::new()).unwrap();
+        let summary = adopt_repository(&mut history, &svn_dir, &git_dir, &git).unwrap();
+        assert_eq!(summary.last_synced_rev.as_deref(), Some("7"));
+        let record = history.find_record(&svn_dir, &git_dir).unwrap();
+        assert_eq!(record.identity().unwrap().svn_uuid, "uuid-b");
+    }
+}
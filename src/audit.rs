@@ -0,0 +1,441 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+};
+
+use crate::{
+    config::{HistoryRecord, RevisionMapping},
+    error::{Result, SyncError},
+    filter::PathFilter,
+    ops::{GitOperations, SvnOperations},
+};
+
+/// Git 仓库中由工具自身维护、SVN 中不存在的文件，审计时忽略
+const GIT_ONLY_FILES: &[&str] = &[".gitignore", ".gitattributes"];
+
+/// 每次交给一个工作线程计算哈希的文件数
+///
+/// 哈希结果约 41 字节一行，一批的输出不超过管道缓冲区，
+/// 同时限制了每个线程同时持有的路径和结果数量
+const HASH_BATCH_SIZE: usize = 512;
+
+/// 审计选项
+#[derive(Debug, Clone, Default)]
+pub struct AuditOptions {
+    /// 抽样检查的版本数量，不设置时检查全部已同步版本
+    pub sample: Option<usize>,
+    /// 只比较通过过滤的路径（用于校验只同步了部分目录的转换）
+    pub filter: PathFilter,
+    /// 并行计算文件哈希的线程数，不设置时使用 CPU 核数
+    pub jobs: Option<NonZeroUsize>,
+}
+
+/// 单个版本的审计结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct RevisionAudit {
+    /// SVN 版本
+    pub svn_rev: String,
+    /// 对应的 Git 提交
+    pub git_hash: String,
+    /// 只存在于 SVN 中的文件
+    pub only_in_svn: Vec<String>,
+    /// 只存在于 Git 中的文件
+    pub only_in_git: Vec<String>,
+    /// 内容不一致的文件
+    pub mismatched: Vec<String>,
+}
+
+impl RevisionAudit {
+    /// 该版本是否一致
+    pub fn passed(&self) -> bool {
+        self.only_in_svn.is_empty() && self.only_in_git.is_empty() && self.mismatched.is_empty()
+    }
+}
+
+/// 审计报告
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditReport {
+    /// 每个被检查版本的结果
+    pub revisions: Vec<RevisionAudit>,
+}
+
+impl AuditReport {
+    /// 是否全部一致
+    pub fn passed(&self) -> bool {
+        self.revisions.iter().all(RevisionAudit::passed)
+    }
+
+    /// 不一致的版本数量
+    pub fn failed_count(&self) -> usize {
+        self.revisions.iter().filter(|r| !r.passed()).count()
+    }
+
+    /// 打印报告
+    pub fn print(&self) {
+        for audit in &self.revisions {
+            if audit.passed() {
+                println!("[通过] r{} -> {}", audit.svn_rev, audit.git_hash);
+                continue;
+            }
+
+            println!("[失败] r{} -> {}", audit.svn_rev, audit.git_hash);
+            for file in &audit.only_in_svn {
+                println!("    仅在 SVN 中: {file}");
+            }
+            for file in &audit.only_in_git {
+                println!("    仅在 Git 中: {file}");
+            }
+            for file in &audit.mismatched {
+                println!("    内容不一致: {file}");
+            }
+        }
+        println!(
+            "共检查 {} 个版本，通过 {} 个，失败 {} 个",
+            self.revisions.len(),
+            self.revisions.len() - self.failed_count(),
+            self.failed_count()
+        );
+    }
+}
+
+/// 审计已完成的同步
+///
+/// 对版本映射中的（抽样）版本逐个导出 SVN 目录树，并与对应 Git 提交的目录树比较。
+/// 只读操作，不会修改 SVN 工作副本或 Git 仓库。
+///
+/// # 参数
+///
+/// * `record`: 要审计的同步对
+/// * `svn_ops`: SVN操作实现
+/// * `git_ops`: Git操作实现，文件哈希在多个线程中并行计算
+/// * `options`: 审计选项
+///
+/// # 返回
+///
+/// 审计报告
+pub fn audit_record(
+    record: &HistoryRecord,
+    svn_ops: &dyn SvnOperations,
+    git_ops: &(dyn GitOperations + Sync),
+    options: &AuditOptions,
+) -> Result<AuditReport> {
+    let map = record.revision_map();
+    if map.is_empty() {
+        return Err(SyncError::App("该同步对没有版本映射记录，无法审计".into()));
+    }
+
+    let mappings = match options.sample {
+        Some(count) => map.sample(count),
+        None => map.entries().iter().collect(),
+    };
+
+    let jobs = options
+        .jobs
+        .or_else(|| thread::available_parallelism().ok())
+        .map_or(1, NonZeroUsize::get);
+    let mut report = AuditReport::default();
+    for (idx, mapping) in mappings.iter().enumerate() {
+        println!(
+            "[{}/{}] 正在审计 SVN r{}",
+            idx + 1,
+            mappings.len(),
+            mapping.svn_rev
+        );
+        report.revisions.push(audit_revision(
+            record,
+            svn_ops,
+            git_ops,
+            mapping,
+            &options.filter,
+            jobs,
+        )?);
+    }
+    Ok(report)
+}
+
+fn audit_revision(
+    record: &HistoryRecord,
+    svn_ops: &dyn SvnOperations,
+    git_ops: &(dyn GitOperations + Sync),
+    mapping: &RevisionMapping,
+    filter: &PathFilter,
+    jobs: usize,
+) -> Result<RevisionAudit> {
+    let export_dir = std::env::temp_dir().join(format!(
+        "svn2git-audit-{}-r{}",
+        std::process::id(),
+        mapping.svn_rev
+    ));
+    let result = (|| {
+        svn_ops.export(record.svn_path(), &mapping.svn_rev, &export_dir)?;
+
+        // 稀疏工作副本只同步了已检出的目录，只比较这部分
+        let in_scope = |name: &str| {
+            filter.matches(name)
+                && record
+                    .sparse_scope()
+                    .is_none_or(|scope| scope.contains(name))
+        };
+        let files: Vec<_> = collect_files(&export_dir)?
+            .into_iter()
+            .filter(|(name, _)| in_scope(name))
+            .collect();
+        let paths: Vec<PathBuf> = files.iter().map(|(_, path)| path.clone()).collect();
+        let hashes = hash_files_parallel(git_ops, record.git_path(), &paths, jobs)?;
+        let svn_tree: BTreeMap<String, String> = files
+            .into_iter()
+            .map(|(name, _)| name)
+            .zip(hashes)
+            .collect();
+
+        let mut git_tree = git_ops.tree_blobs(record.git_path(), &mapping.git_hash)?;
+        git_tree.retain(|name, _| in_scope(name));
+        Ok(compare_trees(mapping, &svn_tree, &git_tree))
+    })();
+
+    let _ = fs::remove_dir_all(&export_dir);
+    result
+}
+
+/// 分批并行计算文件的 Git blob 哈希
+///
+/// 工作线程依次领取下一批文件，结果按输入顺序返回。
+/// 任一批失败时其余线程不再领取新的批次
+///
+/// # 参数
+///
+/// * `git_ops`: Git操作实现
+/// * `repo`: Git 仓库路径（用于应用 `.gitattributes`）
+/// * `files`: 要计算哈希的文件
+/// * `jobs`: 线程数
+fn hash_files_parallel(
+    git_ops: &(dyn GitOperations + Sync),
+    repo: &Path,
+    files: &[PathBuf],
+    jobs: usize,
+) -> Result<Vec<String>> {
+    let batches: Vec<&[PathBuf]> = files.chunks(HASH_BATCH_SIZE).collect();
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<Vec<String>>>>> =
+        Mutex::new(batches.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, batches.len().max(1)) {
+            scope.spawn(|| {
+                loop {
+                    let idx = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(batch) = batches.get(idx) else {
+                        break;
+                    };
+                    let result = git_ops.hash_files(repo, batch);
+                    let failed = result.is_err();
+                    results.lock().unwrap_or_else(|e| e.into_inner())[idx] = Some(result);
+                    if failed {
+                        next.store(batches.len(), Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+    });
+
+    let mut hashes = Vec::with_capacity(files.len());
+    for result in results.into_inner().unwrap_or_else(|e| e.into_inner()) {
+        match result {
+            Some(batch) => hashes.extend(batch?),
+            None => break,
+        }
+    }
+    if hashes.len() != files.len() {
+        return Err(SyncError::App(format!(
+            "计算文件哈希失败：期望 {} 个结果，实际 {} 个",
+            files.len(),
+            hashes.len()
+        )));
+    }
+    Ok(hashes)
+}
+
+/// 递归收集目录下的所有文件
+///
+/// # 返回
+///
+/// (以 `/` 分隔的相对路径, 绝对路径) 列表
+fn collect_files(root: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+            let relative = path
+                .strip_prefix(root)
+                .map_err(|e| SyncError::App(e.to_string()))?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((relative, path));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// 比较 SVN 与 Git 目录树（相对路径 -> blob 哈希）
+fn compare_trees(
+    mapping: &RevisionMapping,
+    svn_tree: &BTreeMap<String, String>,
+    git_tree: &BTreeMap<String, String>,
+) -> RevisionAudit {
+    let mut audit = RevisionAudit {
+        svn_rev: mapping.svn_rev.clone(),
+        git_hash: mapping.git_hash.clone(),
+        only_in_svn: Vec::new(),
+        only_in_git: Vec::new(),
+        mismatched: Vec::new(),
+    };
+
+    for (file, svn_hash) in svn_tree {
+        match git_tree.get(file) {
+            Some(git_hash) if git_hash == svn_hash => {}
+            Some(_) => audit.mismatched.push(file.clone()),
+            None => audit.only_in_svn.push(file.clone()),
+        }
+    }
+    audit.only_in_git = git_tree
+        .keys()
+        .filter(|file| !svn_tree.contains_key(*file) && !GIT_ONLY_FILES.contains(&file.as_str()))
+        .cloned()
+        .collect();
+
+    audit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::{MockGitOperations, MockSvnOperations};
+
+    fn tree(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn mapping() -> RevisionMapping {
+        RevisionMapping {
+            svn_rev: "3".into(),
+            git_hash: "abc".into(),
+        }
+    }
+
+    #[test]
+    fn test_compare_trees() {
+        let svn = tree(&[("a.txt", "1"), ("b.txt", "2"), ("c.txt", "3")]);
+        let git = tree(&[
+            ("a.txt", "1"),
+            ("b.txt", "x"),
+            ("d.txt", "4"),
+            (".gitignore", "5"),
+        ]);
+
+        let audit = compare_trees(&mapping(), &svn, &git);
+        assert!(!audit.passed());
+        assert_eq!(audit.mismatched, vec!["b.txt"]);
+        assert_eq!(audit.only_in_svn, vec!["c.txt"]);
+        assert_eq!(audit.only_in_git, vec!["d.txt"]);
+
+        let audit = compare_trees(&mapping(), &svn, &svn);
+        assert!(audit.passed());
+    }
+
+    #[test]
+    fn test_audit_record_with_mock_operations() {
+        let dir = tempfile::tempdir().unwrap();
+        let svn_dir = dir.path().join("svn");
+        let git_dir = dir.path().join("git");
+
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_log_to_mock(&svn_dir, "1", "初始提交");
+        svn_ops.add_export_file("1", "src/main.rs", "fn main() {}");
+        svn_ops.add_export_file("1", "README", "readme");
+
+        let git_ops = MockGitOperations::new();
+        git_ops.init(&git_dir).unwrap();
+        git_ops.add_file_to_mock(&git_dir, "src/main.rs").unwrap();
+        git_ops.add_all(&git_dir).unwrap();
+        git_ops.commit(&git_dir, "SVN: 初始提交").unwrap();
+        let head = git_ops.rev_parse_head(&git_dir).unwrap();
+
+        let mut record = HistoryRecord::new(1, svn_dir, git_dir);
+        assert!(audit_record(&record, &svn_ops, &git_ops, &AuditOptions::default()).is_err());
+
+        record.set_sync_state("1", &head);
+        let report = audit_record(&record, &svn_ops, &git_ops, &AuditOptions::default()).unwrap();
+        assert_eq!(report.revisions.len(), 1);
+        assert_eq!(report.failed_count(), 1);
+        assert_eq!(report.revisions[0].only_in_svn, vec!["README"]);
+
+        // 只校验 src 子目录时，README 不应被报告
+        let options = AuditOptions {
+            filter: PathFilter::new(vec!["src".into()], vec![]),
+            ..Default::default()
+        };
+        let report = audit_record(&record, &svn_ops, &git_ops, &options).unwrap();
+        assert!(report.passed());
+    }
+
+    #[test]
+    fn test_collect_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("a/b")).unwrap();
+        fs::write(dir.path().join("a/b/c.txt"), "c").unwrap();
+        fs::write(dir.path().join("root.txt"), "r").unwrap();
+
+        let names: Vec<_> = collect_files(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, vec!["a/b/c.txt", "root.txt"]);
+    }
+
+    #[test]
+    fn test_hash_files_parallel_should_keep_input_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let files: Vec<PathBuf> = (0..HASH_BATCH_SIZE * 2 + 7)
+            .map(|i| {
+                let path = dir.path().join(format!("{i}.txt"));
+                fs::write(&path, i.to_string()).unwrap();
+                path
+            })
+            .collect();
+        let git_ops = crate::ops::Git2Operations::new();
+        git_ops.init(dir.path()).unwrap();
+
+        let sequential = git_ops.hash_files(dir.path(), &files).unwrap();
+        for jobs in [1, 4] {
+            let hashes = hash_files_parallel(&git_ops, dir.path(), &files, jobs).unwrap();
+            assert_eq!(hashes, sequential);
+        }
+        assert!(
+            hash_files_parallel(&git_ops, dir.path(), &[], 4)
+                .unwrap()
+                .is_empty()
+        );
+
+        let missing = vec![dir.path().join("missing.txt")];
+        assert!(hash_files_parallel(&git_ops, dir.path(), &missing, 4).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+//! SVN 作者到 Git 作者的映射
+
+use std::str::FromStr;
+
+use crate::{
+    error::{Result, SyncError},
+    ops::SvnLog,
+};
+
+/// 没有作者的 SVN 提交使用的名称（与 git-svn 一致）
+pub const SVN_NO_AUTHOR: &str = "(no author)";
+
+/// 作者回退规则的匹配条件
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuthorMatch {
+    /// 版本的所有变更路径都在该目录下
+    PathPrefix(String),
+    /// 提交说明包含该文本
+    MessageContains(String),
+}
+
+/// 作者回退规则
+///
+/// SVN 作者缺失或为共享的服务账号时，按变更路径或提交说明把版本归属到真实作者。
+/// 命令行格式为 `path:<目录>=<名称> <<邮箱>>` 或 `message:<文本>=<名称> <<邮箱>>`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthorRule {
+    /// 匹配条件
+    pub matcher: AuthorMatch,
+    /// Git 作者名称
+    pub name: String,
+    /// Git 作者邮箱
+    pub email: String,
+}
+
+impl FromStr for AuthorRule {
+    type Err = SyncError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            SyncError::App(format!(
+                "作者规则 '{s}' 格式错误，应为 path:<目录>=<名称> <<邮箱>> 或 message:<文本>=<名称> <<邮箱>>"
+            ))
+        };
+        let (condition, author) = s.rsplit_once('=').ok_or_else(invalid)?;
+        let matcher = match condition.split_once(':').ok_or_else(invalid)? {
+            ("path", prefix) if !prefix.trim_matches('/').is_empty() => {
+                AuthorMatch::PathPrefix(prefix.trim_matches('/').to_string())
+            }
+            ("message", text) if !text.is_empty() => AuthorMatch::MessageContains(text.to_string()),
+            _ => return Err(invalid()),
+        };
+        let (name, email) = author
+            .trim()
+            .strip_suffix('>')
+            .and_then(|author| author.split_once('<'))
+            .ok_or_else(invalid)?;
+        if name.trim().is_empty() || email.trim().is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            matcher,
+            name: name.trim().to_string(),
+            email: email.trim().to_string(),
+        })
+    }
+}
+
+impl AuthorRule {
+    /// 规则是否匹配该版本
+    ///
+    /// 路径规则要求版本有变更路径信息，并且所有路径都在该目录下
+    pub fn matches(&self, log: &SvnLog) -> bool {
+        match &self.matcher {
+            AuthorMatch::PathPrefix(prefix) => {
+                !log.paths.is_empty()
+                    && log.paths.iter().all(|changed| {
+                        let path = changed.path.trim_start_matches('/');
+                        path.strip_prefix(prefix.as_str())
+                            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+                    })
+            }
+            AuthorMatch::MessageContains(text) => log.message.contains(text.as_str()),
+        }
+    }
+}
+
+/// 确定版本的 Git 作者
+///
+/// SVN 作者缺失或在服务账号列表中时，使用第一条匹配的回退规则；
+/// 其他情况使用 SVN 作者，邮箱为 `<作者>@<仓库 UUID>`。
+///
+/// # 参数
+///
+/// * `log`: SVN 日志
+/// * `rules`: 回退规则，按顺序匹配
+/// * `service_accounts`: 共享的服务账号
+/// * `repository_uuid`: SVN 仓库 UUID
+///
+/// # 返回
+///
+/// 作者名称和邮箱
+pub fn resolve_author(
+    log: &SvnLog,
+    rules: &[AuthorRule],
+    service_accounts: &[String],
+    repository_uuid: &str,
+) -> (String, String) {
+    let author = match log.author.trim() {
+        "" => SVN_NO_AUTHOR,
+        author => author,
+    };
+    let shared = author == SVN_NO_AUTHOR || service_accounts.iter().any(|a| a == author);
+    if shared && let Some(rule) = rules.iter().find(|rule| rule.matches(log)) {
+        return (rule.name.clone(), rule.email.clone());
+    }
+    (author.to_string(), format!("{author}@{repository_uuid}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::SvnChangedPath;
+
+    fn log(author: &str, message: &str, paths: &[&str]) -> SvnLog {
+        SvnLog {
+            author: author.into(),
+            message: message.into(),
+            paths: paths
+                .iter()
+                .map(|path| SvnChangedPath {
+                    action: "M".into(),
+                    path: path.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_author_rule() {
+        let rule: AuthorRule = "path:/trunk/ui/=Alice Wang <alice@example.com>"
+            .parse()
+            .unwrap();
+        assert_eq!(rule.matcher, AuthorMatch::PathPrefix("trunk/ui".into()));
+        assert_eq!(rule.name, "Alice Wang");
+        assert_eq!(rule.email, "alice@example.com");
+
+        let rule: AuthorRule = "message:[release]=Bob <bob@example.com>".parse().unwrap();
+        assert_eq!(
+            rule.matcher,
+            AuthorMatch::MessageContains("[release]".into())
+        );
+
+        for invalid in [
+            "trunk=Alice <a@b>",
+            "path:=Alice <a@b>",
+            "path:trunk=Alice",
+            "owner:trunk=Alice <a@b>",
+        ] {
+            assert!(invalid.parse::<AuthorRule>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_resolve_author_with_fallback() {
+        let rules: Vec<AuthorRule> = vec![
+            "path:trunk/ui=Alice <alice@example.com>".parse().unwrap(),
+            "message:[release]=Bob <bob@example.com>".parse().unwrap(),
+        ];
+        let accounts = vec!["buildbot".to_string()];
+        let resolve = |log: &SvnLog| resolve_author(log, &rules, &accounts, "uuid");
+
+        assert_eq!(
+            resolve(&log(
+                "buildbot",
+                "fix",
+                &["/trunk/ui/a.css", "/trunk/ui/b.css"]
+            )),
+            ("Alice".into(), "alice@example.com".into())
+        );
+        assert_eq!(
+            resolve(&log("", "[release] 1.0", &["/trunk/ui2/a.css"])),
+            ("Bob".into(), "bob@example.com".into())
+        );
+        // 真实作者不使用回退规则
+        assert_eq!(
+            resolve(&log("carol", "[release] 1.0", &["/trunk/ui/a.css"])),
+            ("carol".into(), "carol@uuid".into())
+        );
+        // 没有匹配的规则时保留服务账号
+        assert_eq!(
+            resolve(&log("buildbot", "nightly", &["/trunk/core/a.rs"])),
+            ("buildbot".into(), "buildbot@uuid".into())
+        );
+        assert_eq!(
+            resolve(&log("", "nightly", &[])),
+            (SVN_NO_AUTHOR.into(), format!("{SVN_NO_AUTHOR}@uuid"))
+        );
+    }
+}
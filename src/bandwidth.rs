@@ -0,0 +1,118 @@
+//! 远程 SVN 仓库的分批请求：根据每批的用时自动调整批大小
+
+use std::time::Duration;
+
+/// 每次请求默认获取的 SVN 版本数
+pub const DEFAULT_LOG_BATCH: usize = 500;
+
+/// 批大小的下限
+const MIN_BATCH: usize = 10;
+
+/// 一批用时超过该值认为网络较慢，批大小减半
+const SLOW_REQUEST: Duration = Duration::from_secs(20);
+
+/// 一批用时低于该值认为网络通畅，批大小加倍（不超过初始值）
+const FAST_REQUEST: Duration = Duration::from_secs(5);
+
+/// 单个版本更新用时超过该值时提示网络较慢
+pub const SLOW_UPDATE: Duration = Duration::from_secs(60);
+
+/// 根据传输速度自动调整的批大小
+///
+/// 慢速广域网上一次请求大量版本可能长时间没有输出甚至超时，
+/// 每批用时过长时减半，恢复通畅后逐步加倍回到初始值
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdaptiveBatch {
+    size: usize,
+    max: usize,
+}
+
+impl AdaptiveBatch {
+    /// 创建批大小
+    ///
+    /// # 参数
+    ///
+    /// * `max`: 初始（也是最大）批大小
+    pub fn new(max: usize) -> Self {
+        let max = max.max(1);
+        Self { size: max, max }
+    }
+
+    /// 当前批大小
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// 记录一批请求的用时，必要时调整批大小
+    ///
+    /// # 参数
+    ///
+    /// * `items`: 本批获取的数量
+    /// * `elapsed`: 本批用时
+    ///
+    /// # 返回
+    ///
+    /// 批大小有变化时返回新的批大小
+    pub fn record(&mut self, items: usize, elapsed: Duration) -> Option<usize> {
+        let size = if elapsed > SLOW_REQUEST {
+            (self.size / 2).max(MIN_BATCH.min(self.max))
+        } else if elapsed < FAST_REQUEST && items >= self.size {
+            (self.size * 2).min(self.max)
+        } else {
+            self.size
+        };
+        if size == self.size {
+            return None;
+        }
+        self.size = size;
+        Some(size)
+    }
+}
+
+impl Default for AdaptiveBatch {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOG_BATCH)
+    }
+}
+
+/// 是否为需要通过网络访问的 SVN 仓库 URL（`file://` 以外）
+///
+/// # 参数
+///
+/// * `url`: SVN 仓库 URL
+pub fn is_remote_url(url: &str) -> bool {
+    url.contains("://") && !url.starts_with("file://")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptive_batch_should_shrink_on_slow_and_grow_on_fast_requests() {
+        let mut batch = AdaptiveBatch::new(100);
+        assert_eq!(batch.record(100, Duration::from_secs(10)), None);
+        assert_eq!(batch.record(100, Duration::from_secs(30)), Some(50));
+        assert_eq!(batch.record(50, Duration::from_secs(30)), Some(25));
+        for _ in 0..5 {
+            batch.record(batch.size(), Duration::from_secs(60));
+        }
+        assert_eq!(batch.size(), MIN_BATCH);
+
+        // 最后一批不满时不加倍
+        assert_eq!(batch.record(3, Duration::from_secs(1)), None);
+        assert_eq!(batch.record(10, Duration::from_secs(1)), Some(20));
+        for _ in 0..5 {
+            batch.record(batch.size(), Duration::from_secs(1));
+        }
+        assert_eq!(batch.size(), 100);
+    }
+
+    #[test]
+    fn test_is_remote_url() {
+        assert!(is_remote_url("https://svn.example.com/repo"));
+        assert!(is_remote_url("svn+ssh://host/repo"));
+        assert!(!is_remote_url("file:///d:/repo"));
+        assert!(!is_remote_url("d:/svn"));
+    }
+}
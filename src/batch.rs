@@ -0,0 +1,315 @@
+//! 一次同步多个同步对
+
+use std::{
+    fmt::Display,
+    io::{BufRead, BufReader, Read},
+    num::NonZeroUsize,
+    path::PathBuf,
+    process::{Command, Stdio},
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+};
+
+use crate::{config::SyncConfig, error::Result};
+
+/// 一个同步对的同步结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairResult {
+    /// SVN 工作副本目录
+    pub svn_dir: PathBuf,
+    /// Git 仓库目录
+    pub git_dir: PathBuf,
+    /// 失败原因，成功时为 `None`
+    pub error: Option<String>,
+}
+
+/// 多个同步对的同步结果汇总
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatchSummary {
+    results: Vec<PairResult>,
+}
+
+impl BatchSummary {
+    /// 每个同步对的结果（按同步顺序）
+    pub fn results(&self) -> &[PairResult] {
+        &self.results
+    }
+
+    /// 失败的同步对数量
+    pub fn failed(&self) -> usize {
+        self.results.iter().filter(|r| r.error.is_some()).count()
+    }
+}
+
+impl Display for BatchSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = |header: &str, column: fn(&PairResult) -> String| {
+            self.results
+                .iter()
+                .map(|r| display_width(&column(r)))
+                .chain([display_width(header)])
+                .max()
+                .unwrap_or_default()
+        };
+        let svn = |r: &PairResult| r.svn_dir.display().to_string();
+        let git = |r: &PairResult| r.git_dir.display().to_string();
+        let svn_width = width("SVN 目录", svn);
+        let git_width = width("Git 目录", git);
+        let pad = |text: String, width: usize| {
+            let fill = width.saturating_sub(display_width(&text));
+            format!("{text}{}", " ".repeat(fill))
+        };
+
+        writeln!(
+            f,
+            "结果  {}  {}  说明",
+            pad("SVN 目录".into(), svn_width),
+            pad("Git 目录".into(), git_width)
+        )?;
+        for result in &self.results {
+            let (status, reason) = match &result.error {
+                None => ("成功", ""),
+                // 多行错误只显示第一行，完整内容已在同步时输出
+                Some(e) => ("失败", e.lines().next().unwrap_or_default()),
+            };
+            let line = format!(
+                "{status}  {}  {}  {reason}",
+                pad(svn(result), svn_width),
+                pad(git(result), git_width)
+            );
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        write!(
+            f,
+            "共 {} 个同步对，成功 {} 个，失败 {} 个",
+            self.results.len(),
+            self.results.len() - self.failed(),
+            self.failed()
+        )
+    }
+}
+
+/// 文本在终端中占用的列数，中日韩等全角字符按两列计算
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| if c >= '\u{2e80}' { 2 } else { 1 })
+        .sum()
+}
+
+/// 依次同步多个同步对
+///
+/// 某个同步对失败时输出错误并继续同步下一个，全部完成后返回汇总
+///
+/// # 参数
+///
+/// * `configs`: 要同步的同步对
+/// * `sync_one`: 同步一个同步对
+pub fn sync_pairs(
+    configs: Vec<SyncConfig>,
+    mut sync_one: impl FnMut(SyncConfig) -> Result<()>,
+) -> BatchSummary {
+    let total = configs.len();
+    let mut summary = BatchSummary::default();
+    for (i, config) in configs.into_iter().enumerate() {
+        let svn_dir = config.svn_dir.clone();
+        let git_dir = config.git_dir.clone();
+        println!(
+            "[{}/{total}] 同步 {} -> {}",
+            i + 1,
+            svn_dir.display(),
+            git_dir.display()
+        );
+        let error = sync_one(config).err().map(|e| {
+            eprintln!("{} 同步失败：{e}", git_dir.display());
+            e.to_string()
+        });
+        summary.results.push(PairResult {
+            svn_dir,
+            git_dir,
+            error,
+        });
+    }
+    summary
+}
+
+/// 并行同步多个同步对
+///
+/// 每个同步对在单独的子进程中同步，最多同时运行 `jobs` 个。
+/// 子进程的每一行输出加上 `[Git 目录]` 前缀后转发，不同同步对的输出会交错出现。
+/// 子进程以非零状态退出时，取其错误输出的最后一行作为失败原因。
+///
+/// # 参数
+///
+/// * `configs`: 要同步的同步对
+/// * `jobs`: 最多同时同步的数量
+/// * `worker`: 创建同步一个同步对的子进程命令
+///
+/// # 返回
+///
+/// 汇总，顺序与 `configs` 一致
+pub fn sync_pairs_parallel(
+    configs: Vec<SyncConfig>,
+    jobs: NonZeroUsize,
+    worker: impl Fn(&SyncConfig) -> Command + Sync,
+) -> BatchSummary {
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(vec![None; configs.len()]);
+    thread::scope(|scope| {
+        for _ in 0..jobs.get().min(configs.len()) {
+            scope.spawn(|| {
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(config) = configs.get(i) else {
+                        break;
+                    };
+                    let error =
+                        run_worker(worker(config), &config.git_dir.display().to_string()).err();
+                    results.lock().unwrap_or_else(|e| e.into_inner())[i] = Some(PairResult {
+                        svn_dir: config.svn_dir.clone(),
+                        git_dir: config.git_dir.clone(),
+                        error,
+                    });
+                }
+            });
+        }
+    });
+    BatchSummary {
+        results: results
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner())
+            .into_iter()
+            .flatten()
+            .collect(),
+    }
+}
+
+/// 运行子进程并转发带前缀的输出
+///
+/// # 返回
+///
+/// 子进程失败时返回失败原因
+fn run_worker(mut command: Command, prefix: &str) -> std::result::Result<(), String> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("无法启动同步进程：{e}"))?;
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    let last_error = thread::scope(|scope| {
+        if let Some(stdout) = stdout {
+            scope.spawn(|| {
+                forward_lines(stdout, |line| println!("[{prefix}] {line}"));
+            });
+        }
+        let mut last_error = None;
+        if let Some(stderr) = stderr {
+            forward_lines(stderr, |line| {
+                eprintln!("[{prefix}] {line}");
+                if !line.trim().is_empty() {
+                    last_error = Some(line.trim().to_string());
+                }
+            });
+        }
+        last_error
+    });
+
+    let status = child.wait().map_err(|e| format!("等待同步进程失败：{e}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(last_error.unwrap_or_else(|| format!("同步进程异常退出（{status}）")))
+    }
+}
+
+/// 逐行读取输出，非 UTF-8 内容按有损方式转换
+fn forward_lines(output: impl Read, mut on_line: impl FnMut(&str)) {
+    let mut reader = BufReader::new(output);
+    let mut buf = Vec::new();
+    while let Ok(n) = reader.read_until(b'\n', &mut buf) {
+        if n == 0 {
+            break;
+        }
+        on_line(String::from_utf8_lossy(&buf).trim_end_matches(['\r', '\n']));
+        buf.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SyncError;
+
+    #[test]
+    fn test_sync_pairs_should_continue_after_failure() {
+        let configs = ["a", "b", "c"]
+            .iter()
+            .map(|name| {
+                SyncConfig::new(
+                    format!("d:/svn/{name}").into(),
+                    format!("d:/git/{name}").into(),
+                )
+            })
+            .collect();
+        let mut synced = Vec::new();
+        let summary = sync_pairs(configs, |config| {
+            synced.push(config.git_dir.clone());
+            if config.git_dir.ends_with("b") {
+                return Err(SyncError::App("SVN 服务器不可用\n详细信息".into()));
+            }
+            Ok(())
+        });
+
+        assert_eq!(synced.len(), 3);
+        assert_eq!(summary.failed(), 1);
+        assert_eq!(
+            summary.to_string(),
+            "结果  SVN 目录  Git 目录  说明\n\
+             成功  d:/svn/a  d:/git/a\n\
+             失败  d:/svn/b  d:/git/b  Application error: SVN 服务器不可用\n\
+             成功  d:/svn/c  d:/git/c\n\
+             共 3 个同步对，成功 2 个，失败 1 个"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sync_pairs_parallel_should_report_worker_errors_in_order() {
+        let configs = ["a", "b", "c", "d"]
+            .iter()
+            .map(|name| {
+                SyncConfig::new(
+                    format!("d:/svn/{name}").into(),
+                    format!("d:/git/{name}").into(),
+                )
+            })
+            .collect();
+        let summary = sync_pairs_parallel(configs, NonZeroUsize::new(2).unwrap(), |config| {
+            let script = if config.git_dir.ends_with("c") {
+                "echo 同步中; echo 'Error: SVN 服务器不可用' >&2; exit 1"
+            } else {
+                "echo 同步完成"
+            };
+            let mut command = Command::new("sh");
+            command.args(["-c", script]);
+            command
+        });
+
+        let errors: Vec<_> = summary.results().iter().map(|r| r.error.clone()).collect();
+        assert_eq!(
+            errors,
+            [
+                None,
+                None,
+                Some("Error: SVN 服务器不可用".to_string()),
+                None
+            ]
+        );
+        assert!(summary.results()[3].git_dir.ends_with("d"));
+    }
+}
@@ -0,0 +1,97 @@
+//! 时钟：获取当前时间，测试中可替换为固定时间
+
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Duration, Utc};
+
+/// 时钟
+///
+/// 最后使用时间、检查点、同步计划、进度文件和 Mock 提交时间都从时钟获取，
+/// 测试中注入 [`FixedClock`] 后排序、预计剩余时间等与时间相关的行为都是确定的
+pub trait Clock: Debug + Send + Sync {
+    /// 当前时间
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// 系统时钟
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// 固定时间的时钟，只在调用 [`FixedClock::set`] 或 [`FixedClock::advance`] 时变化
+///
+/// 克隆的时钟共享同一个时间，测试中保留一个克隆即可控制注入的时钟
+#[derive(Debug, Clone)]
+pub struct FixedClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl FixedClock {
+    /// 创建时钟
+    ///
+    /// # 参数
+    ///
+    /// * `now`: 初始时间
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    /// 设置当前时间
+    ///
+    /// # 参数
+    ///
+    /// * `now`: 新的时间
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    /// 时间前进
+    ///
+    /// # 参数
+    ///
+    /// * `duration`: 前进的时长
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// 系统时钟的共享引用，作为各组件的默认时钟
+pub fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_clock_should_share_time_between_clones() {
+        let start = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .to_utc();
+        let clock = FixedClock::new(start);
+        let injected: Arc<dyn Clock> = Arc::new(clock.clone());
+        assert_eq!(injected.now(), start);
+
+        clock.advance(Duration::seconds(90));
+        assert_eq!(injected.now(), start + Duration::seconds(90));
+        clock.set(start);
+        assert_eq!(injected.now(), start);
+    }
+}
@@ -1,6 +1,12 @@
-use std::path::PathBuf;
+use std::{num::NonZeroUsize, path::PathBuf};
 
-use clap::{Parser, Subcommand};
+use chrono::{FixedOffset, NaiveDate};
+use clap::{Args, Parser, Subcommand, builder::PossibleValuesParser};
+
+use crate::{
+    authors::AuthorRule, message::RewriteRule, notify::NotifyTarget, ops::SVN_CERT_FAILURES,
+    revisions::RevisionSet,
+};
 
 /// 命令
 #[derive(Debug, Parser)]
@@ -10,9 +16,43 @@ use clap::{Parser, Subcommand};
     about = "同步 SVN 到 Git 仓库的工具",
     long_about = "将 SVN 提交按顺序同步为 Git 提交。支持交互式选择历史配置，也支持通过参数直传目录。",
     arg_required_else_help = true,
-    after_help = "示例:\n  svn2git sync --svn-dir D:\\svn_wc --git-dir D:\\git_repo\n  svn2git sync\n  svn2git history list\n  svn2git history delete 0"
+    after_help = "示例:\n  svn2git sync --svn-dir D:\\svn_wc --git-dir D:\\git_repo\n  svn2git sync\n  svn2git sync --yes --svn-dir /srv/svn_wc --git-dir /srv/git_repo\n  svn2git resume\n  svn2git history list\n  svn2git history delete 0"
 )]
 pub struct Cli {
+    /// 只在内存中保存历史记录等状态，不读写 config.json
+    #[arg(long, global = true)]
+    pub ephemeral: bool,
+
+    /// 单个 svn 命令最多执行的秒数，超时后终止（默认不限制）
+    #[arg(long, global = true, value_name = "SECONDS")]
+    pub svn_timeout: Option<u64>,
+
+    /// 单个 git 命令最多执行的秒数，超时后终止（默认不限制）
+    #[arg(long, global = true, value_name = "SECONDS")]
+    pub git_timeout: Option<u64>,
+
+    /// svn 命令附加 --non-interactive：需要输入密码或确认服务器证书时直接失败，不会挂起等待输入
+    #[arg(long, global = true)]
+    pub svn_non_interactive: bool,
+
+    /// 接受 SVN 服务器证书的这些错误（逗号分隔，如自签名证书用 unknown-ca），隐含 --svn-non-interactive
+    #[arg(
+        long,
+        global = true,
+        value_name = "FAILURES",
+        value_delimiter = ',',
+        value_parser = PossibleValuesParser::new(SVN_CERT_FAILURES)
+    )]
+    pub trust_server_cert_failures: Vec<String>,
+
+    /// 旧仓库提交说明的编码（如 gbk、big5），不是 UTF-8 或被误当作 Latin-1 转换的提交说明按该编码转为 UTF-8
+    #[arg(long, global = true, value_name = "ENCODING")]
+    pub encoding: Option<String>,
+
+    /// 输出同步过程中的全部细节；默认只在终端中输出，标准输出不是终端（如 CI）时每个版本只输出一行
+    #[arg(long, global = true)]
+    pub verbose: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -22,7 +62,7 @@ pub enum Commands {
     /// 同步命令
     #[command(
         about = "执行 SVN -> Git 同步",
-        long_about = "读取 SVN 日志并逐条更新工作副本，然后在 Git 中生成对应提交。\n\n防事故参数：\n- --dry-run: 只预览将要同步的日志，不做任何写操作\n- --limit N: 本次最多同步 N 条，便于小批量验证"
+        long_about = "读取 SVN 日志并逐条更新工作副本，然后在 Git 中生成对应提交。\n\n防事故参数：\n- --dry-run: 只预览将要同步的日志，不做任何写操作\n\n每次运行都会与上次的同步计划比较，列出新增、移除和跳过状态变化的版本\n- --limit N: 本次最多同步 N 条，便于小批量验证\n- --only-revs 120,125,130-140: 只同步指定的版本，用于单独重新处理某些版本\n\n时间窗口：\n- --since/--until YYYY-MM-DD: 只同步该时间段内的提交（转换为 SVN 的 {日期} 版本说明符）\n\n进度文件：\n- 同步期间在配置文件所在目录写入 svn2git-<摘要>.progress.json（开始时输出路径），\n  包含状态、当前版本、完成数量、预计剩余秒数和最近的错误，供外部监控程序轮询\n\n稀疏工作副本：\n- 用 svn checkout --depth / svn update --set-depth 只检出部分目录时，只同步已检出的目录，\n  只修改了未检出路径的版本被跳过；检出范围记录在同步对中，audit 只比较这部分"
     )]
     Sync {
         #[arg(
@@ -47,124 +87,1767 @@ pub enum Commands {
         #[arg(long, value_name = "N", help = "最多同步 N 条日志（按SVN返回顺序）")]
         limit: Option<usize>,
 
-        #[arg(long, help = "仅预览同步计划，不执行写入操作")]
+        #[arg(
+            long,
+            value_name = "REVS",
+            help = "只同步指定的版本，如 120,125,130-140",
+            long_help = "只同步指定的版本，逗号分隔，可以是单个版本或闭区间（如 120,125,130-140）。\n- 单个版本必须在待同步的版本中，区间至少包含一个待同步的版本\n- 已经同步过的版本不能选择，需要重新处理时先 rollback\n- SVN 工作副本按版本整体更新：未选择的版本不单独生成提交，其更改随之后第一个选择的版本提交\n- 可以与 --limit、--since/--until 同时使用，先按版本选择再限制条数"
+        )]
+        only_revs: Option<RevisionSet>,
+
+        #[arg(
+            long,
+            conflicts_with_all = ["only_revs", "yes"],
+            help = "同步前交互选择要同步的版本",
+            long_help = "同步前列出待同步的版本（默认全部选中），可以取消选择个别版本，如体积巨大的第三方代码导入。\n未选择的版本与 --only-revs 相同：不单独生成提交，其更改随之后第一个选择的版本提交"
+        )]
+        select: bool,
+
+        #[arg(
+            long,
+            conflicts_with = "yes",
+            help = "确认同步前显示每个版本的变更摘要",
+            long_help = "确认同步前对每个待同步的版本执行 svn diff -c REV --summarize，在日志下列出变更的路径（每个版本最多 20 个）。\n只比较仓库中的版本，需要访问 SVN 服务器；没有询问确认（低于确认阈值）时不获取"
+        )]
+        preview_diff: bool,
+
+        #[arg(
+            long,
+            help = "仅预览同步计划，不执行写入操作（只记录本次计划，供下次运行比较）"
+        )]
         dry_run: bool,
+
+        #[arg(long, value_name = "YYYY-MM-DD", help = "只同步该日期（含）之后的提交")]
+        since: Option<NaiveDate>,
+
+        #[arg(
+            long,
+            value_name = "YYYY-MM-DD",
+            help = "只同步该日期之前的提交（不含当天）"
+        )]
+        until: Option<NaiveDate>,
+
+        #[arg(
+            short,
+            long,
+            visible_aliases = ["non-interactive", "no-input"],
+            help = "非交互模式：自动确认同步，必须通过参数指定目录",
+            long_help = "非交互模式，适用于 cron/CI。\n- 不弹出历史选择和确认提示，自动开始同步\n- 需要用户输入时直接报错退出，因此必须同时传入 --svn-dir 和 --git-dir"
+        )]
+        yes: bool,
+
+        #[arg(
+            long,
+            value_name = "URL",
+            help = "SVN 仓库 URL：工作副本目录不存在或为空时先从该 URL 检出",
+            long_help = "SVN 仓库 URL。\n- SVN 工作副本目录不存在或为空时，先检出该 URL 的版本 0，再同步全部历史\n- 目录已有内容时忽略"
+        )]
+        svn_url: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "使用配置方案，并记录到该同步对（之后的 sync、sync-all、watch 自动使用）",
+            long_help = "使用 profile save 保存的配置方案，并记录到该同步对，之后的同步（包括 resume、sync-all、watch）自动使用。\n配置方案中的策略覆盖同步对自身的策略；提交参数作为默认值，命令行中的开关和单值参数优先，可重复参数追加在配置方案之后。"
+        )]
+        profile: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "PREFIX",
+            allow_hyphen_values = true,
+            help = "Git 提交标题的前缀，可以为空，并记录到该同步对（默认 \"SVN: \"）"
+        )]
+        message_prefix: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "URL",
+            help = "Git 远程仓库地址：设置为 origin（已存在时更新地址）"
+        )]
+        remote: Option<String>,
+
+        #[arg(
+            long,
+            help = "同步完成后推送所有分支和标签到 origin",
+            long_help = "同步完成后推送所有分支和标签到 origin。\n- 没有新的 SVN 日志时也会推送，可用于重试上次失败的推送\n- 不强制推送，远程分支已分叉时推送失败，本地提交和同步进度保留\n- 也可以用 history policy 把 push 策略设为 always"
+        )]
+        push: bool,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "同步完成后把所有分支和标签打包为 git bundle 文件",
+            long_help = "同步完成后把所有分支和标签打包为 git bundle 文件（已存在时覆盖），\n复制到其他机器后可直接 git clone <FILE>，不需要推送到远程仓库。\n没有新的 SVN 日志时也会生成。libgit2 后端不支持。"
+        )]
+        bundle: Option<PathBuf>,
+
+        #[arg(
+            long,
+            value_name = "N",
+            help = "远程 SVN 仓库每次请求的最大日志条数（默认 500）",
+            long_help = "工作副本指向远程 SVN 仓库（http、https、svn 等，非 file://）时分批获取日志，每批最多 N 条。\n- 某一批用时超过 20 秒时批大小减半（最少 10 条），网络恢复后逐步加倍回到 N\n- 每批完成后输出已获取的条数，慢速网络上不会长时间没有输出"
+        )]
+        log_batch: Option<NonZeroUsize>,
+
+        #[arg(
+            long,
+            value_name = "POLICY",
+            help = "本次同步时 Git 工作目录有未提交更改的处理方式：ignore、fail、stash、ask",
+            long_help = "本次同步时 Git 工作目录有未提交更改的处理方式，覆盖同步对的 dirty-tree 策略：\n- fail: 拒绝同步\n- stash: 同步前 git stash 暂存已跟踪文件的更改，同步结束后（包括失败时）恢复\n- ask: 询问用户选择，非交互模式下拒绝同步\n- ignore: 不检查，已有的更改随第一个版本一起提交（与 --force 相同）"
+        )]
+        dirty_tree: Option<String>,
+
+        #[arg(
+            long,
+            conflicts_with = "dirty_tree",
+            help = "Git 工作目录有未提交的更改（等同于 --dirty-tree ignore），或 Git 仓库已有与 SVN 无关的提交时仍然同步"
+        )]
+        force: bool,
+
+        #[arg(
+            long,
+            help = "同步失败时回滚到同步开始前的状态",
+            long_help = "同步失败时回滚到同步开始前的状态，不留下导入了一半的版本：\n- Git 分支重置到同步开始前的 HEAD（git reset --mixed，工作目录中的文件随 SVN 工作副本恢复）\n- SVN 工作副本更新回同步开始前的版本\n- 同步进度恢复到同步开始前，并清除检查点（之后不能再 resume）"
+        )]
+        rollback_on_failure: bool,
+
+        #[command(flatten)]
+        commit: CommitArgs,
     },
 
-    /// 历史记录命令
-    #[command(about = "查看或删除历史配置")]
-    History {
-        #[command(subcommand)]
-        command: HistoryCommands,
+    /// 暂停命令
+    #[command(
+        about = "暂停同步对的自动同步",
+        long_about = "暂停后 watch 和 sync-all 跳过该同步对，其他同步对照常同步，适用于 SVN 维护期间。\n正在运行的 watch 在下一轮生效，不会打断正在进行的同步。使用 unpause 恢复。",
+        after_help = "示例:\n  svn2git pause 0\n  svn2git unpause 0"
+    )]
+    Pause {
+        #[arg(
+            value_name = "INDEX",
+            help = "历史记录索引（可通过 history list 查看）"
+        )]
+        id: usize,
     },
-}
 
-/// 历史记录命令
-#[derive(Debug, Subcommand)]
-pub enum HistoryCommands {
-    /// 列出历史记录
-    #[command(about = "列出历史同步配置")]
-    List,
+    /// 取消暂停命令
+    #[command(
+        about = "恢复暂停的同步对的自动同步",
+        long_about = "取消 pause 的暂停，watch 和 sync-all 从下一轮起重新同步该同步对。\n本命令只修改暂停标记，不会立即同步；需要继续中断的同步时使用 resume。",
+        after_help = "示例:\n  svn2git unpause 0"
+    )]
+    Unpause {
+        #[arg(
+            value_name = "INDEX",
+            help = "历史记录索引（可通过 history list 查看）"
+        )]
+        id: usize,
+    },
 
-    /// 按 ID 删除历史记录
-    #[command(about = "删除指定索引的历史记录（索引可通过 history list 查看）")]
-    Delete { id: usize },
-}
+    /// 恢复命令
+    #[command(
+        about = "恢复上次中断的同步",
+        long_about = "同步过程中每完成一个版本都会保存检查点。同步被中断（出错或手动终止）后，\n使用本命令从最后一个已提交的版本之后继续，只同步上次计划中剩余的版本，不再询问确认。"
+    )]
+    Resume {
+        #[arg(
+            value_name = "INDEX",
+            help = "历史记录索引（只有一个未完成的同步时可省略）"
+        )]
+        id: Option<usize>,
 
-#[cfg(test)]
-mod tests {
-    use clap::Parser;
-    use clap::error::ErrorKind;
-    use std::path::PathBuf;
+        #[command(flatten)]
+        commit: CommitArgs,
+    },
 
-    use super::{Cli, Commands, HistoryCommands};
+    /// 回滚命令
+    #[command(
+        about = "回滚上次未完成的同步",
+        long_about = "把未完成（出错或手动终止）的同步恢复到同步开始前的状态：Git 分支重置到同步开始前的 HEAD，\nSVN 工作副本更新回同步开始前的版本，同步进度恢复并清除检查点。\n需要输入同步对标签（Git 目录名）确认；非交互时使用 --yes --confirm <标签>。"
+    )]
+    Rollback {
+        #[arg(
+            value_name = "INDEX",
+            help = "历史记录索引（只有一个未完成的同步时可省略）"
+        )]
+        id: Option<usize>,
 
-    #[test]
-    fn test_parse_sync_command_with_paths() {
-        let cli = Cli::parse_from([
-            "svn2git",
-            "sync",
-            "--svn-dir",
-            "d:/svn",
-            "--git-dir",
-            "d:/git",
-        ]);
+        #[arg(short, long, help = "非交互模式：不弹出确认，必须同时传入 --confirm")]
+        yes: bool,
 
-        match cli.command {
-            Commands::Sync {
-                svn_dir,
-                git_dir,
-                limit,
-                dry_run,
-            } => {
-                assert_eq!(svn_dir, Some(PathBuf::from("d:/svn")));
-                assert_eq!(git_dir, Some(PathBuf::from("d:/git")));
-                assert_eq!(limit, None);
-                assert!(!dry_run);
-            }
-            _ => panic!("应解析为 Sync 命令"),
-        }
-    }
+        #[arg(
+            long,
+            value_name = "LABEL",
+            help = "输入同步对标签（Git 目录名）确认回滚"
+        )]
+        confirm: Option<String>,
+    },
 
-    #[test]
-    fn test_parse_sync_command_with_safety_options() {
-        let cli = Cli::parse_from([
-            "svn2git",
-            "sync",
-            "--limit",
-            "5",
-            "--dry-run",
-            "--svn-dir",
-            "d:/svn",
-        ]);
+    /// 重试命令
+    #[command(
+        about = "重试一个提交失败的 SVN 版本",
+        long_about = "错误策略为 continue 时，提交失败的版本会被回滚并记录下来。问题修复后使用本命令只同步这一个版本：\n先把 SVN 工作副本更新到上次同步的版本，再更新到该版本并提交，不再询问确认。\n只能重试下一个待同步的版本。失败版本之后的版本同步成功时，失败版本的更改已随该版本一起提交，\n其失败记录会被清除（history map 中映射到该提交），不再需要重试。",
+        after_help = "示例:\n  svn2git retry 1234\n  svn2git retry 1234 --id 0 --skip-paths trunk/huge"
+    )]
+    Retry {
+        #[arg(value_name = "REV", help = "提交失败的 SVN 版本")]
+        rev: String,
 
-        match cli.command {
-            Commands::Sync {
-                svn_dir,
-                git_dir,
-                limit,
-                dry_run,
-            } => {
-                assert_eq!(svn_dir, Some(PathBuf::from("d:/svn")));
-                assert_eq!(git_dir, None);
-                assert_eq!(limit, Some(5));
-                assert!(dry_run);
-            }
-            _ => panic!("应解析为 Sync 命令"),
-        }
-    }
+        #[arg(
+            long,
+            value_name = "INDEX",
+            help = "历史记录索引（只有一个同步对记录了该版本失败时可省略）"
+        )]
+        id: Option<usize>,
 
-    #[test]
-    fn test_parse_history_list_command() {
-        let cli = Cli::parse_from(["svn2git", "history", "list"]);
-        match cli.command {
-            Commands::History { command } => match command {
-                HistoryCommands::List => {}
-                _ => panic!("应解析为 History List"),
-            },
-            _ => panic!("应解析为 History 命令"),
-        }
-    }
+        #[command(flatten)]
+        commit: CommitArgs,
+    },
 
-    #[test]
-    fn test_parse_history_delete_command() {
-        let cli = Cli::parse_from(["svn2git", "history", "delete", "3"]);
-        match cli.command {
-            Commands::History { command } => match command {
-                HistoryCommands::Delete { id } => assert_eq!(id, 3),
-                _ => panic!("应解析为 History Delete"),
-            },
-            _ => panic!("应解析为 History 命令"),
-        }
-    }
+    /// 环境检查命令
+    #[command(
+        about = "检查运行环境和配置",
+        long_about = "检查 git 和 svn 命令是否已安装且版本满足要求、配置文件能否解析、每个同步对的目录是否存在且为有效的 SVN 工作副本和 Git 仓库，以及引用的配置方案是否存在，并给出修复建议。\n只读检查，不会修改任何内容；发现错误时以非零状态退出。"
+    )]
+    Doctor {
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "要检查的配置文件（默认为 config.json）"
+        )]
+        config: Option<PathBuf>,
+    },
 
-    #[test]
-    fn test_help_contains_examples() {
-        let err = Cli::try_parse_from(["svn2git", "--help"]).unwrap_err();
-        assert_eq!(err.kind(), ErrorKind::DisplayHelp);
-        let msg = err.to_string();
-        assert!(msg.contains("示例:"));
-        assert!(msg.contains("svn2git sync"));
-        assert!(msg.contains("history list"));
+    /// 状态命令
+    #[command(
+        about = "查看同步对的状态",
+        long_about = "查看同步对待同步的 SVN 版本数、SVN 工作副本和 Git 工作目录是否有本地修改，以及上次同步的版本和时间。\n只读取 svn info/status/log 和 git status/log，不会修改任何内容。"
+    )]
+    Status {
+        #[arg(
+            value_name = "INDEX",
+            help = "历史记录索引（可通过 history list 查看）"
+        )]
+        id: usize,
+    },
+
+    /// 审计命令
+    #[command(
+        about = "只读校验已完成的同步",
+        long_about = "根据同步时记录的 SVN 版本与 Git 提交对应关系，导出 SVN 各版本的目录树并与对应 Git 提交逐文件比较，输出通过/失败报告。\n不会修改 SVN 工作副本或 Git 仓库；存在不一致时以非零状态退出。"
+    )]
+    Audit {
+        #[arg(
+            value_name = "INDEX",
+            help = "历史记录索引（可通过 history list 查看）"
+        )]
+        id: usize,
+
+        #[arg(long, value_name = "N", help = "只均匀抽查 N 个版本（默认检查全部）")]
+        sample: Option<usize>,
+
+        #[arg(
+            long,
+            value_name = "PATTERN",
+            help = "只比较匹配的路径（可重复，支持 * ** ?）"
+        )]
+        include: Vec<String>,
+
+        #[arg(
+            long,
+            value_name = "PATTERN",
+            help = "不比较匹配的路径（可重复，优先于 --include）"
+        )]
+        exclude: Vec<String>,
+
+        #[arg(
+            long,
+            value_name = "N",
+            help = "并行计算文件哈希的线程数（默认为 CPU 核数）"
+        )]
+        jobs: Option<NonZeroUsize>,
+    },
+
+    /// 接管命令
+    #[command(
+        about = "接管已转换的 Git 仓库，在新机器上继续同步",
+        long_about = "从已转换的 Git 仓库重建同步对的历史记录：\n- 版本与提交的对应关系来自提交的 SVN-Revision 记录（--git-notes 写入的 note）或 git-svn-id 记录（--git-svn-id 或 git svn 写入）\n- 同步进度和 SVN 仓库 UUID 来自 refs/svn2git/state 镜像标记，没有标记时取最后一个有记录的版本\n接管后用 sync 指定相同的目录即可从中断处继续。同步对已有同步进度时拒绝接管。",
+        after_help = "示例:\n  svn2git adopt d:/git --svn-dir d:/svn"
+    )]
+    Adopt {
+        #[arg(value_name = "GIT_DIR", help = "已转换的 Git 仓库目录")]
+        git_dir: PathBuf,
+
+        #[arg(
+            short,
+            long,
+            value_name = "PATH",
+            help = "继续同步使用的 SVN 工作副本目录"
+        )]
+        svn_dir: PathBuf,
+    },
+
+    /// 导入 dump 命令
+    #[command(
+        name = "import-dump",
+        about = "从 svnadmin dump 文件离线转换",
+        long_about = "解析 svnadmin dump 生成的文件，把其中的版本依次重放为 Git 提交，不需要 svn 命令和网络，适用于无法访问 SVN 服务器的隔离环境。\n每个版本的作者、时间和提交说明来自版本属性，没有文件变化的版本不生成提交。Git 仓库不存在时自动初始化。\n不支持 svnadmin dump --deltas 生成的增量格式。",
+        after_help = "示例:\n  svn2git import-dump repo.dump --git-dir d:/git --root trunk\n  svnadmin dump /srv/svn/repo | svn2git import-dump - --git-dir d:/git"
+    )]
+    ImportDump {
+        #[arg(value_name = "FILE", help = "svnadmin dump 文件，- 表示从标准输入读取")]
+        file: PathBuf,
+
+        #[arg(short, long, value_name = "PATH", help = "Git 仓库目录")]
+        git_dir: PathBuf,
+
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "只导入仓库中的该目录（如 trunk），作为 Git 仓库的根目录"
+        )]
+        root: Option<String>,
+    },
+
+    /// 同步所有同步对
+    #[command(
+        name = "sync-all",
+        about = "依次同步所有历史记录",
+        long_about = "依次同步配置文件中的每一个同步对，某一个失败时继续同步下一个，结束时输出每个同步对的结果。\n已用 pause 暂停的同步对被跳过。\n使用 --jobs N 时最多同时同步 N 个同步对，每个同步对在单独的进程中运行，输出的每一行以 [Git 目录] 开头。\n默认使用当前目录下的 config.json，可用 --config 指定其他配置文件（格式相同），同步进度也保存到该文件。\n有同步对失败时以非零状态退出。",
+        after_help = "示例:\n  svn2git sync-all --yes\n  svn2git sync-all --config d:/mirrors.json --yes\n  svn2git sync-all --yes --jobs 4"
+    )]
+    SyncAll {
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "列出同步对的配置文件（默认为 config.json）"
+        )]
+        config: Option<PathBuf>,
+
+        #[arg(
+            short,
+            long,
+            visible_aliases = ["non-interactive", "no-input"],
+            help = "非交互模式：自动确认每一个同步对的同步，适用于 cron/CI"
+        )]
+        yes: bool,
+
+        #[arg(
+            short,
+            long,
+            value_name = "N",
+            help = "最多同时同步的同步对数量（默认 1，大于 1 时需要 --yes）"
+        )]
+        jobs: Option<NonZeroUsize>,
+
+        /// 并行同步时子进程只同步该 Git 目录对应的同步对
+        #[arg(long, value_name = "GIT_DIR", hide = true)]
+        worker: Option<PathBuf>,
+
+        #[command(flatten)]
+        commit: CommitArgs,
+    },
+
+    /// 监视命令
+    #[command(
+        about = "持续轮询 SVN 并同步新版本",
+        long_about = "每隔一段时间检查 SVN 是否有新版本，有则以非交互模式同步，然后等待下一轮。\n- 同时传入 --svn-dir 和 --git-dir：只同步这一组\n- 都不传：每轮依次同步历史记录中的所有同步对\n\n每个版本完成后都会保存进度，某一轮失败时下一轮从已保存的进度继续。\n连续失败的同步对按指数退避（1 分钟起，最长 1 小时）跳过之后的轮次，连续失败 3 次时只发送一次失败通知，恢复后重新计数。\n每轮开始时重新读取配置文件，新增的同步对、暂停、策略和同步间隔（history schedule）的修改在下一轮生效；收到 SIGHUP 时不再等待间隔，立即开始下一轮（不打断正在进行的同步）。\n收到 Ctrl-C 或 SIGTERM 时等当前一轮结束后退出；再次收到时立即退出，可用 resume 恢复未完成的同步。",
+        after_help = "示例:\n  svn2git watch\n  svn2git watch --svn-dir d:/svn --git-dir d:/git --interval 60"
+    )]
+    Watch {
+        #[arg(
+            short,
+            long,
+            value_name = "PATH",
+            requires = "git_dir",
+            help = "SVN 工作副本目录（不传则同步所有历史记录）"
+        )]
+        svn_dir: Option<PathBuf>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "PATH",
+            requires = "svn_dir",
+            help = "Git 仓库目录"
+        )]
+        git_dir: Option<PathBuf>,
+
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            default_value_t = 300,
+            help = "两轮检查之间的等待秒数（同步对可用 history schedule 单独设置）"
+        )]
+        interval: u64,
+
+        #[command(flatten)]
+        commit: CommitArgs,
+    },
+
+    /// 服务命令
+    #[command(
+        about = "安装、卸载或在前台运行后台同步服务",
+        long_about = "以 watch 模式持续同步。\n- install/uninstall：在 Linux 上注册或删除 systemd 用户服务；在 Windows 上注册或删除开机自动启动的服务（需要管理员权限）。异常退出后由 systemd 或服务控制管理器重启\n- run：在前台运行 watch，输出追加到日志文件，异常退出后自动重启，用于没有服务管理器的环境\n\n服务的工作目录默认为执行命令时的当前目录（即 config.json 所在目录），可用 --working-dir 指定，日志默认写入该目录下的 svn2git.log。",
+        after_help = "示例:\n  svn2git service install --interval 60\n  svn2git service run --log logs/svn2git.log"
+    )]
+    Service {
+        #[command(subcommand)]
+        command: ServiceCommands,
+    },
+
+    /// 检查转换规则
+    #[command(
+        name = "lint-rules",
+        about = "检查转换规则，在开始同步之前发现配置错误",
+        long_about = "检查命令行或配置方案中的转换规则，不访问 SVN 和 Git：\n- 作者回退规则：邮箱格式、被前面规则覆盖而永远不会生效的条件、同一邮箱对应不同名称\n- 跳过路径：匹配所有路径的规则、回溯过多的通配符、重复的规则\n- 作者列表：同时出现在 --skip-author 和 --merge-author 中的作者\n\n发现错误时以非零状态退出，警告不影响退出状态。",
+        after_help = "示例:\n  svn2git lint-rules --profile corp-default\n  svn2git lint-rules --preserve-authors --author-fallback \"path:trunk/ui=Alice <alice@example.com>\""
+    )]
+    LintRules {
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "检查该配置方案（与命令行参数合并后检查）"
+        )]
+        profile: Option<String>,
+
+        #[command(flatten)]
+        commit: CommitArgs,
+    },
+
+    /// 过滤规则预览命令
+    #[command(
+        name = "preview-filters",
+        about = "预览跳过和合并规则会影响哪些 SVN 版本",
+        long_about = "按命令行和同步对的配置方案中的 --skip-paths、--skip-author、--merge-author 规则检查 SVN 版本，\n逐条列出每条规则匹配的版本数和文件变更数，以及同步时会被跳过的版本。\n默认只检查待同步的版本，--all 检查全部版本。只读取 svn log，不会修改任何内容。\n\n被跳过的版本不单独生成提交，规则写错时这些版本的提交说明和作者会悄悄丢失，建议在同步前先预览。",
+        after_help = "示例:\n  svn2git preview-filters 0\n  svn2git preview-filters 0 --all --skip-paths trunk/docs --skip-author buildbot"
+    )]
+    PreviewFilters {
+        #[arg(
+            value_name = "INDEX",
+            help = "历史记录索引（可通过 history list 查看）"
+        )]
+        id: usize,
+
+        #[arg(long, help = "检查全部 SVN 版本（默认只检查待同步的版本）")]
+        all: bool,
+
+        #[command(flatten)]
+        commit: CommitArgs,
+    },
+
+    /// 配置方案命令
+    #[command(
+        about = "管理可在多个同步对之间共用的配置方案",
+        long_about = "配置方案保存一组提交参数（过滤、作者映射、提交说明格式等）和同步策略，\n通过 sync --profile <NAME> 应用到同步对，保存在配置文件所在目录的 profiles 目录中。"
+    )]
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommands,
+    },
+
+    /// 历史记录命令
+    #[command(about = "查看或删除历史配置")]
+    History {
+        #[command(subcommand)]
+        command: HistoryCommands,
+    },
+}
+
+/// 同步和恢复共用的提交参数
+#[derive(Debug, Clone, Default, Args)]
+pub struct CommitArgs {
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "每同步一个版本，把提交中文件的 SHA-256 清单写入该目录"
+    )]
+    pub manifest_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "为每个提交添加记录 SVN 版本、作者和变更路径的 Git note（refs/notes/svn）"
+    )]
+    pub git_notes: bool,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "只更新并提交该 SVN 变更列表中的文件",
+        long_help = "只同步 SVN 变更列表（changelist）中的文件。\n每个版本只把变更列表中的文件更新到该版本，Git 中也只暂存和提交这些文件；\n未修改变更列表文件的版本只记录进度，不生成提交。"
+    )]
+    pub changelist: Option<String>,
+
+    #[arg(
+        long,
+        help = "SVN 与 Git 使用不同目录：每个版本把工作副本（不含 .svn）复制到 Git 目录",
+        long_help = "SVN 工作副本与 Git 仓库使用互不包含的两个目录。\n每次 svn update 后把工作副本中除 .svn 以外的文件复制到 Git 目录，\n并删除 SVN 中已不存在的文件，Git 仓库中不会出现任何 SVN 元数据。"
+    )]
+    pub export: bool,
+
+    #[arg(
+        long,
+        value_name = "PATTERN",
+        help = "所有变更路径都匹配时跳过该版本（可重复）",
+        long_help = "跳过只修改了匹配路径的版本（可重复）。\n按 svn log -v 中的仓库路径匹配（不含开头的 /），如 trunk/docs、**/*.md；\n跳过的版本不更新工作副本也不提交，其更改会随下一个同步的版本进入 Git。"
+    )]
+    pub skip_paths: Vec<String>,
+
+    #[arg(
+        long = "skip-author",
+        value_name = "NAME",
+        help = "跳过这些 SVN 作者的版本（可重复），如定时提交构建产物的机器人账号",
+        long_help = "跳过这些 SVN 作者的版本（可重复），如定时提交构建产物的机器人账号。\n与 --skip-paths 相同，跳过的版本不更新工作副本也不提交，其更改会随下一个同步的版本进入 Git；\n同步结束时列出所有跳过的版本。"
+    )]
+    pub skip_authors: Vec<String>,
+
+    #[arg(
+        long = "merge-author",
+        value_name = "NAME",
+        help = "把这些 SVN 作者的连续版本合并为一个 Git 提交（可重复）",
+        long_help = "把这些 SVN 作者（如机器人账号）的连续版本合并为一个 Git 提交（可重复）。\n只有最后一个版本更新工作副本并提交，更改内容不会丢失；提交说明列出被合并的每个版本。"
+    )]
+    pub merge_authors: Vec<String>,
+
+    #[arg(long, help = "在 Git 提交说明末尾列出 SVN 变更路径")]
+    pub list_changed_files: bool,
+
+    #[arg(
+        long,
+        help = "在提交说明末尾添加 git-svn 格式的 git-svn-id 记录",
+        long_help = "在每个提交说明的最后一行添加 git-svn-id: <URL>@<版本> <仓库UUID>，格式与 git svn 完全相同，\n解析 git-svn 元数据的发布脚本、blame 工具等可以直接用于转换后的仓库。URL 为 SVN 工作副本的 URL。"
+    )]
+    pub git_svn_id: bool,
+
+    #[arg(
+        long,
+        help = "SVN 工作副本格式过旧（升级 svn 客户端之后）时自动执行 svn upgrade 再继续"
+    )]
+    pub auto_upgrade: bool,
+
+    #[arg(
+        long,
+        help = "按 trunk/branches/tags 布局把 SVN 分支映射为 Git 分支",
+        long_help = "SVN 工作副本检出包含 trunk、branches、tags 的布局根目录，通过 svn list 检测标准布局。\n每个版本切换到对应的 Git 分支（trunk -> master，branches/foo -> foo，tags/x -> tags/x），\n再把该分支目录复制到 Git 目录后提交；通过 svn copy 创建的分支从来源分支开始。\nSVN 与 Git 必须使用互不包含的两个目录，不能与 --changelist 同时使用。"
+    )]
+    pub layout: bool,
+
+    #[arg(
+        long,
+        requires = "layout",
+        help = "把复制到 tags/<名称> 的版本转换为 Git 附注标签（需要 --layout）",
+        long_help = "只包含一次 svn copy 到 tags/<名称> 的版本不生成提交，\n而是在来源分支上创建同名的 Git 附注标签，标签说明、创建者和时间取自 SVN 提交。\n其他修改标签目录的版本仍提交到 tags/<名称> 分支。"
+    )]
+    pub git_tags: bool,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "待同步的版本超过 N 个时才询问确认",
+        long_help = "待同步的版本超过 N 个时才询问确认，否则自动开始同步。\n与 --confirm-above-paths 同时设置时超过任一阈值即询问；都不设置时每次都询问。"
+    )]
+    pub confirm_above_revs: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "待同步版本的变更路径总数超过 N 个时才询问确认"
+    )]
+    pub confirm_above_paths: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Git 提交说明标题行最多 N 个字符，超出部分移到正文"
+    )]
+    pub subject_width: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Git 提交说明正文每行最多 N 个字符，超出时在空白处换行"
+    )]
+    pub body_width: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "SVN 提交说明超过 N 个字符时截断并警告（默认 65536）"
+    )]
+    pub max_message_chars: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        help = "SVN 作者超过 N 个字符时截断并警告（默认 256）"
+    )]
+    pub max_author_chars: Option<usize>,
+
+    #[arg(
+        long,
+        help = "提交前把 svn:keywords 文件中展开的关键字还原为 $Id$ 等形式",
+        long_help = "提交前把设置了 svn:keywords 的文件中展开的关键字（如 $Id: a.c 12 2024-01-01 alice $）\n还原为 $Id$，避免每个版本的关键字变化都产生 Git 差异。只处理属性中列出的关键字及其别名。"
+    )]
+    pub strip_keywords: bool,
+
+    #[arg(
+        long,
+        help = "使用 SVN 版本时间作为 Git 提交的作者时间和提交时间",
+        long_help = "使用 SVN 版本时间（svn:date）作为 Git 提交的作者时间和提交时间。\n版本时间早于上一个版本时按 date-order 策略处理：keep 原样保留，warn 保留并警告（默认），\nclamp 调整为上一个版本时间加 1 秒；可通过 history policy <ID> --set date-order=clamp 修改。\n没有 svn:date 的版本使用当前时间提交。"
+    )]
+    pub preserve_dates: bool,
+
+    #[arg(
+        long,
+        value_name = "OFFSET",
+        requires = "preserve_dates",
+        allow_hyphen_values = true,
+        help = "保留的提交时间使用的时区偏移，如 +08:00（默认保留 SVN 的 UTC 时间）",
+        long_help = "保留的提交时间使用的时区偏移，格式为 +HH:MM 或 -HH:MM。\n只改变时间的显示时区，不改变时间点；不传时保留 SVN 服务器记录的 UTC 时间。需要 --preserve-dates。"
+    )]
+    pub date_timezone: Option<FixedOffset>,
+
+    #[arg(
+        long,
+        help = "使用 SVN 作者作为 Git 提交的作者",
+        long_help = "使用 SVN 作者作为 Git 提交的作者，邮箱为 <作者>@<SVN 仓库 UUID>；提交者仍为 Git 配置的用户。"
+    )]
+    pub preserve_authors: bool,
+
+    #[arg(
+        long = "service-account",
+        value_name = "NAME",
+        requires = "preserve_authors",
+        help = "共享的 SVN 服务账号（可重复），其提交按 --author-fallback 归属到真实作者"
+    )]
+    pub service_accounts: Vec<String>,
+
+    #[arg(
+        long = "author-fallback",
+        value_name = "RULE",
+        requires = "preserve_authors",
+        help = "SVN 作者缺失或为服务账号时的作者规则（可重复，按顺序匹配）",
+        long_help = "SVN 作者缺失或为 --service-account 指定的服务账号时，按顺序匹配的作者规则：\n- path:<目录>=<名称> <<邮箱>>: 版本的所有变更路径都在该目录下\n- message:<文本>=<名称> <<邮箱>>: 提交说明包含该文本\n没有匹配的规则时保留 SVN 作者。"
+    )]
+    pub author_fallbacks: Vec<AuthorRule>,
+
+    #[arg(
+        long,
+        help = "另一个同步正在使用同一个 Git 仓库时等待其结束（默认直接报错退出）"
+    )]
+    pub wait: bool,
+
+    #[arg(
+        long,
+        value_name = "TARGET",
+        help = "同步开始、完成和失败时发送通知（可重复）：stdout 或 http(s):// URL",
+        long_help = "同步开始、完成和失败时发送通知（可重复），dry-run 不通知：\n- stdout: 输出一行通知\n- http(s):// URL: 用 curl 以 JSON POST，包含 event（started、finished、failed）、svn_dir、git_dir，\n  完成时还有 revisions 和 duration_seconds，失败时还有 error\n通知失败只输出警告，不影响同步结果。"
+    )]
+    pub notify: Vec<NotifyTarget>,
+}
+
+impl CommitArgs {
+    /// 以配置方案中的参数作为默认值
+    ///
+    /// 开关参数任一方开启即开启，单值参数以命令行为准，
+    /// 可重复参数先使用配置方案中的值，再追加命令行中的值
+    ///
+    /// # 参数
+    ///
+    /// * `defaults`: 配置方案中的参数
+    pub fn with_defaults(self, defaults: CommitArgs) -> CommitArgs {
+        let concat = |mut defaults: Vec<String>, values: Vec<String>| {
+            defaults.extend(values);
+            defaults
+        };
+        let mut author_fallbacks = defaults.author_fallbacks;
+        author_fallbacks.extend(self.author_fallbacks);
+        CommitArgs {
+            manifest_dir: self.manifest_dir.or(defaults.manifest_dir),
+            git_notes: self.git_notes || defaults.git_notes,
+            changelist: self.changelist.or(defaults.changelist),
+            export: self.export || defaults.export,
+            skip_paths: concat(defaults.skip_paths, self.skip_paths),
+            skip_authors: concat(defaults.skip_authors, self.skip_authors),
+            merge_authors: concat(defaults.merge_authors, self.merge_authors),
+            list_changed_files: self.list_changed_files || defaults.list_changed_files,
+            git_svn_id: self.git_svn_id || defaults.git_svn_id,
+            auto_upgrade: self.auto_upgrade || defaults.auto_upgrade,
+            layout: self.layout || defaults.layout,
+            git_tags: self.git_tags || defaults.git_tags,
+            confirm_above_revs: self.confirm_above_revs.or(defaults.confirm_above_revs),
+            confirm_above_paths: self.confirm_above_paths.or(defaults.confirm_above_paths),
+            subject_width: self.subject_width.or(defaults.subject_width),
+            body_width: self.body_width.or(defaults.body_width),
+            max_message_chars: self.max_message_chars.or(defaults.max_message_chars),
+            max_author_chars: self.max_author_chars.or(defaults.max_author_chars),
+            strip_keywords: self.strip_keywords || defaults.strip_keywords,
+            preserve_dates: self.preserve_dates || defaults.preserve_dates,
+            date_timezone: self.date_timezone.or(defaults.date_timezone),
+            preserve_authors: self.preserve_authors || defaults.preserve_authors,
+            service_accounts: concat(defaults.service_accounts, self.service_accounts),
+            author_fallbacks,
+            wait: self.wait || defaults.wait,
+            notify: {
+                let mut notify = defaults.notify;
+                notify.extend(self.notify);
+                notify
+            },
+        }
+    }
+}
+
+/// 历史记录命令
+#[derive(Debug, Subcommand)]
+pub enum HistoryCommands {
+    /// 列出历史记录
+    #[command(about = "列出历史同步配置")]
+    List,
+
+    /// 按 ID 删除历史记录
+    #[command(
+        about = "删除指定索引的历史记录（索引可通过 history list 查看）",
+        long_about = "删除指定索引的历史记录，同时丢失该同步对的进度和版本映射。\n需要输入同步对标签（Git 目录名）确认；非交互时使用 --yes --confirm <标签>。"
+    )]
+    Delete {
+        id: usize,
+
+        #[arg(short, long, help = "非交互模式：不弹出确认，必须同时传入 --confirm")]
+        yes: bool,
+
+        #[arg(
+            long,
+            value_name = "LABEL",
+            help = "输入同步对标签（Git 目录名）确认删除"
+        )]
+        confirm: Option<String>,
+    },
+
+    /// 重新绑定仓库
+    #[command(
+        about = "解除指定历史记录绑定的 SVN 和 Git 仓库，下次同步时重新绑定",
+        long_about = "第一次同步时，同步对会绑定 SVN 仓库的 UUID 和 Git 仓库的根提交，之后每次同步前校验，\n目录被换成其他仓库的工作副本或克隆时拒绝同步。\n确认仓库确实应该更换（例如重新检出了迁移后的 SVN 仓库，或改写了 Git 历史）时执行本命令。"
+    )]
+    Rebind { id: usize },
+
+    /// 管理同步对的环境变量
+    #[command(
+        about = "查看或修改指定历史记录执行 svn/git 命令时使用的环境变量",
+        long_about = "为每个同步对单独配置执行 svn/git 命令时的环境变量，例如代理、SVN_SSH 或凭据助手。\n不带 --set/--unset 时列出当前配置。",
+        after_help = "示例:\n  svn2git history env 0 --set HTTPS_PROXY=http://proxy:8080\n  svn2git history env 0 --set \"SVN_SSH=ssh -i ~/.ssh/svn_key\"\n  svn2git history env 0 --unset HTTPS_PROXY"
+    )]
+    Env {
+        id: usize,
+
+        #[arg(long, value_name = "KEY=VALUE", help = "设置环境变量（可重复）")]
+        set: Vec<String>,
+
+        #[arg(long, value_name = "KEY", help = "移除环境变量（可重复）")]
+        unset: Vec<String>,
+    },
+
+    /// 管理同步对的同步策略
+    #[command(
+        about = "查看或修改指定历史记录的同步策略",
+        long_about = "同步策略决定同步过程中遇到特殊情况时的处理方式，保存在配置文件中。\n不带 --set 时列出当前策略。\n\n可用策略：\n- empty-revision: fail（默认）| skip | allow-empty —— 版本没有产生 Git 更改时\n- conflict: stop（默认）| reset —— Git 暂存区有冲突条目时\n- dirty-tree: ignore（默认）| fail | stash | ask —— 开始同步前 Git 工作目录有未提交更改时\n- error: stop（默认）| continue —— 某个版本同步失败时\n- push: never（默认）| always —— 同步完成后是否推送到 origin",
+        after_help = "示例:\n  svn2git history policy 0\n  svn2git history policy 0 --set empty-revision=skip --set error=continue"
+    )]
+    Policy {
+        id: usize,
+
+        #[arg(long, value_name = "NAME=VALUE", help = "修改策略（可重复）")]
+        set: Vec<String>,
+    },
+
+    /// 管理同步对的提交说明改写规则
+    #[command(
+        about = "查看或修改指定历史记录的提交说明改写规则",
+        long_about = "提交前按顺序用正则表达式替换 SVN 提交说明中的所有匹配，后面的规则作用于前面规则的结果。\n规则写作 PATTERN=>REPLACEMENT，替换内容可以用 $1、${name} 引用捕获组。\n不带 --add/--remove 时列出当前规则及其序号。",
+        after_help = "示例:\n  svn2git history rewrite 0\n  svn2git history rewrite 0 --add '#(\\d+)=>https://issues.example.com/$1'\n  svn2git history rewrite 0 --add '^\\[TICKET-\\d+\\]\\s*=>'\n  svn2git history rewrite 0 --remove 1"
+    )]
+    Rewrite {
+        id: usize,
+
+        #[arg(
+            long,
+            value_name = "PATTERN=>REPLACEMENT",
+            help = "追加改写规则（可重复）"
+        )]
+        add: Vec<RewriteRule>,
+
+        #[arg(long, value_name = "INDEX", help = "按序号移除改写规则")]
+        remove: Option<usize>,
+    },
+
+    /// 设置同步对的配置方案
+    #[command(
+        about = "查看或修改指定历史记录使用的配置方案",
+        after_help = "示例:\n  svn2git history profile 0\n  svn2git history profile 0 corp-default\n  svn2git history profile 0 --unset"
+    )]
+    Profile {
+        id: usize,
+
+        #[arg(conflicts_with = "unset", help = "配置方案名称")]
+        name: Option<String>,
+
+        #[arg(long, help = "不再使用配置方案")]
+        unset: bool,
+    },
+
+    /// 设置同步对的提交标题前缀
+    #[command(
+        about = "查看或修改指定历史记录的 Git 提交标题前缀",
+        long_about = "同步的每个版本生成的 Git 提交标题为「前缀 + SVN 提交说明」，默认前缀为 \"SVN: \"。\n前缀可以为空字符串，此时直接使用 SVN 提交说明。",
+        after_help = "示例:\n  svn2git history prefix 0\n  svn2git history prefix 0 '[svn] '\n  svn2git history prefix 0 ''\n  svn2git history prefix 0 --reset"
+    )]
+    Prefix {
+        id: usize,
+
+        #[arg(conflicts_with = "reset", allow_hyphen_values = true, help = "前缀")]
+        prefix: Option<String>,
+
+        #[arg(long, help = "恢复默认前缀 \"SVN: \"")]
+        reset: bool,
+    },
+
+    /// 设置同步对的监视间隔
+    #[command(
+        about = "查看或修改指定历史记录在 watch 中的同步间隔",
+        long_about = "watch 每轮只同步距上次同步已超过自身间隔的同步对，没有设置间隔的同步对使用 watch 的 --interval。\n正在运行的 watch 在下一轮重新读取间隔，不需要重启。",
+        after_help = "示例:\n  svn2git history schedule 0\n  svn2git history schedule 0 --interval 60\n  svn2git history schedule 0 --reset"
+    )]
+    Schedule {
+        id: usize,
+
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            conflicts_with = "reset",
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "两次同步之间的秒数"
+        )]
+        interval: Option<u64>,
+
+        #[arg(long, help = "恢复使用 watch 的 --interval")]
+        reset: bool,
+    },
+
+    /// 查看同步对的版本映射
+    #[command(
+        about = "查看指定历史记录中 SVN 版本与 Git 提交的对应关系",
+        after_help = "示例:\n  svn2git history map 0\n  svn2git history map 0 --rev 1234\n  svn2git history map 0 --marks svn.marks"
+    )]
+    Map {
+        id: usize,
+
+        #[arg(long, value_name = "REV", help = "只显示该 SVN 版本对应的 Git 提交")]
+        rev: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "导出为 git fast-import 的 marks 文件（:<版本> <提交>）"
+        )]
+        marks: Option<PathBuf>,
+    },
+}
+
+/// 配置方案命令
+#[derive(Debug, Subcommand)]
+pub enum ProfileCommands {
+    /// 保存配置方案
+    #[command(
+        about = "保存配置方案，同名方案会被覆盖",
+        long_about = "保存配置方案，同名方案会被覆盖。\n名称之后的参数与 sync 的提交参数格式相同，另外可用 --policy NAME=VALUE 设置同步策略（可重复）。",
+        after_help = "示例:\n  svn2git profile save corp-default --preserve-authors --skip-paths trunk/docs --policy error=continue"
+    )]
+    Save {
+        name: String,
+
+        #[arg(
+            value_name = "ARGS",
+            trailing_var_arg = true,
+            allow_hyphen_values = true,
+            help = "提交参数和 --policy NAME=VALUE"
+        )]
+        args: Vec<String>,
+    },
+
+    /// 列出配置方案
+    #[command(about = "列出所有配置方案")]
+    List,
+
+    /// 查看配置方案
+    #[command(about = "查看配置方案保存的参数")]
+    Show { name: String },
+
+    /// 删除配置方案
+    #[command(about = "删除配置方案")]
+    Delete { name: String },
+}
+
+/// 服务命令
+#[derive(Debug, Subcommand)]
+pub enum ServiceCommands {
+    /// 安装服务
+    #[command(about = "安装 systemd 用户服务或 Windows 服务")]
+    Install {
+        #[arg(long, default_value = "svn2git", help = "服务名称")]
+        name: String,
+
+        #[command(flatten)]
+        options: ServiceOptions,
+    },
+
+    /// 卸载服务
+    #[command(about = "删除已安装的 systemd 用户服务或 Windows 服务")]
+    Uninstall {
+        #[arg(long, default_value = "svn2git", help = "服务名称")]
+        name: String,
+    },
+
+    /// 在前台运行服务
+    #[command(about = "在前台运行 watch，输出写入日志文件，异常退出后自动重启")]
+    Run {
+        #[command(flatten)]
+        options: ServiceOptions,
+
+        #[arg(
+            long,
+            value_name = "NAME",
+            hide = true,
+            help = "由 Windows 服务控制管理器启动，NAME 为注册的服务名称"
+        )]
+        windows_service: Option<String>,
+    },
+}
+
+/// 服务运行参数
+#[derive(Debug, Clone, Args)]
+pub struct ServiceOptions {
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "两轮检查之间的等待秒数（默认同 watch）"
+    )]
+    pub interval: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "日志文件路径，相对路径相对工作目录（默认 svn2git.log）"
+    )]
+    pub log: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "工作目录，即 config.json 所在目录（默认当前目录）"
+    )]
+    pub working_dir: Option<PathBuf>,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use clap::Parser;
+    use clap::error::ErrorKind;
+    use std::path::PathBuf;
+
+    use super::{Cli, Commands, HistoryCommands, ProfileCommands, ServiceCommands};
+
+    #[test]
+    fn test_parse_sync_command_with_paths() {
+        let cli = Cli::parse_from([
+            "svn2git",
+            "sync",
+            "--svn-dir",
+            "d:/svn",
+            "--git-dir",
+            "d:/git",
+        ]);
+
+        match cli.command {
+            Commands::Sync {
+                svn_dir,
+                git_dir,
+                limit,
+                dry_run,
+                yes,
+                ..
+            } => {
+                assert_eq!(svn_dir, Some(PathBuf::from("d:/svn")));
+                assert_eq!(git_dir, Some(PathBuf::from("d:/git")));
+                assert_eq!(limit, None);
+                assert!(!dry_run);
+                assert!(!yes);
+            }
+            _ => panic!("应解析为 Sync 命令"),
+        }
+    }
+
+    #[test]
+    fn test_parse_sync_command_with_safety_options() {
+        let cli = Cli::parse_from([
+            "svn2git",
+            "sync",
+            "--limit",
+            "5",
+            "--dry-run",
+            "--svn-dir",
+            "d:/svn",
+        ]);
+
+        match cli.command {
+            Commands::Sync {
+                svn_dir,
+                git_dir,
+                limit,
+                dry_run,
+                yes,
+                ..
+            } => {
+                assert_eq!(svn_dir, Some(PathBuf::from("d:/svn")));
+                assert_eq!(git_dir, None);
+                assert_eq!(limit, Some(5));
+                assert!(dry_run);
+                assert!(!yes);
+            }
+            _ => panic!("应解析为 Sync 命令"),
+        }
+    }
+
+    #[test]
+    fn test_parse_command_timeouts() {
+        let cli = Cli::parse_from([
+            "svn2git",
+            "sync",
+            "--svn-timeout",
+            "600",
+            "--git-timeout=60",
+        ]);
+        assert_eq!(cli.svn_timeout, Some(600));
+        assert_eq!(cli.git_timeout, Some(60));
+        assert!(Cli::try_parse_from(["svn2git", "--svn-timeout", "abc", "sync"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_svn_non_interactive_options() {
+        let cli = Cli::parse_from([
+            "svn2git",
+            "sync",
+            "--svn-non-interactive",
+            "--trust-server-cert-failures",
+            "unknown-ca,cn-mismatch",
+        ]);
+        assert!(cli.svn_non_interactive);
+        assert_eq!(
+            cli.trust_server_cert_failures,
+            ["unknown-ca", "cn-mismatch"]
+        );
+        assert!(
+            Cli::try_parse_from([
+                "svn2git",
+                "sync",
+                "--trust-server-cert-failures",
+                "bad-cert"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_global_verbose_option() {
+        assert!(!Cli::parse_from(["svn2git", "sync"]).verbose);
+        assert!(Cli::parse_from(["svn2git", "sync", "--verbose"]).verbose);
+        assert!(Cli::parse_from(["svn2git", "--verbose", "sync-all"]).verbose);
+    }
+
+    #[test]
+    fn test_parse_retry_command() {
+        let cli = Cli::parse_from(["svn2git", "retry", "1234", "--id", "1"]);
+        match cli.command {
+            Commands::Retry { rev, id, .. } => {
+                assert_eq!(rev, "1234");
+                assert_eq!(id, Some(1));
+            }
+            _ => panic!("应解析为 Retry 命令"),
+        }
+    }
+
+    #[test]
+    fn test_parse_preview_filters_command() {
+        let cli = Cli::parse_from([
+            "svn2git",
+            "preview-filters",
+            "2",
+            "--all",
+            "--skip-paths",
+            "trunk/docs",
+            "--skip-author",
+            "buildbot",
+        ]);
+        match cli.command {
+            Commands::PreviewFilters { id, all, commit } => {
+                assert_eq!(id, 2);
+                assert!(all);
+                assert_eq!(commit.skip_paths, ["trunk/docs"]);
+                assert_eq!(commit.skip_authors, ["buildbot"]);
+            }
+            _ => panic!("应解析为 PreviewFilters 命令"),
+        }
+    }
+
+    #[test]
+    fn test_parse_history_list_command() {
+        let cli = Cli::parse_from(["svn2git", "history", "list"]);
+        match cli.command {
+            Commands::History { command } => match command {
+                HistoryCommands::List => {}
+                _ => panic!("应解析为 History List"),
+            },
+            _ => panic!("应解析为 History 命令"),
+        }
+    }
+
+    #[test]
+    fn test_parse_history_delete_command() {
+        let cli = Cli::parse_from(["svn2git", "history", "delete", "3"]);
+        match cli.command {
+            Commands::History { command } => match command {
+                HistoryCommands::Delete { id, yes, confirm } => {
+                    assert_eq!(id, 3);
+                    assert!(!yes);
+                    assert_eq!(confirm, None);
+                }
+                _ => panic!("应解析为 History Delete"),
+            },
+            _ => panic!("应解析为 History 命令"),
+        }
+    }
+
+    #[test]
+    fn test_parse_sync_command_with_date_window() {
+        let cli = Cli::parse_from([
+            "svn2git",
+            "sync",
+            "--since",
+            "2024-01-01",
+            "--until",
+            "2024-06-30",
+        ]);
+        match cli.command {
+            Commands::Sync { since, until, .. } => {
+                assert_eq!(since, NaiveDate::from_ymd_opt(2024, 1, 1));
+                assert_eq!(until, NaiveDate::from_ymd_opt(2024, 6, 30));
+            }
+            _ => panic!("应解析为 Sync 命令"),
+        }
+
+        assert!(Cli::try_parse_from(["svn2git", "sync", "--since", "2024/01/01"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_sync_command_with_manifest_dir() {
+        let cli = Cli::parse_from([
+            "svn2git",
+            "sync",
+            "--manifest-dir",
+            "reports",
+            "--export",
+            "--skip-paths",
+            "trunk/docs",
+            "--skip-paths",
+            "**/*.md",
+            "--skip-author",
+            "buildbot",
+            "--list-changed-files",
+            "--layout",
+            "--git-tags",
+        ]);
+        match cli.command {
+            Commands::Sync { commit, .. } => {
+                assert_eq!(commit.manifest_dir, Some(PathBuf::from("reports")));
+                assert!(commit.export);
+                assert_eq!(commit.skip_paths, vec!["trunk/docs", "**/*.md"]);
+                assert_eq!(commit.skip_authors, vec!["buildbot"]);
+                assert!(commit.list_changed_files);
+                assert!(commit.layout);
+                assert!(commit.git_tags);
+            }
+            _ => panic!("应解析为 Sync 命令"),
+        }
+    }
+
+    #[test]
+    fn test_parse_sync_command_with_svn_url() {
+        let cli = Cli::parse_from([
+            "svn2git",
+            "sync",
+            "--svn-url",
+            "https://svn.example.com/repo/trunk",
+            "--log-batch",
+            "50",
+        ]);
+        match cli.command {
+            Commands::Sync {
+                svn_url, log_batch, ..
+            } => {
+                assert_eq!(
+                    svn_url.as_deref(),
+                    Some("https://svn.example.com/repo/trunk")
+                );
+                assert_eq!(log_batch.map(|n| n.get()), Some(50));
+            }
+            _ => panic!("应解析为 Sync 命令"),
+        }
+        assert!(Cli::try_parse_from(["svn2git", "sync", "--log-batch", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_sync_command_with_dirty_tree() {
+        let cli = Cli::parse_from(["svn2git", "sync", "--dirty-tree", "stash"]);
+        match cli.command {
+            Commands::Sync {
+                dirty_tree, force, ..
+            } => {
+                assert_eq!(dirty_tree.as_deref(), Some("stash"));
+                assert!(!force);
+            }
+            _ => panic!("应解析为 Sync 命令"),
+        }
+        assert!(
+            Cli::try_parse_from(["svn2git", "sync", "--dirty-tree", "fail", "--force"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_sync_command_with_push() {
+        let cli = Cli::parse_from([
+            "svn2git",
+            "sync",
+            "--remote",
+            "git@example.com:team/repo.git",
+            "--push",
+        ]);
+        match cli.command {
+            Commands::Sync { remote, push, .. } => {
+                assert_eq!(remote.as_deref(), Some("git@example.com:team/repo.git"));
+                assert!(push);
+            }
+            _ => panic!("应解析为 Sync 命令"),
+        }
+    }
+
+    #[test]
+    fn test_parse_resume_command_with_changelist() {
+        let cli = Cli::parse_from([
+            "svn2git",
+            "resume",
+            "1",
+            "--changelist",
+            "ui",
+            "--git-notes",
+        ]);
+        match cli.command {
+            Commands::Resume { id, commit } => {
+                assert_eq!(id, Some(1));
+                assert_eq!(commit.changelist.as_deref(), Some("ui"));
+                assert!(commit.git_notes);
+                assert!(!commit.export);
+            }
+            _ => panic!("应解析为 Resume 命令"),
+        }
+    }
+
+    #[test]
+    fn test_parse_sync_command_non_interactive() {
+        for flag in ["--yes", "-y", "--non-interactive", "--no-input"] {
+            let cli = Cli::parse_from(["svn2git", "sync", flag]);
+            match cli.command {
+                Commands::Sync { yes, .. } => assert!(yes, "{flag} 应开启非交互模式"),
+                _ => panic!("应解析为 Sync 命令"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_resume_command() {
+        let cli = Cli::parse_from(["svn2git", "resume"]);
+        assert!(matches!(cli.command, Commands::Resume { id: None, .. }));
+
+        let cli = Cli::parse_from(["svn2git", "resume", "2"]);
+        assert!(matches!(cli.command, Commands::Resume { id: Some(2), .. }));
+    }
+
+    #[test]
+    fn test_parse_rollback_command() {
+        let cli = Cli::parse_from(["svn2git", "rollback"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Rollback {
+                id: None,
+                yes: false,
+                confirm: None
+            }
+        ));
+
+        let cli = Cli::parse_from(["svn2git", "rollback", "2", "--yes", "--confirm", "git"]);
+        match cli.command {
+            Commands::Rollback { id, yes, confirm } => {
+                assert_eq!(id, Some(2));
+                assert!(yes);
+                assert_eq!(confirm.as_deref(), Some("git"));
+            }
+            _ => panic!("应解析为 Rollback 命令"),
+        }
+
+        let cli = Cli::parse_from(["svn2git", "sync", "--rollback-on-failure"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Sync {
+                rollback_on_failure: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_doctor_command() {
+        let cli = Cli::parse_from(["svn2git", "doctor"]);
+        assert!(matches!(cli.command, Commands::Doctor { config: None }));
+
+        let cli = Cli::parse_from(["svn2git", "doctor", "--config", "d:/mirrors.json"]);
+        match cli.command {
+            Commands::Doctor { config } => {
+                assert_eq!(config, Some(PathBuf::from("d:/mirrors.json")))
+            }
+            _ => panic!("应解析为 Doctor 命令"),
+        }
+    }
+
+    #[test]
+    fn test_parse_pause_command() {
+        let cli = Cli::parse_from(["svn2git", "pause", "2"]);
+        assert!(matches!(cli.command, Commands::Pause { id: 2 }));
+        assert!(Cli::try_parse_from(["svn2git", "pause"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_unpause_command() {
+        let cli = Cli::parse_from(["svn2git", "unpause", "2"]);
+        assert!(matches!(cli.command, Commands::Unpause { id: 2 }));
+        assert!(Cli::try_parse_from(["svn2git", "unpause"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_status_command() {
+        let cli = Cli::parse_from(["svn2git", "status", "1"]);
+        assert!(matches!(cli.command, Commands::Status { id: 1 }));
+        assert!(Cli::try_parse_from(["svn2git", "status"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_audit_command() {
+        let cli = Cli::parse_from(["svn2git", "audit", "0", "--sample", "20"]);
+        match cli.command {
+            Commands::Audit { id, sample, .. } => {
+                assert_eq!(id, 0);
+                assert_eq!(sample, Some(20));
+            }
+            _ => panic!("应解析为 Audit 命令"),
+        }
+    }
+
+    #[test]
+    fn test_parse_audit_command_with_filters() {
+        let cli = Cli::parse_from([
+            "svn2git",
+            "audit",
+            "1",
+            "--include",
+            "trunk/src",
+            "--exclude",
+            "**/*.bin",
+            "--jobs",
+            "8",
+        ]);
+        match cli.command {
+            Commands::Audit {
+                include,
+                exclude,
+                jobs,
+                ..
+            } => {
+                assert_eq!(include, vec!["trunk/src"]);
+                assert_eq!(exclude, vec!["**/*.bin"]);
+                assert_eq!(jobs.map(std::num::NonZeroUsize::get), Some(8));
+            }
+            _ => panic!("应解析为 Audit 命令"),
+        }
+        assert!(Cli::try_parse_from(["svn2git", "audit", "1", "--jobs", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_sync_all_command() {
+        let cli = Cli::parse_from(["svn2git", "sync-all"]);
+        match cli.command {
+            Commands::SyncAll { config, yes, .. } => {
+                assert_eq!(config, None);
+                assert!(!yes);
+            }
+            _ => panic!("应解析为 SyncAll 命令"),
+        }
+
+        let cli = Cli::parse_from([
+            "svn2git",
+            "sync-all",
+            "--config",
+            "d:/mirrors.json",
+            "-y",
+            "-j",
+            "4",
+        ]);
+        match cli.command {
+            Commands::SyncAll {
+                config, yes, jobs, ..
+            } => {
+                assert_eq!(config, Some(PathBuf::from("d:/mirrors.json")));
+                assert!(yes);
+                assert_eq!(jobs.map(|n| n.get()), Some(4));
+            }
+            _ => panic!("应解析为 SyncAll 命令"),
+        }
+    }
+
+    #[test]
+    fn test_parse_watch_command() {
+        let cli = Cli::parse_from(["svn2git", "watch"]);
+        match cli.command {
+            Commands::Watch {
+                svn_dir, interval, ..
+            } => {
+                assert_eq!(svn_dir, None);
+                assert_eq!(interval, 300);
+            }
+            _ => panic!("应解析为 Watch 命令"),
+        }
+
+        let cli = Cli::parse_from([
+            "svn2git",
+            "watch",
+            "--svn-dir",
+            "d:/svn",
+            "--git-dir",
+            "d:/git",
+            "--interval",
+            "60",
+        ]);
+        match cli.command {
+            Commands::Watch {
+                git_dir, interval, ..
+            } => {
+                assert_eq!(git_dir, Some(PathBuf::from("d:/git")));
+                assert_eq!(interval, 60);
+            }
+            _ => panic!("应解析为 Watch 命令"),
+        }
+        assert!(Cli::try_parse_from(["svn2git", "watch", "--svn-dir", "d:/svn"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_profile_commands() {
+        let cli = Cli::parse_from([
+            "svn2git",
+            "profile",
+            "save",
+            "corp-default",
+            "--preserve-authors",
+            "--policy",
+            "error=continue",
+        ]);
+        match cli.command {
+            Commands::Profile {
+                command: ProfileCommands::Save { name, args },
+            } => {
+                assert_eq!(name, "corp-default");
+                assert_eq!(args, ["--preserve-authors", "--policy", "error=continue"]);
+            }
+            _ => panic!("应解析为 Profile Save"),
+        }
+
+        let cli = Cli::parse_from(["svn2git", "sync", "--profile", "corp-default"]);
+        match cli.command {
+            Commands::Sync { profile, .. } => assert_eq!(profile.as_deref(), Some("corp-default")),
+            _ => panic!("应解析为 Sync 命令"),
+        }
+
+        assert!(
+            Cli::try_parse_from(["svn2git", "history", "profile", "0", "corp", "--unset"]).is_err()
+        );
+
+        let cli = Cli::parse_from(["svn2git", "sync", "--message-prefix", ""]);
+        match cli.command {
+            Commands::Sync { message_prefix, .. } => {
+                assert_eq!(message_prefix.as_deref(), Some(""))
+            }
+            _ => panic!("应解析为 Sync 命令"),
+        }
+        let cli = Cli::parse_from(["svn2git", "history", "prefix", "0", "[svn] "]);
+        match cli.command {
+            Commands::History {
+                command: HistoryCommands::Prefix { id, prefix, reset },
+            } => {
+                assert_eq!(id, 0);
+                assert_eq!(prefix.as_deref(), Some("[svn] "));
+                assert!(!reset);
+            }
+            _ => panic!("应解析为 History Prefix"),
+        }
+        assert!(
+            Cli::try_parse_from(["svn2git", "history", "prefix", "0", "x", "--reset"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_history_schedule_command() {
+        let cli = Cli::parse_from(["svn2git", "history", "schedule", "0", "--interval", "60"]);
+        match cli.command {
+            Commands::History {
+                command:
+                    HistoryCommands::Schedule {
+                        id,
+                        interval,
+                        reset,
+                    },
+            } => {
+                assert_eq!(id, 0);
+                assert_eq!(interval, Some(60));
+                assert!(!reset);
+            }
+            _ => panic!("应解析为 History Schedule"),
+        }
+        assert!(
+            Cli::try_parse_from(["svn2git", "history", "schedule", "0", "--interval", "0"])
+                .is_err()
+        );
+        assert!(
+            Cli::try_parse_from([
+                "svn2git",
+                "history",
+                "schedule",
+                "0",
+                "--interval",
+                "60",
+                "--reset"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_lint_rules_command() {
+        let cli = Cli::parse_from([
+            "svn2git",
+            "lint-rules",
+            "--profile",
+            "corp-default",
+            "--skip-paths",
+            "trunk/docs",
+        ]);
+        match cli.command {
+            Commands::LintRules { profile, commit } => {
+                assert_eq!(profile.as_deref(), Some("corp-default"));
+                assert_eq!(commit.skip_paths, ["trunk/docs"]);
+            }
+            _ => panic!("应解析为 LintRules 命令"),
+        }
+    }
+
+    #[test]
+    fn test_parse_service_install_command() {
+        let cli = Cli::parse_from(["svn2git", "service", "install"]);
+        match cli.command {
+            Commands::Service {
+                command: ServiceCommands::Install { name, options },
+            } => {
+                assert_eq!(name, "svn2git");
+                assert_eq!(options.interval, None);
+            }
+            _ => panic!("应解析为 Service Install"),
+        }
+    }
+
+    #[test]
+    fn test_parse_service_run_command() {
+        let cli = Cli::parse_from([
+            "svn2git",
+            "service",
+            "run",
+            "--interval",
+            "60",
+            "--log",
+            "logs/svn2git.log",
+        ]);
+        match cli.command {
+            Commands::Service {
+                command:
+                    ServiceCommands::Run {
+                        options,
+                        windows_service,
+                    },
+            } => {
+                assert_eq!(options.interval, Some(60));
+                assert_eq!(options.log, Some(PathBuf::from("logs/svn2git.log")));
+                assert_eq!(windows_service, None);
+            }
+            _ => panic!("应解析为 Service Run"),
+        }
+    }
+
+    #[test]
+    fn test_parse_history_env_command() {
+        let cli = Cli::parse_from([
+            "svn2git", "history", "env", "1", "--set", "A=1", "--set", "B=2", "--unset", "C",
+        ]);
+        match cli.command {
+            Commands::History {
+                command: HistoryCommands::Env { id, set, unset },
+            } => {
+                assert_eq!(id, 1);
+                assert_eq!(set, vec!["A=1", "B=2"]);
+                assert_eq!(unset, vec!["C"]);
+            }
+            _ => panic!("应解析为 History Env"),
+        }
+    }
+
+    #[test]
+    fn test_parse_history_map_command() {
+        let cli = Cli::parse_from(["svn2git", "history", "map", "2", "--rev", "1234"]);
+        match cli.command {
+            Commands::History {
+                command: HistoryCommands::Map { id, rev, marks },
+            } => {
+                assert_eq!(id, 2);
+                assert_eq!(rev.as_deref(), Some("1234"));
+                assert_eq!(marks, None);
+            }
+            _ => panic!("应解析为 History Map"),
+        }
+    }
+
+    #[test]
+    fn test_help_contains_examples() {
+        let err = Cli::try_parse_from(["svn2git", "--help"]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::DisplayHelp);
+        let msg = err.to_string();
+        assert!(msg.contains("示例:"));
+        assert!(msg.contains("svn2git sync"));
+        assert!(msg.contains("history list"));
+    }
+
+    #[test]
+    fn test_parse_ephemeral_global_flag() {
+        let cli = Cli::parse_from(["svn2git", "history", "list", "--ephemeral"]);
+        assert!(cli.ephemeral);
+        let cli = Cli::parse_from(["svn2git", "history", "list"]);
+        assert!(!cli.ephemeral);
+    }
+
+    #[test]
+    fn test_parse_git_tags_requires_layout() {
+        assert!(Cli::try_parse_from(["svn2git", "sync", "--git-tags"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_date_timezone() {
+        let cli = Cli::parse_from([
+            "svn2git",
+            "sync",
+            "--preserve-dates",
+            "--date-timezone",
+            "-05:30",
+        ]);
+        match cli.command {
+            Commands::Sync { commit, .. } => {
+                let offset = commit.date_timezone.unwrap();
+                assert_eq!(offset.local_minus_utc(), -(5 * 3600 + 30 * 60));
+            }
+            _ => panic!("应解析为 sync 子命令"),
+        }
+        assert!(Cli::try_parse_from(["svn2git", "sync", "--date-timezone", "+08:00"]).is_err());
+        assert!(
+            Cli::try_parse_from([
+                "svn2git",
+                "sync",
+                "--preserve-dates",
+                "--date-timezone",
+                "CST"
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_history_policy_command() {
+        let cli = Cli::parse_from([
+            "svn2git",
+            "history",
+            "policy",
+            "2",
+            "--set",
+            "error=continue",
+        ]);
+        match cli.command {
+            Commands::History {
+                command: HistoryCommands::Policy { id, set },
+            } => {
+                assert_eq!(id, 2);
+                assert_eq!(set, vec!["error=continue"]);
+            }
+            _ => panic!("应解析为 history policy 命令"),
+        }
+    }
+
+    #[test]
+    fn test_parse_history_rewrite_command() {
+        let cli = Cli::parse_from([
+            "svn2git",
+            "history",
+            "rewrite",
+            "1",
+            "--add",
+            "#(\\d+)=>ISSUE-$1",
+            "--remove",
+            "0",
+        ]);
+        match cli.command {
+            Commands::History {
+                command: HistoryCommands::Rewrite { id, add, remove },
+            } => {
+                assert_eq!(id, 1);
+                assert_eq!(add.len(), 1);
+                assert_eq!(add[0].apply("fix #12"), "fix ISSUE-12");
+                assert_eq!(remove, Some(0));
+            }
+            _ => panic!("应解析为 history rewrite 命令"),
+        }
+
+        assert!(
+            Cli::try_parse_from(["svn2git", "history", "rewrite", "1", "--add", "(=>x"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_sync_command_with_only_revs() {
+        let cli = Cli::parse_from(["svn2git", "sync", "--only-revs", "120,125,130-140"]);
+        match cli.command {
+            Commands::Sync { only_revs, .. } => {
+                assert_eq!(only_revs.unwrap().to_string(), "r120、r125、r130-r140");
+            }
+            _ => panic!("应解析为 Sync 命令"),
+        }
+        assert!(Cli::try_parse_from(["svn2git", "sync", "--only-revs", "140-130"]).is_err());
     }
 }
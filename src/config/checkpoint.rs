@@ -0,0 +1,113 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 同步开始前的状态，用于回滚未完成的同步
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SyncStartState {
+    /// Git 仓库的 HEAD，`None` 表示还没有任何提交
+    pub git_head: Option<String>,
+    /// SVN 工作副本的版本
+    pub svn_rev: String,
+    /// 最后一次成功同步的 SVN 版本
+    pub last_synced_rev: Option<String>,
+    /// 与最后同步版本对应的 Git 提交哈希
+    pub last_git_hash: Option<String>,
+    /// 版本映射表中的映射数量
+    pub mapped_revisions: usize,
+}
+
+/// 同步检查点
+///
+/// 在开始同步时记录本次计划同步的全部版本，每完成一个版本就从中移除。
+/// 同步中断后，剩余的版本即为需要恢复的部分。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SyncCheckpoint {
+    /// 尚未完成的版本（按同步顺序）
+    pending: Vec<String>,
+    /// 本次同步开始时间
+    started_at: DateTime<Utc>,
+    /// 同步开始前的状态，旧版本保存的检查点没有该字段
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    start: Option<SyncStartState>,
+}
+
+impl SyncCheckpoint {
+    /// 创建新的检查点
+    ///
+    /// # 参数
+    ///
+    /// * `revisions`: 本次计划同步的版本
+    pub fn new(revisions: Vec<String>) -> Self {
+        Self::new_at(revisions, Utc::now())
+    }
+
+    /// 创建指定开始时间的检查点
+    ///
+    /// # 参数
+    ///
+    /// * `revisions`: 本次计划同步的版本
+    /// * `started_at`: 同步开始时间
+    pub fn new_at(revisions: Vec<String>, started_at: DateTime<Utc>) -> Self {
+        Self {
+            pending: revisions,
+            started_at,
+            start: None,
+        }
+    }
+
+    /// 记录同步开始前的状态
+    ///
+    /// # 参数
+    ///
+    /// * `start`: 同步开始前的状态
+    pub fn with_start(mut self, start: SyncStartState) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// 同步开始前的状态
+    pub fn start(&self) -> Option<&SyncStartState> {
+        self.start.as_ref()
+    }
+
+    /// 尚未完成的版本
+    pub fn pending(&self) -> &[String] {
+        &self.pending
+    }
+
+    /// 本次同步开始时间
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    /// 标记版本已完成
+    ///
+    /// # 参数
+    ///
+    /// * `rev`: 已完成的版本
+    pub fn complete(&mut self, rev: &str) {
+        self.pending.retain(|r| r != rev);
+    }
+
+    /// 是否所有版本都已完成
+    pub fn is_finished(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_complete() {
+        let mut checkpoint = SyncCheckpoint::new(vec!["1".into(), "2".into()]);
+        assert!(!checkpoint.is_finished());
+
+        checkpoint.complete("1");
+        assert_eq!(checkpoint.pending(), ["2".to_string()]);
+
+        checkpoint.complete("2");
+        assert!(checkpoint.is_finished());
+    }
+}
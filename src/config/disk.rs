@@ -1,39 +1,91 @@
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use crate::{
-    config::{FileStorage, HistoryRecord},
-    error::{Result, SyncError},
+    config::{HISTORY_KEY, HISTORY_NAMESPACE, StateStore, validate_store_key},
+    error::Result,
 };
 
 /// 实际文件存储
+///
+/// 每个键保存为一个文件：`<目录>/<命名空间>/<键>`，目录为历史记录文件所在的目录。
+/// 历史记录仍保存在创建时指定的文件中，兼容已有的配置文件。
 pub struct DiskStorage {
     path: PathBuf,
 }
 
 impl DiskStorage {
     /// 创建一个新的存储
+    ///
+    /// # 参数
+    ///
+    /// * `path`: 历史记录文件路径
     pub fn new(path: PathBuf) -> Self {
         Self { path }
     }
-}
 
-impl FileStorage for DiskStorage {
-    fn load(&self) -> Result<Vec<HistoryRecord>> {
-        if !self.path.exists() {
-            return Ok(Vec::new());
+    /// 状态文件所在的根目录
+    fn root(&self) -> &Path {
+        self.path.parent().unwrap_or(Path::new(""))
+    }
+
+    /// 键对应的文件路径
+    fn key_path(&self, namespace: &str, key: &str) -> Result<PathBuf> {
+        if namespace == HISTORY_NAMESPACE && key == HISTORY_KEY {
+            return Ok(self.path.clone());
         }
+        validate_store_key(namespace, key)?;
+        Ok(self.root().join(namespace).join(key))
+    }
+}
 
-        let buf = fs::read(&self.path)?;
-        serde_json::from_slice(&buf).map_err(SyncError::Json)
+impl StateStore for DiskStorage {
+    fn get(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.key_path(namespace, key)?;
+        match fs::read(&path) {
+            Ok(buf) => Ok(Some(buf)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
     }
 
-    fn save(&self, records: &[HistoryRecord]) -> Result<()> {
-        if let Some(parent) = self.path.parent() {
+    fn put(&self, namespace: &str, key: &str, value: &[u8]) -> Result<()> {
+        let path = self.key_path(namespace, key)?;
+        if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
         }
+        fs::write(&path, value)?;
+        Ok(())
+    }
 
-        let buf = serde_json::to_vec(records)?;
-        fs::write(&self.path, &buf).map_err(SyncError::Io)
+    fn remove(&self, namespace: &str, key: &str) -> Result<()> {
+        let path = self.key_path(namespace, key)?;
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn keys(&self, namespace: &str) -> Result<Vec<String>> {
+        validate_store_key(namespace, "_")?;
+        let mut keys = Vec::new();
+        if namespace == HISTORY_NAMESPACE && self.path.exists() {
+            keys.push(HISTORY_KEY.to_string());
+        }
+        let dir = self.root().join(namespace);
+        if dir.is_dir() {
+            for entry in fs::read_dir(dir)? {
+                let entry = entry?;
+                if entry.path().is_file() {
+                    keys.push(entry.file_name().to_string_lossy().to_string());
+                }
+            }
+        }
+        keys.sort();
+        keys.dedup();
+        Ok(keys)
     }
 }
 
@@ -42,6 +94,7 @@ mod tests {
     use chrono::{TimeZone, Utc};
 
     use super::*;
+    use crate::config::{FileStorage, HistoryRecord};
 
     #[test]
     fn test_disk_storage() {
@@ -67,4 +120,26 @@ mod tests {
         let records_loaded = storage.load().unwrap();
         assert_eq!(records, records_loaded);
     }
+
+    #[test]
+    fn test_disk_storage_namespaced_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = DiskStorage::new(dir.path().join("config.json"));
+
+        assert_eq!(storage.get("cache", "r1").unwrap(), None);
+        storage.put("cache", "r1", b"one").unwrap();
+        storage.put("cache", "r2", b"two").unwrap();
+        storage.save(&[]).unwrap();
+
+        assert_eq!(storage.get("cache", "r1").unwrap(), Some(b"one".to_vec()));
+        assert_eq!(storage.keys("cache").unwrap(), vec!["r1", "r2"]);
+        assert_eq!(storage.keys(HISTORY_NAMESPACE).unwrap(), vec![HISTORY_KEY]);
+        // 历史记录仍写在原来的配置文件中
+        assert_eq!(fs::read(dir.path().join("config.json")).unwrap(), b"[]");
+
+        storage.remove("cache", "r1").unwrap();
+        storage.remove("cache", "r1").unwrap();
+        assert_eq!(storage.keys("cache").unwrap(), vec!["r2"]);
+        assert!(storage.put("cache", "../escape", b"x").is_err());
+    }
 }
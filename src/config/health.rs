@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 连续失败退避策略
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackoffPolicy {
+    /// 第一次失败后的等待时间
+    pub base_delay: Duration,
+    /// 等待时间上限
+    pub max_delay: Duration,
+    /// 连续失败多少次后标记为异常并发出告警
+    pub unhealthy_threshold: u32,
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        Self {
+            base_delay: Duration::from_secs(60),
+            max_delay: Duration::from_secs(60 * 60),
+            unhealthy_threshold: 3,
+        }
+    }
+}
+
+impl BackoffPolicy {
+    /// 计算第 `failures` 次连续失败后的等待时间（指数退避）
+    ///
+    /// # 参数
+    ///
+    /// * `failures`: 连续失败次数
+    pub fn delay_for(&self, failures: u32) -> Duration {
+        if failures == 0 {
+            return Duration::ZERO;
+        }
+        let factor = 2u32.saturating_pow(failures - 1);
+        self.base_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |d| d.min(self.max_delay))
+    }
+}
+
+/// 一次失败的处理结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailureOutcome {
+    /// 下次重试前需要等待的时间
+    pub backoff: Duration,
+    /// 是否需要发出告警（每次进入异常状态只会为 true 一次）
+    pub should_alert: bool,
+}
+
+/// 同步对的健康状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PairHealth {
+    consecutive_failures: u32,
+    alerted: bool,
+    last_error: Option<String>,
+    /// 退避结束时间，之前的监视轮次跳过该同步对
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    retry_after: Option<DateTime<Utc>>,
+}
+
+impl PairHealth {
+    /// 记录一次失败
+    ///
+    /// # 参数
+    ///
+    /// * `error`: 失败原因
+    /// * `policy`: 退避策略
+    pub fn record_failure(&mut self, error: &str, policy: &BackoffPolicy) -> FailureOutcome {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        self.last_error = Some(error.to_string());
+
+        let should_alert = !self.alerted && self.consecutive_failures >= policy.unhealthy_threshold;
+        if should_alert {
+            self.alerted = true;
+        }
+
+        FailureOutcome {
+            backoff: policy.delay_for(self.consecutive_failures),
+            should_alert,
+        }
+    }
+
+    /// 记录一次成功，重置失败计数
+    ///
+    /// # 返回
+    ///
+    /// 如果之前处于已告警的异常状态，返回 `true`（即已恢复）
+    pub fn record_success(&mut self) -> bool {
+        let recovered = self.alerted;
+        *self = Self::default();
+        recovered
+    }
+
+    /// 连续失败次数
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    /// 最近一次失败原因
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    /// 是否处于异常状态（已达到告警阈值）
+    pub fn is_unhealthy(&self) -> bool {
+        self.alerted
+    }
+
+    /// 设置退避结束时间
+    ///
+    /// # 参数
+    ///
+    /// * `at`: 在此之前不再重试
+    pub fn set_retry_after(&mut self, at: DateTime<Utc>) {
+        self.retry_after = Some(at);
+    }
+
+    /// 退避结束时间，`now` 时已结束或没有失败时返回 `None`
+    ///
+    /// # 参数
+    ///
+    /// * `now`: 当前时间
+    pub fn backing_off_until(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.retry_after.filter(|at| *at > now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> BackoffPolicy {
+        BackoffPolicy {
+            base_delay: Duration::from_secs(10),
+            max_delay: Duration::from_secs(60),
+            unhealthy_threshold: 3,
+        }
+    }
+
+    #[test]
+    fn test_delay_for_should_grow_exponentially_and_cap() {
+        let policy = policy();
+        assert_eq!(policy.delay_for(0), Duration::ZERO);
+        assert_eq!(policy.delay_for(1), Duration::from_secs(10));
+        assert_eq!(policy.delay_for(2), Duration::from_secs(20));
+        assert_eq!(policy.delay_for(3), Duration::from_secs(40));
+        assert_eq!(policy.delay_for(4), Duration::from_secs(60));
+        assert_eq!(policy.delay_for(100), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_record_failure_should_alert_only_once() {
+        let policy = policy();
+        let mut health = PairHealth::default();
+
+        assert!(!health.record_failure("vpn down", &policy).should_alert);
+        assert!(!health.record_failure("vpn down", &policy).should_alert);
+        assert!(health.record_failure("vpn down", &policy).should_alert);
+        assert!(!health.record_failure("vpn down", &policy).should_alert);
+
+        assert!(health.is_unhealthy());
+        assert_eq!(health.consecutive_failures(), 4);
+        assert_eq!(health.last_error(), Some("vpn down"));
+    }
+
+    #[test]
+    fn test_record_success_should_reset_and_report_recovery() {
+        let policy = policy();
+        let mut health = PairHealth::default();
+        for _ in 0..3 {
+            health.record_failure("timeout", &policy);
+        }
+
+        let now = Utc::now();
+        health.set_retry_after(now + Duration::from_secs(30));
+        assert!(health.backing_off_until(now).is_some());
+        assert!(
+            health
+                .backing_off_until(now + Duration::from_secs(31))
+                .is_none()
+        );
+
+        assert!(health.record_success());
+        assert!(health.backing_off_until(now).is_none());
+        assert!(!health.is_unhealthy());
+        assert_eq!(health.consecutive_failures(), 0);
+        assert!(!health.record_success());
+    }
+}
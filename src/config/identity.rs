@@ -0,0 +1,110 @@
+//! 同步对绑定的仓库身份
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SyncError};
+
+/// 同步对绑定的 SVN 和 Git 仓库
+///
+/// 第一次同步时记录，之后每次同步前校验。目录被换成其他仓库的工作副本或克隆时拒绝同步，
+/// 避免把一个镜像的版本写进另一个镜像。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RepoIdentity {
+    /// SVN 仓库 UUID
+    pub svn_uuid: String,
+    /// Git 仓库的根提交；绑定时仓库还没有提交则为 `None`，之后有提交时补上
+    #[serde(default)]
+    pub git_root: Option<String>,
+}
+
+impl RepoIdentity {
+    /// 校验当前的仓库是否与绑定的一致
+    ///
+    /// # 参数
+    ///
+    /// * `current`: 当前目录中的仓库
+    /// * `svn_dir`: SVN 工作副本目录，用于错误信息
+    /// * `git_dir`: Git 仓库目录，用于错误信息
+    ///
+    /// # 返回
+    ///
+    /// 需要更新的绑定（补上了 Git 根提交），不需要更新时为 `None`；仓库不一致时返回错误
+    pub fn verify(
+        &self,
+        current: &RepoIdentity,
+        svn_dir: &Path,
+        git_dir: &Path,
+    ) -> Result<Option<RepoIdentity>> {
+        if self.svn_uuid != current.svn_uuid {
+            return Err(identity_error(format!(
+                "SVN 工作副本 {} 属于仓库 {}，而同步对绑定的是仓库 {}",
+                svn_dir.display(),
+                current.svn_uuid,
+                self.svn_uuid
+            )));
+        }
+        match (&self.git_root, &current.git_root) {
+            (Some(bound), Some(root)) if bound != root => Err(identity_error(format!(
+                "Git 仓库 {} 的根提交为 {}，而同步对绑定的是 {}",
+                git_dir.display(),
+                short_hash(root),
+                short_hash(bound)
+            ))),
+            (Some(bound), None) => Err(identity_error(format!(
+                "Git 仓库 {} 没有任何提交，而同步对绑定的根提交为 {}",
+                git_dir.display(),
+                short_hash(bound)
+            ))),
+            (None, Some(_)) => Ok(Some(current.clone())),
+            _ => Ok(None),
+        }
+    }
+}
+
+fn identity_error(reason: String) -> SyncError {
+    SyncError::App(format!(
+        "{reason}，目录可能被换成了其他仓库，已拒绝同步以免混入其他镜像的提交。\n确认无误后可执行 svn2git history rebind <INDEX> 重新绑定（索引可通过 history list 查看）"
+    ))
+}
+
+fn short_hash(hash: &str) -> &str {
+    hash.get(..10).unwrap_or(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(svn_uuid: &str, git_root: Option<&str>) -> RepoIdentity {
+        RepoIdentity {
+            svn_uuid: svn_uuid.into(),
+            git_root: git_root.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_verify_repo_identity() {
+        let (svn, git) = (Path::new("svn"), Path::new("git"));
+        let bound = identity("uuid-a", Some("0123456789abcdef"));
+
+        assert_eq!(bound.verify(&bound, svn, git).unwrap(), None);
+        let err = bound
+            .verify(&identity("uuid-b", Some("0123456789abcdef")), svn, git)
+            .unwrap_err();
+        assert!(err.to_string().contains("uuid-b"), "{err}");
+        let err = bound
+            .verify(&identity("uuid-a", Some("fedcba9876543210")), svn, git)
+            .unwrap_err();
+        assert!(err.to_string().contains("0123456789"), "{err}");
+        assert!(bound.verify(&identity("uuid-a", None), svn, git).is_err());
+
+        // 绑定时还没有提交，之后补上根提交
+        let unborn = identity("uuid-a", None);
+        assert_eq!(
+            unborn.verify(&bound, svn, git).unwrap(),
+            Some(bound.clone())
+        );
+    }
+}
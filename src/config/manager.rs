@@ -1,14 +1,24 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
+
+use chrono::{DateTime, Utc};
 
 use crate::{
-    config::reocrd::{self, HistoryRecord},
+    clock::{Clock, system_clock},
+    config::{
+        BackoffPolicy, FailureOutcome, RepoIdentity, RunOutcome, SyncCheckpoint, SyncPlan,
+        SyncStartState,
+        reocrd::{self, HistoryRecord},
+    },
+    depth::SparseScope,
     error::{Result, SyncError},
+    message::RewriteRule,
 };
 
 /// 配置文件
 pub struct HistoryManager<S: FileStorage> {
     records: Vec<HistoryRecord>,
     storage: S,
+    clock: Arc<dyn Clock>,
 }
 
 /// 文件存储
@@ -26,9 +36,24 @@ impl<S: FileStorage> HistoryManager<S> {
         Ok(Self {
             records: storage.load()?,
             storage,
+            clock: system_clock(),
         })
     }
 
+    /// 设置时钟，最后使用时间和同步时间都从该时钟获取
+    ///
+    /// # 参数
+    ///
+    /// * `clock`: 时钟
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// 时钟的当前时间
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
     /// 记录是否为空
     pub fn is_empty(&self) -> bool {
         self.records.is_empty()
@@ -44,6 +69,29 @@ impl<S: FileStorage> HistoryManager<S> {
         self.storage.save(&self.records)
     }
 
+    /// 重新从存储中加载记录
+    ///
+    /// 用于长时间运行的进程在不重启的情况下获取其他进程（如 `sync`、
+    /// `history delete`）对配置文件的修改。
+    ///
+    /// # 返回
+    ///
+    /// 重新加载后新增的同步对数量
+    pub fn reload(&mut self) -> Result<usize> {
+        let records = self.storage.load()?;
+        let added = records
+            .iter()
+            .filter(|new| {
+                !self
+                    .records
+                    .iter()
+                    .any(|old| old.path_eq(new.svn_path(), new.git_path()))
+            })
+            .count();
+        self.records = records;
+        Ok(added)
+    }
+
     /// 添加记录
     ///
     /// # 参数
@@ -51,14 +99,363 @@ impl<S: FileStorage> HistoryManager<S> {
     /// * `svn_path`: SVN 路径
     /// * `git_path`: Git 路径
     pub fn add_record(&mut self, svn_path: PathBuf, git_path: PathBuf) {
-        // 删除重复记录
-        self.records.retain(|r| !r.path_eq(&svn_path, &git_path));
-
-        let new_record = HistoryRecord::new(self.records.len() + 1, svn_path, git_path);
-        self.records.push(new_record);
+        // 已有记录只刷新使用时间，保留同步进度
+        let now = self.now();
+        if let Some(record) = self.find_record_mut(&svn_path, &git_path) {
+            record.touch_at(now);
+        } else {
+            let new_record =
+                HistoryRecord::new_with(self.records.len() + 1, svn_path, git_path, now);
+            self.records.push(new_record);
+        }
         self.records.sort_by(reocrd::cmp_last_used);
     }
 
+    /// 记录同步对的一次失败
+    ///
+    /// # 参数
+    ///
+    /// * `svn_path`: SVN 路径
+    /// * `git_path`: Git 路径
+    /// * `error`: 失败原因
+    /// * `policy`: 退避策略
+    ///
+    /// # 返回
+    ///
+    /// 找到对应记录时返回退避和告警决策，否则返回 `None`；退避结束时间从当前时间算起
+    pub fn record_failure(
+        &mut self,
+        svn_path: &PathBuf,
+        git_path: &PathBuf,
+        error: &str,
+        policy: &BackoffPolicy,
+    ) -> Option<FailureOutcome> {
+        let now = self.now();
+        self.find_record_mut(svn_path, git_path).map(|r| {
+            let outcome = r.health_mut().record_failure(error, policy);
+            r.health_mut().set_retry_after(now + outcome.backoff);
+            outcome
+        })
+    }
+
+    /// 记录同步对的一次成功
+    ///
+    /// # 返回
+    ///
+    /// 如果该同步对从异常状态恢复，返回 `true`
+    pub fn record_success(&mut self, svn_path: &PathBuf, git_path: &PathBuf) -> bool {
+        self.find_record_mut(svn_path, git_path)
+            .map(|r| r.health_mut().record_success())
+            .unwrap_or(false)
+    }
+
+    /// 查找同步对对应的记录
+    ///
+    /// # 参数
+    ///
+    /// * `svn_path`: SVN 路径
+    /// * `git_path`: Git 路径
+    pub fn find_record(&self, svn_path: &PathBuf, git_path: &PathBuf) -> Option<&HistoryRecord> {
+        self.records.iter().find(|r| r.path_eq(svn_path, git_path))
+    }
+
+    /// 更新同步对的同步进度
+    ///
+    /// # 参数
+    ///
+    /// * `svn_path`: SVN 路径
+    /// * `git_path`: Git 路径
+    /// * `rev`: 已同步的 SVN 版本
+    /// * `git_hash`: 对应的 Git 提交哈希
+    pub fn update_sync_state(
+        &mut self,
+        svn_path: &PathBuf,
+        git_path: &PathBuf,
+        rev: &str,
+        git_hash: &str,
+    ) {
+        let now = self.now();
+        if let Some(record) = self.find_record_mut(svn_path, git_path) {
+            record.set_sync_state_at(rev, git_hash, now);
+        }
+    }
+
+    /// 记录同步对提交失败的 SVN 版本，供 `retry` 命令重试
+    ///
+    /// # 参数
+    ///
+    /// * `svn_path`: SVN 路径
+    /// * `git_path`: Git 路径
+    /// * `rev`: 失败的 SVN 版本
+    pub fn record_failed_revision(&mut self, svn_path: &PathBuf, git_path: &PathBuf, rev: &str) {
+        if let Some(record) = self.find_record_mut(svn_path, git_path) {
+            record.add_failed_revision(rev);
+        }
+    }
+
+    /// 失败版本的更改已随后续版本提交，清除失败记录
+    ///
+    /// # 参数
+    ///
+    /// * `svn_path`: SVN 路径
+    /// * `git_path`: Git 路径
+    /// * `rev`: 失败的 SVN 版本
+    /// * `into_rev`: 一起提交了其更改的后续版本
+    pub fn fold_failed_revision(
+        &mut self,
+        svn_path: &PathBuf,
+        git_path: &PathBuf,
+        rev: &str,
+        into_rev: &str,
+    ) {
+        if let Some(record) = self.find_record_mut(svn_path, git_path) {
+            record.fold_failed_revision(rev, into_rev);
+        }
+    }
+
+    /// 设置或清除同步对的检查点
+    ///
+    /// # 参数
+    ///
+    /// * `svn_path`: SVN 路径
+    /// * `git_path`: Git 路径
+    /// * `checkpoint`: 新的检查点，`None` 表示清除
+    pub fn set_checkpoint(
+        &mut self,
+        svn_path: &PathBuf,
+        git_path: &PathBuf,
+        checkpoint: Option<SyncCheckpoint>,
+    ) {
+        if let Some(record) = self.find_record_mut(svn_path, git_path) {
+            record.set_checkpoint(checkpoint);
+        }
+    }
+
+    /// 把同步对的进度恢复到同步开始前的状态，并清除检查点
+    ///
+    /// # 参数
+    ///
+    /// * `svn_path`: SVN 路径
+    /// * `git_path`: Git 路径
+    /// * `start`: 同步开始前的状态
+    pub fn restore_sync_state(
+        &mut self,
+        svn_path: &PathBuf,
+        git_path: &PathBuf,
+        start: &SyncStartState,
+    ) {
+        if let Some(record) = self.find_record_mut(svn_path, git_path) {
+            record.restore_sync_state(start);
+        }
+    }
+
+    /// 记录同步对本次计算的同步计划
+    ///
+    /// # 参数
+    ///
+    /// * `svn_path`: SVN 路径
+    /// * `git_path`: Git 路径
+    /// * `plan`: 同步计划
+    pub fn set_last_plan(&mut self, svn_path: &PathBuf, git_path: &PathBuf, plan: SyncPlan) {
+        if let Some(record) = self.find_record_mut(svn_path, git_path) {
+            record.set_last_plan(plan);
+        }
+    }
+
+    /// 设置或清除同步对引用的配置方案
+    ///
+    /// # 参数
+    ///
+    /// * `svn_path`: SVN 路径
+    /// * `git_path`: Git 路径
+    /// * `profile`: 配置方案名称，`None` 表示不使用配置方案
+    pub fn set_profile(&mut self, svn_path: &PathBuf, git_path: &PathBuf, profile: Option<String>) {
+        if let Some(record) = self.find_record_mut(svn_path, git_path) {
+            record.set_profile(profile);
+        }
+    }
+
+    /// 设置或恢复同步对的提交标题前缀
+    ///
+    /// # 参数
+    ///
+    /// * `svn_path`: SVN 路径
+    /// * `git_path`: Git 路径
+    /// * `prefix`: 前缀，可以为空字符串；`None` 表示恢复默认
+    pub fn set_message_prefix(
+        &mut self,
+        svn_path: &PathBuf,
+        git_path: &PathBuf,
+        prefix: Option<String>,
+    ) {
+        if let Some(record) = self.find_record_mut(svn_path, git_path) {
+            record.set_message_prefix(prefix);
+        }
+    }
+
+    /// 设置同步对在监视模式下两次同步之间的秒数
+    ///
+    /// # 参数
+    ///
+    /// * `svn_path`: SVN 路径
+    /// * `git_path`: Git 路径
+    /// * `interval`: 秒数；`None` 表示使用 watch 的 `--interval`
+    pub fn set_watch_interval(
+        &mut self,
+        svn_path: &PathBuf,
+        git_path: &PathBuf,
+        interval: Option<u64>,
+    ) {
+        if let Some(record) = self.find_record_mut(svn_path, git_path) {
+            record.set_watch_interval(interval);
+        }
+    }
+
+    /// 记录同步对工作副本的检出范围
+    ///
+    /// # 参数
+    ///
+    /// * `svn_path`: SVN 路径
+    /// * `git_path`: Git 路径
+    /// * `scope`: 检出范围
+    pub fn set_sparse_scope(&mut self, svn_path: &PathBuf, git_path: &PathBuf, scope: SparseScope) {
+        if let Some(record) = self.find_record_mut(svn_path, git_path) {
+            record.set_sparse_scope(scope);
+        }
+    }
+
+    /// 记录同步对最近一次同步的结果，不保存
+    ///
+    /// # 参数
+    ///
+    /// * `svn_path`: SVN 路径
+    /// * `git_path`: Git 路径
+    /// * `result`: 同步结果，成功时为同步的版本数
+    /// * `revisions`: 失败时已保存进度的版本数
+    /// * `duration_secs`: 用时（秒）
+    ///
+    /// # 返回
+    ///
+    /// 是否找到对应的记录
+    pub fn record_run(
+        &mut self,
+        svn_path: &PathBuf,
+        git_path: &PathBuf,
+        result: &Result<usize>,
+        revisions: usize,
+        duration_secs: u64,
+    ) -> bool {
+        let now = self.clock.now();
+        let Some(record) = self.find_record_mut(svn_path, git_path) else {
+            return false;
+        };
+        record.set_last_run(match result {
+            Ok(synced) => RunOutcome::succeeded(now, *synced, duration_secs),
+            Err(e) => RunOutcome::failed(now, revisions, duration_secs, e),
+        });
+        true
+    }
+
+    /// 绑定同步对的仓库，不保存
+    ///
+    /// # 参数
+    ///
+    /// * `svn_path`: SVN 路径
+    /// * `git_path`: Git 路径
+    /// * `identity`: 绑定的仓库
+    pub fn set_identity(&mut self, svn_path: &PathBuf, git_path: &PathBuf, identity: RepoIdentity) {
+        if let Some(record) = self.find_record_mut(svn_path, git_path) {
+            record.set_identity(Some(identity));
+        }
+    }
+
+    /// 查找可恢复的同步对
+    ///
+    /// # 参数
+    ///
+    /// * `index`: 记录索引；不传时自动选择唯一一个未完成的同步
+    ///
+    /// # 返回
+    ///
+    /// 带有检查点的历史记录
+    pub fn find_resumable(&self, index: Option<usize>) -> Result<&HistoryRecord> {
+        if let Some(index) = index {
+            let record = self
+                .records
+                .get(index)
+                .ok_or_else(|| SyncError::App("索引超出范围".into()))?;
+            if !record.has_checkpoint() {
+                return Err(SyncError::App(format!("记录 {index} 没有需要恢复的同步")));
+            }
+            return Ok(record);
+        }
+
+        let mut resumable = self.records.iter().filter(|r| r.has_checkpoint());
+        match (resumable.next(), resumable.next()) {
+            (Some(record), None) => Ok(record),
+            (None, _) => Err(SyncError::App("没有需要恢复的同步".into())),
+            (Some(_), Some(_)) => Err(SyncError::App(
+                "存在多个未完成的同步，请指定索引（可通过 history list 查看）".into(),
+            )),
+        }
+    }
+
+    /// 查找记录了某个失败版本的同步对
+    ///
+    /// # 参数
+    ///
+    /// * `rev`: 失败的 SVN 版本
+    /// * `index`: 记录索引；不传时自动选择唯一一个记录了该版本的同步对
+    pub fn find_failed(&self, rev: &str, index: Option<usize>) -> Result<&HistoryRecord> {
+        let failed = |record: &&HistoryRecord| record.failed_revisions().iter().any(|r| r == rev);
+        let folded = |into_rev: &str| {
+            SyncError::App(format!(
+                "r{rev} 的更改已随后续版本 r{into_rev} 提交，无需重试"
+            ))
+        };
+        if let Some(index) = index {
+            let record = self
+                .records
+                .get(index)
+                .ok_or_else(|| SyncError::App("索引超出范围".into()))?;
+            if !failed(&record) {
+                return Err(match record.folded_into(rev) {
+                    Some(into_rev) => folded(into_rev),
+                    None => SyncError::App(format!("记录 {index} 中没有 r{rev} 同步失败的记录")),
+                });
+            }
+            return Ok(record);
+        }
+
+        let mut matched = self.records.iter().filter(failed);
+        match (matched.next(), matched.next()) {
+            (Some(record), None) => Ok(record),
+            (None, _) => Err(
+                match self
+                    .records
+                    .iter()
+                    .find_map(|record| record.folded_into(rev))
+                {
+                    Some(into_rev) => folded(into_rev),
+                    None => SyncError::App(format!("没有 r{rev} 同步失败的记录")),
+                },
+            ),
+            (Some(_), Some(_)) => Err(SyncError::App(format!(
+                "多个同步对都有 r{rev} 同步失败的记录，请用 --id 指定索引（可通过 history list 查看）"
+            ))),
+        }
+    }
+
+    fn find_record_mut(
+        &mut self,
+        svn_path: &PathBuf,
+        git_path: &PathBuf,
+    ) -> Option<&mut HistoryRecord> {
+        self.records
+            .iter_mut()
+            .find(|r| r.path_eq(svn_path, git_path))
+    }
+
     /// 删除记录
     ///
     /// # 参数
@@ -77,24 +474,226 @@ impl<S: FileStorage> HistoryManager<S> {
         self.save()
     }
 
+    /// 设置指定记录的环境变量并保存
+    ///
+    /// # 参数
+    ///
+    /// * `index`: 记录索引
+    /// * `key`: 变量名
+    /// * `value`: 变量值
+    pub fn set_env(&mut self, index: usize, key: &str, value: &str) -> Result<()> {
+        self.record_at_mut(index)?.set_env(key, value);
+        self.save()
+    }
+
+    /// 移除指定记录的环境变量并保存
+    ///
+    /// # 参数
+    ///
+    /// * `index`: 记录索引
+    /// * `key`: 变量名
+    ///
+    /// # 返回
+    ///
+    /// 变量是否存在
+    pub fn unset_env(&mut self, index: usize, key: &str) -> Result<bool> {
+        let removed = self.record_at_mut(index)?.unset_env(key);
+        self.save()?;
+        Ok(removed)
+    }
+
+    /// 暂停或恢复指定记录的自动同步并保存
+    ///
+    /// # 参数
+    ///
+    /// * `index`: 记录索引
+    /// * `paused`: 是否暂停
+    ///
+    /// # 返回
+    ///
+    /// 状态是否有变化，没有变化时不保存
+    pub fn set_paused(&mut self, index: usize, paused: bool) -> Result<bool> {
+        let record = self.record_at_mut(index)?;
+        if record.is_paused() == paused {
+            return Ok(false);
+        }
+        record.set_paused(paused);
+        self.save()?;
+        Ok(true)
+    }
+
+    /// 解除指定记录绑定的仓库并保存，下次同步时重新绑定
+    ///
+    /// # 参数
+    ///
+    /// * `index`: 记录索引
+    ///
+    /// # 返回
+    ///
+    /// 之前是否已绑定，没有绑定时不保存
+    pub fn clear_identity(&mut self, index: usize) -> Result<bool> {
+        let record = self.record_at_mut(index)?;
+        if record.identity().is_none() {
+            return Ok(false);
+        }
+        record.set_identity(None);
+        self.save()?;
+        Ok(true)
+    }
+
+    /// 列出指定记录的环境变量
+    ///
+    /// # 参数
+    ///
+    /// * `index`: 记录索引
+    pub fn list_env(&self, index: usize) -> Result<()> {
+        let record = self
+            .records
+            .get(index)
+            .ok_or_else(|| SyncError::App("索引超出范围".into()))?;
+        if record.env().is_empty() {
+            println!("记录 {index} 没有配置环境变量");
+            return Ok(());
+        }
+        for (key, value) in record.env() {
+            println!("{key}={value}");
+        }
+        Ok(())
+    }
+
+    /// 修改指定记录的同步策略并保存
+    ///
+    /// # 参数
+    ///
+    /// * `index`: 记录索引
+    /// * `name`: 策略名
+    /// * `value`: 策略值
+    pub fn set_policy(&mut self, index: usize, name: &str, value: &str) -> Result<()> {
+        self.record_at_mut(index)?.policies_mut().set(name, value)?;
+        self.save()
+    }
+
+    /// 列出指定记录的同步策略
+    ///
+    /// # 参数
+    ///
+    /// * `index`: 记录索引
+    pub fn list_policies(&self, index: usize) -> Result<()> {
+        let record = self
+            .records
+            .get(index)
+            .ok_or_else(|| SyncError::App("索引超出范围".into()))?;
+        println!("{}", record.policies());
+        Ok(())
+    }
+
+    /// 为指定记录追加提交说明的改写规则并保存
+    ///
+    /// # 参数
+    ///
+    /// * `index`: 记录索引
+    /// * `rule`: 改写规则
+    pub fn add_message_rewrite(&mut self, index: usize, rule: RewriteRule) -> Result<()> {
+        self.record_at_mut(index)?.message_rewrites_mut().push(rule);
+        self.save()
+    }
+
+    /// 移除指定记录的一条改写规则并保存
+    ///
+    /// # 参数
+    ///
+    /// * `index`: 记录索引
+    /// * `position`: 规则序号（从 0 开始，与 `history rewrite` 列出的序号相同）
+    pub fn remove_message_rewrite(&mut self, index: usize, position: usize) -> Result<()> {
+        let rules = self.record_at_mut(index)?.message_rewrites_mut();
+        if position >= rules.len() {
+            return Err(SyncError::App(format!("改写规则 {position} 不存在")));
+        }
+        rules.remove(position);
+        self.save()
+    }
+
+    /// 列出指定记录的改写规则
+    ///
+    /// # 参数
+    ///
+    /// * `index`: 记录索引
+    pub fn list_message_rewrites(&self, index: usize) -> Result<()> {
+        let record = self
+            .records
+            .get(index)
+            .ok_or_else(|| SyncError::App("索引超出范围".into()))?;
+        if record.message_rewrites().is_empty() {
+            println!("记录 {index} 没有配置改写规则");
+            return Ok(());
+        }
+        for (position, rule) in record.message_rewrites().iter().enumerate() {
+            println!("{position}: {rule}");
+        }
+        Ok(())
+    }
+
+    /// 列出指定记录的版本映射
+    ///
+    /// # 参数
+    ///
+    /// * `index`: 记录索引
+    /// * `rev`: 只显示该 SVN 版本对应的 Git 提交
+    pub fn list_map(&self, index: usize, rev: Option<&str>) -> Result<()> {
+        let map = self
+            .records
+            .get(index)
+            .ok_or_else(|| SyncError::App("索引超出范围".into()))?
+            .revision_map();
+
+        if let Some(rev) = rev {
+            let rev = rev.trim_start_matches('r');
+            let hash = map
+                .git_hash(rev)
+                .ok_or_else(|| SyncError::App(format!("记录 {index} 中没有 SVN r{rev} 的映射")))?;
+            println!("r{rev} {hash}");
+            return Ok(());
+        }
+
+        if map.is_empty() {
+            println!("记录 {index} 没有版本映射");
+            return Ok(());
+        }
+        for mapping in map.entries() {
+            println!("r{} {}", mapping.svn_rev, mapping.git_hash);
+        }
+        Ok(())
+    }
+
+    fn record_at_mut(&mut self, index: usize) -> Result<&mut HistoryRecord> {
+        self.records
+            .get_mut(index)
+            .ok_or_else(|| SyncError::App("索引超出范围".into()))
+    }
+
     /// 列出所有记录
     pub fn list(&self) {
+        print!("{}", self.render_list());
+    }
+
+    /// `history list` 输出的内容
+    pub fn render_list(&self) -> String {
         if self.records.is_empty() {
-            println!("还没有记录");
-            return;
+            return "还没有记录\n".into();
         }
 
-        reocrd::print_title();
+        let mut output = format!("{}\n", reocrd::LIST_TITLE);
         for record in &self.records {
-            println!("{record}");
+            output.push_str(&format!("{record}\n"));
         }
+        output
     }
 }
 
 #[cfg(test)]
 mod tests {
     #[cfg(test)]
-    use crate::config::{HistoryManager, MockFileStorage};
+    use crate::config::{HistoryManager, MockFileStorage, RepoIdentity};
     #[cfg(test)]
     use std::{fs, path::PathBuf};
 
@@ -116,6 +715,36 @@ mod tests {
         assert!(config.records[0].path_eq(&svn_path, &git_path));
     }
 
+    #[test]
+    fn test_add_record_should_order_by_clock_time() {
+        use crate::clock::FixedClock;
+        use chrono::{Duration, TimeZone, Utc};
+        use std::sync::Arc;
+
+        let mut disk = MockFileStorage::new();
+        disk.expect_load().returning(|| Ok(vec![]));
+        let mut config = HistoryManager::new(disk).unwrap();
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let clock = FixedClock::new(start);
+        config.set_clock(Arc::new(clock.clone()));
+
+        config.add_record(PathBuf::from("svn1"), PathBuf::from("git1"));
+        clock.advance(Duration::minutes(1));
+        config.add_record(PathBuf::from("svn2"), PathBuf::from("git2"));
+        clock.advance(Duration::minutes(1));
+        config.add_record(PathBuf::from("svn1"), PathBuf::from("git1"));
+        let order: Vec<_> = config
+            .records
+            .iter()
+            .map(|r| r.git_path().clone())
+            .collect();
+        assert_eq!(order, [PathBuf::from("git2"), PathBuf::from("git1")]);
+
+        config.update_sync_state(&"svn2".into(), &"git2".into(), "7", "abc");
+        let record = config.find_record(&"svn2".into(), &"git2".into()).unwrap();
+        assert_eq!(record.last_synced_at(), Some(start + Duration::minutes(2)));
+    }
+
     #[test]
     fn test_remove_pair() {
         let mut disk = MockFileStorage::new();
@@ -130,6 +759,337 @@ mod tests {
         assert!(config.records[0].path_eq(&PathBuf::from("svn2"), &PathBuf::from("git2")));
     }
 
+    #[test]
+    fn test_record_failure_and_success() {
+        use crate::config::BackoffPolicy;
+
+        let mut disk = MockFileStorage::new();
+        disk.expect_load().returning(|| Ok(vec![]));
+        let mut config = HistoryManager::new(disk).unwrap();
+        let svn = PathBuf::from("svn1");
+        let git = PathBuf::from("git1");
+        config.add_record(svn.clone(), git.clone());
+
+        let policy = BackoffPolicy {
+            unhealthy_threshold: 2,
+            ..BackoffPolicy::default()
+        };
+        let first = config.record_failure(&svn, &git, "err", &policy).unwrap();
+        assert!(!first.should_alert);
+        let second = config.record_failure(&svn, &git, "err", &policy).unwrap();
+        assert!(second.should_alert);
+        assert!(config.records[0].health().is_unhealthy());
+        assert!(config.records[0].to_string().contains("异常"));
+
+        assert!(config.record_success(&svn, &git));
+        assert!(!config.records[0].health().is_unhealthy());
+        assert!(
+            config
+                .record_failure(&PathBuf::from("x"), &git, "err", &policy)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_update_sync_state() {
+        use crate::config::SyncCheckpoint;
+
+        let mut disk = MockFileStorage::new();
+        disk.expect_load().returning(|| Ok(vec![]));
+        let mut config = HistoryManager::new(disk).unwrap();
+        let svn = PathBuf::from("svn1");
+        let git = PathBuf::from("git1");
+        config.add_record(svn.clone(), git.clone());
+
+        config.update_sync_state(&svn, &git, "12", "abc123");
+        let record = config.find_record(&svn, &git).unwrap();
+        assert_eq!(record.last_synced_rev(), Some("12"));
+        assert_eq!(record.last_git_hash(), Some("abc123"));
+
+        config.set_checkpoint(
+            &svn,
+            &git,
+            Some(SyncCheckpoint::new(vec!["13".into(), "14".into()])),
+        );
+        config.update_sync_state(&svn, &git, "13", "def456");
+        let record = config.find_record(&svn, &git).unwrap();
+        assert_eq!(record.checkpoint().unwrap().pending(), ["14".to_string()]);
+
+        // 最后一个版本完成后检查点自动清除
+        config.update_sync_state(&svn, &git, "14", "fed789");
+        assert!(!config.find_record(&svn, &git).unwrap().has_checkpoint());
+        let map = config.find_record(&svn, &git).unwrap().revision_map();
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.git_hash("13"), Some("def456"));
+
+        // 重复添加同一同步对不应丢失同步进度
+        config.add_record(svn.clone(), git.clone());
+        assert_eq!(config.records.len(), 1);
+        let record = config.find_record(&svn, &git).unwrap();
+        assert_eq!(record.last_synced_rev(), Some("14"));
+    }
+
+    #[test]
+    fn test_set_and_unset_env() {
+        let mut disk = MockFileStorage::new();
+        disk.expect_load().returning(|| Ok(vec![]));
+        disk.expect_save().times(3).returning(|_| Ok(()));
+        let mut config = HistoryManager::new(disk).unwrap();
+        let (svn, git) = (PathBuf::from("svn"), PathBuf::from("git"));
+        config.add_record(svn.clone(), git.clone());
+
+        config
+            .set_env(0, "HTTPS_PROXY", "http://proxy:8080")
+            .unwrap();
+        let env = config.find_record(&svn, &git).unwrap().env();
+        assert_eq!(
+            env.get("HTTPS_PROXY").map(String::as_str),
+            Some("http://proxy:8080")
+        );
+
+        assert!(config.unset_env(0, "HTTPS_PROXY").unwrap());
+        assert!(!config.unset_env(0, "HTTPS_PROXY").unwrap());
+        assert!(config.set_env(5, "A", "b").is_err());
+    }
+
+    #[test]
+    fn test_set_paused_should_save_only_on_change() {
+        let mut disk = MockFileStorage::new();
+        disk.expect_load().returning(|| Ok(vec![]));
+        disk.expect_save().times(2).returning(|_| Ok(()));
+        let mut config = HistoryManager::new(disk).unwrap();
+        config.add_record(PathBuf::from("svn"), PathBuf::from("git"));
+
+        assert!(config.set_paused(0, true).unwrap());
+        assert!(!config.set_paused(0, true).unwrap());
+        assert!(config.records()[0].is_paused());
+        assert!(config.render_list().contains("[已暂停]"));
+        assert!(config.set_paused(0, false).unwrap());
+        assert!(config.set_paused(5, true).is_err());
+    }
+
+    #[test]
+    fn test_clear_identity_should_save_only_when_bound() {
+        let mut disk = MockFileStorage::new();
+        disk.expect_load().returning(|| Ok(vec![]));
+        disk.expect_save().times(1).returning(|_| Ok(()));
+        let mut config = HistoryManager::new(disk).unwrap();
+        let (svn, git) = (PathBuf::from("svn"), PathBuf::from("git"));
+        config.add_record(svn.clone(), git.clone());
+
+        assert!(!config.clear_identity(0).unwrap());
+        config.set_identity(
+            &svn,
+            &git,
+            RepoIdentity {
+                svn_uuid: "uuid".into(),
+                git_root: None,
+            },
+        );
+        assert!(config.records()[0].identity().is_some());
+        assert!(config.clear_identity(0).unwrap());
+        assert!(config.records()[0].identity().is_none());
+        assert!(config.clear_identity(3).is_err());
+    }
+
+    #[test]
+    fn test_add_and_remove_message_rewrites() {
+        let mut disk = MockFileStorage::new();
+        disk.expect_load().returning(|| Ok(vec![]));
+        disk.expect_save().times(3).returning(|_| Ok(()));
+        let mut config = HistoryManager::new(disk).unwrap();
+        let (svn, git) = (PathBuf::from("svn"), PathBuf::from("git"));
+        config.add_record(svn.clone(), git.clone());
+
+        config
+            .add_message_rewrite(0, "a=>b".parse().unwrap())
+            .unwrap();
+        config
+            .add_message_rewrite(0, "c=>d".parse().unwrap())
+            .unwrap();
+        assert!(config.remove_message_rewrite(0, 2).is_err());
+        config.remove_message_rewrite(0, 0).unwrap();
+        assert!(config.list_message_rewrites(0).is_ok());
+
+        let sync_config = config.find_record(&svn, &git).unwrap().to_sync_config();
+        let rules: Vec<String> = sync_config
+            .message_rewrites
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(rules, ["c=>d"]);
+        assert!(
+            config
+                .add_message_rewrite(4, "a=>b".parse().unwrap())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_set_message_prefix_should_round_trip_empty_prefix() {
+        use crate::config::{HistoryRecord, MemoryStorage};
+
+        let mut config = HistoryManager::new(MemoryStorage::new()).unwrap();
+        let (svn, git) = (PathBuf::from("svn"), PathBuf::from("git"));
+        config.add_record(svn.clone(), git.clone());
+        let record = config.find_record(&svn, &git).unwrap();
+        assert_eq!(record.to_sync_config().message_prefix, "SVN: ");
+
+        config.set_message_prefix(&svn, &git, Some(String::new()));
+        let json = serde_json::to_string(config.find_record(&svn, &git).unwrap()).unwrap();
+        let record: HistoryRecord = serde_json::from_str(&json).unwrap();
+        assert_eq!(record.message_prefix(), "");
+        assert_eq!(record.to_sync_config().message_prefix, "");
+
+        config.set_message_prefix(&svn, &git, None);
+        assert_eq!(
+            config.find_record(&svn, &git).unwrap().message_prefix(),
+            "SVN: "
+        );
+    }
+
+    #[test]
+    fn test_list_map() {
+        let mut disk = MockFileStorage::new();
+        disk.expect_load().returning(|| Ok(vec![]));
+        let mut config = HistoryManager::new(disk).unwrap();
+        let (svn, git) = (PathBuf::from("svn"), PathBuf::from("git"));
+        config.add_record(svn.clone(), git.clone());
+        config.update_sync_state(&svn, &git, "1234", "abc");
+
+        assert!(config.list_map(0, None).is_ok());
+        assert!(config.list_map(0, Some("r1234")).is_ok());
+        assert!(config.list_map(0, Some("1235")).is_err());
+        assert!(config.list_map(1, None).is_err());
+    }
+
+    #[test]
+    fn test_find_resumable() {
+        use crate::config::SyncCheckpoint;
+
+        let mut disk = MockFileStorage::new();
+        disk.expect_load().returning(|| Ok(vec![]));
+        let mut config = HistoryManager::new(disk).unwrap();
+        let (svn1, git1) = (PathBuf::from("svn1"), PathBuf::from("git1"));
+        let (svn2, git2) = (PathBuf::from("svn2"), PathBuf::from("git2"));
+        config.add_record(svn1.clone(), git1.clone());
+        config.add_record(svn2.clone(), git2.clone());
+
+        assert!(config.find_resumable(None).is_err());
+
+        let checkpoint = Some(SyncCheckpoint::new(vec!["5".into()]));
+        config.set_checkpoint(&svn1, &git1, checkpoint.clone());
+        assert_eq!(config.find_resumable(None).unwrap().svn_path(), &svn1);
+
+        config.set_checkpoint(&svn2, &git2, checkpoint);
+        assert!(config.find_resumable(None).is_err());
+        let index = config
+            .records
+            .iter()
+            .position(|r| r.svn_path() == &svn2)
+            .unwrap();
+        assert_eq!(
+            config.find_resumable(Some(index)).unwrap().svn_path(),
+            &svn2
+        );
+        assert!(config.find_resumable(Some(9)).is_err());
+    }
+
+    #[test]
+    fn test_find_failed_and_clear_after_sync() {
+        let mut disk = MockFileStorage::new();
+        disk.expect_load().returning(|| Ok(vec![]));
+        let mut config = HistoryManager::new(disk).unwrap();
+        let (svn1, git1) = (PathBuf::from("svn1"), PathBuf::from("git1"));
+        let (svn2, git2) = (PathBuf::from("svn2"), PathBuf::from("git2"));
+        config.add_record(svn1.clone(), git1.clone());
+        config.add_record(svn2.clone(), git2.clone());
+        let index = |svn: &PathBuf| config.records.iter().position(|r| r.svn_path() == svn);
+        let (index1, index2) = (index(&svn1), index(&svn2));
+
+        assert!(config.find_failed("4", None).is_err());
+        config.record_failed_revision(&svn1, &git1, "4");
+        config.record_failed_revision(&svn1, &git1, "4");
+        assert_eq!(
+            config.find_failed("4", None).unwrap().failed_revisions(),
+            ["4"]
+        );
+        assert!(config.find_failed("4", index2).is_err());
+
+        config.record_failed_revision(&svn2, &git2, "4");
+        assert!(config.find_failed("4", None).is_err());
+        assert_eq!(config.find_failed("4", index2).unwrap().svn_path(), &svn2);
+
+        config.update_sync_state(&svn1, &git1, "4", "abc");
+        assert!(config.find_failed("4", index1).is_err());
+
+        // 更改随后续版本提交的失败版本
+        config.fold_failed_revision(&svn2, &git2, "4", "5");
+        let err = config.find_failed("4", None).unwrap_err().to_string();
+        assert!(err.contains("已随后续版本 r5 提交"), "{err}");
+    }
+
+    #[test]
+    fn test_restore_sync_state_should_drop_progress_of_rolled_back_sync() {
+        use crate::config::{SyncCheckpoint, SyncStartState};
+
+        let mut disk = MockFileStorage::new();
+        disk.expect_load().returning(|| Ok(vec![]));
+        let mut config = HistoryManager::new(disk).unwrap();
+        let (svn, git) = (PathBuf::from("svn"), PathBuf::from("git"));
+        config.add_record(svn.clone(), git.clone());
+        config.update_sync_state(&svn, &git, "5", "h5");
+
+        let start = SyncStartState {
+            git_head: Some("h5".into()),
+            svn_rev: "5".into(),
+            last_synced_rev: Some("5".into()),
+            last_git_hash: Some("h5".into()),
+            mapped_revisions: 1,
+        };
+        let checkpoint =
+            SyncCheckpoint::new(vec!["6".into(), "7".into()]).with_start(start.clone());
+        config.set_checkpoint(&svn, &git, Some(checkpoint));
+        config.update_sync_state(&svn, &git, "6", "h6");
+
+        config.restore_sync_state(&svn, &git, &start);
+        let record = config.find_record(&svn, &git).unwrap();
+        assert_eq!(record.last_synced_rev(), Some("5"));
+        assert_eq!(record.last_git_hash(), Some("h5"));
+        assert_eq!(record.revision_map().len(), 1);
+        assert!(!record.has_checkpoint());
+    }
+
+    #[test]
+    fn test_reload_should_pick_up_new_records() {
+        use crate::config::HistoryRecord;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = AtomicUsize::new(0);
+        let mut disk = MockFileStorage::new();
+        disk.expect_load().returning(move || {
+            let mut records = vec![HistoryRecord::new(
+                1,
+                PathBuf::from("svn1"),
+                PathBuf::from("git1"),
+            )];
+            if calls.fetch_add(1, Ordering::SeqCst) > 0 {
+                records.push(HistoryRecord::new(
+                    2,
+                    PathBuf::from("svn2"),
+                    PathBuf::from("git2"),
+                ));
+            }
+            Ok(records)
+        });
+
+        let mut config = HistoryManager::new(disk).unwrap();
+        assert_eq!(config.records.len(), 1);
+        assert_eq!(config.reload().unwrap(), 1);
+        assert_eq!(config.records.len(), 2);
+        assert_eq!(config.reload().unwrap(), 0);
+    }
+
     #[test]
     fn test_list_history() {
         let mut disk = MockFileStorage::new();
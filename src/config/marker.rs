@@ -0,0 +1,122 @@
+//! 写在 Git 仓库中的镜像标记，使仓库本身能说明它是哪个同步对的镜像
+
+use crate::error::{Result, SyncError};
+
+/// 保存镜像标记的引用，不属于任何分支，`git log` 中看不到
+pub const STATE_REF: &str = "refs/svn2git/state";
+
+/// 标记格式的版本，格式不兼容地变化时递增
+const MARKER_FORMAT: &str = "1";
+
+/// Git 仓库中的镜像标记
+///
+/// 每次同步后写入 [`STATE_REF`] 指向的 blob，内容为 `键: 值` 格式的文本。
+/// 本地配置文件丢失后重新添加同步对时，可以从标记中恢复同步进度。
+#[derive(Debug, Clone, PartialEq)]
+pub struct MirrorMarker {
+    /// 同步对编号
+    pub pair_id: usize,
+    /// SVN 仓库 UUID，未知时为 `None`
+    pub svn_uuid: Option<String>,
+    /// 最后同步的 SVN 版本
+    pub last_synced_rev: String,
+    /// 写入标记的 svn2git 版本
+    pub tool_version: String,
+}
+
+impl MirrorMarker {
+    /// 创建由当前版本的 svn2git 写入的标记
+    ///
+    /// # 参数
+    ///
+    /// * `pair_id`: 同步对编号
+    /// * `svn_uuid`: SVN 仓库 UUID
+    /// * `last_synced_rev`: 最后同步的 SVN 版本
+    pub fn new(pair_id: usize, svn_uuid: Option<String>, last_synced_rev: &str) -> Self {
+        Self {
+            pair_id,
+            svn_uuid,
+            last_synced_rev: last_synced_rev.to_string(),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    /// 生成写入 blob 的文本
+    pub fn render(&self) -> String {
+        let mut text = format!(
+            "svn2git-state: {MARKER_FORMAT}\npair-id: {}\n",
+            self.pair_id
+        );
+        if let Some(uuid) = &self.svn_uuid {
+            text.push_str(&format!("svn-uuid: {uuid}\n"));
+        }
+        text.push_str(&format!(
+            "last-synced-rev: {}\ntool-version: {}\n",
+            self.last_synced_rev, self.tool_version
+        ));
+        text
+    }
+
+    /// 解析 blob 中的文本
+    ///
+    /// 忽略不认识的键，以便旧版本读取新版本写入的标记
+    ///
+    /// # 参数
+    ///
+    /// * `text`: blob 内容
+    ///
+    /// # 返回
+    ///
+    /// * `Err(SyncError)` - 不是镜像标记、格式版本不支持或缺少必需的键
+    pub fn parse(text: &str) -> Result<Self> {
+        let value = |key: &str| {
+            text.lines()
+                .filter_map(|line| line.split_once(':'))
+                .find(|(k, _)| k.trim() == key)
+                .map(|(_, v)| v.trim().to_string())
+        };
+        let required =
+            |key: &str| value(key).ok_or_else(|| SyncError::App(format!("镜像标记缺少 {key}")));
+        match value("svn2git-state").as_deref() {
+            Some(MARKER_FORMAT) => {}
+            Some(format) => {
+                return Err(SyncError::App(format!(
+                    "不支持的镜像标记格式 {format}，请升级 svn2git"
+                )));
+            }
+            None => return Err(SyncError::App(format!("{STATE_REF} 不是镜像标记"))),
+        }
+        let pair_id = required("pair-id")?;
+        Ok(Self {
+            pair_id: pair_id
+                .parse()
+                .map_err(|_| SyncError::App(format!("镜像标记中的同步对编号无效：{pair_id}")))?,
+            svn_uuid: value("svn-uuid"),
+            last_synced_rev: required("last-synced-rev")?,
+            tool_version: value("tool-version").unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mirror_marker_round_trip() {
+        let marker = MirrorMarker::new(3, Some("uuid-a".into()), "120");
+        let text = marker.render();
+        assert!(text.starts_with("svn2git-state: 1\npair-id: 3\nsvn-uuid: uuid-a\n"));
+        assert_eq!(MirrorMarker::parse(&text).unwrap(), marker);
+
+        let text = format!("{text}future-key: x\n");
+        assert_eq!(MirrorMarker::parse(&text).unwrap(), marker);
+    }
+
+    #[test]
+    fn test_mirror_marker_parse_should_reject_other_content() {
+        assert!(MirrorMarker::parse("hello").is_err());
+        assert!(MirrorMarker::parse("svn2git-state: 2\npair-id: 0\nlast-synced-rev: 1").is_err());
+        assert!(MirrorMarker::parse("svn2git-state: 1\npair-id: 0").is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use std::{collections::BTreeMap, sync::Mutex};
+
+use crate::{
+    config::{StateStore, validate_store_key},
+    error::Result,
+};
+
+/// 内存状态存储
+///
+/// 所有内容只保存在进程内存中，进程结束即丢弃。用于测试和 `--ephemeral`
+/// 运行，不需要为构造 `HistoryManager` 准备 mock 期望。
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    entries: Mutex<BTreeMap<(String, String), Vec<u8>>>,
+}
+
+impl MemoryStorage {
+    /// 创建一个空的内存存储
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, BTreeMap<(String, String), Vec<u8>>> {
+        // 持锁期间不会 panic，被污染的锁中数据仍然完整
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl StateStore for MemoryStorage {
+    fn get(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>> {
+        validate_store_key(namespace, key)?;
+        Ok(self
+            .entries()
+            .get(&(namespace.to_string(), key.to_string()))
+            .cloned())
+    }
+
+    fn put(&self, namespace: &str, key: &str, value: &[u8]) -> Result<()> {
+        validate_store_key(namespace, key)?;
+        self.entries()
+            .insert((namespace.to_string(), key.to_string()), value.to_vec());
+        Ok(())
+    }
+
+    fn remove(&self, namespace: &str, key: &str) -> Result<()> {
+        validate_store_key(namespace, key)?;
+        self.entries()
+            .remove(&(namespace.to_string(), key.to_string()));
+        Ok(())
+    }
+
+    fn keys(&self, namespace: &str) -> Result<Vec<String>> {
+        validate_store_key(namespace, "_")?;
+        Ok(self
+            .entries()
+            .keys()
+            .filter(|(ns, _)| ns == namespace)
+            .map(|(_, key)| key.clone())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::config::HistoryManager;
+
+    #[test]
+    fn test_memory_storage_get_put_remove_keys() {
+        let storage = MemoryStorage::new();
+        assert_eq!(storage.get("cache", "a").unwrap(), None);
+
+        storage.put("cache", "b", b"2").unwrap();
+        storage.put("cache", "a", b"1").unwrap();
+        storage.put("other", "c", b"3").unwrap();
+        assert_eq!(storage.get("cache", "a").unwrap(), Some(b"1".to_vec()));
+        assert_eq!(storage.keys("cache").unwrap(), vec!["a", "b"]);
+
+        storage.remove("cache", "a").unwrap();
+        storage.remove("cache", "a").unwrap();
+        assert_eq!(storage.keys("cache").unwrap(), vec!["b"]);
+        assert!(storage.put("cache", "", b"x").is_err());
+    }
+
+    #[test]
+    fn test_memory_storage_backs_history_manager() {
+        let mut history = HistoryManager::new(MemoryStorage::new()).unwrap();
+        assert!(history.is_empty());
+
+        history.add_record(PathBuf::from("svn"), PathBuf::from("git"));
+        history.save().unwrap();
+        assert_eq!(history.reload().unwrap(), 0);
+        assert_eq!(history.records().len(), 1);
+    }
+}
@@ -1,7 +1,29 @@
+mod checkpoint;
 mod disk;
+mod health;
+mod identity;
 mod manager;
+mod marker;
+mod memory;
+mod outcome;
+mod pair;
+mod plan;
+mod policies;
 mod reocrd;
+mod revision_map;
+mod store;
 
+pub use checkpoint::*;
 pub use disk::*;
+pub use health::*;
+pub use identity::*;
 pub use manager::*;
+pub use marker::*;
+pub use memory::*;
+pub use outcome::*;
+pub use pair::*;
+pub use plan::*;
+pub use policies::*;
 pub use reocrd::*;
+pub use revision_map::*;
+pub use store::*;
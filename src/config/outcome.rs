@@ -0,0 +1,116 @@
+//! 同步结果的记录
+
+use std::fmt::Display;
+
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::SyncError;
+
+/// 最近一次同步的结果
+///
+/// 每次执行 sync、resume（成功或失败）后保存到同步对的历史记录，`history list` 据此显示状态列
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RunOutcome {
+    /// 结束时间
+    pub finished_at: DateTime<Utc>,
+    /// 是否成功
+    pub success: bool,
+    /// 同步的版本数；失败时为失败前已保存进度的版本数
+    pub revisions: usize,
+    /// 用时（秒）
+    pub duration_secs: u64,
+    /// 失败原因
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// 失败的 svn/git 命令的退出码
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+}
+
+impl RunOutcome {
+    /// 成功的同步
+    ///
+    /// # 参数
+    ///
+    /// * `finished_at`: 结束时间
+    /// * `revisions`: 同步的版本数
+    /// * `duration_secs`: 用时（秒）
+    pub fn succeeded(finished_at: DateTime<Utc>, revisions: usize, duration_secs: u64) -> Self {
+        Self {
+            finished_at,
+            success: true,
+            revisions,
+            duration_secs,
+            error: None,
+            exit_code: None,
+        }
+    }
+
+    /// 失败的同步
+    ///
+    /// # 参数
+    ///
+    /// * `finished_at`: 结束时间
+    /// * `revisions`: 失败前已保存进度的版本数
+    /// * `duration_secs`: 用时（秒）
+    /// * `error`: 失败原因
+    pub fn failed(
+        finished_at: DateTime<Utc>,
+        revisions: usize,
+        duration_secs: u64,
+        error: &SyncError,
+    ) -> Self {
+        Self {
+            finished_at,
+            success: false,
+            revisions,
+            duration_secs,
+            error: Some(error.to_string()),
+            exit_code: error.exit_code(),
+        }
+    }
+}
+
+impl Display for RunOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let time = self
+            .finished_at
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S");
+        if self.success {
+            write!(f, "✅ {time} {} 个版本", self.revisions)
+        } else {
+            write!(f, "❌ {time}")?;
+            if let Some(code) = self.exit_code {
+                write!(f, " 退出码 {code}")?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_outcome_should_keep_error_and_exit_code() {
+        let now = Utc::now();
+        let error = SyncError::SvnCommand {
+            action: "svn update".into(),
+            args: "update -r 5".into(),
+            exit_code: Some(1),
+            stderr: "svn: E170013".into(),
+        };
+        let outcome = RunOutcome::failed(now, 2, 30, &error);
+        assert!(!outcome.success);
+        assert_eq!(outcome.exit_code, Some(1));
+        assert!(outcome.to_string().starts_with("❌ "));
+        assert!(outcome.to_string().ends_with(" 退出码 1"));
+
+        let outcome = RunOutcome::succeeded(now, 3, 5);
+        assert!(outcome.to_string().starts_with("✅ "));
+        assert_eq!(outcome.error, None);
+    }
+}
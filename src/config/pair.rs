@@ -0,0 +1,116 @@
+use std::{fs::OpenOptions, path::PathBuf};
+
+use crate::{
+    config::{FileStorage, HistoryRecord},
+    error::Result,
+};
+
+/// 只写入一个同步对记录的历史记录存储
+///
+/// 多个进程同时同步不同的同步对时共用同一个配置文件，
+/// 直接保存会用本进程读到的旧记录覆盖其他同步对的进度。
+/// 保存时持有配置文件锁重新读取，只替换本同步对的记录。
+pub struct PairStorage<S: FileStorage> {
+    inner: S,
+    svn_path: PathBuf,
+    git_path: PathBuf,
+    lock_path: PathBuf,
+}
+
+impl<S: FileStorage> PairStorage<S> {
+    /// 创建存储
+    ///
+    /// # 参数
+    ///
+    /// * `inner`: 实际的存储
+    /// * `svn_path`: 同步对的 SVN 路径
+    /// * `git_path`: 同步对的 Git 路径
+    /// * `lock_path`: 保存时加锁的文件，同一个配置文件的所有进程必须使用同一个路径
+    pub fn new(inner: S, svn_path: PathBuf, git_path: PathBuf, lock_path: PathBuf) -> Self {
+        Self {
+            inner,
+            svn_path,
+            git_path,
+            lock_path,
+        }
+    }
+}
+
+impl<S: FileStorage> FileStorage for PairStorage<S> {
+    fn load(&self) -> Result<Vec<HistoryRecord>> {
+        self.inner.load()
+    }
+
+    fn save(&self, records: &[HistoryRecord]) -> Result<()> {
+        let Some(record) = records
+            .iter()
+            .find(|r| r.path_eq(&self.svn_path, &self.git_path))
+        else {
+            return Ok(());
+        };
+
+        // 文件关闭时自动释放锁
+        let lock = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.lock_path)?;
+        lock.lock()?;
+        let mut current = self.inner.load()?;
+        match current
+            .iter_mut()
+            .find(|r| r.path_eq(&self.svn_path, &self.git_path))
+        {
+            Some(old) => *old = record.clone(),
+            None => current.push(record.clone()),
+        }
+        self.inner.save(&current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{DiskStorage, HistoryManager};
+
+    #[test]
+    fn test_pair_storage_should_keep_other_pairs_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_file = dir.path().join("config.json");
+        let lock_path = dir.path().join("config.json.lock");
+        let (svn_a, git_a) = (PathBuf::from("d:/svn/a"), PathBuf::from("d:/git/a"));
+        let (svn_b, git_b) = (PathBuf::from("d:/svn/b"), PathBuf::from("d:/git/b"));
+
+        let mut history = HistoryManager::new(DiskStorage::new(config_file.clone())).unwrap();
+        history.add_record(svn_a.clone(), git_a.clone());
+        history.add_record(svn_b.clone(), git_b.clone());
+        history.save().unwrap();
+
+        // 两个同步对同时读取配置文件，随后先后保存各自的进度
+        let open = |svn: &PathBuf, git: &PathBuf| {
+            HistoryManager::new(PairStorage::new(
+                DiskStorage::new(config_file.clone()),
+                svn.clone(),
+                git.clone(),
+                lock_path.clone(),
+            ))
+            .unwrap()
+        };
+        let mut a = open(&svn_a, &git_a);
+        let mut b = open(&svn_b, &git_b);
+        a.update_sync_state(&svn_a, &git_a, "10", "aaa");
+        a.save().unwrap();
+        b.update_sync_state(&svn_b, &git_b, "20", "bbb");
+        b.save().unwrap();
+
+        let history = HistoryManager::new(DiskStorage::new(config_file)).unwrap();
+        let rev = |svn, git| {
+            history
+                .find_record(svn, git)
+                .and_then(|r| r.last_synced_rev())
+                .map(str::to_string)
+        };
+        assert_eq!(rev(&svn_a, &git_a).as_deref(), Some("10"));
+        assert_eq!(rev(&svn_b, &git_b).as_deref(), Some("20"));
+    }
+}
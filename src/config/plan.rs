@@ -0,0 +1,178 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 计划中的一个版本
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlannedRevision {
+    /// SVN 版本
+    pub rev: String,
+    /// 是否因为只修改了跳过规则匹配的路径而不提交
+    #[serde(default)]
+    pub skipped: bool,
+}
+
+/// 同步计划
+///
+/// 每次计算出待同步的版本后保存，下次运行时与新的计划比较，
+/// 用于确认修改跳过规则、日期窗口、数量限制等配置的效果
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SyncPlan {
+    /// 计划同步的版本（按同步顺序）
+    revisions: Vec<PlannedRevision>,
+    /// 计划生成时间
+    created_at: DateTime<Utc>,
+}
+
+impl SyncPlan {
+    /// 创建新的计划
+    ///
+    /// # 参数
+    ///
+    /// * `revisions`: 计划同步的版本
+    pub fn new(revisions: Vec<PlannedRevision>) -> Self {
+        Self::new_at(revisions, Utc::now())
+    }
+
+    /// 创建指定生成时间的计划
+    ///
+    /// # 参数
+    ///
+    /// * `revisions`: 计划同步的版本
+    /// * `created_at`: 计划生成时间
+    pub fn new_at(revisions: Vec<PlannedRevision>, created_at: DateTime<Utc>) -> Self {
+        Self {
+            revisions,
+            created_at,
+        }
+    }
+
+    /// 计划同步的版本
+    pub fn revisions(&self) -> &[PlannedRevision] {
+        &self.revisions
+    }
+
+    /// 计划生成时间
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// 与上次的计划比较
+    ///
+    /// 上次计划中已经同步完成的版本不算作移除
+    ///
+    /// # 参数
+    ///
+    /// * `previous`: 上次的计划
+    /// * `synced_rev`: 当前已同步到的版本
+    pub fn diff(&self, previous: &SyncPlan, synced_rev: Option<&str>) -> PlanDiff {
+        fn find<'a>(plan: &'a SyncPlan, rev: &str) -> Option<&'a PlannedRevision> {
+            plan.revisions.iter().find(|r| r.rev == rev)
+        }
+        let synced = synced_rev.and_then(|rev| rev.parse::<u64>().ok());
+        let mut diff = PlanDiff::default();
+
+        for revision in &self.revisions {
+            match find(previous, &revision.rev) {
+                None => diff.added.push(revision.rev.clone()),
+                Some(old) if !old.skipped && revision.skipped => {
+                    diff.now_skipped.push(revision.rev.clone())
+                }
+                Some(old) if old.skipped && !revision.skipped => {
+                    diff.no_longer_skipped.push(revision.rev.clone())
+                }
+                Some(_) => {}
+            }
+        }
+        for revision in &previous.revisions {
+            let already_synced = synced
+                .zip(revision.rev.parse::<u64>().ok())
+                .is_some_and(|(synced, rev)| rev <= synced);
+            if !already_synced && find(self, &revision.rev).is_none() {
+                diff.removed.push(revision.rev.clone());
+            }
+        }
+        diff
+    }
+}
+
+/// 两次同步计划的差异
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlanDiff {
+    /// 新增的版本
+    pub added: Vec<String>,
+    /// 不再出现的版本（被日期窗口、数量限制等排除）
+    pub removed: Vec<String>,
+    /// 改为跳过的版本
+    pub now_skipped: Vec<String>,
+    /// 不再跳过的版本
+    pub no_longer_skipped: Vec<String>,
+}
+
+impl PlanDiff {
+    /// 两次计划是否相同
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.now_skipped.is_empty()
+            && self.no_longer_skipped.is_empty()
+    }
+}
+
+impl Display for PlanDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sections = [
+            ("新增", &self.added),
+            ("移除", &self.removed),
+            ("改为跳过", &self.now_skipped),
+            ("不再跳过", &self.no_longer_skipped),
+        ];
+        let lines: Vec<String> = sections
+            .iter()
+            .filter(|(_, revs)| !revs.is_empty())
+            .map(|(label, revs)| {
+                let revs: Vec<String> = revs.iter().map(|rev| format!("r{rev}")).collect();
+                format!("  {label} {} 个版本：{}", revs.len(), revs.join("、"))
+            })
+            .collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(revisions: &[(&str, bool)]) -> SyncPlan {
+        SyncPlan::new(
+            revisions
+                .iter()
+                .map(|(rev, skipped)| PlannedRevision {
+                    rev: rev.to_string(),
+                    skipped: *skipped,
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_plan_diff() {
+        let previous = plan(&[("2", false), ("3", false), ("4", true), ("5", false)]);
+        let current = plan(&[("3", true), ("4", false), ("6", false)]);
+
+        let diff = current.diff(&previous, Some("2"));
+        assert_eq!(diff.added, ["6"]);
+        assert_eq!(diff.removed, ["5"]);
+        assert_eq!(diff.now_skipped, ["3"]);
+        assert_eq!(diff.no_longer_skipped, ["4"]);
+        assert_eq!(
+            diff.to_string(),
+            "  新增 1 个版本：r6\n  移除 1 个版本：r5\n  改为跳过 1 个版本：r3\n  不再跳过 1 个版本：r4"
+        );
+
+        // 没有同步进度时，上次计划中缺少的版本都算移除
+        assert_eq!(current.diff(&previous, None).removed, ["2", "5"]);
+        assert!(current.diff(&current, None).is_empty());
+    }
+}
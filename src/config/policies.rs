@@ -0,0 +1,197 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SyncError};
+
+/// SVN 版本没有产生任何 Git 更改时的处理方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum EmptyRevisionPolicy {
+    /// 按提交失败处理，停止同步
+    #[default]
+    Fail,
+    /// 不生成提交，只记录进度
+    Skip,
+    /// 生成不含更改的空提交（`--allow-empty`），保留该版本的提交说明
+    AllowEmpty,
+}
+
+/// Git 暂存区存在冲突条目时的处理方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConflictPolicy {
+    /// 停止同步
+    #[default]
+    Stop,
+    /// 把暂存区重置到 HEAD 后继续，以工作目录中的 SVN 内容为准
+    Reset,
+}
+
+/// 开始同步前 Git 工作目录已有未提交更改时的处理方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DirtyTreePolicy {
+    /// 不检查，已有的更改随第一个版本一起提交
+    #[default]
+    Ignore,
+    /// 拒绝同步
+    Fail,
+    /// 同步前 `git stash` 暂存已跟踪文件的更改，同步结束后恢复
+    Stash,
+    /// 询问用户选择以上一种处理方式，非交互模式下拒绝同步
+    Ask,
+}
+
+/// 某个版本同步失败时的处理方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorPolicy {
+    /// 回滚该版本并停止同步
+    #[default]
+    Stop,
+    /// 回滚该版本后继续同步后续版本，结束时汇总失败的版本
+    Continue,
+}
+
+/// 保留提交时间时，SVN 版本时间早于上一个版本的处理方式
+///
+/// 服务器时钟调整等原因会让 SVN 版本时间不按版本号递增
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DateOrderPolicy {
+    /// 原样保留
+    Keep,
+    /// 原样保留并输出警告
+    #[default]
+    Warn,
+    /// 调整为上一个版本时间加 1 秒
+    Clamp,
+}
+
+/// 同步完成后推送 Git 仓库的方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PushPolicy {
+    /// 不推送，除非命令行传入 `--push`
+    #[default]
+    Never,
+    /// 每次同步完成后推送分支和标签到 `origin`
+    Always,
+}
+
+/// 同步策略
+///
+/// 保存在历史记录中，随 [`SyncConfig`](crate::SyncConfig) 传给同步工具
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SyncPolicies {
+    /// 空版本
+    #[serde(default)]
+    pub empty_revision: EmptyRevisionPolicy,
+    /// Git 冲突
+    #[serde(default)]
+    pub conflict: ConflictPolicy,
+    /// 未提交的更改
+    #[serde(default)]
+    pub dirty_tree: DirtyTreePolicy,
+    /// 版本同步失败
+    #[serde(default)]
+    pub error: ErrorPolicy,
+    /// 推送
+    #[serde(default)]
+    pub push: PushPolicy,
+    /// 版本时间乱序
+    #[serde(default)]
+    pub date_order: DateOrderPolicy,
+}
+
+impl SyncPolicies {
+    /// 按名称修改一项策略
+    ///
+    /// # 参数
+    ///
+    /// * `name`: 策略名，如 `empty-revision`
+    /// * `value`: 策略值，如 `skip`
+    pub fn set(&mut self, name: &str, value: &str) -> Result<()> {
+        match name {
+            "empty-revision" => self.empty_revision = parse_policy(name, value)?,
+            "conflict" => self.conflict = parse_policy(name, value)?,
+            "dirty-tree" => self.dirty_tree = parse_policy(name, value)?,
+            "error" => self.error = parse_policy(name, value)?,
+            "push" => self.push = parse_policy(name, value)?,
+            "date-order" => self.date_order = parse_policy(name, value)?,
+            _ => {
+                return Err(SyncError::App(format!(
+                    "未知的策略 '{name}'，可用：empty-revision、conflict、dirty-tree、error、push、date-order"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for SyncPolicies {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "empty-revision={}", policy_name(&self.empty_revision))?;
+        writeln!(f, "conflict={}", policy_name(&self.conflict))?;
+        writeln!(f, "dirty-tree={}", policy_name(&self.dirty_tree))?;
+        writeln!(f, "error={}", policy_name(&self.error))?;
+        writeln!(f, "push={}", policy_name(&self.push))?;
+        write!(f, "date-order={}", policy_name(&self.date_order))
+    }
+}
+
+/// 按配置文件中的名称解析策略值
+fn parse_policy<T: for<'de> Deserialize<'de>>(name: &str, value: &str) -> Result<T> {
+    serde_json::from_value(serde_json::Value::String(value.to_string()))
+        .map_err(|_| SyncError::App(format!("策略 {name} 不支持取值 '{value}'")))
+}
+
+/// 策略值在配置文件中的名称
+fn policy_name<T: Serialize>(policy: &T) -> String {
+    match serde_json::to_value(policy) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_policy() {
+        let mut policies = SyncPolicies::default();
+        policies.set("empty-revision", "skip").unwrap();
+        policies.set("error", "continue").unwrap();
+        assert_eq!(policies.empty_revision, EmptyRevisionPolicy::Skip);
+        assert_eq!(policies.error, ErrorPolicy::Continue);
+
+        assert!(policies.set("conflict", "merge").is_err());
+        assert!(policies.set("unknown", "stop").is_err());
+        assert!(policies.to_string().contains("empty-revision=skip\n"));
+        policies.set("empty-revision", "allow-empty").unwrap();
+        assert_eq!(policies.empty_revision, EmptyRevisionPolicy::AllowEmpty);
+        assert!(policies.to_string().ends_with("date-order=warn"));
+        policies.set("date-order", "clamp").unwrap();
+        assert_eq!(policies.date_order, DateOrderPolicy::Clamp);
+        policies.set("push", "always").unwrap();
+        assert_eq!(policies.push, PushPolicy::Always);
+    }
+
+    #[test]
+    fn test_set_dirty_tree_policy() {
+        let mut policies = SyncPolicies::default();
+        policies.set("dirty-tree", "stash").unwrap();
+        assert_eq!(policies.dirty_tree, DirtyTreePolicy::Stash);
+        policies.set("dirty-tree", "ask").unwrap();
+        assert!(policies.to_string().contains("dirty-tree=ask\n"));
+    }
+
+    #[test]
+    fn test_deserialize_missing_policies_as_default() {
+        let policies: SyncPolicies = serde_json::from_str(r#"{"dirty_tree":"fail"}"#).unwrap();
+        assert_eq!(policies.dirty_tree, DirtyTreePolicy::Fail);
+        assert_eq!(policies.conflict, ConflictPolicy::Stop);
+    }
+}
@@ -1,16 +1,39 @@
-use std::{fmt::Display, path::PathBuf};
+use std::{collections::BTreeMap, fmt::Display, path::PathBuf};
 
 use chrono::{DateTime, Local, Utc};
 
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
-use crate::ops::{GitOperationsFactory, ProviderType};
+use crate::config::{
+    PairHealth, RepoIdentity, RevisionMap, RunOutcome, SyncCheckpoint, SyncPlan, SyncPolicies,
+    SyncStartState,
+};
+use crate::depth::SparseScope;
+use crate::message::{DEFAULT_MESSAGE_PREFIX, RewriteRule};
+use crate::ops::{
+    CommandEnv, CommandSettings, GitOperationsFactory, GitProvider, ProviderType,
+    SvnOperationsFactory, SvnProvider,
+};
 
 /// 同步配置
 pub struct SyncConfig {
     pub svn_dir: PathBuf,
     pub git_dir: PathBuf,
     pub git_provider: ProviderType,
+    pub svn_provider: ProviderType,
+    /// 执行 svn/git 命令时额外设置的环境变量
+    pub env: CommandEnv,
+    /// 同步策略
+    pub policies: SyncPolicies,
+    /// 引用的配置方案名称
+    pub profile: Option<String>,
+    /// 提交说明的改写规则
+    pub message_rewrites: Vec<RewriteRule>,
+    /// Git 提交标题的前缀，可以为空
+    pub message_prefix: String,
+    /// 执行 svn/git 命令的设置
+    pub commands: CommandSettings,
 }
 
 impl SyncConfig {
@@ -22,14 +45,7 @@ impl SyncConfig {
     /// * `git_dir` - Git目录路径
     pub fn new(svn_dir: PathBuf, git_dir: PathBuf) -> Self {
         let git_provider = GitOperationsFactory::create_from_env();
-        Self {
-            svn_dir,
-            git_dir,
-            git_provider: match git_provider {
-                crate::ops::GitProvider::Real(_) => ProviderType::Real,
-                crate::ops::GitProvider::Mock(_) => ProviderType::Mock,
-            },
-        }
+        Self::with_git_provider(svn_dir, git_dir, git_provider.provider_type())
     }
 
     /// 创建指定Git提供者的同步配置
@@ -43,21 +59,70 @@ impl SyncConfig {
         svn_dir: PathBuf,
         git_dir: PathBuf,
         git_provider: ProviderType,
+    ) -> Self {
+        let svn_provider = match SvnOperationsFactory::create_from_env() {
+            crate::ops::SvnProvider::Real(_) => ProviderType::Real,
+            crate::ops::SvnProvider::Mock(_) => ProviderType::Mock,
+        };
+        Self::with_providers(svn_dir, git_dir, git_provider, svn_provider)
+    }
+
+    /// 创建指定Git和SVN提供者的同步配置
+    ///
+    /// # 参数
+    ///
+    /// * `svn_dir` - SVN目录路径
+    /// * `git_dir` - Git目录路径
+    /// * `git_provider` - Git提供者类型
+    /// * `svn_provider` - SVN提供者类型
+    pub fn with_providers(
+        svn_dir: PathBuf,
+        git_dir: PathBuf,
+        git_provider: ProviderType,
+        svn_provider: ProviderType,
     ) -> Self {
         Self {
             svn_dir,
             git_dir,
             git_provider,
+            svn_provider,
+            env: CommandEnv::new(),
+            policies: SyncPolicies::default(),
+            profile: None,
+            message_rewrites: Vec::new(),
+            message_prefix: DEFAULT_MESSAGE_PREFIX.to_string(),
+            commands: CommandSettings::default(),
         }
     }
 
+    /// 设置执行 svn/git 命令的设置
+    ///
+    /// # 参数
+    ///
+    /// * `commands` - 执行命令的设置
+    pub fn with_commands(mut self, commands: CommandSettings) -> Self {
+        self.commands = commands;
+        self
+    }
+
     /// 获取Git操作实例
     ///
     /// # 返回值
     ///
     /// 返回配置的Git操作实例
     pub fn create_git_operations(&self) -> crate::ops::GitProvider {
-        GitOperationsFactory::create(self.git_provider.clone())
+        GitProvider::with_env(self.git_provider.clone(), self.env.clone())
+            .with_settings(self.commands.clone())
+    }
+
+    /// 获取SVN操作实例
+    ///
+    /// # 返回值
+    ///
+    /// 返回配置的SVN操作实例
+    pub fn create_svn_operations(&self) -> crate::ops::SvnProvider {
+        SvnProvider::with_env(self.svn_provider.clone(), self.env.clone())
+            .with_settings(self.commands.clone())
     }
 }
 
@@ -68,6 +133,62 @@ pub struct HistoryRecord {
     svn_path: PathBuf,
     git_path: PathBuf,
     last_used: DateTime<Utc>,
+    #[serde(default)]
+    health: PairHealth,
+    /// 最后一次成功同步的 SVN 版本
+    #[serde(default)]
+    last_synced_rev: Option<String>,
+    /// 与 `last_synced_rev` 对应的 Git 提交哈希
+    #[serde(default)]
+    last_git_hash: Option<String>,
+    /// 最后一次成功同步的时间
+    #[serde(default)]
+    last_synced_at: Option<DateTime<Utc>>,
+    /// 未完成同步的检查点
+    #[serde(default)]
+    checkpoint: Option<SyncCheckpoint>,
+    /// 执行 svn/git 命令时额外设置的环境变量
+    #[serde(default)]
+    env: CommandEnv,
+    /// 已同步版本与 Git 提交的对应关系
+    #[serde(default)]
+    revision_map: RevisionMap,
+    /// 同步策略
+    #[serde(default)]
+    policies: SyncPolicies,
+    /// 上次计算的同步计划
+    #[serde(default)]
+    last_plan: Option<SyncPlan>,
+    /// 引用的配置方案名称
+    #[serde(default)]
+    profile: Option<String>,
+    /// 提交失败、尚未成功重试的 SVN 版本
+    #[serde(default)]
+    failed_revisions: Vec<String>,
+    /// 失败后更改随后续版本提交的 SVN 版本，值为一起提交的后续版本
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    folded_revisions: BTreeMap<String, String>,
+    /// 是否已暂停：暂停后 watch 和 sync-all 跳过该同步对
+    #[serde(default)]
+    paused: bool,
+    /// 稀疏工作副本的检出范围，完整检出时为 `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sparse_scope: Option<SparseScope>,
+    /// 绑定的 SVN 和 Git 仓库，第一次同步时记录
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    identity: Option<RepoIdentity>,
+    /// 提交说明的改写规则，按顺序应用
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    message_rewrites: Vec<RewriteRule>,
+    /// Git 提交标题的前缀，`None` 表示使用默认的 `SVN: `
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    message_prefix: Option<String>,
+    /// 最近一次同步的结果
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_run: Option<RunOutcome>,
+    /// 监视模式下两次同步之间的秒数，`None` 表示使用 watch 的 `--interval`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    watch_interval: Option<u64>,
 }
 
 impl HistoryRecord {
@@ -101,9 +222,383 @@ impl HistoryRecord {
             svn_path,
             git_path,
             last_used,
+            health: PairHealth::default(),
+            last_synced_rev: None,
+            last_git_hash: None,
+            last_synced_at: None,
+            checkpoint: None,
+            env: CommandEnv::new(),
+            revision_map: RevisionMap::default(),
+            policies: SyncPolicies::default(),
+            last_plan: None,
+            profile: None,
+            failed_revisions: Vec::new(),
+            folded_revisions: BTreeMap::new(),
+            paused: false,
+            sparse_scope: None,
+            identity: None,
+            message_rewrites: Vec::new(),
+            message_prefix: None,
+            last_run: None,
+            watch_interval: None,
         }
     }
 
+    /// 记录的编号
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// SVN 路径
+    pub fn svn_path(&self) -> &PathBuf {
+        &self.svn_path
+    }
+
+    /// Git 路径
+    pub fn git_path(&self) -> &PathBuf {
+        &self.git_path
+    }
+
+    /// 同步对标签，用于危险操作的输入确认
+    ///
+    /// 取 Git 目录名，没有目录名时使用完整路径
+    pub fn label(&self) -> String {
+        self.git_path
+            .file_name()
+            .unwrap_or(self.git_path.as_os_str())
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// 刷新最后使用时间
+    pub fn touch(&mut self) {
+        self.touch_at(Utc::now());
+    }
+
+    /// 把最后使用时间设置为指定时间
+    ///
+    /// # 参数
+    ///
+    /// * `now`: 当前时间
+    pub fn touch_at(&mut self, now: DateTime<Utc>) {
+        self.last_used = now;
+    }
+
+    /// 最后一次成功同步的 SVN 版本
+    pub fn last_synced_rev(&self) -> Option<&str> {
+        self.last_synced_rev.as_deref()
+    }
+
+    /// 与最后同步版本对应的 Git 提交哈希
+    pub fn last_git_hash(&self) -> Option<&str> {
+        self.last_git_hash.as_deref()
+    }
+
+    /// 记录同步进度
+    ///
+    /// # 参数
+    ///
+    /// * `rev`: 已同步的 SVN 版本
+    /// * `git_hash`: 对应的 Git 提交哈希
+    pub fn set_sync_state(&mut self, rev: &str, git_hash: &str) {
+        self.set_sync_state_at(rev, git_hash, Utc::now());
+    }
+
+    /// 记录同步进度，同步时间使用指定时间
+    ///
+    /// # 参数
+    ///
+    /// * `rev`: 已同步的 SVN 版本
+    /// * `git_hash`: 对应的 Git 提交哈希
+    /// * `now`: 当前时间
+    pub fn set_sync_state_at(&mut self, rev: &str, git_hash: &str, now: DateTime<Utc>) {
+        self.last_synced_rev = Some(rev.to_string());
+        self.last_git_hash = Some(git_hash.to_string());
+        self.last_synced_at = Some(now);
+        self.revision_map.insert(rev, git_hash);
+        self.failed_revisions.retain(|failed| failed != rev);
+        if let Some(checkpoint) = &mut self.checkpoint {
+            checkpoint.complete(rev);
+            if checkpoint.is_finished() {
+                self.checkpoint = None;
+            }
+        }
+    }
+
+    /// 把同步进度恢复到同步开始前的状态，并清除检查点
+    ///
+    /// # 参数
+    ///
+    /// * `start`: 同步开始前的状态
+    pub fn restore_sync_state(&mut self, start: &SyncStartState) {
+        self.last_synced_rev = start.last_synced_rev.clone();
+        self.last_git_hash = start.last_git_hash.clone();
+        self.revision_map.truncate(start.mapped_revisions);
+        self.checkpoint = None;
+    }
+
+    /// 最后一次成功同步的时间
+    pub fn last_synced_at(&self) -> Option<DateTime<Utc>> {
+        self.last_synced_at
+    }
+
+    /// 已同步版本与 Git 提交的对应关系
+    pub fn revision_map(&self) -> &RevisionMap {
+        &self.revision_map
+    }
+
+    /// 未完成同步的检查点
+    pub fn checkpoint(&self) -> Option<&SyncCheckpoint> {
+        self.checkpoint.as_ref()
+    }
+
+    /// 提交失败、尚未成功重试的 SVN 版本
+    pub fn failed_revisions(&self) -> &[String] {
+        &self.failed_revisions
+    }
+
+    /// 记录提交失败的 SVN 版本，该版本同步成功后自动移除
+    ///
+    /// # 参数
+    ///
+    /// * `rev`: 失败的 SVN 版本
+    pub fn add_failed_revision(&mut self, rev: &str) {
+        if !self.failed_revisions.iter().any(|failed| failed == rev) {
+            self.failed_revisions.push(rev.to_string());
+        }
+    }
+
+    /// 失败后更改随后续版本提交时，一起提交的后续版本
+    ///
+    /// # 参数
+    ///
+    /// * `rev`: 失败的 SVN 版本
+    pub fn folded_into(&self, rev: &str) -> Option<&str> {
+        self.folded_revisions.get(rev).map(String::as_str)
+    }
+
+    /// 失败版本的更改已随后续版本提交：移除失败记录，并映射到后续版本的提交
+    ///
+    /// # 参数
+    ///
+    /// * `rev`: 失败的 SVN 版本
+    /// * `into_rev`: 一起提交了其更改的后续版本
+    pub fn fold_failed_revision(&mut self, rev: &str, into_rev: &str) {
+        self.failed_revisions.retain(|failed| failed != rev);
+        self.folded_revisions
+            .insert(rev.to_string(), into_rev.to_string());
+        if let Some(hash) = self.revision_map.git_hash(into_rev).map(str::to_string) {
+            self.revision_map.insert(rev, &hash);
+        }
+    }
+
+    /// 执行 svn/git 命令时额外设置的环境变量
+    pub fn env(&self) -> &CommandEnv {
+        &self.env
+    }
+
+    /// 设置环境变量
+    ///
+    /// # 参数
+    ///
+    /// * `key`: 变量名
+    /// * `value`: 变量值
+    pub fn set_env(&mut self, key: &str, value: &str) {
+        self.env.insert(key.to_string(), value.to_string());
+    }
+
+    /// 移除环境变量
+    ///
+    /// # 返回
+    ///
+    /// 变量是否存在
+    pub fn unset_env(&mut self, key: &str) -> bool {
+        self.env.remove(key).is_some()
+    }
+
+    /// 同步策略
+    pub fn policies(&self) -> &SyncPolicies {
+        &self.policies
+    }
+
+    /// 获取可修改的同步策略
+    pub fn policies_mut(&mut self) -> &mut SyncPolicies {
+        &mut self.policies
+    }
+
+    /// 上次计算的同步计划
+    pub fn last_plan(&self) -> Option<&SyncPlan> {
+        self.last_plan.as_ref()
+    }
+
+    /// 记录本次计算的同步计划
+    ///
+    /// # 参数
+    ///
+    /// * `plan`: 同步计划
+    pub fn set_last_plan(&mut self, plan: SyncPlan) {
+        self.last_plan = Some(plan);
+    }
+
+    /// 引用的配置方案名称
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// 设置或清除引用的配置方案
+    ///
+    /// # 参数
+    ///
+    /// * `profile`: 配置方案名称，`None` 表示不使用配置方案
+    pub fn set_profile(&mut self, profile: Option<String>) {
+        self.profile = profile;
+    }
+
+    /// Git 提交标题的前缀
+    pub fn message_prefix(&self) -> &str {
+        self.message_prefix
+            .as_deref()
+            .unwrap_or(DEFAULT_MESSAGE_PREFIX)
+    }
+
+    /// 设置 Git 提交标题的前缀
+    ///
+    /// # 参数
+    ///
+    /// * `prefix`: 前缀，可以为空字符串；`None` 表示恢复默认的 `SVN: `
+    pub fn set_message_prefix(&mut self, prefix: Option<String>) {
+        self.message_prefix = prefix;
+    }
+
+    /// 监视模式下两次同步之间的秒数，`None` 表示使用 watch 的 `--interval`
+    pub fn watch_interval(&self) -> Option<u64> {
+        self.watch_interval
+    }
+
+    /// 设置监视模式下两次同步之间的秒数
+    ///
+    /// # 参数
+    ///
+    /// * `interval`: 秒数；`None` 表示使用 watch 的 `--interval`
+    pub fn set_watch_interval(&mut self, interval: Option<u64>) {
+        self.watch_interval = interval;
+    }
+
+    /// 是否已暂停自动同步
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// 暂停或恢复自动同步
+    ///
+    /// # 参数
+    ///
+    /// * `paused`: 是否暂停
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// 稀疏工作副本的检出范围，完整检出时为 `None`
+    pub fn sparse_scope(&self) -> Option<&SparseScope> {
+        self.sparse_scope.as_ref()
+    }
+
+    /// 记录工作副本的检出范围，完整检出时清除
+    ///
+    /// # 参数
+    ///
+    /// * `scope`: 检出范围
+    pub fn set_sparse_scope(&mut self, scope: SparseScope) {
+        self.sparse_scope = Some(scope).filter(|scope| !scope.is_full());
+    }
+
+    /// 绑定的 SVN 和 Git 仓库，还没有绑定时为 `None`
+    pub fn identity(&self) -> Option<&RepoIdentity> {
+        self.identity.as_ref()
+    }
+
+    /// 绑定或解除绑定仓库
+    ///
+    /// # 参数
+    ///
+    /// * `identity`: 绑定的仓库，`None` 表示解除绑定，下次同步时重新绑定
+    pub fn set_identity(&mut self, identity: Option<RepoIdentity>) {
+        self.identity = identity;
+    }
+
+    /// 提交说明的改写规则
+    pub fn message_rewrites(&self) -> &[RewriteRule] {
+        &self.message_rewrites
+    }
+
+    /// 获取可修改的改写规则
+    pub fn message_rewrites_mut(&mut self) -> &mut Vec<RewriteRule> {
+        &mut self.message_rewrites
+    }
+
+    /// 最近一次同步的结果，还没有同步过时为 `None`
+    pub fn last_run(&self) -> Option<&RunOutcome> {
+        self.last_run.as_ref()
+    }
+
+    /// 记录最近一次同步的结果
+    ///
+    /// # 参数
+    ///
+    /// * `outcome`: 同步结果
+    pub fn set_last_run(&mut self, outcome: RunOutcome) {
+        self.last_run = Some(outcome);
+    }
+
+    /// 是否存在未完成的同步
+    pub fn has_checkpoint(&self) -> bool {
+        self.checkpoint.is_some()
+    }
+
+    /// 设置或清除检查点
+    ///
+    /// # 参数
+    ///
+    /// * `checkpoint`: 新的检查点，`None` 表示清除
+    pub fn set_checkpoint(&mut self, checkpoint: Option<SyncCheckpoint>) {
+        self.checkpoint = checkpoint;
+    }
+
+    /// 获取健康状态
+    pub fn health(&self) -> &PairHealth {
+        &self.health
+    }
+
+    /// 获取可修改的健康状态
+    pub fn health_mut(&mut self) -> &mut PairHealth {
+        &mut self.health
+    }
+
+    /// 同步对在状态存储中的键
+    ///
+    /// 由 SVN 路径和 Git 路径计算，版本映射、检查点和同步计划按这个键保存
+    pub fn state_key(&self) -> String {
+        let pair = format!(
+            "{}\n{}",
+            self.svn_path.to_string_lossy(),
+            self.git_path.to_string_lossy()
+        );
+        let digest = format!("{:x}", Sha256::digest(pair.as_bytes()));
+        digest[..16].to_string()
+    }
+
+    /// 替换版本映射表，用于从状态存储读取
+    pub(crate) fn set_revision_map(&mut self, revision_map: RevisionMap) {
+        self.revision_map = revision_map;
+    }
+
+    /// 清除单独保存在状态存储中的版本映射、检查点和同步计划
+    pub(crate) fn clear_stored_state(&mut self) {
+        self.revision_map = RevisionMap::default();
+        self.checkpoint = None;
+        self.last_plan = None;
+    }
+
     /// 检查 id 是否相同
     ///
     /// # 参数
@@ -126,7 +621,13 @@ impl HistoryRecord {
     /// 转换为 `SyncConfig`
     pub fn to_sync_config(&self) -> SyncConfig {
         // 对于历史记录，我们使用默认的Git提供者（从环境变量读取）
-        SyncConfig::new(self.svn_path.clone(), self.git_path.clone())
+        let mut config = SyncConfig::new(self.svn_path.clone(), self.git_path.clone());
+        config.env = self.env.clone();
+        config.policies = self.policies.clone();
+        config.profile = self.profile.clone();
+        config.message_rewrites = self.message_rewrites.clone();
+        config.message_prefix = self.message_prefix().to_string();
+        config
     }
 }
 
@@ -135,10 +636,8 @@ pub fn cmp_last_used(a: &HistoryRecord, b: &HistoryRecord) -> std::cmp::Ordering
     a.last_used.cmp(&b.last_used)
 }
 
-/// 打印标题行
-pub fn print_title() {
-    println!("ID \tSVN Path \tGit Path \tLast Used");
-}
+/// 列表的标题行
+pub const LIST_TITLE: &str = "ID \tSVN Path \tGit Path \tLast Used \tLast Run";
 
 impl Display for HistoryRecord {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -152,6 +651,24 @@ impl Display for HistoryRecord {
             self.last_used
                 .with_timezone(&Local)
                 .format("%Y-%m-%d %H:%M:%S")
-        )
+        )?;
+        match &self.last_run {
+            Some(outcome) => write!(f, " \t{outcome}")?,
+            None => write!(f, " \t-")?,
+        }
+        if self.health.is_unhealthy() {
+            write!(
+                f,
+                " \t[异常: 连续失败 {} 次]",
+                self.health.consecutive_failures()
+            )?;
+        }
+        if let Some(checkpoint) = &self.checkpoint {
+            write!(f, " \t[未完成: 剩余 {} 条]", checkpoint.pending().len())?;
+        }
+        if self.paused {
+            write!(f, " \t[已暂停]")?;
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+
+/// SVN 版本与 Git 提交的对应关系
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RevisionMapping {
+    /// SVN 版本
+    pub svn_rev: String,
+    /// 对应的 Git 提交哈希
+    pub git_hash: String,
+}
+
+/// 按同步顺序记录的版本映射表
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(transparent)]
+pub struct RevisionMap {
+    entries: Vec<RevisionMapping>,
+}
+
+impl RevisionMap {
+    /// 记录一个版本对应的 Git 提交，已存在的版本会被覆盖
+    ///
+    /// # 参数
+    ///
+    /// * `svn_rev`: SVN 版本
+    /// * `git_hash`: Git 提交哈希
+    pub fn insert(&mut self, svn_rev: &str, git_hash: &str) {
+        match self.entries.iter_mut().find(|m| m.svn_rev == svn_rev) {
+            Some(mapping) => mapping.git_hash = git_hash.to_string(),
+            None => self.entries.push(RevisionMapping {
+                svn_rev: svn_rev.to_string(),
+                git_hash: git_hash.to_string(),
+            }),
+        }
+    }
+
+    /// 查找版本对应的 Git 提交
+    pub fn git_hash(&self, svn_rev: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|m| m.svn_rev == svn_rev)
+            .map(|m| m.git_hash.as_str())
+    }
+
+    /// 所有映射（按同步顺序）
+    pub fn entries(&self) -> &[RevisionMapping] {
+        &self.entries
+    }
+
+    /// 只保留前 `len` 个映射，用于回滚同步
+    ///
+    /// # 参数
+    ///
+    /// * `len`: 保留的映射数量
+    pub fn truncate(&mut self, len: usize) {
+        self.entries.truncate(len);
+    }
+
+    /// 映射数量
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 是否为空
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 转换为 `git fast-import` 的 marks 文件格式
+    ///
+    /// 每行 `:<SVN版本> <Git提交>`，可通过 `--import-marks` 供后续增量导入使用
+    pub fn to_marks(&self) -> String {
+        self.entries
+            .iter()
+            .map(|m| format!(":{} {}\n", m.svn_rev, m.git_hash))
+            .collect()
+    }
+
+    /// 均匀抽取最多 `count` 个映射，总是包含第一个和最后一个
+    ///
+    /// # 参数
+    ///
+    /// * `count`: 抽样数量
+    pub fn sample(&self, count: usize) -> Vec<&RevisionMapping> {
+        let len = self.entries.len();
+        if count >= len {
+            return self.entries.iter().collect();
+        }
+        match count {
+            0 => Vec::new(),
+            1 => vec![&self.entries[len - 1]],
+            _ => (0..count)
+                .map(|i| &self.entries[i * (len - 1) / (count - 1)])
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map_of(count: usize) -> RevisionMap {
+        let mut map = RevisionMap::default();
+        for rev in 1..=count {
+            map.insert(&rev.to_string(), &format!("hash{rev}"));
+        }
+        map
+    }
+
+    #[test]
+    fn test_insert_and_lookup() {
+        let mut map = map_of(2);
+        assert_eq!(map.git_hash("2"), Some("hash2"));
+
+        map.insert("2", "rewritten");
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.git_hash("2"), Some("rewritten"));
+        assert_eq!(map.git_hash("3"), None);
+    }
+
+    #[test]
+    fn test_to_marks() {
+        assert_eq!(map_of(2).to_marks(), ":1 hash1\n:2 hash2\n");
+        assert_eq!(RevisionMap::default().to_marks(), "");
+    }
+
+    #[test]
+    fn test_sample_should_spread_evenly() {
+        let map = map_of(10);
+        let revs: Vec<_> = map.sample(4).iter().map(|m| m.svn_rev.as_str()).collect();
+        assert_eq!(revs, vec!["1", "4", "7", "10"]);
+
+        assert_eq!(map.sample(1)[0].svn_rev, "10");
+        assert_eq!(map.sample(20).len(), 10);
+        assert!(map.sample(0).is_empty());
+    }
+}
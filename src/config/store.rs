@@ -0,0 +1,251 @@
+use std::collections::BTreeSet;
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    config::{FileStorage, HistoryRecord},
+    error::{Result, SyncError},
+};
+
+/// 历史记录所在的命名空间
+pub const HISTORY_NAMESPACE: &str = "history";
+
+/// 历史记录在命名空间中的键
+pub const HISTORY_KEY: &str = "records";
+
+/// 版本映射表所在的命名空间，键为 [`HistoryRecord::state_key`]
+pub const REVISION_MAP_NAMESPACE: &str = "revision-map";
+
+/// 同步检查点所在的命名空间，键为 [`HistoryRecord::state_key`]
+pub const CHECKPOINT_NAMESPACE: &str = "checkpoint";
+
+/// 同步计划所在的命名空间，键为 [`HistoryRecord::state_key`]
+pub const PLAN_NAMESPACE: &str = "plan";
+
+/// 按同步对单独保存的状态所在的命名空间
+pub const PAIR_STATE_NAMESPACES: [&str; 3] =
+    [REVISION_MAP_NAMESPACE, CHECKPOINT_NAMESPACE, PLAN_NAMESPACE];
+
+/// 持久化状态存储
+///
+/// 所有需要持久化的状态（历史记录、版本映射、日志、缓存等）都按
+/// “命名空间 + 键” 保存为字节内容，不同功能使用不同的命名空间，
+/// 存储后端（磁盘文件、数据库、内存）只需实现这一个接口。
+pub trait StateStore {
+    /// 读取键的内容，不存在时返回 `None`
+    ///
+    /// # 参数
+    ///
+    /// * `namespace`: 命名空间
+    /// * `key`: 键
+    fn get(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// 写入键的内容，已存在时覆盖
+    ///
+    /// # 参数
+    ///
+    /// * `namespace`: 命名空间
+    /// * `key`: 键
+    /// * `value`: 内容
+    fn put(&self, namespace: &str, key: &str, value: &[u8]) -> Result<()>;
+
+    /// 删除键，不存在时忽略
+    ///
+    /// # 参数
+    ///
+    /// * `namespace`: 命名空间
+    /// * `key`: 键
+    fn remove(&self, namespace: &str, key: &str) -> Result<()>;
+
+    /// 列出命名空间中的所有键（按字典序）
+    ///
+    /// # 参数
+    ///
+    /// * `namespace`: 命名空间
+    fn keys(&self, namespace: &str) -> Result<Vec<String>>;
+}
+
+/// 装箱的状态存储，便于运行时选择存储后端
+impl<T: StateStore + ?Sized> StateStore for Box<T> {
+    fn get(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>> {
+        (**self).get(namespace, key)
+    }
+
+    fn put(&self, namespace: &str, key: &str, value: &[u8]) -> Result<()> {
+        (**self).put(namespace, key, value)
+    }
+
+    fn remove(&self, namespace: &str, key: &str) -> Result<()> {
+        (**self).remove(namespace, key)
+    }
+
+    fn keys(&self, namespace: &str) -> Result<Vec<String>> {
+        (**self).keys(namespace)
+    }
+}
+
+/// 任何状态存储都可以保存历史记录（JSON 格式）
+///
+/// 版本映射、检查点和同步计划随同步不断增长或变化，按同步对分别保存在
+/// 各自的命名空间中，历史记录中只保留其余配置。旧版本写在历史记录中的内容
+/// 在没有对应的键时照常读取，下次保存时迁移到命名空间。
+impl<T: StateStore> FileStorage for T {
+    fn load(&self) -> Result<Vec<HistoryRecord>> {
+        let mut records: Vec<HistoryRecord> = match self.get(HISTORY_NAMESPACE, HISTORY_KEY)? {
+            Some(buf) => serde_json::from_slice(&buf).map_err(SyncError::Json)?,
+            None => return Ok(Vec::new()),
+        };
+        for record in &mut records {
+            let key = record.state_key();
+            if let Some(revision_map) = read_state(self, REVISION_MAP_NAMESPACE, &key)? {
+                record.set_revision_map(revision_map);
+            }
+            if let Some(checkpoint) = read_state(self, CHECKPOINT_NAMESPACE, &key)? {
+                record.set_checkpoint(Some(checkpoint));
+            }
+            if let Some(plan) = read_state(self, PLAN_NAMESPACE, &key)? {
+                record.set_last_plan(plan);
+            }
+        }
+        Ok(records)
+    }
+
+    fn save(&self, records: &[HistoryRecord]) -> Result<()> {
+        let mut keys = BTreeSet::new();
+        let mut stored = Vec::with_capacity(records.len());
+        for record in records {
+            let key = record.state_key();
+            let revision_map = Some(record.revision_map()).filter(|map| !map.is_empty());
+            write_state(self, REVISION_MAP_NAMESPACE, &key, revision_map)?;
+            write_state(self, CHECKPOINT_NAMESPACE, &key, record.checkpoint())?;
+            write_state(self, PLAN_NAMESPACE, &key, record.last_plan())?;
+            keys.insert(key);
+
+            let mut record = record.clone();
+            record.clear_stored_state();
+            stored.push(record);
+        }
+        // 已删除的同步对不再保留状态
+        for namespace in PAIR_STATE_NAMESPACES {
+            for key in self.keys(namespace)? {
+                if !keys.contains(&key) {
+                    self.remove(namespace, &key)?;
+                }
+            }
+        }
+
+        let buf = serde_json::to_vec(&stored)?;
+        self.put(HISTORY_NAMESPACE, HISTORY_KEY, &buf)
+    }
+}
+
+/// 读取 JSON 格式的状态，不存在时返回 `None`
+fn read_state<S: StateStore, T: DeserializeOwned>(
+    store: &S,
+    namespace: &str,
+    key: &str,
+) -> Result<Option<T>> {
+    match store.get(namespace, key)? {
+        Some(buf) => Ok(Some(serde_json::from_slice(&buf)?)),
+        None => Ok(None),
+    }
+}
+
+/// 以 JSON 格式写入状态，`None` 表示删除；内容没有变化时不写入
+fn write_state<S: StateStore, T: Serialize>(
+    store: &S,
+    namespace: &str,
+    key: &str,
+    state: Option<&T>,
+) -> Result<()> {
+    let Some(state) = state else {
+        return store.remove(namespace, key);
+    };
+    let buf = serde_json::to_vec(state)?;
+    if store.get(namespace, key)?.as_deref() != Some(buf.as_slice()) {
+        store.put(namespace, key, &buf)?;
+    }
+    Ok(())
+}
+
+/// 检查命名空间和键能否安全地用作路径段
+pub(crate) fn validate_store_key(namespace: &str, key: &str) -> Result<()> {
+    for part in [namespace, key] {
+        if part.is_empty() || part == "." || part == ".." || part.contains(['/', '\\']) {
+            return Err(SyncError::App(format!(
+                "无效的存储键 {namespace}/{key}：不能为空，也不能包含路径分隔符"
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::config::{DiskStorage, HistoryManager, MemoryStorage, SyncCheckpoint};
+
+    #[test]
+    fn test_validate_store_key() {
+        assert!(validate_store_key("history", "records").is_ok());
+        assert!(validate_store_key("cache", "r12.json").is_ok());
+        assert!(validate_store_key("", "records").is_err());
+        assert!(validate_store_key("history", "..").is_err());
+        assert!(validate_store_key("history", "a/b").is_err());
+        assert!(validate_store_key("a\\b", "records").is_err());
+    }
+
+    #[test]
+    fn test_save_should_keep_pair_state_in_namespaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = DiskStorage::new(dir.path().join("config.json"));
+        let (svn, git) = (PathBuf::from("svn"), PathBuf::from("git"));
+        let mut history =
+            HistoryManager::new(DiskStorage::new(dir.path().join("config.json"))).unwrap();
+        history.add_record(svn.clone(), git.clone());
+        history.update_sync_state(&svn, &git, "7", "abc123");
+        history.set_checkpoint(&svn, &git, Some(SyncCheckpoint::new(vec!["8".into()])));
+        history.save().unwrap();
+
+        let key = history.find_record(&svn, &git).unwrap().state_key();
+        for namespace in [REVISION_MAP_NAMESPACE, CHECKPOINT_NAMESPACE] {
+            assert_eq!(storage.keys(namespace).unwrap(), [key.as_str()]);
+        }
+        // 历史记录中不再包含版本映射和检查点
+        let records = storage
+            .get(HISTORY_NAMESPACE, HISTORY_KEY)
+            .unwrap()
+            .unwrap();
+        let records = String::from_utf8(records).unwrap();
+        assert!(!records.contains("svn_rev") && !records.contains("pending"));
+
+        let loaded = storage.load().unwrap();
+        assert_eq!(loaded[0].revision_map().git_hash("7"), Some("abc123"));
+        assert!(loaded[0].has_checkpoint());
+
+        // 删除同步对时一起删除它的状态
+        history.remove_record(0).unwrap();
+        for namespace in PAIR_STATE_NAMESPACES {
+            assert!(storage.keys(namespace).unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_load_should_migrate_state_saved_in_history_records() {
+        let storage = MemoryStorage::new();
+        let legacy = r#"[{"id":1,"svn_path":"svn","git_path":"git","last_used":"2025-01-01T00:00:00Z","revision_map":[{"svn_rev":"7","git_hash":"abc123"}]}]"#;
+        storage
+            .put(HISTORY_NAMESPACE, HISTORY_KEY, legacy.as_bytes())
+            .unwrap();
+
+        let records = storage.load().unwrap();
+        assert_eq!(records[0].revision_map().git_hash("7"), Some("abc123"));
+
+        storage.save(&records).unwrap();
+        let key = records[0].state_key();
+        assert!(storage.get(REVISION_MAP_NAMESPACE, &key).unwrap().is_some());
+        assert_eq!(storage.load().unwrap(), records);
+    }
+}
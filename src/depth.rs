@@ -0,0 +1,171 @@
+//! SVN 稀疏工作副本：各目录的检出深度（`svn checkout --depth`/`svn update --set-depth`）
+
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// SVN 目录的检出深度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SvnDepth {
+    /// 只有目录本身
+    Empty,
+    /// 目录和其中的文件
+    Files,
+    /// 目录、其中的文件和（深度为 empty 的）子目录
+    Immediates,
+    /// 完整的目录树
+    Infinity,
+}
+
+impl FromStr for SvnDepth {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "empty" => Ok(Self::Empty),
+            "files" => Ok(Self::Files),
+            "immediates" => Ok(Self::Immediates),
+            "infinity" => Ok(Self::Infinity),
+            _ => Err(format!(
+                "无效的检出深度：{s}（可用 empty、files、immediates、infinity）"
+            )),
+        }
+    }
+}
+
+impl Display for SvnDepth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Empty => "empty",
+            Self::Files => "files",
+            Self::Immediates => "immediates",
+            Self::Infinity => "infinity",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// 工作副本实际检出的范围
+///
+/// 记录每个已检出目录（相对工作副本根目录，`/` 分隔，根目录为空字符串）的深度。
+/// 深度为 infinity 的目录之下的目录不单独记录。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SparseScope {
+    dirs: BTreeMap<String, SvnDepth>,
+}
+
+impl SparseScope {
+    /// 根据 `svn info -R` 列出的目录深度创建检出范围
+    ///
+    /// # 参数
+    ///
+    /// * `dirs`: 已检出的目录及其深度
+    pub fn new(dirs: impl IntoIterator<Item = (String, SvnDepth)>) -> Self {
+        let all: BTreeMap<String, SvnDepth> = dirs.into_iter().collect();
+        let dirs = all
+            .iter()
+            .filter(|(dir, _)| {
+                !ancestors(dir).any(|parent| all.get(parent) == Some(&SvnDepth::Infinity))
+            })
+            .map(|(dir, depth)| (dir.clone(), *depth))
+            .collect();
+        Self { dirs }
+    }
+
+    /// 完整检出的工作副本
+    pub fn full() -> Self {
+        Self::new([(String::new(), SvnDepth::Infinity)])
+    }
+
+    /// 是否为完整检出（不是稀疏工作副本）
+    pub fn is_full(&self) -> bool {
+        self.dirs.get("") == Some(&SvnDepth::Infinity)
+    }
+
+    /// 文件是否在检出范围内
+    ///
+    /// # 参数
+    ///
+    /// * `path`: 相对工作副本根目录的文件路径，`/` 分隔
+    pub fn contains(&self, path: &str) -> bool {
+        let path = path.trim_matches('/');
+        let mut dirs = ancestors(path);
+        let Some(parent) = dirs.next() else {
+            return false;
+        };
+        if let Some(depth) = self.dirs.get(parent) {
+            return *depth != SvnDepth::Empty;
+        }
+        dirs.any(|dir| self.dirs.get(dir) == Some(&SvnDepth::Infinity))
+    }
+
+    /// 已检出的目录及其深度
+    pub fn dirs(&self) -> &BTreeMap<String, SvnDepth> {
+        &self.dirs
+    }
+}
+
+impl Display for SparseScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let dirs: Vec<String> = self
+            .dirs
+            .iter()
+            .map(|(dir, depth)| match dir.is_empty() {
+                true => format!("根目录={depth}"),
+                false => format!("{dir}={depth}"),
+            })
+            .collect();
+        write!(f, "{}", dirs.join("，"))
+    }
+}
+
+/// 路径的各级上级目录，由近到远，最后是根目录（空字符串）
+fn ancestors(path: &str) -> impl Iterator<Item = &str> {
+    let mut rest = Some(path).filter(|path| !path.is_empty());
+    std::iter::from_fn(move || {
+        let current = rest?;
+        let parent = current.rsplit_once('/').map_or("", |(parent, _)| parent);
+        rest = Some(parent).filter(|parent| !parent.is_empty());
+        Some(parent)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparse_scope_contains() {
+        let scope = SparseScope::new([
+            (String::new(), SvnDepth::Immediates),
+            ("docs".into(), SvnDepth::Empty),
+            ("src".into(), SvnDepth::Infinity),
+            ("src/core".into(), SvnDepth::Infinity),
+            ("lib".into(), SvnDepth::Files),
+        ]);
+        assert!(!scope.is_full());
+        assert_eq!(scope.dirs().len(), 4);
+
+        assert!(scope.contains("README"));
+        assert!(scope.contains("src/core/a.rs"));
+        assert!(scope.contains("lib/a.jar"));
+        assert!(!scope.contains("lib/x/a.jar"));
+        assert!(!scope.contains("docs/guide.md"));
+        assert!(!scope.contains("tests/a.rs"));
+        assert_eq!(
+            scope.to_string(),
+            "根目录=immediates，docs=empty，lib=files，src=infinity"
+        );
+
+        assert!(SparseScope::full().is_full());
+        assert!(SparseScope::full().contains("a/b/c.txt"));
+    }
+
+    #[test]
+    fn test_parse_svn_depth() {
+        assert_eq!("files".parse::<SvnDepth>(), Ok(SvnDepth::Files));
+        assert!("all".parse::<SvnDepth>().is_err());
+        assert_eq!(SvnDepth::Immediates.to_string(), "immediates");
+    }
+}
@@ -0,0 +1,427 @@
+//! 环境检查：确认外部命令、配置文件和同步对目录可用
+
+use std::{fmt::Display, path::Path};
+
+use crate::{
+    config::{DiskStorage, HistoryManager, HistoryRecord, StateStore},
+    error::Result,
+    ops::{GitOperations, RealGitOperations, RealSvnOperations, SvnOperations},
+    profile::ConversionProfile,
+};
+
+/// 支持的最低 Git 版本
+pub const MIN_GIT_VERSION: (u32, u32) = (2, 0);
+
+/// 支持的最低 SVN 版本（单个 `.svn` 目录的工作副本格式）
+pub const MIN_SVN_VERSION: (u32, u32) = (1, 7);
+
+/// 检查结果的级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckLevel {
+    /// 检查通过
+    Ok,
+    /// 可以同步，但可能不是想要的效果
+    Warning,
+    /// 同步会失败，需要先修复
+    Error,
+}
+
+/// 一项检查的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct DoctorCheck {
+    /// 级别
+    pub level: CheckLevel,
+    /// 检查的对象，如 `Git`、`同步对 0`
+    pub subject: String,
+    /// 检查结果
+    pub message: String,
+    /// 修复建议
+    pub fix: Option<String>,
+}
+
+impl DoctorCheck {
+    fn ok(subject: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            level: CheckLevel::Ok,
+            subject: subject.into(),
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn problem(
+        level: CheckLevel,
+        subject: impl Into<String>,
+        message: impl Into<String>,
+        fix: impl Into<String>,
+    ) -> Self {
+        Self {
+            level,
+            subject: subject.into(),
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+impl Display for DoctorCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self.level {
+            CheckLevel::Ok => "通过",
+            CheckLevel::Warning => "警告",
+            CheckLevel::Error => "错误",
+        };
+        write!(f, "[{label}] {}：{}", self.subject, self.message)?;
+        if let Some(fix) = &self.fix {
+            write!(f, "\n    修复：{fix}")?;
+        }
+        Ok(())
+    }
+}
+
+/// 环境检查报告
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DoctorReport {
+    checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    /// 所有检查结果
+    pub fn checks(&self) -> &[DoctorCheck] {
+        &self.checks
+    }
+
+    /// 指定级别的检查数量
+    pub fn count(&self, level: CheckLevel) -> usize {
+        self.checks.iter().filter(|c| c.level == level).count()
+    }
+}
+
+impl Display for DoctorReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for check in &self.checks {
+            writeln!(f, "{check}")?;
+        }
+        match (
+            self.count(CheckLevel::Error),
+            self.count(CheckLevel::Warning),
+        ) {
+            (0, 0) => write!(f, "环境检查通过"),
+            (errors, warnings) => write!(f, "发现 {errors} 个错误，{warnings} 个警告"),
+        }
+    }
+}
+
+/// 检查运行环境
+///
+/// 依次检查 git 和 svn 命令及其版本、配置文件能否解析、每个同步对的目录是否为有效的
+/// SVN 工作副本和 Git 仓库，以及引用的配置方案是否存在。只读操作，不会修改任何内容。
+///
+/// # 参数
+///
+/// * `config_file`: 配置文件
+pub fn run_doctor(config_file: &Path) -> DoctorReport {
+    let mut report = DoctorReport::default();
+    report.checks.push(check_tool(
+        "Git",
+        RealGitOperations::check_git_available(),
+        MIN_GIT_VERSION,
+        "安装 Git 并确认 git 命令在 PATH 中",
+    ));
+    report.checks.push(check_tool(
+        "SVN",
+        RealSvnOperations::check_svn_available(),
+        MIN_SVN_VERSION,
+        "安装 Subversion 命令行客户端（Windows 上 TortoiseSVN 需勾选 command line client tools）并确认 svn 命令在 PATH 中",
+    ));
+
+    let (check, records) = check_config(config_file);
+    report.checks.push(check);
+    let profiles = DiskStorage::new(config_file.into());
+    for (index, record) in records.iter().enumerate() {
+        let config = record.to_sync_config();
+        report.checks.extend(check_pair(
+            index,
+            record,
+            &config.create_svn_operations(),
+            &config.create_git_operations(),
+            &profiles,
+        ));
+    }
+    report
+}
+
+/// 检查外部命令及其版本
+///
+/// # 参数
+///
+/// * `name`: 命令名称
+/// * `version`: 版本检查的结果，成功时为命令输出的版本信息
+/// * `min`: 最低版本（主版本号，次版本号）
+/// * `install_hint`: 命令不可用时的修复建议
+pub fn check_tool(
+    name: &str,
+    version: Result<String>,
+    min: (u32, u32),
+    install_hint: &str,
+) -> DoctorCheck {
+    let output = match version {
+        Ok(output) => output,
+        Err(e) => {
+            return DoctorCheck::problem(CheckLevel::Error, name, e.to_string(), install_hint);
+        }
+    };
+    match parse_version(&output) {
+        Some(version) if version >= min => DoctorCheck::ok(name, output),
+        Some(_) => DoctorCheck::problem(
+            CheckLevel::Error,
+            name,
+            format!("版本过低：{output}"),
+            format!("升级到 {}.{} 或更高版本", min.0, min.1),
+        ),
+        None => DoctorCheck::problem(
+            CheckLevel::Warning,
+            name,
+            format!("无法识别版本：{output}"),
+            format!("确认版本不低于 {}.{}", min.0, min.1),
+        ),
+    }
+}
+
+/// 从版本输出中取出主版本号和次版本号，如 `git version 2.43.0` -> (2, 43)
+fn parse_version(output: &str) -> Option<(u32, u32)> {
+    let version = output
+        .split_whitespace()
+        .find(|word| word.starts_with(|c: char| c.is_ascii_digit()))?;
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts
+        .next()
+        .map(|minor| {
+            minor
+                .chars()
+                .take_while(char::is_ascii_digit)
+                .collect::<String>()
+        })
+        .and_then(|minor| minor.parse().ok())
+        .unwrap_or(0);
+    Some((major, minor))
+}
+
+/// 检查配置文件能否解析
+///
+/// # 参数
+///
+/// * `config_file`: 配置文件
+///
+/// # 返回
+///
+/// 检查结果和配置文件中的同步对，无法解析时同步对为空
+pub fn check_config(config_file: &Path) -> (DoctorCheck, Vec<HistoryRecord>) {
+    let subject = format!("配置文件 {}", config_file.display());
+    if !config_file.exists() {
+        return (
+            DoctorCheck::problem(
+                CheckLevel::Warning,
+                subject,
+                "不存在",
+                "执行 svn2git sync 添加第一个同步对",
+            ),
+            Vec::new(),
+        );
+    }
+    match HistoryManager::new(DiskStorage::new(config_file.into())) {
+        Ok(history) => (
+            DoctorCheck::ok(subject, format!("{} 个同步对", history.records().len())),
+            history.records().to_vec(),
+        ),
+        Err(e) => (
+            DoctorCheck::problem(
+                CheckLevel::Error,
+                subject,
+                format!("无法解析：{e}"),
+                "修复文件中的 JSON 格式错误，或备份后删除该文件重新添加同步对",
+            ),
+            Vec::new(),
+        ),
+    }
+}
+
+/// 检查一个同步对
+///
+/// # 参数
+///
+/// * `index`: 同步对的历史记录索引
+/// * `record`: 同步对
+/// * `svn_ops`: SVN操作实现
+/// * `git_ops`: Git操作实现
+/// * `profiles`: 保存配置方案的状态存储
+pub fn check_pair(
+    index: usize,
+    record: &HistoryRecord,
+    svn_ops: &dyn SvnOperations,
+    git_ops: &dyn GitOperations,
+    profiles: &dyn StateStore,
+) -> Vec<DoctorCheck> {
+    let subject = format!("同步对 {index}");
+    let (svn_dir, git_dir) = (record.svn_path(), record.git_path());
+    let mut checks = Vec::new();
+
+    checks.push(if !svn_dir.exists() {
+        DoctorCheck::problem(
+            CheckLevel::Error,
+            &subject,
+            format!("SVN 目录 {} 不存在", svn_dir.display()),
+            format!(
+                "执行 svn checkout <URL> {}，或同步时用 --svn-url 指定仓库自动检出",
+                svn_dir.display()
+            ),
+        )
+    } else {
+        match svn_ops.info(svn_dir) {
+            Ok(info) => DoctorCheck::ok(
+                &subject,
+                format!(
+                    "SVN 工作副本 {}（{} r{}）",
+                    svn_dir.display(),
+                    info.url,
+                    info.revision
+                ),
+            ),
+            Err(e) => DoctorCheck::problem(
+                CheckLevel::Error,
+                &subject,
+                format!("{} 不是有效的 SVN 工作副本：{e}", svn_dir.display()),
+                format!(
+                    "在该目录执行 svn cleanup；工作副本格式过旧时执行 svn upgrade；否则重新检出。需要代理或凭据时用 history env {index} --set 设置环境变量"
+                ),
+            ),
+        }
+    });
+
+    checks.push(if !git_dir.exists() {
+        DoctorCheck::problem(
+            CheckLevel::Error,
+            &subject,
+            format!("Git 目录 {} 不存在", git_dir.display()),
+            format!("执行 git init {} 创建仓库后再同步", git_dir.display()),
+        )
+    } else {
+        match git_ops.is_repository(git_dir) {
+            Ok(true) => DoctorCheck::ok(&subject, format!("Git 仓库 {}", git_dir.display())),
+            Ok(false) => DoctorCheck::problem(
+                CheckLevel::Error,
+                &subject,
+                format!("{} 不是已初始化的 Git 仓库", git_dir.display()),
+                "在该目录执行 git init，或在 history 中改为正确的 Git 目录",
+            ),
+            Err(e) => DoctorCheck::problem(
+                CheckLevel::Error,
+                &subject,
+                format!("无法检查 Git 目录 {}：{e}", git_dir.display()),
+                "确认 git 命令可用且有权限读取该目录",
+            ),
+        }
+    });
+
+    if let Some(name) = record.profile()
+        && let Err(e) = ConversionProfile::load(profiles, name)
+    {
+        checks.push(DoctorCheck::problem(
+            CheckLevel::Error,
+            &subject,
+            e.to_string(),
+            format!("用 profile save {name} 重新创建，或执行 history profile {index} --unset"),
+        ));
+    }
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::MemoryStorage,
+        error::SyncError,
+        ops::{MockGitOperations, MockSvnOperations},
+    };
+
+    #[test]
+    fn test_check_tool_versions() {
+        let check = |output: Result<String>| check_tool("Git", output, (2, 0), "安装 Git");
+        assert_eq!(check(Ok("git version 2.43.0".into())).level, CheckLevel::Ok);
+        assert_eq!(
+            check(Ok("git version 2.39.3 (Apple Git-146)".into())).level,
+            CheckLevel::Ok
+        );
+        let old = check(Ok("git version 1.9.5.msysgit.0".into()));
+        assert_eq!(old.level, CheckLevel::Error);
+        assert_eq!(old.fix.as_deref(), Some("升级到 2.0 或更高版本"));
+        assert_eq!(check(Ok("unknown".into())).level, CheckLevel::Warning);
+        let missing = check(Err(SyncError::App("无法执行Git命令".into())));
+        assert_eq!(missing.level, CheckLevel::Error);
+        assert_eq!(
+            missing.to_string(),
+            "[错误] Git：Application error: 无法执行Git命令\n    修复：安装 Git"
+        );
+
+        assert_eq!(parse_version("1.14.2"), Some((1, 14)));
+        assert_eq!(
+            parse_version("svn, version 1.7.22 (r1694152)"),
+            Some((1, 7))
+        );
+    }
+
+    #[test]
+    fn test_check_config_and_pairs() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_file = dir.path().join("config.json");
+        assert_eq!(check_config(&config_file).0.level, CheckLevel::Warning);
+
+        std::fs::write(&config_file, "{ broken").unwrap();
+        let (check, records) = check_config(&config_file);
+        assert_eq!(check.level, CheckLevel::Error);
+        assert!(records.is_empty());
+
+        let svn_dir = dir.path().join("svn");
+        let git_dir = dir.path().join("git");
+        let mut record = HistoryRecord::new(1, svn_dir.clone(), git_dir.clone());
+        record.set_profile(Some("missing".into()));
+        std::fs::remove_file(&config_file).unwrap();
+        let mut history = HistoryManager::new(DiskStorage::new(config_file.clone())).unwrap();
+        history.add_record(svn_dir.clone(), git_dir.clone());
+        history.save().unwrap();
+        let (check, records) = check_config(&config_file);
+        assert_eq!((check.level, records.len()), (CheckLevel::Ok, 1));
+
+        let svn_ops = MockSvnOperations::new();
+        let git_ops = MockGitOperations::new();
+        let profiles = MemoryStorage::new();
+        let checks = check_pair(0, &record, &svn_ops, &git_ops, &profiles);
+        let levels: Vec<_> = checks.iter().map(|c| c.level).collect();
+        assert_eq!(levels, [CheckLevel::Error; 3]);
+        assert!(checks[0].message.contains("不存在"));
+        assert!(checks[1].fix.as_deref().unwrap().contains("git init"));
+        assert!(
+            checks[2]
+                .fix
+                .as_deref()
+                .unwrap()
+                .contains("history profile 0 --unset")
+        );
+
+        std::fs::create_dir(&svn_dir).unwrap();
+        std::fs::create_dir(&git_dir).unwrap();
+        svn_ops.add_log_to_mock(&svn_dir, "1", "初始提交");
+        let checks = check_pair(0, &record, &svn_ops, &git_ops, &profiles);
+        assert!(checks[1].message.contains("不是已初始化的 Git 仓库"));
+
+        git_ops.init(&git_dir).unwrap();
+        record.set_profile(None);
+        let checks = check_pair(0, &record, &svn_ops, &git_ops, &profiles);
+        assert!(
+            checks.iter().all(|c| c.level == CheckLevel::Ok),
+            "{checks:?}"
+        );
+    }
+}
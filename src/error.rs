@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use thiserror::Error;
 
 /// 导出错误类型
@@ -18,6 +20,68 @@ pub enum SyncError {
     #[error("Application error: {0}")]
     App(String),
 
+    #[error("{} 不是有效的 SVN 工作副本：{reason}", path.display())]
+    NotSvnWorkingCopy { path: PathBuf, reason: String },
+
+    #[error("{} 不是已初始化的 Git 仓库，请先执行 git init", .0.display())]
+    NotGitRepository(PathBuf),
+
+    #[error(
+        "Git 工作目录 {} 有未提交的更改，按策略拒绝同步（可用 --dirty-tree stash 暂存后同步，或 --force 继续）",
+        .0.display()
+    )]
+    DirtyWorkingTree(PathBuf),
+
+    #[error("SVN 更新到 r{rev} 时发生冲突：{}", paths.join("、"))]
+    SvnConflict { rev: String, paths: Vec<String> },
+
+    #[error("同步第 {index} 条日志失败（SVN r{rev}）：{source}{}", describe_rollback(rollback.as_deref()))]
+    Revision {
+        /// 日志在本次同步中的序号，从 1 开始
+        index: usize,
+        /// 失败的 SVN 版本
+        rev: String,
+        /// 原始错误，保留类型便于识别认证失败等情况
+        source: Box<SyncError>,
+        /// 回滚 Git 仓库和 SVN 工作副本的结果
+        rollback: Option<String>,
+    },
+
+    #[error("{command} 超过 {} 秒未结束，已终止（可用 --svn-timeout/--git-timeout 调整）", timeout.as_secs_f64())]
+    CommandTimeout {
+        command: String,
+        timeout: std::time::Duration,
+    },
+
+    #[error("{action}失败（{program} {args}，{}）：{}", describe_exit(*exit_code), describe_stderr(stderr))]
+    GitCommand {
+        /// 失败的操作，如“获取Git状态”
+        action: String,
+        /// 命令名称
+        program: String,
+        /// 命令参数，以空格连接
+        args: String,
+        /// 退出码，被信号终止时为 `None`
+        exit_code: Option<i32>,
+        /// 标准错误（为空时取标准输出）
+        stderr: String,
+    },
+
+    #[error("{action}失败（svn {args}，{}）：{}", describe_exit(*exit_code), describe_stderr(stderr))]
+    SvnCommand {
+        /// 失败的操作，如“svn log”
+        action: String,
+        /// 命令参数，以空格连接
+        args: String,
+        /// 退出码，被信号终止时为 `None`
+        exit_code: Option<i32>,
+        /// 标准错误（为空时取标准输出）
+        stderr: String,
+    },
+
+    #[error("找不到 {0} 命令，请确认已安装并已加入 PATH")]
+    CommandNotFound(String),
+
     #[error("Json error: {0}")]
     Json(#[from] serde_json::Error),
 
@@ -26,4 +90,81 @@ pub enum SyncError {
 
     #[error("Roxmltree error: {0}")]
     Roxmltree(#[from] roxmltree::Error),
+
+    #[error("Git2 error: {0}")]
+    Git2(#[from] git2::Error),
+}
+
+/// SVN 认证失败的错误码：认证失败、无法获取凭据
+const SVN_AUTH_ERRORS: [&str; 2] = ["E170001", "E215004"];
+
+/// SVN 工作副本格式过旧、需要 `svn upgrade` 的错误码
+const SVN_UPGRADE_REQUIRED: &str = "E155036";
+
+/// Git 认证失败时标准错误中的提示
+const GIT_AUTH_ERRORS: [&str; 5] = [
+    "Authentication failed",
+    "could not read Username",
+    "could not read Password",
+    "Permission denied (publickey",
+    "terminal prompts disabled",
+];
+
+impl SyncError {
+    /// 去掉同步版本时附加的上下文后的原始错误
+    pub fn root_cause(&self) -> &SyncError {
+        match self {
+            Self::Revision { source, .. } => source.root_cause(),
+            e => e,
+        }
+    }
+
+    /// 外部命令的退出码，不是命令失败的错误或被信号终止时为 `None`
+    pub fn exit_code(&self) -> Option<i32> {
+        match self.root_cause() {
+            Self::GitCommand { exit_code, .. } | Self::SvnCommand { exit_code, .. } => *exit_code,
+            _ => None,
+        }
+    }
+
+    /// 是否是 svn/git 认证失败（用户名密码错误、无法获取凭据、SSH 密钥被拒绝等）
+    pub fn is_authentication_failure(&self) -> bool {
+        match self.root_cause() {
+            Self::SvnCommand { stderr, .. } => {
+                SVN_AUTH_ERRORS.iter().any(|code| stderr.contains(code))
+            }
+            Self::GitCommand { stderr, .. } => {
+                GIT_AUTH_ERRORS.iter().any(|hint| stderr.contains(hint))
+            }
+            _ => false,
+        }
+    }
+
+    /// 是否是 SVN 工作副本格式过旧（升级 svn 客户端之后），需要先执行 `svn upgrade`
+    pub fn is_upgrade_required(&self) -> bool {
+        matches!(self.root_cause(), Self::SvnCommand { stderr, .. } if stderr.contains(SVN_UPGRADE_REQUIRED))
+    }
+
+    /// 是否是找不到 svn/git 等外部命令
+    pub fn is_command_not_found(&self) -> bool {
+        matches!(self.root_cause(), Self::CommandNotFound(_))
+    }
+}
+
+fn describe_exit(exit_code: Option<i32>) -> String {
+    match exit_code {
+        Some(code) => format!("退出码 {code}"),
+        None => "被信号终止".into(),
+    }
+}
+
+fn describe_rollback(rollback: Option<&str>) -> String {
+    rollback.map(|r| format!("（{r}）")).unwrap_or_default()
+}
+
+fn describe_stderr(stderr: &str) -> &str {
+    match stderr.trim() {
+        "" => "无详细信息",
+        stderr => stderr,
+    }
 }
@@ -0,0 +1,124 @@
+/// 路径过滤规则
+///
+/// 规则使用 `/` 分隔的相对路径通配符：`*` 匹配单级目录中的任意字符，
+/// `**` 匹配任意多级目录，`?` 匹配单个字符。规则匹配某个目录时，
+/// 该目录下的所有文件都视为匹配。
+///
+/// 未设置包含规则时默认包含所有路径；排除规则优先于包含规则。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PathFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl PathFilter {
+    /// 创建路径过滤规则
+    ///
+    /// # 参数
+    ///
+    /// * `include`: 包含规则
+    /// * `exclude`: 排除规则
+    pub fn new(include: Vec<String>, exclude: Vec<String>) -> Self {
+        let normalize = |patterns: Vec<String>| {
+            patterns
+                .into_iter()
+                .map(|p| p.replace('\\', "/").trim_matches('/').to_string())
+                .filter(|p| !p.is_empty())
+                .collect()
+        };
+        Self {
+            include: normalize(include),
+            exclude: normalize(exclude),
+        }
+    }
+
+    /// 是否没有任何规则
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// 判断路径是否通过过滤
+    ///
+    /// # 参数
+    ///
+    /// * `path`: `/` 分隔的相对路径
+    pub fn matches(&self, path: &str) -> bool {
+        if self.exclude.iter().any(|p| matches_path_or_parent(p, path)) {
+            return false;
+        }
+        self.include.is_empty() || self.include.iter().any(|p| matches_path_or_parent(p, path))
+    }
+}
+
+/// 规则匹配路径本身或其任一上级目录
+fn matches_path_or_parent(pattern: &str, path: &str) -> bool {
+    let segments: Vec<&str> = path.split('/').collect();
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    (1..=segments.len()).any(|len| glob_match(&pattern, &segments[..len]))
+}
+
+/// 按路径段匹配通配符
+fn glob_match(pattern: &[&str], segments: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => segments.is_empty(),
+        Some((&"**", rest)) => (0..=segments.len()).any(|skip| glob_match(rest, &segments[skip..])),
+        Some((first, rest)) => match segments.split_first() {
+            Some((segment, remaining)) => {
+                segment_match(first.as_bytes(), segment.as_bytes()) && glob_match(rest, remaining)
+            }
+            None => false,
+        },
+    }
+}
+
+/// 匹配单个路径段（支持 `*` 和 `?`）
+fn segment_match(pattern: &[u8], text: &[u8]) -> bool {
+    match (pattern.split_first(), text.split_first()) {
+        (None, None) => true,
+        (Some((b'*', rest)), _) => {
+            segment_match(rest, text) || (!text.is_empty() && segment_match(pattern, &text[1..]))
+        }
+        (Some((b'?', rest)), Some((_, text_rest))) => segment_match(rest, text_rest),
+        (Some((p, rest)), Some((t, text_rest))) => p == t && segment_match(rest, text_rest),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(include: &[&str], exclude: &[&str]) -> PathFilter {
+        PathFilter::new(
+            include.iter().map(|s| s.to_string()).collect(),
+            exclude.iter().map(|s| s.to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        let f = PathFilter::default();
+        assert!(f.is_empty());
+        assert!(f.matches("any/path.txt"));
+    }
+
+    #[test]
+    fn test_directory_rule_matches_subtree() {
+        let f = filter(&["src/"], &["src/generated"]);
+        assert!(f.matches("src/main.rs"));
+        assert!(f.matches("src/a/b.rs"));
+        assert!(!f.matches("src/generated/x.rs"));
+        assert!(!f.matches("docs/readme.md"));
+        assert!(!f.matches("srcx/main.rs"));
+    }
+
+    #[test]
+    fn test_wildcards() {
+        let f = filter(&[], &["**/*.bin", "build?"]);
+        assert!(!f.matches("a.bin"));
+        assert!(!f.matches("deep/dir/a.bin"));
+        assert!(!f.matches("build1/out.txt"));
+        assert!(f.matches("build/out.txt"));
+        assert!(f.matches("a.binx"));
+    }
+}
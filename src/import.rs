@@ -0,0 +1,288 @@
+//! 离线转换：把 `svnadmin dump` 输出重放为 Git 提交，不需要 svn 命令和网络
+
+use std::{
+    fs,
+    io::{BufRead, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+use chrono::DateTime;
+use encoding_rs::Encoding;
+
+use crate::{
+    SVN_NO_AUTHOR,
+    error::{Result, SyncError},
+    message::{DEFAULT_MESSAGE_PREFIX, MessageLimits, format_commit_message},
+    ops::{CommitMetadata, DumpFileChange, DumpReader, DumpRevision, DumpTree, GitOperations},
+    sync::build_git_commit_message,
+};
+
+/// dump 导入选项
+#[derive(Debug, Clone, Default)]
+pub struct DumpImportOptions {
+    /// 只导入仓库中的该目录（如 `trunk`），作为 Git 仓库的根目录；默认导入整个仓库
+    pub root: Option<String>,
+    /// 旧提交说明的编码，`None` 表示都是 UTF-8
+    pub log_encoding: Option<&'static Encoding>,
+}
+
+/// dump 导入结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DumpImportSummary {
+    /// 读取的版本数（包括 r0）
+    pub revisions: usize,
+    /// 生成的 Git 提交数，没有文件变化的版本不生成提交
+    pub commits: usize,
+    /// 最后一个版本
+    pub last_revision: Option<u64>,
+}
+
+/// 把 dump 中的版本依次重放为 Git 提交
+///
+/// Git 仓库不存在时先初始化。每个版本的作者、时间和提交说明来自版本属性，
+/// 作者邮箱与在线同步相同，为 `作者@仓库UUID`。
+///
+/// # 参数
+///
+/// * `dump`: `svnadmin dump` 的输出
+/// * `git_dir`: Git 仓库目录
+/// * `git`: Git 操作
+/// * `options`: 导入选项
+///
+/// # 返回
+///
+/// 导入的版本数和提交数
+pub fn import_dump(
+    dump: impl BufRead,
+    git_dir: &Path,
+    git: &dyn GitOperations,
+    options: &DumpImportOptions,
+) -> Result<DumpImportSummary> {
+    if !git.is_repository(git_dir)? {
+        fs::create_dir_all(git_dir)?;
+        git.init(git_dir)?;
+        println!("已初始化 Git 仓库 {}", git_dir.display());
+    }
+    let root = options
+        .root
+        .as_deref()
+        .map(|root| root.trim_matches('/'))
+        .unwrap_or_default();
+
+    let mut reader = DumpReader::new(dump).with_log_encoding(options.log_encoding);
+    let mut tree = DumpTree::new();
+    let mut summary = DumpImportSummary::default();
+    while let Some(revision) = reader.next() {
+        let revision = revision?;
+        summary.revisions += 1;
+        summary.last_revision = Some(revision.number);
+        let changes: Vec<DumpFileChange> = tree
+            .apply(&revision)?
+            .into_iter()
+            .filter(|change| strip_root(change.path(), root).is_some())
+            .collect();
+        if changes.is_empty() {
+            continue;
+        }
+        let uuid = reader.uuid().unwrap_or("svn");
+        commit_revision(&revision, &changes, root, uuid, git_dir, git)?;
+        summary.commits += 1;
+        println!("已导入 r{}：{} 个文件", revision.number, changes.len());
+    }
+    Ok(summary)
+}
+
+/// 把一个版本的文件变化写入 Git 工作目录并提交
+fn commit_revision(
+    revision: &DumpRevision,
+    changes: &[DumpFileChange],
+    root: &str,
+    uuid: &str,
+    git_dir: &Path,
+    git: &dyn GitOperations,
+) -> Result<()> {
+    let mut executables = Vec::new();
+    for change in changes {
+        let relative = strip_root(change.path(), root).unwrap_or_default();
+        let target = worktree_path(git_dir, relative)?;
+        match change {
+            DumpFileChange::Write {
+                content,
+                executable,
+                ..
+            } => {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&target, content)?;
+                if *executable {
+                    executables.push(relative.to_string());
+                }
+            }
+            DumpFileChange::Delete { .. } => remove_file(git_dir, &target)?,
+        }
+    }
+
+    git.add_all(git_dir)?;
+    if !executables.is_empty() {
+        git.set_executable(git_dir, &executables)?;
+    }
+    let author = match revision.author().trim() {
+        "" => SVN_NO_AUTHOR,
+        author => author,
+    };
+    let metadata = CommitMetadata {
+        author_name: Some(author.to_string()),
+        author_email: Some(format!("{author}@{uuid}")),
+        date: DateTime::parse_from_rfc3339(revision.date()).ok(),
+        allow_empty: false,
+    };
+    let message = format_commit_message(
+        &build_git_commit_message(revision.message(), DEFAULT_MESSAGE_PREFIX),
+        &MessageLimits::default(),
+    );
+    git.commit_with(git_dir, &message, &metadata)
+}
+
+/// 去掉导入根目录前缀，不在根目录下时返回 `None`
+fn strip_root<'a>(path: &'a str, root: &str) -> Option<&'a str> {
+    if root.is_empty() {
+        return Some(path);
+    }
+    path.strip_prefix(root)?.strip_prefix('/')
+}
+
+/// dump 中的路径对应的工作目录路径，拒绝 `..` 等会写到仓库之外的路径
+fn worktree_path(git_dir: &Path, path: &str) -> Result<PathBuf> {
+    let valid = !path.is_empty()
+        && path
+            .split('/')
+            .all(|part| !matches!(part, "" | "." | "..") && !part.contains('\\'))
+        && !path.split('/').any(|part| part == ".git");
+    if !valid {
+        return Err(SyncError::App(format!("dump 中的路径无效：{path}")));
+    }
+    Ok(git_dir.join(path))
+}
+
+/// 删除文件，并删除因此变空的上级目录（Git 不记录空目录）
+fn remove_file(git_dir: &Path, target: &Path) -> Result<()> {
+    match fs::remove_file(target) {
+        Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+        _ => {}
+    }
+    let mut dir = target.parent();
+    while let Some(current) = dir
+        && current != git_dir
+    {
+        if fs::remove_dir(current).is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::Git2Operations;
+
+    fn props(pairs: &[(&str, &str)]) -> String {
+        let mut block = String::new();
+        for (key, value) in pairs {
+            block.push_str(&format!(
+                "K {}\n{key}\nV {}\n{value}\n",
+                key.len(),
+                value.len()
+            ));
+        }
+        block + "PROPS-END\n"
+    }
+
+    fn revision(number: u64, author: &str, log: &str, nodes: &[(&str, &str, &str)]) -> String {
+        let props = props(&[
+            ("svn:author", author),
+            ("svn:date", "2024-01-02T03:04:05.000000Z"),
+            ("svn:log", log),
+        ]);
+        let mut out = format!(
+            "Revision-number: {number}\nProp-content-length: {0}\nContent-length: {0}\n\n{props}\n",
+            props.len()
+        );
+        for (path, action, text) in nodes {
+            match *action {
+                "delete" => out.push_str(&format!("Node-path: {path}\nNode-action: delete\n\n")),
+                _ => out.push_str(&format!(
+                    "Node-path: {path}\nNode-kind: file\nNode-action: {action}\nText-content-length: {0}\nContent-length: {0}\n\n{text}\n\n",
+                    text.len()
+                )),
+            }
+        }
+        out
+    }
+
+    fn dump() -> String {
+        [
+            "SVN-fs-dump-format-version: 2\n\nUUID: repo-uuid\n\n".to_string(),
+            revision(
+                1,
+                "alice",
+                "init",
+                &[("trunk/a.txt", "add", "a"), ("tags/v1/x.txt", "add", "x")],
+            ),
+            revision(2, "", "", &[("trunk/sub/b.txt", "add", "b")]),
+            revision(3, "bob", "only tags", &[("tags/v1/x.txt", "change", "y")]),
+            revision(4, "bob", "remove", &[("trunk/sub/b.txt", "delete", "")]),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_import_dump_should_replay_revisions_into_git() {
+        let dir = tempfile::tempdir().unwrap();
+        let git_dir = dir.path().join("git");
+        let git = Git2Operations::new();
+        git.init(&git_dir).unwrap();
+        git.config_user(&git_dir, "importer", "importer@example.com")
+            .unwrap();
+
+        let options = DumpImportOptions {
+            root: Some("trunk".into()),
+            ..Default::default()
+        };
+        let summary = import_dump(dump().as_bytes(), &git_dir, &git, &options).unwrap();
+        assert_eq!(
+            summary,
+            DumpImportSummary {
+                revisions: 4,
+                commits: 3,
+                last_revision: Some(4),
+            }
+        );
+
+        assert_eq!(fs::read_to_string(git_dir.join("a.txt")).unwrap(), "a");
+        assert!(!git_dir.join("sub").exists());
+        assert!(!git_dir.join("tags").exists());
+
+        let repo = git2::Repository::open(&git_dir).unwrap();
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.message(), Some("SVN: remove"));
+        assert_eq!(head.author().email(), Some("bob@repo-uuid"));
+        let first = head.parent(0).unwrap().parent(0).unwrap();
+        assert_eq!(first.author().name(), Some("alice"));
+        assert_eq!(first.time().seconds(), 1704164645);
+        assert_eq!(head.parent(0).unwrap().author().name(), Some(SVN_NO_AUTHOR));
+    }
+
+    #[test]
+    fn test_import_dump_should_reject_paths_outside_worktree() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(worktree_path(dir.path(), "trunk/../../x").is_err());
+        assert!(worktree_path(dir.path(), ".git/config").is_err());
+        assert_eq!(
+            worktree_path(dir.path(), "trunk/a.txt").unwrap(),
+            dir.path().join("trunk/a.txt")
+        );
+    }
+}
@@ -2,7 +2,7 @@ use crate::{
     config::{DiskStorage, FileStorage, HistoryManager, SyncConfig},
     error::Result,
     interactor::{DefaultUserInteractor, UserInteractor},
-    ops::SvnLog,
+    ops::{RevisionDiff, SvnLog},
 };
 
 use std::{path::PathBuf, str::FromStr};
@@ -74,7 +74,7 @@ pub fn select_or_create_config_with_interactor<S: FileStorage>(
 #[deprecated(note = "使用 confirm_sync_with_interactor 以获得更好的可测试性")]
 pub fn confirm_sync(svn_logs: &[SvnLog]) -> bool {
     let interactor = DefaultUserInteractor;
-    interactor.confirm_sync(svn_logs)
+    interactor.confirm_sync(svn_logs, &[])
 }
 
 /// 确认是否同步（使用自定义用户交互器）
@@ -82,6 +82,7 @@ pub fn confirm_sync(svn_logs: &[SvnLog]) -> bool {
 /// # 参数
 ///
 /// * `svn_logs`: SVN 日志列表
+/// * `diffs`: 各版本的变更摘要，未开启预览时为空
 /// * `interactor`: 用户交互器
 ///
 /// # 返回
@@ -98,13 +99,83 @@ pub fn confirm_sync(svn_logs: &[SvnLog]) -> bool {
 /// let svn_logs = vec![SvnLog {
 ///     version: "1".into(),
 ///     message: "测试提交".into(),
+///     ..Default::default()
 /// }];
 ///
-/// let should_sync = confirm_sync_with_interactor(&svn_logs, &interactor);
+/// let should_sync = confirm_sync_with_interactor(&svn_logs, &[], &interactor);
 /// assert!(should_sync);
 /// ```
-pub fn confirm_sync_with_interactor(svn_logs: &[SvnLog], interactor: &dyn UserInteractor) -> bool {
-    interactor.confirm_sync(svn_logs)
+pub fn confirm_sync_with_interactor(
+    svn_logs: &[SvnLog],
+    diffs: &[RevisionDiff],
+    interactor: &dyn UserInteractor,
+) -> bool {
+    interactor.confirm_sync(svn_logs, diffs)
+}
+
+/// 同步前询问确认的阈值
+///
+/// 日常镜像通常只有几个版本，每次都确认没有意义；设置了阈值时，
+/// 只有待同步的版本数或变更路径总数超过阈值才询问，都没有设置时总是询问
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConfirmThreshold {
+    /// 版本数超过该值时询问
+    pub revisions: Option<usize>,
+    /// 变更路径总数超过该值时询问
+    pub changed_paths: Option<usize>,
+}
+
+impl ConfirmThreshold {
+    /// 待同步的版本是否需要用户确认
+    ///
+    /// # 参数
+    ///
+    /// * `svn_logs`: 待同步的 SVN 日志列表
+    pub fn requires_confirmation(&self, svn_logs: &[SvnLog]) -> bool {
+        let changed_paths = svn_logs.iter().map(|log| log.paths.len()).sum();
+        self.requires_confirmation_for(svn_logs.len(), changed_paths)
+    }
+
+    /// 按待同步的版本数和变更路径总数判断是否需要用户确认
+    ///
+    /// # 参数
+    ///
+    /// * `revisions`: 待同步的版本数
+    /// * `changed_paths`: 变更路径总数
+    pub fn requires_confirmation_for(&self, revisions: usize, changed_paths: usize) -> bool {
+        if self.revisions.is_none() && self.changed_paths.is_none() {
+            return true;
+        }
+        self.revisions.is_some_and(|max| revisions > max)
+            || self.changed_paths.is_some_and(|max| changed_paths > max)
+    }
+}
+
+/// 两步确认危险操作
+///
+/// 命令行已通过 `--confirm` 输入标签时直接比较（配合 `--yes` 用于脚本），
+/// 否则交给交互器让用户输入标签。
+///
+/// # 参数
+///
+/// * `action`: 操作说明
+/// * `label`: 需要输入的同步对标签
+/// * `typed`: 命令行中输入的标签
+/// * `interactor`: 用户交互器
+///
+/// # 返回
+///
+/// 是否确认执行
+pub fn confirm_destructive_with_interactor(
+    action: &str,
+    label: &str,
+    typed: Option<&str>,
+    interactor: &dyn UserInteractor,
+) -> bool {
+    match typed {
+        Some(typed) => typed == label,
+        None => interactor.confirm_destructive(action, label),
+    }
 }
 
 /// 选择或创建配置
@@ -125,7 +196,7 @@ fn select_or_create_config<S: FileStorage>(
     history: &mut HistoryManager<S>,
     interactor: &dyn UserInteractor,
 ) -> Result<SyncConfig> {
-    let config = match (svn_dir, git_dir) {
+    let mut config = match (svn_dir, git_dir) {
         (Some(svn), Some(git)) => SyncConfig::new(svn, git),
         _ => {
             if !history.is_empty() {
@@ -152,6 +223,12 @@ fn select_or_create_config<S: FileStorage>(
     history.add_record(config.svn_dir.clone(), config.git_dir.clone());
     history.save()?;
 
+    // 命令行直接传入路径时，同样使用该同步对已配置的环境变量和配置方案
+    if let Some(record) = history.find_record(&config.svn_dir, &config.git_dir) {
+        config.env = record.env().clone();
+        config.profile = record.profile().map(str::to_string);
+    }
+
     Ok(config)
 }
 
@@ -213,6 +290,118 @@ mod tests {
         assert_eq!(config.git_dir, git);
     }
 
+    #[test]
+    fn test_confirm_threshold_requires_confirmation() {
+        let log = |paths: usize| SvnLog {
+            paths: vec![Default::default(); paths],
+            ..Default::default()
+        };
+        let logs = vec![log(3), log(4)];
+
+        assert!(ConfirmThreshold::default().requires_confirmation(&[]));
+        let revisions = ConfirmThreshold {
+            revisions: Some(2),
+            ..Default::default()
+        };
+        assert!(!revisions.requires_confirmation(&logs));
+        assert!(revisions.requires_confirmation(&[log(0), log(0), log(0)]));
+        let changed_paths = ConfirmThreshold {
+            changed_paths: Some(6),
+            ..revisions
+        };
+        assert!(changed_paths.requires_confirmation(&logs));
+        assert!(!changed_paths.requires_confirmation(&logs[..1]));
+    }
+
+    #[test]
+    fn test_confirm_destructive_with_interactor() {
+        use crate::interactor::NonInteractiveUserInteractor;
+
+        // 命令行传入的标签必须完全一致
+        let interactor = MockUserInteractor::new();
+        assert!(confirm_destructive_with_interactor(
+            "删除",
+            "repo",
+            Some("repo"),
+            &interactor
+        ));
+        assert!(!confirm_destructive_with_interactor(
+            "删除",
+            "repo",
+            Some("rep"),
+            &interactor
+        ));
+
+        // 未传入标签时由交互器确认，非交互模式直接拒绝
+        let mut interactor = MockUserInteractor::new();
+        interactor
+            .expect_confirm_destructive()
+            .withf(|action, label| action == "删除" && label == "repo")
+            .returning(|_, _| true);
+        assert!(confirm_destructive_with_interactor(
+            "删除",
+            "repo",
+            None,
+            &interactor
+        ));
+        assert!(!confirm_destructive_with_interactor(
+            "删除",
+            "repo",
+            None,
+            &NonInteractiveUserInteractor
+        ));
+    }
+
+    #[test]
+    fn test_select_or_create_config_should_apply_pair_env() {
+        let mut storage = MockFileStorage::new();
+        storage.expect_load().returning(|| {
+            let mut record = HistoryRecord::new(1, PathBuf::from("svn"), PathBuf::from("git"));
+            record.set_env("SVN_SSH", "ssh -i key");
+            Ok(vec![record])
+        });
+        storage.expect_save().returning(|_| Ok(()));
+        let mut history = HistoryManager::new(storage).unwrap();
+
+        let interactor = MockUserInteractor::new();
+        let config = select_or_create_config(
+            Some(PathBuf::from("svn")),
+            Some(PathBuf::from("git")),
+            &mut history,
+            &interactor,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.env.get("SVN_SSH").map(String::as_str),
+            Some("ssh -i key")
+        );
+    }
+
+    #[test]
+    fn test_select_or_create_config_non_interactive_requires_paths() {
+        use crate::interactor::NonInteractiveUserInteractor;
+
+        let mut storage = MockFileStorage::new();
+        storage.expect_load().returning(|| {
+            Ok(vec![HistoryRecord::new(
+                1,
+                PathBuf::from("svn_history"),
+                PathBuf::from("git_history"),
+            )])
+        });
+        storage.expect_save().times(0);
+        let mut history = HistoryManager::new(storage).unwrap();
+
+        let result = select_or_create_config(
+            Some(PathBuf::from("svn_only")),
+            None,
+            &mut history,
+            &NonInteractiveUserInteractor,
+        );
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_select_or_create_config_should_select_history_when_exists() {
         let mut storage = MockFileStorage::new();
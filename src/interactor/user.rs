@@ -1,9 +1,11 @@
-use inquire::{Confirm, Select, Text};
+use std::path::Path;
+
+use inquire::{Confirm, MultiSelect, Select, Text};
 
 use crate::{
-    config::HistoryRecord,
+    config::{DirtyTreePolicy, HistoryRecord},
     error::{Result, SyncError},
-    ops::SvnLog,
+    ops::{RevisionDiff, SvnLog},
 };
 
 /// 用户交互接口
@@ -28,12 +30,57 @@ pub trait UserInteractor {
     /// # 参数
     ///
     /// * `svn_logs`: SVN 日志列表
-    /// * `git_log`: Git 日志
+    /// * `diffs`: 各版本的变更摘要，未开启预览时为空
     ///
     /// # 返回
     ///
     /// 是否同步
-    fn confirm_sync(&self, svn_logs: &[SvnLog]) -> bool;
+    fn confirm_sync(&self, svn_logs: &[SvnLog], diffs: &[RevisionDiff]) -> bool;
+    /// 选择要同步的版本
+    ///
+    /// 默认全部选中，用户可以取消选择个别版本（如体积巨大的第三方代码导入）
+    ///
+    /// # 参数
+    ///
+    /// * `svn_logs`: 待同步的 SVN 日志列表
+    ///
+    /// # 返回
+    ///
+    /// 选中的版本号，按同步顺序排列
+    fn select_revisions(&self, svn_logs: &[SvnLog]) -> Result<Vec<String>>;
+    /// 确认危险操作（删除、回滚等不可撤销的操作）
+    ///
+    /// 用户必须完整输入同步对标签才算确认，避免误按回车
+    ///
+    /// # 参数
+    ///
+    /// * `action`: 操作说明
+    /// * `label`: 需要输入的同步对标签
+    ///
+    /// # 返回
+    ///
+    /// 是否确认执行
+    fn confirm_destructive(&self, action: &str, label: &str) -> bool;
+    /// 确认是否在 Git 目录初始化仓库
+    ///
+    /// # 参数
+    ///
+    /// * `git_dir`: 不是 Git 仓库的目录
+    ///
+    /// # 返回
+    ///
+    /// 是否初始化
+    fn confirm_git_init(&self, git_dir: &Path) -> bool;
+    /// Git 工作目录有未提交的更改时选择处理方式
+    ///
+    /// # 参数
+    ///
+    /// * `git_dir`: Git 目录
+    ///
+    /// # 返回
+    ///
+    /// 处理方式：拒绝同步、暂存后同步或继续同步
+    fn choose_dirty_tree_policy(&self, git_dir: &Path) -> DirtyTreePolicy;
 }
 
 /// 默认的用户交互器
@@ -65,7 +112,7 @@ impl UserInteractor for DefaultUserInteractor {
             .map_err(|e| e.into())
     }
 
-    fn confirm_sync(&self, svn_logs: &[SvnLog]) -> bool {
+    fn confirm_sync(&self, svn_logs: &[SvnLog], diffs: &[RevisionDiff]) -> bool {
         println!("检测到 {} 条 SVN 日志：", svn_logs.len());
         for (idx, log) in svn_logs.iter().enumerate() {
             println!(
@@ -74,6 +121,11 @@ impl UserInteractor for DefaultUserInteractor {
                 log.version,
                 summarize_message(&log.message)
             );
+            if let Some(diff) = diffs.iter().find(|diff| diff.version == log.version) {
+                for line in diff_preview_lines(diff) {
+                    println!("         {line}");
+                }
+            }
         }
 
         match Confirm::new("是否开始执行同步？")
@@ -88,6 +140,130 @@ impl UserInteractor for DefaultUserInteractor {
             }
         }
     }
+
+    fn select_revisions(&self, svn_logs: &[SvnLog]) -> Result<Vec<String>> {
+        let options: Vec<String> = svn_logs
+            .iter()
+            .map(|log| format!("r{} | {}", log.version, summarize_message(&log.message)))
+            .collect();
+        let all: Vec<usize> = (0..options.len()).collect();
+        let selected = MultiSelect::new("选择要同步的版本（空格切换，回车确认）", options)
+            .with_default(&all)
+            .raw_prompt()?;
+        Ok(selected
+            .into_iter()
+            .map(|option| svn_logs[option.index].version.clone())
+            .collect())
+    }
+
+    fn confirm_destructive(&self, action: &str, label: &str) -> bool {
+        println!("即将{action}，此操作不可撤销");
+        match Text::new(&format!("输入 {label} 确认：")).prompt() {
+            Ok(input) => input.trim() == label,
+            Err(e) => {
+                eprintln!("确认操作时出现错误：{e}");
+                false
+            }
+        }
+    }
+
+    fn confirm_git_init(&self, git_dir: &Path) -> bool {
+        match Confirm::new(&format!(
+            "{} 不是 Git 仓库，是否执行 git init 并提交当前 SVN 工作副本？",
+            git_dir.display()
+        ))
+        .with_default(true)
+        .prompt()
+        {
+            Ok(confirm) => confirm,
+            Err(e) => {
+                eprintln!("询问是否初始化 Git 仓库时出现错误：{e}");
+                false
+            }
+        }
+    }
+
+    fn choose_dirty_tree_policy(&self, git_dir: &Path) -> DirtyTreePolicy {
+        const OPTIONS: [(&str, DirtyTreePolicy); 3] = [
+            ("中止同步", DirtyTreePolicy::Fail),
+            (
+                "暂存更改（git stash），同步结束后恢复",
+                DirtyTreePolicy::Stash,
+            ),
+            (
+                "继续同步，已有的更改随第一个版本一起提交",
+                DirtyTreePolicy::Ignore,
+            ),
+        ];
+        let labels = OPTIONS.iter().map(|(label, _)| *label).collect();
+        match Select::new(
+            &format!("Git 工作目录 {} 有未提交的更改", git_dir.display()),
+            labels,
+        )
+        .prompt()
+        {
+            Ok(selection) => OPTIONS
+                .iter()
+                .find(|(label, _)| *label == selection)
+                .map_or(DirtyTreePolicy::Fail, |(_, policy)| *policy),
+            Err(e) => {
+                eprintln!("选择处理方式时出现错误：{e}");
+                DirtyTreePolicy::Fail
+            }
+        }
+    }
+}
+
+/// 非交互式用户交互器
+///
+/// 用于 cron/CI 等无人值守场景：自动确认同步，任何需要用户输入的操作都直接返回错误，
+/// 不会调用 `inquire`。
+pub struct NonInteractiveUserInteractor;
+
+impl UserInteractor for NonInteractiveUserInteractor {
+    fn select_history_record(&self, _records: &[HistoryRecord]) -> Result<usize> {
+        Err(SyncError::App(
+            "非交互模式下无法选择历史记录，请同时指定 --svn-dir 和 --git-dir".into(),
+        ))
+    }
+
+    fn input_svn_dir(&self) -> Result<String> {
+        Err(SyncError::App(
+            "非交互模式下无法输入 SVN 文件夹，请指定 --svn-dir".into(),
+        ))
+    }
+
+    fn input_git_dir(&self) -> Result<String> {
+        Err(SyncError::App(
+            "非交互模式下无法输入 Git 文件夹，请指定 --git-dir".into(),
+        ))
+    }
+
+    fn confirm_sync(&self, svn_logs: &[SvnLog], _diffs: &[RevisionDiff]) -> bool {
+        println!("非交互模式：自动确认同步 {} 条 SVN 日志", svn_logs.len());
+        true
+    }
+
+    fn select_revisions(&self, _svn_logs: &[SvnLog]) -> Result<Vec<String>> {
+        Err(SyncError::App(
+            "非交互模式下无法选择版本，请使用 --only-revs 指定".into(),
+        ))
+    }
+
+    fn confirm_destructive(&self, action: &str, label: &str) -> bool {
+        eprintln!("非交互模式下{action}需要同时传入 --confirm {label}");
+        false
+    }
+
+    fn confirm_git_init(&self, git_dir: &Path) -> bool {
+        println!("非交互模式：自动初始化 Git 仓库 {}", git_dir.display());
+        true
+    }
+
+    fn choose_dirty_tree_policy(&self, _git_dir: &Path) -> DirtyTreePolicy {
+        eprintln!("非交互模式下无法询问如何处理未提交的更改，请使用 --dirty-tree 或 --force");
+        DirtyTreePolicy::Fail
+    }
 }
 
 fn summarize_message(message: &str) -> String {
@@ -107,6 +283,25 @@ fn summarize_message(message: &str) -> String {
     shortened
 }
 
+/// 一个版本的变更摘要，每行一个路径，路径过多时只显示前面一部分
+fn diff_preview_lines(diff: &RevisionDiff) -> Vec<String> {
+    const MAX_PATHS: usize = 20;
+    if diff.entries.is_empty() {
+        return vec!["(没有文件变更)".to_string()];
+    }
+
+    let mut lines: Vec<String> = diff
+        .entries
+        .iter()
+        .take(MAX_PATHS)
+        .map(|entry| format!("{} {}", entry.status(), entry.path))
+        .collect();
+    if diff.entries.len() > MAX_PATHS {
+        lines.push(format!("... 另有 {} 个", diff.entries.len() - MAX_PATHS));
+    }
+    lines
+}
+
 /// 测试用Mock用户交互器，用于测试
 #[cfg(test)]
 pub struct TestUserInteractor {
@@ -184,9 +379,25 @@ impl UserInteractor for TestUserInteractor {
         Ok(self.git_dir_input.clone())
     }
 
-    fn confirm_sync(&self, _svn_logs: &[SvnLog]) -> bool {
+    fn confirm_sync(&self, _svn_logs: &[SvnLog], _diffs: &[RevisionDiff]) -> bool {
+        self.confirm_result
+    }
+
+    fn select_revisions(&self, svn_logs: &[SvnLog]) -> Result<Vec<String>> {
+        Ok(svn_logs.iter().map(|log| log.version.clone()).collect())
+    }
+
+    fn confirm_destructive(&self, _action: &str, _label: &str) -> bool {
         self.confirm_result
     }
+
+    fn confirm_git_init(&self, _git_dir: &Path) -> bool {
+        self.confirm_result
+    }
+
+    fn choose_dirty_tree_policy(&self, _git_dir: &Path) -> DirtyTreePolicy {
+        DirtyTreePolicy::Fail
+    }
 }
 
 #[cfg(test)]
@@ -239,12 +450,49 @@ mod tests {
         let svn_logs: Vec<SvnLog> = vec![SvnLog {
             version: "1".into(),
             message: "message".into(),
+            ..Default::default()
         }];
 
-        let result = interactor.confirm_sync(&svn_logs);
+        let result = interactor.confirm_sync(&svn_logs, &[]);
         assert!(!result);
     }
 
+    #[test]
+    fn test_non_interactive_user_interactor() {
+        let interactor = NonInteractiveUserInteractor;
+        let records = vec![HistoryRecord::new(1, "svn".into(), "git".into())];
+
+        assert!(interactor.select_history_record(&records).is_err());
+        assert!(interactor.input_svn_dir().is_err());
+        assert!(interactor.input_git_dir().is_err());
+        assert!(interactor.confirm_sync(&[], &[]));
+        assert!(interactor.select_revisions(&[]).is_err());
+    }
+
+    #[test]
+    fn test_diff_preview_lines_should_limit_paths() {
+        let entry = |path: &str| crate::ops::SvnDiffSummary {
+            item: "modified".into(),
+            props: "none".into(),
+            kind: "file".into(),
+            path: path.into(),
+        };
+        let diff = RevisionDiff {
+            version: "5".into(),
+            entries: (0..23).map(|i| entry(&format!("f{i}.txt"))).collect(),
+        };
+        let lines = diff_preview_lines(&diff);
+        assert_eq!(lines.len(), 21);
+        assert_eq!(lines[0], "M  f0.txt");
+        assert_eq!(lines[20], "... 另有 3 个");
+
+        let empty = RevisionDiff {
+            version: "6".into(),
+            entries: Vec::new(),
+        };
+        assert_eq!(diff_preview_lines(&empty), ["(没有文件变更)"]);
+    }
+
     #[test]
     fn test_summarize_message_with_empty_message() {
         assert_eq!(summarize_message("   "), "(空提交说明)");
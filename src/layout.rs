@@ -0,0 +1,295 @@
+use std::{collections::BTreeSet, path::Path};
+
+use crate::{
+    error::{Result, SyncError},
+    ops::{SvnLog, SvnOperations},
+};
+
+/// trunk 对应的 Git 分支
+pub const TRUNK_BRANCH: &str = "master";
+
+/// SVN 仓库目录布局
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvnLayout {
+    /// 主干目录
+    pub trunk: String,
+    /// 分支所在目录
+    pub branches: String,
+    /// 标签所在目录
+    pub tags: String,
+}
+
+/// 布局中的一条开发线
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LayoutTarget {
+    /// 主干
+    Trunk,
+    /// `branches/<名称>`
+    Branch(String),
+    /// `tags/<名称>`
+    Tag(String),
+}
+
+/// 一个 SVN 版本在布局中的修改
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutRevision {
+    /// 修改的开发线
+    pub target: LayoutTarget,
+    /// 本版本通过 `svn copy` 创建该开发线时的来源
+    pub copied_from: Option<LayoutTarget>,
+    /// 本版本删除了整条开发线
+    pub deleted: bool,
+}
+
+impl Default for SvnLayout {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl SvnLayout {
+    /// 标准的 `trunk` / `branches` / `tags` 布局
+    pub fn standard() -> Self {
+        Self {
+            trunk: "trunk".to_string(),
+            branches: "branches".to_string(),
+            tags: "tags".to_string(),
+        }
+    }
+
+    /// 根据仓库目录的子项判断是否为标准布局
+    ///
+    /// 有 `trunk` 目录，并且有 `branches` 或 `tags` 目录时视为标准布局
+    ///
+    /// # 参数
+    ///
+    /// * `entries`: `svn list` 的子项，目录以 `/` 结尾
+    pub fn detect(entries: &[String]) -> Option<Self> {
+        let layout = Self::standard();
+        let has_dir = |name: &str| entries.iter().any(|e| e.trim_end_matches('/') == name);
+        (has_dir(&layout.trunk) && (has_dir(&layout.branches) || has_dir(&layout.tags)))
+            .then_some(layout)
+    }
+
+    /// 判断布局根目录下的路径属于哪条开发线
+    ///
+    /// # 参数
+    ///
+    /// * `path`: 相对布局根目录的路径，如 `branches/dev/src/main.rs`
+    ///
+    /// # 返回
+    ///
+    /// 开发线和路径在其中的相对路径（开发线根目录本身为空字符串）；
+    /// `branches`、`tags` 目录本身和布局以外的路径返回 `None`
+    pub fn classify<'a>(&self, path: &'a str) -> Option<(LayoutTarget, &'a str)> {
+        let path = path.trim_matches('/');
+        let (first, rest) = path.split_once('/').unwrap_or((path, ""));
+        if first == self.trunk {
+            return Some((LayoutTarget::Trunk, rest));
+        }
+        if rest.is_empty() {
+            return None;
+        }
+        let (name, rest) = rest.split_once('/').unwrap_or((rest, ""));
+        if first == self.branches {
+            Some((LayoutTarget::Branch(name.to_string()), rest))
+        } else if first == self.tags {
+            Some((LayoutTarget::Tag(name.to_string()), rest))
+        } else {
+            None
+        }
+    }
+
+    /// 开发线相对布局根目录的路径
+    ///
+    /// # 参数
+    ///
+    /// * `target`: 开发线
+    pub fn dir(&self, target: &LayoutTarget) -> String {
+        match target {
+            LayoutTarget::Trunk => self.trunk.clone(),
+            LayoutTarget::Branch(name) => format!("{}/{name}", self.branches),
+            LayoutTarget::Tag(name) => format!("{}/{name}", self.tags),
+        }
+    }
+
+    /// 分析一个 SVN 版本修改了哪条开发线
+    ///
+    /// # 参数
+    ///
+    /// * `log`: SVN 日志，需要变更路径
+    /// * `root`: 布局根目录在仓库中的路径，仓库根目录为空字符串
+    ///
+    /// # 返回
+    ///
+    /// * `Ok(None)` - 没有修改任何开发线（如只创建了 `branches` 目录）
+    /// * `Err(SyncError)` - 一个版本同时修改了多条开发线
+    pub fn revision(&self, log: &SvnLog, root: &str) -> Result<Option<LayoutRevision>> {
+        let relative = |path: &str| {
+            path.strip_prefix(root)
+                .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+                .map(str::to_string)
+        };
+
+        let mut targets = BTreeSet::new();
+        let mut copied_from = None;
+        let mut deleted = false;
+        for changed in &log.paths {
+            let Some(path) = relative(&changed.path) else {
+                continue;
+            };
+            let Some((target, rest)) = self.classify(&path) else {
+                continue;
+            };
+            if rest.is_empty() {
+                deleted |= changed.action == "D";
+                copied_from = changed
+                    .copy_from
+                    .as_deref()
+                    .and_then(&relative)
+                    .and_then(|from| self.classify(&from).map(|(source, _)| source));
+            }
+            targets.insert(target);
+        }
+
+        let mut targets = targets.into_iter();
+        let Some(target) = targets.next() else {
+            return Ok(None);
+        };
+        if let Some(other) = targets.next() {
+            return Err(SyncError::App(format!(
+                "SVN r{} 同时修改了多条开发线（{} 和 {}），无法映射为一个 Git 分支上的提交",
+                log.version,
+                self.dir(&target),
+                self.dir(&other)
+            )));
+        }
+        Ok(Some(LayoutRevision {
+            target,
+            copied_from,
+            deleted,
+        }))
+    }
+}
+
+impl LayoutTarget {
+    /// 开发线对应的 Git 分支名
+    ///
+    /// trunk 为 [`TRUNK_BRANCH`]，`branches/foo` 为 `foo`，`tags/x` 为 `tags/x`
+    pub fn git_branch(&self) -> String {
+        match self {
+            LayoutTarget::Trunk => TRUNK_BRANCH.to_string(),
+            LayoutTarget::Branch(name) => name.clone(),
+            LayoutTarget::Tag(name) => format!("tags/{name}"),
+        }
+    }
+}
+
+/// 通过 `svn list` 检测工作副本对应的仓库目录是否为标准布局
+///
+/// # 参数
+///
+/// * `svn_ops`: SVN操作实现
+/// * `svn_dir`: SVN 工作副本目录（应检出布局根目录）
+pub fn detect_layout(svn_ops: &dyn SvnOperations, svn_dir: &Path) -> Result<Option<SvnLayout>> {
+    Ok(SvnLayout::detect(&svn_ops.list(svn_dir)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::SvnChangedPath;
+
+    fn changed(action: &str, path: &str, copy_from: Option<&str>) -> SvnChangedPath {
+        SvnChangedPath {
+            action: action.into(),
+            path: path.into(),
+            copy_from: copy_from.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn log(paths: Vec<SvnChangedPath>) -> SvnLog {
+        SvnLog {
+            version: "5".into(),
+            paths,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_detect_standard_layout() {
+        let entries = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            SvnLayout::detect(&entries(&["branches/", "tags/", "trunk/"])),
+            Some(SvnLayout::standard())
+        );
+        assert_eq!(
+            SvnLayout::detect(&entries(&["trunk/", "tags/"])),
+            Some(SvnLayout::standard())
+        );
+        assert_eq!(SvnLayout::detect(&entries(&["trunk/", "src/"])), None);
+        assert_eq!(SvnLayout::detect(&entries(&["src/", "README"])), None);
+    }
+
+    #[test]
+    fn test_classify_and_git_branch() {
+        let layout = SvnLayout::standard();
+        assert_eq!(
+            layout.classify("trunk/src/main.rs"),
+            Some((LayoutTarget::Trunk, "src/main.rs"))
+        );
+        assert_eq!(
+            layout.classify("/branches/dev"),
+            Some((LayoutTarget::Branch("dev".into()), ""))
+        );
+        assert_eq!(
+            layout.classify("tags/v1.0/a.txt"),
+            Some((LayoutTarget::Tag("v1.0".into()), "a.txt"))
+        );
+        assert_eq!(layout.classify("branches"), None);
+        assert_eq!(layout.classify("other/a.txt"), None);
+
+        assert_eq!(LayoutTarget::Trunk.git_branch(), TRUNK_BRANCH);
+        assert_eq!(LayoutTarget::Branch("dev".into()).git_branch(), "dev");
+        assert_eq!(LayoutTarget::Tag("v1.0".into()).git_branch(), "tags/v1.0");
+        assert_eq!(
+            layout.dir(&LayoutTarget::Branch("dev".into())),
+            "branches/dev"
+        );
+    }
+
+    #[test]
+    fn test_revision_target() {
+        let layout = SvnLayout::standard();
+        let copy = log(vec![changed(
+            "A",
+            "/proj/branches/dev",
+            Some("/proj/trunk"),
+        )]);
+        assert_eq!(
+            layout.revision(&copy, "/proj").unwrap(),
+            Some(LayoutRevision {
+                target: LayoutTarget::Branch("dev".into()),
+                copied_from: Some(LayoutTarget::Trunk),
+                deleted: false,
+            })
+        );
+
+        let delete = log(vec![changed("D", "/branches/dev", None)]);
+        assert!(layout.revision(&delete, "").unwrap().unwrap().deleted);
+
+        let skeleton = log(vec![
+            changed("A", "/branches", None),
+            changed("A", "/tags", None),
+        ]);
+        assert_eq!(layout.revision(&skeleton, "").unwrap(), None);
+
+        let mixed = log(vec![
+            changed("M", "/trunk/a.txt", None),
+            changed("M", "/branches/dev/a.txt", None),
+        ]);
+        let err = layout.revision(&mixed, "").unwrap_err().to_string();
+        assert!(err.contains("trunk 和 branches/dev"));
+    }
+}
@@ -1,16 +1,74 @@
+mod adopt;
+mod audit;
+mod authors;
+mod bandwidth;
+mod batch;
+mod clock;
 mod command;
 mod config;
+mod depth;
+mod doctor;
 mod error;
+mod filter;
+mod import;
 mod interactor;
+mod layout;
+mod lint;
+mod lock;
+mod log_stream;
+mod manifest;
+mod message;
+mod mirror;
+mod notify;
 mod ops;
+mod output;
+mod preview;
+mod profile;
+mod progress;
+mod properties;
+mod revisions;
+mod service;
+mod status;
 mod sync;
+mod sync_options;
+mod telemetry;
+mod watch;
 
+pub use adopt::*;
+pub use audit::*;
+pub use authors::*;
+pub use bandwidth::*;
+pub use batch::*;
+pub use clock::*;
 pub use command::*;
 pub use config::*;
+pub use depth::*;
+pub use doctor::*;
 pub use error::*;
+pub use filter::*;
+pub use import::*;
 pub use interactor::*;
+pub use layout::*;
+pub use lint::*;
+pub use lock::*;
+pub use log_stream::*;
+pub use manifest::*;
+pub use message::*;
+pub use mirror::*;
+pub use notify::*;
 pub use ops::*;
+pub use output::*;
+pub use preview::*;
+pub use profile::*;
+pub use progress::*;
+pub use properties::*;
+pub use revisions::*;
+pub use service::*;
+pub use status::*;
 pub use sync::*;
+pub use sync_options::*;
+pub use telemetry::*;
+pub use watch::*;
 
 // 测试工具模块
 pub mod test_utils;
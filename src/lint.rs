@@ -0,0 +1,276 @@
+//! 转换规则检查：在开始长时间同步之前发现配置错误
+
+use std::fmt::Display;
+
+use crate::{
+    authors::{AuthorMatch, AuthorRule},
+    command::CommitArgs,
+};
+
+/// 通配符规则中 `**` 段或单段中 `*` 的数量超过该值时，回溯匹配可能很慢
+const MAX_WILDCARDS: usize = 3;
+
+/// 检查结果的级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    /// 规则不会按预期生效，应修改后再同步
+    Error,
+    /// 规则可以使用，但可能不是想要的效果
+    Warning,
+}
+
+/// 一条检查结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintIssue {
+    /// 级别
+    pub level: LintLevel,
+    /// 说明
+    pub message: String,
+}
+
+impl LintIssue {
+    fn error(message: String) -> Self {
+        Self {
+            level: LintLevel::Error,
+            message,
+        }
+    }
+
+    fn warning(message: String) -> Self {
+        Self {
+            level: LintLevel::Warning,
+            message,
+        }
+    }
+}
+
+impl Display for LintIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.level {
+            LintLevel::Error => write!(f, "错误：{}", self.message),
+            LintLevel::Warning => write!(f, "警告：{}", self.message),
+        }
+    }
+}
+
+/// 检查提交参数中的转换规则
+///
+/// 检查作者回退规则（邮箱格式、重复或被前面规则覆盖的条件、同一邮箱对应不同名称）、
+/// 跳过路径的通配符（匹配所有路径、回溯过多）、作者列表之间的冲突以及提交说明宽度。
+///
+/// # 参数
+///
+/// * `args`: 提交参数
+///
+/// # 返回
+///
+/// 发现的问题，按检查顺序排列
+pub fn lint_commit_args(args: &CommitArgs) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    lint_author_rules(&args.author_fallbacks, &mut issues);
+    lint_author_lists(args, &mut issues);
+    for pattern in &args.skip_paths {
+        lint_path_pattern("--skip-paths", pattern, &mut issues);
+    }
+    report_duplicates("--skip-paths", &args.skip_paths, &mut issues);
+    for (name, width) in [
+        ("--subject-width", args.subject_width),
+        ("--body-width", args.body_width),
+        ("--max-message-chars", args.max_message_chars),
+        ("--max-author-chars", args.max_author_chars),
+    ] {
+        if width == Some(0) {
+            issues.push(LintIssue::error(format!("{name} 不能为 0")));
+        }
+    }
+    issues
+}
+
+/// 检查作者回退规则
+fn lint_author_rules(rules: &[AuthorRule], issues: &mut Vec<LintIssue>) {
+    for (i, rule) in rules.iter().enumerate() {
+        let number = i + 1;
+        if !is_valid_email(&rule.email) {
+            issues.push(LintIssue::error(format!(
+                "作者规则 {number} 的邮箱 '{}' 格式无效",
+                rule.email
+            )));
+        }
+        if let Some((earlier, _)) = rules[..i]
+            .iter()
+            .enumerate()
+            .find(|(_, earlier)| shadows(&earlier.matcher, &rule.matcher))
+        {
+            issues.push(LintIssue::warning(format!(
+                "作者规则 {number} 的条件被规则 {} 覆盖，永远不会生效",
+                earlier + 1
+            )));
+        }
+        if let Some(other) = rules[..i]
+            .iter()
+            .find(|other| other.email.eq_ignore_ascii_case(&rule.email) && other.name != rule.name)
+        {
+            issues.push(LintIssue::warning(format!(
+                "邮箱 {} 对应了不同的作者名称：{}、{}",
+                rule.email, other.name, rule.name
+            )));
+        }
+    }
+}
+
+/// 前一条规则匹配的版本是否包含后一条规则匹配的所有版本
+fn shadows(earlier: &AuthorMatch, later: &AuthorMatch) -> bool {
+    match (earlier, later) {
+        (AuthorMatch::PathPrefix(earlier), AuthorMatch::PathPrefix(later)) => later
+            .strip_prefix(earlier.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/')),
+        (AuthorMatch::MessageContains(earlier), AuthorMatch::MessageContains(later)) => {
+            later.contains(earlier.as_str())
+        }
+        _ => false,
+    }
+}
+
+/// 简单检查邮箱格式：`本地部分@域名`，不含空白和尖括号
+fn is_valid_email(email: &str) -> bool {
+    email.split_once('@').is_some_and(|(local, domain)| {
+        !local.is_empty() && !domain.is_empty() && !domain.contains('@')
+    }) && !email.contains(|c: char| c.is_whitespace() || c == '<' || c == '>')
+}
+
+/// 检查服务账号、跳过作者和合并作者列表
+fn lint_author_lists(args: &CommitArgs, issues: &mut Vec<LintIssue>) {
+    report_duplicates("--service-account", &args.service_accounts, issues);
+    report_duplicates("--skip-author", &args.skip_authors, issues);
+    report_duplicates("--merge-author", &args.merge_authors, issues);
+    for author in &args.merge_authors {
+        if args.skip_authors.contains(author) {
+            issues.push(LintIssue::error(format!(
+                "作者 {author} 同时出现在 --skip-author 和 --merge-author 中，其版本会被跳过而不会合并"
+            )));
+        }
+    }
+    if !args.service_accounts.is_empty() && args.author_fallbacks.is_empty() {
+        issues.push(LintIssue::warning(
+            "设置了 --service-account 但没有 --author-fallback 规则，服务账号的版本仍使用原作者"
+                .into(),
+        ));
+    }
+}
+
+/// 检查路径通配符
+fn lint_path_pattern(option: &str, pattern: &str, issues: &mut Vec<LintIssue>) {
+    let normalized = pattern.replace('\\', "/");
+    let segments: Vec<&str> = normalized
+        .trim_matches('/')
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+    if segments.is_empty() || segments.iter().all(|s| s.chars().all(|c| c == '*')) {
+        issues.push(LintIssue::error(format!(
+            "{option} '{pattern}' 匹配所有路径，会跳过每一个版本"
+        )));
+        return;
+    }
+    if segments.windows(2).any(|pair| pair == ["**", "**"]) {
+        issues.push(LintIssue::warning(format!(
+            "{option} '{pattern}' 包含连续的 **，与单个 ** 等价但匹配更慢"
+        )));
+    }
+    let globstars = segments.iter().filter(|s| **s == "**").count();
+    let max_stars = segments
+        .iter()
+        .filter(|s| **s != "**")
+        .map(|s| s.matches('*').count())
+        .max()
+        .unwrap_or_default();
+    if globstars > MAX_WILDCARDS || max_stars > MAX_WILDCARDS {
+        issues.push(LintIssue::warning(format!(
+            "{option} '{pattern}' 通配符过多，匹配长路径时回溯可能非常慢"
+        )));
+    }
+    if segments.iter().any(|s| *s != "**" && s.contains("**")) {
+        issues.push(LintIssue::warning(format!(
+            "{option} '{pattern}' 中与其他字符相连的 ** 只匹配单级目录，跨目录匹配需写成单独的 ** 段"
+        )));
+    }
+}
+
+/// 报告重复的值
+fn report_duplicates(option: &str, values: &[String], issues: &mut Vec<LintIssue>) {
+    for (i, value) in values.iter().enumerate() {
+        // 只在第二次出现时报告一次
+        if values[..i].iter().filter(|v| *v == value).count() == 1 {
+            issues.push(LintIssue::warning(format!("{option} '{value}' 重复出现")));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(rules: &[&str]) -> Vec<AuthorRule> {
+        rules.iter().map(|rule| rule.parse().unwrap()).collect()
+    }
+
+    fn messages(args: &CommitArgs) -> Vec<String> {
+        lint_commit_args(args)
+            .iter()
+            .map(|issue| issue.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_author_rules() {
+        let args = CommitArgs {
+            author_fallbacks: rules(&[
+                "path:trunk=Alice <alice@example.com>",
+                "path:trunk/ui=Bob <bob@example.com>",
+                "message:[release]=Carol <carol example.com>",
+                "message:[release] 1.0=Dave <ALICE@example.com>",
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(
+            messages(&args),
+            [
+                "警告：作者规则 2 的条件被规则 1 覆盖，永远不会生效",
+                "错误：作者规则 3 的邮箱 'carol example.com' 格式无效",
+                "警告：作者规则 4 的条件被规则 3 覆盖，永远不会生效",
+                "警告：邮箱 ALICE@example.com 对应了不同的作者名称：Alice、Dave",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_path_patterns_and_author_lists() {
+        let args = CommitArgs {
+            skip_paths: vec![
+                "**".into(),
+                "trunk/**/**/docs".into(),
+                "*a*b*c*d*".into(),
+                "docs**".into(),
+                "trunk/docs".into(),
+                "trunk/docs".into(),
+            ],
+            skip_authors: vec!["buildbot".into()],
+            merge_authors: vec!["buildbot".into()],
+            subject_width: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(
+            messages(&args),
+            [
+                "错误：作者 buildbot 同时出现在 --skip-author 和 --merge-author 中，其版本会被跳过而不会合并",
+                "错误：--skip-paths '**' 匹配所有路径，会跳过每一个版本",
+                "警告：--skip-paths 'trunk/**/**/docs' 包含连续的 **，与单个 ** 等价但匹配更慢",
+                "警告：--skip-paths '*a*b*c*d*' 通配符过多，匹配长路径时回溯可能非常慢",
+                "警告：--skip-paths 'docs**' 中与其他字符相连的 ** 只匹配单级目录，跨目录匹配需写成单独的 ** 段",
+                "警告：--skip-paths 'trunk/docs' 重复出现",
+                "错误：--subject-width 不能为 0",
+            ]
+        );
+        assert!(lint_commit_args(&CommitArgs::default()).is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+//! 同步锁：防止同一个 Git 仓库同时运行多个同步
+
+use std::{
+    fs::{File, OpenOptions, TryLockError},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use sha2::{Digest, Sha256};
+
+use crate::error::{Result, SyncError};
+
+/// 同步锁
+///
+/// 使用操作系统的文件锁，进程退出（包括崩溃）时自动释放，不会留下失效的锁。
+/// 锁文件本身保留，内容为持有者的进程号和加锁时间，仅用于提示。
+#[derive(Debug)]
+pub struct SyncLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl SyncLock {
+    /// 同步对的锁文件路径
+    ///
+    /// 锁文件放在配置文件所在目录，文件名包含 Git 目录路径的摘要，
+    /// 因此不同的同步对互不影响，也不会出现在 Git 工作目录中
+    ///
+    /// # 参数
+    ///
+    /// * `config_dir`: 配置文件所在目录
+    /// * `git_dir`: 同步对的 Git 目录
+    pub fn path_for(config_dir: &Path, git_dir: &Path) -> PathBuf {
+        let git_dir = git_dir
+            .canonicalize()
+            .unwrap_or_else(|_| git_dir.to_path_buf());
+        let digest = format!("{:x}", Sha256::digest(git_dir.to_string_lossy().as_bytes()));
+        config_dir.join(format!("svn2git-{}.lock", &digest[..12]))
+    }
+
+    /// 获取锁
+    ///
+    /// # 参数
+    ///
+    /// * `path`: 锁文件路径
+    /// * `wait`: 被占用时是否等待释放，否则直接返回错误
+    pub fn acquire(path: &Path, wait: bool) -> Result<Self> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let mut holder = String::new();
+                file.read_to_string(&mut holder)?;
+                let holder = match holder.trim() {
+                    "" => "未知进程".to_string(),
+                    holder => holder.to_string(),
+                };
+                if !wait {
+                    return Err(SyncError::App(format!(
+                        "另一个同步正在进行（{holder}），锁文件：{}。可使用 --wait 等待其结束",
+                        path.display()
+                    )));
+                }
+                println!("另一个同步正在进行（{holder}），等待其结束...");
+                file.lock()?;
+            }
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        write!(
+            file,
+            "PID {}，开始于 {}",
+            std::process::id(),
+            Local::now().format("%Y-%m-%d %H:%M:%S")
+        )?;
+        Ok(Self {
+            file,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// 锁文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for SyncLock {
+    fn drop(&mut self) {
+        let _ = self.file.set_len(0);
+        let _ = self.file.unlock();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use super::*;
+
+    #[test]
+    fn test_lock_should_reject_second_holder_until_released() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sync.lock");
+
+        let lock = SyncLock::acquire(&path, false).unwrap();
+        let err = SyncLock::acquire(&path, false).unwrap_err().to_string();
+        assert!(
+            err.contains(&format!("PID {}", std::process::id())),
+            "{err}"
+        );
+
+        drop(lock);
+        SyncLock::acquire(&path, false).unwrap();
+    }
+
+    #[test]
+    fn test_lock_with_wait_should_block_until_released() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sync.lock");
+        let lock = SyncLock::acquire(&path, false).unwrap();
+
+        let releaser = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            drop(lock);
+        });
+        SyncLock::acquire(&path, true).unwrap();
+        releaser.join().unwrap();
+    }
+
+    #[test]
+    fn test_lock_path_should_differ_per_git_dir() {
+        let config_dir = Path::new("conf");
+        let a = SyncLock::path_for(config_dir, Path::new("d:/git/a"));
+        let b = SyncLock::path_for(config_dir, Path::new("d:/git/b"));
+        assert_ne!(a, b);
+        assert_eq!(a, SyncLock::path_for(config_dir, Path::new("d:/git/a")));
+        assert!(a.starts_with(config_dir));
+    }
+}
@@ -0,0 +1,450 @@
+//! 分段获取 SVN 日志：每次只请求一段版本，按需惰性获取下一段
+
+use std::{collections::VecDeque, path::Path, time::Instant};
+
+use crate::{
+    bandwidth::AdaptiveBatch,
+    error::Result,
+    ops::{SvnLog, SvnLogQuery, SvnOperations},
+};
+
+/// 同步时在内存中最多保留的完整日志条数
+pub const DEFAULT_LOG_WINDOW: usize = 1000;
+
+/// 按段获取的 SVN 日志迭代器
+///
+/// 待同步的版本很多时，一次 `svn log --xml BASE:HEAD` 会在内存中生成巨大的 XML，
+/// 这里每次用 `--limit` 只请求一段，边读取边返回，取完当前段再从最后一个版本之后请求下一段。
+/// 查询设置了 `limit` 时最多返回这么多条，迭代器提前停止时不再请求之后的版本。
+/// 远程仓库根据每段的用时自动调整段大小，并在每段完成后输出进度。
+pub struct SvnLogStream<'a> {
+    svn: &'a dyn SvnOperations,
+    path: &'a Path,
+    query: SvnLogQuery,
+    /// 最多返回的日志条数
+    remaining: Option<usize>,
+    batch: AdaptiveBatch,
+    remote: bool,
+    /// 正在读取的一段
+    current: Option<LogChunk<'a>>,
+    fetched: usize,
+    done: bool,
+}
+
+/// 正在读取的一段日志
+struct LogChunk<'a> {
+    logs: Box<dyn Iterator<Item = Result<SvnLog>> + 'a>,
+    /// 请求的条数
+    size: usize,
+    /// 已读取的条数
+    count: usize,
+    /// 已读取的最后一个版本
+    last: Option<String>,
+    started: Instant,
+}
+
+impl<'a> SvnLogStream<'a> {
+    /// 创建日志迭代器，第一次迭代时才请求第一段
+    ///
+    /// # 参数
+    ///
+    /// * `svn`: SVN 操作
+    /// * `path`: SVN 工作副本目录
+    /// * `query`: 日志查询范围，`limit` 为最多返回的总条数
+    /// * `batch`: 每段的（最大）版本数
+    /// * `remote`: 是否为远程仓库，远程仓库根据用时调整段大小并输出进度
+    pub fn new(
+        svn: &'a dyn SvnOperations,
+        path: &'a Path,
+        query: SvnLogQuery,
+        batch: usize,
+        remote: bool,
+    ) -> Self {
+        Self {
+            svn,
+            path,
+            remaining: query.limit,
+            query,
+            batch: AdaptiveBatch::new(batch),
+            remote,
+            current: None,
+            fetched: 0,
+            done: false,
+        }
+    }
+
+    /// 请求下一段日志，已取够时标记结束
+    fn fetch_next(&mut self) -> Result<()> {
+        let size = match self.remaining {
+            Some(remaining) => self.batch.size().min(remaining),
+            None => self.batch.size(),
+        };
+        if size == 0 {
+            self.done = true;
+            return Ok(());
+        }
+        self.query.limit = Some(size);
+        let started = Instant::now();
+        self.current = Some(LogChunk {
+            logs: self.svn.log_entries(self.path, &self.query)?,
+            size,
+            count: 0,
+            last: None,
+            started,
+        });
+        Ok(())
+    }
+
+    /// 一段读完后输出进度，没有更多日志时标记结束
+    fn finish_chunk(&mut self, chunk: LogChunk<'a>) {
+        let elapsed = chunk.started.elapsed();
+        let Some(last) = chunk.last else {
+            self.done = true;
+            return;
+        };
+        let (count, size) = (chunk.count, chunk.size);
+        let first_chunk = self.fetched == 0;
+        self.fetched += count;
+        if let Some(remaining) = &mut self.remaining {
+            *remaining = remaining.saturating_sub(count);
+        }
+
+        // 不满一段说明已经到了查询范围的末尾；版本没有前进时也停止，避免重复请求同一段
+        let stalled = self.query.after_rev.as_deref() == Some(last.as_str());
+        if count < size || stalled {
+            self.done = true;
+        }
+        if self.remote || !(first_chunk && self.done) {
+            println!(
+                "已获取 {} 条 SVN 日志（到 r{last}，本批用时 {:.1} 秒）",
+                self.fetched,
+                elapsed.as_secs_f64()
+            );
+        }
+        if self.done {
+            return;
+        }
+        // 之后的段从已获取的最后一个版本继续，不再按起始日期查询
+        self.query.after_rev = Some(last);
+        self.query.since = None;
+        if self.remote {
+            match self.batch.record(count, elapsed) {
+                Some(new_size) if new_size < size => {
+                    println!("网络较慢，每次请求的日志条数降为 {new_size}")
+                }
+                Some(new_size) => println!("网络恢复，每次请求的日志条数增加到 {new_size}"),
+                None => {}
+            }
+        }
+    }
+}
+
+impl Iterator for SvnLogStream<'_> {
+    type Item = Result<SvnLog>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some(chunk) = &mut self.current else {
+                if self.done {
+                    return None;
+                }
+                if let Err(err) = self.fetch_next() {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+                continue;
+            };
+            match chunk.logs.next() {
+                Some(Ok(log)) => {
+                    chunk.count += 1;
+                    chunk.last = Some(log.version.clone());
+                    return Some(Ok(log));
+                }
+                Some(Err(err)) => {
+                    self.current = None;
+                    self.done = true;
+                    return Some(Err(err));
+                }
+                None => {
+                    let chunk = self.current.take().expect("当前段存在");
+                    self.finish_chunk(chunk);
+                }
+            }
+        }
+    }
+}
+
+/// 待同步的日志
+///
+/// 计算待同步版本时只保留前一个窗口的完整日志用于确认，之后的版本只计数；
+/// 同步时取完当前窗口再从最后一个版本之后获取下一个窗口，
+/// 待同步的版本有上万个时也不必把全部日志留在内存中
+#[derive(Debug, Default)]
+pub struct PendingLogs {
+    buffer: VecDeque<SvnLog>,
+    /// 待同步的总条数
+    total: usize,
+    /// 尚未获取的条数
+    unfetched: usize,
+    /// 全部待同步版本的变更路径数
+    changed_paths: usize,
+    /// 获取下一个窗口的查询范围
+    query: SvnLogQuery,
+}
+
+impl PendingLogs {
+    /// 全部日志都已在内存中
+    ///
+    /// # 参数
+    ///
+    /// * `logs`: 待同步的日志
+    pub fn from_logs(logs: Vec<SvnLog>) -> Self {
+        Self {
+            total: logs.len(),
+            changed_paths: logs.iter().map(|log| log.paths.len()).sum(),
+            buffer: logs.into(),
+            ..Default::default()
+        }
+    }
+
+    /// 从日志流中计算待同步的日志，只保留前 `window` 条
+    ///
+    /// # 参数
+    ///
+    /// * `logs`: 待同步的日志流
+    /// * `query`: 获取日志流的查询范围，用于之后获取剩余的窗口
+    /// * `window`: 保留的日志条数
+    /// * `visit`: 依次访问每条日志，用于记录同步计划
+    pub fn scan(
+        logs: impl Iterator<Item = Result<SvnLog>>,
+        query: SvnLogQuery,
+        window: usize,
+        mut visit: impl FnMut(&SvnLog),
+    ) -> Result<Self> {
+        let mut pending = Self {
+            query,
+            ..Default::default()
+        };
+        for log in logs {
+            let log = log?;
+            visit(&log);
+            pending.total += 1;
+            pending.changed_paths += log.paths.len();
+            if pending.buffer.len() < window {
+                pending.buffer.push_back(log);
+            } else {
+                pending.unfetched += 1;
+            }
+        }
+        if let Some(last) = pending.buffer.back() {
+            pending.query.after_rev = Some(last.version.clone());
+            pending.query.since = None;
+        }
+        Ok(pending)
+    }
+
+    /// 待同步的总条数
+    pub fn len(&self) -> usize {
+        self.total
+    }
+
+    /// 是否没有待同步的日志
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    /// 全部待同步版本的变更路径数
+    pub fn changed_paths(&self) -> usize {
+        self.changed_paths
+    }
+
+    /// 已在内存中的日志，全部日志都在内存中时即为全部待同步的日志
+    pub fn loaded(&mut self) -> &[SvnLog] {
+        self.buffer.make_contiguous()
+    }
+
+    /// 是否全部日志都已在内存中
+    pub fn is_loaded(&self) -> bool {
+        self.unfetched == 0
+    }
+
+    /// 取出全部日志，只能在 [`Self::is_loaded`] 时调用
+    pub fn into_logs(self) -> Vec<SvnLog> {
+        debug_assert!(self.is_loaded());
+        self.buffer.into()
+    }
+
+    /// 下一条日志，当前窗口取完时调用 `fetch` 获取下一个窗口
+    ///
+    /// # 参数
+    ///
+    /// * `window`: 每个窗口的日志条数
+    /// * `fetch`: 按查询范围获取日志
+    pub fn next(
+        &mut self,
+        window: usize,
+        fetch: impl FnOnce(SvnLogQuery) -> Result<Vec<SvnLog>>,
+    ) -> Result<Option<SvnLog>> {
+        self.fill(window, fetch)?;
+        Ok(self.buffer.pop_front())
+    }
+
+    /// 查看下一条日志但不取出
+    ///
+    /// # 参数
+    ///
+    /// * `window`: 每个窗口的日志条数
+    /// * `fetch`: 按查询范围获取日志
+    pub fn peek(
+        &mut self,
+        window: usize,
+        fetch: impl FnOnce(SvnLogQuery) -> Result<Vec<SvnLog>>,
+    ) -> Result<Option<&SvnLog>> {
+        self.fill(window, fetch)?;
+        Ok(self.buffer.front())
+    }
+
+    /// 当前窗口取完时获取下一个窗口；计算之后新提交的版本不会被获取
+    fn fill(
+        &mut self,
+        window: usize,
+        fetch: impl FnOnce(SvnLogQuery) -> Result<Vec<SvnLog>>,
+    ) -> Result<()> {
+        if !self.buffer.is_empty() || self.unfetched == 0 {
+            return Ok(());
+        }
+        let logs = fetch(SvnLogQuery {
+            limit: Some(self.unfetched.min(window.max(1))),
+            ..self.query.clone()
+        })?;
+        match logs.last() {
+            Some(last) => {
+                self.query.after_rev = Some(last.version.clone());
+                self.unfetched -= logs.len().min(self.unfetched);
+            }
+            // 计算之后版本不见了（如仓库被替换），不再获取
+            None => self.unfetched = 0,
+        }
+        self.buffer.extend(logs);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::ops::MockSvnOperations;
+
+    fn create_svn_ops(revs: &[&str]) -> MockSvnOperations {
+        let svn_ops = MockSvnOperations::new();
+        for rev in revs {
+            svn_ops.add_log_to_mock(&PathBuf::from("svn_dir"), rev, "m");
+        }
+        svn_ops
+    }
+
+    #[test]
+    fn test_stream_should_fetch_chunks_lazily() {
+        let svn_ops = create_svn_ops(&["1", "2", "3", "4", "5"]);
+        let path = PathBuf::from("svn_dir");
+        let mut stream = SvnLogStream::new(&svn_ops, &path, SvnLogQuery::default(), 2, false);
+
+        assert_eq!(stream.next().unwrap().unwrap().version, "1");
+        assert_eq!(stream.next().unwrap().unwrap().version, "2");
+        assert_eq!(svn_ops.log_queries().len(), 1);
+        assert_eq!(stream.next().unwrap().unwrap().version, "3");
+        assert_eq!(svn_ops.log_queries().len(), 2);
+
+        let rest: Vec<String> = stream.map(|log| log.unwrap().version).collect();
+        assert_eq!(rest, ["4", "5"]);
+        let queries: Vec<_> = svn_ops
+            .log_queries()
+            .into_iter()
+            .map(|query| (query.after_rev, query.limit))
+            .collect();
+        assert_eq!(
+            queries,
+            [
+                (None, Some(2)),
+                (Some("2".to_string()), Some(2)),
+                (Some("4".to_string()), Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stream_should_stop_at_query_limit() {
+        let svn_ops = create_svn_ops(&["1", "2", "3", "4", "5"]);
+        let path = PathBuf::from("svn_dir");
+        let query = SvnLogQuery {
+            limit: Some(3),
+            ..Default::default()
+        };
+
+        let logs: Vec<String> = SvnLogStream::new(&svn_ops, &path, query, 2, false)
+            .map(|log| log.unwrap().version)
+            .collect();
+        assert_eq!(logs, ["1", "2", "3"]);
+        let limits: Vec<_> = svn_ops
+            .log_queries()
+            .into_iter()
+            .map(|query| query.limit)
+            .collect();
+        assert_eq!(limits, [Some(2), Some(1)]);
+    }
+
+    #[test]
+    fn test_pending_logs_should_keep_one_window_and_fetch_the_rest_lazily() {
+        let svn_ops = create_svn_ops(&["1", "2", "3", "4", "5"]);
+        let path = PathBuf::from("svn_dir");
+        let mut visited = Vec::new();
+        let mut pending = PendingLogs::scan(
+            SvnLogStream::new(&svn_ops, &path, SvnLogQuery::default(), 10, false),
+            SvnLogQuery::default(),
+            2,
+            |log| visited.push(log.version.clone()),
+        )
+        .unwrap();
+        assert_eq!(visited, ["1", "2", "3", "4", "5"]);
+        assert_eq!(pending.len(), 5);
+        assert!(!pending.is_loaded());
+        assert_eq!(pending.loaded().len(), 2);
+
+        // 计算之后新提交的版本不同步
+        svn_ops.add_log_to_mock(&path, "6", "m");
+        let fetch = |query: SvnLogQuery| svn_ops.get_logs(&path, &query);
+        let mut synced = Vec::new();
+        while let Some(log) = pending.next(2, fetch).unwrap() {
+            if synced.len() == 1 {
+                assert_eq!(pending.peek(2, fetch).unwrap().unwrap().version, "3");
+            }
+            synced.push(log.version);
+        }
+        assert_eq!(synced, ["1", "2", "3", "4", "5"]);
+        let queries: Vec<_> = svn_ops
+            .log_queries()
+            .into_iter()
+            .skip(1)
+            .map(|query| (query.after_rev, query.limit))
+            .collect();
+        assert_eq!(
+            queries,
+            [
+                (Some("2".to_string()), Some(2)),
+                (Some("4".to_string()), Some(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stream_should_yield_error_once() {
+        let path = PathBuf::from("missing_dir");
+        let svn_ops = MockSvnOperations::new();
+        let mut stream = SvnLogStream::new(&svn_ops, &path, SvnLogQuery::default(), 2, false);
+
+        assert!(stream.next().unwrap().is_err());
+        assert!(stream.next().is_none());
+    }
+}
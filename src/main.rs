@@ -1,38 +1,880 @@
+use std::{
+    fs::File,
+    io::{BufReader, IsTerminal},
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, Instant},
+};
+
 use clap::Parser;
 
+#[cfg(windows)]
+use svn2git::run_windows_service;
 use svn2git::{
-    Cli, Commands, DefaultUserInteractor, DiskStorage, HistoryCommands, HistoryManager, Result,
-    SyncRunOptions, SyncTool, select_or_create_config_with_interactor,
+    AuditOptions, BackoffPolicy, CheckLevel, Cli, CommandSettings, Commands, CommitArgs,
+    ConversionProfile, DefaultUserInteractor, DirtyTreePolicy, DiskStorage, DumpImportOptions,
+    FileStorage, HistoryCommands, HistoryManager, HistoryRecord, LintLevel, MemoryStorage,
+    NonInteractiveUserInteractor, PAIR_STATE_NAMESPACES, PairStorage, PathFilter, ProfileCommands,
+    ProgressFile, PublishPolicy, Result, ServiceCommands, ServiceOptions, ServiceSpec, StateStore,
+    StopSignal, SvnLogQuery, SvnOperations, SyncConfig, SyncError, SyncLock, SyncRunOptions,
+    SyncTool, UserInteractor, WatchSchedule, adopt_repository, apply_profile, audit_record,
+    confirm_destructive_with_interactor, flush_traces, import_dump, install_service,
+    lint_commit_args, pair_status, parse_env_assignment, preview_filters, run_doctor, run_service,
+    select_or_create_config_with_interactor, sync_pairs, sync_pairs_parallel, trace_span,
+    traceparent, uninstall_service, watch,
 };
 
+/// 配置文件路径
+const CONFIG_FILE: &str = "config.json";
+
+/// 同步对的锁文件路径，与配置文件放在同一目录
+fn sync_lock_path(config_file: &Path, git_dir: &Path) -> PathBuf {
+    let config_dir = config_file.parent().unwrap_or_else(|| Path::new(""));
+    SyncLock::path_for(config_dir, git_dir)
+}
+
+/// 同步对的进度文件路径，与锁文件放在同一目录
+fn sync_progress_path(config_file: &Path, git_dir: &Path) -> PathBuf {
+    let config_dir = config_file.parent().unwrap_or_else(|| Path::new(""));
+    ProgressFile::path_for(config_dir, git_dir)
+}
+
+/// 按命令行参数生成服务描述，工作目录默认为当前目录
+fn service_spec(name: &str, options: ServiceOptions) -> Result<ServiceSpec> {
+    let working_dir = match options.working_dir {
+        Some(dir) => std::path::absolute(dir)?,
+        None => std::env::current_dir()?,
+    };
+    Ok(
+        ServiceSpec::new(name, std::env::current_exe()?, working_dir)
+            .with_interval(options.interval)
+            .with_log_path(options.log),
+    )
+}
+
+/// 配置文件、保存配置文件时加锁的文件和同步对状态目录，位于 Git 目录中时不参与提交
+fn state_files(config_file: &Path) -> Vec<PathBuf> {
+    let mut lock_name = config_file.as_os_str().to_owned();
+    lock_name.push(".lock");
+    let config_dir = config_file.parent().unwrap_or_else(|| Path::new(""));
+    let mut files = vec![config_file.to_path_buf(), lock_name.into()];
+    files.extend(PAIR_STATE_NAMESPACES.map(|namespace| config_dir.join(namespace)));
+    files
+}
+
+/// 读取配置文件中的同步对，跳过已暂停的同步对
+///
+/// # 参数
+///
+/// * `config_file`: 配置文件
+/// * `commands`: 执行 svn/git 命令的设置
+/// * `filter`: 只保留满足条件的记录
+fn load_pairs(
+    config_file: &Path,
+    commands: &CommandSettings,
+    filter: impl Fn(&HistoryRecord) -> bool,
+) -> Result<Vec<SyncConfig>> {
+    let history = HistoryManager::new(DiskStorage::new(config_file.into()))?;
+    select_pairs(&history, config_file, commands, filter)
+}
+
+/// 从已加载的历史记录中选出同步对，跳过已暂停的同步对
+///
+/// # 参数
+///
+/// * `history`: 历史记录
+/// * `config_file`: 历史记录所在的配置文件，用于提示
+/// * `commands`: 执行 svn/git 命令的设置
+/// * `filter`: 只保留满足条件的记录
+fn select_pairs(
+    history: &HistoryManager<impl FileStorage>,
+    config_file: &Path,
+    commands: &CommandSettings,
+    filter: impl Fn(&HistoryRecord) -> bool,
+) -> Result<Vec<SyncConfig>> {
+    let records: Vec<_> = history
+        .records()
+        .iter()
+        .enumerate()
+        .filter(|(_, record)| filter(record))
+        .collect();
+    if records.is_empty() {
+        return Err(SyncError::App(format!(
+            "{} 中没有同步对，请先执行 sync 添加",
+            config_file.display()
+        )));
+    }
+    // 暂停的同步对不算错误，只是跳过
+    Ok(records
+        .into_iter()
+        .filter(|(index, record)| {
+            if record.is_paused() {
+                println!(
+                    "{} 已暂停，跳过（可用 svn2git unpause {index} 恢复）",
+                    record.label()
+                );
+            }
+            !record.is_paused()
+        })
+        .map(|(_, record)| record.to_sync_config().with_commands(commands.clone()))
+        .collect())
+}
+
+/// 同步配置文件中的一个同步对
+///
+/// 重新读取配置文件，保存时只写入本同步对的记录，不会覆盖同时运行的其他同步对的进度
+///
+/// # 参数
+///
+/// * `config_file`: 配置文件
+/// * `config`: 同步对
+/// * `commit`: 提交参数
+/// * `yes`: 是否自动确认
+/// * `watch`: 是否为监视模式的一轮：没有新版本时跳过，连续失败时退避
+/// * `verbose`: 是否输出同步过程中的细节
+fn sync_pair(
+    config_file: &Path,
+    mut config: SyncConfig,
+    commit: &CommitArgs,
+    yes: bool,
+    watch: bool,
+    verbose: bool,
+) -> Result<()> {
+    let mut lock_name = config_file.as_os_str().to_owned();
+    lock_name.push(".lock");
+    let storage = PairStorage::new(
+        DiskStorage::new(config_file.into()),
+        config.svn_dir.clone(),
+        config.git_dir.clone(),
+        lock_name.into(),
+    );
+    let history = HistoryManager::new(storage)?;
+    let commit = apply_profile(
+        &DiskStorage::new(config_file.into()),
+        &mut config,
+        commit.clone(),
+    )?;
+    let interactor: Box<dyn UserInteractor> = if yes {
+        Box::new(NonInteractiveUserInteractor)
+    } else {
+        Box::new(DefaultUserInteractor)
+    };
+    let git_operations = Box::new(config.create_git_operations());
+    let lock_file = sync_lock_path(config_file, &config.git_dir);
+    let progress_file = sync_progress_path(config_file, &config.git_dir);
+    SyncTool::new(config, history, interactor, git_operations)
+        .with_commit_args(commit)
+        .with_lock_file(Some(lock_file))
+        .with_progress_file(Some(progress_file))
+        .with_state_files(state_files(config_file))
+        .with_backoff(watch.then(BackoffPolicy::default))
+        .with_watch(watch)
+        .with_verbose(verbose)
+        .run()
+}
+
+/// 收到 SIGHUP 时请求监视循环重新加载配置并立即开始下一轮
+#[cfg(unix)]
+fn reload_on_hangup(stop: &StopSignal) -> Result<()> {
+    let mut signals = signal_hook::iterator::Signals::new([signal_hook::consts::SIGHUP])
+        .map_err(|e| SyncError::App(format!("注册 SIGHUP 处理失败：{e}")))?;
+    let stop = stop.clone();
+    std::thread::spawn(move || {
+        for _ in signals.forever() {
+            eprintln!("收到 SIGHUP，重新加载配置并开始下一轮（不打断正在进行的同步）");
+            stop.request_reload();
+        }
+    });
+    Ok(())
+}
+
+/// 没有 SIGHUP 的平台上每轮开始时仍会重新读取配置
+#[cfg(not(unix))]
+fn reload_on_hangup(_stop: &StopSignal) -> Result<()> {
+    Ok(())
+}
+
 fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let result = run(Cli::parse());
+    // 提前返回的错误也要导出已记录的链路
+    flush_traces();
+    if let Err(e) = &result
+        && e.is_authentication_failure()
+    {
+        eprintln!(
+            "提示：svn/git 认证失败，请检查凭据，或用 history env --set 为同步对设置 SVN_SSH、GIT_ASKPASS 等环境变量"
+        );
+    }
+    result
+}
 
-    let storage = DiskStorage::new("config.json".into());
+fn run(cli: Cli) -> Result<()> {
+    // 标准输出不是终端（如 CI 日志、重定向到文件）时使用简要模式，`--verbose` 强制输出细节
+    let verbose = cli.verbose || std::io::stdout().is_terminal();
+    let commands = CommandSettings::default()
+        .with_svn_non_interactive(cli.svn_non_interactive, &cli.trust_server_cert_failures)?
+        .with_timeouts(
+            cli.svn_timeout.map(Duration::from_secs),
+            cli.git_timeout.map(Duration::from_secs),
+        )
+        .with_log_encoding(cli.encoding.as_deref())?;
+    let storage: Box<dyn StateStore> = if cli.ephemeral {
+        Box::new(MemoryStorage::new())
+    } else {
+        Box::new(DiskStorage::new(CONFIG_FILE.into()))
+    };
     let mut history = HistoryManager::new(storage)?;
+    // 配置方案与历史记录保存在同一个存储中
+    let profiles: Box<dyn StateStore> = if cli.ephemeral {
+        Box::new(MemoryStorage::new())
+    } else {
+        Box::new(DiskStorage::new(CONFIG_FILE.into()))
+    };
 
     match cli.command {
         Commands::Sync {
             svn_dir,
             git_dir,
             limit,
+            only_revs,
+            select,
+            preview_diff,
             dry_run,
+            yes,
+            since,
+            until,
+            svn_url,
+            profile,
+            message_prefix,
+            remote,
+            push,
+            bundle,
+            log_batch,
+            dirty_tree,
+            force,
+            rollback_on_failure,
+            commit,
         } => {
-            let interactor = DefaultUserInteractor;
-            let config = select_or_create_config_with_interactor(
+            let interactor: Box<dyn UserInteractor> = if yes {
+                Box::new(NonInteractiveUserInteractor)
+            } else {
+                Box::new(DefaultUserInteractor)
+            };
+            let mut config = select_or_create_config_with_interactor(
                 svn_dir,
                 git_dir,
                 &mut history,
-                &interactor,
-            )?;
+                interactor.as_ref(),
+            )?
+            .with_commands(commands);
+            if let Some(name) = profile {
+                ConversionProfile::load(profiles.as_ref(), &name)?;
+                history.set_profile(&config.svn_dir, &config.git_dir, Some(name.clone()));
+                history.save()?;
+                config.profile = Some(name);
+            }
+            if let Some(prefix) = message_prefix {
+                history.set_message_prefix(&config.svn_dir, &config.git_dir, Some(prefix.clone()));
+                history.save()?;
+                config.message_prefix = prefix;
+            }
+            let commit = apply_profile(profiles.as_ref(), &mut config, commit)?;
+            if force {
+                config.policies.dirty_tree = DirtyTreePolicy::Ignore;
+            } else if let Some(policy) = dirty_tree {
+                config.policies.set("dirty-tree", &policy)?;
+            }
+            let git_operations = Box::new(config.create_git_operations());
+            let lock_file = sync_lock_path(Path::new(CONFIG_FILE), &config.git_dir);
+            let progress_file = sync_progress_path(Path::new(CONFIG_FILE), &config.git_dir);
+            let mut tool = SyncTool::new(config, history, interactor, git_operations)
+                .with_commit_args(commit)
+                .with_lock_file(Some(lock_file))
+                .with_progress_file(Some(progress_file))
+                .with_state_files(state_files(Path::new(CONFIG_FILE)))
+                .with_svn_url(svn_url)
+                .with_publish_policy(PublishPolicy {
+                    git_remote: remote,
+                    bundle,
+                    push,
+                })
+                .with_log_batch(log_batch)
+                .with_rollback_on_failure(rollback_on_failure)
+                .with_allow_unrelated_history(force)
+                .with_verbose(verbose);
+            tool.run_with_options(&SyncRunOptions {
+                dry_run,
+                limit,
+                only_revs,
+                select,
+                preview_diff,
+                since,
+                until,
+            })?;
+        }
+        Commands::Pause { id } => {
+            let label = history
+                .records()
+                .get(id)
+                .ok_or_else(|| SyncError::App("索引超出范围".into()))?
+                .label();
+            match history.set_paused(id, true)? {
+                true => println!("已暂停 {label} 的自动同步，可用 svn2git unpause {id} 恢复"),
+                false => println!("{label} 已经处于暂停状态"),
+            }
+        }
+        Commands::Unpause { id } => {
+            let label = history
+                .records()
+                .get(id)
+                .ok_or_else(|| SyncError::App("索引超出范围".into()))?
+                .label();
+            match history.set_paused(id, false)? {
+                true => println!("已恢复 {label} 的自动同步"),
+                false => println!("{label} 没有被暂停"),
+            }
+        }
+        Commands::Resume { id, commit } => {
+            let mut config = history
+                .find_resumable(id)?
+                .to_sync_config()
+                .with_commands(commands);
+            let commit = apply_profile(profiles.as_ref(), &mut config, commit)?;
+            let interactor = Box::new(DefaultUserInteractor);
+            let git_operations = Box::new(config.create_git_operations());
+            let lock_file = sync_lock_path(Path::new(CONFIG_FILE), &config.git_dir);
+            let progress_file = sync_progress_path(Path::new(CONFIG_FILE), &config.git_dir);
+            let mut tool = SyncTool::new(config, history, interactor, git_operations)
+                .with_commit_args(commit)
+                .with_lock_file(Some(lock_file))
+                .with_progress_file(Some(progress_file))
+                .with_state_files(state_files(Path::new(CONFIG_FILE)))
+                .with_verbose(verbose);
+            tool.resume()?;
+        }
+        Commands::Rollback { id, yes, confirm } => {
+            let record = history.find_resumable(id)?;
+            let (config, label) = (
+                record.to_sync_config().with_commands(commands),
+                record.label(),
+            );
+            let interactor: Box<dyn UserInteractor> = if yes {
+                Box::new(NonInteractiveUserInteractor)
+            } else {
+                Box::new(DefaultUserInteractor)
+            };
+            if !confirm_destructive_with_interactor(
+                &format!("回滚 {label} 未完成的同步"),
+                &label,
+                confirm.as_deref(),
+                interactor.as_ref(),
+            ) {
+                return Err(SyncError::App("标签不匹配，已取消回滚".into()));
+            }
+            let git_operations = Box::new(config.create_git_operations());
+            let lock_file = sync_lock_path(Path::new(CONFIG_FILE), &config.git_dir);
+            let mut tool = SyncTool::new(config, history, interactor, git_operations)
+                .with_lock_file(Some(lock_file))
+                .with_verbose(verbose);
+            tool.rollback()?;
+        }
+        Commands::Retry { rev, id, commit } => {
+            let mut config = history
+                .find_failed(&rev, id)?
+                .to_sync_config()
+                .with_commands(commands);
+            let commit = apply_profile(profiles.as_ref(), &mut config, commit)?;
             let interactor = Box::new(DefaultUserInteractor);
             let git_operations = Box::new(config.create_git_operations());
-            let tool = SyncTool::new(config, history, interactor, git_operations);
-            tool.run_with_options(&SyncRunOptions { dry_run, limit })?;
+            let lock_file = sync_lock_path(Path::new(CONFIG_FILE), &config.git_dir);
+            let progress_file = sync_progress_path(Path::new(CONFIG_FILE), &config.git_dir);
+            let mut tool = SyncTool::new(config, history, interactor, git_operations)
+                .with_commit_args(commit)
+                .with_lock_file(Some(lock_file))
+                .with_progress_file(Some(progress_file))
+                .with_state_files(state_files(Path::new(CONFIG_FILE)))
+                .with_verbose(verbose);
+            tool.retry(&rev)?;
+        }
+        Commands::SyncAll {
+            config,
+            yes,
+            jobs,
+            worker,
+            commit,
+        } => {
+            if cli.ephemeral {
+                return Err(SyncError::App(
+                    "sync-all 需要逐个保存同步进度，不能与 --ephemeral 同时使用".into(),
+                ));
+            }
+            drop(history);
+            let config_file = config.unwrap_or_else(|| CONFIG_FILE.into());
+            if let Some(worker) = worker {
+                let configs = load_pairs(&config_file, &commands, |record| {
+                    record.git_path() == &worker
+                })?;
+                for config in configs {
+                    sync_pair(&config_file, config, &commit, yes, false, verbose)?;
+                }
+                return Ok(());
+            }
+
+            let configs = load_pairs(&config_file, &commands, |_| true)?;
+            let span = trace_span("svn2git.sync-all");
+            let summary = match jobs.filter(|jobs| jobs.get() > 1) {
+                Some(jobs) => {
+                    if !yes {
+                        return Err(SyncError::App(
+                            "并行同步时无法逐个确认，--jobs 大于 1 时需要同时传入 --yes".into(),
+                        ));
+                    }
+                    // 子进程使用相同的参数，只同步指定的同步对；子进程的链路挂在本次 sync-all 下
+                    let exe = std::env::current_exe()?;
+                    let parent = traceparent();
+                    sync_pairs_parallel(configs, jobs, |config| {
+                        let mut command = Command::new(&exe);
+                        command
+                            .args(std::env::args_os().skip(1))
+                            .arg("--worker")
+                            .arg(&config.git_dir);
+                        if let Some(parent) = &parent {
+                            command.env("TRACEPARENT", parent);
+                        }
+                        command
+                    })
+                }
+                None => sync_pairs(configs, |config| {
+                    sync_pair(&config_file, config, &commit, yes, false, verbose)
+                }),
+            };
+            drop(span);
+            println!("{summary}");
+            if summary.failed() > 0 {
+                return Err(SyncError::App(format!(
+                    "{} 个同步对同步失败",
+                    summary.failed()
+                )));
+            }
+        }
+        Commands::Watch {
+            svn_dir,
+            git_dir,
+            interval,
+            commit,
+        } => {
+            if cli.ephemeral {
+                return Err(SyncError::App(
+                    "watch 需要在两轮之间保存同步进度，不能与 --ephemeral 同时使用".into(),
+                ));
+            }
+            // 指定的同步对先加入历史记录，之后每轮都从配置文件读取
+            let pair = match (svn_dir, git_dir) {
+                (Some(svn_dir), Some(git_dir)) => {
+                    let config = select_or_create_config_with_interactor(
+                        Some(svn_dir),
+                        Some(git_dir),
+                        &mut history,
+                        &NonInteractiveUserInteractor,
+                    )?;
+                    Some((config.svn_dir, config.git_dir))
+                }
+                _ => None,
+            };
+
+            let stop = StopSignal::new();
+            let handler_stop = stop.clone();
+            ctrlc::set_handler(move || {
+                if handler_stop.is_stopped() {
+                    eprintln!("再次收到中断信号，立即退出，可用 resume 恢复未完成的同步");
+                    std::process::exit(130);
+                }
+                eprintln!("收到中断信号，本轮同步结束后退出（再次中断立即退出）");
+                handler_stop.stop();
+            })
+            .map_err(|e| SyncError::App(format!("注册中断信号处理失败：{e}")))?;
+            reload_on_hangup(&stop)?;
+
+            let interval = Duration::from_secs(interval);
+            let mut schedule = WatchSchedule::new(interval);
+            watch(interval, &stop, || {
+                // 每轮重新读取配置文件，使用上一轮保存的同步进度，并获取新增的同步对和修改的间隔
+                let config_file = Path::new(CONFIG_FILE);
+                let added = history.reload()?;
+                if added > 0 {
+                    println!("已重新加载配置，新增 {added} 个同步对");
+                }
+                let watched = |record: &HistoryRecord| {
+                    pair.as_ref()
+                        .is_none_or(|(svn_dir, git_dir)| record.path_eq(svn_dir, git_dir))
+                };
+                let started = Instant::now();
+                let configs: Vec<_> = select_pairs(&history, config_file, &commands, watched)?
+                    .into_iter()
+                    .filter(|config| {
+                        history
+                            .find_record(&config.svn_dir, &config.git_dir)
+                            .is_none_or(|record| schedule.is_due(record, started))
+                    })
+                    .collect();
+                for config in &configs {
+                    schedule.mark_run(config.svn_dir.clone(), config.git_dir.clone(), started);
+                }
+                if configs.is_empty() {
+                    println!("没有到达同步间隔的同步对");
+                } else {
+                    let summary = sync_pairs(configs, |config| {
+                        sync_pair(config_file, config, &commit, true, true, verbose)
+                    });
+                    // 持续运行时每轮导出一次
+                    flush_traces();
+                    if summary.failed() > 0 {
+                        eprintln!("本轮同步失败：\n{summary}");
+                    }
+                }
+                let records = history
+                    .records()
+                    .iter()
+                    .filter(|record| watched(record) && !record.is_paused());
+                Ok(schedule.next_wait(records, Instant::now()))
+            });
+        }
+        Commands::Doctor { config } => {
+            let report = run_doctor(&config.unwrap_or_else(|| CONFIG_FILE.into()));
+            println!("{report}");
+            if report.count(CheckLevel::Error) > 0 {
+                return Err(SyncError::App("环境检查未通过".into()));
+            }
+        }
+        Commands::Status { id } => {
+            let record = history
+                .records()
+                .get(id)
+                .ok_or_else(|| SyncError::App("索引超出范围".into()))?;
+            let config = record.to_sync_config().with_commands(commands);
+            let status = pair_status(
+                record,
+                &config.create_svn_operations(),
+                &config.create_git_operations(),
+            )?;
+            println!("{status}");
+        }
+        Commands::Audit {
+            id,
+            sample,
+            include,
+            exclude,
+            jobs,
+        } => {
+            let record = history
+                .records()
+                .get(id)
+                .ok_or_else(|| SyncError::App("索引超出范围".into()))?;
+            let config = record.to_sync_config().with_commands(commands);
+            let report = audit_record(
+                record,
+                &config.create_svn_operations(),
+                &config.create_git_operations(),
+                &AuditOptions {
+                    sample,
+                    filter: PathFilter::new(include, exclude),
+                    jobs,
+                },
+            )?;
+            report.print();
+            if !report.passed() {
+                return Err(SyncError::App(format!(
+                    "审计未通过：{} 个版本不一致",
+                    report.failed_count()
+                )));
+            }
+        }
+        Commands::Adopt { git_dir, svn_dir } => {
+            let git = SyncConfig::new(svn_dir.clone(), git_dir.clone())
+                .with_commands(commands)
+                .create_git_operations();
+            let summary = adopt_repository(&mut history, &svn_dir, &git_dir, &git)?;
+            if let Some(marker) = &summary.marker {
+                println!(
+                    "找到镜像标记：原同步对编号 {}，由 svn2git {} 写入",
+                    marker.pair_id, marker.tool_version
+                );
+            }
+            println!(
+                "已接管 {}：找到 {} 个 SVN 版本的提交，同步进度 r{}",
+                git_dir.display(),
+                summary.mapped,
+                summary.last_synced_rev.unwrap_or_default()
+            );
         }
+        Commands::ImportDump {
+            file,
+            git_dir,
+            root,
+        } => {
+            let options = DumpImportOptions {
+                root,
+                log_encoding: commands.log_encoding,
+            };
+            let git = SyncConfig::new(PathBuf::new(), git_dir.clone())
+                .with_commands(commands)
+                .create_git_operations();
+            let summary = match file.as_os_str() == "-" {
+                true => import_dump(std::io::stdin().lock(), &git_dir, &git, &options)?,
+                false => {
+                    let dump = BufReader::new(File::open(&file)?);
+                    import_dump(dump, &git_dir, &git, &options)?
+                }
+            };
+            println!(
+                "导入完成：读取 {} 个版本，生成 {} 个提交",
+                summary.revisions, summary.commits
+            );
+        }
+        Commands::Service { command } => match command {
+            ServiceCommands::Install { name, options } => {
+                let spec = service_spec(&name, options)?;
+                let path = install_service(&spec)?;
+                if cfg!(windows) {
+                    println!("已注册 Windows 服务：{name}");
+                    println!("执行以下命令启动服务：");
+                    println!("  sc.exe start {name}");
+                } else {
+                    println!("已写入服务文件：{}", path.display());
+                    println!("执行以下命令启动服务：");
+                    println!("  systemctl --user daemon-reload");
+                    println!("  systemctl --user enable --now {name}");
+                }
+            }
+            ServiceCommands::Uninstall { name } => {
+                let spec =
+                    ServiceSpec::new(&name, std::env::current_exe()?, std::env::current_dir()?);
+                let path = uninstall_service(&spec)?;
+                if cfg!(windows) {
+                    println!("已删除 Windows 服务：{name}");
+                } else {
+                    println!("已删除服务文件：{}", path.display());
+                    println!("如服务仍在运行，请执行：systemctl --user disable --now {name}");
+                }
+            }
+            ServiceCommands::Run {
+                options,
+                windows_service,
+            } => {
+                if cli.ephemeral {
+                    return Err(SyncError::App(
+                        "service run 需要在两轮之间保存同步进度，不能与 --ephemeral 同时使用"
+                            .into(),
+                    ));
+                }
+                let name = windows_service.as_deref().unwrap_or("svn2git");
+                let spec = service_spec(name, options)?;
+                #[cfg(windows)]
+                if windows_service.is_some() {
+                    return run_windows_service(spec);
+                }
+                // 中断信号同时发给 watch 子进程，由它在本轮结束后退出；这里只停止重启
+                let stop = StopSignal::new();
+                let handler_stop = stop.clone();
+                ctrlc::set_handler(move || handler_stop.stop())
+                    .map_err(|e| SyncError::App(format!("注册中断信号处理失败：{e}")))?;
+                println!("以 watch 模式运行，日志写入 {}", spec.log_path.display());
+                run_service(&spec, &stop)?;
+            }
+        },
+        Commands::LintRules { profile, commit } => {
+            let commit = match profile {
+                Some(name) => ConversionProfile::load(profiles.as_ref(), &name)?
+                    .apply(&mut SyncConfig::new("".into(), "".into()), commit)?,
+                None => commit,
+            };
+            let issues = lint_commit_args(&commit);
+            for issue in &issues {
+                println!("{issue}");
+            }
+            let errors = issues
+                .iter()
+                .filter(|issue| issue.level == LintLevel::Error)
+                .count();
+            if errors > 0 {
+                return Err(SyncError::App(format!("转换规则中有 {errors} 个错误")));
+            }
+            if issues.is_empty() {
+                println!("未发现问题");
+            }
+        }
+        Commands::PreviewFilters { id, all, commit } => {
+            let record = history
+                .records()
+                .get(id)
+                .ok_or_else(|| SyncError::App("索引超出范围".into()))?;
+            let mut config = record.to_sync_config().with_commands(commands);
+            let commit = apply_profile(profiles.as_ref(), &mut config, commit)?;
+            let query = SvnLogQuery {
+                after_rev: match all {
+                    true => Some("0".into()),
+                    false => record.last_synced_rev().map(str::to_string),
+                },
+                ..Default::default()
+            };
+            let logs = config
+                .create_svn_operations()
+                .get_logs(&config.svn_dir, &query)?;
+            println!("{}", preview_filters(&logs, &commit));
+        }
+        Commands::Profile { command } => match command {
+            ProfileCommands::Save { name, args } => {
+                ConversionProfile::from_args(args)?.save(profiles.as_ref(), &name)?;
+                println!("已保存配置方案 {name}");
+            }
+            ProfileCommands::List => {
+                let names = ConversionProfile::list(profiles.as_ref())?;
+                if names.is_empty() {
+                    println!("没有配置方案");
+                }
+                for name in names {
+                    println!("{name}");
+                }
+            }
+            ProfileCommands::Show { name } => {
+                let profile = ConversionProfile::load(profiles.as_ref(), &name)?;
+                println!("{}", profile.args().join(" "));
+            }
+            ProfileCommands::Delete { name } => {
+                ConversionProfile::delete(profiles.as_ref(), &name)?;
+                println!("已删除配置方案 {name}");
+            }
+        },
         Commands::History { command } => match command {
             HistoryCommands::List => history.list(),
-            HistoryCommands::Delete { id } => history.remove_record(id)?,
+            HistoryCommands::Delete { id, yes, confirm } => {
+                let label = history
+                    .records()
+                    .get(id)
+                    .ok_or_else(|| SyncError::App("索引超出范围".into()))?
+                    .label();
+                let interactor: Box<dyn UserInteractor> = if yes {
+                    Box::new(NonInteractiveUserInteractor)
+                } else {
+                    Box::new(DefaultUserInteractor)
+                };
+                if !confirm_destructive_with_interactor(
+                    &format!("删除历史记录 {id}（{label}）"),
+                    &label,
+                    confirm.as_deref(),
+                    interactor.as_ref(),
+                ) {
+                    return Err(SyncError::App("标签不匹配，已取消删除".into()));
+                }
+                history.remove_record(id)?
+            }
+            HistoryCommands::Rebind { id } => match history.clear_identity(id)? {
+                true => println!("已解除绑定，下次同步时绑定当前的 SVN 和 Git 仓库"),
+                false => println!("该同步对还没有绑定仓库"),
+            },
+            HistoryCommands::Env { id, set, unset } => {
+                for assignment in &set {
+                    let (key, value) = parse_env_assignment(assignment).ok_or_else(|| {
+                        SyncError::App(format!("无效的环境变量 '{assignment}'，应为 KEY=VALUE"))
+                    })?;
+                    history.set_env(id, &key, &value)?;
+                }
+                for key in &unset {
+                    if !history.unset_env(id, key)? {
+                        println!("环境变量 {key} 未配置");
+                    }
+                }
+                history.list_env(id)?;
+            }
+            HistoryCommands::Policy { id, set } => {
+                for assignment in &set {
+                    let (name, value) = assignment.split_once('=').ok_or_else(|| {
+                        SyncError::App(format!("无效的策略 '{assignment}'，应为 NAME=VALUE"))
+                    })?;
+                    history.set_policy(id, name.trim(), value.trim())?;
+                }
+                history.list_policies(id)?;
+            }
+            HistoryCommands::Rewrite { id, add, remove } => {
+                if let Some(position) = remove {
+                    history.remove_message_rewrite(id, position)?;
+                }
+                for rule in add {
+                    history.add_message_rewrite(id, rule)?;
+                }
+                history.list_message_rewrites(id)?;
+            }
+            HistoryCommands::Profile { id, name, unset } => {
+                let record = history
+                    .records()
+                    .get(id)
+                    .ok_or_else(|| SyncError::App("索引超出范围".into()))?;
+                let (svn_path, git_path) = (record.svn_path().clone(), record.git_path().clone());
+                if unset || name.is_some() {
+                    if let Some(name) = &name {
+                        ConversionProfile::load(profiles.as_ref(), name)?;
+                    }
+                    history.set_profile(&svn_path, &git_path, name);
+                    history.save()?;
+                }
+                match history
+                    .find_record(&svn_path, &git_path)
+                    .and_then(|record| record.profile())
+                {
+                    Some(name) => println!("配置方案：{name}"),
+                    None => println!("未使用配置方案"),
+                }
+            }
+            HistoryCommands::Prefix { id, prefix, reset } => {
+                let record = history
+                    .records()
+                    .get(id)
+                    .ok_or_else(|| SyncError::App("索引超出范围".into()))?;
+                let (svn_path, git_path) = (record.svn_path().clone(), record.git_path().clone());
+                if reset || prefix.is_some() {
+                    history.set_message_prefix(&svn_path, &git_path, prefix);
+                    history.save()?;
+                }
+                if let Some(record) = history.find_record(&svn_path, &git_path) {
+                    println!("提交标题前缀：{:?}", record.message_prefix());
+                }
+            }
+            HistoryCommands::Schedule {
+                id,
+                interval,
+                reset,
+            } => {
+                let record = history
+                    .records()
+                    .get(id)
+                    .ok_or_else(|| SyncError::App("索引超出范围".into()))?;
+                let (svn_path, git_path) = (record.svn_path().clone(), record.git_path().clone());
+                if reset || interval.is_some() {
+                    history.set_watch_interval(&svn_path, &git_path, interval);
+                    history.save()?;
+                }
+                if let Some(record) = history.find_record(&svn_path, &git_path) {
+                    match record.watch_interval() {
+                        Some(interval) => println!("监视间隔：{interval} 秒"),
+                        None => println!("监视间隔：使用 watch 的 --interval"),
+                    }
+                }
+            }
+            HistoryCommands::Map { id, rev, marks } => match marks {
+                Some(path) => {
+                    let record = history
+                        .records()
+                        .get(id)
+                        .ok_or_else(|| SyncError::App("索引超出范围".into()))?;
+                    std::fs::write(&path, record.revision_map().to_marks())?;
+                    println!(
+                        "已导出 {} 条版本映射到 {}",
+                        record.revision_map().len(),
+                        path.display()
+                    );
+                }
+                None => history.list_map(id, rev.as_deref())?,
+            },
         },
     }
 
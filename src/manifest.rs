@@ -0,0 +1,108 @@
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::error::Result;
+
+/// 写入某个版本的校验清单
+///
+/// 清单为 `sha256sum` 兼容格式（`<SHA-256>  <相对路径>`），文件名为
+/// `r<版本>-<Git提交>.sha256`，可在 Git 工作目录中用 `sha256sum -c` 复核。
+///
+/// # 参数
+///
+/// * `dir`: 清单输出目录
+/// * `rev`: SVN 版本
+/// * `git_hash`: 对应的 Git 提交
+/// * `root`: Git 工作目录
+/// * `files`: 提交中的文件（相对 `root`，`/` 分隔）
+///
+/// # 返回
+///
+/// 清单文件路径
+pub fn write_manifest<'a>(
+    dir: &Path,
+    rev: &str,
+    git_hash: &str,
+    root: &Path,
+    files: impl IntoIterator<Item = &'a String>,
+) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+
+    let mut content = String::new();
+    for file in files {
+        let hash = sha256_file(&root.join(file))?;
+        content.push_str(&format!("{hash}  {file}\n"));
+    }
+
+    let path = dir.join(format!("r{rev}-{git_hash}.sha256"));
+    fs::write(&path, content)?;
+    Ok(path)
+}
+
+/// 计算文件的 SHA-256
+///
+/// # 参数
+///
+/// * `path`: 文件路径
+///
+/// # 返回
+///
+/// 小写十六进制摘要
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut HashWriter(&mut hasher))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 将写入的数据送入摘要计算
+struct HashWriter<'a>(&'a mut Sha256);
+
+impl Write for HashWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.txt");
+        fs::write(&file, "abc").unwrap();
+
+        assert_eq!(
+            sha256_file(&file).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_write_manifest() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("src")).unwrap();
+        fs::write(root.path().join("src/main.rs"), "abc").unwrap();
+        let out = tempfile::tempdir().unwrap();
+
+        let files = vec!["src/main.rs".to_string()];
+        let path = write_manifest(out.path(), "7", "deadbeef", root.path(), &files).unwrap();
+
+        assert_eq!(path.file_name().unwrap(), "r7-deadbeef.sha256");
+        assert_eq!(
+            fs::read_to_string(path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  src/main.rs\n"
+        );
+    }
+}
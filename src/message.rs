@@ -0,0 +1,366 @@
+//! Git 提交说明的整理
+
+use std::{fmt::Display, str::FromStr};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, SyncError};
+
+/// 命令行同步时 SVN 提交说明默认最多的字符数
+pub const DEFAULT_MAX_MESSAGE_CHARS: usize = 64 * 1024;
+
+/// 命令行同步时 SVN 作者默认最多的字符数
+pub const DEFAULT_MAX_AUTHOR_CHARS: usize = 256;
+
+/// 同步对没有设置时 Git 提交标题的默认前缀
+pub const DEFAULT_MESSAGE_PREFIX: &str = "SVN: ";
+
+/// 提交说明的长度限制
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MessageLimits {
+    /// 标题行最多字符数，超出部分移到正文开头
+    pub subject_width: Option<usize>,
+    /// 正文每行最多字符数，超出时在空白处换行
+    pub body_width: Option<usize>,
+    /// SVN 提交说明最多字符数，超出部分截断
+    pub max_message_chars: Option<usize>,
+    /// SVN 作者最多字符数，超出部分截断
+    pub max_author_chars: Option<usize>,
+}
+
+/// 截断超长的字段
+///
+/// 超过 `max` 个字符时保留前 `max` 个字符并追加省略号，
+/// 避免异常的 SVN 数据（如几 MB 的提交说明）导致提交失败或产生巨大的 Git 对象
+///
+/// # 参数
+///
+/// * `text`: 字段内容
+/// * `max`: 最多字符数
+///
+/// # 返回
+///
+/// 超长时返回截断后的内容，否则返回 `None`
+pub fn truncate_field(text: &str, max: usize) -> Option<String> {
+    let (cut, _) = text.char_indices().nth(max)?;
+    Some(format!("{}…", &text[..cut]))
+}
+
+/// 移除控制字符
+///
+/// 换行符统一为 `\n`，保留制表符，其余控制字符（如 `\0`、`\x1b`）直接删除
+pub fn strip_control_chars(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .chars()
+        .filter(|c| !c.is_control() || matches!(c, '\n' | '\t'))
+        .collect()
+}
+
+/// 整理提交说明
+///
+/// 移除控制字符和行尾空白，标题行超长时在空白处截断并把剩余部分移到正文，
+/// 标题与正文之间保留一个空行，正文按宽度换行。
+/// 标题行没有空白时按字符截断；正文中没有空白的超长单词（如 URL、连续的中文）不拆分。
+///
+/// # 参数
+///
+/// * `message`: 提交说明
+/// * `limits`: 长度限制
+pub fn format_commit_message(message: &str, limits: &MessageLimits) -> String {
+    let cleaned = strip_control_chars(message);
+    let mut lines = cleaned.lines().map(str::trim_end);
+    let first = lines.next().unwrap_or_default();
+    let mut body: Vec<String> = lines.map(str::to_string).collect();
+    while body.first().is_some_and(|line| line.is_empty()) {
+        body.remove(0);
+    }
+
+    let (subject, overflow) = match limits.subject_width {
+        Some(width) => split_subject(first, width),
+        None => (first.to_string(), None),
+    };
+    if let Some(overflow) = overflow {
+        if body.is_empty() {
+            body.push(overflow);
+        } else {
+            body.insert(0, overflow);
+            body.insert(1, String::new());
+        }
+    }
+
+    let mut result = subject;
+    if !body.is_empty() {
+        result.push_str("\n\n");
+        let body: Vec<String> = match limits.body_width {
+            Some(width) => body
+                .iter()
+                .flat_map(|line| wrap_line(line, width))
+                .collect(),
+            None => body,
+        };
+        result.push_str(body.join("\n").trim_end());
+    }
+    result
+}
+
+/// SVN 提交说明的改写规则
+///
+/// 提交前按正则表达式替换 SVN 提交说明中的所有匹配，例如把 `#1234` 改为问题单的完整链接，
+/// 或去掉工单前缀。替换内容可以用 `$1`、`${name}` 引用捕获组，需要跨行匹配时在表达式中加 `(?m)` 等标志。
+/// 以 `PATTERN=>REPLACEMENT` 的形式输入，保存到同步对的历史记录中
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "RewriteRuleDef", into = "RewriteRuleDef")]
+pub struct RewriteRule {
+    pattern: Regex,
+    replacement: String,
+}
+
+/// 改写规则在配置文件中的格式
+#[derive(Serialize, Deserialize)]
+struct RewriteRuleDef {
+    pattern: String,
+    replacement: String,
+}
+
+impl RewriteRule {
+    /// 创建改写规则
+    ///
+    /// # 参数
+    ///
+    /// * `pattern`: 正则表达式
+    /// * `replacement`: 替换内容
+    ///
+    /// # 返回
+    ///
+    /// * `Err(SyncError)` - 正则表达式无效
+    pub fn new(pattern: &str, replacement: &str) -> Result<Self> {
+        let pattern = Regex::new(pattern)
+            .map_err(|e| SyncError::App(format!("改写规则的正则表达式无效：{e}")))?;
+        Ok(Self {
+            pattern,
+            replacement: replacement.to_string(),
+        })
+    }
+
+    /// 正则表达式
+    pub fn pattern(&self) -> &str {
+        self.pattern.as_str()
+    }
+
+    /// 替换内容
+    pub fn replacement(&self) -> &str {
+        &self.replacement
+    }
+
+    /// 替换提交说明中的所有匹配
+    pub fn apply(&self, message: &str) -> String {
+        self.pattern
+            .replace_all(message, self.replacement.as_str())
+            .into_owned()
+    }
+}
+
+impl PartialEq for RewriteRule {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern() == other.pattern() && self.replacement == other.replacement
+    }
+}
+
+impl FromStr for RewriteRule {
+    type Err = SyncError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (pattern, replacement) = s.split_once("=>").ok_or_else(|| {
+            SyncError::App(format!("无效的改写规则 '{s}'，应为 PATTERN=>REPLACEMENT"))
+        })?;
+        if pattern.is_empty() {
+            return Err(SyncError::App(format!("改写规则 '{s}' 缺少正则表达式")));
+        }
+        Self::new(pattern, replacement)
+    }
+}
+
+impl Display for RewriteRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}=>{}", self.pattern(), self.replacement)
+    }
+}
+
+impl TryFrom<RewriteRuleDef> for RewriteRule {
+    type Error = SyncError;
+
+    fn try_from(def: RewriteRuleDef) -> Result<Self> {
+        Self::new(&def.pattern, &def.replacement)
+    }
+}
+
+impl From<RewriteRule> for RewriteRuleDef {
+    fn from(rule: RewriteRule) -> Self {
+        Self {
+            pattern: rule.pattern().to_string(),
+            replacement: rule.replacement,
+        }
+    }
+}
+
+/// 按顺序应用改写规则，后面的规则作用于前面规则的结果
+///
+/// # 参数
+///
+/// * `message`: SVN 提交说明
+/// * `rules`: 改写规则
+pub fn rewrite_message(message: &str, rules: &[RewriteRule]) -> String {
+    rules
+        .iter()
+        .fold(message.to_string(), |message, rule| rule.apply(&message))
+}
+
+/// 在宽度以内的最后一个空白处拆分标题行
+fn split_subject(subject: &str, width: usize) -> (String, Option<String>) {
+    if subject.chars().count() <= width {
+        return (subject.to_string(), None);
+    }
+    let limit = subject
+        .char_indices()
+        .nth(width)
+        .map_or(subject.len(), |(i, _)| i);
+    let cut = if subject[limit..].starts_with(char::is_whitespace) {
+        limit
+    } else {
+        subject[..limit]
+            .rfind(char::is_whitespace)
+            .filter(|&i| i > 0)
+            .unwrap_or(limit)
+    };
+    let (head, tail) = subject.split_at(cut);
+    (head.trim_end().to_string(), Some(tail.trim().to_string()))
+}
+
+/// 按宽度在空白处换行，保留行首缩进
+fn wrap_line(line: &str, width: usize) -> Vec<String> {
+    if line.chars().count() <= width {
+        return vec![line.to_string()];
+    }
+    let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+    let mut lines = Vec::new();
+    let mut current = indent.clone();
+    for word in line.split_whitespace() {
+        let current_width = current.chars().count();
+        if current_width > indent.chars().count()
+            && current_width + 1 + word.chars().count() > width
+        {
+            lines.push(std::mem::replace(&mut current, indent.clone()));
+        }
+        if current.chars().count() > indent.chars().count() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    lines.push(current);
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_field() {
+        assert_eq!(truncate_field("abc", 3), None);
+        assert_eq!(truncate_field("abcd", 3).as_deref(), Some("abc…"));
+        assert_eq!(truncate_field("中文提交说明", 2).as_deref(), Some("中文…"));
+        assert_eq!(truncate_field("", 0), None);
+    }
+
+    #[test]
+    fn test_strip_control_chars() {
+        assert_eq!(
+            strip_control_chars("a\u{0}b\x1b[31mc\r\nd\re\tf"),
+            "ab[31mc\nd\ne\tf"
+        );
+    }
+
+    #[test]
+    fn test_format_commit_message_without_limits() {
+        let limits = MessageLimits::default();
+        assert_eq!(format_commit_message("修复bug", &limits), "修复bug");
+        assert_eq!(
+            format_commit_message("标题  \r\n\r\n\r\n正文\x07\n", &limits),
+            "标题\n\n正文"
+        );
+    }
+
+    #[test]
+    fn test_format_commit_message_should_move_long_subject_into_body() {
+        let limits = MessageLimits {
+            subject_width: Some(20),
+            body_width: Some(12),
+            ..Default::default()
+        };
+        assert_eq!(
+            format_commit_message("SVN: fix parser when input is empty", &limits),
+            "SVN: fix parser when\n\ninput is\nempty"
+        );
+        assert_eq!(
+            format_commit_message(
+                "SVN: fix parser when input is empty\nmore details here",
+                &limits
+            ),
+            "SVN: fix parser when\n\ninput is\nempty\n\nmore details\nhere"
+        );
+        // 没有空白时按字符截断
+        assert_eq!(
+            format_commit_message("修复了解析器在输入为空时崩溃的问题并补充测试用例", &limits),
+            "修复了解析器在输入为空时崩溃的问题并补充\n\n测试用例"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_message_should_apply_rules_in_order() {
+        let rules: Vec<RewriteRule> = [
+            r"^\[TICKET-\d+\]\s*=>",
+            r"#(\d+)=>https://issues.example.com/$1",
+        ]
+        .iter()
+        .map(|rule| rule.parse().unwrap())
+        .collect();
+        assert_eq!(
+            rewrite_message("[TICKET-7] fix #12 and #13", &rules),
+            "fix https://issues.example.com/12 and https://issues.example.com/13"
+        );
+        assert_eq!(rewrite_message("unchanged", &rules), "unchanged");
+        assert_eq!(
+            rules[1].to_string(),
+            r"#(\d+)=>https://issues.example.com/$1"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_rule_should_reject_invalid_input() {
+        assert!("no-separator".parse::<RewriteRule>().is_err());
+        assert!("=>x".parse::<RewriteRule>().is_err());
+        assert!("(unclosed=>x".parse::<RewriteRule>().is_err());
+        assert!(
+            serde_json::from_str::<RewriteRule>(r#"{"pattern":"(","replacement":""}"#).is_err()
+        );
+
+        let rule: RewriteRule = "a+=>b".parse().unwrap();
+        let json = serde_json::to_string(&rule).unwrap();
+        assert_eq!(json, r#"{"pattern":"a+","replacement":"b"}"#);
+        assert_eq!(serde_json::from_str::<RewriteRule>(&json).unwrap(), rule);
+    }
+
+    #[test]
+    fn test_wrap_line_should_keep_indent_and_long_words() {
+        assert_eq!(
+            wrap_line("  - one two three", 10),
+            vec!["  - one", "  two", "  three"]
+        );
+        assert_eq!(
+            wrap_line("https://example.com/very/long/url", 10),
+            vec!["https://example.com/very/long/url"]
+        );
+    }
+}
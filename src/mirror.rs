@@ -0,0 +1,209 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::error::{Result, SyncError};
+
+/// 复制时跳过的 SVN 管理目录
+const SVN_ADMIN_DIR: &str = ".svn";
+
+/// 目标目录中不会被删除的 Git 目录
+const GIT_DIR: &str = ".git";
+
+/// 目标根目录中由工具维护、不随 SVN 删除的文件
+const MANAGED_FILES: [&str; 2] = [".gitattributes", ".gitignore"];
+
+/// 一次镜像复制的结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MirrorStats {
+    /// 新增或内容变化而复制的文件数
+    pub copied: usize,
+    /// 源目录中已不存在而删除的文件数
+    pub removed: usize,
+}
+
+/// 把 SVN 工作副本镜像到独立的 Git 目录
+///
+/// 复制 `src` 下除 `.svn` 以外的所有文件（内容相同的文件不重写），
+/// 并删除 `dest` 中源目录已不存在、且已被 Git 跟踪的文件和随之变空的目录。
+/// 未跟踪和被忽略的文件、`.git` 目录以及根目录的 `.gitattributes`、`.gitignore` 保持不变，
+/// `keep` 中的文件（如放在 Git 目录中的配置文件、锁文件）既不覆盖也不删除。
+///
+/// # 参数
+///
+/// * `src`: SVN 工作副本目录
+/// * `dest`: Git 目录，不能与 `src` 互相包含
+/// * `tracked`: Git 已跟踪的文件（相对 `dest`）
+/// * `keep`: 不能改动的文件（相对 `dest`）
+///
+/// # 返回
+///
+/// 复制和删除的文件数
+pub fn mirror_tree(
+    src: &Path,
+    dest: &Path,
+    tracked: &BTreeSet<PathBuf>,
+    keep: &BTreeSet<PathBuf>,
+) -> Result<MirrorStats> {
+    if src.starts_with(dest) || dest.starts_with(src) {
+        return Err(SyncError::App(format!(
+            "复制策略要求 SVN 目录与 Git 目录互不包含：{} / {}",
+            src.display(),
+            dest.display()
+        )));
+    }
+
+    let mut stats = MirrorStats::default();
+    let mut files = BTreeSet::new();
+    collect_files(src, Path::new(""), SVN_ADMIN_DIR, &mut files)?;
+    for file in files.difference(keep) {
+        let (from, to) = (src.join(file), dest.join(file));
+        if to.is_file() && fs::read(&from)? == fs::read(&to)? {
+            continue;
+        }
+        if to.is_dir() {
+            fs::remove_dir_all(&to)?;
+        }
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&from, &to)?;
+        stats.copied += 1;
+    }
+
+    let mut existing = BTreeSet::new();
+    collect_files(dest, Path::new(""), GIT_DIR, &mut existing)?;
+    for file in existing.difference(&files) {
+        if !tracked.contains(file) || keep.contains(file) {
+            continue;
+        }
+        if file.parent() == Some(Path::new(""))
+            && MANAGED_FILES
+                .iter()
+                .any(|managed| file.as_os_str() == *managed)
+        {
+            continue;
+        }
+        fs::remove_file(dest.join(file))?;
+        stats.removed += 1;
+        remove_empty_parents(dest, file)?;
+    }
+
+    Ok(stats)
+}
+
+/// 递归收集目录下的文件（相对 `root`），跳过名为 `skip` 的目录
+fn collect_files(
+    root: &Path,
+    relative: &Path,
+    skip: &str,
+    files: &mut BTreeSet<PathBuf>,
+) -> Result<()> {
+    let dir = root.join(relative);
+    if !dir.exists() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_name() == skip {
+            continue;
+        }
+        let path = relative.join(entry.file_name());
+        if entry.path().is_dir() {
+            collect_files(root, &path, skip, files)?;
+        } else {
+            files.insert(path);
+        }
+    }
+    Ok(())
+}
+
+/// 删除文件后逐级删除变空的上级目录（不删除 `root`）
+fn remove_empty_parents(root: &Path, file: &Path) -> Result<()> {
+    let mut parent = file.parent();
+    while let Some(dir) = parent.filter(|dir| !dir.as_os_str().is_empty()) {
+        let path = root.join(dir);
+        if fs::read_dir(&path)?.next().is_some() {
+            break;
+        }
+        fs::remove_dir(&path)?;
+        parent = dir.parent();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_mirror_tree_should_copy_and_remove_files() {
+        let (svn, git) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        write(&svn.path().join("src/main.rs"), "fn main() {}");
+        write(&svn.path().join("README"), "readme");
+        write(&svn.path().join(".svn/wc.db"), "svn");
+        write(&git.path().join("README"), "readme");
+        write(&git.path().join("old/deep/gone.txt"), "gone");
+        write(&git.path().join(".git/HEAD"), "ref: refs/heads/master");
+        write(&git.path().join(".gitattributes"), "*.psd lockable");
+        let tracked = BTreeSet::from(["README".into(), "old/deep/gone.txt".into()]);
+
+        let stats = mirror_tree(svn.path(), git.path(), &tracked, &BTreeSet::new()).unwrap();
+        assert_eq!(
+            stats,
+            MirrorStats {
+                copied: 1,
+                removed: 1
+            }
+        );
+        assert_eq!(
+            fs::read_to_string(git.path().join("src/main.rs")).unwrap(),
+            "fn main() {}"
+        );
+        assert!(!git.path().join(".svn").exists());
+        assert!(!git.path().join("old").exists());
+        assert!(git.path().join(".git/HEAD").exists());
+        assert!(git.path().join(".gitattributes").exists());
+
+        assert_eq!(
+            mirror_tree(svn.path(), git.path(), &tracked, &BTreeSet::new()).unwrap(),
+            MirrorStats::default()
+        );
+    }
+
+    #[test]
+    fn test_mirror_tree_should_keep_untracked_and_state_files() {
+        let (svn, git) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        write(&svn.path().join("config.json"), "from svn");
+        write(&git.path().join("config.json"), "state");
+        write(&git.path().join("svn2git-a.lock"), "123");
+        write(&git.path().join("build/out.o"), "ignored");
+        let tracked = BTreeSet::from(["svn2git-a.lock".into()]);
+        let keep = BTreeSet::from(["config.json".into(), "svn2git-a.lock".into()]);
+
+        let stats = mirror_tree(svn.path(), git.path(), &tracked, &keep).unwrap();
+        assert_eq!(stats, MirrorStats::default());
+        assert_eq!(
+            fs::read_to_string(git.path().join("config.json")).unwrap(),
+            "state"
+        );
+        assert!(git.path().join("svn2git-a.lock").exists());
+        assert!(git.path().join("build/out.o").exists());
+    }
+
+    #[test]
+    fn test_mirror_tree_should_reject_nested_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let svn = dir.path().join("wc");
+        let none = BTreeSet::new();
+        assert!(mirror_tree(&svn, dir.path(), &none, &none).is_err());
+        assert!(mirror_tree(dir.path(), dir.path(), &none, &none).is_err());
+    }
+}
@@ -0,0 +1,252 @@
+//! 同步通知：同步开始、完成和失败时通知外部系统
+
+use std::{
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+    str::FromStr,
+    time::Duration,
+};
+
+use serde_json::json;
+
+use crate::{
+    config::SyncConfig,
+    error::{Result, SyncError},
+};
+
+/// 一次同步的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncReport {
+    /// SVN 工作副本目录
+    pub svn_dir: PathBuf,
+    /// Git 仓库目录
+    pub git_dir: PathBuf,
+    /// 本次处理的 SVN 版本数
+    pub revisions: usize,
+    /// 用时
+    pub duration: Duration,
+}
+
+/// 同步通知
+///
+/// 通过 [`SyncTool::with_notifier`](crate::SyncTool::with_notifier) 注册，
+/// 通知失败只输出警告，不影响同步结果
+pub trait Notifier {
+    /// 同步开始
+    ///
+    /// # 参数
+    ///
+    /// * `config`: 同步配置
+    fn notify_started(&self, config: &SyncConfig) -> Result<()>;
+
+    /// 同步完成
+    ///
+    /// # 参数
+    ///
+    /// * `report`: 同步结果
+    fn notify_finished(&self, report: &SyncReport) -> Result<()>;
+
+    /// 同步失败
+    ///
+    /// # 参数
+    ///
+    /// * `config`: 同步配置
+    /// * `error`: 失败原因
+    fn notify_failed(&self, config: &SyncConfig, error: &SyncError) -> Result<()>;
+}
+
+/// 输出到标准输出的通知
+#[derive(Debug, Clone, Default)]
+pub struct StdoutNotifier;
+
+impl Notifier for StdoutNotifier {
+    fn notify_started(&self, config: &SyncConfig) -> Result<()> {
+        println!("[通知] 开始同步 {}", config.git_dir.display());
+        Ok(())
+    }
+
+    fn notify_finished(&self, report: &SyncReport) -> Result<()> {
+        println!(
+            "[通知] {} 同步完成：{} 个版本，用时 {} 秒",
+            report.git_dir.display(),
+            report.revisions,
+            report.duration.as_secs()
+        );
+        Ok(())
+    }
+
+    fn notify_failed(&self, config: &SyncConfig, error: &SyncError) -> Result<()> {
+        println!("[通知] {} 同步失败：{error}", config.git_dir.display());
+        Ok(())
+    }
+}
+
+/// 以 JSON 格式 POST 到 URL 的通知
+///
+/// 通过 `curl` 发送，请求体包含 `event`（started、finished、failed）、`svn_dir`、`git_dir`，
+/// 完成时还有 `revisions` 和 `duration_seconds`，失败时还有 `error`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookNotifier {
+    url: String,
+}
+
+impl WebhookNotifier {
+    /// 创建通知
+    ///
+    /// # 参数
+    ///
+    /// * `url`: 接收通知的 URL
+    pub fn new(url: String) -> Self {
+        Self { url }
+    }
+
+    fn post(&self, payload: serde_json::Value) -> Result<()> {
+        post_json(&self.url, &payload)
+    }
+}
+
+/// 通过 `curl` 把 JSON POST 到 URL
+///
+/// # 参数
+///
+/// * `url`: 目标 URL
+/// * `payload`: 请求体
+pub(crate) fn post_json(url: &str, payload: &serde_json::Value) -> Result<()> {
+    let mut child = Command::new("curl")
+        .args(["-fsS", "--max-time", "30", "-X", "POST"])
+        .args([
+            "-H",
+            "Content-Type: application/json",
+            "--data-binary",
+            "@-",
+        ])
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SyncError::App(format!("无法执行 curl 发送请求：{e}")))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(payload.to_string().as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(SyncError::App(format!(
+            "POST 到 {url} 失败：{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify_started(&self, config: &SyncConfig) -> Result<()> {
+        self.post(webhook_payload("started", config, json!({})))
+    }
+
+    fn notify_finished(&self, report: &SyncReport) -> Result<()> {
+        self.post(json!({
+            "event": "finished",
+            "svn_dir": report.svn_dir,
+            "git_dir": report.git_dir,
+            "revisions": report.revisions,
+            "duration_seconds": report.duration.as_secs(),
+        }))
+    }
+
+    fn notify_failed(&self, config: &SyncConfig, error: &SyncError) -> Result<()> {
+        self.post(webhook_payload(
+            "failed",
+            config,
+            json!({ "error": error.to_string() }),
+        ))
+    }
+}
+
+/// 通知的请求体
+fn webhook_payload(
+    event: &str,
+    config: &SyncConfig,
+    extra: serde_json::Value,
+) -> serde_json::Value {
+    let mut payload = json!({
+        "event": event,
+        "svn_dir": config.svn_dir,
+        "git_dir": config.git_dir,
+    });
+    if let (Some(payload), Some(extra)) = (payload.as_object_mut(), extra.as_object()) {
+        payload.extend(extra.clone());
+    }
+    payload
+}
+
+/// 命令行指定的通知目标
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotifyTarget {
+    /// 输出到标准输出
+    Stdout,
+    /// POST 到 URL
+    Webhook(String),
+}
+
+impl FromStr for NotifyTarget {
+    type Err = SyncError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "stdout" => Ok(Self::Stdout),
+            url if url.starts_with("http://") || url.starts_with("https://") => {
+                Ok(Self::Webhook(url.to_string()))
+            }
+            _ => Err(SyncError::App(format!(
+                "通知目标 '{s}' 无效，应为 stdout 或 http(s):// 开头的 URL"
+            ))),
+        }
+    }
+}
+
+impl NotifyTarget {
+    /// 创建对应的通知
+    pub fn create_notifier(&self) -> Box<dyn Notifier> {
+        match self {
+            Self::Stdout => Box::new(StdoutNotifier),
+            Self::Webhook(url) => Box::new(WebhookNotifier::new(url.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_notify_target() {
+        assert_eq!(
+            "stdout".parse::<NotifyTarget>().unwrap(),
+            NotifyTarget::Stdout
+        );
+        assert_eq!(
+            "https://hooks.example.com/svn"
+                .parse::<NotifyTarget>()
+                .unwrap(),
+            NotifyTarget::Webhook("https://hooks.example.com/svn".into())
+        );
+        assert!("email".parse::<NotifyTarget>().is_err());
+    }
+
+    #[test]
+    fn test_webhook_payload() {
+        let config = SyncConfig::new("d:/svn".into(), "d:/git".into());
+        let payload = webhook_payload("failed", &config, json!({ "error": "网络错误" }));
+        assert_eq!(
+            payload,
+            json!({
+                "event": "failed",
+                "svn_dir": "d:/svn",
+                "git_dir": "d:/git",
+                "error": "网络错误",
+            })
+        );
+    }
+}
@@ -0,0 +1,441 @@
+//! 外部命令环境变量和超时
+//!
+//! 每个同步对可以配置执行 svn/git 命令时额外设置的环境变量（代理、SVN_SSH、凭据助手等）；
+//! 超时等设置由 [`CommandSettings`] 随同步对传给 svn/git 操作实例
+
+use std::{
+    collections::BTreeMap,
+    io::{ErrorKind, Read},
+    process::{Child, ChildStdout, Command, Output, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use encoding_rs::Encoding;
+
+use super::log_encoding::encoding_for_label;
+use super::svn::SVN_CERT_FAILURES;
+use crate::error::{Result, SyncError};
+
+/// 等待有超时的子进程时检查是否结束的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// 执行外部命令时额外设置的环境变量
+pub type CommandEnv = BTreeMap<String, String>;
+
+/// 执行外部命令的设置
+///
+/// 每个同步对独立设置，由 [`SyncConfig`](crate::SyncConfig) 传给 svn/git 操作实例
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommandSettings {
+    /// svn 命令是否附加 `--non-interactive`
+    pub svn_non_interactive: bool,
+    /// svn 命令接受的服务器证书错误，非空时隐含非交互
+    pub trust_server_cert_failures: Vec<String>,
+    /// svn 命令的超时时间，`None` 表示不限制
+    pub svn_timeout: Option<Duration>,
+    /// git 命令的超时时间，`None` 表示不限制
+    pub git_timeout: Option<Duration>,
+    /// 旧提交说明的编码，`None` 表示都是 UTF-8
+    pub log_encoding: Option<&'static Encoding>,
+}
+
+impl CommandSettings {
+    /// 设置 svn 命令的非交互选项
+    ///
+    /// 开启后每个 svn 命令都附加 `--non-interactive`，需要输入密码或确认服务器证书时直接失败，
+    /// 不会在 CI 中挂起等待输入。`trust_cert_failures` 非空时同时附加
+    /// `--trust-server-cert-failures`，接受自签名等证书错误（需要 svn 1.9 及以上）
+    ///
+    /// # 参数
+    ///
+    /// * `non_interactive`: 是否附加 `--non-interactive`
+    /// * `trust_cert_failures`: 接受的证书错误，取值见 [`SVN_CERT_FAILURES`]，非空时隐含非交互
+    ///
+    /// # 返回
+    ///
+    /// * `Err(SyncError)` - 证书错误的取值无效
+    pub fn with_svn_non_interactive(
+        mut self,
+        non_interactive: bool,
+        trust_cert_failures: &[String],
+    ) -> Result<Self> {
+        if let Some(invalid) = trust_cert_failures
+            .iter()
+            .find(|failure| !SVN_CERT_FAILURES.contains(&failure.as_str()))
+        {
+            return Err(SyncError::App(format!(
+                "无效的证书错误类型 {invalid}，可选：{}",
+                SVN_CERT_FAILURES.join(", ")
+            )));
+        }
+        self.svn_non_interactive = non_interactive;
+        self.trust_server_cert_failures = trust_cert_failures.to_vec();
+        Ok(self)
+    }
+
+    /// 设置 svn 和 git 命令的超时时间
+    ///
+    /// 服务器无响应时 `svn update` 等命令可能永远不结束，超时后终止子进程并返回
+    /// [`SyncError::CommandTimeout`]
+    ///
+    /// # 参数
+    ///
+    /// * `svn_timeout`: svn 命令的超时时间，`None` 表示不限制
+    /// * `git_timeout`: git 命令的超时时间，`None` 表示不限制
+    pub fn with_timeouts(
+        mut self,
+        svn_timeout: Option<Duration>,
+        git_timeout: Option<Duration>,
+    ) -> Self {
+        self.svn_timeout = svn_timeout;
+        self.git_timeout = git_timeout;
+        self
+    }
+
+    /// 设置旧提交说明的编码，读取 SVN 日志和 dump 时按该编码转码
+    ///
+    /// # 参数
+    ///
+    /// * `label`: 编码名称，如 `gbk`、`gb18030`、`big5`、`shift_jis`，`None` 表示不转码
+    ///
+    /// # 返回
+    ///
+    /// * `Err(SyncError)` - 不认识的编码名称
+    pub fn with_log_encoding(mut self, label: Option<&str>) -> Result<Self> {
+        self.log_encoding = label.map(encoding_for_label).transpose()?;
+        Ok(self)
+    }
+}
+
+/// 创建带有额外环境变量的命令
+///
+/// # 参数
+///
+/// * `program`: 命令名称
+/// * `env`: 额外的环境变量
+pub(crate) fn command_with_env(program: &str, env: &CommandEnv) -> Command {
+    let mut cmd = Command::new(program);
+    cmd.envs(env);
+    cmd
+}
+
+/// 执行命令并收集输出，设置了超时时超时后终止子进程
+///
+/// 与 [`Command::output`] 相同，标准输入为空
+///
+/// # 参数
+///
+/// * `command`: 要执行的命令
+/// * `timeout`: 超时时间，`None` 表示不限制
+pub(crate) fn output_with_timeout(
+    command: &mut Command,
+    timeout: Option<Duration>,
+) -> Result<Output> {
+    if timeout.is_none() {
+        return command.output().map_err(|e| spawn_error(command, e));
+    }
+    let child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| spawn_error(command, e))?;
+    wait_child(command, child, timeout)
+}
+
+/// 标准输出由调用方逐步读取的子进程
+///
+/// 后台线程等待子进程结束并收集标准错误，设置了超时时超时后终止子进程
+pub(crate) struct StreamingChild {
+    waiter: thread::JoinHandle<Result<Output>>,
+}
+
+impl StreamingChild {
+    /// 启动命令，返回子进程的标准输出
+    ///
+    /// # 参数
+    ///
+    /// * `command`: 要执行的命令，标准输入为空
+    /// * `timeout`: 超时时间，`None` 表示不限制
+    pub(crate) fn spawn(
+        command: &mut Command,
+        timeout: Option<Duration>,
+    ) -> Result<(ChildStdout, Self)> {
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| spawn_error(command, e))?;
+        let stdout = child.stdout.take().expect("标准输出是管道");
+        let name = command_name(command);
+        let waiter = thread::spawn(move || wait_output(child, timeout, name));
+        Ok((stdout, Self { waiter }))
+    }
+
+    /// 等待子进程结束，返回退出状态和标准错误，标准输出为空
+    ///
+    /// 调用前应读完或关闭标准输出，否则子进程可能因管道写满而一直阻塞
+    pub(crate) fn wait(self) -> Result<Output> {
+        self.waiter
+            .join()
+            .unwrap_or_else(|_| Err(SyncError::App("等待子进程的线程异常退出".into())))
+    }
+}
+
+/// 启动命令失败时的错误
+///
+/// 命令不存在时返回 [`SyncError::CommandNotFound`]；工作目录不存在时同样是
+/// `NotFound`，这种情况保留原始的 IO 错误
+pub(crate) fn spawn_error(command: &Command, error: std::io::Error) -> SyncError {
+    let missing_dir = command.get_current_dir().is_some_and(|dir| !dir.is_dir());
+    if error.kind() == ErrorKind::NotFound && !missing_dir {
+        SyncError::CommandNotFound(command.get_program().to_string_lossy().into_owned())
+    } else {
+        SyncError::Io(error)
+    }
+}
+
+/// 命令以非零状态退出时的错误
+///
+/// `svn` 命令返回 [`SyncError::SvnCommand`]，其他命令返回 [`SyncError::GitCommand`]；
+/// 标准错误为空时（如 `git commit` 没有可提交的更改）使用标准输出
+///
+/// # 参数
+///
+/// * `action`: 失败的操作，用于错误信息
+/// * `command`: 执行的命令
+/// * `output`: 命令的输出
+pub(crate) fn command_error(action: &str, command: &Command, output: &Output) -> SyncError {
+    let args = command
+        .get_args()
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let stderr = match String::from_utf8_lossy(&output.stderr).trim() {
+        "" => String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        stderr => stderr.to_string(),
+    };
+    let exit_code = output.status.code();
+    match command.get_program().to_string_lossy().as_ref() {
+        "svn" => SyncError::SvnCommand {
+            action: action.to_string(),
+            args,
+            exit_code,
+            stderr,
+        },
+        program => SyncError::GitCommand {
+            action: action.to_string(),
+            program: program.to_string(),
+            args,
+            exit_code,
+            stderr,
+        },
+    }
+}
+
+/// 等待已启动的子进程结束并收集输出，设置了超时时超时后终止子进程
+///
+/// # 参数
+///
+/// * `command`: 启动子进程的命令，用于错误信息
+/// * `child`: 标准输出和标准错误为管道的子进程
+/// * `timeout`: 超时时间，`None` 表示不限制
+pub(crate) fn wait_child(
+    command: &Command,
+    child: Child,
+    timeout: Option<Duration>,
+) -> Result<Output> {
+    wait_output(child, timeout, command_name(command))
+}
+
+/// 命令名称和子命令，用于超时错误
+fn command_name(command: &Command) -> String {
+    let program = command.get_program().to_string_lossy();
+    match command.get_args().next() {
+        Some(subcommand) => format!("{program} {}", subcommand.to_string_lossy()),
+        None => program.into_owned(),
+    }
+}
+
+/// 等待子进程结束并收集输出，超时后终止子进程并返回 [`SyncError::CommandTimeout`]
+fn wait_output(mut child: Child, timeout: Option<Duration>, name: String) -> Result<Output> {
+    let Some(timeout) = timeout else {
+        return Ok(child.wait_with_output()?);
+    };
+    // 在后台读取输出，避免管道写满后子进程阻塞
+    let read = |pipe: Option<Box<dyn Read + Send>>| {
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        })
+    };
+    let stdout = read(child.stdout.take().map(|p| Box::new(p) as _));
+    let stderr = read(child.stderr.take().map(|p| Box::new(p) as _));
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(SyncError::CommandTimeout {
+                command: name,
+                timeout,
+            });
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// 解析 `KEY=VALUE` 形式的环境变量
+///
+/// # 返回
+///
+/// 格式无效（缺少 `=` 或变量名为空）时返回 `None`
+pub fn parse_env_assignment(assignment: &str) -> Option<(String, String)> {
+    let (key, value) = assignment.split_once('=')?;
+    let key = key.trim();
+    if key.is_empty() {
+        return None;
+    }
+    Some((key.to_string(), value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_with_env() {
+        let env = CommandEnv::from([("SVN_SSH".to_string(), "ssh -i key".to_string())]);
+        let cmd = command_with_env("svn", &env);
+        let envs: Vec<_> = cmd.get_envs().collect();
+        assert_eq!(
+            envs,
+            vec![(
+                std::ffi::OsStr::new("SVN_SSH"),
+                Some(std::ffi::OsStr::new("ssh -i key"))
+            )]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_with_timeout_should_kill_slow_command() {
+        let started = Instant::now();
+        let err = output_with_timeout(
+            Command::new("sleep").arg("5"),
+            Some(Duration::from_millis(100)),
+        )
+        .unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(3));
+        assert!(
+            matches!(&err, SyncError::CommandTimeout { command, .. } if command == "sleep 5"),
+            "{err}"
+        );
+
+        let output = output_with_timeout(
+            Command::new("sh").args(["-c", "echo ok; echo err >&2"]),
+            Some(Duration::from_secs(10)),
+        )
+        .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"ok\n");
+        assert_eq!(output.stderr, b"err\n");
+    }
+
+    #[test]
+    fn test_parse_env_assignment() {
+        assert_eq!(
+            parse_env_assignment("HTTPS_PROXY=http://proxy:8080"),
+            Some(("HTTPS_PROXY".into(), "http://proxy:8080".into()))
+        );
+        assert_eq!(
+            parse_env_assignment("EMPTY="),
+            Some(("EMPTY".into(), "".into()))
+        );
+        assert_eq!(parse_env_assignment("NO_VALUE"), None);
+        assert_eq!(parse_env_assignment("=value"), None);
+    }
+
+    #[test]
+    fn test_command_error_should_capture_program_args_and_stderr() {
+        let mut command = Command::new("svn");
+        command.args(["update", "-r", "5"]);
+        let output = Output {
+            status: exit_status(1),
+            stdout: Vec::new(),
+            stderr: "svn: E170001: Authentication failed\n".into(),
+        };
+        let err = command_error("svn 更新到 5", &command, &output);
+        assert!(matches!(
+            &err,
+            SyncError::SvnCommand { args, exit_code: Some(1), stderr, .. }
+                if args == "update -r 5" && stderr == "svn: E170001: Authentication failed"
+        ));
+        assert!(err.is_authentication_failure());
+        assert!(!err.is_upgrade_required());
+        assert_eq!(err.exit_code(), Some(1));
+        assert_eq!(
+            err.to_string(),
+            "svn 更新到 5失败（svn update -r 5，退出码 1）：svn: E170001: Authentication failed"
+        );
+
+        // git commit 没有可提交的更改时说明在标准输出中
+        let mut command = Command::new("git");
+        command.args(["commit", "-F", "-"]);
+        let output = Output {
+            status: exit_status(1),
+            stdout: "nothing to commit, working tree clean\n".into(),
+            stderr: Vec::new(),
+        };
+        let err = command_error("Git commit", &command, &output);
+        assert!(matches!(
+            &err,
+            SyncError::GitCommand { program, stderr, .. }
+                if program == "git" && stderr == "nothing to commit, working tree clean"
+        ));
+        assert!(!err.is_authentication_failure());
+    }
+
+    #[test]
+    fn test_spawn_error_should_detect_missing_program() {
+        let err =
+            output_with_timeout(&mut Command::new("svn2git-missing-command"), None).unwrap_err();
+        assert!(err.is_command_not_found());
+        assert_eq!(
+            err.to_string(),
+            "找不到 svn2git-missing-command 命令，请确认已安装并已加入 PATH"
+        );
+
+        // 工作目录不存在不是找不到命令
+        let mut command = Command::new("svn2git-missing-command");
+        command.current_dir("/svn2git/missing/dir");
+        let err = output_with_timeout(&mut command, None).unwrap_err();
+        assert!(matches!(err, SyncError::Io(_)));
+    }
+
+    #[cfg(unix)]
+    fn exit_status(code: i32) -> std::process::ExitStatus {
+        use std::os::unix::process::ExitStatusExt;
+        std::process::ExitStatus::from_raw(code << 8)
+    }
+
+    #[cfg(windows)]
+    fn exit_status(code: i32) -> std::process::ExitStatus {
+        use std::os::windows::process::ExitStatusExt;
+        std::process::ExitStatus::from_raw(code as u32)
+    }
+}
@@ -0,0 +1,775 @@
+//! 解析 `svnadmin dump` 输出，不需要 svn 命令和网络即可离线转换
+//!
+//! 支持格式版本 2 和 3，但不支持 `svnadmin dump --deltas` 生成的增量内容
+
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, Read},
+    rc::Rc,
+    str::FromStr,
+};
+
+use encoding_rs::Encoding;
+
+use super::log_encoding::decode_log_bytes;
+use crate::error::{Result, SyncError};
+
+/// 节点类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpNodeKind {
+    File,
+    Dir,
+}
+
+/// 节点操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpNodeAction {
+    Add,
+    Change,
+    Delete,
+    Replace,
+}
+
+impl FromStr for DumpNodeAction {
+    type Err = SyncError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "add" => Ok(Self::Add),
+            "change" => Ok(Self::Change),
+            "delete" => Ok(Self::Delete),
+            "replace" => Ok(Self::Replace),
+            _ => Err(dump_error(format!("未知的 Node-action：{s}"))),
+        }
+    }
+}
+
+/// 版本中的一个节点（文件或目录的变更）
+#[derive(Debug, Clone, PartialEq)]
+pub struct DumpNode {
+    /// 仓库内的路径，不以 `/` 开头，如 `trunk/src/main.rs`
+    pub path: String,
+    /// 节点类型，删除时没有
+    pub kind: Option<DumpNodeKind>,
+    pub action: DumpNodeAction,
+    /// 复制来源的版本和路径
+    pub copy_from: Option<(u64, String)>,
+    /// 完整的属性，没有 `Prop-content-length` 时为 `None`（属性没有变化）
+    pub props: Option<BTreeMap<String, String>>,
+    /// 完整的文件内容，没有 `Text-content-length` 时为 `None`（内容没有变化）
+    pub text: Option<Vec<u8>>,
+}
+
+/// dump 中的一个版本
+#[derive(Debug, Clone, PartialEq)]
+pub struct DumpRevision {
+    pub number: u64,
+    /// 版本属性（`svn:author`、`svn:date`、`svn:log` 等）
+    pub props: BTreeMap<String, String>,
+    pub nodes: Vec<DumpNode>,
+}
+
+impl DumpRevision {
+    /// 提交作者，匿名提交时为空
+    pub fn author(&self) -> &str {
+        self.prop("svn:author")
+    }
+
+    /// 提交时间（UTC，如 `2024-01-02T03:04:05.000000Z`）
+    pub fn date(&self) -> &str {
+        self.prop("svn:date")
+    }
+
+    /// 提交说明
+    pub fn message(&self) -> &str {
+        self.prop("svn:log")
+    }
+
+    fn prop(&self, name: &str) -> &str {
+        self.props.get(name).map_or("", String::as_str)
+    }
+}
+
+/// 一条记录：头部字段和紧随其后的属性、内容
+struct DumpRecord {
+    headers: BTreeMap<String, String>,
+    props: Option<BTreeMap<String, String>>,
+    text: Option<Vec<u8>>,
+}
+
+impl DumpRecord {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
+
+/// 逐个版本读取 dump 流
+///
+/// 每次只在内存中保留一个版本的内容，可以处理比内存大得多的 dump 文件
+pub struct DumpReader<R> {
+    reader: R,
+    /// 已读取的下一个版本的记录
+    pending: Option<DumpRecord>,
+    uuid: Option<String>,
+    /// 旧提交说明的编码，`None` 表示都是 UTF-8
+    log_encoding: Option<&'static Encoding>,
+    started: bool,
+    done: bool,
+}
+
+impl<R: BufRead> DumpReader<R> {
+    /// 创建 dump 读取器，第一次迭代时检查格式版本
+    ///
+    /// # 参数
+    ///
+    /// * `reader`: `svnadmin dump` 的输出
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            pending: None,
+            uuid: None,
+            log_encoding: None,
+            started: false,
+            done: false,
+        }
+    }
+
+    /// 设置旧提交说明的编码，不是 UTF-8 的属性值按该编码转码
+    ///
+    /// # 参数
+    ///
+    /// * `encoding`: 旧提交说明的编码，`None` 表示都是 UTF-8
+    pub fn with_log_encoding(mut self, encoding: Option<&'static Encoding>) -> Self {
+        self.log_encoding = encoding;
+        self
+    }
+
+    /// 读取格式版本，跳过仓库 UUID，定位到第一个版本
+    fn start(&mut self) -> Result<()> {
+        let record = self
+            .read_record()?
+            .ok_or_else(|| dump_error("文件为空".into()))?;
+        match record.header("SVN-fs-dump-format-version") {
+            Some("1" | "2" | "3") => {}
+            Some(version) => return Err(dump_error(format!("不支持格式版本 {version}"))),
+            None => return Err(dump_error("缺少 SVN-fs-dump-format-version".into())),
+        }
+        self.pending = self.read_record()?;
+        if let Some(uuid) = self
+            .pending
+            .as_ref()
+            .and_then(|record| record.header("UUID"))
+        {
+            self.uuid = Some(uuid.to_string());
+            self.pending = self.read_record()?;
+        }
+        Ok(())
+    }
+
+    /// 仓库 UUID，读取第一个版本之后才可用；旧格式的 dump 可能没有
+    pub fn uuid(&self) -> Option<&str> {
+        self.uuid.as_deref()
+    }
+
+    /// 读取下一个版本及其所有节点
+    fn next_revision(&mut self) -> Result<Option<DumpRevision>> {
+        let Some(record) = self.pending.take() else {
+            return Ok(None);
+        };
+        let number = record
+            .header("Revision-number")
+            .ok_or_else(|| dump_error("预期是 Revision-number 记录".into()))?;
+        let number = parse_number(number, "Revision-number")?;
+        let mut revision = DumpRevision {
+            number,
+            props: record.props.unwrap_or_default(),
+            nodes: Vec::new(),
+        };
+        loop {
+            match self.read_record()? {
+                Some(record) if record.header("Node-path").is_some() => {
+                    revision.nodes.push(parse_node(record)?);
+                }
+                next => {
+                    self.pending = next;
+                    return Ok(Some(revision));
+                }
+            }
+        }
+    }
+
+    /// 读取一条记录，已到文件末尾时返回 `None`
+    fn read_record(&mut self) -> Result<Option<DumpRecord>> {
+        let mut headers = BTreeMap::new();
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                if headers.is_empty() {
+                    return Ok(None);
+                }
+                return Err(dump_error("记录头不完整".into()));
+            }
+            let line = line.trim_end_matches(['\n', '\r']);
+            if line.is_empty() {
+                // 记录之间可以有任意多个空行
+                if headers.is_empty() {
+                    continue;
+                }
+                break;
+            }
+            let (name, value) = line
+                .split_once(": ")
+                .ok_or_else(|| dump_error(format!("无效的记录头：{line}")))?;
+            headers.insert(name.to_string(), value.to_string());
+        }
+
+        let length = |name: &str| {
+            headers
+                .get(name)
+                .map(|value| parse_number(value, name))
+                .transpose()
+        };
+        if headers.get("Text-delta").is_some_and(|v| v == "true")
+            || headers.get("Prop-delta").is_some_and(|v| v == "true")
+        {
+            return Err(dump_error(
+                "不支持增量格式，请用不带 --deltas 的 svnadmin dump 重新导出".into(),
+            ));
+        }
+        let prop_len = length("Prop-content-length")?;
+        let text_len = length("Text-content-length")?;
+        let props = prop_len
+            .map(|len| parse_props(&self.read_bytes(len)?, self.log_encoding))
+            .transpose()?;
+        let text = text_len.map(|len| self.read_bytes(len)).transpose()?;
+        // 旧版本的 dump 只有 Content-length，内容之后多出的部分忽略
+        let content_len = length("Content-length")?.unwrap_or(0);
+        let read = prop_len.unwrap_or(0).saturating_add(text_len.unwrap_or(0));
+        if content_len > read {
+            self.read_bytes(content_len - read)?;
+        }
+        Ok(Some(DumpRecord {
+            headers,
+            props,
+            text,
+        }))
+    }
+
+    fn read_bytes(&mut self, len: u64) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        (&mut self.reader).take(len).read_to_end(&mut buf)?;
+        if (buf.len() as u64) < len {
+            return Err(dump_error("内容长度与记录头不一致，文件可能被截断".into()));
+        }
+        Ok(buf)
+    }
+}
+
+impl<R: BufRead> Iterator for DumpReader<R> {
+    type Item = Result<DumpRevision>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = match self.started {
+            true => self.next_revision(),
+            false => {
+                self.started = true;
+                self.start().and_then(|_| self.next_revision())
+            }
+        };
+        match result {
+            Ok(Some(revision)) => Some(Ok(revision)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// 解析完整的 dump 输出
+///
+/// # 参数
+///
+/// * `dump`: `svnadmin dump` 的输出
+///
+/// # 返回
+///
+/// 所有版本；格式不正确时返回错误
+pub fn parse_svn_dump(dump: &[u8]) -> Result<Vec<DumpRevision>> {
+    DumpReader::new(dump).collect()
+}
+
+fn parse_node(record: DumpRecord) -> Result<DumpNode> {
+    let path = record.header("Node-path").unwrap_or_default();
+    let action = record
+        .header("Node-action")
+        .ok_or_else(|| dump_error(format!("节点 {path} 缺少 Node-action")))?
+        .parse()?;
+    let kind = match record.header("Node-kind") {
+        Some("file") => Some(DumpNodeKind::File),
+        Some("dir") => Some(DumpNodeKind::Dir),
+        Some(kind) => return Err(dump_error(format!("节点 {path} 的类型无效：{kind}"))),
+        None => None,
+    };
+    let copy_from = match (
+        record.header("Node-copyfrom-rev"),
+        record.header("Node-copyfrom-path"),
+    ) {
+        (Some(rev), Some(from)) => Some((
+            parse_number(rev, "Node-copyfrom-rev")?,
+            from.trim_matches('/').to_string(),
+        )),
+        _ => None,
+    };
+    Ok(DumpNode {
+        path: path.trim_matches('/').to_string(),
+        kind,
+        action,
+        copy_from,
+        props: record.props,
+        text: record.text,
+    })
+}
+
+/// 解析属性块：`K <长度>`、键、`V <长度>`、值，以 `PROPS-END` 结束
+///
+/// 旧仓库中不是 UTF-8 的属性值（如 GBK 编码的提交说明）按 `encoding` 转码
+fn parse_props(
+    data: &[u8],
+    encoding: Option<&'static Encoding>,
+) -> Result<BTreeMap<String, String>> {
+    let mut props = BTreeMap::new();
+    let mut rest = data;
+    loop {
+        let (line, after) = split_line(rest)?;
+        if line == b"PROPS-END" {
+            return Ok(props);
+        }
+        let key = read_prop_field(line, after, b'K')?;
+        rest = key.1;
+        let (line, after) = split_line(rest)?;
+        let value = read_prop_field(line, after, b'V')?;
+        rest = value.1;
+        props.insert(
+            String::from_utf8_lossy(key.0).into_owned(),
+            decode_log_bytes(value.0, encoding)
+                .unwrap_or_else(|| String::from_utf8_lossy(value.0))
+                .into_owned(),
+        );
+    }
+}
+
+/// 读取 `<tag> <长度>` 之后指定长度的内容，返回内容和之后的剩余部分
+fn read_prop_field<'a>(line: &[u8], rest: &'a [u8], tag: u8) -> Result<(&'a [u8], &'a [u8])> {
+    let invalid = || dump_error("属性块格式无效".into());
+    let len = match line {
+        [t, b' ', len @ ..] if *t == tag => str::from_utf8(len)
+            .ok()
+            .and_then(|len| len.parse::<usize>().ok())
+            .ok_or_else(invalid)?,
+        _ => return Err(invalid()),
+    };
+    if rest.len() <= len || rest[len] != b'\n' {
+        return Err(invalid());
+    }
+    Ok((&rest[..len], &rest[len + 1..]))
+}
+
+fn split_line(data: &[u8]) -> Result<(&[u8], &[u8])> {
+    let end = data
+        .iter()
+        .position(|b| *b == b'\n')
+        .ok_or_else(|| dump_error("属性块不完整".into()))?;
+    Ok((&data[..end], &data[end + 1..]))
+}
+
+fn parse_number(value: &str, name: &str) -> Result<u64> {
+    value
+        .parse()
+        .map_err(|_| dump_error(format!("{name} 不是有效的数字：{value}")))
+}
+
+fn dump_error(reason: String) -> SyncError {
+    SyncError::App(format!("无效的 SVN dump：{reason}"))
+}
+
+/// 仓库中的一个节点
+#[derive(Debug, Clone, PartialEq)]
+enum TreeEntry {
+    Dir,
+    File { content: Rc<[u8]>, executable: bool },
+}
+
+/// 重放版本后文件的变化
+#[derive(Debug, Clone, PartialEq)]
+pub enum DumpFileChange {
+    /// 新增或修改文件
+    Write {
+        path: String,
+        content: Rc<[u8]>,
+        executable: bool,
+    },
+    /// 删除文件
+    Delete { path: String },
+}
+
+impl DumpFileChange {
+    /// 仓库内的路径
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Write { path, .. } | Self::Delete { path } => path,
+        }
+    }
+}
+
+/// 按版本重放 dump 得到的仓库目录树
+///
+/// 保存每个路径的所有历史状态，`svn copy` 可以从任意早先的版本复制，
+/// 而不需要为每个版本保存一份完整的目录树。文件内容在各版本之间共享。
+#[derive(Debug, Default)]
+pub struct DumpTree {
+    /// 路径 → 按版本排列的状态，`None` 表示在该版本被删除
+    history: BTreeMap<String, Vec<(u64, Option<TreeEntry>)>>,
+}
+
+impl DumpTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 重放一个版本
+    ///
+    /// # 参数
+    ///
+    /// * `revision`: 要重放的版本，版本号必须递增
+    ///
+    /// # 返回
+    ///
+    /// 该版本中文件的变化，按重放顺序排列
+    pub fn apply(&mut self, revision: &DumpRevision) -> Result<Vec<DumpFileChange>> {
+        let mut changes = Vec::new();
+        for node in &revision.nodes {
+            self.apply_node(revision.number, node, &mut changes)?;
+        }
+        Ok(changes)
+    }
+
+    /// 路径在指定版本的状态
+    fn entry_at(&self, path: &str, rev: u64) -> Option<&TreeEntry> {
+        let states = self.history.get(path)?;
+        let index = states.partition_point(|(r, _)| *r <= rev);
+        states[..index].last()?.1.as_ref()
+    }
+
+    /// 指定版本中 `path` 本身及其下所有存在的路径
+    fn subtree_at(&self, path: &str, rev: u64) -> Vec<(String, TreeEntry)> {
+        self.history
+            .range(path.to_string()..)
+            .take_while(|(p, _)| p.starts_with(path))
+            .filter(|(p, _)| is_within(p, path))
+            .filter_map(|(p, _)| Some((p.clone(), self.entry_at(p, rev)?.clone())))
+            .collect()
+    }
+
+    fn set(&mut self, path: &str, rev: u64, entry: Option<TreeEntry>) {
+        let states = self.history.entry(path.to_string()).or_default();
+        match states.last_mut() {
+            Some((r, state)) if *r == rev => *state = entry,
+            _ => states.push((rev, entry)),
+        }
+    }
+
+    fn apply_node(
+        &mut self,
+        rev: u64,
+        node: &DumpNode,
+        changes: &mut Vec<DumpFileChange>,
+    ) -> Result<()> {
+        let path = node.path.as_str();
+        if matches!(
+            node.action,
+            DumpNodeAction::Delete | DumpNodeAction::Replace
+        ) {
+            let removed = self.subtree_at(path, rev);
+            if removed.is_empty() {
+                return Err(dump_error(format!("r{rev} 删除了不存在的路径 {path}")));
+            }
+            for (p, entry) in removed {
+                self.set(&p, rev, None);
+                if let TreeEntry::File { .. } = entry {
+                    changes.push(DumpFileChange::Delete { path: p });
+                }
+            }
+        }
+        if node.action == DumpNodeAction::Delete {
+            return Ok(());
+        }
+
+        if let Some((from_rev, from)) = &node.copy_from {
+            let copied = self.subtree_at(from, *from_rev);
+            if copied.is_empty() {
+                return Err(dump_error(format!(
+                    "r{rev} 的复制来源 {from}@{from_rev} 不存在"
+                )));
+            }
+            for (p, entry) in copied {
+                let target = format!("{path}{}", &p[from.len()..]);
+                self.write(rev, &target, entry, changes);
+            }
+        }
+
+        let current = self.entry_at(path, rev).cloned();
+        let entry = match (node.kind, current) {
+            (Some(DumpNodeKind::Dir), _) | (None, Some(TreeEntry::Dir)) => TreeEntry::Dir,
+            (_, current) => {
+                let (old_content, old_executable) = match current {
+                    Some(TreeEntry::File {
+                        content,
+                        executable,
+                    }) => (Some(content), executable),
+                    _ => (None, false),
+                };
+                let content = match &node.text {
+                    Some(text) => Rc::from(text.as_slice()),
+                    None => match old_content {
+                        Some(content) => content,
+                        None if node.action == DumpNodeAction::Change => {
+                            return Err(dump_error(format!("r{rev} 修改了不存在的文件 {path}")));
+                        }
+                        None => Rc::from(&[][..]),
+                    },
+                };
+                let executable = match &node.props {
+                    Some(props) => props.contains_key("svn:executable"),
+                    None => old_executable,
+                };
+                TreeEntry::File {
+                    content,
+                    executable,
+                }
+            }
+        };
+        if node.copy_from.is_none() || node.text.is_some() || node.props.is_some() {
+            self.write(rev, path, entry, changes);
+        }
+        Ok(())
+    }
+
+    /// 写入节点，文件内容或可执行属性变化时记录变更
+    fn write(&mut self, rev: u64, path: &str, entry: TreeEntry, changes: &mut Vec<DumpFileChange>) {
+        if self.entry_at(path, rev) == Some(&entry) {
+            return;
+        }
+        if let TreeEntry::File {
+            content,
+            executable,
+        } = &entry
+        {
+            changes.retain(|change| change.path() != path);
+            changes.push(DumpFileChange::Write {
+                path: path.to_string(),
+                content: content.clone(),
+                executable: *executable,
+            });
+        }
+        self.set(path, rev, Some(entry));
+    }
+}
+
+/// `path` 是否为 `dir` 本身或在 `dir` 之下
+fn is_within(path: &str, dir: &str) -> bool {
+    dir.is_empty() || path == dir || path.strip_prefix(dir).is_some_and(|p| p.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 生成属性块
+    fn props(pairs: &[(&str, &str)]) -> String {
+        let mut block = String::new();
+        for (key, value) in pairs {
+            block.push_str(&format!(
+                "K {}\n{key}\nV {}\n{value}\n",
+                key.len(),
+                value.len()
+            ));
+        }
+        block + "PROPS-END\n"
+    }
+
+    fn revision(number: u64, log: &str) -> String {
+        let props = props(&[
+            ("svn:author", "alice"),
+            ("svn:date", "2024-01-02T03:04:05.000000Z"),
+            ("svn:log", log),
+        ]);
+        format!(
+            "Revision-number: {number}\nProp-content-length: {0}\nContent-length: {0}\n\n{props}\n",
+            props.len()
+        )
+    }
+
+    fn file_node(path: &str, action: &str, text: &str, extra: &str) -> String {
+        let props = props(&[]);
+        format!(
+            "Node-path: {path}\nNode-kind: file\nNode-action: {action}\n{extra}Prop-content-length: {}\nText-content-length: {}\nContent-length: {}\n\n{props}{text}\n\n",
+            props.len(),
+            text.len(),
+            props.len() + text.len()
+        )
+    }
+
+    fn node(path: &str, kind: &str, action: &str, extra: &str) -> String {
+        format!("Node-path: {path}\n{kind}Node-action: {action}\n{extra}\n")
+    }
+
+    fn sample_dump() -> String {
+        [
+            "SVN-fs-dump-format-version: 2\n\nUUID: 6e1ba1b3-0000-0000-0000-000000000000\n\n"
+                .to_string(),
+            "Revision-number: 0\nProp-content-length: 10\nContent-length: 10\n\nPROPS-END\n\n"
+                .to_string(),
+            revision(1, "init"),
+            node("trunk", "Node-kind: dir\n", "add", ""),
+            file_node("trunk/a.txt", "add", "hello", ""),
+            file_node("trunk/lib/b.txt", "add", "b", ""),
+            revision(2, "branch"),
+            node(
+                "branches/dev",
+                "Node-kind: dir\n",
+                "add",
+                "Node-copyfrom-rev: 1\nNode-copyfrom-path: trunk\n",
+            ),
+            file_node("trunk/a.txt", "change", "changed", ""),
+            revision(3, "delete"),
+            node("trunk/lib", "", "delete", ""),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn test_parse_svn_dump() {
+        let revisions = parse_svn_dump(sample_dump().as_bytes()).unwrap();
+
+        let numbers: Vec<u64> = revisions.iter().map(|r| r.number).collect();
+        assert_eq!(numbers, [0, 1, 2, 3]);
+        let r1 = &revisions[1];
+        assert_eq!(
+            (r1.author(), r1.date(), r1.message()),
+            ("alice", "2024-01-02T03:04:05.000000Z", "init")
+        );
+        assert_eq!(r1.nodes.len(), 3);
+        assert_eq!(r1.nodes[1].text.as_deref(), Some(&b"hello"[..]));
+        assert_eq!(r1.nodes[0].kind, Some(DumpNodeKind::Dir));
+        assert_eq!(
+            revisions[2].nodes[0].copy_from,
+            Some((1, "trunk".to_string()))
+        );
+        assert_eq!(revisions[3].nodes[0].action, DumpNodeAction::Delete);
+    }
+
+    #[test]
+    fn test_parse_svn_dump_should_reject_invalid_input() {
+        assert!(parse_svn_dump(b"").is_err());
+        assert!(parse_svn_dump(b"SVN-fs-dump-format-version: 9\n\n").is_err());
+
+        let truncated = sample_dump();
+        let truncated = &truncated.as_bytes()[..truncated.len() - 30];
+        assert!(parse_svn_dump(truncated).is_err());
+
+        let delta = "SVN-fs-dump-format-version: 3\n\nRevision-number: 1\n\nNode-path: a\nNode-kind: file\nNode-action: add\nText-delta: true\nText-content-length: 0\nContent-length: 0\n\n";
+        let err = parse_svn_dump(delta.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("--deltas"), "{err}");
+    }
+
+    #[test]
+    fn test_dump_tree_should_replay_copies_and_deletes() {
+        let mut tree = DumpTree::new();
+        let changes: Vec<Vec<DumpFileChange>> = parse_svn_dump(sample_dump().as_bytes())
+            .unwrap()
+            .iter()
+            .map(|revision| tree.apply(revision).unwrap())
+            .collect();
+        let summary = |changes: &[DumpFileChange]| -> Vec<String> {
+            changes
+                .iter()
+                .map(|change| match change {
+                    DumpFileChange::Write { path, content, .. } => {
+                        format!("W {path}={}", String::from_utf8_lossy(content))
+                    }
+                    DumpFileChange::Delete { path } => format!("D {path}"),
+                })
+                .collect()
+        };
+
+        assert!(changes[0].is_empty());
+        assert_eq!(
+            summary(&changes[1]),
+            ["W trunk/a.txt=hello", "W trunk/lib/b.txt=b"]
+        );
+        // 从 r1 复制，不受同一版本中对 trunk 的修改影响
+        assert_eq!(
+            summary(&changes[2]),
+            [
+                "W branches/dev/a.txt=hello",
+                "W branches/dev/lib/b.txt=b",
+                "W trunk/a.txt=changed"
+            ]
+        );
+        assert_eq!(summary(&changes[3]), ["D trunk/lib/b.txt"]);
+    }
+
+    #[test]
+    fn test_dump_tree_should_keep_executable_from_props() {
+        let exec = props(&[("svn:executable", "*")]);
+        let dump = format!(
+            "SVN-fs-dump-format-version: 2\n\n{}Node-path: run.sh\nNode-kind: file\nNode-action: add\nProp-content-length: {}\nText-content-length: 2\nContent-length: {}\n\n{exec}ls\n\n{}{}",
+            revision(1, "add"),
+            exec.len(),
+            exec.len() + 2,
+            revision(2, "edit"),
+            "Node-path: run.sh\nNode-kind: file\nNode-action: change\nText-content-length: 3\nContent-length: 3\n\npwd\n\n",
+        );
+        let mut tree = DumpTree::new();
+        let changes: Vec<_> = parse_svn_dump(dump.as_bytes())
+            .unwrap()
+            .iter()
+            .flat_map(|revision| tree.apply(revision).unwrap())
+            .collect();
+        assert!(changes.iter().all(|change| matches!(
+            change,
+            DumpFileChange::Write {
+                executable: true,
+                ..
+            }
+        )));
+        assert_eq!(changes.len(), 2);
+    }
+
+    #[test]
+    fn test_dump_tree_should_reject_missing_copy_source() {
+        let mut tree = DumpTree::new();
+        let revision = DumpRevision {
+            number: 1,
+            props: BTreeMap::new(),
+            nodes: vec![DumpNode {
+                path: "branches/x".into(),
+                kind: Some(DumpNodeKind::Dir),
+                action: DumpNodeAction::Add,
+                copy_from: Some((0, "trunk".into())),
+                props: None,
+                text: None,
+            }],
+        };
+        assert!(tree.apply(&revision).is_err());
+    }
+}
@@ -43,6 +43,34 @@ pub fn git_commit_with_ops<T: GitOperations + ?Sized>(
     Ok(())
 }
 
+/// 只提交指定路径的 Git 更改（使用自定义Git操作实现）
+///
+/// 与 [`git_commit_with_ops`] 相同，但只暂存 `files` 中的路径，
+/// 工作目录中的其他更改保持未暂存状态。
+///
+/// # 参数
+///
+/// * `git_ops`: Git操作实现对象
+/// * `path`: Git 本地目录
+/// * `files`: 需要提交的路径（相对仓库根目录）
+/// * `message`: 提交消息
+pub fn git_commit_paths_with_ops<T: GitOperations + ?Sized>(
+    git_ops: &T,
+    path: &Path,
+    files: &[String],
+    message: &str,
+) -> Result<()> {
+    println!("正在提交 {} 个路径的 Git 更改", files.len());
+
+    git_ops.add_paths(path, files)?;
+    println!("已添加指定路径的更改到暂存区");
+
+    git_ops.commit(path, message)?;
+    println!("Git 提交成功：{}", message);
+
+    Ok(())
+}
+
 /// 使用默认真实Git实现提交更改
 ///
 /// 这是一个便捷函数，使用RealGitOperations作为默认实现
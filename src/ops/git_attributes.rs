@@ -0,0 +1,224 @@
+//! `.gitattributes` / `.gitignore` 维护
+//!
+//! 同步时生成的规则写在 `.gitattributes`、`.gitignore` 中由工具管理的区块内，
+//! 区块之外的内容保持不变，用户可以自由添加自己的规则
+
+use std::{fs, path::Path};
+
+use crate::error::Result;
+
+/// 更新 `.gitattributes` 中由工具管理的区块
+///
+/// 区块以 `# BEGIN svn2git <section>` 和 `# END svn2git <section>` 标记，
+/// 每次调用都会整体替换区块内容；`lines` 为空时移除该区块。
+///
+/// # 参数
+///
+/// * `git_dir`: Git 本地目录
+/// * `section`: 区块名称，如 `svn:needs-lock`
+/// * `lines`: 区块内的规则
+///
+/// # 返回
+///
+/// 文件内容是否发生变化
+pub fn update_gitattributes_section(
+    git_dir: &Path,
+    section: &str,
+    lines: &[String],
+) -> Result<bool> {
+    update_managed_section(&git_dir.join(".gitattributes"), section, lines)
+}
+
+/// 更新 `.gitignore` 中由工具管理的区块
+///
+/// 区块格式与 [`update_gitattributes_section`] 相同。
+///
+/// # 参数
+///
+/// * `git_dir`: Git 本地目录
+/// * `section`: 区块名称
+/// * `lines`: 区块内的忽略规则
+///
+/// # 返回
+///
+/// 文件内容是否发生变化
+pub fn update_gitignore_section(git_dir: &Path, section: &str, lines: &[String]) -> Result<bool> {
+    update_managed_section(&git_dir.join(".gitignore"), section, lines)
+}
+
+/// 更新 `.git/info/exclude` 中由工具管理的区块
+///
+/// 规则与 `.gitignore` 相同，但只在本地生效，不会出现在提交中。
+/// `.git` 不是目录（如 `git worktree` 创建的工作树）时改为更新 `.gitignore`。
+///
+/// # 参数
+///
+/// * `git_dir`: Git 本地目录
+/// * `section`: 区块名称
+/// * `lines`: 区块内的忽略规则
+///
+/// # 返回
+///
+/// 文件内容是否发生变化
+pub fn update_git_exclude_section(git_dir: &Path, section: &str, lines: &[String]) -> Result<bool> {
+    let dot_git = git_dir.join(".git");
+    if dot_git.exists() && !dot_git.is_dir() {
+        return update_gitignore_section(git_dir, section, lines);
+    }
+    let info = dot_git.join("info");
+    if lines.is_empty() && !info.exists() {
+        return Ok(false);
+    }
+    fs::create_dir_all(&info)?;
+    update_managed_section(&info.join("exclude"), section, lines)
+}
+
+/// 整体替换文件中 `# BEGIN svn2git <section>` 到 `# END svn2git <section>` 的区块
+///
+/// 区块已存在时原地替换，保持与其他区块的顺序，内容相同时不写入文件；不存在时追加到末尾
+fn update_managed_section(path: &Path, section: &str, lines: &[String]) -> Result<bool> {
+    let original = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let begin = format!("# BEGIN svn2git {section}");
+    let end = format!("# END svn2git {section}");
+    let mut kept = Vec::new();
+    let mut position = None;
+    let mut in_section = false;
+    for line in original.lines() {
+        if line == begin {
+            in_section = true;
+            position.get_or_insert(kept.len());
+        } else if line == end {
+            in_section = false;
+        } else if !in_section {
+            kept.push(line);
+        }
+    }
+
+    if !lines.is_empty() {
+        let block = std::iter::once(begin.as_str())
+            .chain(lines.iter().map(String::as_str))
+            .chain(std::iter::once(end.as_str()));
+        let at = position.unwrap_or(kept.len());
+        kept.splice(at..at, block);
+    }
+    let mut content = kept.join("\n");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+
+    if content == original {
+        return Ok(false);
+    }
+    fs::write(path, content)?;
+    Ok(true)
+}
+
+/// 将相对路径转换为只匹配该文件的 `.gitattributes` 模式
+///
+/// 模式以 `/` 开头锚定到仓库根目录，空白和通配符会被转义。
+///
+/// # 参数
+///
+/// * `path`: `/` 分隔的相对路径
+pub fn gitattributes_pattern(path: &str) -> String {
+    let mut pattern = String::from("/");
+    for ch in path.chars() {
+        match ch {
+            ' ' | '\t' => pattern.push_str("[[:space:]]"),
+            '*' | '?' | '[' | '\\' => {
+                pattern.push('\\');
+                pattern.push(ch);
+            }
+            _ => pattern.push(ch),
+        }
+    }
+    pattern
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_section_should_keep_user_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".gitattributes");
+        fs::write(&path, "*.sh text eol=lf\n").unwrap();
+
+        let lines = vec!["/a.psd lockable".to_string()];
+        assert!(update_gitattributes_section(dir.path(), "svn:needs-lock", &lines).unwrap());
+        assert!(!update_gitattributes_section(dir.path(), "svn:needs-lock", &lines).unwrap());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "*.sh text eol=lf\n# BEGIN svn2git svn:needs-lock\n/a.psd lockable\n# END svn2git svn:needs-lock\n"
+        );
+
+        assert!(update_gitattributes_section(dir.path(), "svn:needs-lock", &[]).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "*.sh text eol=lf\n");
+    }
+
+    #[test]
+    fn test_update_section_should_replace_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let (a, b) = (vec!["/a binary".to_string()], vec!["/b text".to_string()]);
+        update_gitattributes_section(dir.path(), "first", &a).unwrap();
+        update_gitattributes_section(dir.path(), "second", &b).unwrap();
+
+        // 内容不变时不因移动区块而重写文件
+        assert!(!update_gitattributes_section(dir.path(), "first", &a).unwrap());
+        assert!(update_gitattributes_section(dir.path(), "first", &b).unwrap());
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".gitattributes")).unwrap(),
+            "# BEGIN svn2git first\n/b text\n# END svn2git first\n\
+             # BEGIN svn2git second\n/b text\n# END svn2git second\n"
+        );
+    }
+
+    #[test]
+    fn test_update_git_exclude_section_should_stay_out_of_worktree() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!update_git_exclude_section(dir.path(), "state", &[]).unwrap());
+        assert!(!dir.path().join(".git").exists());
+
+        let lines = vec!["/config.json".to_string()];
+        assert!(update_git_exclude_section(dir.path(), "state", &lines).unwrap());
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".git/info/exclude")).unwrap(),
+            "# BEGIN svn2git state\n/config.json\n# END svn2git state\n"
+        );
+        assert!(!dir.path().join(".gitignore").exists());
+    }
+
+    #[test]
+    fn test_update_section_should_not_create_empty_file() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!update_gitattributes_section(dir.path(), "svn:needs-lock", &[]).unwrap());
+        assert!(!dir.path().join(".gitattributes").exists());
+    }
+
+    #[test]
+    fn test_gitattributes_pattern() {
+        assert_eq!(
+            gitattributes_pattern("docs/a b.doc"),
+            "/docs/a[[:space:]]b.doc"
+        );
+        assert_eq!(gitattributes_pattern("x[1]*.bin"), "/x\\[1]\\*.bin");
+    }
+
+    #[test]
+    fn test_update_gitignore_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let lines = vec![".svn/".to_string()];
+        assert!(update_gitignore_section(dir.path(), "svn-metadata", &lines).unwrap());
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".gitignore")).unwrap(),
+            "# BEGIN svn2git svn-metadata\n.svn/\n# END svn2git svn-metadata\n"
+        );
+        assert!(!dir.path().join(".gitattributes").exists());
+    }
+}
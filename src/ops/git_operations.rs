@@ -3,7 +3,80 @@
 //! 定义Git操作的统一接口，支持真实Git命令和Mock实现
 
 use crate::error::Result;
-use std::path::Path;
+use chrono::{DateTime, FixedOffset, Utc};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// Git 身份和时间（附注标签的创建者等）
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitSignature {
+    /// 名称
+    pub name: String,
+    /// 邮箱
+    pub email: String,
+    /// 时间，`None` 表示当前时间
+    pub date: Option<DateTime<Utc>>,
+}
+
+/// 提交的作者和时间，未设置的项使用仓库配置的用户和当前时间
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommitMetadata {
+    /// 作者名称
+    pub author_name: Option<String>,
+    /// 作者邮箱，与作者名称同时设置
+    pub author_email: Option<String>,
+    /// 作者时间和提交时间，保留其中的时区偏移
+    pub date: Option<DateTime<FixedOffset>>,
+    /// 暂存区与 HEAD 相同时仍然提交（`git commit --allow-empty`）
+    pub allow_empty: bool,
+}
+
+/// 历史中的一个提交及其 note
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GitCommitEntry {
+    /// 完整的提交哈希
+    pub hash: String,
+    /// 完整的提交说明
+    pub message: String,
+    /// 指定 note 引用中该提交的 note
+    pub note: Option<String>,
+}
+
+/// Git 后端支持的功能
+///
+/// 同步开始前按请求的功能检查后端，不支持时在计划阶段报错，不会在同步到一半时失败
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GitCapabilities {
+    /// 按仓库配置（`commit.gpgsign`）签名提交
+    pub signing: bool,
+    /// 添加 Git notes
+    pub notes: bool,
+    /// 指定提交的作者和时间（[`CommitMetadata`]）
+    pub author_override: bool,
+    /// 把符号链接提交为链接而不是普通文件
+    pub symlinks: bool,
+    /// 暂存区与 HEAD 相同时仍然提交
+    pub empty_commits: bool,
+    /// 生成 git bundle 文件
+    pub bundle: bool,
+    /// 按同步对配置的环境变量（[`CommandEnv`](super::CommandEnv)）访问仓库
+    pub command_env: bool,
+}
+
+impl GitCapabilities {
+    /// 支持所有功能
+    pub const ALL: Self = Self {
+        signing: true,
+        notes: true,
+        author_override: true,
+        symlinks: true,
+        empty_commits: true,
+        bundle: true,
+        command_env: true,
+    };
+}
 
 /// Git操作抽象特征
 ///
@@ -70,6 +143,50 @@ pub trait GitOperations {
     /// * `Err(SyncError)` - 提交失败
     fn commit(&self, path: &Path, message: &str) -> Result<()>;
 
+    /// 以指定的作者和时间提交更改
+    ///
+    /// 提交者仍为仓库配置的用户，提交时间与作者时间相同
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    /// * `message` - 提交消息
+    /// * `metadata` - 作者和时间
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 提交成功
+    /// * `Err(SyncError)` - 提交失败
+    fn commit_with(&self, path: &Path, message: &str, metadata: &CommitMetadata) -> Result<()>;
+
+    /// 只添加指定路径的更改（含删除）到暂存区
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    /// * `files` - 相对仓库根目录的路径
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 添加成功
+    /// * `Err(SyncError)` - 添加失败
+    fn add_paths(&self, path: &Path, files: &[String]) -> Result<()>;
+
+    /// 在暂存区中把文件标记为可执行，等同 `git update-index --chmod=+x`
+    ///
+    /// 只修改暂存区中的文件模式，不依赖文件系统的权限位，Windows 上同样生效
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    /// * `files` - 已暂存的文件（相对仓库根目录）
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 标记成功
+    /// * `Err(SyncError)` - 文件不在暂存区或标记失败
+    fn set_executable(&self, path: &Path, files: &[String]) -> Result<()>;
+
     /// 获取Git状态
     ///
     /// # 参数
@@ -95,6 +212,20 @@ pub trait GitOperations {
     /// * `Err(SyncError)` - 获取历史失败
     fn log(&self, path: &Path, count: Option<usize>) -> Result<String>;
 
+    /// 检查目录是否为已初始化的 Git 仓库
+    ///
+    /// 只认仓库根目录，位于其他仓库中的子目录不算
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(bool)` - true表示已初始化
+    /// * `Err(SyncError)` - 检查失败
+    fn is_repository(&self, path: &Path) -> Result<bool>;
+
     /// 检查工作目录是否干净
     ///
     /// # 参数
@@ -106,9 +237,263 @@ pub trait GitOperations {
     /// * `Ok(bool)` - true表示工作目录干净，false表示有未提交的更改
     /// * `Err(SyncError)` - 检查失败
     fn is_clean(&self, path: &Path) -> Result<bool>;
+
+    /// 获取当前 HEAD 指向的提交哈希
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(String)` - 完整的提交哈希
+    /// * `Err(SyncError)` - 仓库没有任何提交或获取失败
+    fn rev_parse_head(&self, path: &Path) -> Result<String>;
+
+    /// 获取从 HEAD 沿第一父提交回溯到的根提交，用于识别仓库
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(String)` - 完整的提交哈希
+    /// * `Err(SyncError)` - 仓库没有任何提交或获取失败
+    fn root_commit(&self, path: &Path) -> Result<String>;
+
+    /// 从根提交开始，按时间顺序列出 HEAD 沿第一父提交的历史
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    /// * `notes_ref` - 同时读取的 note 引用，如 `refs/notes/svn`
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec)` - 提交列表，最早的提交在前
+    /// * `Err(SyncError)` - 仓库没有任何提交或读取失败
+    fn commit_history(&self, path: &Path, notes_ref: &str) -> Result<Vec<GitCommitEntry>>;
+
+    /// 列出指定提交中所有文件的 blob 哈希
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    /// * `rev` - 提交哈希或引用
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(BTreeMap)` - 相对路径（`/` 分隔）到 blob 哈希的映射
+    /// * `Err(SyncError)` - 提交不存在或读取失败
+    fn tree_blobs(&self, path: &Path, rev: &str) -> Result<BTreeMap<String, String>>;
+
+    /// 按仓库配置计算文件的 blob 哈希（不写入对象库）
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    /// * `files` - 需要计算的文件
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<String>)` - 与 `files` 一一对应的 blob 哈希
+    /// * `Err(SyncError)` - 计算失败
+    fn hash_files(&self, path: &Path, files: &[PathBuf]) -> Result<Vec<String>>;
+
+    /// 为提交添加 Git note，已有 note 时覆盖
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    /// * `notes_ref` - note 引用，如 `refs/notes/svn`
+    /// * `commit` - 提交哈希或引用
+    /// * `note` - note 内容
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 添加成功
+    /// * `Err(SyncError)` - 提交不存在或添加失败
+    fn add_note(&self, path: &Path, notes_ref: &str, commit: &str, note: &str) -> Result<()>;
+
+    /// 把 HEAD 和暂存区重置到指定提交，保留工作目录中的文件（`git reset --mixed`）
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    /// * `commit` - 目标提交，`None` 表示回到还没有任何提交的状态
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 重置成功
+    /// * `Err(SyncError)` - 提交不存在或重置失败
+    fn reset_to(&self, path: &Path, commit: Option<&str>) -> Result<()>;
+
+    /// 暂存已跟踪文件的未提交更改（`git stash push`），未跟踪的文件保留在工作目录
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    /// * `message` - stash 说明
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(bool)` - 是否创建了 stash，没有可暂存的更改时为 false
+    /// * `Err(SyncError)` - 暂存失败
+    fn stash(&self, path: &Path, message: &str) -> Result<bool>;
+
+    /// 恢复最近一次暂存的更改并删除该 stash（`git stash pop`）
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 恢复成功
+    /// * `Err(SyncError)` - 没有 stash 或恢复时冲突，冲突时 stash 保留
+    fn stash_pop(&self, path: &Path) -> Result<()>;
+
+    /// 创建分支（不切换）
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    /// * `name` - 分支名
+    /// * `start` - 分支起点（提交或分支名），`None` 表示当前 HEAD
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 创建成功
+    /// * `Err(SyncError)` - 分支已存在、起点不存在或创建失败
+    fn create_branch(&self, path: &Path, name: &str, start: Option<&str>) -> Result<()>;
+
+    /// 切换到已有分支
+    ///
+    /// 仓库还没有任何提交时，只把 HEAD 指向该分支，下一次提交会创建它
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    /// * `name` - 分支名
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 切换成功
+    /// * `Err(SyncError)` - 分支不存在或切换失败
+    fn checkout_branch(&self, path: &Path, name: &str) -> Result<()>;
+
+    /// 创建附注标签
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    /// * `name` - 标签名
+    /// * `target` - 标签指向的提交或分支名
+    /// * `message` - 标签说明
+    /// * `tagger` - 标签创建者，`None` 表示使用仓库配置的用户和当前时间
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 创建成功
+    /// * `Err(SyncError)` - 标签已存在、目标不存在或创建失败
+    fn tag(
+        &self,
+        path: &Path,
+        name: &str,
+        target: &str,
+        message: &str,
+        tagger: Option<&GitSignature>,
+    ) -> Result<()>;
+
+    /// 添加远程仓库，已存在时更新其地址
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    /// * `name` - 远程仓库名，如 `origin`
+    /// * `url` - 远程仓库地址
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 设置成功
+    /// * `Err(SyncError)` - 设置失败
+    fn add_remote(&self, path: &Path, name: &str, url: &str) -> Result<()>;
+
+    /// 推送所有本地分支和标签到远程仓库
+    ///
+    /// 不强制推送，远程分支已分叉时推送失败，本地提交不受影响。
+    /// `refs/svn2git/` 下的镜像标记一并推送并覆盖远程的标记
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    /// * `remote` - 远程仓库名
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 推送成功
+    /// * `Err(SyncError)` - 远程仓库不存在、被拒绝或网络错误
+    fn push(&self, path: &Path, remote: &str) -> Result<()>;
+
+    /// 把所有本地分支和标签打包为 git bundle 文件（`git bundle create`）
+    ///
+    /// 生成的文件可以复制到其他机器上 `git clone`，不需要推送到远程仓库
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    /// * `file` - bundle 文件路径，已存在时覆盖
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 生成成功
+    /// * `Err(SyncError)` - 仓库没有提交或写入失败
+    fn bundle(&self, path: &Path, file: &Path) -> Result<()>;
+
+    /// 把文本写为 blob，并让引用指向它，引用已存在时覆盖
+    ///
+    /// 用于在仓库中保存不属于任何分支的数据，如 `refs/svn2git/state`
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    /// * `reference` - 完整的引用名
+    /// * `content` - blob 内容
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(())` - 写入成功
+    /// * `Err(SyncError)` - 引用名无效或写入失败
+    fn write_blob_ref(&self, path: &Path, reference: &str, content: &str) -> Result<()>;
+
+    /// 读取引用指向的 blob
+    ///
+    /// # 参数
+    ///
+    /// * `path` - Git仓库路径
+    /// * `reference` - 完整的引用名
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Some(String))` - blob 内容
+    /// * `Ok(None)` - 引用不存在
+    /// * `Err(SyncError)` - 引用指向的不是 blob 或读取失败
+    fn read_blob_ref(&self, path: &Path, reference: &str) -> Result<Option<String>>;
+
+    /// 后端支持的功能
+    ///
+    /// 默认支持所有功能，不支持某些功能的实现需要覆盖此方法
+    fn capabilities(&self) -> GitCapabilities {
+        GitCapabilities::ALL
+    }
 }
 
 // 重新导出具体实现
-pub use super::git_provider::{GitOperationsFactory, GitProvider, ProviderType};
+pub use super::git_provider::{
+    CustomGitBackend, GitBackendFactory, GitOperationsFactory, GitProvider, ProviderType,
+    register_git_backend, registered_git_backends,
+};
+pub use super::libgit2_git::Git2Operations;
 pub use super::mock_git::MockGitOperations;
 pub use super::real_git::RealGitOperations;
@@ -1,21 +1,122 @@
 //! Git提供者模块
 //!
-//! 提供统一的Git操作抽象，支持真实Git和Mock实现的无缝切换
+//! 提供统一的Git操作抽象，支持真实Git和Mock实现的无缝切换。
+//! 内置实现之外的后端（gitoxide、fast-import 等）通过 [`register_git_backend`]
+//! 按名称注册，下游 crate 无需修改本模块即可接入
+
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Display},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
+
+use super::command_env::{CommandEnv, CommandSettings};
+use super::git_operations::{
+    CommitMetadata, GitCapabilities, GitCommitEntry, GitOperations, GitSignature, RealGitOperations,
+};
+use super::libgit2_git::Git2Operations;
+use super::mock_git::MockGitOperations;
 
-use std::path::Path;
+/// 内置的Git提供者名称（含别名），不能被注册的后端使用
+const BUILTIN_PROVIDERS: [&str; 4] = ["real", "mock", "libgit2", "git2"];
 
-use super::git_operations::{GitOperations, RealGitOperations};
-use super::mock_git::MockGitOperations;
+/// 创建自定义Git后端的工厂，参数为执行外部命令时额外设置的环境变量
+pub type GitBackendFactory =
+    Arc<dyn Fn(&CommandEnv) -> Arc<dyn GitOperations + Send + Sync> + Send + Sync>;
+
+/// 按名称（小写）注册的自定义Git后端
+static GIT_BACKENDS: RwLock<BTreeMap<String, GitBackendFactory>> = RwLock::new(BTreeMap::new());
+
+/// 注册自定义Git后端
+///
+/// 注册后可以通过 `ProviderType::Custom(name)`、`"name".parse::<ProviderType>()`
+/// 或环境变量 `SVN2GIT_GIT_PROVIDER=name` 使用；重复注册同一名称时替换之前的工厂
+///
+/// # 参数
+///
+/// * `name` - 后端名称，不区分大小写
+/// * `factory` - 创建后端实例的工厂
+///
+/// # 返回值
+///
+/// * `Err(String)` - 名称为空或与内置提供者重名
+///
+/// # 示例
+///
+/// ```
+/// use std::sync::Arc;
+/// use svn2git::{GitProvider, MockGitOperations, ProviderType, register_git_backend};
+///
+/// register_git_backend("fast-import", |_env| Arc::new(MockGitOperations::new())).unwrap();
+/// let provider_type: ProviderType = "fast-import".parse().unwrap();
+/// let provider = GitProvider::new(provider_type.clone());
+/// assert_eq!(provider.provider_type(), provider_type);
+/// ```
+pub fn register_git_backend(
+    name: &str,
+    factory: impl Fn(&CommandEnv) -> Arc<dyn GitOperations + Send + Sync> + Send + Sync + 'static,
+) -> Result<(), String> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() || BUILTIN_PROVIDERS.contains(&name.as_str()) {
+        return Err(format!(
+            "无法注册Git后端 '{name}'：名称为空或与内置提供者重名"
+        ));
+    }
+    GIT_BACKENDS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(name, Arc::new(factory));
+    Ok(())
+}
+
+/// 已注册的自定义Git后端名称
+pub fn registered_git_backends() -> Vec<String> {
+    let backends = GIT_BACKENDS.read().unwrap_or_else(|e| e.into_inner());
+    backends.keys().cloned().collect()
+}
+
+fn git_backend_factory(name: &str) -> Option<GitBackendFactory> {
+    let backends = GIT_BACKENDS.read().unwrap_or_else(|e| e.into_inner());
+    backends.get(name).cloned()
+}
+
+/// 通过 [`register_git_backend`] 注册的Git后端实例
+#[derive(Clone)]
+pub struct CustomGitBackend {
+    name: String,
+    ops: Arc<dyn GitOperations + Send + Sync>,
+}
+
+impl CustomGitBackend {
+    /// 后端名称
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Debug for CustomGitBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomGitBackend")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
 
 /// Git提供者类型
 ///
-/// 支持真实Git操作和Mock操作两种实现方式
+/// 支持真实Git命令、libgit2和Mock三种内置实现，以及注册的自定义后端
 #[derive(Debug, Clone)]
 pub enum GitProvider {
     /// 真实Git操作实现
     Real(RealGitOperations),
     /// Mock Git操作实现（用于测试）
     Mock(MockGitOperations),
+    /// libgit2 Git操作实现
+    Libgit2(Git2Operations),
+    /// 注册的自定义后端
+    Custom(CustomGitBackend),
 }
 
 impl GitProvider {
@@ -41,9 +142,68 @@ impl GitProvider {
     /// let mock_provider = GitProvider::new(ProviderType::Mock);
     /// ```
     pub fn new(provider_type: ProviderType) -> Self {
+        Self::with_env(provider_type, CommandEnv::new())
+    }
+
+    /// 创建执行命令时附带额外环境变量的Git提供者
+    ///
+    /// Mock实现会忽略环境变量；libgit2实现不执行外部命令，不支持环境变量，
+    /// 同步开始前由 [`GitCapabilities::command_env`] 检查报错；自定义后端的环境变量交给注册的工厂。
+    /// 自定义后端未注册时输出警告并使用真实Git命令
+    ///
+    /// # 参数
+    ///
+    /// * `provider_type` - 提供者类型
+    /// * `env` - 额外的环境变量
+    pub fn with_env(provider_type: ProviderType, env: CommandEnv) -> Self {
         match provider_type {
-            ProviderType::Real => Self::Real(RealGitOperations::new()),
+            ProviderType::Real => Self::Real(RealGitOperations::with_env(env)),
             ProviderType::Mock => Self::Mock(MockGitOperations::new()),
+            ProviderType::Libgit2 => Self::Libgit2(Git2Operations::new()),
+            ProviderType::Custom(name) => match git_backend_factory(&name) {
+                Some(factory) => Self::Custom(CustomGitBackend {
+                    ops: factory(&env),
+                    name,
+                }),
+                None => {
+                    eprintln!("警告: 未注册的Git后端 '{name}', 使用默认的Real实现");
+                    Self::Real(RealGitOperations::with_env(env))
+                }
+            },
+        }
+    }
+
+    /// 设置执行git命令的设置
+    ///
+    /// 只对真实Git命令生效，其他实现不执行外部git命令，会忽略这些设置
+    ///
+    /// # 参数
+    ///
+    /// * `settings` - 执行命令的设置
+    pub fn with_settings(self, settings: CommandSettings) -> Self {
+        match self {
+            Self::Real(ops) => Self::Real(ops.with_settings(settings)),
+            other => other,
+        }
+    }
+
+    /// 获取提供者对应的类型
+    pub fn provider_type(&self) -> ProviderType {
+        match self {
+            GitProvider::Real(_) => ProviderType::Real,
+            GitProvider::Mock(_) => ProviderType::Mock,
+            GitProvider::Libgit2(_) => ProviderType::Libgit2,
+            GitProvider::Custom(backend) => ProviderType::Custom(backend.name.clone()),
+        }
+    }
+
+    /// 实际执行操作的实现，所有 [`GitOperations`] 方法都转发给它
+    fn ops(&self) -> &dyn GitOperations {
+        match self {
+            GitProvider::Real(ops) => ops,
+            GitProvider::Mock(ops) => ops,
+            GitProvider::Libgit2(ops) => ops,
+            GitProvider::Custom(backend) => backend.ops.as_ref(),
         }
     }
 
@@ -66,52 +226,151 @@ impl GitProvider {
 
 impl GitOperations for GitProvider {
     fn init(&self, path: &Path) -> crate::error::Result<()> {
-        match self {
-            GitProvider::Real(ops) => ops.init(path),
-            GitProvider::Mock(ops) => ops.init(path),
-        }
+        self.ops().init(path)
     }
 
     fn config_user(&self, path: &Path, name: &str, email: &str) -> crate::error::Result<()> {
-        match self {
-            GitProvider::Real(ops) => ops.config_user(path, name, email),
-            GitProvider::Mock(ops) => ops.config_user(path, name, email),
-        }
+        self.ops().config_user(path, name, email)
     }
 
     fn add_all(&self, path: &Path) -> crate::error::Result<()> {
-        match self {
-            GitProvider::Real(ops) => ops.add_all(path),
-            GitProvider::Mock(ops) => ops.add_all(path),
-        }
+        self.ops().add_all(path)
     }
 
     fn commit(&self, path: &Path, message: &str) -> crate::error::Result<()> {
-        match self {
-            GitProvider::Real(ops) => ops.commit(path, message),
-            GitProvider::Mock(ops) => ops.commit(path, message),
-        }
+        self.ops().commit(path, message)
+    }
+
+    fn commit_with(
+        &self,
+        path: &Path,
+        message: &str,
+        metadata: &CommitMetadata,
+    ) -> crate::error::Result<()> {
+        self.ops().commit_with(path, message, metadata)
     }
 
     fn status(&self, path: &Path) -> crate::error::Result<String> {
-        match self {
-            GitProvider::Real(ops) => ops.status(path),
-            GitProvider::Mock(ops) => ops.status(path),
-        }
+        self.ops().status(path)
     }
 
     fn log(&self, path: &Path, count: Option<usize>) -> crate::error::Result<String> {
-        match self {
-            GitProvider::Real(ops) => ops.log(path, count),
-            GitProvider::Mock(ops) => ops.log(path, count),
-        }
+        self.ops().log(path, count)
+    }
+
+    fn is_repository(&self, path: &Path) -> crate::error::Result<bool> {
+        self.ops().is_repository(path)
     }
 
     fn is_clean(&self, path: &Path) -> crate::error::Result<bool> {
-        match self {
-            GitProvider::Real(ops) => ops.is_clean(path),
-            GitProvider::Mock(ops) => ops.is_clean(path),
-        }
+        self.ops().is_clean(path)
+    }
+
+    fn rev_parse_head(&self, path: &Path) -> crate::error::Result<String> {
+        self.ops().rev_parse_head(path)
+    }
+
+    fn root_commit(&self, path: &Path) -> crate::error::Result<String> {
+        self.ops().root_commit(path)
+    }
+
+    fn write_blob_ref(
+        &self,
+        path: &Path,
+        reference: &str,
+        content: &str,
+    ) -> crate::error::Result<()> {
+        self.ops().write_blob_ref(path, reference, content)
+    }
+
+    fn read_blob_ref(&self, path: &Path, reference: &str) -> crate::error::Result<Option<String>> {
+        self.ops().read_blob_ref(path, reference)
+    }
+
+    fn commit_history(
+        &self,
+        path: &Path,
+        notes_ref: &str,
+    ) -> crate::error::Result<Vec<GitCommitEntry>> {
+        self.ops().commit_history(path, notes_ref)
+    }
+
+    fn tree_blobs(&self, path: &Path, rev: &str) -> crate::error::Result<BTreeMap<String, String>> {
+        self.ops().tree_blobs(path, rev)
+    }
+
+    fn hash_files(&self, path: &Path, files: &[PathBuf]) -> crate::error::Result<Vec<String>> {
+        self.ops().hash_files(path, files)
+    }
+
+    fn add_paths(&self, path: &Path, files: &[String]) -> crate::error::Result<()> {
+        self.ops().add_paths(path, files)
+    }
+
+    fn set_executable(&self, path: &Path, files: &[String]) -> crate::error::Result<()> {
+        self.ops().set_executable(path, files)
+    }
+
+    fn add_note(
+        &self,
+        path: &Path,
+        notes_ref: &str,
+        commit: &str,
+        note: &str,
+    ) -> crate::error::Result<()> {
+        self.ops().add_note(path, notes_ref, commit, note)
+    }
+
+    fn reset_to(&self, path: &Path, commit: Option<&str>) -> crate::error::Result<()> {
+        self.ops().reset_to(path, commit)
+    }
+
+    fn stash(&self, path: &Path, message: &str) -> crate::error::Result<bool> {
+        self.ops().stash(path, message)
+    }
+
+    fn stash_pop(&self, path: &Path) -> crate::error::Result<()> {
+        self.ops().stash_pop(path)
+    }
+
+    fn create_branch(
+        &self,
+        path: &Path,
+        name: &str,
+        start: Option<&str>,
+    ) -> crate::error::Result<()> {
+        self.ops().create_branch(path, name, start)
+    }
+
+    fn checkout_branch(&self, path: &Path, name: &str) -> crate::error::Result<()> {
+        self.ops().checkout_branch(path, name)
+    }
+
+    fn tag(
+        &self,
+        path: &Path,
+        name: &str,
+        target: &str,
+        message: &str,
+        tagger: Option<&GitSignature>,
+    ) -> crate::error::Result<()> {
+        self.ops().tag(path, name, target, message, tagger)
+    }
+
+    fn add_remote(&self, path: &Path, name: &str, url: &str) -> crate::error::Result<()> {
+        self.ops().add_remote(path, name, url)
+    }
+
+    fn push(&self, path: &Path, remote: &str) -> crate::error::Result<()> {
+        self.ops().push(path, remote)
+    }
+
+    fn bundle(&self, path: &Path, file: &Path) -> crate::error::Result<()> {
+        self.ops().bundle(path, file)
+    }
+
+    fn capabilities(&self) -> GitCapabilities {
+        self.ops().capabilities()
     }
 }
 
@@ -124,6 +383,45 @@ pub enum ProviderType {
     Real,
     /// 使用Mock实现（用于测试）
     Mock,
+    /// 使用 libgit2（git2 crate），不依赖外部 git 命令
+    Libgit2,
+    /// 使用通过 [`register_git_backend`] 注册的后端（名称为小写）
+    Custom(String),
+}
+
+impl FromStr for ProviderType {
+    type Err = String;
+
+    /// 解析提供者名称，不区分大小写；自定义后端需要已注册
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase();
+        match name.as_str() {
+            "real" => Ok(Self::Real),
+            "mock" => Ok(Self::Mock),
+            "libgit2" | "git2" => Ok(Self::Libgit2),
+            _ if git_backend_factory(&name).is_some() => Ok(Self::Custom(name)),
+            _ => {
+                let mut supported = vec!["real".to_string(), "mock".into(), "libgit2".into()];
+                supported.extend(registered_git_backends());
+                Err(format!(
+                    "无效的Git提供者类型: {}。支持的类型: {}",
+                    s,
+                    supported.join(", ")
+                ))
+            }
+        }
+    }
+}
+
+impl Display for ProviderType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProviderType::Real => write!(f, "real"),
+            ProviderType::Mock => write!(f, "mock"),
+            ProviderType::Libgit2 => write!(f, "libgit2"),
+            ProviderType::Custom(name) => write!(f, "{name}"),
+        }
+    }
 }
 
 /// Git操作工厂
@@ -149,21 +447,14 @@ impl GitOperationsFactory {
     ///
     /// # 参数
     ///
-    /// * `type_str` - 提供者类型字符串 ("real" 或 "mock")
+    /// * `type_str` - 提供者类型字符串 ("real"、"mock"、"libgit2" 或已注册的自定义后端名称)
     ///
     /// # 返回值
     ///
     /// * `Ok(GitProvider)` - 创建成功
     /// * `Err(String)` - 无效的类型字符串
     pub fn create_from_string(type_str: &str) -> Result<GitProvider, String> {
-        match type_str.to_lowercase().as_str() {
-            "real" => Ok(GitProvider::new(ProviderType::Real)),
-            "mock" => Ok(GitProvider::new(ProviderType::Mock)),
-            _ => Err(format!(
-                "无效的Git提供者类型: {}。支持的类型: real, mock",
-                type_str
-            )),
-        }
+        type_str.parse().map(GitProvider::new)
     }
 
     /// 根据环境变量创建Git操作实例
@@ -236,6 +527,15 @@ mod tests {
         assert!(matches!(mock_result.unwrap(), GitProvider::Mock(_)));
         assert!(matches!(upper_result.unwrap(), GitProvider::Real(_)));
 
+        assert!(matches!(
+            GitOperationsFactory::create_from_string("libgit2").unwrap(),
+            GitProvider::Libgit2(_)
+        ));
+        assert!(matches!(
+            GitOperationsFactory::create_from_string("git2").unwrap(),
+            GitProvider::Libgit2(_)
+        ));
+
         // 测试无效输入
         let invalid_result = GitOperationsFactory::create_from_string("invalid");
         assert!(invalid_result.is_err());
@@ -263,4 +563,32 @@ mod tests {
         assert_eq!(ProviderType::Mock, ProviderType::Mock);
         assert_ne!(ProviderType::Real, ProviderType::Mock);
     }
+
+    #[test]
+    fn test_registered_backend_should_be_created_by_name() {
+        let err = register_git_backend("Real", |_| Arc::new(MockGitOperations::new()));
+        assert!(err.is_err());
+
+        register_git_backend("Test-Backend", |env| {
+            assert_eq!(env.get("GIT_SSH").map(String::as_str), Some("ssh -i key"));
+            Arc::new(MockGitOperations::new())
+        })
+        .unwrap();
+        assert!(registered_git_backends().contains(&"test-backend".to_string()));
+
+        let provider_type: ProviderType = "TEST-backend".parse().unwrap();
+        assert_eq!(provider_type, ProviderType::Custom("test-backend".into()));
+        assert_eq!(provider_type.to_string(), "test-backend");
+
+        let env = CommandEnv::from([("GIT_SSH".to_string(), "ssh -i key".to_string())]);
+        let provider = GitProvider::with_env(provider_type.clone(), env);
+        assert!(
+            matches!(&provider, GitProvider::Custom(backend) if backend.name() == "test-backend")
+        );
+        assert_eq!(provider.provider_type(), provider_type);
+        assert!(provider.init(Path::new("/test/repo")).is_ok());
+
+        let err = GitOperationsFactory::create_from_string("gitoxide").unwrap_err();
+        assert!(err.contains("test-backend"));
+    }
 }
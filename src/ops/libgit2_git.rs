@@ -0,0 +1,781 @@
+//! libgit2 Git操作实现
+//!
+//! 通过 `git2` 库直接读写仓库，不依赖外部 git 命令，也没有逐条命令的进程开销
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use git2::{
+    ErrorCode, IndexAddOption, ObjectType, Oid, PushOptions, RemoteCallbacks, Repository,
+    ResetType, Signature, Status, StatusOptions, Time, TreeWalkMode, TreeWalkResult,
+    build::CheckoutBuilder,
+};
+
+use super::git_operations::{
+    CommitMetadata, GitCapabilities, GitCommitEntry, GitOperations, GitSignature,
+};
+use crate::error::{Result, SyncError};
+
+/// libgit2 Git操作实现
+///
+/// 与 [`RealGitOperations`](super::RealGitOperations) 行为保持一致，以下差异除外：
+///
+/// * 不执行外部命令，因此不使用同步对配置的环境变量
+/// * `hash_files` 直接计算文件内容的哈希，不应用 `.gitattributes` 中的换行符等过滤器
+#[derive(Debug, Clone, Default)]
+pub struct Git2Operations;
+
+impl Git2Operations {
+    /// 创建新的libgit2 Git操作实例
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use svn2git::Git2Operations;
+    ///
+    /// let git_ops = Git2Operations::new();
+    /// ```
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// 打开路径所在的仓库（与在该目录执行 git 命令一样向上查找）
+    fn open(path: &Path) -> Result<Repository> {
+        Repository::discover(path)
+            .map_err(|e| SyncError::App(format!("打开Git仓库失败，路径: {:?}, 错误: {}", path, e)))
+    }
+
+    /// 提交使用的签名（读取仓库的 user.name / user.email 配置）
+    fn signature(repo: &Repository) -> Result<Signature<'static>> {
+        repo.signature().map_err(|e| {
+            SyncError::App(format!(
+                "读取Git用户信息失败，请先配置 user.name 和 user.email，错误: {}",
+                e
+            ))
+        })
+    }
+
+    /// 提交暂存区，按元数据设置作者，指定时间时作者和提交者时间都使用该时间
+    fn commit_with_metadata(
+        &self,
+        path: &Path,
+        message: &str,
+        metadata: &CommitMetadata,
+    ) -> Result<()> {
+        let repo = Self::open(path)?;
+        let user = Self::signature(&repo)?;
+        let time = match metadata.date {
+            Some(date) => Time::new(date.timestamp(), date.offset().local_minus_utc() / 60),
+            None => user.when(),
+        };
+        let author = Signature::new(
+            metadata
+                .author_name
+                .as_deref()
+                .or(user.name())
+                .unwrap_or_default(),
+            metadata
+                .author_email
+                .as_deref()
+                .or(user.email())
+                .unwrap_or_default(),
+            &time,
+        )?;
+        let committer = Signature::new(
+            user.name().unwrap_or_default(),
+            user.email().unwrap_or_default(),
+            &time,
+        )?;
+        let tree = repo.find_tree(repo.index()?.write_tree()?)?;
+
+        let parent = match repo.head() {
+            Ok(head) => Some(head.peel_to_commit()?),
+            Err(_) => None,
+        };
+        if !metadata.allow_empty && parent.as_ref().is_some_and(|p| p.tree_id() == tree.id()) {
+            return Err(SyncError::App(format!(
+                "Git commit失败，路径: {:?}, 提交信息: '{}', 没有需要提交的更改",
+                path, message
+            )));
+        }
+
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &author, &committer, message, &tree, &parents)?;
+        Ok(())
+    }
+}
+
+impl GitOperations for Git2Operations {
+    fn init(&self, path: &Path) -> Result<()> {
+        Repository::init(path)
+            .map_err(|e| SyncError::App(format!("Git初始化失败，路径: {:?}, 错误: {}", path, e)))?;
+        Ok(())
+    }
+
+    fn config_user(&self, path: &Path, name: &str, email: &str) -> Result<()> {
+        let repo = Self::open(path)?;
+        let mut config = repo.config()?;
+        config.set_str("user.name", name)?;
+        config.set_str("user.email", email)?;
+        Ok(())
+    }
+
+    fn add_all(&self, path: &Path) -> Result<()> {
+        self.add_paths(path, &[".".to_string()])
+    }
+
+    fn add_paths(&self, path: &Path, files: &[String]) -> Result<()> {
+        let repo = Self::open(path)?;
+        let mut index = repo.index()?;
+        // add_all 只处理新增和修改，update_all 补上已删除的文件
+        index.add_all(files, IndexAddOption::DEFAULT, None)?;
+        index.update_all(files, None)?;
+        index.write()?;
+        Ok(())
+    }
+
+    fn set_executable(&self, path: &Path, files: &[String]) -> Result<()> {
+        let repo = Self::open(path)?;
+        let mut index = repo.index()?;
+        for file in files {
+            let mut entry = index.get_path(Path::new(file), 0).ok_or_else(|| {
+                SyncError::App(format!(
+                    "设置Git可执行位失败，路径: {:?}, 文件不在暂存区: {}",
+                    path, file
+                ))
+            })?;
+            entry.mode = 0o100755;
+            index.add(&entry)?;
+        }
+        index.write()?;
+        Ok(())
+    }
+
+    fn commit(&self, path: &Path, message: &str) -> Result<()> {
+        self.commit_with_metadata(path, message, &CommitMetadata::default())
+    }
+
+    fn commit_with(&self, path: &Path, message: &str, metadata: &CommitMetadata) -> Result<()> {
+        self.commit_with_metadata(path, message, metadata)
+    }
+
+    fn status(&self, path: &Path) -> Result<String> {
+        let repo = Self::open(path)?;
+        let mut options = StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .include_ignored(false);
+
+        let mut output = String::new();
+        for entry in repo.statuses(Some(&mut options))?.iter() {
+            let file = entry.path().unwrap_or_default();
+            output.push_str(&format!("{} {}\n", porcelain_code(entry.status()), file));
+        }
+        Ok(output)
+    }
+
+    fn log(&self, path: &Path, count: Option<usize>) -> Result<String> {
+        let repo = Self::open(path)?;
+        let mut walk = repo.revwalk()?;
+        walk.push_head()?;
+
+        let mut output = String::new();
+        for oid in walk.take(count.unwrap_or(usize::MAX)) {
+            let commit = repo.find_commit(oid?)?;
+            let id = commit.id().to_string();
+            output.push_str(&format!(
+                "{} {}\n",
+                &id[..7],
+                commit.summary().unwrap_or_default()
+            ));
+        }
+        Ok(output)
+    }
+
+    fn is_repository(&self, path: &Path) -> Result<bool> {
+        // open 不会向上查找，位于其他仓库中的子目录不算
+        Ok(Repository::open(path).is_ok_and(|repo| !repo.is_bare()))
+    }
+
+    fn is_clean(&self, path: &Path) -> Result<bool> {
+        Ok(self.status(path)?.trim().is_empty())
+    }
+
+    fn rev_parse_head(&self, path: &Path) -> Result<String> {
+        let repo = Self::open(path)?;
+        let head = repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .map_err(|e| {
+                SyncError::App(format!("获取Git HEAD失败，路径: {:?}, 错误: {}", path, e))
+            })?;
+        Ok(head.id().to_string())
+    }
+
+    fn root_commit(&self, path: &Path) -> Result<String> {
+        let repo = Self::open(path)?;
+        let mut walk = repo.revwalk()?;
+        walk.push_head()?;
+        walk.simplify_first_parent()?;
+        let mut root = None;
+        for oid in walk {
+            root = Some(oid?);
+        }
+        root.map(|oid| oid.to_string())
+            .ok_or_else(|| SyncError::App(format!("Git仓库还没有任何提交，路径: {:?}", path)))
+    }
+
+    fn commit_history(&self, path: &Path, notes_ref: &str) -> Result<Vec<GitCommitEntry>> {
+        let repo = Self::open(path)?;
+        let mut walk = repo.revwalk()?;
+        walk.push_head()?;
+        walk.simplify_first_parent()?;
+        walk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+        let mut entries = Vec::new();
+        for oid in walk {
+            let oid = oid?;
+            let commit = repo.find_commit(oid)?;
+            let note = repo
+                .find_note(Some(notes_ref), oid)
+                .ok()
+                .and_then(|note| note.message().map(|message| message.trim_end().to_string()));
+            entries.push(GitCommitEntry {
+                hash: oid.to_string(),
+                message: String::from_utf8_lossy(commit.message_bytes())
+                    .trim_end()
+                    .to_string(),
+                note,
+            });
+        }
+        Ok(entries)
+    }
+
+    fn tree_blobs(&self, path: &Path, rev: &str) -> Result<BTreeMap<String, String>> {
+        let repo = Self::open(path)?;
+        let tree = repo
+            .revparse_single(rev)
+            .and_then(|object| object.peel_to_tree())
+            .map_err(|e| {
+                SyncError::App(format!(
+                    "读取Git提交 {} 的文件列表失败，路径: {:?}, 错误: {}",
+                    rev, path, e
+                ))
+            })?;
+
+        let mut blobs = BTreeMap::new();
+        tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+            if entry.kind() == Some(ObjectType::Blob) {
+                let name = entry.name().unwrap_or_default();
+                blobs.insert(format!("{root}{name}"), entry.id().to_string());
+            }
+            TreeWalkResult::Ok
+        })?;
+        Ok(blobs)
+    }
+
+    fn hash_files(&self, _path: &Path, files: &[PathBuf]) -> Result<Vec<String>> {
+        files
+            .iter()
+            .map(|file| Ok(Oid::hash_file(ObjectType::Blob, file)?.to_string()))
+            .collect()
+    }
+
+    fn add_note(&self, path: &Path, notes_ref: &str, commit: &str, note: &str) -> Result<()> {
+        let repo = Self::open(path)?;
+        let signature = Self::signature(&repo)?;
+        let oid = repo
+            .revparse_single(commit)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| {
+                SyncError::App(format!(
+                    "添加Git note失败，路径: {:?}, 提交: {}, 错误: {}",
+                    path, commit, e
+                ))
+            })?
+            .id();
+        repo.note(&signature, &signature, Some(notes_ref), oid, note, true)?;
+        Ok(())
+    }
+
+    fn reset_to(&self, path: &Path, commit: Option<&str>) -> Result<()> {
+        let repo = Self::open(path)?;
+        let Some(commit) = commit else {
+            if let Ok(mut head) = repo.head() {
+                head.delete()?;
+            }
+            let mut index = repo.index()?;
+            index.clear()?;
+            index.write()?;
+            return Ok(());
+        };
+
+        let target = repo.revparse_single(commit).map_err(|e| {
+            SyncError::App(format!(
+                "Git reset失败，路径: {:?}, 目标: {}, 错误: {}",
+                path, commit, e
+            ))
+        })?;
+        repo.reset(&target, ResetType::Mixed, None)?;
+        Ok(())
+    }
+
+    fn stash(&self, path: &Path, message: &str) -> Result<bool> {
+        let mut repo = Self::open(path)?;
+        let signature = Self::signature(&repo)?;
+        match repo.stash_save(&signature, message, None) {
+            Ok(_) => Ok(true),
+            Err(e) if e.code() == ErrorCode::NotFound => Ok(false),
+            Err(e) => Err(SyncError::App(format!(
+                "暂存Git更改失败，路径: {:?}, 错误: {}",
+                path, e
+            ))),
+        }
+    }
+
+    fn stash_pop(&self, path: &Path) -> Result<()> {
+        let mut repo = Self::open(path)?;
+        repo.stash_pop(0, None).map_err(|e| {
+            SyncError::App(format!(
+                "恢复暂存的Git更改失败，路径: {:?}, 错误: {}",
+                path, e
+            ))
+        })
+    }
+
+    fn create_branch(&self, path: &Path, name: &str, start: Option<&str>) -> Result<()> {
+        let repo = Self::open(path)?;
+        let start = start.unwrap_or("HEAD");
+        let commit = repo
+            .revparse_single(start)
+            .and_then(|object| object.peel_to_commit())
+            .and_then(|commit| repo.branch(name, &commit, false).map(|_| ()));
+        commit.map_err(|e| {
+            SyncError::App(format!(
+                "创建Git分支失败，路径: {:?}, 分支: {}, 错误: {}",
+                path, name, e
+            ))
+        })
+    }
+
+    fn checkout_branch(&self, path: &Path, name: &str) -> Result<()> {
+        let repo = Self::open(path)?;
+        let reference = format!("refs/heads/{name}");
+        if repo.head().is_ok() {
+            let branch = repo.revparse_single(&reference).map_err(|e| {
+                SyncError::App(format!(
+                    "切换Git分支失败，路径: {:?}, 分支: {}, 错误: {}",
+                    path, name, e
+                ))
+            })?;
+            repo.checkout_tree(&branch, Some(CheckoutBuilder::new().safe()))?;
+        }
+        repo.set_head(&reference)?;
+        Ok(())
+    }
+
+    fn tag(
+        &self,
+        path: &Path,
+        name: &str,
+        target: &str,
+        message: &str,
+        tagger: Option<&GitSignature>,
+    ) -> Result<()> {
+        let repo = Self::open(path)?;
+        let signature = match tagger {
+            Some(GitSignature {
+                name,
+                email,
+                date: Some(date),
+            }) => Signature::new(name, email, &Time::new(date.timestamp(), 0))?,
+            Some(tagger) => Signature::now(&tagger.name, &tagger.email)?,
+            None => Self::signature(&repo)?,
+        };
+        let object = repo
+            .revparse_single(target)
+            .and_then(|object| repo.tag(name, &object, &signature, message, false));
+        object.map(|_| ()).map_err(|e| {
+            SyncError::App(format!(
+                "创建Git标签失败，路径: {:?}, 标签: {}, 目标: {}, 错误: {}",
+                path, name, target, e
+            ))
+        })
+    }
+
+    fn add_remote(&self, path: &Path, name: &str, url: &str) -> Result<()> {
+        let repo = Self::open(path)?;
+        let result = match repo.find_remote(name) {
+            Ok(_) => repo.remote_set_url(name, url),
+            Err(_) => repo.remote(name, url).map(|_| ()),
+        };
+        result.map_err(|e| {
+            SyncError::App(format!(
+                "设置Git远程仓库失败，路径: {:?}, 远程仓库: {}, 地址: {}, 错误: {}",
+                path, name, url, e
+            ))
+        })
+    }
+
+    fn push(&self, path: &Path, remote: &str) -> Result<()> {
+        let fail = |e: &dyn std::fmt::Display| {
+            SyncError::App(format!(
+                "Git推送失败，路径: {:?}, 远程仓库: {}, 错误: {}",
+                path, remote, e
+            ))
+        };
+        let repo = Self::open(path)?;
+        // libgit2 推送不支持通配符 refspec，逐个列出本地分支、标签和镜像标记
+        let mut refspecs = Vec::new();
+        for reference in repo.references().map_err(|e| fail(&e))? {
+            let reference = reference.map_err(|e| fail(&e))?;
+            match reference.name() {
+                Some(name) if name.starts_with("refs/heads/") || name.starts_with("refs/tags/") => {
+                    refspecs.push(format!("{name}:{name}"));
+                }
+                // 镜像标记指向 blob，无法判断是否快进，总是覆盖
+                Some(name) if name.starts_with("refs/svn2git/") => {
+                    refspecs.push(format!("+{name}:{name}"));
+                }
+                _ => {}
+            }
+        }
+
+        let mut rejected = Vec::new();
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.push_update_reference(|name, status| {
+            if let Some(status) = status {
+                rejected.push(format!("{name}（{status}）"));
+            }
+            Ok(())
+        });
+        let mut options = PushOptions::new();
+        options.remote_callbacks(callbacks);
+        repo.find_remote(remote)
+            .and_then(|mut r| r.push(&refspecs, Some(&mut options)))
+            .map_err(|e| fail(&e))?;
+        drop(options);
+
+        if !rejected.is_empty() {
+            return Err(fail(&format!("远程仓库拒绝更新 {}", rejected.join("、"))));
+        }
+        Ok(())
+    }
+
+    fn bundle(&self, _path: &Path, _file: &Path) -> Result<()> {
+        Err(SyncError::App("libgit2 后端不支持生成 git bundle".into()))
+    }
+
+    fn write_blob_ref(&self, path: &Path, reference: &str, content: &str) -> Result<()> {
+        let repo = Self::open(path)?;
+        let blob = repo.blob(content.as_bytes())?;
+        repo.reference(reference, blob, true, "svn2git: update state")?;
+        Ok(())
+    }
+
+    fn read_blob_ref(&self, path: &Path, reference: &str) -> Result<Option<String>> {
+        let repo = Self::open(path)?;
+        let object = match repo.find_reference(reference) {
+            Ok(found) => found.peel(git2::ObjectType::Blob)?,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let blob = object
+            .as_blob()
+            .ok_or_else(|| SyncError::App(format!("Git引用 {reference} 指向的不是 blob")))?;
+        Ok(Some(String::from_utf8_lossy(blob.content()).into_owned()))
+    }
+
+    fn capabilities(&self) -> GitCapabilities {
+        // 创建提交时不读取 commit.gpgsign，不会签名；libgit2 没有 bundle 接口，
+        // 也不执行外部命令，同步对配置的环境变量不会生效
+        GitCapabilities {
+            signing: false,
+            bundle: false,
+            command_env: false,
+            ..GitCapabilities::ALL
+        }
+    }
+}
+
+/// 将 libgit2 的文件状态转换为 `git status --porcelain` 的两位状态码
+fn porcelain_code(status: Status) -> String {
+    if status.is_conflicted() {
+        return "UU".to_string();
+    }
+    if status.is_wt_new() && !status.is_index_new() {
+        return "??".to_string();
+    }
+
+    let index = if status.is_index_new() {
+        'A'
+    } else if status.is_index_modified() || status.is_index_typechange() {
+        'M'
+    } else if status.is_index_deleted() {
+        'D'
+    } else if status.is_index_renamed() {
+        'R'
+    } else {
+        ' '
+    };
+    let worktree = if status.is_wt_modified() || status.is_wt_typechange() {
+        'M'
+    } else if status.is_wt_deleted() {
+        'D'
+    } else if status.is_wt_renamed() {
+        'R'
+    } else {
+        ' '
+    };
+    format!("{index}{worktree}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn init_repo() -> (tempfile::TempDir, Git2Operations) {
+        let dir = tempfile::tempdir().unwrap();
+        let ops = Git2Operations::new();
+        ops.init(dir.path()).unwrap();
+        ops.config_user(dir.path(), "测试用户", "test@example.com")
+            .unwrap();
+        (dir, ops)
+    }
+
+    #[test]
+    fn test_commit_and_read_back() {
+        let (dir, ops) = init_repo();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        assert_eq!(ops.status(dir.path()).unwrap(), "?? src/main.rs\n");
+
+        ops.add_all(dir.path()).unwrap();
+        ops.commit(dir.path(), "SVN: 初始提交").unwrap();
+        assert!(ops.is_clean(dir.path()).unwrap());
+        assert!(ops.commit(dir.path(), "没有更改").is_err());
+
+        let head = ops.rev_parse_head(dir.path()).unwrap();
+        assert_eq!(head.len(), 40);
+        assert!(ops.log(dir.path(), None).unwrap().contains("SVN: 初始提交"));
+
+        let blobs = ops.tree_blobs(dir.path(), &head).unwrap();
+        let hashes = ops
+            .hash_files(dir.path(), &[dir.path().join("src/main.rs")])
+            .unwrap();
+        assert_eq!(blobs.get("src/main.rs"), Some(&hashes[0]));
+    }
+
+    #[test]
+    fn test_root_commit_should_follow_first_parent() {
+        let (dir, ops) = init_repo();
+        assert!(ops.root_commit(dir.path()).is_err());
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        ops.add_all(dir.path()).unwrap();
+        ops.commit(dir.path(), "first").unwrap();
+        let root = ops.rev_parse_head(dir.path()).unwrap();
+
+        fs::write(dir.path().join("a.txt"), "b").unwrap();
+        ops.add_all(dir.path()).unwrap();
+        ops.commit(dir.path(), "second").unwrap();
+        assert_eq!(ops.root_commit(dir.path()).unwrap(), root);
+    }
+
+    #[test]
+    fn test_add_paths_should_stage_deletions() {
+        let (dir, ops) = init_repo();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        fs::write(dir.path().join("b.txt"), "b").unwrap();
+        ops.add_all(dir.path()).unwrap();
+        ops.commit(dir.path(), "first").unwrap();
+
+        fs::remove_file(dir.path().join("a.txt")).unwrap();
+        fs::write(dir.path().join("b.txt"), "b2").unwrap();
+        ops.add_paths(dir.path(), &["a.txt".to_string()]).unwrap();
+        assert_eq!(ops.status(dir.path()).unwrap(), "D  a.txt\n M b.txt\n");
+    }
+
+    #[test]
+    fn test_add_note() {
+        let (dir, ops) = init_repo();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        ops.add_all(dir.path()).unwrap();
+        ops.commit(dir.path(), "first").unwrap();
+
+        ops.add_note(dir.path(), "refs/notes/svn", "HEAD", "SVN-Revision: 1")
+            .unwrap();
+        let repo = Repository::open(dir.path()).unwrap();
+        let head = repo.head().unwrap().target().unwrap();
+        let note = repo.find_note(Some("refs/notes/svn"), head).unwrap();
+        assert_eq!(note.message(), Some("SVN-Revision: 1"));
+    }
+
+    #[test]
+    fn test_rev_parse_head_on_invalid_path() {
+        let ops = Git2Operations::new();
+        assert!(ops.rev_parse_head(Path::new("/不存在的路径")).is_err());
+    }
+
+    #[test]
+    fn test_reset_to_should_keep_working_tree() {
+        let (dir, ops) = init_repo();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        ops.add_all(dir.path()).unwrap();
+        ops.commit(dir.path(), "first").unwrap();
+        let first = ops.rev_parse_head(dir.path()).unwrap();
+
+        fs::write(dir.path().join("b.txt"), "b").unwrap();
+        ops.add_all(dir.path()).unwrap();
+        ops.commit(dir.path(), "second").unwrap();
+
+        ops.reset_to(dir.path(), Some(&first)).unwrap();
+        assert_eq!(ops.rev_parse_head(dir.path()).unwrap(), first);
+        assert_eq!(ops.status(dir.path()).unwrap(), "?? b.txt\n");
+
+        ops.reset_to(dir.path(), None).unwrap();
+        assert!(ops.rev_parse_head(dir.path()).is_err());
+        assert_eq!(ops.status(dir.path()).unwrap(), "?? a.txt\n?? b.txt\n");
+    }
+
+    #[test]
+    fn test_create_and_checkout_branch() {
+        let (dir, ops) = init_repo();
+        ops.checkout_branch(dir.path(), "master").unwrap();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        ops.add_all(dir.path()).unwrap();
+        ops.commit(dir.path(), "trunk").unwrap();
+        let trunk = ops.rev_parse_head(dir.path()).unwrap();
+
+        ops.create_branch(dir.path(), "dev", None).unwrap();
+        assert!(ops.create_branch(dir.path(), "dev", None).is_err());
+        ops.checkout_branch(dir.path(), "dev").unwrap();
+        fs::write(dir.path().join("b.txt"), "b").unwrap();
+        ops.add_all(dir.path()).unwrap();
+        ops.commit(dir.path(), "dev").unwrap();
+
+        ops.checkout_branch(dir.path(), "master").unwrap();
+        assert_eq!(ops.rev_parse_head(dir.path()).unwrap(), trunk);
+        assert!(!dir.path().join("b.txt").exists());
+        assert!(ops.checkout_branch(dir.path(), "missing").is_err());
+    }
+
+    #[test]
+    fn test_tag_with_tagger() {
+        let (dir, ops) = init_repo();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        ops.add_all(dir.path()).unwrap();
+        ops.commit(dir.path(), "first").unwrap();
+
+        let tagger = GitSignature {
+            name: "alice".into(),
+            email: "alice@uuid".into(),
+            date: chrono::DateTime::from_timestamp(1_700_000_000, 0),
+        };
+        ops.tag(dir.path(), "v1.0", "HEAD", "release", Some(&tagger))
+            .unwrap();
+        assert!(ops.tag(dir.path(), "v1.0", "HEAD", "again", None).is_err());
+
+        let repo = Repository::open(dir.path()).unwrap();
+        let tag = repo
+            .revparse_single("refs/tags/v1.0")
+            .unwrap()
+            .peel_to_tag()
+            .unwrap();
+        let signature = tag.tagger().unwrap();
+        assert_eq!(signature.name(), Some("alice"));
+        assert_eq!(signature.when().seconds(), 1_700_000_000);
+        assert_eq!(tag.message(), Some("release"));
+    }
+
+    #[test]
+    fn test_commit_with_should_set_author_and_keep_offset() {
+        let (dir, ops) = init_repo();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        ops.add_all(dir.path()).unwrap();
+        let date = chrono::DateTime::parse_from_rfc3339("2024-01-02T11:04:05+08:00").unwrap();
+        let metadata = CommitMetadata {
+            author_name: Some("alice".into()),
+            author_email: Some("alice@uuid".into()),
+            date: Some(date),
+            ..Default::default()
+        };
+        ops.commit_with(dir.path(), "dated", &metadata).unwrap();
+
+        let repo = Repository::open(dir.path()).unwrap();
+        let commit = repo.head().unwrap().peel_to_commit().unwrap();
+        for signature in [commit.author(), commit.committer()] {
+            assert_eq!(signature.when().seconds(), date.timestamp());
+            assert_eq!(signature.when().offset_minutes(), 480);
+        }
+        assert_eq!(commit.author().name(), Some("alice"));
+        assert_eq!(commit.author().email(), Some("alice@uuid"));
+        assert_eq!(commit.committer().name(), Some("测试用户"));
+    }
+
+    #[test]
+    fn test_commit_with_allow_empty_should_commit_unchanged_tree() {
+        let (dir, ops) = init_repo();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        ops.add_all(dir.path()).unwrap();
+        ops.commit(dir.path(), "first").unwrap();
+        assert!(ops.commit(dir.path(), "empty").is_err());
+
+        let metadata = CommitMetadata {
+            allow_empty: true,
+            ..Default::default()
+        };
+        ops.commit_with(dir.path(), "empty", &metadata).unwrap();
+        let log = ops.log(dir.path(), None).unwrap();
+        assert_eq!(log.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_set_executable_should_update_index_mode() {
+        let (dir, ops) = init_repo();
+        fs::write(dir.path().join("build.sh"), "#!/bin/sh").unwrap();
+        ops.add_all(dir.path()).unwrap();
+        ops.set_executable(dir.path(), &["build.sh".to_string()])
+            .unwrap();
+        assert!(
+            ops.set_executable(dir.path(), &["missing.sh".to_string()])
+                .is_err()
+        );
+
+        let index = Repository::open(dir.path()).unwrap().index().unwrap();
+        let entry = index.get_path(Path::new("build.sh"), 0).unwrap();
+        assert_eq!(entry.mode, 0o100755);
+    }
+
+    #[test]
+    fn test_push_should_update_remote_and_reject_diverged_history() {
+        let (dir, ops) = init_repo();
+        let remote = tempfile::tempdir().unwrap();
+        Repository::init_bare(remote.path()).unwrap();
+        let url = remote.path().to_str().unwrap();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        ops.add_all(dir.path()).unwrap();
+        ops.commit(dir.path(), "first").unwrap();
+        ops.tag(dir.path(), "v1.0", "HEAD", "release", None)
+            .unwrap();
+
+        assert!(ops.push(dir.path(), "origin").is_err());
+        ops.add_remote(dir.path(), "origin", "/missing").unwrap();
+        ops.add_remote(dir.path(), "origin", url).unwrap();
+        ops.push(dir.path(), "origin").unwrap();
+        let head = ops.rev_parse_head(dir.path()).unwrap();
+        let bare = Repository::open_bare(remote.path()).unwrap();
+        assert_eq!(bare.head().unwrap().target().unwrap().to_string(), head);
+        assert!(bare.find_reference("refs/tags/v1.0").is_ok());
+
+        // 本地历史与远程分叉时拒绝推送，本地提交保留
+        ops.reset_to(dir.path(), None).unwrap();
+        ops.add_all(dir.path()).unwrap();
+        ops.commit(dir.path(), "rewritten").unwrap();
+        let rewritten = ops.rev_parse_head(dir.path()).unwrap();
+        assert!(ops.push(dir.path(), "origin").is_err());
+        assert_eq!(ops.rev_parse_head(dir.path()).unwrap(), rewritten);
+    }
+}
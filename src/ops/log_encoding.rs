@@ -0,0 +1,114 @@
+//! 旧 SVN 仓库中非 UTF-8 提交说明的转码
+//!
+//! 早期的 SVN 客户端按本机编码（如 GBK、Big5）写入提交说明，之后读出时常见两种情况：
+//! - 原始字节不是 UTF-8（如 `svnadmin dump` 中的属性、部分服务端的 `svn log --xml` 输出），直接解析会失败
+//! - 字节被当作 Latin-1 转成了 UTF-8，得到 `ÐÞ¸Ä` 这样的乱码
+//!
+//! 同步对设置了编码（[`CommandSettings::log_encoding`](super::CommandSettings::log_encoding)）后，
+//! 两种情况都按该编码转为 UTF-8；本身就是正常 UTF-8 的内容不受影响。
+
+use std::borrow::Cow;
+
+use encoding_rs::Encoding;
+
+use crate::error::{Result, SyncError};
+
+/// 按名称查找旧提交说明的编码
+///
+/// # 参数
+///
+/// * `label`: 编码名称，如 `gbk`、`gb18030`、`big5`、`shift_jis`
+///
+/// # 返回
+///
+/// * `Err(SyncError)` - 不认识的编码名称
+pub(crate) fn encoding_for_label(label: &str) -> Result<&'static Encoding> {
+    Encoding::for_label(label.trim().as_bytes()).ok_or_else(|| {
+        SyncError::App(format!(
+            "不支持的编码 {label}，可用如 gbk、gb18030、big5、shift_jis、euc-kr"
+        ))
+    })
+}
+
+/// 把 SVN 输出的字节转为文本
+///
+/// 是合法的 UTF-8 时原样返回，否则按设置的编码解码
+///
+/// # 参数
+///
+/// * `bytes`: SVN 输出
+/// * `encoding`: 旧提交说明的编码
+///
+/// # 返回
+///
+/// 不是 UTF-8 且没有设置编码时返回 `None`
+pub(crate) fn decode_log_bytes<'a>(
+    bytes: &'a [u8],
+    encoding: Option<&'static Encoding>,
+) -> Option<Cow<'a, str>> {
+    match (str::from_utf8(bytes), encoding) {
+        (Ok(text), _) => Some(Cow::Borrowed(text)),
+        (Err(_), Some(encoding)) => Some(encoding.decode_without_bom_handling(bytes).0),
+        (Err(_), None) => None,
+    }
+}
+
+/// 修复被当作 Latin-1 转成 UTF-8 的旧编码文本
+///
+/// 只有全部字符都在 Latin-1 范围内、含有非 ASCII 字符，且还原出的字节按设置的编码能无错解码时才转换，
+/// 避免误改本来就正确的文本（如 `café`）
+///
+/// # 参数
+///
+/// * `text`: 提交说明或作者
+/// * `encoding`: 旧提交说明的编码
+pub(crate) fn repair_log_text<'a>(
+    text: &'a str,
+    encoding: Option<&'static Encoding>,
+) -> Cow<'a, str> {
+    let Some(encoding) = encoding else {
+        return Cow::Borrowed(text);
+    };
+    if text.is_ascii() || text.chars().any(|c| u32::from(c) > 0xFF) {
+        return Cow::Borrowed(text);
+    }
+    let bytes: Vec<u8> = text.chars().map(|c| c as u8).collect();
+    match encoding.decode_without_bom_handling_and_without_replacement(&bytes) {
+        Some(decoded) => Cow::Owned(decoded.into_owned()),
+        None => Cow::Borrowed(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding_rs::{BIG5, GBK};
+
+    use super::*;
+
+    #[test]
+    fn test_decode_log_bytes_should_fall_back_to_encoding() {
+        let (gbk, _, _) = GBK.encode("修复问题");
+        assert_eq!(decode_log_bytes(&gbk, Some(GBK)).unwrap(), "修复问题");
+        assert_eq!(
+            decode_log_bytes("修复问题".as_bytes(), None).unwrap(),
+            "修复问题"
+        );
+        assert!(decode_log_bytes(&gbk, None).is_none());
+    }
+
+    #[test]
+    fn test_repair_log_text_should_fix_latin1_mojibake() {
+        let (big5, _, _) = BIG5.encode("修正錯誤");
+        let mojibake: String = big5.iter().map(|&b| char::from(b)).collect();
+        assert_eq!(repair_log_text(&mojibake, Some(BIG5)), "修正錯誤");
+        assert_eq!(repair_log_text(&mojibake, None), mojibake);
+        assert_eq!(repair_log_text("修复问题", Some(GBK)), "修复问题");
+        assert_eq!(repair_log_text("plain text", Some(GBK)), "plain text");
+    }
+
+    #[test]
+    fn test_encoding_for_label_should_reject_unknown_label() {
+        assert_eq!(encoding_for_label(" GBK ").unwrap(), GBK);
+        assert!(encoding_for_label("no-such-encoding").is_err());
+    }
+}
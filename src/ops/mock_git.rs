@@ -2,13 +2,29 @@
 //!
 //! 提供Git操作的内存模拟实现，用于测试和开发环境
 
-use crate::error::{Result, SyncError};
+use super::git_operations::{CommitMetadata, GitCommitEntry, GitSignature};
+use crate::{
+    clock::{Clock, FixedClock},
+    error::{Result, SyncError},
+};
+use chrono::DateTime;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     path::{Path, PathBuf},
     sync::{Arc, RwLock},
 };
 
+/// Mock 提交的默认时间
+const DEFAULT_COMMIT_TIME: &str = "2024-01-01T00:00:00Z";
+
+/// Mock 仓库默认的固定时钟，保证提交时间可重复
+fn default_clock() -> Arc<dyn Clock> {
+    let now = DateTime::parse_from_rfc3339(DEFAULT_COMMIT_TIME)
+        .expect("默认提交时间格式有效")
+        .to_utc();
+    Arc::new(FixedClock::new(now))
+}
+
 /// Git文件状态枚举
 #[derive(Debug, Clone, PartialEq)]
 pub enum GitFileStatus {
@@ -37,6 +53,26 @@ pub struct MockGitRepo {
     initialized: bool,
     /// 当前分支
     branch: String,
+    /// 已创建的分支：分支名 -> 起点提交（仓库没有提交时为 `None`）
+    branches: BTreeMap<String, Option<String>>,
+    /// 附注标签：标签名 -> (指向的提交, 说明, 创建者)
+    tags: BTreeMap<String, (String, String, Option<GitSignature>)>,
+    /// Git notes：note 引用 -> (提交哈希 -> 内容)
+    notes: HashMap<String, HashMap<String, String>>,
+    /// 暂存区中标记为可执行的文件
+    executables: BTreeSet<String>,
+    /// 远程仓库：名称 -> 地址
+    remotes: BTreeMap<String, String>,
+    /// 推送记录：远程仓库名 -> 推送时的提交哈希
+    pushed: BTreeMap<String, Option<String>>,
+    /// 生成的 bundle：文件路径 -> 生成时的提交哈希
+    bundles: BTreeMap<PathBuf, String>,
+    /// 指向 blob 的引用：引用名 -> blob 内容
+    blob_refs: BTreeMap<String, String>,
+    /// stash 栈，每项是暂存时已跟踪文件的状态
+    stashes: Vec<HashMap<String, GitFileStatus>>,
+    /// 没有指定提交时间时使用的时钟
+    clock: Arc<dyn Clock>,
 }
 
 /// Git提交记录
@@ -48,6 +84,8 @@ pub struct GitCommit {
     pub message: String,
     /// 提交时间戳
     pub timestamp: String,
+    /// 作者名称，`None` 表示仓库配置的用户
+    pub author: Option<String>,
     /// 包含的文件列表
     pub files: Vec<String>,
 }
@@ -69,6 +107,16 @@ impl MockGitRepo {
             commits: Vec::new(),
             initialized: false,
             branch: "main".to_string(),
+            branches: BTreeMap::new(),
+            tags: BTreeMap::new(),
+            notes: HashMap::new(),
+            executables: BTreeSet::new(),
+            remotes: BTreeMap::new(),
+            pushed: BTreeMap::new(),
+            bundles: BTreeMap::new(),
+            blob_refs: BTreeMap::new(),
+            stashes: Vec::new(),
+            clock: default_clock(),
         }
     }
 
@@ -138,6 +186,48 @@ impl MockGitRepo {
         Ok(())
     }
 
+    /// 模拟 `git add -A -- <paths>` 操作
+    ///
+    /// 只将指定的未跟踪和已修改文件添加到暂存区
+    ///
+    /// # 参数
+    ///
+    /// * `paths` - 文件路径
+    pub fn add_paths(&mut self, paths: &[String]) -> Result<()> {
+        if !self.initialized {
+            return Err(SyncError::App("Git仓库未初始化".to_string()));
+        }
+
+        for path in paths {
+            if let Some(status) = self.files.get_mut(path)
+                && matches!(status, GitFileStatus::Untracked | GitFileStatus::Modified)
+            {
+                *status = GitFileStatus::Staged;
+            }
+        }
+        Ok(())
+    }
+
+    /// 模拟 `git update-index --chmod=+x` 操作
+    ///
+    /// # 参数
+    ///
+    /// * `paths` - 要标记为可执行的文件，必须已被跟踪
+    pub fn set_executable(&mut self, paths: &[String]) -> Result<()> {
+        for path in paths {
+            if !self.files.contains_key(path) {
+                return Err(SyncError::App(format!("文件 {path} 不在暂存区")));
+            }
+            self.executables.insert(path.clone());
+        }
+        Ok(())
+    }
+
+    /// 获取标记为可执行的文件
+    pub fn get_executables(&self) -> &BTreeSet<String> {
+        &self.executables
+    }
+
     /// 模拟 `git commit -m "message"` 操作
     ///
     /// 提交所有暂存的文件
@@ -151,6 +241,18 @@ impl MockGitRepo {
     /// * `Ok(())` - 提交成功
     /// * `Err(SyncError)` - 提交失败（如仓库未初始化、没有暂存文件等）
     pub fn commit(&mut self, message: &str) -> Result<()> {
+        self.commit_with(message, &CommitMetadata::default())
+    }
+
+    /// 模拟以指定的作者和时间提交
+    ///
+    /// 指定时间时提交记录的时间戳为该时间的 RFC 3339 格式
+    ///
+    /// # 参数
+    ///
+    /// * `message` - 提交消息
+    /// * `metadata` - 作者和时间
+    pub fn commit_with(&mut self, message: &str, metadata: &CommitMetadata) -> Result<()> {
         if !self.initialized {
             return Err(SyncError::App("Git仓库未初始化".to_string()));
         }
@@ -163,7 +265,7 @@ impl MockGitRepo {
             .map(|(path, _)| path.clone())
             .collect();
 
-        if staged_files.is_empty() {
+        if staged_files.is_empty() && !metadata.allow_empty {
             return Err(SyncError::App("没有暂存的文件可以提交".to_string()));
         }
 
@@ -171,7 +273,10 @@ impl MockGitRepo {
         let commit = GitCommit {
             hash: format!("commit{}", self.commits.len() + 1),
             message: message.to_string(),
-            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            timestamp: metadata
+                .date
+                .map_or_else(|| self.clock.now().to_rfc3339(), |d| d.to_rfc3339()),
+            author: metadata.author_name.clone(),
             files: staged_files.clone(),
         };
 
@@ -188,16 +293,247 @@ impl MockGitRepo {
         Ok(())
     }
 
+    /// 模拟 `git reset --mixed <commit>` 操作
+    ///
+    /// 移除目标提交之后的提交，其中的文件在工作目录中保留为未暂存的更改
+    ///
+    /// # 参数
+    ///
+    /// * `commit` - 目标提交哈希，`None` 表示移除所有提交
+    pub fn reset_to(&mut self, commit: Option<&str>) -> Result<()> {
+        let keep = match commit {
+            Some(commit) => {
+                self.commits
+                    .iter()
+                    .position(|c| c.hash == commit)
+                    .ok_or_else(|| SyncError::App(format!("Git提交 {commit} 不存在")))?
+                    + 1
+            }
+            None => 0,
+        };
+        self.commits.truncate(keep);
+
+        let committed: HashSet<&String> =
+            self.commits.iter().flat_map(|c| c.files.iter()).collect();
+        for (path, status) in self.files.iter_mut() {
+            *status = match (committed.contains(path), &status) {
+                (true, GitFileStatus::Committed) => GitFileStatus::Committed,
+                (true, _) => GitFileStatus::Modified,
+                (false, _) => GitFileStatus::Untracked,
+            };
+        }
+        Ok(())
+    }
+
+    /// 模拟 `git stash push`：暂存已跟踪文件的更改，未跟踪的文件保留
+    ///
+    /// # 返回值
+    ///
+    /// 是否有可暂存的更改
+    pub fn stash(&mut self) -> bool {
+        let committed: HashSet<String> = self
+            .commits
+            .iter()
+            .flat_map(|c| c.files.iter().cloned())
+            .collect();
+        let stashed: HashMap<String, GitFileStatus> = self
+            .files
+            .iter()
+            .filter(|(_, status)| matches!(status, GitFileStatus::Staged | GitFileStatus::Modified))
+            .map(|(path, status)| (path.clone(), status.clone()))
+            .collect();
+        if stashed.is_empty() {
+            return false;
+        }
+        for path in stashed.keys() {
+            if committed.contains(path) {
+                self.files.insert(path.clone(), GitFileStatus::Committed);
+            } else {
+                self.files.remove(path);
+            }
+        }
+        self.stashes.push(stashed);
+        true
+    }
+
+    /// 模拟 `git stash pop`：恢复最近一次暂存的更改
+    pub fn stash_pop(&mut self) -> Result<()> {
+        let stashed = self
+            .stashes
+            .pop()
+            .ok_or_else(|| SyncError::App("没有可恢复的 stash".to_string()))?;
+        self.files.extend(stashed);
+        Ok(())
+    }
+
     /// 获取提交历史
     pub fn get_commits(&self) -> &Vec<GitCommit> {
         &self.commits
     }
 
+    /// 获取提交的 note
+    ///
+    /// # 参数
+    ///
+    /// * `notes_ref` - note 引用
+    /// * `commit` - 提交哈希
+    pub fn get_note(&self, notes_ref: &str, commit: &str) -> Option<&str> {
+        self.notes
+            .get(notes_ref)
+            .and_then(|notes| notes.get(commit))
+            .map(String::as_str)
+    }
+
     /// 获取当前分支名
     pub fn get_branch(&self) -> &str {
         &self.branch
     }
 
+    /// 获取已创建的分支及其起点提交
+    pub fn get_branches(&self) -> &BTreeMap<String, Option<String>> {
+        &self.branches
+    }
+
+    /// 模拟 `git branch <name> [<start>]`
+    ///
+    /// Mock仓库只有一条线性历史，分支只记录名称和起点
+    ///
+    /// # 参数
+    ///
+    /// * `name` - 分支名
+    /// * `start` - 起点提交或分支名，`None` 表示当前 HEAD
+    pub fn create_branch(&mut self, name: &str, start: Option<&str>) -> Result<()> {
+        if name == self.branch || self.branches.contains_key(name) {
+            return Err(SyncError::App(format!("Git分支 {name} 已存在")));
+        }
+        let head = self.commits.last().map(|c| c.hash.clone());
+        let start = match start {
+            None => head.ok_or_else(|| SyncError::App("Git仓库还没有任何提交".to_string()))?,
+            Some(start) if start == self.branch => {
+                head.ok_or_else(|| SyncError::App(format!("Git分支 {start} 还没有提交")))?
+            }
+            Some(start) => match self.branches.get(start) {
+                Some(commit) => commit
+                    .clone()
+                    .ok_or_else(|| SyncError::App(format!("Git分支 {start} 还没有提交")))?,
+                None if self.commits.iter().any(|c| c.hash == start) => start.to_string(),
+                None => return Err(SyncError::App(format!("Git提交 {start} 不存在"))),
+            },
+        };
+        self.branches.insert(name.to_string(), Some(start));
+        Ok(())
+    }
+
+    /// 获取附注标签：标签名 -> (指向的提交, 说明, 创建者)
+    pub fn get_tags(&self) -> &BTreeMap<String, (String, String, Option<GitSignature>)> {
+        &self.tags
+    }
+
+    /// 模拟 `git tag -a <name> <target> -m <message>`
+    ///
+    /// # 参数
+    ///
+    /// * `name` - 标签名
+    /// * `target` - `HEAD`、分支名或提交哈希
+    /// * `message` - 标签说明
+    /// * `tagger` - 标签创建者
+    pub fn tag(
+        &mut self,
+        name: &str,
+        target: &str,
+        message: &str,
+        tagger: Option<&GitSignature>,
+    ) -> Result<()> {
+        if self.tags.contains_key(name) {
+            return Err(SyncError::App(format!("Git标签 {name} 已存在")));
+        }
+        let commit = if target == "HEAD" || target == self.branch {
+            self.commits.last().map(|c| c.hash.clone())
+        } else if let Some(start) = self.branches.get(target) {
+            start.clone()
+        } else {
+            self.commits
+                .iter()
+                .find(|c| c.hash == target)
+                .map(|c| c.hash.clone())
+        }
+        .ok_or_else(|| SyncError::App(format!("Git提交 {target} 不存在")))?;
+        self.tags.insert(
+            name.to_string(),
+            (commit, message.to_string(), tagger.cloned()),
+        );
+        Ok(())
+    }
+
+    /// 模拟 `git remote add <name> <url>`，已存在时更新地址
+    pub fn add_remote(&mut self, name: &str, url: &str) {
+        self.remotes.insert(name.to_string(), url.to_string());
+    }
+
+    /// 获取远程仓库：名称 -> 地址
+    pub fn get_remotes(&self) -> &BTreeMap<String, String> {
+        &self.remotes
+    }
+
+    /// 模拟 `git push <remote>`，记录推送时的最新提交
+    ///
+    /// # 参数
+    ///
+    /// * `remote` - 远程仓库名
+    pub fn push(&mut self, remote: &str) -> Result<()> {
+        if !self.remotes.contains_key(remote) {
+            return Err(SyncError::App(format!("Git远程仓库 {remote} 不存在")));
+        }
+        let head = self.commits.last().map(|c| c.hash.clone());
+        self.pushed.insert(remote.to_string(), head);
+        Ok(())
+    }
+
+    /// 获取推送记录：远程仓库名 -> 推送时的提交哈希
+    pub fn get_pushed(&self) -> &BTreeMap<String, Option<String>> {
+        &self.pushed
+    }
+
+    /// 模拟 `git bundle create <file>`，记录生成时的最新提交
+    ///
+    /// # 参数
+    ///
+    /// * `file` - bundle 文件路径
+    pub fn bundle(&mut self, file: &Path) -> Result<()> {
+        let head = self
+            .commits
+            .last()
+            .ok_or_else(|| SyncError::App("仓库没有提交，无法生成 git bundle".into()))?;
+        self.bundles.insert(file.to_path_buf(), head.hash.clone());
+        Ok(())
+    }
+
+    /// 获取生成的 bundle：文件路径 -> 生成时的提交哈希
+    pub fn get_bundles(&self) -> &BTreeMap<PathBuf, String> {
+        &self.bundles
+    }
+
+    /// 模拟 `git checkout <name>`
+    ///
+    /// 仓库还没有提交时直接把当前分支改为该分支
+    ///
+    /// # 参数
+    ///
+    /// * `name` - 分支名
+    pub fn checkout_branch(&mut self, name: &str) -> Result<()> {
+        if name == self.branch {
+            return Ok(());
+        }
+        if !self.commits.is_empty() && !self.branches.contains_key(name) {
+            return Err(SyncError::App(format!("Git分支 {name} 不存在")));
+        }
+        let previous = std::mem::replace(&mut self.branch, name.to_string());
+        let head = self.commits.last().map(|c| c.hash.clone());
+        self.branches.remove(name);
+        self.branches.insert(previous, head);
+        Ok(())
+    }
+
     /// 检查工作目录是否干净（没有未提交的更改）
     pub fn is_working_directory_clean(&self) -> bool {
         self.files
@@ -239,6 +575,8 @@ impl MockGitRepo {
 pub struct MockGitOperations {
     /// 存储所有Mock仓库
     repos: Arc<RwLock<HashMap<String, MockGitRepo>>>,
+    /// 没有指定提交时间时使用的时钟
+    clock: Arc<dyn Clock>,
 }
 
 impl MockGitOperations {
@@ -250,9 +588,22 @@ impl MockGitOperations {
     pub fn new() -> Self {
         Self {
             repos: Arc::new(RwLock::new(HashMap::new())),
+            clock: default_clock(),
         }
     }
 
+    /// 设置时钟
+    ///
+    /// 没有指定提交时间的提交使用该时钟的当前时间，默认固定为 2024-01-01T00:00:00Z
+    ///
+    /// # 参数
+    ///
+    /// * `clock` - 时钟
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// 手动添加文件到Mock仓库状态中
     ///
     /// 这个方法用于测试，当在文件系统中创建了文件后，
@@ -301,18 +652,22 @@ impl MockGitOperations {
         {
             let repos = self.repos.read().unwrap();
             if let Some(repo) = repos.get(&path_str) {
-                return repo.clone();
+                let mut repo = repo.clone();
+                repo.clock = self.clock.clone();
+                return repo;
             }
         }
 
         // 如果不存在，则创建新的
-        {
+        let mut repo = {
             let mut repos = self.repos.write().unwrap();
             repos
                 .entry(path_str)
                 .or_insert_with(|| MockGitRepo::new(path.to_path_buf()))
                 .clone()
-        }
+        };
+        repo.clock = self.clock.clone();
+        repo
     }
 
     /// 更新Mock仓库
@@ -379,6 +734,13 @@ impl super::git_operations::GitOperations for MockGitOperations {
         result
     }
 
+    fn commit_with(&self, path: &Path, message: &str, metadata: &CommitMetadata) -> Result<()> {
+        let mut repo = self.get_or_create_repo(path);
+        let result = repo.commit_with(message, metadata);
+        self.update_repo(path, repo)?;
+        result
+    }
+
     fn status(&self, path: &Path) -> Result<String> {
         let repo = self.get_or_create_repo(path);
         if repo.is_working_directory_clean() {
@@ -404,12 +766,182 @@ impl super::git_operations::GitOperations for MockGitOperations {
         Ok(result)
     }
 
+    fn is_repository(&self, path: &Path) -> Result<bool> {
+        let repos = self.repos.read().unwrap();
+        Ok(repos
+            .get(path.to_string_lossy().as_ref())
+            .is_some_and(|repo| repo.is_initialized()))
+    }
+
     fn is_clean(&self, path: &Path) -> Result<bool> {
         let repo = self.get_or_create_repo(path);
         Ok(repo.is_working_directory_clean())
     }
+
+    fn rev_parse_head(&self, path: &Path) -> Result<String> {
+        let repo = self.get_or_create_repo(path);
+        repo.get_commits()
+            .last()
+            .map(|commit| commit.hash.clone())
+            .ok_or_else(|| SyncError::App("Git仓库还没有任何提交".to_string()))
+    }
+
+    fn root_commit(&self, path: &Path) -> Result<String> {
+        let repo = self.get_or_create_repo(path);
+        repo.get_commits()
+            .first()
+            .map(|commit| commit.hash.clone())
+            .ok_or_else(|| SyncError::App("Git仓库还没有任何提交".to_string()))
+    }
+
+    fn commit_history(&self, path: &Path, notes_ref: &str) -> Result<Vec<GitCommitEntry>> {
+        let repo = self.get_or_create_repo(path);
+        if repo.get_commits().is_empty() {
+            return Err(SyncError::App("Git仓库还没有任何提交".to_string()));
+        }
+        let notes = repo.notes.get(notes_ref);
+        Ok(repo
+            .get_commits()
+            .iter()
+            .map(|commit| GitCommitEntry {
+                hash: commit.hash.clone(),
+                message: commit.message.clone(),
+                note: notes.and_then(|notes| notes.get(&commit.hash)).cloned(),
+            })
+            .collect())
+    }
+
+    fn tree_blobs(&self, path: &Path, rev: &str) -> Result<BTreeMap<String, String>> {
+        // Mock仓库不保存文件内容，使用固定的 blob 哈希，仅用于比较文件列表
+        let repo = self.get_or_create_repo(path);
+        let index = repo
+            .get_commits()
+            .iter()
+            .position(|commit| commit.hash == rev)
+            .ok_or_else(|| SyncError::App(format!("Git提交 {rev} 不存在")))?;
+        Ok(repo.get_commits()[..=index]
+            .iter()
+            .flat_map(|commit| commit.files.iter())
+            .map(|file| (file.clone(), MOCK_BLOB_HASH.to_string()))
+            .collect())
+    }
+
+    fn hash_files(&self, _path: &Path, files: &[PathBuf]) -> Result<Vec<String>> {
+        Ok(vec![MOCK_BLOB_HASH.to_string(); files.len()])
+    }
+
+    fn add_paths(&self, path: &Path, files: &[String]) -> Result<()> {
+        let mut repo = self.get_or_create_repo(path);
+        repo.add_paths(files)?;
+        self.update_repo(path, repo)
+    }
+
+    fn set_executable(&self, path: &Path, files: &[String]) -> Result<()> {
+        let mut repo = self.get_or_create_repo(path);
+        repo.set_executable(files)?;
+        self.update_repo(path, repo)
+    }
+
+    fn add_note(&self, path: &Path, notes_ref: &str, commit: &str, note: &str) -> Result<()> {
+        let mut repo = self.get_or_create_repo(path);
+        let hash = match commit {
+            "HEAD" => repo.get_commits().last().map(|c| c.hash.clone()),
+            _ => repo
+                .get_commits()
+                .iter()
+                .find(|c| c.hash == commit)
+                .map(|c| c.hash.clone()),
+        }
+        .ok_or_else(|| SyncError::App(format!("Git提交 {commit} 不存在")))?;
+
+        repo.notes
+            .entry(notes_ref.to_string())
+            .or_default()
+            .insert(hash, note.to_string());
+        self.update_repo(path, repo)
+    }
+
+    fn reset_to(&self, path: &Path, commit: Option<&str>) -> Result<()> {
+        let mut repo = self.get_or_create_repo(path);
+        repo.reset_to(commit)?;
+        self.update_repo(path, repo)
+    }
+
+    fn stash(&self, path: &Path, _message: &str) -> Result<bool> {
+        let mut repo = self.get_or_create_repo(path);
+        let stashed = repo.stash();
+        self.update_repo(path, repo)?;
+        Ok(stashed)
+    }
+
+    fn stash_pop(&self, path: &Path) -> Result<()> {
+        let mut repo = self.get_or_create_repo(path);
+        repo.stash_pop()?;
+        self.update_repo(path, repo)
+    }
+
+    fn create_branch(&self, path: &Path, name: &str, start: Option<&str>) -> Result<()> {
+        let mut repo = self.get_or_create_repo(path);
+        repo.create_branch(name, start)?;
+        self.update_repo(path, repo)
+    }
+
+    fn checkout_branch(&self, path: &Path, name: &str) -> Result<()> {
+        let mut repo = self.get_or_create_repo(path);
+        repo.checkout_branch(name)?;
+        self.update_repo(path, repo)
+    }
+
+    fn tag(
+        &self,
+        path: &Path,
+        name: &str,
+        target: &str,
+        message: &str,
+        tagger: Option<&GitSignature>,
+    ) -> Result<()> {
+        let mut repo = self.get_or_create_repo(path);
+        repo.tag(name, target, message, tagger)?;
+        self.update_repo(path, repo)
+    }
+
+    fn add_remote(&self, path: &Path, name: &str, url: &str) -> Result<()> {
+        let mut repo = self.get_or_create_repo(path);
+        repo.add_remote(name, url);
+        self.update_repo(path, repo)
+    }
+
+    fn push(&self, path: &Path, remote: &str) -> Result<()> {
+        let mut repo = self.get_or_create_repo(path);
+        repo.push(remote)?;
+        self.update_repo(path, repo)
+    }
+
+    fn bundle(&self, path: &Path, file: &Path) -> Result<()> {
+        let mut repo = self.get_or_create_repo(path);
+        repo.bundle(file)?;
+        self.update_repo(path, repo)
+    }
+
+    fn write_blob_ref(&self, path: &Path, reference: &str, content: &str) -> Result<()> {
+        let mut repo = self.get_or_create_repo(path);
+        repo.blob_refs
+            .insert(reference.to_string(), content.to_string());
+        self.update_repo(path, repo)
+    }
+
+    fn read_blob_ref(&self, path: &Path, reference: &str) -> Result<Option<String>> {
+        Ok(self
+            .get_or_create_repo(path)
+            .blob_refs
+            .get(reference)
+            .cloned())
+    }
 }
 
+/// Mock实现中所有文件使用的 blob 哈希
+const MOCK_BLOB_HASH: &str = "mock-blob";
+
 #[cfg(test)]
 mod tests {
     use super::super::git_operations::GitOperations;
@@ -482,6 +1014,55 @@ mod tests {
         assert!(repo.is_working_directory_clean());
     }
 
+    #[test]
+    fn test_commit_timestamp_should_follow_injected_clock() {
+        use chrono::{Duration, TimeZone, Utc};
+
+        let clock = FixedClock::new(Utc.with_ymd_and_hms(2025, 6, 1, 8, 0, 0).unwrap());
+        let ops = MockGitOperations::new().with_clock(Arc::new(clock.clone()));
+        let path = PathBuf::from("/test/clock");
+        ops.init(&path).unwrap();
+        for name in ["a.txt", "b.txt"] {
+            ops.add_file_to_mock(&path, name).unwrap();
+            ops.add_all(&path).unwrap();
+            ops.commit(&path, name).unwrap();
+            clock.advance(Duration::hours(1));
+        }
+
+        let repo = ops.get_repo_state(&path).unwrap();
+        let timestamps: Vec<_> = repo.get_commits().iter().map(|c| &c.timestamp).collect();
+        assert_eq!(
+            timestamps,
+            ["2025-06-01T08:00:00+00:00", "2025-06-01T09:00:00+00:00"]
+        );
+
+        // 未注入时钟时使用固定的默认时间
+        let mut repo = MockGitRepo::new(PathBuf::from("/test"));
+        repo.init().unwrap();
+        repo.add_file("a.txt");
+        repo.add_all().unwrap();
+        repo.commit("a").unwrap();
+        assert_eq!(repo.get_commits()[0].timestamp, "2024-01-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn test_commit_with_should_record_author_and_date() {
+        let mut repo = MockGitRepo::new(PathBuf::from("/test"));
+        repo.init().expect("初始化失败");
+        repo.add_file("test.txt");
+        repo.add_all().expect("添加失败");
+
+        let metadata = CommitMetadata {
+            author_name: Some("alice".into()),
+            author_email: Some("alice@uuid".into()),
+            date: chrono::DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z").ok(),
+            ..Default::default()
+        };
+        repo.commit_with("测试提交", &metadata).unwrap();
+        assert_eq!(repo.get_commits()[0].timestamp, "2024-01-02T03:04:05+00:00");
+        assert_eq!(repo.get_commits()[0].author.as_deref(), Some("alice"));
+    }
+
     #[test]
     fn test_modify_committed_file() {
         let mut repo = MockGitRepo::new(PathBuf::from("/test"));
@@ -521,6 +1102,13 @@ mod tests {
 
         // 测试工作目录状态
         assert!(ops.is_clean(&path).is_ok());
+
+        // 没有提交时获取HEAD应该失败
+        assert!(ops.rev_parse_head(&path).is_err());
+        ops.add_file_to_mock(&path, "a.txt").unwrap();
+        ops.add_all(&path).unwrap();
+        ops.commit(&path, "提交").unwrap();
+        assert_eq!(ops.rev_parse_head(&path).unwrap(), "commit1");
     }
 
     #[test]
@@ -538,4 +1126,144 @@ mod tests {
             Some(GitFileStatus::Untracked)
         );
     }
+
+    #[test]
+    fn test_add_note() {
+        let ops = MockGitOperations::new();
+        let path = PathBuf::from("/test/notes");
+        ops.init(&path).unwrap();
+        assert!(ops.add_note(&path, "refs/notes/svn", "HEAD", "r1").is_err());
+
+        ops.add_file_to_mock(&path, "a.txt").unwrap();
+        ops.add_all(&path).unwrap();
+        ops.commit(&path, "first").unwrap();
+        ops.add_note(&path, "refs/notes/svn", "HEAD", "r1").unwrap();
+        ops.add_note(&path, "refs/notes/svn", "commit1", "r1 覆盖")
+            .unwrap();
+
+        let repo = ops.get_repo_state(&path).unwrap();
+        assert_eq!(repo.get_note("refs/notes/svn", "commit1"), Some("r1 覆盖"));
+        assert_eq!(repo.get_note("refs/notes/other", "commit1"), None);
+    }
+
+    #[test]
+    fn test_reset_to_should_drop_later_commits() {
+        let ops = MockGitOperations::new();
+        let path = PathBuf::from("/test/reset");
+        ops.init(&path).unwrap();
+        ops.add_file_to_mock(&path, "a.txt").unwrap();
+        ops.add_all(&path).unwrap();
+        ops.commit(&path, "first").unwrap();
+        ops.add_file_to_mock(&path, "b.txt").unwrap();
+        ops.add_all(&path).unwrap();
+        ops.commit(&path, "second").unwrap();
+
+        ops.reset_to(&path, Some("commit1")).unwrap();
+        assert_eq!(ops.rev_parse_head(&path).unwrap(), "commit1");
+        let repo = ops.get_repo_state(&path).unwrap();
+        assert_eq!(
+            repo.get_file_status("a.txt"),
+            Some(GitFileStatus::Committed)
+        );
+        assert_eq!(
+            repo.get_file_status("b.txt"),
+            Some(GitFileStatus::Untracked)
+        );
+
+        assert!(ops.reset_to(&path, Some("commit9")).is_err());
+        ops.reset_to(&path, None).unwrap();
+        assert!(ops.rev_parse_head(&path).is_err());
+    }
+
+    #[test]
+    fn test_add_paths_only_stages_listed_files() {
+        let ops = MockGitOperations::new();
+        let path = PathBuf::from("/test/add_paths");
+        ops.init(&path).unwrap();
+        ops.add_file_to_mock(&path, "a.txt").unwrap();
+        ops.add_file_to_mock(&path, "b.txt").unwrap();
+
+        ops.add_paths(&path, &["a.txt".to_string()]).unwrap();
+        let repo = ops.get_repo_state(&path).unwrap();
+        assert_eq!(repo.get_file_status("a.txt"), Some(GitFileStatus::Staged));
+        assert_eq!(
+            repo.get_file_status("b.txt"),
+            Some(GitFileStatus::Untracked)
+        );
+    }
+
+    #[test]
+    fn test_create_and_checkout_branch() {
+        let ops = MockGitOperations::new();
+        let path = PathBuf::from("/test/branch");
+        ops.init(&path).unwrap();
+        ops.checkout_branch(&path, "master").unwrap();
+        assert!(ops.create_branch(&path, "dev", None).is_err());
+
+        ops.add_file_to_mock(&path, "a.txt").unwrap();
+        ops.add_all(&path).unwrap();
+        ops.commit(&path, "first").unwrap();
+        ops.create_branch(&path, "dev", Some("master")).unwrap();
+        assert!(ops.create_branch(&path, "dev", None).is_err());
+        assert!(ops.checkout_branch(&path, "missing").is_err());
+
+        ops.checkout_branch(&path, "dev").unwrap();
+        let repo = ops.get_repo_state(&path).unwrap();
+        assert_eq!(repo.get_branch(), "dev");
+        assert_eq!(
+            repo.get_branches().get("master"),
+            Some(&Some("commit1".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_tag_should_point_to_branch_head() {
+        let ops = MockGitOperations::new();
+        let path = PathBuf::from("/test/tag");
+        ops.init(&path).unwrap();
+        ops.add_file_to_mock(&path, "a.txt").unwrap();
+        ops.add_all(&path).unwrap();
+        ops.commit(&path, "first").unwrap();
+
+        ops.tag(&path, "v1.0", "main", "release", None).unwrap();
+        assert!(ops.tag(&path, "v1.0", "HEAD", "again", None).is_err());
+        assert!(ops.tag(&path, "v2.0", "missing", "release", None).is_err());
+        let repo = ops.get_repo_state(&path).unwrap();
+        assert_eq!(
+            repo.get_tags().get("v1.0"),
+            Some(&("commit1".to_string(), "release".to_string(), None))
+        );
+    }
+
+    #[test]
+    fn test_push_should_require_remote() {
+        let ops = MockGitOperations::new();
+        let path = PathBuf::from("/test/push");
+        ops.init(&path).unwrap();
+        ops.add_file_to_mock(&path, "a.txt").unwrap();
+        ops.add_all(&path).unwrap();
+        ops.commit(&path, "first").unwrap();
+
+        assert!(ops.push(&path, "origin").is_err());
+        ops.add_remote(&path, "origin", "old").unwrap();
+        ops.add_remote(&path, "origin", "https://example.com/repo.git")
+            .unwrap();
+        ops.push(&path, "origin").unwrap();
+        let repo = ops.get_repo_state(&path).unwrap();
+        assert_eq!(
+            repo.get_remotes().get("origin").map(String::as_str),
+            Some("https://example.com/repo.git")
+        );
+        assert_eq!(
+            repo.get_pushed().get("origin"),
+            Some(&Some("commit1".to_string()))
+        );
+
+        ops.bundle(&path, Path::new("repo.bundle")).unwrap();
+        let repo = ops.get_repo_state(&path).unwrap();
+        assert_eq!(
+            repo.get_bundles().get(Path::new("repo.bundle")),
+            Some(&"commit1".to_string())
+        );
+    }
 }
@@ -0,0 +1,793 @@
+//! Mock SVN操作实现
+//!
+//! 提供SVN操作的内存模拟实现，用于测试和开发环境
+
+use crate::{
+    depth::SparseScope,
+    error::{Result, SyncError},
+};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    thread,
+    time::Duration,
+};
+
+use super::mock_git::MockGitOperations;
+use super::svn::{SvnDiffSummary, SvnInfo, SvnLog, SvnLogQuery, SvnProperties};
+use super::svn_operations::SvnOperations;
+
+/// Mock SVN工作副本
+///
+/// 在内存中模拟SVN工作副本及其所属仓库的提交历史
+#[derive(Debug, Clone)]
+pub struct MockSvnRepo {
+    /// 工作副本路径
+    pub path: PathBuf,
+    /// 是否已初始化（已检出）
+    initialized: bool,
+    /// 仓库中的全部提交，按版本升序
+    logs: Vec<SvnLog>,
+    /// 工作副本当前所在版本（BASE）
+    current_revision: Option<String>,
+    /// 仓库URL
+    url: String,
+    /// 仓库UUID
+    uuid: String,
+}
+
+impl MockSvnRepo {
+    /// 创建新的Mock SVN工作副本
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 工作副本路径
+    ///
+    /// # 返回值
+    ///
+    /// 返回新的MockSvnRepo实例
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            initialized: false,
+            logs: Vec::new(),
+            current_revision: None,
+            url: "file:///mock/repo".to_string(),
+            uuid: "00000000-0000-0000-0000-000000000000".to_string(),
+        }
+    }
+
+    /// 初始化SVN工作副本
+    pub fn init(&mut self) -> Result<()> {
+        if self.initialized {
+            return Err(SyncError::App("SVN仓库已经初始化".to_string()));
+        }
+        self.initialized = true;
+        Ok(())
+    }
+
+    /// 检查工作副本是否已初始化
+    pub fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+
+    /// 向仓库添加一条提交
+    ///
+    /// # 参数
+    ///
+    /// * `log` - 提交日志
+    pub fn add_log(&mut self, log: SvnLog) {
+        self.logs.push(log);
+    }
+
+    /// 获取工作副本当前版本
+    pub fn current_revision(&self) -> Option<&str> {
+        self.current_revision.as_deref()
+    }
+
+    /// 获取 BASE 之后尚未更新的提交
+    pub fn pending_logs(&self) -> Vec<SvnLog> {
+        self.logs_after(self.current_revision.as_deref())
+    }
+
+    /// 获取指定版本之后的提交
+    ///
+    /// # 参数
+    ///
+    /// * `rev` - 起始版本（不包含），为空时返回全部提交
+    pub fn logs_after(&self, rev: Option<&str>) -> Vec<SvnLog> {
+        let start = match rev {
+            Some(rev) => self
+                .logs
+                .iter()
+                .position(|log| log.version == rev)
+                .map(|idx| idx + 1)
+                .unwrap_or(0),
+            None => 0,
+        };
+        self.logs[start..].to_vec()
+    }
+
+    /// 模拟 `svn update -r` 操作
+    ///
+    /// # 参数
+    ///
+    /// * `rev` - 目标版本
+    pub fn update_to(&mut self, rev: &str) -> Result<()> {
+        if !self.initialized {
+            return Err(SyncError::App("SVN工作副本未初始化".to_string()));
+        }
+        // 与真实仓库一样，r0 表示还没有任何提交的状态
+        if rev == "0" {
+            self.current_revision = None;
+            return Ok(());
+        }
+        if !self.logs.iter().any(|log| log.version == rev) {
+            return Err(SyncError::App(format!("SVN版本 {rev} 不存在")));
+        }
+        self.current_revision = Some(rev.to_string());
+        Ok(())
+    }
+}
+
+/// 各版本导出时写出的文件：版本 -> [(相对路径, 内容)]
+type ExportFiles = HashMap<String, Vec<(String, String)>>;
+
+/// 设置的版本属性：属性名 -> (相对路径 -> 属性值)
+type Properties = HashMap<String, BTreeMap<String, String>>;
+
+/// 变更列表：名称 -> 相对路径
+type Changelists = HashMap<String, Vec<String>>;
+
+/// 只更新部分路径的操作：(版本, 路径)
+type PathUpdates = Vec<(String, Vec<String>)>;
+
+/// Mock SVN操作实现
+///
+/// 使用内存状态模拟SVN操作，用于测试
+#[derive(Debug, Clone)]
+pub struct MockSvnOperations {
+    /// 存储所有Mock工作副本
+    repos: Arc<RwLock<HashMap<String, MockSvnRepo>>>,
+    /// 更新时需要模拟失败的版本
+    failing_revisions: Arc<RwLock<HashSet<String>>>,
+    /// 按调用顺序记录成功更新到的版本
+    updated_revisions: Arc<RwLock<Vec<String>>>,
+    /// 按调用顺序记录日志查询条件
+    log_queries: Arc<RwLock<Vec<SvnLogQuery>>>,
+    /// 读取版本属性的次数
+    proplist_calls: Arc<RwLock<usize>>,
+    /// 各版本导出时写出的文件：版本 -> [(相对路径, 内容)]
+    export_files: Arc<RwLock<ExportFiles>>,
+    /// 设置的版本属性：属性名 -> (相对路径 -> 属性值)
+    properties: Arc<RwLock<Properties>>,
+    /// 变更列表：名称 -> 相对路径
+    changelists: Arc<RwLock<Changelists>>,
+    /// 按调用顺序记录只更新部分路径的操作：(版本, 路径)
+    path_updates: Arc<RwLock<PathUpdates>>,
+    /// `list` 返回的仓库目录子项
+    list_entries: Arc<RwLock<Vec<String>>>,
+    /// 工作副本是否需要 `svn upgrade`，需要时 `info` 失败
+    needs_upgrade: Arc<RwLock<bool>>,
+    /// `depth_scope` 返回的检出范围，`None` 表示完整检出
+    depth_scope: Arc<RwLock<Option<SparseScope>>>,
+    /// 工作副本中有本地修改的路径
+    modified_paths: Arc<RwLock<Vec<String>>>,
+    /// 每次访问仓库（日志、更新、检出、导出）前的模拟网络延迟
+    latency: Arc<RwLock<Duration>>,
+    /// 接下来需要模拟网络错误的仓库访问次数
+    transient_failures: Arc<RwLock<usize>>,
+    /// 更新到指定版本时产生冲突的路径：版本 -> 相对路径
+    conflicts: Arc<RwLock<HashMap<String, Vec<String>>>>,
+    /// 与工作副本共用文件的 Git Mock 仓库
+    linked_git: Arc<RwLock<Option<(MockGitOperations, PathBuf)>>>,
+}
+
+impl MockSvnOperations {
+    /// 创建新的Mock SVN操作实例
+    pub fn new() -> Self {
+        Self {
+            repos: Arc::new(RwLock::new(HashMap::new())),
+            failing_revisions: Arc::new(RwLock::new(HashSet::new())),
+            updated_revisions: Arc::new(RwLock::new(Vec::new())),
+            log_queries: Arc::new(RwLock::new(Vec::new())),
+            proplist_calls: Arc::new(RwLock::new(0)),
+            export_files: Arc::new(RwLock::new(HashMap::new())),
+            properties: Arc::new(RwLock::new(HashMap::new())),
+            changelists: Arc::new(RwLock::new(HashMap::new())),
+            path_updates: Arc::new(RwLock::new(Vec::new())),
+            list_entries: Arc::new(RwLock::new(Vec::new())),
+            needs_upgrade: Arc::new(RwLock::new(false)),
+            depth_scope: Arc::new(RwLock::new(None)),
+            modified_paths: Arc::new(RwLock::new(Vec::new())),
+            latency: Arc::new(RwLock::new(Duration::ZERO)),
+            transient_failures: Arc::new(RwLock::new(0)),
+            conflicts: Arc::new(RwLock::new(HashMap::new())),
+            linked_git: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// 向Mock工作副本所属仓库添加一条提交
+    ///
+    /// 如果工作副本尚未初始化，会自动初始化
+    ///
+    /// # 参数
+    ///
+    /// * `repo_path` - 工作副本路径
+    /// * `version` - SVN版本号
+    /// * `message` - 提交说明
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use svn2git::{MockSvnOperations, SvnLogQuery, SvnOperations};
+    /// use std::path::PathBuf;
+    ///
+    /// let svn_ops = MockSvnOperations::new();
+    /// let wc = PathBuf::from("/test/wc");
+    /// svn_ops.add_log_to_mock(&wc, "1", "初始提交");
+    ///
+    /// assert_eq!(svn_ops.get_logs(&wc, &SvnLogQuery::default()).unwrap().len(), 1);
+    /// ```
+    pub fn add_log_to_mock(&self, repo_path: &Path, version: &str, message: &str) {
+        self.add_svn_log_to_mock(
+            repo_path,
+            SvnLog {
+                version: version.to_string(),
+                message: message.to_string(),
+                ..Default::default()
+            },
+        );
+    }
+
+    /// 向Mock工作副本所属仓库添加一条完整的提交（含作者、变更路径）
+    ///
+    /// 如果工作副本尚未初始化，会自动初始化
+    ///
+    /// # 参数
+    ///
+    /// * `repo_path` - 工作副本路径
+    /// * `log` - 提交日志
+    pub fn add_svn_log_to_mock(&self, repo_path: &Path, log: SvnLog) {
+        let mut repos = self.repos.write().unwrap();
+        let repo = repos
+            .entry(repo_path.to_string_lossy().to_string())
+            .or_insert_with(|| MockSvnRepo::new(repo_path.to_path_buf()));
+        repo.initialized = true;
+        repo.add_log(log);
+    }
+
+    /// 设置更新到指定版本时返回错误
+    ///
+    /// # 参数
+    ///
+    /// * `rev` - 模拟失败的版本
+    pub fn fail_update_at(&self, rev: &str) {
+        self.failing_revisions
+            .write()
+            .unwrap()
+            .insert(rev.to_string());
+    }
+
+    /// 取消 [`Self::fail_update_at`] 设置的失败，模拟问题修复后重试
+    ///
+    /// # 参数
+    ///
+    /// * `rev` - 不再失败的版本
+    pub fn clear_update_failure(&self, rev: &str) {
+        self.failing_revisions.write().unwrap().remove(rev);
+    }
+
+    /// 模拟工作副本与 Git 工作目录共用文件
+    ///
+    /// 之后每次成功更新到某个版本，该版本新增、修改和替换的路径（去掉开头的 `/`）
+    /// 都作为工作目录中的更改告知 Git Mock，端到端测试中 `svn update` 由此产生可以提交的更改；
+    /// 没有变更路径的版本在 Git 中是空版本
+    ///
+    /// # 参数
+    ///
+    /// * `git` - Git Mock
+    /// * `git_dir` - Git 仓库路径
+    pub fn link_git(&self, git: MockGitOperations, git_dir: &Path) {
+        *self.linked_git.write().unwrap() = Some((git, git_dir.to_path_buf()));
+    }
+
+    /// 设置每次访问仓库前的模拟网络延迟
+    ///
+    /// 作用于获取日志、更新、检出和导出，用于测试超时和进度提示
+    ///
+    /// # 参数
+    ///
+    /// * `latency` - 延迟时间
+    pub fn set_latency(&self, latency: Duration) {
+        *self.latency.write().unwrap() = latency;
+    }
+
+    /// 设置接下来的若干次仓库访问返回网络错误，之后恢复正常
+    ///
+    /// 按调用次数计数，结果是确定的，用于测试重试和退避
+    ///
+    /// # 参数
+    ///
+    /// * `count` - 连续失败的次数
+    pub fn fail_next_requests(&self, count: usize) {
+        *self.transient_failures.write().unwrap() = count;
+    }
+
+    /// 设置更新到指定版本时产生冲突
+    ///
+    /// 与真实的 `svn update` 一样，工作副本仍然更新到该版本，冲突的路径加入本地修改，
+    /// 返回 [`SyncError::SvnConflict`]
+    ///
+    /// # 参数
+    ///
+    /// * `rev` - SVN版本
+    /// * `paths` - 冲突的相对路径
+    pub fn inject_conflict(&self, rev: &str, paths: &[&str]) {
+        self.conflicts.write().unwrap().insert(
+            rev.to_string(),
+            paths.iter().map(|p| p.to_string()).collect(),
+        );
+    }
+
+    /// 设置导出指定版本时写出的文件
+    ///
+    /// # 参数
+    ///
+    /// * `rev` - SVN版本
+    /// * `file_path` - 相对路径
+    /// * `content` - 文件内容
+    pub fn add_export_file(&self, rev: &str, file_path: &str, content: &str) {
+        self.export_files
+            .write()
+            .unwrap()
+            .entry(rev.to_string())
+            .or_default()
+            .push((file_path.to_string(), content.to_string()));
+    }
+
+    /// 为工作副本中的路径设置版本属性
+    ///
+    /// # 参数
+    ///
+    /// * `file_path` - 相对路径
+    /// * `name` - 属性名
+    /// * `value` - 属性值
+    pub fn set_property(&self, file_path: &str, name: &str, value: &str) {
+        self.properties
+            .write()
+            .unwrap()
+            .entry(name.to_string())
+            .or_default()
+            .insert(file_path.to_string(), value.to_string());
+    }
+
+    /// 把路径加入变更列表
+    ///
+    /// # 参数
+    ///
+    /// * `changelist` - 变更列表名称
+    /// * `file_path` - 相对路径
+    pub fn add_to_changelist(&self, changelist: &str, file_path: &str) {
+        self.changelists
+            .write()
+            .unwrap()
+            .entry(changelist.to_string())
+            .or_default()
+            .push(file_path.to_string());
+    }
+
+    /// 设置 `list` 返回的仓库目录子项
+    ///
+    /// # 参数
+    ///
+    /// * `entries` - 子项名称，目录以 `/` 结尾
+    pub fn set_list_entries(&self, entries: &[&str]) {
+        *self.list_entries.write().unwrap() = entries.iter().map(|e| e.to_string()).collect();
+    }
+
+    /// 模拟升级 svn 客户端后格式过旧的工作副本，`upgrade` 之前 `info` 失败
+    pub fn set_needs_upgrade(&self) {
+        *self.needs_upgrade.write().unwrap() = true;
+    }
+
+    /// 设置工作副本的检出范围（模拟 `svn update --set-depth`）
+    ///
+    /// # 参数
+    ///
+    /// * `scope` - 检出范围
+    pub fn set_depth_scope(&self, scope: SparseScope) {
+        *self.depth_scope.write().unwrap() = Some(scope);
+    }
+
+    /// 设置工作副本中有本地修改的路径
+    ///
+    /// # 参数
+    ///
+    /// * `paths` - 相对路径
+    pub fn set_modified_paths(&self, paths: &[&str]) {
+        *self.modified_paths.write().unwrap() = paths.iter().map(|p| p.to_string()).collect();
+    }
+
+    /// 获取所有只更新部分路径的操作（按调用顺序）
+    pub fn path_updates(&self) -> PathUpdates {
+        self.path_updates.read().unwrap().clone()
+    }
+
+    /// 获取所有成功执行过的更新版本（按调用顺序）
+    pub fn updated_revisions(&self) -> Vec<String> {
+        self.updated_revisions.read().unwrap().clone()
+    }
+
+    /// 获取所有日志查询条件（按调用顺序）
+    pub fn log_queries(&self) -> Vec<SvnLogQuery> {
+        self.log_queries.read().unwrap().clone()
+    }
+
+    /// 读取版本属性的次数
+    pub fn proplist_calls(&self) -> usize {
+        *self.proplist_calls.read().unwrap()
+    }
+
+    /// 获取Mock工作副本状态（用于测试验证）
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 工作副本路径
+    pub fn get_repo_state(&self, path: &Path) -> Option<MockSvnRepo> {
+        let repos = self.repos.read().unwrap();
+        repos.get(path.to_string_lossy().as_ref()).cloned()
+    }
+
+    /// 模拟一次仓库访问：等待设置的延迟，按设置返回网络错误
+    fn simulate_request(&self, operation: &str) -> Result<()> {
+        let latency = *self.latency.read().unwrap();
+        if !latency.is_zero() {
+            thread::sleep(latency);
+        }
+        let mut failures = self.transient_failures.write().unwrap();
+        if *failures > 0 {
+            *failures -= 1;
+            return Err(SyncError::App(format!(
+                "模拟 svn {operation} 网络错误：svn: E170013: Unable to connect to a repository"
+            )));
+        }
+        Ok(())
+    }
+
+    fn initialized_repo(&self, path: &Path) -> Result<MockSvnRepo> {
+        match self.get_repo_state(path) {
+            Some(repo) if repo.is_initialized() => Ok(repo),
+            _ => Err(SyncError::App(format!(
+                "{} 不是SVN工作副本",
+                path.display()
+            ))),
+        }
+    }
+
+    /// 路径所在的工作副本（与真实 svn 一样接受工作副本中的子目录）
+    fn containing_repo(&self, path: &Path) -> Result<MockSvnRepo> {
+        path.ancestors()
+            .find_map(|dir| self.initialized_repo(dir).ok())
+            .ok_or_else(|| SyncError::App(format!("{} 不是SVN工作副本", path.display())))
+    }
+
+    fn update_repo(&self, path: &Path, repo: MockSvnRepo) {
+        let mut repos = self.repos.write().unwrap();
+        repos.insert(path.to_string_lossy().to_string(), repo);
+    }
+}
+
+impl Default for MockSvnOperations {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SvnOperations for MockSvnOperations {
+    fn get_logs(&self, path: &Path, query: &SvnLogQuery) -> Result<Vec<SvnLog>> {
+        // Mock日志没有提交日期，日期范围只记录不过滤
+        self.log_queries.write().unwrap().push(query.clone());
+        self.simulate_request("log")?;
+        let repo = self.initialized_repo(path)?;
+        let mut logs = match &query.after_rev {
+            Some(rev) => repo.logs_after(Some(rev)),
+            None => repo.pending_logs(),
+        };
+        if let Some(limit) = query.limit {
+            logs.truncate(limit);
+        }
+        Ok(logs)
+    }
+
+    fn update_to_rev(&self, path: &Path, rev: &str) -> Result<()> {
+        if self.failing_revisions.read().unwrap().contains(rev) {
+            return Err(SyncError::App(format!("模拟 svn update 到 {rev} 失败")));
+        }
+        self.simulate_request("update")?;
+
+        let mut repo = self.initialized_repo(path)?;
+        repo.update_to(rev)?;
+        let changed: Vec<String> = repo
+            .logs
+            .iter()
+            .filter(|log| log.version == rev)
+            .flat_map(|log| &log.paths)
+            .filter(|changed| changed.action != "D")
+            .map(|changed| changed.path.trim_start_matches('/').to_string())
+            .collect();
+        self.update_repo(path, repo);
+        if let Some(paths) = self.conflicts.read().unwrap().get(rev) {
+            self.modified_paths
+                .write()
+                .unwrap()
+                .extend(paths.iter().cloned());
+            return Err(SyncError::SvnConflict {
+                rev: rev.to_string(),
+                paths: paths.clone(),
+            });
+        }
+        if let Some((git, git_dir)) = &*self.linked_git.read().unwrap() {
+            for file in &changed {
+                git.add_file_to_mock(git_dir, file)?;
+            }
+        }
+        self.updated_revisions
+            .write()
+            .unwrap()
+            .push(rev.to_string());
+        Ok(())
+    }
+
+    fn update_paths_to_rev(&self, path: &Path, rev: &str, paths: &[String]) -> Result<()> {
+        // 工作副本只记录一个版本，部分更新同样推进 BASE
+        self.update_to_rev(path, rev)?;
+        self.path_updates
+            .write()
+            .unwrap()
+            .push((rev.to_string(), paths.to_vec()));
+        Ok(())
+    }
+
+    fn changelist_paths(&self, path: &Path, changelist: &str) -> Result<Vec<String>> {
+        self.initialized_repo(path)?;
+        Ok(self
+            .changelists
+            .read()
+            .unwrap()
+            .get(changelist)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn info(&self, path: &Path) -> Result<SvnInfo> {
+        let repo = self.initialized_repo(path)?;
+        if *self.needs_upgrade.read().unwrap() {
+            return Err(SyncError::SvnCommand {
+                action: "svn info".into(),
+                args: format!("info --xml {}", path.display()),
+                exit_code: Some(1),
+                stderr: "svn: E155036: Please see the 'svn upgrade' command".into(),
+            });
+        }
+        Ok(SvnInfo {
+            url: repo.url.clone(),
+            repository_root: repo.url.clone(),
+            repository_uuid: repo.uuid.clone(),
+            revision: repo.current_revision().unwrap_or("0").to_string(),
+        })
+    }
+
+    fn modified_paths(&self, path: &Path) -> Result<Vec<String>> {
+        self.initialized_repo(path)?;
+        Ok(self.modified_paths.read().unwrap().clone())
+    }
+
+    fn revert_all(&self, path: &Path) -> Result<()> {
+        self.initialized_repo(path)?;
+        self.modified_paths.write().unwrap().clear();
+        Ok(())
+    }
+
+    fn upgrade(&self, path: &Path) -> Result<()> {
+        self.initialized_repo(path)?;
+        *self.needs_upgrade.write().unwrap() = false;
+        Ok(())
+    }
+
+    fn checkout(&self, url: &str, path: &Path, rev: &str) -> Result<()> {
+        self.simulate_request("checkout")?;
+        if self.initialized_repo(path).is_ok() {
+            return Err(SyncError::App(format!(
+                "{} 已经是SVN工作副本",
+                path.display()
+            )));
+        }
+
+        let mut repo = self
+            .get_repo_state(path)
+            .unwrap_or_else(|| MockSvnRepo::new(path.to_path_buf()));
+        repo.init()?;
+        repo.url = url.to_string();
+        repo.current_revision = Some(rev.to_string());
+        self.update_repo(path, repo);
+        Ok(())
+    }
+
+    fn export(&self, path: &Path, rev: &str, dest: &Path) -> Result<()> {
+        self.simulate_request("export")?;
+        self.initialized_repo(path)?;
+        fs::create_dir_all(dest)?;
+        let files = self.export_files.read().unwrap();
+        for (file_path, content) in files.get(rev).into_iter().flatten() {
+            let target = dest.join(file_path);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(target, content)?;
+        }
+        Ok(())
+    }
+
+    fn proplist(&self, path: &Path) -> Result<SvnProperties> {
+        self.containing_repo(path)?;
+        *self.proplist_calls.write().unwrap() += 1;
+        Ok(self
+            .properties
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(name, values)| (name.clone(), values.clone()))
+            .collect())
+    }
+
+    fn list(&self, path: &Path) -> Result<Vec<String>> {
+        self.initialized_repo(path)?;
+        Ok(self.list_entries.read().unwrap().clone())
+    }
+
+    fn depth_scope(&self, path: &Path) -> Result<SparseScope> {
+        self.initialized_repo(path)?;
+        Ok(self
+            .depth_scope
+            .read()
+            .unwrap()
+            .clone()
+            .unwrap_or_else(SparseScope::full))
+    }
+
+    /// 由仓库中该版本日志的变更路径生成摘要
+    fn diff_summary(&self, path: &Path, rev: &str) -> Result<Vec<SvnDiffSummary>> {
+        let repo = self.initialized_repo(path)?;
+        let log = repo
+            .logs
+            .iter()
+            .find(|log| log.version == rev)
+            .ok_or_else(|| SyncError::App(format!("版本 {rev} 不存在")))?;
+        Ok(log
+            .paths
+            .iter()
+            .map(|changed| SvnDiffSummary {
+                item: match changed.action.as_str() {
+                    "A" => "added",
+                    "D" => "deleted",
+                    "R" => "replaced",
+                    _ => "modified",
+                }
+                .to_string(),
+                props: "none".to_string(),
+                kind: changed.kind.clone(),
+                path: changed.path.trim_start_matches('/').to_string(),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_svn_repo_creation() {
+        let repo = MockSvnRepo::new("/test".into());
+        assert!(!repo.is_initialized());
+        assert!(repo.current_revision().is_none());
+    }
+
+    #[test]
+    fn test_mock_svn_repo_init() {
+        let mut repo = MockSvnRepo::new("/test".into());
+        assert!(repo.init().is_ok());
+        assert!(repo.is_initialized());
+        assert!(repo.init().is_err());
+    }
+
+    #[test]
+    fn test_get_logs_should_only_return_revisions_after_base() {
+        let ops = MockSvnOperations::new();
+        let wc = PathBuf::from("/test/wc");
+        ops.add_log_to_mock(&wc, "1", "a");
+        ops.add_log_to_mock(&wc, "2", "b");
+        ops.add_log_to_mock(&wc, "3", "c");
+
+        ops.update_to_rev(&wc, "1").unwrap();
+        let logs = ops.get_logs(&wc, &SvnLogQuery::default()).unwrap();
+        let versions: Vec<_> = logs.iter().map(|l| l.version.as_str()).collect();
+        assert_eq!(versions, vec!["2", "3"]);
+        assert_eq!(ops.updated_revisions(), vec!["1"]);
+    }
+
+    #[test]
+    fn test_update_to_rev_should_fail_when_configured() {
+        let ops = MockSvnOperations::new();
+        let wc = PathBuf::from("/test/wc");
+        ops.add_log_to_mock(&wc, "1", "a");
+        ops.fail_update_at("1");
+
+        assert!(ops.update_to_rev(&wc, "1").is_err());
+        assert!(ops.updated_revisions().is_empty());
+    }
+
+    #[test]
+    fn test_info_and_checkout() {
+        let ops = MockSvnOperations::new();
+        let wc = PathBuf::from("/test/wc");
+        assert!(ops.info(&wc).is_err());
+
+        ops.checkout("https://svn.example.com/repo", &wc, "5")
+            .unwrap();
+        let info = ops.info(&wc).unwrap();
+        assert_eq!(info.url, "https://svn.example.com/repo");
+        assert_eq!(info.revision, "5");
+
+        assert!(
+            ops.checkout("https://svn.example.com/repo", &wc, "5")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_simulated_latency_and_transient_failures() {
+        let ops = MockSvnOperations::new();
+        let wc = PathBuf::from("/test/wc");
+        ops.add_log_to_mock(&wc, "1", "a");
+        ops.set_latency(Duration::from_millis(20));
+        ops.fail_next_requests(2);
+
+        let started = std::time::Instant::now();
+        let err = ops.get_logs(&wc, &SvnLogQuery::default()).unwrap_err();
+        assert!(err.to_string().contains("E170013"), "{err}");
+        assert!(ops.update_to_rev(&wc, "1").is_err());
+        assert!(started.elapsed() >= Duration::from_millis(40));
+
+        // 失败次数用完后恢复正常
+        assert_eq!(ops.get_logs(&wc, &SvnLogQuery::default()).unwrap().len(), 1);
+        ops.update_to_rev(&wc, "1").unwrap();
+        assert_eq!(ops.updated_revisions(), ["1"]);
+    }
+
+    #[test]
+    fn test_injected_conflict_should_update_working_copy_and_report_paths() {
+        let ops = MockSvnOperations::new();
+        let wc = PathBuf::from("/test/wc");
+        ops.add_log_to_mock(&wc, "1", "a");
+        ops.add_log_to_mock(&wc, "2", "b");
+        ops.inject_conflict("2", &["src/a.rs", "src/b.rs"]);
+
+        ops.update_to_rev(&wc, "1").unwrap();
+        let err = ops.update_to_rev(&wc, "2").unwrap_err();
+        assert!(
+            matches!(&err, SyncError::SvnConflict { rev, paths } if rev == "2" && paths == &["src/a.rs", "src/b.rs"]),
+            "{err}"
+        );
+        assert_eq!(ops.info(&wc).unwrap().revision, "2");
+        assert_eq!(ops.modified_paths(&wc).unwrap(), ["src/a.rs", "src/b.rs"]);
+        assert_eq!(ops.updated_revisions(), ["1"]);
+
+        ops.revert_all(&wc).unwrap();
+        assert!(ops.modified_paths(&wc).unwrap().is_empty());
+        assert_eq!(ops.info(&wc).unwrap().revision, "2");
+    }
+}
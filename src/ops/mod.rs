@@ -1,18 +1,50 @@
+mod command_env;
+mod dump;
 mod git;
+mod git_attributes;
 mod git_operations;
 mod git_provider;
+mod libgit2_git;
+mod log_encoding;
 mod mock_git;
+mod mock_svn;
 mod real_git;
+mod real_svn;
 mod svn;
+mod svn_operations;
+mod svn_provider;
+
+// 外部命令环境变量
+pub use command_env::{CommandEnv, CommandSettings, parse_env_assignment};
+pub(crate) use command_env::{command_error, output_with_timeout};
 
 // Git操作抽象和实现
 pub use git_operations::{
-    GitOperations, GitOperationsFactory, GitProvider, MockGitOperations, ProviderType,
-    RealGitOperations,
+    CommitMetadata, CustomGitBackend, Git2Operations, GitBackendFactory, GitCapabilities,
+    GitCommitEntry, GitOperations, GitOperationsFactory, GitProvider, GitSignature,
+    MockGitOperations, ProviderType, RealGitOperations, register_git_backend,
+    registered_git_backends,
 };
 
 // Git操作函数（只导出公共API）
-pub use git::{git_commit_real, git_commit_with_ops};
+pub use git::{git_commit_paths_with_ops, git_commit_real, git_commit_with_ops};
+
+// .gitattributes / .gitignore 维护
+pub use git_attributes::{
+    gitattributes_pattern, update_git_exclude_section, update_gitattributes_section,
+    update_gitignore_section,
+};
+
+// SVN操作抽象和实现
+pub use svn_operations::{
+    MockSvnOperations, MockSvnRepo, RealSvnOperations, SvnOperations, SvnOperationsFactory,
+    SvnProvider,
+};
 
 // SVN操作
 pub use svn::*;
+
+// 旧提交说明的转码
+
+// svnadmin dump 解析
+pub use dump::*;
@@ -2,15 +2,28 @@
 //!
 //! 使用真实的git命令执行操作，用于生产环境
 
-use super::git_operations::GitOperations;
-use crate::error::{Result, SyncError};
-use std::path::Path;
+use super::command_env::{
+    CommandEnv, CommandSettings, command_error, command_with_env, spawn_error, wait_child,
+};
+use super::git_operations::{CommitMetadata, GitCommitEntry, GitOperations, GitSignature};
+use crate::error::Result;
+use crate::telemetry::{TracedCommand, command_span};
+use chrono::{DateTime, FixedOffset};
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+};
 
 /// 真实Git操作实现
 ///
 /// 使用真实的git命令执行操作
 #[derive(Debug, Clone)]
-pub struct RealGitOperations;
+pub struct RealGitOperations {
+    env: CommandEnv,
+    settings: CommandSettings,
+}
 
 impl RealGitOperations {
     /// 创建新的真实Git操作实例
@@ -27,23 +40,112 @@ impl RealGitOperations {
     /// let git_ops = RealGitOperations::new();
     /// ```
     pub fn new() -> Self {
-        Self
+        Self::with_env(CommandEnv::new())
+    }
+
+    /// 创建执行git命令时附带额外环境变量的实例
+    ///
+    /// # 参数
+    ///
+    /// * `env` - 额外的环境变量
+    pub fn with_env(env: CommandEnv) -> Self {
+        Self {
+            env,
+            settings: CommandSettings::default(),
+        }
+    }
+
+    /// 设置执行git命令的设置（超时时间等）
+    ///
+    /// # 参数
+    ///
+    /// * `settings` - 执行命令的设置
+    pub fn with_settings(mut self, settings: CommandSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    fn git(&self) -> Command {
+        command_with_env("git", &self.env)
+    }
+
+    /// 最近一次 stash 的提交哈希，没有 stash 时为 `None`
+    fn stash_ref(&self, path: &Path) -> Result<Option<String>> {
+        let output = self
+            .git()
+            .args(["rev-parse", "-q", "--verify", "refs/stash"])
+            .current_dir(path)
+            .traced_output(self.settings.git_timeout)?;
+        Ok(output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string()))
+    }
+
+    /// 执行命令并通过标准输入传入文本，命令失败时返回 [`SyncError::GitCommand`](crate::SyncError::GitCommand)
+    ///
+    /// 提交说明等文本不放在命令行参数中，多行内容、引号和超长文本都能原样传递，
+    /// 也不受 Windows 命令行长度限制
+    fn output_with_stdin(
+        &self,
+        command: &mut Command,
+        input: &str,
+        action: &str,
+    ) -> Result<Output> {
+        let span = command_span(command);
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| spawn_error(command, e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(input.as_bytes())?;
+        }
+        let output = wait_child(command, child, self.settings.git_timeout)?;
+        span.record_exit(&output.status);
+        if !output.status.success() {
+            return Err(command_error(action, command, &output));
+        }
+        Ok(output)
+    }
+
+    /// 执行 `git commit`，通过环境变量设置作者，指定时间时同时设置作者时间和提交时间
+    ///
+    /// 提交说明通过 `-F -` 从标准输入传入
+    fn run_commit(&self, path: &Path, message: &str, metadata: &CommitMetadata) -> Result<()> {
+        let mut command = self.git();
+        command.args(["commit", "-F", "-"]);
+        if metadata.allow_empty {
+            command.arg("--allow-empty");
+        }
+        if let Some(name) = &metadata.author_name {
+            command.env("GIT_AUTHOR_NAME", name);
+        }
+        if let Some(email) = &metadata.author_email {
+            command.env("GIT_AUTHOR_EMAIL", email);
+        }
+        if let Some(date) = metadata.date {
+            let date = git_date(date);
+            command
+                .env("GIT_AUTHOR_DATE", &date)
+                .env("GIT_COMMITTER_DATE", &date);
+        }
+        self.output_with_stdin(command.current_dir(path), message, "Git commit")?;
+        Ok(())
     }
 
     /// 检查Git是否可用
     ///
     /// # 返回值
     ///
-    /// * `Ok(())` - Git可用
-    /// * `Err(SyncError)` - Git不可用
-    pub fn check_git_available() -> Result<()> {
-        let output = std::process::Command::new("git").arg("--version").output();
-
-        match output {
-            Ok(output) if output.status.success() => Ok(()),
-            Ok(_) => Err(SyncError::App("Git命令执行失败".to_string())),
-            Err(e) => Err(SyncError::App(format!("无法执行Git命令: {}", e))),
-        }
+    /// * `Ok(String)` - Git可用，返回 `git --version` 的输出
+    /// * `Err(SyncError)` - Git不可用，找不到git命令时为 [`SyncError::CommandNotFound`](crate::SyncError::CommandNotFound)
+    pub fn check_git_available() -> Result<String> {
+        let output = Command::new("git")
+            .arg("--version")
+            .checked_output("检查Git版本", None)?;
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
     }
 }
 
@@ -55,172 +157,403 @@ impl Default for RealGitOperations {
 
 impl GitOperations for RealGitOperations {
     fn init(&self, path: &Path) -> Result<()> {
-        let output = std::process::Command::new("git")
+        self.git()
             .arg("init")
             .current_dir(path)
-            .output()?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(SyncError::App(format!(
-                "Git初始化失败，路径: {:?}, 错误: {}",
-                path,
-                if stderr.is_empty() {
-                    "无详细信息"
-                } else {
-                    &stderr
-                }
-            )));
-        }
+            .checked_output("Git初始化", self.settings.git_timeout)?;
 
         Ok(())
     }
 
     fn config_user(&self, path: &Path, name: &str, email: &str) -> Result<()> {
         // 配置用户名
-        let name_output = std::process::Command::new("git")
+        self.git()
             .args(["config", "user.name", name])
             .current_dir(path)
-            .output()?;
-
-        if !name_output.status.success() {
-            let stderr = String::from_utf8_lossy(&name_output.stderr);
-            return Err(SyncError::App(format!(
-                "配置Git用户名失败，路径: {:?}, 错误: {}",
-                path,
-                if stderr.is_empty() {
-                    "无详细信息"
-                } else {
-                    &stderr
-                }
-            )));
-        }
+            .checked_output("配置Git用户名", self.settings.git_timeout)?;
 
         // 配置邮箱
-        let email_output = std::process::Command::new("git")
+        self.git()
             .args(["config", "user.email", email])
             .current_dir(path)
-            .output()?;
-
-        if !email_output.status.success() {
-            let stderr = String::from_utf8_lossy(&email_output.stderr);
-            return Err(SyncError::App(format!(
-                "配置Git邮箱失败，路径: {:?}, 错误: {}",
-                path,
-                if stderr.is_empty() {
-                    "无详细信息"
-                } else {
-                    &stderr
-                }
-            )));
-        }
+            .checked_output("配置Git邮箱", self.settings.git_timeout)?;
 
         Ok(())
     }
 
     fn add_all(&self, path: &Path) -> Result<()> {
-        let output = std::process::Command::new("git")
+        self.git()
             .args(["add", "."])
             .current_dir(path)
-            .output()?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(SyncError::App(format!(
-                "Git add失败，路径: {:?}, 错误: {}",
-                path,
-                if stderr.is_empty() {
-                    "无详细信息"
-                } else {
-                    &stderr
-                }
-            )));
-        }
+            .checked_output("Git add", self.settings.git_timeout)?;
 
         Ok(())
     }
 
-    fn commit(&self, path: &Path, message: &str) -> Result<()> {
-        let output = std::process::Command::new("git")
-            .args(["commit", "-m", message])
+    fn add_paths(&self, path: &Path, files: &[String]) -> Result<()> {
+        self.git()
+            .args(["add", "-A", "--"])
+            .args(files)
             .current_dir(path)
-            .output()?;
+            .checked_output("Git add", self.settings.git_timeout)?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            return Err(SyncError::App(format!(
-                "Git commit失败，路径: {:?}, 提交信息: '{}', stdout: {}, stderr: {}",
-                path,
-                message,
-                if stdout.is_empty() {
-                    "无输出"
-                } else {
-                    &stdout
-                },
-                if stderr.is_empty() {
-                    "无错误信息"
-                } else {
-                    &stderr
-                }
-            )));
-        }
+        Ok(())
+    }
+
+    fn set_executable(&self, path: &Path, files: &[String]) -> Result<()> {
+        self.git()
+            .args(["update-index", "--chmod=+x", "--"])
+            .args(files)
+            .current_dir(path)
+            .checked_output("设置Git可执行位", self.settings.git_timeout)?;
 
         Ok(())
     }
 
+    fn commit(&self, path: &Path, message: &str) -> Result<()> {
+        self.run_commit(path, message, &CommitMetadata::default())
+    }
+
+    fn commit_with(&self, path: &Path, message: &str, metadata: &CommitMetadata) -> Result<()> {
+        self.run_commit(path, message, metadata)
+    }
+
     fn status(&self, path: &Path) -> Result<String> {
-        let output = std::process::Command::new("git")
+        let output = self
+            .git()
             .args(["status", "--porcelain"])
             .current_dir(path)
-            .output()?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(SyncError::App(format!(
-                "获取Git状态失败，路径: {:?}, 错误: {}",
-                path,
-                if stderr.is_empty() {
-                    "无详细信息"
-                } else {
-                    &stderr
-                }
-            )));
-        }
+            .checked_output("获取Git状态", self.settings.git_timeout)?;
 
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
     fn log(&self, path: &Path, count: Option<usize>) -> Result<String> {
-        let mut cmd = std::process::Command::new("git");
+        let mut cmd = self.git();
         cmd.args(["log", "--oneline"]);
 
         if let Some(n) = count {
             cmd.args(["-n", &n.to_string()]);
         }
 
-        let output = cmd.current_dir(path).output()?;
+        let output = cmd
+            .current_dir(path)
+            .checked_output("获取Git日志", self.settings.git_timeout)?;
 
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    fn is_repository(&self, path: &Path) -> Result<bool> {
+        if !path.is_dir() {
+            return Ok(false);
+        }
+        let output = self
+            .git()
+            .args(["rev-parse", "--show-toplevel"])
+            .current_dir(path)
+            .traced_output(self.settings.git_timeout)?;
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(SyncError::App(format!(
-                "获取Git日志失败，路径: {:?}, 错误: {}",
-                path,
-                if stderr.is_empty() {
-                    "无详细信息"
-                } else {
-                    &stderr
-                }
-            )));
+            return Ok(false);
         }
-
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        // 位于其他仓库中的子目录也能执行成功，需要比较仓库根目录
+        let toplevel = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+        Ok(toplevel.canonicalize()? == path.canonicalize()?)
     }
 
     fn is_clean(&self, path: &Path) -> Result<bool> {
         let status_output = self.status(path)?;
         Ok(status_output.trim().is_empty())
     }
+
+    fn rev_parse_head(&self, path: &Path) -> Result<String> {
+        let output = self
+            .git()
+            .args(["rev-parse", "HEAD"])
+            .current_dir(path)
+            .checked_output("获取Git HEAD", self.settings.git_timeout)?;
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    fn root_commit(&self, path: &Path) -> Result<String> {
+        let output = self
+            .git()
+            .args(["rev-list", "--first-parent", "--max-parents=0", "HEAD"])
+            .current_dir(path)
+            .checked_output("获取Git根提交", self.settings.git_timeout)?;
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    fn commit_history(&self, path: &Path, notes_ref: &str) -> Result<Vec<GitCommitEntry>> {
+        // 字段用 NUL 分隔，提交之间用 RS 分隔，提交说明和 note 中都不会出现
+        let output = self
+            .git()
+            .args(["log", "--first-parent", "--reverse"])
+            .arg(format!("--notes={notes_ref}"))
+            .arg("--format=%H%x00%B%x00%N%x1e")
+            .current_dir(path)
+            .checked_output("读取Git提交历史", self.settings.git_timeout)?;
+
+        Ok(parse_commit_history(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    }
+
+    fn tree_blobs(&self, path: &Path, rev: &str) -> Result<BTreeMap<String, String>> {
+        let output = self
+            .git()
+            .args(["ls-tree", "-r", "-z", "--full-tree", rev])
+            .current_dir(path)
+            .checked_output("读取Git提交的文件列表", self.settings.git_timeout)?;
+
+        Ok(parse_ls_tree(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    fn hash_files(&self, path: &Path, files: &[PathBuf]) -> Result<Vec<String>> {
+        if files.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut command = self.git();
+        command.args(["hash-object", "--stdin-paths"]);
+        let span = command_span(&command);
+        let mut child = command
+            .current_dir(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| spawn_error(&command, e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            for file in files {
+                writeln!(stdin, "{}", file.display())?;
+            }
+        }
+        let output = child.wait_with_output()?;
+        span.record_exit(&output.status);
+
+        if !output.status.success() {
+            return Err(command_error("计算文件哈希", &command, &output));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect())
+    }
+
+    fn add_note(&self, path: &Path, notes_ref: &str, commit: &str, note: &str) -> Result<()> {
+        self.output_with_stdin(
+            self.git()
+                .args(["notes", "--ref", notes_ref, "add", "-f", "-F", "-", commit])
+                .current_dir(path),
+            note,
+            "添加Git note",
+        )?;
+
+        Ok(())
+    }
+
+    fn reset_to(&self, path: &Path, commit: Option<&str>) -> Result<()> {
+        let steps: &[&[&str]] = match commit {
+            Some(commit) => &[&["reset", "-q", "--mixed", commit]],
+            None => &[&["update-ref", "-d", "HEAD"], &["read-tree", "--empty"]],
+        };
+
+        for args in steps {
+            self.git()
+                .args(*args)
+                .current_dir(path)
+                .checked_output("Git reset", self.settings.git_timeout)?;
+        }
+
+        Ok(())
+    }
+
+    fn stash(&self, path: &Path, message: &str) -> Result<bool> {
+        // 没有可暂存的更改时 git stash 也返回成功，比较前后的 refs/stash 判断是否创建
+        let before = self.stash_ref(path)?;
+        self.git()
+            .args(["stash", "push", "-q", "-m", message])
+            .current_dir(path)
+            .checked_output("暂存Git更改", self.settings.git_timeout)?;
+        Ok(self.stash_ref(path)? != before)
+    }
+
+    fn stash_pop(&self, path: &Path) -> Result<()> {
+        self.git()
+            .args(["stash", "pop", "-q"])
+            .current_dir(path)
+            .checked_output("恢复暂存的Git更改", self.settings.git_timeout)?;
+        Ok(())
+    }
+
+    fn create_branch(&self, path: &Path, name: &str, start: Option<&str>) -> Result<()> {
+        let mut command = self.git();
+        command.args(["branch", "--no-track", name]);
+        if let Some(start) = start {
+            command.arg(start);
+        }
+        command
+            .current_dir(path)
+            .checked_output("创建Git分支", self.settings.git_timeout)?;
+
+        Ok(())
+    }
+
+    fn checkout_branch(&self, path: &Path, name: &str) -> Result<()> {
+        let reference = format!("refs/heads/{name}");
+        let args: &[&str] = if self.rev_parse_head(path).is_ok() {
+            &["checkout", "-q", name]
+        } else {
+            &["symbolic-ref", "HEAD", &reference]
+        };
+        self.git()
+            .args(args)
+            .current_dir(path)
+            .checked_output("切换Git分支", self.settings.git_timeout)?;
+
+        Ok(())
+    }
+
+    fn tag(
+        &self,
+        path: &Path,
+        name: &str,
+        target: &str,
+        message: &str,
+        tagger: Option<&GitSignature>,
+    ) -> Result<()> {
+        let mut command = self.git();
+        command.args(["tag", "-a", name, target, "-F", "-"]);
+        // 附注标签的创建者取自提交者身份
+        if let Some(tagger) = tagger {
+            command
+                .env("GIT_COMMITTER_NAME", &tagger.name)
+                .env("GIT_COMMITTER_EMAIL", &tagger.email);
+            if let Some(date) = tagger.date {
+                command.env("GIT_COMMITTER_DATE", format!("@{} +0000", date.timestamp()));
+            }
+        }
+        self.output_with_stdin(command.current_dir(path), message, "创建Git标签")?;
+        Ok(())
+    }
+
+    fn add_remote(&self, path: &Path, name: &str, url: &str) -> Result<()> {
+        let exists = self
+            .git()
+            .args(["remote", "get-url", name])
+            .current_dir(path)
+            .traced_output(self.settings.git_timeout)?
+            .status
+            .success();
+        let action = if exists { "set-url" } else { "add" };
+        self.git()
+            .args(["remote", action, name, url])
+            .current_dir(path)
+            .checked_output("设置Git远程仓库", self.settings.git_timeout)?;
+
+        Ok(())
+    }
+
+    fn push(&self, path: &Path, remote: &str) -> Result<()> {
+        self.git()
+            .args([
+                "push",
+                remote,
+                "refs/heads/*:refs/heads/*",
+                "refs/tags/*:refs/tags/*",
+                "+refs/svn2git/*:refs/svn2git/*",
+            ])
+            .current_dir(path)
+            .checked_output("Git推送", self.settings.git_timeout)?;
+
+        Ok(())
+    }
+
+    fn bundle(&self, path: &Path, file: &Path) -> Result<()> {
+        // git 在仓库目录中执行，相对路径需要先转换
+        let file = std::path::absolute(file)?;
+        self.git()
+            .args(["bundle", "create"])
+            .arg(&file)
+            .args(["--branches", "--tags"])
+            .current_dir(path)
+            .checked_output("生成 git bundle", self.settings.git_timeout)?;
+        Ok(())
+    }
+
+    fn write_blob_ref(&self, path: &Path, reference: &str, content: &str) -> Result<()> {
+        let output = self.output_with_stdin(
+            self.git()
+                .args(["hash-object", "-w", "--stdin"])
+                .current_dir(path),
+            content,
+            "写入Git blob",
+        )?;
+        let blob = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        self.git()
+            .args(["update-ref", reference, &blob])
+            .current_dir(path)
+            .checked_output("更新Git引用", self.settings.git_timeout)?;
+        Ok(())
+    }
+
+    fn read_blob_ref(&self, path: &Path, reference: &str) -> Result<Option<String>> {
+        let exists = self
+            .git()
+            .args(["rev-parse", "-q", "--verify", reference])
+            .current_dir(path)
+            .traced_output(self.settings.git_timeout)?
+            .status
+            .success();
+        if !exists {
+            return Ok(None);
+        }
+        let output = self
+            .git()
+            .args(["cat-file", "blob", reference])
+            .current_dir(path)
+            .checked_output("读取Git引用", self.settings.git_timeout)?;
+        Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+    }
+}
+
+/// 解析 [`RealGitOperations::commit_history`] 的输出
+fn parse_commit_history(output: &str) -> Vec<GitCommitEntry> {
+    output
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(3, '\0');
+            let hash = fields.next().filter(|hash| !hash.is_empty())?;
+            let message = fields.next().unwrap_or_default();
+            let note = fields.next().unwrap_or_default();
+            Some(GitCommitEntry {
+                hash: hash.to_string(),
+                message: message.trim_end().to_string(),
+                note: (!note.trim().is_empty()).then(|| note.trim_end().to_string()),
+            })
+        })
+        .collect()
+}
+
+/// 转换为 Git 内部时间格式 `@<秒> <+hhmm>`
+fn git_date(date: DateTime<FixedOffset>) -> String {
+    format!("@{} {}", date.timestamp(), date.format("%z"))
+}
+
+/// 解析 `git ls-tree -r -z` 的输出，只保留普通文件（blob）
+fn parse_ls_tree(output: &str) -> BTreeMap<String, String> {
+    output
+        .split('\0')
+        .filter_map(|entry| {
+            let (meta, file) = entry.split_once('\t')?;
+            let mut parts = meta.split_whitespace();
+            let (_mode, kind, hash) = (parts.next()?, parts.next()?, parts.next()?);
+            (kind == "blob").then(|| (file.to_string(), hash.to_string()))
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -228,6 +561,180 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_parse_ls_tree() {
+        let output = [
+            "100644 blob aaa\tsrc/main.rs",
+            "160000 commit bbb\tvendor/lib",
+            "100755 blob ccc\trun.sh",
+            "",
+        ]
+        .join("\0");
+        let blobs = parse_ls_tree(&output);
+        assert_eq!(blobs.len(), 2);
+        assert_eq!(blobs.get("src/main.rs").map(String::as_str), Some("aaa"));
+        assert_eq!(blobs.get("run.sh").map(String::as_str), Some("ccc"));
+    }
+
+    #[test]
+    fn test_git_date() {
+        let date = DateTime::parse_from_rfc3339("2024-01-02T11:04:05+08:00").unwrap();
+        assert_eq!(git_date(date), "@1704164645 +0800");
+    }
+
+    #[test]
+    fn test_commit_message_via_stdin_should_survive_intact() {
+        if RealGitOperations::check_git_available().is_err() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let ops = RealGitOperations::new();
+        ops.init(dir.path()).unwrap();
+        ops.config_user(dir.path(), "测试用户", "test@example.com")
+            .unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        ops.add_all(dir.path()).unwrap();
+
+        let message = format!(
+            "SVN: \"引号\" 'single' $HOME `cmd`\n\n-m 不是参数\n{}",
+            "长".repeat(20_000)
+        );
+        ops.commit(dir.path(), &message).unwrap();
+        let output = Command::new("git")
+            .args(["log", "-1", "--format=%B"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim_end(), message);
+    }
+
+    #[test]
+    fn test_bundle_should_be_cloneable() {
+        if RealGitOperations::check_git_available().is_err() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let ops = RealGitOperations::new();
+        ops.init(dir.path()).unwrap();
+        ops.config_user(dir.path(), "测试用户", "test@example.com")
+            .unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        ops.add_all(dir.path()).unwrap();
+        ops.commit(dir.path(), "SVN: init").unwrap();
+
+        let out = tempfile::tempdir().unwrap();
+        let bundle = out.path().join("repo.bundle");
+        ops.bundle(dir.path(), &bundle).unwrap();
+        let status = Command::new("git")
+            .args(["clone", "-q"])
+            .arg(&bundle)
+            .arg(out.path().join("clone"))
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert_eq!(
+            std::fs::read_to_string(out.path().join("clone/a.txt")).unwrap(),
+            "a"
+        );
+    }
+
+    #[test]
+    fn test_blob_ref_should_round_trip_between_backends() {
+        if RealGitOperations::check_git_available().is_err() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let ops = RealGitOperations::new();
+        let git2 = crate::ops::Git2Operations::new();
+        ops.init(dir.path()).unwrap();
+        let reference = "refs/svn2git/state";
+        assert_eq!(ops.read_blob_ref(dir.path(), reference).unwrap(), None);
+        assert_eq!(git2.read_blob_ref(dir.path(), reference).unwrap(), None);
+
+        ops.write_blob_ref(dir.path(), reference, "pair-id: 0\n")
+            .unwrap();
+        assert_eq!(
+            git2.read_blob_ref(dir.path(), reference)
+                .unwrap()
+                .as_deref(),
+            Some("pair-id: 0\n")
+        );
+        git2.write_blob_ref(dir.path(), reference, "pair-id: 1\n")
+            .unwrap();
+        assert_eq!(
+            ops.read_blob_ref(dir.path(), reference).unwrap().as_deref(),
+            Some("pair-id: 1\n")
+        );
+    }
+
+    #[test]
+    fn test_commit_history_should_list_first_parent_commits_with_notes() {
+        if RealGitOperations::check_git_available().is_err() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let ops = RealGitOperations::new();
+        ops.init(dir.path()).unwrap();
+        ops.config_user(dir.path(), "测试用户", "test@example.com")
+            .unwrap();
+        for (content, message) in [("a", "SVN: first\n\nbody"), ("b", "SVN: second")] {
+            std::fs::write(dir.path().join("a.txt"), content).unwrap();
+            ops.add_all(dir.path()).unwrap();
+            ops.commit(dir.path(), message).unwrap();
+        }
+        ops.add_note(dir.path(), "refs/notes/svn", "HEAD", "SVN-Revision: 2")
+            .unwrap();
+
+        let history = ops.commit_history(dir.path(), "refs/notes/svn").unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].message, "SVN: first\n\nbody");
+        assert_eq!(history[0].note, None);
+        assert_eq!(history[1].hash, ops.rev_parse_head(dir.path()).unwrap());
+        assert_eq!(history[1].note.as_deref(), Some("SVN-Revision: 2"));
+        let git2 = crate::ops::Git2Operations::new();
+        assert_eq!(
+            git2.commit_history(dir.path(), "refs/notes/svn").unwrap(),
+            history
+        );
+    }
+
+    #[test]
+    fn test_push_should_update_remote_branches_and_tags() {
+        if RealGitOperations::check_git_available().is_err() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let remote = tempfile::tempdir().unwrap();
+        let ops = RealGitOperations::new();
+        ops.init(dir.path()).unwrap();
+        ops.config_user(dir.path(), "测试用户", "test@example.com")
+            .unwrap();
+        Command::new("git")
+            .args(["init", "-q", "--bare"])
+            .current_dir(remote.path())
+            .output()
+            .unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        ops.add_all(dir.path()).unwrap();
+        ops.commit(dir.path(), "first").unwrap();
+        ops.tag(dir.path(), "v1.0", "HEAD", "release", None)
+            .unwrap();
+
+        assert!(ops.push(dir.path(), "origin").is_err());
+        ops.add_remote(dir.path(), "origin", "/missing").unwrap();
+        ops.add_remote(dir.path(), "origin", remote.path().to_str().unwrap())
+            .unwrap();
+        ops.push(dir.path(), "origin").unwrap();
+        let output = Command::new("git")
+            .args(["for-each-ref", "--format=%(refname)"])
+            .current_dir(remote.path())
+            .output()
+            .unwrap();
+        let refs = String::from_utf8_lossy(&output.stdout);
+        assert!(refs.lines().any(|r| r.starts_with("refs/heads/")));
+        assert!(refs.lines().any(|r| r == "refs/tags/v1.0"));
+    }
+
     #[test]
     fn test_real_git_operations_creation() {
         let _ops = RealGitOperations::new();
@@ -244,6 +751,56 @@ mod tests {
         // 不强制断言，因为测试环境可能没有Git
     }
 
+    #[test]
+    fn test_is_repository_should_only_accept_repository_root() {
+        if RealGitOperations::check_git_available().is_err() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let ops = RealGitOperations::new();
+        assert!(!ops.is_repository(dir.path()).unwrap());
+        assert!(!ops.is_repository(&dir.path().join("missing")).unwrap());
+
+        ops.init(dir.path()).unwrap();
+        let sub_dir = dir.path().join("sub");
+        std::fs::create_dir(&sub_dir).unwrap();
+        assert!(ops.is_repository(dir.path()).unwrap());
+        assert!(!ops.is_repository(&sub_dir).unwrap());
+    }
+
+    #[test]
+    fn test_stash_should_keep_untracked_files_and_restore_changes() {
+        if RealGitOperations::check_git_available().is_err() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let ops = RealGitOperations::new();
+        ops.init(dir.path()).unwrap();
+        ops.config_user(dir.path(), "测试用户", "test@example.com")
+            .unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        ops.add_all(dir.path()).unwrap();
+        ops.commit(dir.path(), "初始提交").unwrap();
+
+        std::fs::write(dir.path().join("new.txt"), "new").unwrap();
+        assert!(!ops.stash(dir.path(), "暂存").unwrap(), "只有未跟踪的文件");
+
+        std::fs::write(dir.path().join("a.txt"), "changed").unwrap();
+        assert!(ops.stash(dir.path(), "暂存").unwrap());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+            "a"
+        );
+        assert!(dir.path().join("new.txt").exists());
+
+        ops.stash_pop(dir.path()).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("a.txt")).unwrap(),
+            "changed"
+        );
+        assert!(ops.stash_pop(dir.path()).is_err());
+    }
+
     #[test]
     fn test_real_git_status_on_invalid_path() {
         let ops = RealGitOperations::new();
@@ -276,6 +833,22 @@ mod tests {
         assert!(result.is_err(), "在无效路径上执行Git提交应该返回错误");
     }
 
+    #[test]
+    fn test_real_git_rev_parse_head_on_invalid_path() {
+        let ops = RealGitOperations::new();
+        let invalid_path = PathBuf::from("/不存在的路径");
+        let result = ops.rev_parse_head(&invalid_path);
+        assert!(result.is_err(), "在无效路径上获取HEAD应该返回错误");
+    }
+
+    #[test]
+    fn test_real_git_add_note_on_invalid_path() {
+        let ops = RealGitOperations::new();
+        let invalid_path = PathBuf::from("/不存在的路径");
+        let result = ops.add_note(&invalid_path, "refs/notes/svn", "HEAD", "SVN-Revision: 1");
+        assert!(result.is_err(), "在无效路径上添加note应该返回错误");
+    }
+
     #[test]
     fn test_real_git_init_on_invalid_path() {
         let ops = RealGitOperations::new();
@@ -284,4 +857,27 @@ mod tests {
         // 在无法创建的路径上初始化Git应该失败
         assert!(result.is_err(), "在无法创建的路径上初始化Git应该返回错误");
     }
+
+    #[test]
+    fn test_failed_command_should_return_git_command_error() {
+        if RealGitOperations::check_git_available().is_err() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let ops = RealGitOperations::new();
+        ops.init(dir.path()).unwrap();
+        ops.config_user(dir.path(), "测试用户", "test@example.com")
+            .unwrap();
+
+        let err = ops.commit(dir.path(), "空提交").unwrap_err();
+        assert!(
+            matches!(&err, crate::SyncError::GitCommand { args, exit_code: Some(1), stderr, .. }
+                if args == "commit -F -" && !stderr.is_empty()),
+            "{err:?}"
+        );
+        assert!(
+            err.to_string()
+                .starts_with("Git commit失败（git commit -F -，退出码 1）：")
+        );
+    }
 }
@@ -0,0 +1,169 @@
+//! 真实SVN操作实现
+//!
+//! 使用真实的svn命令执行操作，用于生产环境
+
+use std::path::Path;
+
+use super::command_env::{CommandEnv, CommandSettings};
+use super::svn::{
+    SvnDiffSummary, SvnInfo, SvnLog, SvnLogQuery, SvnProperties, get_svn_logs,
+    svn_changelist_paths, svn_checkout, svn_depth_scope, svn_diff_summary, svn_export, svn_info,
+    svn_list, svn_log_entries, svn_proplist, svn_revert_all, svn_status, svn_update_paths_to_rev,
+    svn_update_to_rev, svn_upgrade,
+};
+use super::svn_operations::SvnOperations;
+use crate::depth::SparseScope;
+use crate::error::Result;
+use crate::telemetry::TracedCommand;
+
+/// 真实SVN操作实现
+///
+/// 使用真实的svn命令执行操作
+#[derive(Debug, Clone, Default)]
+pub struct RealSvnOperations {
+    env: CommandEnv,
+    settings: CommandSettings,
+}
+
+impl RealSvnOperations {
+    /// 创建新的真实SVN操作实例
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use svn2git::RealSvnOperations;
+    ///
+    /// let svn_ops = RealSvnOperations::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 创建执行svn命令时附带额外环境变量的实例
+    ///
+    /// # 参数
+    ///
+    /// * `env` - 额外的环境变量
+    pub fn with_env(env: CommandEnv) -> Self {
+        Self {
+            env,
+            settings: CommandSettings::default(),
+        }
+    }
+
+    /// 设置执行svn命令的设置（非交互选项等）
+    ///
+    /// # 参数
+    ///
+    /// * `settings` - 执行命令的设置
+    pub fn with_settings(mut self, settings: CommandSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// 检查SVN是否可用
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(String)` - SVN可用，返回 `svn --version --quiet` 的输出（如 `1.14.2`）
+    /// * `Err(SyncError)` - SVN不可用，找不到svn命令时为 [`SyncError::CommandNotFound`](crate::SyncError::CommandNotFound)
+    pub fn check_svn_available() -> Result<String> {
+        let output = std::process::Command::new("svn")
+            .args(["--version", "--quiet"])
+            .checked_output("检查SVN版本", None)?;
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+impl SvnOperations for RealSvnOperations {
+    fn get_logs(&self, path: &Path, query: &SvnLogQuery) -> Result<Vec<SvnLog>> {
+        get_svn_logs(&path.to_path_buf(), query, &self.env, &self.settings)
+    }
+
+    fn log_entries<'a>(
+        &'a self,
+        path: &Path,
+        query: &SvnLogQuery,
+    ) -> Result<Box<dyn Iterator<Item = Result<SvnLog>> + 'a>> {
+        let logs = svn_log_entries(&path.to_path_buf(), query, &self.env, &self.settings)?;
+        Ok(Box::new(logs))
+    }
+
+    fn update_to_rev(&self, path: &Path, rev: &str) -> Result<()> {
+        svn_update_to_rev(&path.to_path_buf(), rev, &self.env, &self.settings)
+    }
+
+    fn update_paths_to_rev(&self, path: &Path, rev: &str, paths: &[String]) -> Result<()> {
+        svn_update_paths_to_rev(&path.to_path_buf(), rev, paths, &self.env, &self.settings)
+    }
+
+    fn changelist_paths(&self, path: &Path, changelist: &str) -> Result<Vec<String>> {
+        svn_changelist_paths(&path.to_path_buf(), changelist, &self.env, &self.settings)
+    }
+
+    fn info(&self, path: &Path) -> Result<SvnInfo> {
+        svn_info(&path.to_path_buf(), &self.env, &self.settings)
+    }
+
+    fn modified_paths(&self, path: &Path) -> Result<Vec<String>> {
+        svn_status(&path.to_path_buf(), &self.env, &self.settings)
+    }
+
+    fn revert_all(&self, path: &Path) -> Result<()> {
+        svn_revert_all(&path.to_path_buf(), &self.env, &self.settings)
+    }
+
+    fn upgrade(&self, path: &Path) -> Result<()> {
+        svn_upgrade(&path.to_path_buf(), &self.env, &self.settings)
+    }
+
+    fn checkout(&self, url: &str, path: &Path, rev: &str) -> Result<()> {
+        svn_checkout(url, &path.to_path_buf(), rev, &self.env, &self.settings)
+    }
+
+    fn export(&self, path: &Path, rev: &str, dest: &Path) -> Result<()> {
+        svn_export(
+            &path.to_path_buf(),
+            rev,
+            &dest.to_path_buf(),
+            &self.env,
+            &self.settings,
+        )
+    }
+
+    fn proplist(&self, path: &Path) -> Result<SvnProperties> {
+        svn_proplist(&path.to_path_buf(), &self.env, &self.settings)
+    }
+
+    fn list(&self, path: &Path) -> Result<Vec<String>> {
+        svn_list(&path.to_path_buf(), &self.env, &self.settings)
+    }
+
+    fn depth_scope(&self, path: &Path) -> Result<SparseScope> {
+        svn_depth_scope(&path.to_path_buf(), &self.env, &self.settings)
+    }
+
+    fn diff_summary(&self, path: &Path, rev: &str) -> Result<Vec<SvnDiffSummary>> {
+        svn_diff_summary(&path.to_path_buf(), rev, &self.env, &self.settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_check_svn_available() {
+        // 测试环境可能没有安装SVN，只验证不会panic
+        let result = RealSvnOperations::check_svn_available();
+        println!("SVN可用性检查: {:?}", result);
+    }
+
+    #[test]
+    fn test_real_svn_info_on_invalid_path() {
+        let ops = RealSvnOperations::new();
+        let result = ops.info(&PathBuf::from("/不存在的路径"));
+        assert!(result.is_err(), "在无效路径上获取SVN信息应该返回错误");
+    }
+}
@@ -1,14 +1,163 @@
-use std::{path::PathBuf, process::Command};
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    process::{ChildStdout, Command},
+};
 
+use chrono::NaiveDate;
+use encoding_rs::Encoding;
 use roxmltree::Document;
 
-use crate::error::{Result, SyncError};
+use super::command_env::{
+    CommandEnv, CommandSettings, StreamingChild, command_error, command_with_env,
+};
+use super::log_encoding::{decode_log_bytes, repair_log_text};
+use crate::{
+    depth::{SparseScope, SvnDepth},
+    error::{Result, SyncError},
+    telemetry::{Span, TracedCommand, command_span},
+};
+
+/// `--trust-server-cert-failures` 可以接受的证书错误
+pub const SVN_CERT_FAILURES: [&str; 5] = [
+    "unknown-ca",
+    "cn-mismatch",
+    "expired",
+    "not-yet-valid",
+    "other",
+];
+
+/// 创建 svn 子命令，附加 [`CommandSettings`] 中的非交互选项
+fn svn_command(subcommand: &str, env: &CommandEnv, settings: &CommandSettings) -> Command {
+    let mut command = command_with_env("svn", env);
+    command.arg(subcommand);
+    let trust_cert_failures = &settings.trust_server_cert_failures;
+    if settings.svn_non_interactive || !trust_cert_failures.is_empty() {
+        command.arg("--non-interactive");
+    }
+    if !trust_cert_failures.is_empty() {
+        command.arg(format!(
+            "--trust-server-cert-failures={}",
+            trust_cert_failures.join(",")
+        ));
+    }
+    command
+}
 
 /// SVN 日志
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct SvnLog {
     pub version: String,
     pub message: String,
+    /// 提交作者，匿名提交时为空
+    pub author: String,
+    /// 提交时间（`svn log --xml` 中的 UTC 时间，如 `2024-01-02T03:04:05.000000Z`）
+    pub date: String,
+    /// 本次提交变更的路径
+    pub paths: Vec<SvnChangedPath>,
+}
+
+/// SVN 提交中变更的路径
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SvnChangedPath {
+    /// 变更类型：`A` 新增、`M` 修改、`D` 删除、`R` 替换
+    pub action: String,
+    /// 仓库内的绝对路径，如 `/trunk/src/main.rs`
+    pub path: String,
+    /// 复制来源的仓库路径（`svn copy` 产生的新增或替换），如 `/trunk`
+    pub copy_from: Option<String>,
+    /// 复制来源的版本，与 `copy_from` 同时出现
+    pub copy_from_rev: Option<String>,
+    /// 节点类型：`file` 或 `dir`，旧版本的 svn 服务端可能不提供（为空）
+    pub kind: String,
+}
+
+/// `svn diff --summarize` 中的一项变更
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SvnDiffSummary {
+    /// 内容变更：`added`、`modified`、`deleted`、`replaced`，只修改了属性时为 `none`
+    pub item: String,
+    /// 属性是否修改：`modified` 或 `none`
+    pub props: String,
+    /// 节点类型：`file` 或 `dir`
+    pub kind: String,
+    /// 相对工作副本对应目录（`/` 分隔）的路径
+    pub path: String,
+}
+
+impl SvnDiffSummary {
+    /// `svn diff --summarize` 文本输出中的状态列，如 `A`、`M `、` M`
+    pub fn status(&self) -> String {
+        let item = match self.item.as_str() {
+            "added" => 'A',
+            "modified" => 'M',
+            "deleted" => 'D',
+            "replaced" => 'R',
+            _ => ' ',
+        };
+        let props = match self.props.as_str() {
+            "modified" => 'M',
+            _ => ' ',
+        };
+        format!("{item}{props}")
+    }
+}
+
+/// 一个版本的变更摘要，用于同步前预览
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RevisionDiff {
+    /// SVN 版本
+    pub version: String,
+    /// 变更的路径
+    pub entries: Vec<SvnDiffSummary>,
+}
+
+/// 工作副本中的版本属性：属性名 -> (相对工作副本根目录的 `/` 分隔路径 -> 属性值)
+pub type SvnProperties = BTreeMap<String, BTreeMap<String, String>>;
+
+/// SVN 日志查询范围
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SvnLogQuery {
+    /// 上次已同步的版本；设置后从该版本之后开始，否则从工作副本 BASE 之后开始
+    pub after_rev: Option<String>,
+    /// 只同步该日期（含）之后的提交
+    pub since: Option<NaiveDate>,
+    /// 只同步该日期之前的提交（不含当天）
+    pub until: Option<NaiveDate>,
+    /// 最多返回的日志条数，`None` 表示不限制
+    pub limit: Option<usize>,
+}
+
+impl SvnLogQuery {
+    /// 构造 `svn log -r` 的版本范围参数
+    ///
+    /// 日期会转换为 SVN 的 `{YYYY-MM-DD}` 版本说明符，它表示该日期零点时的最新版本。
+    fn revision_range(&self) -> String {
+        let start = match (&self.since, &self.after_rev) {
+            (Some(date), _) => format!("{{{date}}}"),
+            (None, Some(rev)) => rev.clone(),
+            (None, None) => "BASE".to_string(),
+        };
+        let end = match &self.until {
+            Some(date) => format!("{{{date}}}"),
+            None => "HEAD".to_string(),
+        };
+        format!("{start}:{end}")
+    }
+}
+
+/// SVN 工作副本信息（`svn info` 的结果）
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvnInfo {
+    /// 工作副本对应的 URL
+    pub url: String,
+    /// 仓库根 URL
+    pub repository_root: String,
+    /// 仓库 UUID
+    pub repository_uuid: String,
+    /// 工作副本当前修订版本
+    pub revision: String,
 }
 
 /// 获取 SVN 日志
@@ -16,64 +165,518 @@ pub struct SvnLog {
 /// # 参数
 ///
 /// * `path`: SVN 本地目录
-/// * `git_log`: Git 日志信息，可选
+/// * `query`: 日志查询范围
+/// * `env`: 额外的环境变量
+/// * `settings`: 执行命令的设置
 ///
 /// # 返回
 ///
 /// SVN 日志列表
-pub fn get_svn_logs(path: &PathBuf) -> Result<Vec<SvnLog>> {
-    println!("正在获取 SVN 日志");
+pub fn get_svn_logs(
+    path: &PathBuf,
+    query: &SvnLogQuery,
+    env: &CommandEnv,
+    settings: &CommandSettings,
+) -> Result<Vec<SvnLog>> {
+    svn_log_entries(path, query, env, settings)?.collect()
+}
 
-    let mut cmd = Command::new("svn");
-    cmd.arg("log")
-        .arg("--xml")
+/// 执行 `svn log`，边读取输出边逐条返回日志
+///
+/// 与 [`get_svn_logs`] 相同，但不把整个输出和全部日志留在内存中；
+/// 读完输出后检查命令的退出状态，命令失败或超时时最后一项是命令的错误
+///
+/// # 参数
+///
+/// * `path`: SVN 本地目录
+/// * `query`: 日志查询范围
+/// * `env`: 额外的环境变量
+/// * `settings`: 执行命令的设置
+pub fn svn_log_entries(
+    path: &PathBuf,
+    query: &SvnLogQuery,
+    env: &CommandEnv,
+    settings: &CommandSettings,
+) -> Result<impl Iterator<Item = Result<SvnLog>> + use<>> {
+    let mut cmd = svn_command("log", env, settings);
+    cmd.arg("--xml")
+        .arg("-v")
         .arg("-r")
-        .arg("BASE:HEAD")
-        .arg(path);
+        .arg(query.revision_range());
+    if let Some(limit) = query.limit {
+        // 第一条可能是已同步的版本，多取一条
+        cmd.arg("--limit").arg((limit + 1).to_string());
+    }
+    cmd.arg(path);
 
-    let output = cmd.output()?;
-    if !output.status.success() {
-        let err = String::from_utf8_lossy(&output.stderr);
-        return Err(SyncError::App(format!(
-            "svn log 命令执行失败，错误信息：{err}"
-        )));
+    let span = command_span(&cmd);
+    let (stdout, child) = StreamingChild::spawn(&mut cmd, settings.svn_timeout)?;
+    let output = SvnLogOutput {
+        reader: Some(SvnLogReader::new(
+            BufReader::new(stdout),
+            settings.log_encoding,
+        )),
+        child: Some(child),
+        command: cmd,
+        span,
+    };
+    let logs = filter_query_logs(output, query).inspect(|log| {
+        if let Ok(log) = log
+            && log.message.is_empty()
+        {
+            // 允许空消息，但记录警告
+            // 某些SVN提交可能确实为空消息，这是合法的
+            println!("警告: SVN版本 {} 的提交消息为空", log.version);
+        }
+    });
+    Ok(logs)
+}
+
+/// 正在执行的 `svn log` 命令的输出
+struct SvnLogOutput {
+    /// 读完或出错后关闭
+    reader: Option<SvnLogReader<BufReader<ChildStdout>>>,
+    /// 读完或出错后等待命令结束
+    child: Option<StreamingChild>,
+    command: Command,
+    span: Span,
+}
+
+impl SvnLogOutput {
+    /// 读完剩余的输出并等待命令结束，命令失败时返回命令的错误
+    fn finish(&mut self, child: StreamingChild) -> Result<()> {
+        if let Some(reader) = self.reader.take() {
+            // 解析出错时仍读完输出，让 svn 正常结束，不因管道关闭而报错
+            let _ = std::io::copy(&mut reader.into_inner(), &mut std::io::sink());
+        }
+        let output = child.wait();
+        match &output {
+            Ok(output) => self.span.record_exit(&output.status),
+            Err(e) => self.span.record_error(e),
+        }
+        let output = output?;
+        if !output.status.success() {
+            return Err(command_error("svn log", &self.command, &output));
+        }
+        Ok(())
     }
+}
+
+impl Iterator for SvnLogOutput {
+    type Item = Result<SvnLog>;
 
-    let logs = parse_svn_log_xml(&output.stdout)?;
-    Ok(exclude_current_base_log(logs))
+    fn next(&mut self) -> Option<Self::Item> {
+        let log = self.reader.as_mut()?.next();
+        if matches!(log, Some(Ok(_))) {
+            return log;
+        }
+        // 输出读完或不完整时，命令失败（如超时被终止）的错误优先
+        let child = self.child.take()?;
+        match self.finish(child) {
+            Ok(()) => log,
+            Err(e) => Some(Err(e)),
+        }
+    }
 }
 
-/// 解析 SVN 日志 XML
-fn parse_svn_log_xml(xml: &[u8]) -> Result<Vec<SvnLog>> {
+/// 导出指定版本的 SVN 目录树（不含 .svn 元数据）
+///
+/// # 参数
+///
+/// * `path`: SVN 本地目录
+/// * `rev`: SVN 版本
+/// * `dest`: 导出目标目录
+/// * `env`: 额外的环境变量
+/// * `settings`: 执行命令的设置
+pub fn svn_export(
+    path: &PathBuf,
+    rev: &str,
+    dest: &PathBuf,
+    env: &CommandEnv,
+    settings: &CommandSettings,
+) -> Result<()> {
+    svn_command("export", env, settings)
+        .arg("--force")
+        .arg("-r")
+        .arg(rev)
+        .arg(path)
+        .arg(dest)
+        .checked_output("svn export", settings.svn_timeout)?;
+
+    Ok(())
+}
+
+/// 递归读取工作副本中所有路径的版本属性
+///
+/// 一次 `svn proplist -R -v` 取得全部属性，各项属性映射共用同一结果
+///
+/// # 参数
+///
+/// * `path`: SVN 本地目录
+/// * `env`: 额外的环境变量
+/// * `settings`: 执行命令的设置
+///
+/// # 返回
+///
+/// 属性名 -> (相对工作副本根目录（`/` 分隔）的路径 -> 属性值)
+pub fn svn_proplist(
+    path: &PathBuf,
+    env: &CommandEnv,
+    settings: &CommandSettings,
+) -> Result<SvnProperties> {
+    let output = svn_command("proplist", env, settings)
+        .arg("-R")
+        .arg("-v")
+        .arg("--xml")
+        .arg(path)
+        .checked_output("svn proplist", settings.svn_timeout)?;
+
+    parse_svn_proplist_xml(&output.stdout, path)
+}
+
+/// 解析 `svn proplist -v --xml` 输出，路径转换为相对 `base` 的 `/` 分隔路径
+fn parse_svn_proplist_xml(xml: &[u8], base: &Path) -> Result<SvnProperties> {
     let xml_str = str::from_utf8(xml)?;
     let doc = Document::parse(xml_str)?;
 
-    let root = doc.root_element();
-    if root.tag_name().name() != "log" {
-        return Err(SyncError::App("无效的 XML 根，预期是 <log>".into()));
+    let mut properties = SvnProperties::new();
+    for target in doc
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "target")
+    {
+        let target_path = target
+            .attribute("path")
+            .ok_or(SyncError::App("svn proplist 输出中缺少 path 属性".into()))?;
+        let relative = relative_svn_path(target_path, base);
+        for property in target
+            .children()
+            .filter(|n| n.is_element() && n.tag_name().name() == "property")
+        {
+            let name = property
+                .attribute("name")
+                .ok_or(SyncError::App("svn proplist 输出中缺少 name 属性".into()))?;
+            properties.entry(name.to_string()).or_default().insert(
+                relative.clone(),
+                property.text().unwrap_or_default().to_string(),
+            );
+        }
     }
+    Ok(properties)
+}
 
-    let mut logs = Vec::new();
-    for entry in root
-        .children()
-        .filter(|e| e.is_element() && e.tag_name().name() == "logentry")
-    {
-        let version = entry
-            .attribute("revision")
-            .ok_or(SyncError::App("日志条目中缺少 revision 属性".into()))?
-            .to_string();
+/// 获取工作副本中属于指定变更列表的文件
+///
+/// # 参数
+///
+/// * `path`: SVN 本地目录
+/// * `changelist`: 变更列表名称
+/// * `env`: 额外的环境变量
+/// * `settings`: 执行命令的设置
+///
+/// # 返回
+///
+/// 相对工作副本根目录（`/` 分隔）的文件路径
+pub fn svn_changelist_paths(
+    path: &PathBuf,
+    changelist: &str,
+    env: &CommandEnv,
+    settings: &CommandSettings,
+) -> Result<Vec<String>> {
+    let output = svn_command("info", env, settings)
+        .arg("-R")
+        .arg("--xml")
+        .arg("--changelist")
+        .arg(changelist)
+        .arg(path)
+        .checked_output("读取变更列表", settings.svn_timeout)?;
 
-        let message = get_svn_msg(entry);
-        if message.is_empty() {
-            // 允许空消息，但记录警告
-            // 某些SVN提交可能确实为空消息，这是合法的
-            println!("警告: SVN版本 {} 的提交消息为空", version);
+    parse_svn_changelist_xml(&output.stdout, path)
+}
+
+/// 获取工作副本中有本地修改的路径
+///
+/// 使用 `svn status -q`，不包含未纳入版本控制的文件
+///
+/// # 参数
+///
+/// * `path`: SVN 本地目录
+/// * `env`: 额外的环境变量
+/// * `settings`: 执行命令的设置
+///
+/// # 返回
+///
+/// 相对工作副本根目录（`/` 分隔）的路径
+pub fn svn_status(
+    path: &PathBuf,
+    env: &CommandEnv,
+    settings: &CommandSettings,
+) -> Result<Vec<String>> {
+    let output = svn_command("status", env, settings)
+        .arg("-q")
+        .arg("--xml")
+        .arg(path)
+        .checked_output("svn status", settings.svn_timeout)?;
+
+    parse_svn_status_xml(&output.stdout, path)
+}
+
+/// 解析 `svn status --xml` 输出中有修改的路径
+fn parse_svn_status_xml(xml: &[u8], base: &Path) -> Result<Vec<String>> {
+    let xml_str = str::from_utf8(xml)?;
+    let doc = Document::parse(xml_str)?;
+
+    Ok(doc
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "entry")
+        .filter(|entry| {
+            entry
+                .children()
+                .find(|n| n.is_element() && n.tag_name().name() == "wc-status")
+                .is_some_and(|status| {
+                    !matches!(
+                        status.attribute("item"),
+                        Some("normal" | "unversioned" | "ignored" | "external" | "none")
+                    ) || !matches!(status.attribute("props"), None | Some("normal" | "none"))
+                })
+        })
+        .filter_map(|n| n.attribute("path"))
+        .map(|p| relative_svn_path(p, base))
+        .collect())
+}
+
+/// 解析 `svn info --changelist --xml` 输出中的文件路径
+fn parse_svn_changelist_xml(xml: &[u8], base: &Path) -> Result<Vec<String>> {
+    let xml_str = str::from_utf8(xml)?;
+    let doc = Document::parse(xml_str)?;
+
+    Ok(doc
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "entry")
+        .filter(|n| n.attribute("kind") != Some("dir"))
+        .filter_map(|n| n.attribute("path"))
+        .map(|p| relative_svn_path(p, base))
+        .collect())
+}
+
+/// 将 svn 输出中的路径转换为相对 `base` 的 `/` 分隔路径
+fn relative_svn_path(target_path: &str, base: &Path) -> String {
+    Path::new(target_path)
+        .strip_prefix(base)
+        .unwrap_or(Path::new(target_path))
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 解析 `svn log --xml` 的输出
+///
+/// 不需要调用 svn，可以用来离线解析保存下来的日志。带 `-v` 的输出会解析出变更路径。
+/// 逐条解析 `<logentry>`，不为整个输出建立 DOM；需要边读边解析时使用 [`SvnLogReader`]。
+///
+/// # 参数
+///
+/// * `xml`: `svn log --xml` 的输出（UTF-8 或 `encoding` 指定的编码）
+/// * `encoding`: 旧提交说明的编码，`None` 表示都是 UTF-8
+///
+/// # 返回
+///
+/// 按输出顺序排列的日志；XML 不完整或格式不正确时返回错误
+pub fn parse_svn_log_xml(xml: &[u8], encoding: Option<&'static Encoding>) -> Result<Vec<SvnLog>> {
+    SvnLogReader::new(xml, encoding).collect()
+}
+
+/// 从 `svn log --xml` 的输出中逐条读取日志
+///
+/// 几百 MB 的详细日志一次读入并解析为 DOM 时，内存占用是输出本身的数倍。这里每次只读取到
+/// 下一个 `</logentry>`，只为这一条日志建立 DOM。文本和属性值中的 `<` 都会被转义，
+/// 因此 `</logentry>` 只会出现在元素的结束处。旧编码的提交说明逐条转码，
+/// GBK、Big5 等编码的多字节字符中不会出现 `<` 和 `>`。
+pub struct SvnLogReader<R> {
+    reader: R,
+    encoding: Option<&'static Encoding>,
+    /// 当前标签或日志条目的原始字节，每次读取前清空
+    chunk: Vec<u8>,
+    started: bool,
+    done: bool,
+}
+
+impl<R: BufRead> SvnLogReader<R> {
+    /// 创建日志读取器，第一次迭代时检查根元素是 `<log>`
+    ///
+    /// # 参数
+    ///
+    /// * `reader`: `svn log --xml` 的输出
+    /// * `encoding`: 旧提交说明的编码，`None` 表示都是 UTF-8
+    pub fn new(reader: R, encoding: Option<&'static Encoding>) -> Self {
+        Self {
+            reader,
+            encoding,
+            chunk: Vec::new(),
+            started: false,
+            done: false,
         }
+    }
 
-        logs.push(SvnLog { version, message });
+    /// 取回底层的读取器
+    pub fn into_inner(self) -> R {
+        self.reader
     }
 
-    Ok(logs)
+    /// 跳过 XML 声明、注释和 DOCTYPE，检查根元素是 `<log>`
+    fn start(&mut self) -> Result<()> {
+        if !self.next_tag()? || !is_tag(&self.chunk, "log") {
+            return Err(SyncError::App("无效的 XML 根，预期是 <log>".into()));
+        }
+        self.done = self.chunk.ends_with(b"/>");
+        Ok(())
+    }
+
+    /// 读取下一条日志，遇到 `</log>` 时返回 `None`
+    fn next_entry(&mut self) -> Result<Option<SvnLog>> {
+        loop {
+            if !self.next_tag()? {
+                return Err(incomplete_log_xml());
+            }
+            if is_tag(&self.chunk, "logentry") {
+                if !self.chunk.ends_with(b"/>") {
+                    self.read_through(b"</logentry>")?;
+                }
+                return self.parse_entry().map(Some);
+            }
+            if is_tag(&self.chunk, "/log") {
+                return Ok(None);
+            }
+            // 忽略 <logentry> 以外的元素
+        }
+    }
+
+    /// 把 `chunk` 中的一条 `<logentry>` 转码并解析
+    fn parse_entry(&self) -> Result<SvnLog> {
+        let xml = decode_log_bytes(&self.chunk, self.encoding).ok_or_else(|| {
+            SyncError::App(
+                "svn log 的输出不是 UTF-8，旧仓库的提交说明可以用 --encoding 指定编码".into(),
+            )
+        })?;
+        let mut log = parse_svn_log_entry(&xml)?;
+        log.message = repair_log_text(&log.message, self.encoding).into_owned();
+        log.author = repair_log_text(&log.author, self.encoding).into_owned();
+        Ok(log)
+    }
+
+    /// 读取下一个标签到 `chunk`（从 `<` 到 `>`），跳过之前的文本以及 XML 声明、注释和 DOCTYPE
+    ///
+    /// # 返回
+    ///
+    /// 已到输出末尾时返回 `false`
+    fn next_tag(&mut self) -> Result<bool> {
+        loop {
+            if !self.skip_past(b'<')? {
+                return Ok(false);
+            }
+            self.chunk.clear();
+            self.chunk.push(b'<');
+            self.read_through(b">")?;
+            let end: &[u8] = if self.chunk.starts_with(b"<?") {
+                b"?>"
+            } else if self.chunk.starts_with(b"<!--") {
+                b"-->"
+            } else if self.chunk.starts_with(b"<!") {
+                b">"
+            } else {
+                return Ok(true);
+            };
+            self.read_through(end)?;
+        }
+    }
+
+    /// 丢弃输出直到 `byte` 之后
+    ///
+    /// # 返回
+    ///
+    /// 已到输出末尾时返回 `false`
+    fn skip_past(&mut self, byte: u8) -> Result<bool> {
+        loop {
+            let buf = self.reader.fill_buf()?;
+            if buf.is_empty() {
+                return Ok(false);
+            }
+            match buf.iter().position(|&b| b == byte) {
+                Some(i) => {
+                    self.reader.consume(i + 1);
+                    return Ok(true);
+                }
+                None => {
+                    let len = buf.len();
+                    self.reader.consume(len);
+                }
+            }
+        }
+    }
+
+    /// 继续读取到 `chunk`，直到以 `pattern` 结尾
+    fn read_through(&mut self, pattern: &[u8]) -> Result<()> {
+        let last = pattern[pattern.len() - 1];
+        while !self.chunk.ends_with(pattern) {
+            let read = self.reader.read_until(last, &mut self.chunk)?;
+            if read == 0 || self.chunk.last() != Some(&last) {
+                return Err(incomplete_log_xml());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for SvnLogReader<R> {
+    type Item = Result<SvnLog>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.started {
+            self.started = true;
+            if let Err(e) = self.start() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+        if self.done {
+            return None;
+        }
+        let log = self.next_entry().transpose();
+        if !matches!(log, Some(Ok(_))) {
+            self.done = true;
+        }
+        log
+    }
+}
+
+/// 解析一条 `<logentry>` 元素
+fn parse_svn_log_entry(chunk: &str) -> Result<SvnLog> {
+    let doc = Document::parse(chunk)?;
+    let entry = doc.root_element();
+    let version = entry
+        .attribute("revision")
+        .ok_or(SyncError::App("日志条目中缺少 revision 属性".into()))?
+        .to_string();
+
+    Ok(SvnLog {
+        version,
+        message: get_svn_msg(entry),
+        author: get_svn_child_text(entry, "author"),
+        date: get_svn_child_text(entry, "date"),
+        paths: get_svn_changed_paths(entry),
+    })
+}
+
+/// `tag` 是否是指定名称的标签（`<name` 之后是空白、`>` 或 `/`）
+fn is_tag(tag: &[u8], name: &str) -> bool {
+    tag[1..]
+        .strip_prefix(name.as_bytes())
+        .and_then(|after| after.first())
+        .is_some_and(|&c| c.is_ascii_whitespace() || c == b'>' || c == b'/')
+}
+
+fn incomplete_log_xml() -> SyncError {
+    SyncError::App("SVN 日志 XML 不完整".into())
 }
 
 /// 获取 SVN 日志消息
@@ -92,45 +695,533 @@ fn get_svn_msg(entry: roxmltree::Node<'_, '_>) -> String {
     message
 }
 
+/// 获取 SVN 日志条目中子元素的文本
+fn get_svn_child_text(entry: roxmltree::Node<'_, '_>, name: &str) -> String {
+    entry
+        .children()
+        .find(|n| n.is_element() && n.tag_name().name() == name)
+        .and_then(|n| n.text())
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+/// 获取 SVN 日志条目中的变更路径（需要 `svn log -v`）
+fn get_svn_changed_paths(entry: roxmltree::Node<'_, '_>) -> Vec<SvnChangedPath> {
+    entry
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "path")
+        .map(|n| SvnChangedPath {
+            action: n.attribute("action").unwrap_or_default().to_string(),
+            path: n.text().unwrap_or_default().trim().to_string(),
+            copy_from: n.attribute("copyfrom-path").map(str::to_string),
+            copy_from_rev: n.attribute("copyfrom-rev").map(str::to_string),
+            kind: n.attribute("kind").unwrap_or_default().to_string(),
+        })
+        .collect()
+}
+
 /// 拉取 SVN 指定版本到本地
 ///
 /// # 参数
 ///
 /// * `path`: SVN 本地目录
 /// * `rev`: SVN 版本
-pub fn svn_update_to_rev(path: &PathBuf, rev: &str) -> Result<()> {
-    println!("正在拉取 SVN 版本 {rev} 到本地");
-
-    let output = Command::new("svn")
-        .arg("update")
+/// * `env`: 额外的环境变量
+/// * `settings`: 执行命令的设置
+pub fn svn_update_to_rev(
+    path: &PathBuf,
+    rev: &str,
+    env: &CommandEnv,
+    settings: &CommandSettings,
+) -> Result<()> {
+    let mut command = svn_command("update", env, settings);
+    command
+        .arg("--accept")
+        .arg("postpone")
         .arg("-r")
         .arg(rev)
-        .current_dir(path)
-        .output()?;
+        .current_dir(path);
+    let output = command.traced_output(settings.svn_timeout)?;
+    check_update_output(&command, &output, rev)
+}
+
+/// 检查 `svn update` 的结果
+///
+/// 冲突被推迟（`--accept postpone`）时命令仍然成功退出，需要从输出中识别冲突的路径
+fn check_update_output(
+    command: &std::process::Command,
+    output: &std::process::Output,
+    rev: &str,
+) -> Result<()> {
+    let conflicts = parse_update_conflicts(&String::from_utf8_lossy(&output.stdout));
+    if !conflicts.is_empty() {
+        return Err(SyncError::SvnConflict {
+            rev: rev.to_string(),
+            paths: conflicts,
+        });
+    }
     if !output.status.success() {
-        return Err(SyncError::App(format!(
-            "svn 更新到 {rev} 失败，错误信息：{output:?}"
-        )));
+        return Err(command_error(&format!("svn 更新到 {rev}"), command, output));
     }
+    Ok(())
+}
+
+/// 解析 `svn update` 输出中冲突的路径
+///
+/// 每行前四列依次是内容、属性、锁和树冲突的状态，之后是一个空格和路径；
+/// 内容、属性或树冲突列为 `C` 时该路径有冲突
+fn parse_update_conflicts(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            // 状态列都是 ASCII，先按字节判断，避免多字节字符使列数不足 4 个
+            let columns = line.as_bytes().get(..5)?;
+            let valid = columns[4] == b' '
+                && columns[..4].iter().all(|c| b" ADUCGEB".contains(c))
+                && line.len() > 5;
+            let conflicted = [0, 1, 3].iter().any(|&i| columns[i] == b'C');
+            (valid && conflicted).then(|| line[5..].trim().to_string())
+        })
+        .collect()
+}
 
-    println!("SVN 更新到 {rev} 成功");
+/// 放弃工作副本中的本地修改和冲突
+///
+/// 先执行 `svn cleanup` 清除中断操作留下的锁，再执行 `svn revert -R .`
+///
+/// # 参数
+///
+/// * `path`: SVN 本地目录
+/// * `env`: 额外的环境变量
+/// * `settings`: 执行命令的设置
+pub fn svn_revert_all(path: &PathBuf, env: &CommandEnv, settings: &CommandSettings) -> Result<()> {
+    for args in [&["cleanup"][..], &["revert", "-R", "."][..]] {
+        svn_command(args[0], env, settings)
+            .args(&args[1..])
+            .current_dir(path)
+            .checked_output(&format!("svn {}", args[0]), settings.svn_timeout)?;
+    }
     Ok(())
 }
 
-/// 排除当前工作副本 BASE 对应的日志条目
+/// 升级旧格式的工作副本
 ///
-/// `svn log -r BASE:HEAD` 的第一条通常是当前 BASE 修订版本，
-/// 这条往往已同步，不应再次进入同步队列。
-fn exclude_current_base_log(mut logs: Vec<SvnLog>) -> Vec<SvnLog> {
-    if !logs.is_empty() {
-        logs.remove(0);
+/// # 参数
+///
+/// * `path`: SVN 本地目录
+/// * `env`: 额外的环境变量
+/// * `settings`: 执行命令的设置
+pub fn svn_upgrade(path: &PathBuf, env: &CommandEnv, settings: &CommandSettings) -> Result<()> {
+    svn_command("upgrade", env, settings)
+        .arg(path)
+        .checked_output("svn upgrade", settings.svn_timeout)?;
+    Ok(())
+}
+
+/// 只把指定路径拉取到 SVN 指定版本
+///
+/// # 参数
+///
+/// * `path`: SVN 本地目录
+/// * `rev`: SVN 版本
+/// * `paths`: 相对 `path` 的路径
+/// * `env`: 额外的环境变量
+/// * `settings`: 执行命令的设置
+pub fn svn_update_paths_to_rev(
+    path: &PathBuf,
+    rev: &str,
+    paths: &[String],
+    env: &CommandEnv,
+    settings: &CommandSettings,
+) -> Result<()> {
+    let mut command = svn_command("update", env, settings);
+    command
+        .arg("--accept")
+        .arg("postpone")
+        .arg("-r")
+        .arg(rev)
+        .arg("--")
+        .args(paths)
+        .current_dir(path);
+    let output = command.traced_output(settings.svn_timeout)?;
+    check_update_output(&command, &output, rev)
+}
+
+/// 获取 SVN 工作副本信息
+///
+/// # 参数
+///
+/// * `path`: SVN 本地目录
+/// * `env`: 额外的环境变量
+/// * `settings`: 执行命令的设置
+pub fn svn_info(path: &PathBuf, env: &CommandEnv, settings: &CommandSettings) -> Result<SvnInfo> {
+    let output = svn_command("info", env, settings)
+        .arg("--xml")
+        .arg(path)
+        .checked_output("svn info", settings.svn_timeout)?;
+
+    parse_svn_info_xml(&output.stdout)
+}
+
+/// 获取一个版本的变更摘要
+///
+/// 对工作副本对应的 URL 执行 `svn diff -c REV --summarize`，只比较仓库中的版本，不受本地修改影响
+///
+/// # 参数
+///
+/// * `path`: SVN 本地目录
+/// * `rev`: SVN 版本
+/// * `env`: 额外的环境变量
+/// * `settings`: 执行命令的设置
+///
+/// # 返回
+///
+/// 该版本中变更的路径，路径相对工作副本对应的目录
+pub fn svn_diff_summary(
+    path: &PathBuf,
+    rev: &str,
+    env: &CommandEnv,
+    settings: &CommandSettings,
+) -> Result<Vec<SvnDiffSummary>> {
+    let url = svn_info(path, env, settings)?.url;
+    let output = svn_command("diff", env, settings)
+        .arg("--summarize")
+        .arg("--xml")
+        .arg("-c")
+        .arg(rev)
+        .arg(&url)
+        .checked_output("svn diff", settings.svn_timeout)?;
+
+    parse_svn_diff_summary_xml(&output.stdout, &url)
+}
+
+/// 解析 `svn diff --summarize --xml` 输出，路径转换为相对 `base_url` 的路径
+fn parse_svn_diff_summary_xml(xml: &[u8], base_url: &str) -> Result<Vec<SvnDiffSummary>> {
+    let xml_str = str::from_utf8(xml)?;
+    let doc = Document::parse(xml_str)?;
+    let base_url = base_url.trim_end_matches('/');
+
+    Ok(doc
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "path")
+        .map(|n| {
+            let url = n.text().unwrap_or_default().trim();
+            let path = url
+                .strip_prefix(base_url)
+                .map(|rest| rest.trim_start_matches('/'))
+                .unwrap_or(url);
+            SvnDiffSummary {
+                item: n.attribute("item").unwrap_or_default().to_string(),
+                props: n.attribute("props").unwrap_or_default().to_string(),
+                kind: n.attribute("kind").unwrap_or_default().to_string(),
+                path: path.to_string(),
+            }
+        })
+        .collect())
+}
+
+/// 读取工作副本实际检出的范围
+///
+/// `svn info -R` 只列出已检出的节点，每个目录的 `<depth>` 是它的检出深度
+///
+/// # 参数
+///
+/// * `path`: SVN 本地目录
+/// * `env`: 额外的环境变量
+/// * `settings`: 执行命令的设置
+pub fn svn_depth_scope(
+    path: &PathBuf,
+    env: &CommandEnv,
+    settings: &CommandSettings,
+) -> Result<SparseScope> {
+    let output = svn_command("info", env, settings)
+        .arg("--xml")
+        .arg("-R")
+        .arg(path)
+        .checked_output("svn info -R", settings.svn_timeout)?;
+
+    parse_svn_depth_xml(&output.stdout, path)
+}
+
+/// 解析 `svn info -R --xml` 输出中各目录的检出深度，路径转换为相对 `base` 的 `/` 分隔路径
+///
+/// 没有 `<depth>` 的目录按 infinity 处理
+fn parse_svn_depth_xml(xml: &[u8], base: &Path) -> Result<SparseScope> {
+    let xml_str = str::from_utf8(xml)?;
+    let doc = Document::parse(xml_str)?;
+
+    let mut dirs = Vec::new();
+    for entry in doc
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "entry")
+        .filter(|n| n.attribute("kind") == Some("dir"))
+    {
+        let path = entry
+            .attribute("path")
+            .ok_or(SyncError::App("svn info 输出中缺少 path 属性".into()))?;
+        let depth = match entry
+            .descendants()
+            .find(|n| n.is_element() && n.tag_name().name() == "depth")
+            .and_then(|n| n.text())
+        {
+            Some(depth) => depth.trim().parse().map_err(SyncError::App)?,
+            None => SvnDepth::Infinity,
+        };
+        dirs.push((relative_svn_path(path, base), depth));
+    }
+    Ok(SparseScope::new(dirs))
+}
+
+/// 检出 SVN 仓库到本地目录
+///
+/// # 参数
+///
+/// * `url`: SVN 仓库 URL
+/// * `path`: 检出目标目录
+/// * `rev`: 检出的版本
+/// * `env`: 额外的环境变量
+/// * `settings`: 执行命令的设置
+pub fn svn_checkout(
+    url: &str,
+    path: &PathBuf,
+    rev: &str,
+    env: &CommandEnv,
+    settings: &CommandSettings,
+) -> Result<()> {
+    println!("正在检出 SVN {url}@{rev} 到 {}", path.display());
+
+    svn_command("checkout", env, settings)
+        .arg("-r")
+        .arg(rev)
+        .arg(url)
+        .arg(path)
+        .checked_output("svn checkout", settings.svn_timeout)?;
+
+    println!("SVN 检出完成");
+    Ok(())
+}
+
+/// 列出工作副本对应的仓库目录在 HEAD 版本中的直接子项
+///
+/// 读取的是仓库而不是工作副本，工作副本停留在旧版本时也能看到最新的目录结构
+///
+/// # 参数
+///
+/// * `path`: SVN 本地目录
+/// * `env`: 额外的环境变量
+/// * `settings`: 执行命令的设置
+///
+/// # 返回
+///
+/// 子项名称，目录以 `/` 结尾（与 `svn list` 的输出一致）
+pub fn svn_list(
+    path: &PathBuf,
+    env: &CommandEnv,
+    settings: &CommandSettings,
+) -> Result<Vec<String>> {
+    let output = svn_command("list", env, settings)
+        .arg("--xml")
+        .arg("-r")
+        .arg("HEAD")
+        .arg(path)
+        .checked_output("svn list", settings.svn_timeout)?;
+
+    parse_svn_list_xml(&output.stdout)
+}
+
+/// 解析 `svn list --xml` 输出
+fn parse_svn_list_xml(xml: &[u8]) -> Result<Vec<String>> {
+    let xml_str = str::from_utf8(xml)?;
+    let doc = Document::parse(xml_str)?;
+
+    Ok(doc
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "entry")
+        .map(|entry| {
+            let name = get_svn_child_text(entry, "name");
+            match entry.attribute("kind") {
+                Some("dir") => format!("{name}/"),
+                _ => name,
+            }
+        })
+        .collect())
+}
+
+/// 解析 `svn info --xml` 输出
+fn parse_svn_info_xml(xml: &[u8]) -> Result<SvnInfo> {
+    let xml_str = str::from_utf8(xml)?;
+    let doc = Document::parse(xml_str)?;
+
+    let entry = doc
+        .descendants()
+        .find(|n| n.is_element() && n.tag_name().name() == "entry")
+        .ok_or(SyncError::App("svn info 输出中缺少 <entry>".into()))?;
+    let revision = entry
+        .attribute("revision")
+        .ok_or(SyncError::App("svn info 输出中缺少 revision 属性".into()))?
+        .to_string();
+
+    let text_of = |name: &str| {
+        entry
+            .descendants()
+            .find(|n| n.is_element() && n.tag_name().name() == name)
+            .and_then(|n| n.text())
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+
+    Ok(SvnInfo {
+        url: text_of("url"),
+        repository_root: text_of("root"),
+        repository_uuid: text_of("uuid"),
+        revision,
+    })
+}
+
+/// 模糊测试的解析入口（`fuzzing` 特性），见 `fuzz/` 目录
+///
+/// 这些函数解析的都是外部命令的输出，对任意输入只允许返回错误，不允许 panic
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing {
+    use std::path::Path;
+
+    use crate::{SvnInfo, SvnLog, error::Result};
+
+    /// 解析 `svn log --xml` 输出
+    pub fn parse_svn_log_xml(xml: &[u8]) -> Result<Vec<SvnLog>> {
+        super::parse_svn_log_xml(xml, None)
+    }
+
+    /// 解析 `svnadmin dump` 输出并重放每个版本
+    pub fn replay_svn_dump(dump: &[u8]) -> Result<usize> {
+        let mut tree = crate::DumpTree::new();
+        let mut changes = 0;
+        for revision in crate::DumpReader::new(dump) {
+            changes += tree.apply(&revision?)?.len();
+        }
+        Ok(changes)
+    }
+
+    /// 解析 `svn info --xml` 输出
+    pub fn parse_svn_info_xml(xml: &[u8]) -> Result<SvnInfo> {
+        super::parse_svn_info_xml(xml)
+    }
+
+    /// 解析 `svn status --xml` 输出
+    pub fn parse_svn_status_xml(xml: &[u8], base: &Path) -> Result<Vec<String>> {
+        super::parse_svn_status_xml(xml, base)
+    }
+
+    /// 解析 `svn update` 输出中冲突的路径
+    pub fn parse_update_conflicts(output: &str) -> Vec<String> {
+        super::parse_update_conflicts(output)
+    }
+}
+
+/// 按查询范围排除不需要同步的日志，最多保留 `limit` 条
+///
+/// `svn log -r BASE:HEAD` 的第一条是当前 BASE 修订版本，`{since}` 解析为该日期之前的最后一个版本，
+/// 这两种情况都排除第一条；`svn log -r N:HEAD` 包含已在上次同步中提交的版本 N 本身，
+/// 排除已同步版本及之前的日志条目。出错的项原样保留。
+fn filter_query_logs<I: Iterator<Item = Result<SvnLog>>>(
+    logs: I,
+    query: &SvnLogQuery,
+) -> impl Iterator<Item = Result<SvnLog>> + use<I> {
+    let mut skip_first = query.since.is_some() || query.after_rev.is_none();
+    let synced_rev = query.after_rev.clone();
+    logs.filter(move |log| match log {
+        Ok(_) if std::mem::take(&mut skip_first) => false,
+        Ok(log) => synced_rev
+            .as_deref()
+            .is_none_or(|synced_rev| !is_synced(log, synced_rev)),
+        Err(_) => true,
+    })
+    .take(query.limit.unwrap_or(usize::MAX))
+}
+
+/// 日志是否是已同步的版本或之前的版本
+fn is_synced(log: &SvnLog, synced_rev: &str) -> bool {
+    match (log.version.parse::<u64>(), synced_rev.parse::<u64>()) {
+        (Ok(version), Ok(synced)) => version <= synced,
+        (Err(_), Ok(_)) => false,
+        _ => log.version == synced_rev,
     }
-    logs
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{SvnLog, exclude_current_base_log, parse_svn_log_xml};
+    use chrono::NaiveDate;
+
+    use super::{
+        BufReader, Command, CommandEnv, Result, StreamingChild, SvnChangedPath, SvnLog,
+        SvnLogOutput, SvnLogQuery, SvnLogReader, SyncError, command_span, filter_query_logs,
+        parse_svn_changelist_xml, parse_svn_depth_xml, parse_svn_diff_summary_xml,
+        parse_svn_info_xml, parse_svn_list_xml, parse_svn_log_xml, parse_svn_proplist_xml,
+        parse_svn_status_xml, parse_update_conflicts, svn_command,
+    };
+    use crate::ops::CommandSettings;
+
+    #[test]
+    fn test_svn_command_should_append_non_interactive_options() {
+        let args = |command: Command| {
+            command
+                .get_args()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+        assert!(
+            CommandSettings::default()
+                .with_svn_non_interactive(true, &["bad".into()])
+                .is_err()
+        );
+
+        let settings = CommandSettings::default()
+            .with_svn_non_interactive(false, &["unknown-ca".into(), "expired".into()])
+            .unwrap();
+        let mut command = svn_command("update", &CommandEnv::new(), &settings);
+        command.args(["--", "a.txt"]);
+        assert_eq!(
+            args(command),
+            [
+                "update",
+                "--non-interactive",
+                "--trust-server-cert-failures=unknown-ca,expired",
+                "--",
+                "a.txt"
+            ]
+        );
+
+        let settings = CommandSettings::default();
+        assert_eq!(
+            args(svn_command("info", &CommandEnv::new(), &settings)),
+            ["info"]
+        );
+    }
+
+    #[test]
+    fn test_parse_update_conflicts() {
+        let output = [
+            "Updating '.':",
+            "U    src/main.rs",
+            "C    src/lib.rs",
+            " C   docs/readme.txt",
+            "   C assets/logo.png",
+            "A    new.txt",
+            "Updated to revision 5.",
+            "Summary of conflicts:",
+            "  Text conflicts: 1",
+        ]
+        .join("\n");
+        assert_eq!(
+            parse_update_conflicts(&output),
+            vec!["src/lib.rs", "docs/readme.txt", "assets/logo.png"]
+        );
+        assert!(parse_update_conflicts("U    a.txt\nUpdated to revision 2.\n").is_empty());
+        // 模糊测试发现：前 4 个字节中含多字节字符时不应 panic
+        assert!(parse_update_conflicts("é  C path\nC\u{3000}x").is_empty());
+    }
 
     #[test]
     fn test_parse_svn_log_xml_success() {
@@ -144,7 +1235,7 @@ mod tests {
   </logentry>
 </log>"#;
 
-        let result = parse_svn_log_xml(xml).unwrap();
+        let result = parse_svn_log_xml(xml, None).unwrap();
         assert_eq!(result.len(), 2);
         assert_eq!(result[0].version, "101");
         assert_eq!(result[0].message, "first commit");
@@ -152,6 +1243,45 @@ mod tests {
         assert_eq!(result[1].message, "second commit");
     }
 
+    #[test]
+    fn test_parse_svn_log_xml_with_author_and_paths() {
+        let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
+<log>
+  <logentry revision="7">
+    <author>alice</author>
+    <date>2024-01-02T03:04:05.000000Z</date>
+    <paths>
+      <path action="M" kind="file">/trunk/src/main.rs</path>
+      <path action="A" kind="dir" copyfrom-path="/branches/dev/docs" copyfrom-rev="6">/trunk/docs</path>
+    </paths>
+    <msg>update</msg>
+  </logentry>
+</log>"#;
+
+        let result = parse_svn_log_xml(xml, None).unwrap();
+        assert_eq!(result[0].author, "alice");
+        assert_eq!(result[0].date, "2024-01-02T03:04:05.000000Z");
+        assert_eq!(
+            result[0].paths,
+            vec![
+                SvnChangedPath {
+                    action: "M".into(),
+                    path: "/trunk/src/main.rs".into(),
+                    copy_from: None,
+                    copy_from_rev: None,
+                    kind: "file".into(),
+                },
+                SvnChangedPath {
+                    action: "A".into(),
+                    path: "/trunk/docs".into(),
+                    copy_from: Some("/branches/dev/docs".into()),
+                    copy_from_rev: Some("6".into()),
+                    kind: "dir".into(),
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_svn_log_xml_should_fail_when_root_invalid() {
         let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
@@ -161,12 +1291,37 @@ mod tests {
   </logentry>
 </invalid>"#;
 
-        let result = parse_svn_log_xml(xml);
+        let result = parse_svn_log_xml(xml, None);
         assert!(result.is_err());
         let err = result.unwrap_err().to_string();
         assert!(err.contains("无效的 XML 根"));
     }
 
+    #[test]
+    fn test_parse_svn_log_xml_should_parse_entries_one_by_one() {
+        let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- svn log -->
+<log>
+<logentry revision="1"><msg>a &lt;/logentry&gt; &amp; b</msg></logentry>
+<logentry revision="2"/>
+</log>"#;
+        let result = parse_svn_log_xml(xml, None).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].message, "a </logentry> & b");
+        assert_eq!(result[1].version, "2");
+
+        assert!(parse_svn_log_xml(b"<log/>", None).unwrap().is_empty());
+        assert!(parse_svn_log_xml(b"<logs></logs>", None).is_err());
+        for truncated in [
+            &b"<log><logentry revision=\"1\"><msg>a</msg>"[..],
+            b"<log><logentry revision=\"1\"></logentry>",
+            b"<log><logentry",
+        ] {
+            let err = parse_svn_log_xml(truncated, None).unwrap_err();
+            assert!(err.to_string().contains("不完整"), "{err}");
+        }
+    }
+
     #[test]
     fn test_parse_svn_log_xml_should_fail_when_revision_missing() {
         let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
@@ -176,7 +1331,7 @@ mod tests {
   </logentry>
 </log>"#;
 
-        let result = parse_svn_log_xml(xml);
+        let result = parse_svn_log_xml(xml, None);
         assert!(result.is_err());
         let err = result.unwrap_err().to_string();
         assert!(err.contains("缺少 revision"));
@@ -191,32 +1346,303 @@ mod tests {
   </logentry>
 </log>"#;
 
-        let result = parse_svn_log_xml(xml).unwrap();
+        let result = parse_svn_log_xml(xml, None).unwrap();
         assert_eq!(result.len(), 1);
         assert_eq!(result[0].version, "200");
         assert!(result[0].message.is_empty());
     }
 
     #[test]
-    fn test_exclude_current_base_log_should_drop_first_entry() {
-        let logs = vec![
-            SvnLog {
-                version: "10".into(),
-                message: "base".into(),
-            },
-            SvnLog {
-                version: "11".into(),
-                message: "next".into(),
-            },
-        ];
-        let filtered = exclude_current_base_log(logs);
-        assert_eq!(filtered.len(), 1);
-        assert_eq!(filtered[0].version, "11");
+    fn test_parse_svn_log_xml_should_decode_with_given_encoding() {
+        let xml = "<log><logentry revision=\"3\"><msg>修复问题</msg></logentry></log>";
+        let (gbk, _, _) = encoding_rs::GBK.encode(xml);
+
+        assert!(parse_svn_log_xml(&gbk, None).is_err());
+        let result = parse_svn_log_xml(&gbk, Some(encoding_rs::GBK)).unwrap();
+        assert_eq!(result[0].message, "修复问题");
+    }
+
+    fn logs(versions: &[&str]) -> impl Iterator<Item = Result<SvnLog>> + use<> {
+        let logs: Vec<_> = versions
+            .iter()
+            .map(|version| {
+                Ok(SvnLog {
+                    version: version.to_string(),
+                    ..Default::default()
+                })
+            })
+            .collect();
+        logs.into_iter()
+    }
+
+    fn versions(logs: impl Iterator<Item = Result<SvnLog>>) -> Vec<String> {
+        logs.map(|log| log.unwrap().version).collect()
+    }
+
+    #[test]
+    fn test_filter_query_logs_should_drop_current_base_entry() {
+        let query = SvnLogQuery::default();
+        assert_eq!(
+            versions(filter_query_logs(logs(&["10", "11"]), &query)),
+            ["11"]
+        );
+        assert!(versions(filter_query_logs(logs(&[]), &query)).is_empty());
+    }
+
+    #[test]
+    fn test_filter_query_logs_should_drop_synced_and_older() {
+        let query = SvnLogQuery {
+            after_rev: Some("10".into()),
+            limit: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            versions(filter_query_logs(
+                logs(&["9", "10", "11", "12", "13"]),
+                &query
+            )),
+            ["11", "12"]
+        );
+    }
+
+    #[test]
+    fn test_filter_query_logs_should_keep_errors() {
+        let failing = std::iter::once(Err(SyncError::App("svn log 失败".into())));
+        let mut filtered = filter_query_logs(failing, &SvnLogQuery::default());
+        assert!(filtered.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_svn_log_reader_should_read_entries_incrementally() {
+        let xml = b"<log><logentry revision=\"1\"><msg>a</msg></logentry>\n<logentry revision=\"2\"><msg>b";
+        // 每次只提供一个字节，条目可以跨越多次读取
+        let reader = std::io::BufReader::with_capacity(1, &xml[..]);
+        let mut logs = SvnLogReader::new(reader, None);
+        assert_eq!(logs.next().unwrap().unwrap().message, "a");
+        assert!(
+            logs.next()
+                .unwrap()
+                .unwrap_err()
+                .to_string()
+                .contains("不完整")
+        );
+        assert!(logs.next().is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_svn_log_output_should_report_command_failure_after_entries() {
+        let mut command = Command::new("sh");
+        command.args([
+            "-c",
+            "printf '<log><logentry revision=\"1\"><msg>a</msg></logentry>'; echo denied >&2; exit 1",
+        ]);
+        let span = command_span(&command);
+        let (stdout, child) = StreamingChild::spawn(&mut command, None).unwrap();
+        let mut output = SvnLogOutput {
+            reader: Some(SvnLogReader::new(BufReader::new(stdout), None)),
+            child: Some(child),
+            command,
+            span,
+        };
+
+        assert_eq!(output.next().unwrap().unwrap().version, "1");
+        let err = output.next().unwrap().unwrap_err();
+        assert!(err.to_string().contains("denied"), "{err}");
+        assert!(output.next().is_none());
+    }
+
+    #[test]
+    fn test_log_query_revision_range() {
+        assert_eq!(SvnLogQuery::default().revision_range(), "BASE:HEAD");
+        let query = SvnLogQuery {
+            after_rev: Some("42".into()),
+            ..Default::default()
+        };
+        assert_eq!(query.revision_range(), "42:HEAD");
+    }
+
+    #[test]
+    fn test_log_query_revision_range_with_dates() {
+        let since = NaiveDate::from_ymd_opt(2024, 1, 1);
+        let until = NaiveDate::from_ymd_opt(2024, 3, 1);
+
+        let query = SvnLogQuery {
+            since,
+            until,
+            ..Default::default()
+        };
+        assert_eq!(query.revision_range(), "{2024-01-01}:{2024-03-01}");
+
+        let query = SvnLogQuery {
+            after_rev: Some("42".into()),
+            until,
+            ..Default::default()
+        };
+        assert_eq!(query.revision_range(), "42:{2024-03-01}");
+
+        // 同时存在时从日期开始查询，已同步的版本再按版本号过滤
+        let query = SvnLogQuery {
+            after_rev: Some("42".into()),
+            since,
+            until: None,
+            ..Default::default()
+        };
+        assert_eq!(query.revision_range(), "{2024-01-01}:HEAD");
+    }
+
+    #[test]
+    fn test_parse_svn_info_xml() {
+        let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
+<info>
+<entry kind="dir" path="." revision="42">
+<url>https://svn.example.com/repo/trunk</url>
+<repository>
+<root>https://svn.example.com/repo</root>
+<uuid>13f79535-47bb-0310-9956-ffa450edef68</uuid>
+</repository>
+</entry>
+</info>"#;
+
+        let info = parse_svn_info_xml(xml).unwrap();
+        assert_eq!(info.revision, "42");
+        assert_eq!(info.url, "https://svn.example.com/repo/trunk");
+        assert_eq!(info.repository_root, "https://svn.example.com/repo");
+        assert_eq!(info.repository_uuid, "13f79535-47bb-0310-9956-ffa450edef68");
     }
 
     #[test]
-    fn test_exclude_current_base_log_empty_input() {
-        let filtered = exclude_current_base_log(Vec::new());
-        assert!(filtered.is_empty());
+    fn test_parse_svn_diff_summary_xml() {
+        let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
+<diff>
+<paths>
+<path item="modified" props="none" kind="file">https://svn.example.com/repo/trunk/src/main.rs</path>
+<path item="added" props="none" kind="dir">https://svn.example.com/repo/trunk/docs</path>
+<path item="none" props="modified" kind="dir">https://svn.example.com/repo/trunk</path>
+</paths>
+</diff>"#;
+
+        let entries =
+            parse_svn_diff_summary_xml(xml, "https://svn.example.com/repo/trunk/").unwrap();
+        let summary: Vec<(String, &str)> = entries
+            .iter()
+            .map(|entry| (entry.status(), entry.path.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("M ".to_string(), "src/main.rs"),
+                ("A ".to_string(), "docs"),
+                (" M".to_string(), ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_svn_depth_xml() {
+        let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
+<info>
+<entry kind="dir" path="/work/wc" revision="42">
+<wc-info><depth>immediates</depth></wc-info>
+</entry>
+<entry kind="file" path="/work/wc/README" revision="42"></entry>
+<entry kind="dir" path="/work/wc/docs" revision="42">
+<wc-info><depth>empty</depth></wc-info>
+</entry>
+<entry kind="dir" path="/work/wc/src" revision="42">
+<wc-info><depth>infinity</depth></wc-info>
+</entry>
+<entry kind="dir" path="/work/wc/src/core" revision="42"></entry>
+</info>"#;
+
+        let scope = parse_svn_depth_xml(xml, std::path::Path::new("/work/wc")).unwrap();
+        assert_eq!(
+            scope.to_string(),
+            "根目录=immediates，docs=empty，src=infinity"
+        );
+        assert!(scope.contains("src/core/a.rs"));
+        assert!(!scope.contains("docs/a.md"));
+    }
+
+    #[test]
+    fn test_parse_svn_proplist_xml() {
+        let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
+<properties>
+<target path="/work/wc/docs/spec.docx">
+<property name="svn:needs-lock">*</property>
+<property name="svn:mime-type">application/octet-stream</property>
+</target>
+<target path="/work/wc/logo.psd">
+<property name="svn:needs-lock">*</property>
+</target>
+</properties>"#;
+
+        let properties = parse_svn_proplist_xml(xml, std::path::Path::new("/work/wc")).unwrap();
+        assert_eq!(properties.len(), 2);
+        let locked = &properties["svn:needs-lock"];
+        assert_eq!(locked.len(), 2);
+        assert_eq!(locked["docs/spec.docx"], "*");
+        assert_eq!(locked["logo.psd"], "*");
+        assert_eq!(
+            properties["svn:mime-type"]["docs/spec.docx"],
+            "application/octet-stream"
+        );
+    }
+
+    #[test]
+    fn test_parse_svn_changelist_xml() {
+        let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
+<info>
+<entry kind="file" path="/work/wc/src/a.rs" revision="3">
+<changelist>ui</changelist>
+</entry>
+<entry kind="dir" path="/work/wc/docs" revision="3">
+<changelist>ui</changelist>
+</entry>
+</info>"#;
+
+        let paths = parse_svn_changelist_xml(xml, std::path::Path::new("/work/wc")).unwrap();
+        assert_eq!(paths, vec!["src/a.rs"]);
+    }
+
+    #[test]
+    fn test_parse_svn_status_xml() {
+        let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
+<status>
+<target path="/work/wc">
+<entry path="/work/wc/src/a.rs">
+<wc-status item="modified" props="none" revision="3"></wc-status>
+</entry>
+<entry path="/work/wc/docs">
+<wc-status item="normal" props="modified" revision="3"></wc-status>
+</entry>
+<entry path="/work/wc/lib">
+<wc-status item="external" props="none"></wc-status>
+</entry>
+<entry path="/work/wc/new.txt">
+<wc-status item="added" props="none" revision="-1"></wc-status>
+</entry>
+</target>
+</status>"#;
+
+        let paths = parse_svn_status_xml(xml, std::path::Path::new("/work/wc")).unwrap();
+        assert_eq!(paths, vec!["src/a.rs", "docs", "new.txt"]);
+    }
+
+    #[test]
+    fn test_parse_svn_list_xml() {
+        let xml = br#"<?xml version="1.0" encoding="UTF-8"?>
+<lists>
+<list path="/wc">
+<entry kind="dir"><name>branches</name><commit revision="3"/></entry>
+<entry kind="file"><name>README</name><size>4</size><commit revision="1"/></entry>
+<entry kind="dir"><name>trunk</name><commit revision="2"/></entry>
+</list>
+</lists>"#;
+
+        assert_eq!(
+            parse_svn_list_xml(xml).unwrap(),
+            vec!["branches/", "README", "trunk/"]
+        );
     }
 }
@@ -0,0 +1,189 @@
+//! SVN操作抽象接口
+//!
+//! 定义SVN操作的统一接口，支持真实SVN命令和Mock实现
+
+use crate::{depth::SparseScope, error::Result};
+use std::path::Path;
+
+use super::svn::{SvnDiffSummary, SvnInfo, SvnLog, SvnLogQuery, SvnProperties};
+
+/// SVN操作抽象特征
+///
+/// 提供所有SVN相关操作的统一接口，支持真实实现和Mock实现
+pub trait SvnOperations {
+    /// 获取待同步日志
+    ///
+    /// # 参数
+    ///
+    /// * `path` - SVN工作副本路径
+    /// * `query` - 查询范围（默认从工作副本 BASE 之后开始）
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<SvnLog>)` - 按版本升序排列的日志
+    /// * `Err(SyncError)` - 获取失败
+    fn get_logs(&self, path: &Path, query: &SvnLogQuery) -> Result<Vec<SvnLog>>;
+
+    /// 逐条获取待同步日志
+    ///
+    /// 与 [`SvnOperations::get_logs`] 相同，但边读取边返回，不把全部日志留在内存中；
+    /// 默认实现先获取全部日志
+    ///
+    /// # 参数
+    ///
+    /// * `path` - SVN工作副本路径
+    /// * `query` - 查询范围（默认从工作副本 BASE 之后开始）
+    ///
+    /// # 返回值
+    ///
+    /// 按版本升序排列的日志，读取或解析失败时最后一项是错误
+    fn log_entries<'a>(
+        &'a self,
+        path: &Path,
+        query: &SvnLogQuery,
+    ) -> Result<Box<dyn Iterator<Item = Result<SvnLog>> + 'a>> {
+        Ok(Box::new(self.get_logs(path, query)?.into_iter().map(Ok)))
+    }
+
+    /// 将工作副本更新到指定版本
+    ///
+    /// # 参数
+    ///
+    /// * `path` - SVN工作副本路径
+    /// * `rev` - 目标版本
+    fn update_to_rev(&self, path: &Path, rev: &str) -> Result<()>;
+
+    /// 只把指定路径更新到指定版本
+    ///
+    /// # 参数
+    ///
+    /// * `path` - SVN工作副本路径
+    /// * `rev` - 目标版本
+    /// * `paths` - 相对工作副本根目录（`/` 分隔）的路径
+    fn update_paths_to_rev(&self, path: &Path, rev: &str, paths: &[String]) -> Result<()>;
+
+    /// 获取属于指定变更列表的文件
+    ///
+    /// # 参数
+    ///
+    /// * `path` - SVN工作副本路径
+    /// * `changelist` - 变更列表名称
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<String>)` - 相对工作副本根目录（`/` 分隔）的文件路径
+    /// * `Err(SyncError)` - 读取失败
+    fn changelist_paths(&self, path: &Path, changelist: &str) -> Result<Vec<String>>;
+
+    /// 获取工作副本信息
+    ///
+    /// # 参数
+    ///
+    /// * `path` - SVN工作副本路径
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(SvnInfo)` - 工作副本信息
+    /// * `Err(SyncError)` - 目录不是有效的工作副本等
+    fn info(&self, path: &Path) -> Result<SvnInfo>;
+
+    /// 获取工作副本中有本地修改的路径
+    ///
+    /// # 参数
+    ///
+    /// * `path` - SVN工作副本路径
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<String>)` - 相对工作副本根目录（`/` 分隔）的路径，没有修改时为空
+    /// * `Err(SyncError)` - 读取失败
+    fn modified_paths(&self, path: &Path) -> Result<Vec<String>>;
+
+    /// 放弃工作副本中的所有本地修改和冲突（`svn cleanup` 后 `svn revert -R`）
+    ///
+    /// 更新产生冲突后用于恢复工作副本，BASE 版本保持不变
+    ///
+    /// # 参数
+    ///
+    /// * `path` - SVN工作副本路径
+    fn revert_all(&self, path: &Path) -> Result<()>;
+
+    /// 把旧格式的工作副本升级为当前 svn 客户端的格式（`svn upgrade`）
+    ///
+    /// # 参数
+    ///
+    /// * `path` - SVN工作副本路径
+    fn upgrade(&self, path: &Path) -> Result<()>;
+
+    /// 检出SVN仓库
+    ///
+    /// # 参数
+    ///
+    /// * `url` - SVN仓库URL
+    /// * `path` - 检出目标目录
+    /// * `rev` - 检出版本
+    fn checkout(&self, url: &str, path: &Path, rev: &str) -> Result<()>;
+
+    /// 导出指定版本的目录树（只读，不修改工作副本）
+    ///
+    /// # 参数
+    ///
+    /// * `path` - SVN工作副本路径
+    /// * `rev` - 导出版本
+    /// * `dest` - 导出目标目录
+    fn export(&self, path: &Path, rev: &str, dest: &Path) -> Result<()>;
+
+    /// 递归读取工作副本中所有路径的版本属性
+    ///
+    /// # 参数
+    ///
+    /// * `path` - SVN工作副本路径
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(SvnProperties)` - 属性名 -> (相对工作副本根目录（`/` 分隔）的路径 -> 属性值)
+    /// * `Err(SyncError)` - 读取失败
+    fn proplist(&self, path: &Path) -> Result<SvnProperties>;
+
+    /// 列出工作副本对应的仓库目录在 HEAD 版本中的直接子项
+    ///
+    /// # 参数
+    ///
+    /// * `path` - SVN工作副本路径
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<String>)` - 子项名称，目录以 `/` 结尾
+    /// * `Err(SyncError)` - 读取失败
+    fn list(&self, path: &Path) -> Result<Vec<String>>;
+
+    /// 读取工作副本实际检出的范围（各目录的 `--set-depth`）
+    ///
+    /// # 参数
+    ///
+    /// * `path` - SVN工作副本路径
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(SparseScope)` - 检出范围，完整检出时 [`SparseScope::is_full`] 为 true
+    /// * `Err(SyncError)` - 读取失败
+    fn depth_scope(&self, path: &Path) -> Result<SparseScope>;
+
+    /// 获取一个版本的变更摘要（`svn diff -c REV --summarize`）
+    ///
+    /// # 参数
+    ///
+    /// * `path` - SVN工作副本路径
+    /// * `rev` - SVN 版本
+    ///
+    /// # 返回值
+    ///
+    /// * `Ok(Vec<SvnDiffSummary>)` - 变更的路径，相对工作副本对应的目录
+    /// * `Err(SyncError)` - 读取失败
+    fn diff_summary(&self, path: &Path, rev: &str) -> Result<Vec<SvnDiffSummary>>;
+}
+
+// 重新导出具体实现
+pub use super::mock_svn::{MockSvnOperations, MockSvnRepo};
+pub use super::real_svn::RealSvnOperations;
+pub use super::svn_provider::{SvnOperationsFactory, SvnProvider};
@@ -0,0 +1,250 @@
+//! SVN提供者模块
+//!
+//! 提供统一的SVN操作抽象，支持真实SVN和Mock实现的无缝切换
+
+use std::path::Path;
+
+use super::command_env::{CommandEnv, CommandSettings};
+use super::git_provider::ProviderType;
+use super::mock_svn::MockSvnOperations;
+use super::real_svn::RealSvnOperations;
+use super::svn::{SvnInfo, SvnLog, SvnLogQuery, SvnProperties};
+use super::svn_operations::SvnOperations;
+
+/// SVN提供者类型
+///
+/// 支持真实SVN操作和Mock操作两种实现方式
+#[derive(Debug, Clone)]
+pub enum SvnProvider {
+    /// 真实SVN操作实现
+    Real(RealSvnOperations),
+    /// Mock SVN操作实现（用于测试）
+    Mock(MockSvnOperations),
+}
+
+impl SvnProvider {
+    /// 创建新的SVN提供者实例
+    ///
+    /// # 参数
+    ///
+    /// * `provider_type` - 提供者类型
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use svn2git::{ProviderType, SvnProvider};
+    ///
+    /// let real_provider = SvnProvider::new(ProviderType::Real);
+    /// let mock_provider = SvnProvider::new(ProviderType::Mock);
+    /// ```
+    pub fn new(provider_type: ProviderType) -> Self {
+        match provider_type {
+            // SVN没有libgit2和自定义Git后端对应的实现，使用真实SVN命令
+            ProviderType::Real | ProviderType::Libgit2 | ProviderType::Custom(_) => {
+                Self::Real(RealSvnOperations::new())
+            }
+            ProviderType::Mock => Self::Mock(MockSvnOperations::new()),
+        }
+    }
+
+    /// 创建执行命令时附带额外环境变量的SVN提供者
+    ///
+    /// Mock实现不执行外部命令，会忽略环境变量
+    ///
+    /// # 参数
+    ///
+    /// * `provider_type` - 提供者类型
+    /// * `env` - 额外的环境变量
+    pub fn with_env(provider_type: ProviderType, env: CommandEnv) -> Self {
+        match provider_type {
+            ProviderType::Real | ProviderType::Libgit2 | ProviderType::Custom(_) => {
+                Self::Real(RealSvnOperations::with_env(env))
+            }
+            ProviderType::Mock => Self::Mock(MockSvnOperations::new()),
+        }
+    }
+
+    /// 设置执行svn命令的设置
+    ///
+    /// Mock实现不执行外部命令，会忽略这些设置
+    ///
+    /// # 参数
+    ///
+    /// * `settings` - 执行命令的设置
+    pub fn with_settings(self, settings: CommandSettings) -> Self {
+        match self {
+            Self::Real(ops) => Self::Real(ops.with_settings(settings)),
+            Self::Mock(ops) => Self::Mock(ops),
+        }
+    }
+}
+
+impl SvnOperations for SvnProvider {
+    fn get_logs(&self, path: &Path, query: &SvnLogQuery) -> crate::error::Result<Vec<SvnLog>> {
+        match self {
+            SvnProvider::Real(ops) => ops.get_logs(path, query),
+            SvnProvider::Mock(ops) => ops.get_logs(path, query),
+        }
+    }
+
+    fn log_entries<'a>(
+        &'a self,
+        path: &Path,
+        query: &SvnLogQuery,
+    ) -> crate::error::Result<Box<dyn Iterator<Item = crate::error::Result<SvnLog>> + 'a>> {
+        match self {
+            SvnProvider::Real(ops) => ops.log_entries(path, query),
+            SvnProvider::Mock(ops) => ops.log_entries(path, query),
+        }
+    }
+
+    fn update_to_rev(&self, path: &Path, rev: &str) -> crate::error::Result<()> {
+        match self {
+            SvnProvider::Real(ops) => ops.update_to_rev(path, rev),
+            SvnProvider::Mock(ops) => ops.update_to_rev(path, rev),
+        }
+    }
+
+    fn update_paths_to_rev(
+        &self,
+        path: &Path,
+        rev: &str,
+        paths: &[String],
+    ) -> crate::error::Result<()> {
+        match self {
+            SvnProvider::Real(ops) => ops.update_paths_to_rev(path, rev, paths),
+            SvnProvider::Mock(ops) => ops.update_paths_to_rev(path, rev, paths),
+        }
+    }
+
+    fn changelist_paths(&self, path: &Path, changelist: &str) -> crate::error::Result<Vec<String>> {
+        match self {
+            SvnProvider::Real(ops) => ops.changelist_paths(path, changelist),
+            SvnProvider::Mock(ops) => ops.changelist_paths(path, changelist),
+        }
+    }
+
+    fn info(&self, path: &Path) -> crate::error::Result<SvnInfo> {
+        match self {
+            SvnProvider::Real(ops) => ops.info(path),
+            SvnProvider::Mock(ops) => ops.info(path),
+        }
+    }
+
+    fn modified_paths(&self, path: &Path) -> crate::error::Result<Vec<String>> {
+        match self {
+            SvnProvider::Real(ops) => ops.modified_paths(path),
+            SvnProvider::Mock(ops) => ops.modified_paths(path),
+        }
+    }
+
+    fn upgrade(&self, path: &Path) -> crate::error::Result<()> {
+        match self {
+            SvnProvider::Real(ops) => ops.upgrade(path),
+            SvnProvider::Mock(ops) => ops.upgrade(path),
+        }
+    }
+
+    fn revert_all(&self, path: &Path) -> crate::error::Result<()> {
+        match self {
+            SvnProvider::Real(ops) => ops.revert_all(path),
+            SvnProvider::Mock(ops) => ops.revert_all(path),
+        }
+    }
+
+    fn checkout(&self, url: &str, path: &Path, rev: &str) -> crate::error::Result<()> {
+        match self {
+            SvnProvider::Real(ops) => ops.checkout(url, path, rev),
+            SvnProvider::Mock(ops) => ops.checkout(url, path, rev),
+        }
+    }
+
+    fn export(&self, path: &Path, rev: &str, dest: &Path) -> crate::error::Result<()> {
+        match self {
+            SvnProvider::Real(ops) => ops.export(path, rev, dest),
+            SvnProvider::Mock(ops) => ops.export(path, rev, dest),
+        }
+    }
+
+    fn proplist(&self, path: &Path) -> crate::error::Result<SvnProperties> {
+        match self {
+            SvnProvider::Real(ops) => ops.proplist(path),
+            SvnProvider::Mock(ops) => ops.proplist(path),
+        }
+    }
+
+    fn list(&self, path: &Path) -> crate::error::Result<Vec<String>> {
+        match self {
+            SvnProvider::Real(ops) => ops.list(path),
+            SvnProvider::Mock(ops) => ops.list(path),
+        }
+    }
+
+    fn depth_scope(&self, path: &Path) -> crate::error::Result<crate::depth::SparseScope> {
+        match self {
+            SvnProvider::Real(ops) => ops.depth_scope(path),
+            SvnProvider::Mock(ops) => ops.depth_scope(path),
+        }
+    }
+
+    fn diff_summary(
+        &self,
+        path: &Path,
+        rev: &str,
+    ) -> crate::error::Result<Vec<super::SvnDiffSummary>> {
+        match self {
+            SvnProvider::Real(ops) => ops.diff_summary(path, rev),
+            SvnProvider::Mock(ops) => ops.diff_summary(path, rev),
+        }
+    }
+}
+
+/// SVN操作工厂
+///
+/// 提供创建不同SVN操作实现的统一接口
+pub struct SvnOperationsFactory;
+
+impl SvnOperationsFactory {
+    /// 根据提供者类型创建SVN操作实例
+    pub fn create(provider_type: ProviderType) -> SvnProvider {
+        SvnProvider::new(provider_type)
+    }
+
+    /// 根据环境变量创建SVN操作实例
+    ///
+    /// 从环境变量 `SVN2GIT_SVN_PROVIDER` 读取提供者类型（"real" 或 "mock"），
+    /// 未设置或无效时使用默认的Real实现
+    pub fn create_from_env() -> SvnProvider {
+        match std::env::var("SVN2GIT_SVN_PROVIDER") {
+            Ok(type_str) => match type_str.to_lowercase().as_str() {
+                "real" => SvnProvider::new(ProviderType::Real),
+                "mock" => SvnProvider::new(ProviderType::Mock),
+                _ => {
+                    eprintln!(
+                        "警告: 无效的SVN提供者类型 '{}', 使用默认的Real实现",
+                        type_str
+                    );
+                    SvnProvider::new(ProviderType::Real)
+                }
+            },
+            Err(_) => SvnProvider::new(ProviderType::Real),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svn_provider_creation() {
+        assert!(matches!(
+            SvnProvider::new(ProviderType::Real),
+            SvnProvider::Real(_)
+        ));
+        assert!(matches!(
+            SvnOperationsFactory::create(ProviderType::Mock),
+            SvnProvider::Mock(_)
+        ));
+    }
+}
@@ -0,0 +1,59 @@
+//! 终端输出的详细程度
+//!
+//! 在 CI 等非终端环境中，大规模同步逐个步骤的输出可能产生几 MB 的日志。
+//! 简要模式下同步过程中的细节（如 svn update、git add 的每一步）不再输出，
+//! 每个版本只输出一行结果，另外保留警告、错误和最终汇总。
+
+use std::fmt::Display;
+
+/// 同步过程的输出，决定是否输出细节
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reporter {
+    /// 是否输出同步过程中的细节
+    verbose: bool,
+}
+
+impl Reporter {
+    /// 创建输出
+    ///
+    /// # 参数
+    ///
+    /// * `verbose`: 是否输出同步过程中的细节
+    pub fn new(verbose: bool) -> Self {
+        Self { verbose }
+    }
+
+    /// 是否输出同步过程中的细节
+    pub fn is_verbose(&self) -> bool {
+        self.verbose
+    }
+
+    /// 输出同步过程中的细节，简要模式下不输出
+    ///
+    /// # 参数
+    ///
+    /// * `message`: 细节内容
+    pub fn detail(&self, message: impl Display) {
+        if self.verbose {
+            println!("{message}");
+        }
+    }
+}
+
+impl Default for Reporter {
+    /// 默认输出细节
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reporter_verbose() {
+        assert!(Reporter::default().is_verbose());
+        assert!(!Reporter::new(false).is_verbose());
+    }
+}
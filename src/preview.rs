@@ -0,0 +1,230 @@
+//! 过滤规则预览：在同步之前统计每条跳过/合并规则会影响的 SVN 版本和文件
+
+use std::fmt::Display;
+
+use crate::{command::CommitArgs, filter::PathFilter, ops::SvnLog};
+
+/// 一条过滤规则的影响范围
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterImpact {
+    /// 规则，如 `--skip-paths trunk/docs`
+    pub filter: String,
+    /// 规则匹配的版本
+    pub revisions: Vec<String>,
+    /// 规则匹配的文件变更数
+    pub files: usize,
+}
+
+/// 过滤规则预览
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterPreview {
+    /// 检查的版本
+    pub revisions: Vec<String>,
+    /// 检查的文件变更数
+    pub files: usize,
+    /// 每条规则的影响范围，按命令行参数顺序排列
+    pub impacts: Vec<FilterImpact>,
+    /// 按跳过规则不会单独生成提交的版本
+    pub skipped: Vec<String>,
+}
+
+impl FilterPreview {
+    /// 没有匹配任何版本的规则
+    pub fn unused_filters(&self) -> impl Iterator<Item = &FilterImpact> {
+        self.impacts
+            .iter()
+            .filter(|impact| impact.revisions.is_empty())
+    }
+}
+
+/// 统计过滤规则对一组 SVN 版本的影响
+///
+/// 跳过路径按同步时的规则判断：版本的所有变更路径都匹配跳过规则时才跳过整个版本，
+/// 只有部分路径匹配时这些文件仍随版本提交；没有变更路径信息的版本不按路径跳过。
+///
+/// # 参数
+///
+/// * `logs`: SVN 版本（需要包含变更路径）
+/// * `args`: 提交参数中的过滤规则
+pub fn preview_filters(logs: &[SvnLog], args: &CommitArgs) -> FilterPreview {
+    let mut impacts = Vec::new();
+    for pattern in &args.skip_paths {
+        let filter = PathFilter::new(Vec::new(), vec![pattern.clone()]);
+        let mut impact = FilterImpact {
+            filter: format!("--skip-paths {pattern}"),
+            revisions: Vec::new(),
+            files: 0,
+        };
+        for log in logs {
+            let files = log
+                .paths
+                .iter()
+                .filter(|changed| !filter.matches(changed.path.trim_start_matches('/')))
+                .count();
+            if files > 0 {
+                impact.revisions.push(log.version.clone());
+                impact.files += files;
+            }
+        }
+        impacts.push(impact);
+    }
+    for (flag, authors) in [
+        ("--skip-author", &args.skip_authors),
+        ("--merge-author", &args.merge_authors),
+    ] {
+        for author in authors {
+            let matched: Vec<&SvnLog> = logs.iter().filter(|log| &log.author == author).collect();
+            impacts.push(FilterImpact {
+                filter: format!("{flag} {author}"),
+                revisions: matched.iter().map(|log| log.version.clone()).collect(),
+                files: matched.iter().map(|log| log.paths.len()).sum(),
+            });
+        }
+    }
+
+    let skip_paths = PathFilter::new(Vec::new(), args.skip_paths.clone());
+    let skipped =
+        logs.iter()
+            .filter(|log| {
+                args.skip_authors.contains(&log.author)
+                    || (!skip_paths.is_empty()
+                        && !log.paths.is_empty()
+                        && log.paths.iter().all(|changed| {
+                            !skip_paths.matches(changed.path.trim_start_matches('/'))
+                        }))
+            })
+            .map(|log| log.version.clone())
+            .collect();
+
+    FilterPreview {
+        revisions: logs.iter().map(|log| log.version.clone()).collect(),
+        files: logs.iter().map(|log| log.paths.len()).sum(),
+        impacts,
+        skipped,
+    }
+}
+
+impl Display for FilterPreview {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.revisions.first(), self.revisions.last()) {
+            (Some(first), Some(last)) => writeln!(
+                f,
+                "检查 {} 个 SVN 版本（r{first} - r{last}），共 {} 个文件变更",
+                self.revisions.len(),
+                self.files
+            )?,
+            _ => writeln!(f, "没有需要检查的 SVN 版本")?,
+        }
+        if self.impacts.is_empty() {
+            return write!(f, "没有配置跳过或合并规则");
+        }
+        for impact in &self.impacts {
+            if impact.revisions.is_empty() {
+                writeln!(f, "{}：未匹配任何版本", impact.filter)?;
+            } else {
+                writeln!(
+                    f,
+                    "{}：匹配 {} 个版本，{} 个文件变更（{}）",
+                    impact.filter,
+                    impact.revisions.len(),
+                    impact.files,
+                    format_revisions(&impact.revisions)
+                )?;
+            }
+        }
+        match self.skipped.is_empty() {
+            true => write!(f, "不会跳过任何版本"),
+            false => write!(
+                f,
+                "将跳过 {} 个版本，其更改随下一个同步的版本进入 Git：{}",
+                self.skipped.len(),
+                format_revisions(&self.skipped)
+            ),
+        }
+    }
+}
+
+/// 版本列表，超过 10 个时只列出前 10 个
+fn format_revisions(revisions: &[String]) -> String {
+    const SHOWN: usize = 10;
+    let mut text = revisions
+        .iter()
+        .take(SHOWN)
+        .map(|rev| format!("r{rev}"))
+        .collect::<Vec<_>>()
+        .join("、");
+    if revisions.len() > SHOWN {
+        text.push_str(&format!(" 等 {} 个", revisions.len()));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::SvnChangedPath;
+
+    fn log(version: &str, author: &str, paths: &[&str]) -> SvnLog {
+        SvnLog {
+            version: version.into(),
+            author: author.into(),
+            paths: paths
+                .iter()
+                .map(|path| SvnChangedPath {
+                    action: "M".into(),
+                    path: format!("/{path}"),
+                    copy_from: None,
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_preview_filters() {
+        let logs = [
+            log("1", "alice", &["trunk/src/a.c", "trunk/docs/a.md"]),
+            log("2", "alice", &["trunk/docs/b.md"]),
+            log("3", "buildbot", &["trunk/version.txt"]),
+            log("4", "ci", &["trunk/src/b.c"]),
+        ];
+        let args = CommitArgs {
+            skip_paths: vec!["trunk/docs".into(), "branches".into()],
+            skip_authors: vec!["buildbot".into()],
+            merge_authors: vec!["ci".into()],
+            ..Default::default()
+        };
+
+        let preview = preview_filters(&logs, &args);
+        assert_eq!(preview.files, 5);
+        assert_eq!(preview.impacts[0].revisions, ["1", "2"]);
+        assert_eq!(preview.impacts[0].files, 2);
+        assert_eq!(preview.impacts[2].revisions, ["3"]);
+        assert_eq!(preview.impacts[3].files, 1);
+        // r1 还修改了其他文件，不会被跳过
+        assert_eq!(preview.skipped, ["2", "3"]);
+        assert_eq!(
+            preview
+                .unused_filters()
+                .map(|i| &i.filter)
+                .collect::<Vec<_>>(),
+            ["--skip-paths branches"]
+        );
+
+        let text = preview.to_string();
+        assert!(text.starts_with("检查 4 个 SVN 版本（r1 - r4），共 5 个文件变更"));
+        assert!(text.contains("--skip-paths trunk/docs：匹配 2 个版本，2 个文件变更（r1、r2）"));
+        assert!(text.contains("--skip-paths branches：未匹配任何版本"));
+        assert!(text.ends_with("将跳过 2 个版本，其更改随下一个同步的版本进入 Git：r2、r3"));
+    }
+
+    #[test]
+    fn test_format_revisions_should_truncate_long_lists() {
+        let revisions: Vec<String> = (1..=12).map(|rev| rev.to_string()).collect();
+        assert_eq!(
+            format_revisions(&revisions),
+            "r1、r2、r3、r4、r5、r6、r7、r8、r9、r10 等 12 个"
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! 配置方案：可在多个同步对之间共用的一组转换参数
+
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    command::CommitArgs,
+    config::{StateStore, SyncConfig},
+    error::{Result, SyncError},
+};
+
+/// 配置方案在状态存储中的命名空间
+pub const PROFILE_NAMESPACE: &str = "profiles";
+
+/// 配置方案中可以保存的参数
+#[derive(Debug, Parser)]
+#[command(name = "profile", no_binary_name = true)]
+struct ProfileArgs {
+    #[arg(long = "policy", value_name = "NAME=VALUE")]
+    policies: Vec<String>,
+
+    #[command(flatten)]
+    commit: CommitArgs,
+}
+
+/// 配置方案
+///
+/// 以命令行参数的形式保存，格式与 sync 的提交参数相同（如 `--preserve-authors`、`--skip-paths`），
+/// 另外可以用 `--policy NAME=VALUE` 设置同步策略
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConversionProfile {
+    args: Vec<String>,
+}
+
+impl ConversionProfile {
+    /// 从命令行参数创建配置方案
+    ///
+    /// # 参数
+    ///
+    /// * `args`: 提交参数和 `--policy` 参数
+    pub fn from_args(args: Vec<String>) -> Result<Self> {
+        let profile = Self { args };
+        profile.apply(
+            &mut SyncConfig::new("".into(), "".into()),
+            CommitArgs::default(),
+        )?;
+        Ok(profile)
+    }
+
+    /// 保存的命令行参数
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// 把配置方案应用到同步配置
+    ///
+    /// 配置方案中的策略覆盖同步对自身的策略，提交参数作为命令行参数的默认值
+    ///
+    /// # 参数
+    ///
+    /// * `config`: 同步配置
+    /// * `commit`: 命令行传入的提交参数
+    ///
+    /// # 返回
+    ///
+    /// 合并后的提交参数
+    pub fn apply(&self, config: &mut SyncConfig, commit: CommitArgs) -> Result<CommitArgs> {
+        let parsed = ProfileArgs::try_parse_from(&self.args)
+            .map_err(|e| SyncError::App(format!("配置方案参数无效：{e}")))?;
+        for assignment in &parsed.policies {
+            let (name, value) = assignment.split_once('=').ok_or_else(|| {
+                SyncError::App(format!("无效的策略 '{assignment}'，应为 NAME=VALUE"))
+            })?;
+            config.policies.set(name.trim(), value.trim())?;
+        }
+        Ok(commit.with_defaults(parsed.commit))
+    }
+
+    /// 读取配置方案
+    ///
+    /// # 参数
+    ///
+    /// * `store`: 状态存储
+    /// * `name`: 配置方案名称
+    pub fn load(store: &dyn StateStore, name: &str) -> Result<Self> {
+        let buf = store.get(PROFILE_NAMESPACE, name)?.ok_or_else(|| {
+            SyncError::App(format!("配置方案 '{name}' 不存在，可用 profile list 查看"))
+        })?;
+        Ok(serde_json::from_slice(&buf)?)
+    }
+
+    /// 保存配置方案，同名的方案会被覆盖
+    ///
+    /// # 参数
+    ///
+    /// * `store`: 状态存储
+    /// * `name`: 配置方案名称
+    pub fn save(&self, store: &dyn StateStore, name: &str) -> Result<()> {
+        store.put(PROFILE_NAMESPACE, name, &serde_json::to_vec(self)?)
+    }
+
+    /// 删除配置方案
+    ///
+    /// # 参数
+    ///
+    /// * `store`: 状态存储
+    /// * `name`: 配置方案名称
+    pub fn delete(store: &dyn StateStore, name: &str) -> Result<()> {
+        Self::load(store, name)?;
+        store.remove(PROFILE_NAMESPACE, name)
+    }
+
+    /// 列出所有配置方案的名称
+    ///
+    /// # 参数
+    ///
+    /// * `store`: 状态存储
+    pub fn list(store: &dyn StateStore) -> Result<Vec<String>> {
+        store.keys(PROFILE_NAMESPACE)
+    }
+}
+
+/// 应用同步对引用的配置方案
+///
+/// 同步对没有引用配置方案时原样返回提交参数
+///
+/// # 参数
+///
+/// * `store`: 保存配置方案的状态存储
+/// * `config`: 同步配置
+/// * `commit`: 命令行传入的提交参数
+///
+/// # 返回
+///
+/// 合并后的提交参数
+pub fn apply_profile(
+    store: &dyn StateStore,
+    config: &mut SyncConfig,
+    commit: CommitArgs,
+) -> Result<CommitArgs> {
+    match config.profile.clone() {
+        Some(name) => {
+            println!("使用配置方案：{name}");
+            ConversionProfile::load(store, &name)?.apply(config, commit)
+        }
+        None => Ok(commit),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ErrorPolicy, MemoryStorage};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_profile_should_provide_defaults_for_command_line() {
+        let store = MemoryStorage::new();
+        ConversionProfile::from_args(args(&[
+            "--preserve-authors",
+            "--skip-paths",
+            "trunk/docs",
+            "--subject-width",
+            "72",
+            "--policy",
+            "error=continue",
+        ]))
+        .unwrap()
+        .save(&store, "corp-default")
+        .unwrap();
+        assert_eq!(ConversionProfile::list(&store).unwrap(), ["corp-default"]);
+
+        let mut config = SyncConfig::new("d:/svn".into(), "d:/git".into());
+        config.profile = Some("corp-default".into());
+        let commit = CommitArgs {
+            skip_paths: vec!["trunk/build".into()],
+            subject_width: Some(50),
+            ..Default::default()
+        };
+        let commit = apply_profile(&store, &mut config, commit).unwrap();
+
+        assert!(commit.preserve_authors);
+        assert_eq!(commit.skip_paths, ["trunk/docs", "trunk/build"]);
+        assert_eq!(commit.subject_width, Some(50));
+        assert_eq!(config.policies.error, ErrorPolicy::Continue);
+    }
+
+    #[test]
+    fn test_profile_should_reject_invalid_args_and_missing_name() {
+        assert!(ConversionProfile::from_args(args(&["--no-such-flag"])).is_err());
+        assert!(ConversionProfile::from_args(args(&["--policy", "error=retry"])).is_err());
+
+        let store = MemoryStorage::new();
+        let mut config = SyncConfig::new("d:/svn".into(), "d:/git".into());
+        config.profile = Some("missing".into());
+        assert!(apply_profile(&store, &mut config, CommitArgs::default()).is_err());
+        assert!(ConversionProfile::delete(&store, "missing").is_err());
+    }
+}
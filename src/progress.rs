@@ -0,0 +1,269 @@
+//! 同步进度文件：供外部监控程序轮询
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    clock::{Clock, system_clock},
+    error::Result,
+    lock::SyncLock,
+};
+
+/// 同步状态
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProgressState {
+    /// 正在同步
+    #[default]
+    Running,
+    /// 同步完成
+    Completed,
+    /// 同步失败
+    Failed,
+}
+
+/// 进度文件的内容
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SyncProgress {
+    /// 同步状态
+    pub state: ProgressState,
+    /// 同步进程的进程号
+    pub pid: u32,
+    /// 正在同步的 SVN 版本
+    pub current_rev: Option<String>,
+    /// 已处理的版本数
+    pub done: usize,
+    /// 本次要同步的版本总数
+    pub total: usize,
+    /// 预计剩余秒数，还没有完成任何版本时为 `None`
+    pub eta_seconds: Option<u64>,
+    /// 最近一次错误
+    pub last_error: Option<String>,
+    /// 开始时间
+    pub started_at: Option<DateTime<Utc>>,
+    /// 最后更新时间
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// 同步进度文件
+///
+/// 每开始同步一个版本更新一次，先写入临时文件再重命名，读取方不会读到写了一半的内容。
+/// 写入失败只输出一次警告，不影响同步。
+#[derive(Debug)]
+pub struct ProgressFile {
+    path: Option<PathBuf>,
+    progress: SyncProgress,
+    warned: bool,
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for ProgressFile {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl ProgressFile {
+    /// 同步对的进度文件路径，与锁文件放在同一目录
+    ///
+    /// # 参数
+    ///
+    /// * `config_dir`: 配置文件所在目录
+    /// * `git_dir`: 同步对的 Git 目录
+    pub fn path_for(config_dir: &Path, git_dir: &Path) -> PathBuf {
+        SyncLock::path_for(config_dir, git_dir).with_extension("progress.json")
+    }
+
+    /// 创建进度文件
+    ///
+    /// # 参数
+    ///
+    /// * `path`: 进度文件路径，`None` 表示不写入
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            progress: SyncProgress::default(),
+            warned: false,
+            clock: system_clock(),
+        }
+    }
+
+    /// 设置时钟，开始时间、更新时间和预计剩余时间都从该时钟计算
+    ///
+    /// # 参数
+    ///
+    /// * `clock`: 时钟
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// 进度文件路径，不写入时为 `None`
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// 当前进度
+    pub fn progress(&self) -> &SyncProgress {
+        &self.progress
+    }
+
+    /// 开始同步
+    ///
+    /// # 参数
+    ///
+    /// * `total`: 要同步的版本总数
+    pub fn start(&mut self, total: usize) {
+        if let Some(path) = &self.path {
+            println!("同步进度写入 {}", path.display());
+        }
+        self.progress = SyncProgress {
+            pid: std::process::id(),
+            total,
+            started_at: Some(self.clock.now()),
+            ..Default::default()
+        };
+        self.write();
+    }
+
+    /// 开始同步一个版本
+    ///
+    /// # 参数
+    ///
+    /// * `done`: 已处理的版本数
+    /// * `rev`: 版本号
+    pub fn revision(&mut self, done: usize, rev: &str) {
+        self.progress.done = done;
+        self.progress.current_rev = Some(rev.to_string());
+        let now = self.clock.now();
+        self.progress.eta_seconds = self
+            .progress
+            .started_at
+            .filter(|_| done > 0)
+            .map(|started| {
+                let elapsed = (now - started).to_std().unwrap_or_default();
+                let per_revision = elapsed.as_secs_f64() / done as f64;
+                (per_revision * self.progress.total.saturating_sub(done) as f64).round() as u64
+            });
+        self.write();
+    }
+
+    /// 记录错误，同步继续
+    ///
+    /// # 参数
+    ///
+    /// * `error`: 错误信息
+    pub fn error(&mut self, error: &str) {
+        self.progress.last_error = Some(error.to_string());
+        self.write();
+    }
+
+    /// 同步结束
+    ///
+    /// # 参数
+    ///
+    /// * `result`: 同步结果
+    pub fn finish(&mut self, result: &Result<()>) {
+        match result {
+            Ok(()) => {
+                self.progress.state = ProgressState::Completed;
+                self.progress.done = self.progress.total;
+                self.progress.current_rev = None;
+            }
+            Err(e) => {
+                self.progress.state = ProgressState::Failed;
+                self.progress.last_error = Some(e.to_string());
+            }
+        }
+        self.progress.eta_seconds = None;
+        self.write();
+    }
+
+    fn write(&mut self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        self.progress.updated_at = Some(self.clock.now());
+        let result = serde_json::to_vec_pretty(&self.progress)
+            .map_err(std::io::Error::other)
+            .and_then(|buf| {
+                let tmp = path.with_extension("tmp");
+                fs::write(&tmp, buf)?;
+                fs::rename(&tmp, path)
+            });
+        if let Err(e) = result
+            && !self.warned
+        {
+            eprintln!("警告：写入进度文件 {} 失败：{e}", path.display());
+            self.warned = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SyncError;
+
+    fn read(path: &Path) -> SyncProgress {
+        serde_json::from_slice(&fs::read(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_progress_file_should_track_revisions_and_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = ProgressFile::path_for(dir.path(), Path::new("d:/git"));
+        assert!(path.to_string_lossy().ends_with(".progress.json"));
+
+        let mut file = ProgressFile::new(Some(path.clone()));
+        file.start(3);
+        assert_eq!(read(&path).state, ProgressState::Running);
+        assert_eq!(read(&path).eta_seconds, None);
+
+        file.revision(1, "12");
+        let progress = read(&path);
+        assert_eq!(progress.current_rev.as_deref(), Some("12"));
+        assert_eq!((progress.done, progress.total), (1, 3));
+        assert!(progress.eta_seconds.is_some());
+
+        file.error("r12 同步失败");
+        file.finish(&Err(SyncError::App("以下 SVN 版本同步失败：r12".into())));
+        let progress = read(&path);
+        assert_eq!(progress.state, ProgressState::Failed);
+        assert!(progress.last_error.unwrap().contains("r12"));
+        assert_eq!(progress.pid, std::process::id());
+    }
+
+    #[test]
+    fn test_progress_file_eta_should_follow_clock() {
+        use crate::clock::FixedClock;
+        use chrono::{Duration, TimeZone};
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let clock = FixedClock::new(start);
+        let mut file = ProgressFile::new(None);
+        file.set_clock(Arc::new(clock.clone()));
+        file.start(4);
+        clock.advance(Duration::seconds(30));
+        file.revision(1, "1");
+        assert_eq!(file.progress().eta_seconds, Some(90));
+        clock.advance(Duration::seconds(30));
+        file.revision(3, "3");
+        assert_eq!(file.progress().eta_seconds, Some(20));
+        assert_eq!(file.progress().started_at, Some(start));
+    }
+
+    #[test]
+    fn test_progress_file_without_path_should_not_write() {
+        let mut file = ProgressFile::new(None);
+        file.start(1);
+        file.finish(&Ok(()));
+        assert_eq!(file.progress().state, ProgressState::Completed);
+        assert_eq!(file.progress().done, 1);
+    }
+}
@@ -0,0 +1,664 @@
+//! SVN 版本属性到 Git 的映射
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::Result,
+    ops::{
+        SvnProperties, gitattributes_pattern, update_gitattributes_section,
+        update_gitignore_section,
+    },
+};
+
+/// 需要加锁才能编辑的文件属性
+pub const SVN_NEEDS_LOCK: &str = "svn:needs-lock";
+
+/// 文件 MIME 类型属性
+pub const SVN_MIME_TYPE: &str = "svn:mime-type";
+
+/// 可执行文件属性
+pub const SVN_EXECUTABLE: &str = "svn:executable";
+
+/// 关键字替换属性
+pub const SVN_KEYWORDS: &str = "svn:keywords";
+
+/// 关键字及其别名，`svn:keywords` 中写任一名称都会展开同组的全部名称
+const KEYWORD_ALIASES: &[&[&str]] = &[
+    &["Id"],
+    &["Header"],
+    &["Revision", "Rev", "LastChangedRevision"],
+    &["Date", "LastChangedDate"],
+    &["Author", "LastChangedBy"],
+    &["URL", "HeadURL"],
+];
+
+/// 文件换行符属性
+pub const SVN_EOL_STYLE: &str = "svn:eol-style";
+
+/// 目录忽略规则属性，只作用于目录的直接子项
+pub const SVN_IGNORE: &str = "svn:ignore";
+
+/// 目录忽略规则属性，作用于目录下的所有层级
+pub const SVN_GLOBAL_IGNORES: &str = "svn:global-ignores";
+
+/// 同步 `svn:needs-lock` 文件
+///
+/// SVN 会把设置了 `svn:needs-lock` 的文件检出为只读，直到用户获取锁。
+/// Git 副本与 SVN 工作副本不是同一个文件时，清除 Git 副本的只读位，
+/// 并把这些文件以 `lockable` 属性写入 `.gitattributes`，供 Git LFS 文件锁使用。
+///
+/// # 参数
+///
+/// * `props`: 工作副本的版本属性，见 [`SvnOperations::proplist`](crate::ops::SvnOperations::proplist)
+/// * `svn_dir`: SVN 工作副本目录
+/// * `git_dir`: Git 本地目录
+///
+/// # 返回
+///
+/// `.gitattributes` 有变化时为需要加锁的文件数量，否则为 0
+pub fn sync_needs_lock(props: &SvnProperties, svn_dir: &Path, git_dir: &Path) -> Result<usize> {
+    let locked = property(props, SVN_NEEDS_LOCK);
+    let prefix = git_relative_prefix(svn_dir, git_dir);
+
+    let mut lines = Vec::new();
+    for file in locked.keys() {
+        let git_path = format!("{prefix}{file}");
+        let git_file = git_dir.join(&git_path);
+        if git_file != svn_dir.join(file) && git_file.is_file() {
+            clear_readonly(&git_file)?;
+        }
+        lines.push(format!("{} lockable", gitattributes_pattern(&git_path)));
+    }
+
+    let changed = update_gitattributes_section(git_dir, SVN_NEEDS_LOCK, &lines)?;
+    Ok(if changed { lines.len() } else { 0 })
+}
+
+/// 同步 `svn:mime-type` 标记的二进制文件
+///
+/// SVN 按 MIME 类型判断文件是否为二进制文件，不会对其做换行符转换和合并。
+/// 这些文件以 `binary` 属性写入 `.gitattributes`，避免 Git 转换换行符或尝试文本 diff。
+///
+/// # 参数
+///
+/// * `props`: 工作副本的版本属性，见 [`SvnOperations::proplist`](crate::ops::SvnOperations::proplist)
+/// * `svn_dir`: SVN 工作副本目录
+/// * `git_dir`: Git 本地目录
+///
+/// # 返回
+///
+/// `.gitattributes` 有变化时为二进制文件数量，否则为 0
+pub fn sync_mime_types(props: &SvnProperties, svn_dir: &Path, git_dir: &Path) -> Result<usize> {
+    let prefix = git_relative_prefix(svn_dir, git_dir);
+
+    let lines: Vec<String> = property(props, SVN_MIME_TYPE)
+        .iter()
+        .filter(|(_, mime_type)| is_binary_mime_type(mime_type))
+        .map(|(file, _)| {
+            format!(
+                "{} binary",
+                gitattributes_pattern(&format!("{prefix}{file}"))
+            )
+        })
+        .collect();
+
+    let changed = update_gitattributes_section(git_dir, SVN_MIME_TYPE, &lines)?;
+    Ok(if changed { lines.len() } else { 0 })
+}
+
+/// 同步 `svn:ignore` 和 `svn:global-ignores` 忽略规则
+///
+/// 规则写入 Git 仓库对应目录下 `.gitignore` 中由工具管理的区块：
+/// `svn:ignore` 每行一个模式，只匹配直接子项，转换为以 `/` 开头的模式；
+/// `svn:global-ignores` 以空白分隔，匹配所有层级，原样写入。
+/// 属性被删除的目录会移除对应区块，区块以外的内容保持不变。
+///
+/// # 参数
+///
+/// * `props`: 工作副本的版本属性，见 [`SvnOperations::proplist`](crate::ops::SvnOperations::proplist)
+/// * `svn_dir`: SVN 工作副本目录
+/// * `git_dir`: Git 本地目录
+///
+/// # 返回
+///
+/// `.gitignore` 有变化的目录数量
+pub fn sync_ignores(props: &SvnProperties, svn_dir: &Path, git_dir: &Path) -> Result<usize> {
+    let ignores = property(props, SVN_IGNORE);
+    let global_ignores = property(props, SVN_GLOBAL_IGNORES);
+    let prefix = git_relative_prefix(svn_dir, git_dir);
+    let git_subdir = |dir: &str| git_dir.join(format!("{prefix}{dir}"));
+
+    let mut managed = BTreeSet::new();
+    let mut changed = 0;
+    for dir in ignores.keys().chain(global_ignores.keys()) {
+        let target = git_subdir(dir);
+        if !target.is_dir() {
+            continue;
+        }
+        let local: Vec<String> = ignores
+            .get(dir)
+            .map(|value| value.lines().map(str::trim).collect::<Vec<_>>())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|pattern| !pattern.is_empty())
+            .map(|pattern| gitignore_pattern(pattern, true))
+            .collect();
+        let global: Vec<String> = global_ignores
+            .get(dir)
+            .map(|value| value.split_whitespace().collect::<Vec<_>>())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|pattern| gitignore_pattern(pattern, false))
+            .collect();
+        // 两个区块都要更新，不能短路
+        let local_changed = update_gitignore_section(&target, SVN_IGNORE, &local)?;
+        if update_gitignore_section(&target, SVN_GLOBAL_IGNORES, &global)? | local_changed {
+            changed += 1;
+        }
+        managed.insert(target);
+    }
+
+    for stale in gitignore_dirs(&git_subdir(""))? {
+        if !managed.contains(&stale) {
+            let local_changed = update_gitignore_section(&stale, SVN_IGNORE, &[])?;
+            if update_gitignore_section(&stale, SVN_GLOBAL_IGNORES, &[])? | local_changed {
+                changed += 1;
+            }
+        }
+    }
+    Ok(changed)
+}
+
+/// 把 SVN 忽略模式转换为 `.gitignore` 模式
+///
+/// 以 `#`、`!` 开头的模式需要转义，否则 Git 会当作注释或取反规则
+fn gitignore_pattern(pattern: &str, anchored: bool) -> String {
+    let escaped = if pattern.starts_with(['#', '!']) {
+        format!("\\{pattern}")
+    } else {
+        pattern.to_string()
+    };
+    if anchored {
+        format!("/{escaped}")
+    } else {
+        escaped
+    }
+}
+
+/// 查找目录下所有包含 `.gitignore` 的目录，跳过 `.git` 和 `.svn` 管理目录
+fn gitignore_dirs(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut dirs = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name();
+            if name == ".gitignore" {
+                dirs.push(dir.clone());
+            } else if name != ".git" && name != ".svn" && entry.file_type()?.is_dir() {
+                pending.push(entry.path());
+            }
+        }
+    }
+    Ok(dirs)
+}
+
+/// 获取设置了 `svn:executable` 的文件
+///
+/// Windows 上检出的工作副本没有可执行位，需要由调用方在 Git 暂存区中标记。
+///
+/// # 参数
+///
+/// * `props`: 工作副本的版本属性，见 [`SvnOperations::proplist`](crate::ops::SvnOperations::proplist)
+/// * `svn_dir`: SVN 工作副本目录
+/// * `git_dir`: Git 本地目录
+///
+/// # 返回
+///
+/// Git 目录中存在的可执行文件（相对 Git 仓库根目录）
+pub fn svn_executables(props: &SvnProperties, svn_dir: &Path, git_dir: &Path) -> Vec<String> {
+    let prefix = git_relative_prefix(svn_dir, git_dir);
+    property(props, SVN_EXECUTABLE)
+        .keys()
+        .map(|file| format!("{prefix}{file}"))
+        .filter(|file| git_dir.join(file).is_file())
+        .collect()
+}
+
+/// 还原设置了 `svn:keywords` 的文件中已展开的关键字
+///
+/// 把 `$Id: a.c 12 2024-01-01 alice $` 还原为 `$Id$`，避免每个版本的关键字变化都产生 Git 差异。
+/// SVN 比较文件时会先还原关键字，因此修改共用的工作副本不会被视为本地修改。
+///
+/// # 参数
+///
+/// * `props`: 工作副本的版本属性，见 [`SvnOperations::proplist`](crate::ops::SvnOperations::proplist)
+/// * `svn_dir`: SVN 工作副本目录
+/// * `git_dir`: Git 本地目录
+///
+/// # 返回
+///
+/// 内容被修改的文件数量
+pub fn strip_keywords(props: &SvnProperties, svn_dir: &Path, git_dir: &Path) -> Result<usize> {
+    let prefix = git_relative_prefix(svn_dir, git_dir);
+
+    let mut stripped = 0;
+    for (file, value) in property(props, SVN_KEYWORDS) {
+        let git_file = git_dir.join(format!("{prefix}{file}"));
+        if !git_file.is_file() {
+            continue;
+        }
+        let names: Vec<&str> = value
+            .split_whitespace()
+            .filter_map(|name| KEYWORD_ALIASES.iter().find(|group| group.contains(&name)))
+            .flat_map(|group| group.iter().copied())
+            .collect();
+        if let Some(content) = collapse_keywords(&fs::read(&git_file)?, &names) {
+            fs::write(&git_file, content)?;
+            stripped += 1;
+        }
+    }
+    Ok(stripped)
+}
+
+/// 把内容中 `$名称: 值 $` 形式的关键字还原为 `$名称$`
+///
+/// 关键字必须在同一行内结束；固定宽度形式（`$名称:: 值 $`）同样还原
+///
+/// # 返回
+///
+/// 内容有变化时返回新内容
+fn collapse_keywords(content: &[u8], names: &[&str]) -> Option<Vec<u8>> {
+    let mut result = Vec::with_capacity(content.len());
+    let mut changed = false;
+    let mut rest = content;
+    while let Some(start) = rest.iter().position(|&b| b == b'$') {
+        result.extend_from_slice(&rest[..=start]);
+        rest = &rest[start + 1..];
+        let expanded = names.iter().find_map(|name| {
+            let value = rest.strip_prefix(name.as_bytes())?.strip_prefix(b":")?;
+            let end = value.iter().position(|&b| matches!(b, b'$' | b'\n'))?;
+            (value[end] == b'$').then_some((name, name.len() + 1 + end))
+        });
+        if let Some((name, len)) = expanded {
+            result.extend_from_slice(name.as_bytes());
+            rest = &rest[len..];
+            changed = true;
+        }
+    }
+    result.extend_from_slice(rest);
+    changed.then_some(result)
+}
+
+/// 同步 `svn:eol-style` 换行符设置
+///
+/// 转换为 `.gitattributes` 中的 `text` / `eol` 属性：`native` 为 `text`（按平台转换），
+/// `LF`、`CRLF` 为固定换行符；Git 不支持 `CR`，这类文件按 `-text` 原样保存。
+/// 同时设置为二进制 MIME 类型的文件由 [`sync_mime_types`] 标记为 binary，这里跳过。
+///
+/// # 参数
+///
+/// * `props`: 工作副本的版本属性，见 [`SvnOperations::proplist`](crate::ops::SvnOperations::proplist)
+/// * `svn_dir`: SVN 工作副本目录
+/// * `git_dir`: Git 本地目录
+///
+/// # 返回
+///
+/// `.gitattributes` 有变化时为写入换行符属性的文件数量，否则为 0
+pub fn sync_eol_styles(props: &SvnProperties, svn_dir: &Path, git_dir: &Path) -> Result<usize> {
+    let types = property(props, SVN_MIME_TYPE);
+    let prefix = git_relative_prefix(svn_dir, git_dir);
+
+    let mut lines = Vec::new();
+    for (file, style) in property(props, SVN_EOL_STYLE) {
+        if types.get(file).is_some_and(|t| is_binary_mime_type(t)) {
+            continue;
+        }
+        let attributes = match style.trim() {
+            "native" => "text",
+            "LF" => "text eol=lf",
+            "CRLF" => "text eol=crlf",
+            "CR" => "-text",
+            other => {
+                eprintln!("警告：{file} 的 {SVN_EOL_STYLE} 值 '{other}' 无效，已忽略");
+                continue;
+            }
+        };
+        lines.push(format!(
+            "{} {attributes}",
+            gitattributes_pattern(&format!("{prefix}{file}"))
+        ));
+    }
+
+    let changed = update_gitattributes_section(git_dir, SVN_EOL_STYLE, &lines)?;
+    Ok(if changed { lines.len() } else { 0 })
+}
+
+/// 按 SVN 的规则判断 MIME 类型是否为二进制
+///
+/// 与 `svn_mime_type_is_binary` 一致：`text/` 开头以及 `image/x-xbitmap`、
+/// `image/x-xpixmap` 视为文本，其余均为二进制。
+pub fn is_binary_mime_type(mime_type: &str) -> bool {
+    let mime_type = mime_type.split(';').next().unwrap_or_default().trim();
+    !(mime_type.starts_with("text/") || matches!(mime_type, "image/x-xbitmap" | "image/x-xpixmap"))
+}
+
+/// 设置了指定属性的路径 -> 属性值，没有路径设置该属性时为空
+fn property<'a>(props: &'a SvnProperties, name: &str) -> &'a BTreeMap<String, String> {
+    static EMPTY: BTreeMap<String, String> = BTreeMap::new();
+    props.get(name).unwrap_or(&EMPTY)
+}
+
+/// SVN 工作副本在 Git 仓库中的相对目录（以 `/` 结尾），不在 Git 仓库内时为空
+pub(crate) fn git_relative_prefix(svn_dir: &Path, git_dir: &Path) -> String {
+    match svn_dir.strip_prefix(git_dir) {
+        Ok(relative) if !relative.as_os_str().is_empty() => {
+            let mut prefix = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            prefix.push('/');
+            prefix
+        }
+        _ => String::new(),
+    }
+}
+
+/// 清除文件的只读位（仅为所有者添加写权限）
+///
+/// # 返回
+///
+/// 文件原本是否只读
+fn clear_readonly(path: &Path) -> Result<bool> {
+    let mut permissions = fs::metadata(path)?.permissions();
+    if !permissions.readonly() {
+        return Ok(false);
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        permissions.set_mode(permissions.mode() | 0o200);
+    }
+    #[cfg(not(unix))]
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+
+    fs::set_permissions(path, permissions)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::{MockSvnOperations, SvnOperations};
+
+    #[test]
+    fn test_git_relative_prefix() {
+        assert_eq!(
+            git_relative_prefix(Path::new("/repo/wc"), Path::new("/repo")),
+            "wc/"
+        );
+        assert_eq!(
+            git_relative_prefix(Path::new("/repo"), Path::new("/repo")),
+            ""
+        );
+        assert_eq!(
+            git_relative_prefix(Path::new("/svn"), Path::new("/git")),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_is_binary_mime_type() {
+        assert!(is_binary_mime_type("application/octet-stream"));
+        assert!(is_binary_mime_type("image/png"));
+        assert!(!is_binary_mime_type("text/plain; charset=UTF-8"));
+        assert!(!is_binary_mime_type("image/x-xpixmap"));
+    }
+
+    #[test]
+    fn test_sync_mime_types_should_mark_binary_files() {
+        let svn = tempfile::tempdir().unwrap();
+        let git = tempfile::tempdir().unwrap();
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_log_to_mock(svn.path(), "1", "初始提交");
+        svn_ops.set_property("data.bin", SVN_MIME_TYPE, "application/octet-stream");
+        svn_ops.set_property("notes.txt", SVN_MIME_TYPE, "text/plain");
+
+        assert_eq!(
+            sync_mime_types(
+                &svn_ops.proplist(svn.path()).unwrap(),
+                svn.path(),
+                git.path()
+            )
+            .unwrap(),
+            1
+        );
+        assert_eq!(
+            fs::read_to_string(git.path().join(".gitattributes")).unwrap(),
+            "# BEGIN svn2git svn:mime-type\n/data.bin binary\n# END svn2git svn:mime-type\n"
+        );
+        // 没有变化时不再计数
+        assert_eq!(
+            sync_mime_types(
+                &svn_ops.proplist(svn.path()).unwrap(),
+                svn.path(),
+                git.path()
+            )
+            .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_svn_executables_should_skip_missing_files() {
+        let git = tempfile::tempdir().unwrap();
+        let svn_dir = git.path().join("wc");
+        fs::create_dir_all(&svn_dir).unwrap();
+        fs::write(svn_dir.join("build.sh"), "#!/bin/sh").unwrap();
+
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_log_to_mock(&svn_dir, "1", "初始提交");
+        svn_ops.set_property("build.sh", SVN_EXECUTABLE, "*");
+        svn_ops.set_property("removed.sh", SVN_EXECUTABLE, "*");
+
+        assert_eq!(
+            svn_executables(&svn_ops.proplist(&svn_dir).unwrap(), &svn_dir, git.path()),
+            vec!["wc/build.sh"]
+        );
+    }
+
+    #[test]
+    fn test_collapse_keywords() {
+        let names = ["Id", "Rev", "Revision", "LastChangedRevision"];
+        assert_eq!(
+            collapse_keywords(
+                b"// $Id: a.c 12 2024-01-01 alice $\n// $Rev:: 12   $ $Author: bob $",
+                &names
+            )
+            .unwrap(),
+            b"// $Id$\n// $Rev$ $Author: bob $"
+        );
+        assert_eq!(collapse_keywords(b"cost: $5 and $Id$", &names), None);
+        assert_eq!(collapse_keywords(b"$Id: unterminated\n$", &names), None);
+    }
+
+    #[test]
+    fn test_strip_keywords_should_only_touch_files_with_property() {
+        let svn = tempfile::tempdir().unwrap();
+        let git = tempfile::tempdir().unwrap();
+        fs::write(git.path().join("a.c"), "/* $Revision: 42 $ $Id: x $ */").unwrap();
+        fs::write(git.path().join("b.c"), "/* $Id: b.c 42 $ */").unwrap();
+
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_log_to_mock(svn.path(), "1", "初始提交");
+        svn_ops.set_property("a.c", SVN_KEYWORDS, "Rev");
+        svn_ops.set_property("missing.c", SVN_KEYWORDS, "Id");
+
+        assert_eq!(
+            strip_keywords(
+                &svn_ops.proplist(svn.path()).unwrap(),
+                svn.path(),
+                git.path()
+            )
+            .unwrap(),
+            1
+        );
+        assert_eq!(
+            fs::read_to_string(git.path().join("a.c")).unwrap(),
+            "/* $Revision$ $Id: x $ */"
+        );
+        assert_eq!(
+            fs::read_to_string(git.path().join("b.c")).unwrap(),
+            "/* $Id: b.c 42 $ */"
+        );
+    }
+
+    #[test]
+    fn test_sync_eol_styles_should_write_text_attributes() {
+        let svn = tempfile::tempdir().unwrap();
+        let git = tempfile::tempdir().unwrap();
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_log_to_mock(svn.path(), "1", "初始提交");
+        svn_ops.set_property("a.txt", SVN_EOL_STYLE, "native");
+        svn_ops.set_property("b.sh", SVN_EOL_STYLE, "LF");
+        svn_ops.set_property("c.bat", SVN_EOL_STYLE, "CRLF");
+        svn_ops.set_property("d.mac", SVN_EOL_STYLE, "CR");
+        svn_ops.set_property("e.txt", SVN_EOL_STYLE, "bogus");
+        svn_ops.set_property("f.bin", SVN_EOL_STYLE, "native");
+        svn_ops.set_property("f.bin", SVN_MIME_TYPE, "application/octet-stream");
+
+        assert_eq!(
+            sync_eol_styles(
+                &svn_ops.proplist(svn.path()).unwrap(),
+                svn.path(),
+                git.path()
+            )
+            .unwrap(),
+            4
+        );
+        assert_eq!(
+            fs::read_to_string(git.path().join(".gitattributes")).unwrap(),
+            "# BEGIN svn2git svn:eol-style\n/a.txt text\n/b.sh text eol=lf\n\
+             /c.bat text eol=crlf\n/d.mac -text\n# END svn2git svn:eol-style\n"
+        );
+    }
+
+    #[test]
+    fn test_sync_needs_lock_should_mark_lockable_and_clear_readonly() {
+        let svn = tempfile::tempdir().unwrap();
+        let git = tempfile::tempdir().unwrap();
+        let git_file = git.path().join("logo.psd");
+        fs::write(&git_file, "psd").unwrap();
+        let mut permissions = fs::metadata(&git_file).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&git_file, permissions).unwrap();
+
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_log_to_mock(svn.path(), "1", "初始提交");
+        svn_ops.set_property("logo.psd", SVN_NEEDS_LOCK, "*");
+
+        assert_eq!(
+            sync_needs_lock(
+                &svn_ops.proplist(svn.path()).unwrap(),
+                svn.path(),
+                git.path()
+            )
+            .unwrap(),
+            1
+        );
+        assert!(!fs::metadata(&git_file).unwrap().permissions().readonly());
+        assert_eq!(
+            fs::read_to_string(git.path().join(".gitattributes")).unwrap(),
+            "# BEGIN svn2git svn:needs-lock\n/logo.psd lockable\n# END svn2git svn:needs-lock\n"
+        );
+    }
+
+    #[test]
+    fn test_sync_needs_lock_should_keep_readonly_in_shared_working_copy() {
+        let git = tempfile::tempdir().unwrap();
+        let svn_dir = git.path().join("wc");
+        fs::create_dir_all(&svn_dir).unwrap();
+        let file = svn_dir.join("spec.docx");
+        fs::write(&file, "doc").unwrap();
+        let mut permissions = fs::metadata(&file).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&file, permissions).unwrap();
+
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_log_to_mock(&svn_dir, "1", "初始提交");
+        svn_ops.set_property("spec.docx", SVN_NEEDS_LOCK, "*");
+
+        sync_needs_lock(&svn_ops.proplist(&svn_dir).unwrap(), &svn_dir, git.path()).unwrap();
+        // 与 SVN 工作副本是同一个文件，保留只读位以免绕过 SVN 加锁提醒
+        assert!(fs::metadata(&file).unwrap().permissions().readonly());
+        assert!(
+            fs::read_to_string(git.path().join(".gitattributes"))
+                .unwrap()
+                .contains("/wc/spec.docx lockable")
+        );
+    }
+
+    #[test]
+    fn test_sync_ignores_should_write_gitignore_per_directory() {
+        let svn = tempfile::tempdir().unwrap();
+        let git = tempfile::tempdir().unwrap();
+        fs::create_dir_all(git.path().join("src")).unwrap();
+        fs::create_dir_all(git.path().join("old")).unwrap();
+        fs::write(
+            git.path().join("old/.gitignore"),
+            "keep.me\n# BEGIN svn2git svn:ignore\n/stale\n# END svn2git svn:ignore\n",
+        )
+        .unwrap();
+
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_log_to_mock(svn.path(), "1", "初始提交");
+        svn_ops.set_property("", SVN_IGNORE, "target\n*.log\n");
+        svn_ops.set_property("", SVN_GLOBAL_IGNORES, "*.o #tmp");
+        svn_ops.set_property("src", SVN_IGNORE, "gen");
+
+        // 根目录、src 写入规则，old 移除过时的区块
+        assert_eq!(
+            sync_ignores(
+                &svn_ops.proplist(svn.path()).unwrap(),
+                svn.path(),
+                git.path()
+            )
+            .unwrap(),
+            3
+        );
+        assert_eq!(
+            fs::read_to_string(git.path().join(".gitignore")).unwrap(),
+            "# BEGIN svn2git svn:ignore\n/target\n/*.log\n# END svn2git svn:ignore\n\
+             # BEGIN svn2git svn:global-ignores\n*.o\n\\#tmp\n# END svn2git svn:global-ignores\n"
+        );
+        assert_eq!(
+            fs::read_to_string(git.path().join("src/.gitignore")).unwrap(),
+            "# BEGIN svn2git svn:ignore\n/gen\n# END svn2git svn:ignore\n"
+        );
+        assert_eq!(
+            fs::read_to_string(git.path().join("old/.gitignore")).unwrap(),
+            "keep.me\n"
+        );
+        assert_eq!(
+            sync_ignores(
+                &svn_ops.proplist(svn.path()).unwrap(),
+                svn.path(),
+                git.path()
+            )
+            .unwrap(),
+            0
+        );
+    }
+}
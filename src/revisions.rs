@@ -0,0 +1,189 @@
+//! 版本集合：`--only-revs 120,125,130-140` 指定的 SVN 版本
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    config::RevisionMap,
+    error::{Result, SyncError},
+    ops::SvnLog,
+};
+
+/// 由单个版本和闭区间组成的 SVN 版本集合
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevisionSet {
+    /// 按输入顺序排列的闭区间，单个版本的起止相同
+    ranges: Vec<(u64, u64)>,
+}
+
+impl RevisionSet {
+    /// 版本是否在集合中，不是数字的版本不在任何集合中
+    pub fn contains(&self, rev: &str) -> bool {
+        rev.parse::<u64>().is_ok_and(|rev| {
+            self.ranges
+                .iter()
+                .any(|&(start, end)| (start..=end).contains(&rev))
+        })
+    }
+
+    /// 从待同步的版本中选出集合中的版本
+    ///
+    /// 单个版本必须在待同步的版本中；区间只要求至少包含一个待同步的版本，
+    /// 区间内与该工作副本无关的版本号被忽略。已经同步过（版本映射中有对应提交）的版本不能再选择。
+    ///
+    /// # 参数
+    ///
+    /// * `pending`: 待同步的版本（按同步顺序）
+    /// * `map`: 已同步版本的映射
+    ///
+    /// # 返回
+    ///
+    /// * `Err(SyncError)` - 选择了已同步的版本，或指定的版本（区间）中没有待同步的版本
+    pub fn select(&self, pending: Vec<SvnLog>, map: &RevisionMap) -> Result<Vec<SvnLog>> {
+        if let Some(mapped) = map.entries().iter().find(|m| self.contains(&m.svn_rev)) {
+            return Err(SyncError::App(format!(
+                "r{} 已经同步为 Git 提交 {}，--only-revs 只能选择待同步的版本",
+                mapped.svn_rev, mapped.git_hash
+            )));
+        }
+        for &(start, end) in &self.ranges {
+            let matched = pending.iter().any(|log| {
+                log.version
+                    .parse::<u64>()
+                    .is_ok_and(|rev| (start..=end).contains(&rev))
+            });
+            if !matched {
+                return Err(SyncError::App(format!(
+                    "{} 不在待同步的版本中",
+                    format_range(start, end)
+                )));
+            }
+        }
+        Ok(pending
+            .into_iter()
+            .filter(|log| self.contains(&log.version))
+            .collect())
+    }
+}
+
+impl FromStr for RevisionSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let parse = |rev: &str| {
+            rev.trim()
+                .trim_start_matches('r')
+                .parse::<u64>()
+                .map_err(|_| format!("无效的版本号：{rev}"))
+        };
+        let mut ranges = Vec::new();
+        for part in s.split(',').filter(|part| !part.trim().is_empty()) {
+            let range = match part.split_once('-') {
+                Some((start, end)) => (parse(start)?, parse(end)?),
+                None => (parse(part)?, parse(part)?),
+            };
+            if range.0 > range.1 {
+                return Err(format!("版本区间 {part} 的起始版本大于结束版本"));
+            }
+            ranges.push(range);
+        }
+        if ranges.is_empty() {
+            return Err("至少需要指定一个版本".into());
+        }
+        Ok(Self { ranges })
+    }
+}
+
+impl Display for RevisionSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ranges: Vec<String> = self
+            .ranges
+            .iter()
+            .map(|&(start, end)| format_range(start, end))
+            .collect();
+        write!(f, "{}", ranges.join("、"))
+    }
+}
+
+fn format_range(start: u64, end: u64) -> String {
+    match start == end {
+        true => format!("r{start}"),
+        false => format!("r{start}-r{end}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logs(revs: &[&str]) -> Vec<SvnLog> {
+        revs.iter()
+            .map(|rev| SvnLog {
+                version: rev.to_string(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_revision_set() {
+        let set: RevisionSet = "120, r125,130-140".parse().unwrap();
+        assert!(set.contains("120"));
+        assert!(set.contains("135"));
+        assert!(!set.contains("121"));
+        assert!(!set.contains("HEAD"));
+        assert_eq!(set.to_string(), "r120、r125、r130-r140");
+
+        assert!("".parse::<RevisionSet>().is_err());
+        assert!("12-a".parse::<RevisionSet>().is_err());
+        assert_eq!(
+            "140-130".parse::<RevisionSet>().unwrap_err(),
+            "版本区间 140-130 的起始版本大于结束版本"
+        );
+    }
+
+    #[test]
+    fn test_select_should_validate_against_pending_and_mapped_revisions() {
+        let pending = logs(&["121", "122", "125", "131", "150"]);
+        let mut map = RevisionMap::default();
+        map.insert("120", "abc123");
+
+        let set: RevisionSet = "122,130-140".parse().unwrap();
+        let selected = set.select(pending.clone(), &map).unwrap();
+        assert_eq!(
+            selected
+                .iter()
+                .map(|l| l.version.as_str())
+                .collect::<Vec<_>>(),
+            ["122", "131"]
+        );
+
+        let err = "120,125"
+            .parse::<RevisionSet>()
+            .unwrap()
+            .select(pending.clone(), &map);
+        assert!(
+            err.unwrap_err()
+                .to_string()
+                .contains("r120 已经同步为 Git 提交 abc123")
+        );
+
+        let err = "123"
+            .parse::<RevisionSet>()
+            .unwrap()
+            .select(pending.clone(), &map);
+        assert!(
+            err.unwrap_err()
+                .to_string()
+                .contains("r123 不在待同步的版本中")
+        );
+        let err = "140-149"
+            .parse::<RevisionSet>()
+            .unwrap()
+            .select(pending, &map);
+        assert!(
+            err.unwrap_err()
+                .to_string()
+                .contains("r140-r149 不在待同步的版本中")
+        );
+    }
+}
@@ -0,0 +1,632 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    process::{Child, Command, ExitStatus, Stdio},
+    time::{Duration, Instant},
+};
+
+use chrono::Local;
+
+use crate::{
+    error::{Result, SyncError},
+    watch::StopSignal,
+};
+
+/// 触发停止后等待子进程自行退出的时长，超时后强制结束
+const STOP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 子进程运行期间检查停止信号的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 后台服务描述
+///
+/// 用于生成 systemd 单元文件、注册 Windows 服务，或由 [`run_service`] 在前台运行，
+/// 使 svn2git 能以常驻服务的方式持续同步。
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceSpec {
+    /// 服务名称
+    pub name: String,
+    /// svn2git 可执行文件路径
+    pub exe: PathBuf,
+    /// 启动参数
+    pub args: Vec<String>,
+    /// 工作目录（config.json 所在目录）
+    pub working_dir: PathBuf,
+    /// 日志文件路径
+    pub log_path: PathBuf,
+    /// 异常退出后重启前的等待秒数
+    pub restart_sec: u64,
+    /// watch 两轮之间的等待秒数，`None` 表示使用 watch 的默认值
+    pub interval: Option<u64>,
+}
+
+impl ServiceSpec {
+    /// 创建服务描述
+    ///
+    /// 默认以 `watch` 模式运行，日志写入工作目录下的 `svn2git.log`。
+    ///
+    /// # 参数
+    ///
+    /// * `name`: 服务名称
+    /// * `exe`: svn2git 可执行文件路径
+    /// * `working_dir`: 工作目录
+    pub fn new(name: &str, exe: PathBuf, working_dir: PathBuf) -> Self {
+        Self {
+            name: name.to_string(),
+            exe,
+            args: vec!["watch".to_string()],
+            log_path: working_dir.join("svn2git.log"),
+            working_dir,
+            restart_sec: 30,
+            interval: None,
+        }
+    }
+
+    /// 设置 watch 两轮之间的等待秒数，不设置时使用 watch 的默认值
+    pub fn with_interval(mut self, interval: Option<u64>) -> Self {
+        if let Some(interval) = interval {
+            self.args
+                .extend(["--interval".to_string(), interval.to_string()]);
+        }
+        self.interval = interval;
+        self
+    }
+
+    /// 设置日志文件路径，不设置时使用工作目录下的 `svn2git.log`
+    pub fn with_log_path(mut self, log_path: Option<PathBuf>) -> Self {
+        if let Some(log_path) = log_path {
+            self.log_path = self.working_dir.join(log_path);
+        }
+        self
+    }
+
+    /// 生成 systemd 单元文件内容
+    ///
+    /// 可执行文件和参数按 systemd 的规则加引号，路径中的 `%` 会被转义
+    pub fn systemd_unit(&self) -> String {
+        let exec_start = std::iter::once(self.exe.to_string_lossy())
+            .chain(self.args.iter().map(|arg| arg.into()))
+            .map(|arg| systemd_quote(&arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let log = escape_specifiers(&self.log_path.to_string_lossy());
+
+        format!(
+            "[Unit]\n\
+             Description=svn2git 持续同步 ({name})\n\
+             \n\
+             [Service]\n\
+             Type=simple\n\
+             WorkingDirectory={dir}\n\
+             ExecStart={exec_start}\n\
+             Restart=on-failure\n\
+             RestartSec={restart}\n\
+             StandardOutput=append:{log}\n\
+             StandardError=append:{log}\n\
+             \n\
+             [Install]\n\
+             WantedBy=default.target\n",
+            name = self.name,
+            dir = escape_specifiers(&self.working_dir.to_string_lossy()),
+            restart = self.restart_sec,
+        )
+    }
+
+    /// systemd 用户单元文件路径（`~/.config/systemd/user/<name>.service`）
+    pub fn systemd_unit_path(&self) -> Result<PathBuf> {
+        let config_home = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) => PathBuf::from(dir),
+            None => std::env::var_os("HOME")
+                .map(|home| PathBuf::from(home).join(".config"))
+                .ok_or_else(|| SyncError::App("无法确定用户目录（未设置 HOME）".into()))?,
+        };
+        Ok(config_home
+            .join("systemd")
+            .join("user")
+            .join(format!("{}.service", self.name)))
+    }
+
+    /// 注册为 Windows 服务时的启动参数
+    ///
+    /// 服务进程由服务控制管理器以系统目录为当前目录启动，工作目录和日志路径都写成绝对路径
+    pub fn windows_arguments(&self) -> Vec<String> {
+        let mut args = vec![
+            "service".to_string(),
+            "run".to_string(),
+            "--windows-service".to_string(),
+            self.name.clone(),
+            "--working-dir".to_string(),
+            self.working_dir.to_string_lossy().to_string(),
+            "--log".to_string(),
+            self.log_path.to_string_lossy().to_string(),
+        ];
+        if let Some(interval) = self.interval {
+            args.extend(["--interval".to_string(), interval.to_string()]);
+        }
+        args
+    }
+}
+
+/// 转义 systemd 的 `%` 说明符
+fn escape_specifiers(value: &str) -> String {
+    value.replace('%', "%%")
+}
+
+/// 按 systemd `ExecStart=` 的规则引用一个参数
+///
+/// 包含空白、引号或反斜杠的参数用双引号括起并转义，`$` 写成 `$$` 以免被当作环境变量展开
+fn systemd_quote(arg: &str) -> String {
+    let arg = escape_specifiers(arg).replace('$', "$$");
+    let plain = !arg.is_empty()
+        && !arg
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '\\' | ';'));
+    if plain {
+        return arg;
+    }
+    let escaped = arg.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{escaped}\"")
+}
+
+/// 安装后台服务
+///
+/// - Linux：写入 systemd 用户单元文件，之后需执行
+///   `systemctl --user daemon-reload && systemctl --user enable --now <name>` 启动
+/// - Windows：注册开机自动启动的服务，异常退出后由服务控制管理器重启，
+///   之后需执行 `sc.exe start <name>` 启动（需要管理员权限）
+///
+/// # 返回
+///
+/// 单元文件路径，Windows 上为服务名称
+pub fn install_service(spec: &ServiceSpec) -> Result<PathBuf> {
+    #[cfg(windows)]
+    {
+        windows::install(spec)?;
+        Ok(PathBuf::from(&spec.name))
+    }
+    #[cfg(not(windows))]
+    {
+        install_systemd_unit(spec)
+    }
+}
+
+/// 写入 systemd 用户单元文件
+#[cfg(not(windows))]
+fn install_systemd_unit(spec: &ServiceSpec) -> Result<PathBuf> {
+    if !cfg!(target_os = "linux") {
+        return Err(SyncError::App(
+            "当前平台暂不支持自动安装服务，请用系统的服务管理工具运行 svn2git service run".into(),
+        ));
+    }
+
+    let path = spec.systemd_unit_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, spec.systemd_unit())?;
+    Ok(path)
+}
+
+/// 在前台运行服务
+///
+/// 以 watch 模式启动 svn2git 子进程，标准输出和标准错误追加到日志文件；
+/// 子进程异常退出时等待 `restart_sec` 秒后重启，正常退出或触发停止信号后返回。
+/// 触发停止信号时通知子进程退出（Unix 上发送 SIGTERM，watch 在本轮结束后退出），
+/// 超过 60 秒仍未退出时强制结束；Windows 上直接结束子进程，已完成的版本不受影响。
+/// 用于没有 systemd 的环境，也是注册的 Windows 服务实际运行的内容。
+///
+/// # 参数
+///
+/// * `spec`: 服务描述
+/// * `stop`: 停止信号，触发后不再重启子进程
+///
+/// # 返回
+///
+/// 启动子进程的次数
+pub fn run_service(spec: &ServiceSpec, stop: &StopSignal) -> Result<usize> {
+    if let Some(parent) = spec.log_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut starts = 0;
+    loop {
+        let mut log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&spec.log_path)?;
+        starts += 1;
+        writeln!(
+            log,
+            "[{}] 启动 {} {}",
+            timestamp(),
+            spec.exe.display(),
+            spec.args.join(" ")
+        )?;
+        let mut child = Command::new(&spec.exe)
+            .args(&spec.args)
+            .current_dir(&spec.working_dir)
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log.try_clone()?)
+            .spawn()
+            .map_err(|e| SyncError::App(format!("启动 {} 失败：{e}", spec.exe.display())))?;
+        let status = wait_child(&mut child, stop, &mut log)?;
+        if status.success() || stop.is_stopped() {
+            writeln!(log, "[{}] 已退出（{status}）", timestamp())?;
+            return Ok(starts);
+        }
+        writeln!(
+            log,
+            "[{}] 异常退出（{status}），{} 秒后重启",
+            timestamp(),
+            spec.restart_sec
+        )?;
+        if stop.wait(Duration::from_secs(spec.restart_sec)) {
+            return Ok(starts);
+        }
+    }
+}
+
+/// 等待子进程退出，期间触发停止信号时结束子进程
+fn wait_child(child: &mut Child, stop: &StopSignal, log: &mut File) -> Result<ExitStatus> {
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        if stop.wait(POLL_INTERVAL) {
+            break;
+        }
+    }
+
+    writeln!(log, "[{}] 收到停止请求，结束 watch 子进程", timestamp())?;
+    terminate(child);
+    let deadline = Instant::now() + STOP_TIMEOUT;
+    while Instant::now() < deadline {
+        if let Some(status) = child.try_wait()? {
+            return Ok(status);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    writeln!(
+        log,
+        "[{}] 子进程 {} 秒内没有退出，强制结束",
+        timestamp(),
+        STOP_TIMEOUT.as_secs()
+    )?;
+    // 子进程可能恰好已经退出
+    let _ = child.kill();
+    Ok(child.wait()?)
+}
+
+/// 通知子进程退出：发送 SIGTERM，由 watch 在本轮结束后退出
+#[cfg(unix)]
+fn terminate(child: &mut Child) {
+    // 子进程尚未被回收，pid 仍然有效
+    unsafe {
+        libc::kill(child.id() as libc::pid_t, libc::SIGTERM);
+    }
+}
+
+/// 通知子进程退出：Windows 上没有可以发给单个子进程的终止信号，直接结束
+#[cfg(not(unix))]
+fn terminate(child: &mut Child) {
+    let _ = child.kill();
+}
+
+/// 日志行的时间戳
+fn timestamp() -> String {
+    Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// 作为 Windows 服务运行
+///
+/// 由服务控制管理器按 [`ServiceSpec::windows_arguments`] 启动，
+/// 收到停止或关机请求时停止 [`run_service`]。
+///
+/// # 参数
+///
+/// * `spec`: 服务描述
+#[cfg(windows)]
+pub fn run_windows_service(spec: ServiceSpec) -> Result<()> {
+    windows::dispatch(spec)
+}
+
+/// 卸载后台服务
+///
+/// - Linux：删除 systemd 用户单元文件，卸载前应先执行 `systemctl --user disable --now <name>`
+/// - Windows：停止并删除注册的服务
+///
+/// # 返回
+///
+/// 被删除的单元文件路径，Windows 上为服务名称
+pub fn uninstall_service(spec: &ServiceSpec) -> Result<PathBuf> {
+    #[cfg(windows)]
+    {
+        windows::uninstall(&spec.name)?;
+        Ok(PathBuf::from(&spec.name))
+    }
+    #[cfg(not(windows))]
+    {
+        uninstall_systemd_unit(spec)
+    }
+}
+
+/// 删除 systemd 用户单元文件
+#[cfg(not(windows))]
+fn uninstall_systemd_unit(spec: &ServiceSpec) -> Result<PathBuf> {
+    let path = spec.systemd_unit_path()?;
+    if !path.exists() {
+        return Err(SyncError::App(format!("服务 {} 未安装", spec.name)));
+    }
+    fs::remove_file(&path)?;
+    Ok(path)
+}
+
+/// 通过服务控制管理器注册和运行 Windows 服务
+#[cfg(windows)]
+mod windows {
+    use std::{ffi::OsString, sync::OnceLock, time::Duration};
+
+    use windows_service::{
+        define_windows_service,
+        service::{
+            ServiceAccess, ServiceAction, ServiceActionType, ServiceControl, ServiceControlAccept,
+            ServiceErrorControl, ServiceExitCode, ServiceFailureActions, ServiceFailureResetPeriod,
+            ServiceInfo, ServiceStartType, ServiceState, ServiceStatus, ServiceType,
+        },
+        service_control_handler::{self, ServiceControlHandlerResult},
+        service_dispatcher,
+        service_manager::{ServiceManager, ServiceManagerAccess},
+    };
+
+    use super::{ServiceSpec, run_service};
+    use crate::{
+        error::{Result, SyncError},
+        watch::StopSignal,
+    };
+
+    /// 服务入口由系统回调、不能携带参数，启动分派前把服务描述放在这里
+    static SPEC: OnceLock<ServiceSpec> = OnceLock::new();
+
+    define_windows_service!(ffi_service_main, service_main);
+
+    fn service_error(e: windows_service::Error) -> SyncError {
+        SyncError::App(format!("Windows 服务操作失败：{e}"))
+    }
+
+    /// 注册开机自动启动的服务，进程异常退出时由服务控制管理器重启
+    pub fn install(spec: &ServiceSpec) -> Result<()> {
+        let manager = ServiceManager::local_computer(
+            None::<&str>,
+            ServiceManagerAccess::CONNECT | ServiceManagerAccess::CREATE_SERVICE,
+        )
+        .map_err(service_error)?;
+        let info = ServiceInfo {
+            name: OsString::from(&spec.name),
+            display_name: OsString::from(format!("svn2git 持续同步 ({})", spec.name)),
+            service_type: ServiceType::OWN_PROCESS,
+            start_type: ServiceStartType::AutoStart,
+            error_control: ServiceErrorControl::Normal,
+            executable_path: spec.exe.clone(),
+            launch_arguments: spec
+                .windows_arguments()
+                .into_iter()
+                .map(OsString::from)
+                .collect(),
+            dependencies: vec![],
+            account_name: None,
+            account_password: None,
+        };
+        let service = manager
+            .create_service(&info, ServiceAccess::CHANGE_CONFIG)
+            .map_err(service_error)?;
+        let restart = ServiceAction {
+            action_type: ServiceActionType::Restart,
+            delay: Duration::from_secs(spec.restart_sec),
+        };
+        service
+            .update_failure_actions(ServiceFailureActions {
+                reset_period: ServiceFailureResetPeriod::After(Duration::from_secs(24 * 3600)),
+                reboot_msg: None,
+                command: None,
+                actions: Some(vec![restart; 3]),
+            })
+            .map_err(service_error)?;
+        // 以非零退出码停止也按失败处理，不只是进程崩溃
+        service
+            .set_failure_actions_on_non_crash_failures(true)
+            .map_err(service_error)
+    }
+
+    /// 停止并删除服务
+    pub fn uninstall(name: &str) -> Result<()> {
+        let manager = ServiceManager::local_computer(None::<&str>, ServiceManagerAccess::CONNECT)
+            .map_err(service_error)?;
+        let service = manager
+            .open_service(
+                name,
+                ServiceAccess::QUERY_STATUS | ServiceAccess::STOP | ServiceAccess::DELETE,
+            )
+            .map_err(|_| SyncError::App(format!("服务 {name} 未安装")))?;
+        // 标记删除后，服务停止时才真正删除
+        service.delete().map_err(service_error)?;
+        if service.query_status().map_err(service_error)?.current_state != ServiceState::Stopped {
+            service.stop().map_err(service_error)?;
+        }
+        Ok(())
+    }
+
+    /// 连接服务控制管理器，阻塞到服务停止
+    pub fn dispatch(spec: ServiceSpec) -> Result<()> {
+        let name = spec.name.clone();
+        let _ = SPEC.set(spec);
+        service_dispatcher::start(name, ffi_service_main).map_err(service_error)
+    }
+
+    fn service_main(_arguments: Vec<OsString>) {
+        let Some(spec) = SPEC.get() else {
+            return;
+        };
+        let stop = StopSignal::new();
+        let handler_stop = stop.clone();
+        let handler = move |control| match control {
+            ServiceControl::Stop | ServiceControl::Shutdown => {
+                handler_stop.stop();
+                ServiceControlHandlerResult::NoError
+            }
+            ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+            _ => ServiceControlHandlerResult::NotImplemented,
+        };
+        let Ok(status) = service_control_handler::register(&spec.name, handler) else {
+            return;
+        };
+        let report = |current_state, controls_accepted, exit_code| {
+            let _ = status.set_service_status(ServiceStatus {
+                service_type: ServiceType::OWN_PROCESS,
+                current_state,
+                controls_accepted,
+                exit_code,
+                checkpoint: 0,
+                wait_hint: Duration::default(),
+                process_id: None,
+            });
+        };
+
+        report(
+            ServiceState::Running,
+            ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+            ServiceExitCode::NO_ERROR,
+        );
+        let exit_code = match run_service(spec, &stop) {
+            Ok(_) => ServiceExitCode::NO_ERROR,
+            Err(_) => ServiceExitCode::ServiceSpecific(1),
+        };
+        report(
+            ServiceState::Stopped,
+            ServiceControlAccept::empty(),
+            exit_code,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_systemd_unit() {
+        let spec = ServiceSpec::new(
+            "svn2git",
+            PathBuf::from("/usr/local/bin/svn2git"),
+            PathBuf::from("/srv/mirror"),
+        );
+        let unit = spec.systemd_unit();
+
+        assert!(unit.contains("ExecStart=/usr/local/bin/svn2git watch\n"));
+        assert!(unit.contains("WorkingDirectory=/srv/mirror\n"));
+        assert!(unit.contains("Restart=on-failure\n"));
+        assert!(unit.contains("RestartSec=30\n"));
+        assert!(unit.contains("StandardOutput=append:/srv/mirror/svn2git.log\n"));
+        // 用户服务管理器看不到系统的 network-online.target
+        assert!(!unit.contains("network-online.target"), "{unit}");
+    }
+
+    #[test]
+    fn test_windows_arguments_should_use_absolute_paths() {
+        let spec = ServiceSpec::new(
+            "mirror",
+            PathBuf::from(r"C:\tools\svn2git.exe"),
+            PathBuf::from(r"D:\mirror"),
+        )
+        .with_interval(Some(60))
+        .with_log_path(Some(PathBuf::from("sync.log")));
+
+        assert_eq!(
+            spec.windows_arguments(),
+            [
+                "service",
+                "run",
+                "--windows-service",
+                "mirror",
+                "--working-dir",
+                r"D:\mirror",
+                "--log",
+                &PathBuf::from(r"D:\mirror")
+                    .join("sync.log")
+                    .to_string_lossy(),
+                "--interval",
+                "60",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_systemd_unit_should_quote_exec_start() {
+        let spec = ServiceSpec::new(
+            "svn2git",
+            PathBuf::from("/opt/my tools/svn2git"),
+            PathBuf::from("/srv/100% mirror"),
+        )
+        .with_interval(Some(60))
+        .with_log_path(Some(PathBuf::from("logs/sync.log")));
+        let unit = spec.systemd_unit();
+
+        assert!(
+            unit.contains("ExecStart=\"/opt/my tools/svn2git\" watch --interval 60\n"),
+            "{unit}"
+        );
+        assert!(
+            unit.contains("WorkingDirectory=/srv/100%% mirror\n"),
+            "{unit}"
+        );
+        assert!(
+            unit.contains("StandardError=append:/srv/100%% mirror/logs/sync.log\n"),
+            "{unit}"
+        );
+        assert_eq!(systemd_quote(r#"a"b\c"#), r#""a\"b\\c""#);
+        assert_eq!(systemd_quote("$HOME"), "$$HOME");
+        assert_eq!(systemd_quote(""), r#""""#);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_service_should_restart_until_success_and_log_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut spec = ServiceSpec::new("svn2git", PathBuf::from("sh"), dir.path().to_path_buf());
+        // 第一次启动失败，第二次成功
+        spec.args = vec![
+            "-c".into(),
+            "if [ -e started ]; then echo ok; else touch started; echo boom >&2; exit 3; fi".into(),
+        ];
+        spec.restart_sec = 0;
+
+        assert_eq!(run_service(&spec, &StopSignal::new()).unwrap(), 2);
+        let log = fs::read_to_string(dir.path().join("svn2git.log")).unwrap();
+        assert!(log.contains("boom\n"), "{log}");
+        assert!(log.contains("异常退出"), "{log}");
+        assert!(log.contains("ok\n"), "{log}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_service_should_terminate_running_child_when_stopped() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut spec = ServiceSpec::new("svn2git", PathBuf::from("sh"), dir.path().to_path_buf());
+        spec.args = vec!["-c".into(), "exec sleep 30".into()];
+
+        let stop = StopSignal::new();
+        let stopper = stop.clone();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(300));
+            stopper.stop();
+        });
+        let started = Instant::now();
+        assert_eq!(run_service(&spec, &stop).unwrap(), 1);
+        assert!(started.elapsed() < Duration::from_secs(10));
+        handle.join().unwrap();
+
+        let log = fs::read_to_string(dir.path().join("svn2git.log")).unwrap();
+        assert!(log.contains("收到停止请求"), "{log}");
+        assert!(!log.contains("强制结束"), "{log}");
+    }
+}
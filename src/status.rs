@@ -0,0 +1,230 @@
+//! 同步对状态：只读查看待同步版本数和两侧工作目录是否干净
+
+use std::{fmt::Display, path::PathBuf};
+
+use chrono::{DateTime, Local, Utc};
+
+use crate::{
+    config::HistoryRecord,
+    depth::SparseScope,
+    error::Result,
+    ops::{GitOperations, SvnLogQuery, SvnOperations},
+};
+
+/// 同步对的状态
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairStatus {
+    /// SVN 工作副本目录
+    pub svn_dir: PathBuf,
+    /// Git 仓库目录
+    pub git_dir: PathBuf,
+    /// SVN 工作副本当前版本
+    pub svn_revision: String,
+    /// 等待同步的 SVN 版本
+    pub pending_revisions: Vec<String>,
+    /// SVN 工作副本中有本地修改的路径
+    pub svn_modified: Vec<String>,
+    /// Git 工作目录是否干净
+    pub git_clean: bool,
+    /// Git 最新提交（`git log --oneline` 的第一行），还没有提交时为 `None`
+    pub git_last_commit: Option<String>,
+    /// Git HEAD 是否与记录的最后同步提交不同
+    pub git_head_moved: bool,
+    /// 最后一次成功同步的 SVN 版本
+    pub last_synced_rev: Option<String>,
+    /// 最后一次成功同步的时间
+    pub last_synced_at: Option<DateTime<Utc>>,
+    /// 稀疏工作副本的检出范围，完整检出时为 `None`
+    pub sparse_scope: Option<SparseScope>,
+}
+
+impl PairStatus {
+    /// 两侧都没有本地修改，也没有待同步的版本
+    pub fn is_up_to_date(&self) -> bool {
+        self.pending_revisions.is_empty()
+            && self.svn_modified.is_empty()
+            && self.git_clean
+            && !self.git_head_moved
+    }
+}
+
+impl Display for PairStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "SVN 目录：{}", self.svn_dir.display())?;
+        writeln!(f, "Git 目录：{}", self.git_dir.display())?;
+        writeln!(f, "SVN 工作副本版本：r{}", self.svn_revision)?;
+        if let Some(scope) = &self.sparse_scope {
+            writeln!(f, "检出范围：稀疏检出（{scope}）")?;
+        }
+
+        match &self.last_synced_rev {
+            Some(rev) => write!(f, "上次同步：r{rev}")?,
+            None => write!(f, "上次同步：尚未同步")?,
+        }
+        if let Some(at) = self.last_synced_at {
+            write!(
+                f,
+                "（{}）",
+                at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S")
+            )?;
+        }
+        writeln!(f)?;
+
+        match (
+            self.pending_revisions.first(),
+            self.pending_revisions.last(),
+        ) {
+            (Some(first), Some(last)) => writeln!(
+                f,
+                "待同步：{} 个版本（r{first} - r{last}）",
+                self.pending_revisions.len()
+            )?,
+            _ => writeln!(f, "待同步：无")?,
+        }
+
+        if self.svn_modified.is_empty() {
+            writeln!(f, "SVN 工作副本：干净")?;
+        } else {
+            writeln!(
+                f,
+                "SVN 工作副本：{} 个路径有本地修改",
+                self.svn_modified.len()
+            )?;
+            for path in &self.svn_modified {
+                writeln!(f, "    {path}")?;
+            }
+        }
+
+        writeln!(
+            f,
+            "Git 工作目录：{}",
+            if self.git_clean {
+                "干净"
+            } else {
+                "有未提交的修改"
+            }
+        )?;
+        match &self.git_last_commit {
+            Some(commit) => write!(f, "Git 最新提交：{commit}")?,
+            None => write!(f, "Git 最新提交：无")?,
+        }
+        if self.git_head_moved {
+            write!(
+                f,
+                "\n警告：Git HEAD 与上次同步的提交不同，可能有同步之外的提交"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// 查看同步对的状态
+///
+/// 执行 `svn info`（含 `svn info -R` 读取检出范围）、`svn status`、`svn log` 和 `git status`、`git log`，
+/// 只读操作，不会修改 SVN 工作副本、Git 仓库或历史记录。
+///
+/// # 参数
+///
+/// * `record`: 同步对
+/// * `svn_ops`: SVN操作实现
+/// * `git_ops`: Git操作实现
+///
+/// # 返回
+///
+/// 同步对的状态
+pub fn pair_status(
+    record: &HistoryRecord,
+    svn_ops: &dyn SvnOperations,
+    git_ops: &dyn GitOperations,
+) -> Result<PairStatus> {
+    let (svn_dir, git_dir) = (record.svn_path(), record.git_path());
+    let info = svn_ops.info(svn_dir)?;
+    let query = SvnLogQuery {
+        after_rev: record.last_synced_rev().map(str::to_string),
+        ..Default::default()
+    };
+    let pending_revisions = svn_ops
+        .get_logs(svn_dir, &query)?
+        .into_iter()
+        .map(|log| log.version)
+        .collect();
+    let svn_modified = svn_ops.modified_paths(svn_dir)?;
+    let sparse_scope = Some(svn_ops.depth_scope(svn_dir)?).filter(|scope| !scope.is_full());
+
+    // Git 仓库还没有初始化或没有提交时不算错误
+    let git_clean = git_ops.is_clean(git_dir).unwrap_or(true);
+    let git_last_commit = git_ops
+        .log(git_dir, Some(1))
+        .ok()
+        .and_then(|log| log.lines().next().map(str::to_string))
+        .filter(|line| !line.is_empty());
+    let git_head_moved = match (record.last_git_hash(), git_ops.rev_parse_head(git_dir)) {
+        (Some(synced), Ok(head)) => synced != head,
+        _ => false,
+    };
+
+    Ok(PairStatus {
+        svn_dir: svn_dir.clone(),
+        git_dir: git_dir.clone(),
+        svn_revision: info.revision,
+        pending_revisions,
+        svn_modified,
+        git_clean,
+        git_last_commit,
+        git_head_moved,
+        last_synced_rev: record.last_synced_rev().map(str::to_string),
+        last_synced_at: record.last_synced_at(),
+        sparse_scope,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::{MockGitOperations, MockSvnOperations};
+
+    #[test]
+    fn test_pair_status_should_report_pending_and_dirty_state() {
+        let svn_dir = PathBuf::from("/status/svn");
+        let git_dir = PathBuf::from("/status/git");
+
+        let svn_ops = MockSvnOperations::new();
+        for rev in ["1", "2", "3"] {
+            svn_ops.add_log_to_mock(&svn_dir, rev, "提交");
+        }
+        let git_ops = MockGitOperations::new();
+        git_ops.init(&git_dir).unwrap();
+        git_ops.add_file_to_mock(&git_dir, "a.txt").unwrap();
+        git_ops.add_all(&git_dir).unwrap();
+        git_ops.commit(&git_dir, "SVN: 提交").unwrap();
+        let head = git_ops.rev_parse_head(&git_dir).unwrap();
+
+        let mut record = HistoryRecord::new(1, svn_dir.clone(), git_dir.clone());
+        record.set_sync_state("1", &head);
+        let status = pair_status(&record, &svn_ops, &git_ops).unwrap();
+        assert_eq!(status.pending_revisions, ["2", "3"]);
+        assert!(status.svn_modified.is_empty());
+        assert!(status.git_clean);
+        assert!(!status.git_head_moved);
+        assert!(status.last_synced_at.is_some());
+        assert!(!status.is_up_to_date());
+        let text = status.to_string();
+        assert!(text.contains("待同步：2 个版本（r2 - r3）"), "{text}");
+        assert!(
+            text.contains(&format!("Git 最新提交：{head} SVN: 提交")),
+            "{text}"
+        );
+
+        // 只读：不更新工作副本也不修改历史记录
+        assert!(svn_ops.updated_revisions().is_empty());
+        assert_eq!(record.last_synced_rev(), Some("1"));
+
+        svn_ops.set_modified_paths(&["src/a.rs"]);
+        record.set_sync_state("3", "0000000");
+        let status = pair_status(&record, &svn_ops, &git_ops).unwrap();
+        assert!(status.pending_revisions.is_empty());
+        assert_eq!(status.svn_modified, ["src/a.rs"]);
+        assert!(status.git_head_moved);
+        assert!(status.to_string().contains("    src/a.rs"));
+    }
+}
@@ -1,29 +1,72 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, Utc};
+
 use crate::{
-    config::{FileStorage, HistoryManager, SyncConfig},
+    authors::{SVN_NO_AUTHOR, resolve_author},
+    bandwidth::{DEFAULT_LOG_BATCH, SLOW_UPDATE, is_remote_url},
+    clock::{Clock, system_clock},
+    command::CommitArgs,
+    config::{
+        BackoffPolicy, ConflictPolicy, DateOrderPolicy, DirtyTreePolicy, EmptyRevisionPolicy,
+        ErrorPolicy, FileStorage, HistoryManager, MirrorMarker, PlannedRevision, PushPolicy,
+        RepoIdentity, STATE_REF, SyncCheckpoint, SyncConfig, SyncPlan, SyncStartState,
+    },
+    depth::SparseScope,
     error::{Result, SyncError},
-    interactor::{UserInteractor, confirm_sync_with_interactor},
-    ops::{GitOperations, get_svn_logs, git_commit_with_ops, svn_update_to_rev},
+    interactor::{ConfirmThreshold, UserInteractor, confirm_sync_with_interactor},
+    layout::{LayoutRevision, LayoutTarget, SvnLayout, detect_layout},
+    lock::SyncLock,
+    log_stream::{DEFAULT_LOG_WINDOW, PendingLogs, SvnLogStream},
+    manifest::write_manifest,
+    message::{
+        DEFAULT_MAX_AUTHOR_CHARS, DEFAULT_MAX_MESSAGE_CHARS, DEFAULT_MESSAGE_PREFIX, MessageLimits,
+        format_commit_message, rewrite_message, strip_control_chars, truncate_field,
+    },
+    mirror::{MirrorStats, mirror_tree},
+    notify::{Notifier, NotifyTarget, SyncReport},
+    ops::{
+        CommitMetadata, GitOperations, GitSignature, RevisionDiff, SvnChangedPath, SvnInfo, SvnLog,
+        SvnLogQuery, SvnOperations, SvnProperties, gitattributes_pattern,
+        update_git_exclude_section, update_gitignore_section,
+    },
+    output::Reporter,
+    progress::ProgressFile,
+    properties::{
+        SVN_EOL_STYLE, SVN_EXECUTABLE, SVN_IGNORE, SVN_KEYWORDS, SVN_MIME_TYPE, SVN_NEEDS_LOCK,
+        git_relative_prefix, strip_keywords, svn_executables, sync_eol_styles, sync_ignores,
+        sync_mime_types, sync_needs_lock,
+    },
+    revisions::RevisionSet,
+    sync_options::{CommitPolicy, PublishPolicy, RevisionPolicy, SyncFiles, SyncHooks},
+    telemetry::{Span, trace_span},
 };
 
-/// SVN操作抽象接口
-#[cfg_attr(test, mockall::automock)]
-pub trait SvnOperations {
-    fn get_logs(&self, path: &std::path::Path) -> Result<Vec<crate::ops::SvnLog>>;
-    fn update_to_rev(&self, path: &std::path::Path, rev: &str) -> Result<()>;
-}
+/// 保存 SVN 元数据的 Git note 引用
+pub const SVN_NOTES_REF: &str = "refs/notes/svn";
 
-/// 真实SVN操作实现
-pub struct RealSvnOperations;
+/// 同步完成后推送的 Git 远程仓库名
+pub const GIT_REMOTE: &str = "origin";
 
-impl SvnOperations for RealSvnOperations {
-    fn get_logs(&self, path: &std::path::Path) -> Result<Vec<crate::ops::SvnLog>> {
-        get_svn_logs(&path.to_path_buf())
-    }
+/// 忽略 SVN 管理目录的 `.gitignore` 规则（不锚定，旧版本每层目录都有 `.svn`）
+const SVN_METADATA_IGNORE: &str = ".svn/";
 
-    fn update_to_rev(&self, path: &std::path::Path, rev: &str) -> Result<()> {
-        svn_update_to_rev(&path.to_path_buf(), rev)
-    }
-}
+/// 同步前暂存未提交更改时的 stash 说明
+const DIRTY_TREE_STASH: &str = "svn2git: 同步前暂存的更改";
+
+/// 自动初始化 Git 仓库时设置的提交者
+const INIT_USER_NAME: &str = "svn2git";
+const INIT_USER_EMAIL: &str = "svn2git@localhost";
+
+/// 自动初始化 Git 仓库时初始提交说明的开头
+const INIT_COMMIT_PREFIX: &str = "Initial state (SVN r";
 
 /// 同步运行选项（防事故）
 #[derive(Debug, Clone, Default)]
@@ -32,6 +75,16 @@ pub struct SyncRunOptions {
     pub dry_run: bool,
     /// 最多同步多少条日志（按SVN返回顺序）
     pub limit: Option<usize>,
+    /// 只同步该日期（含）之后的提交
+    pub since: Option<NaiveDate>,
+    /// 只同步该日期之前的提交（不含当天）
+    pub until: Option<NaiveDate>,
+    /// 只同步这些版本，未选择的版本的更改随之后第一个选择的版本提交
+    pub only_revs: Option<RevisionSet>,
+    /// 同步前交互选择要同步的版本，未选择的版本与 `only_revs` 相同，更改随之后第一个选择的版本提交
+    pub select: bool,
+    /// 确认同步前显示每个版本的变更摘要（`svn diff -c REV --summarize`）
+    pub preview_diff: bool,
 }
 
 /// 同步工具
@@ -41,11 +94,39 @@ pub struct SyncTool<S: FileStorage> {
     interactor: Box<dyn UserInteractor>,
     git_operations: Box<dyn GitOperations>,
     svn_operations: Box<dyn SvnOperations>,
+    revisions: RevisionPolicy,
+    commits: CommitPolicy,
+    publish: PublishPolicy,
+    files: SyncFiles,
+    hooks: SyncHooks,
+    svn_url: Option<String>,
+    log_batch: usize,
+    log_window: usize,
+    /// 工作副本的 URL 和仓库 UUID，检查目录时记录，用于 git-svn-id
+    svn_location: Option<(String, String)>,
+    auto_upgrade: bool,
+    layout: Option<SvnLayout>,
+    layout_target: Option<LayoutTarget>,
+    /// 稀疏工作副本的检出范围和工作副本在仓库中的路径（不含开头的 `/`）
+    sparse_scope: Option<(SparseScope, String)>,
+    repository_uuid: Option<String>,
+    last_commit_date: Option<DateTime<FixedOffset>>,
+    backoff: Option<BackoffPolicy>,
+    watch: bool,
+    stashed: bool,
+    rollback_on_failure: bool,
+    allow_unrelated_history: bool,
+    sync_start: Option<SyncStartState>,
+    /// 本次运行中已保存进度的版本数，失败时记录到同步结果
+    saved_revisions: usize,
+    clock: Arc<dyn Clock>,
 }
 
 impl<S: FileStorage> SyncTool<S> {
     /// 创建一个新的同步工具
     ///
+    /// SVN操作实现由 `config.svn_provider` 决定
+    ///
     /// # 参数
     ///
     /// * `config` - 同步配置
@@ -58,16 +139,19 @@ impl<S: FileStorage> SyncTool<S> {
         interactor: Box<dyn UserInteractor>,
         git_operations: Box<dyn GitOperations>,
     ) -> Self {
-        Self::with_svn_operations(
-            config,
-            history,
-            interactor,
-            git_operations,
-            Box::new(RealSvnOperations),
-        )
+        let svn_operations = Box::new(config.create_svn_operations());
+        Self::with_svn_operations(config, history, interactor, git_operations, svn_operations)
     }
 
     /// 创建自定义SVN实现的同步工具
+    ///
+    /// # 参数
+    ///
+    /// * `config` - 同步配置
+    /// * `history` - 历史记录管理器
+    /// * `interactor` - 用户交互器
+    /// * `git_operations` - Git操作实现
+    /// * `svn_operations` - SVN操作实现
     pub fn with_svn_operations(
         config: SyncConfig,
         history: HistoryManager<S>,
@@ -81,9 +165,298 @@ impl<S: FileStorage> SyncTool<S> {
             interactor,
             git_operations,
             svn_operations,
+            revisions: RevisionPolicy::default(),
+            commits: CommitPolicy::default(),
+            publish: PublishPolicy::default(),
+            files: SyncFiles::default(),
+            hooks: SyncHooks::default(),
+            svn_url: None,
+            log_batch: DEFAULT_LOG_BATCH,
+            log_window: DEFAULT_LOG_WINDOW,
+            svn_location: None,
+            auto_upgrade: false,
+            layout: None,
+            layout_target: None,
+            sparse_scope: None,
+            repository_uuid: None,
+            last_commit_date: None,
+            backoff: None,
+            watch: false,
+            stashed: false,
+            rollback_on_failure: false,
+            allow_unrelated_history: false,
+            sync_start: None,
+            saved_revisions: 0,
+            clock: system_clock(),
         }
     }
 
+    /// 设置版本的选取方式
+    ///
+    /// # 参数
+    ///
+    /// * `policy` - 变更列表、跳过和合并版本的规则、分支映射和确认阈值
+    pub fn with_revision_policy(mut self, policy: RevisionPolicy) -> Self {
+        self.revisions = policy;
+        self
+    }
+
+    /// 设置 Git 提交的生成方式
+    ///
+    /// # 参数
+    ///
+    /// * `policy` - 提交说明、作者、提交时间和附加记录的设置
+    pub fn with_commit_policy(mut self, policy: CommitPolicy) -> Self {
+        self.commits = policy;
+        self
+    }
+
+    /// 设置同步完成后的发布方式
+    ///
+    /// # 参数
+    ///
+    /// * `policy` - 远程仓库地址、bundle 文件和是否推送
+    pub fn with_publish_policy(mut self, policy: PublishPolicy) -> Self {
+        self.publish = policy;
+        self
+    }
+
+    /// 设置 SVN 仓库 URL
+    ///
+    /// 同步开始时如果工作副本目录不存在或为空，先从该 URL 检出版本 0
+    ///
+    /// # 参数
+    ///
+    /// * `url` - SVN 仓库 URL，`None` 表示要求工作副本已存在
+    pub fn with_svn_url(mut self, url: Option<String>) -> Self {
+        self.svn_url = url;
+        self
+    }
+
+    /// 设置远程 SVN 仓库每次请求的最大日志条数
+    ///
+    /// 工作副本指向远程仓库时分批获取日志，并根据每批用时自动调整批大小
+    ///
+    /// # 参数
+    ///
+    /// * `size` - 最大批大小，`None` 表示使用 [`DEFAULT_LOG_BATCH`]
+    pub fn with_log_batch(mut self, size: Option<NonZeroUsize>) -> Self {
+        self.log_batch = size.map_or(DEFAULT_LOG_BATCH, NonZeroUsize::get);
+        self
+    }
+
+    /// 设置同步时在内存中最多保留的完整日志条数
+    ///
+    /// 待同步的版本超过该值时，确认同步只列出前这么多条，之后的日志在同步时分窗口获取
+    ///
+    /// # 参数
+    ///
+    /// * `size` - 窗口大小，默认 [`DEFAULT_LOG_WINDOW`]
+    pub fn with_log_window(mut self, size: usize) -> Self {
+        self.log_window = size.max(1);
+        self
+    }
+
+    /// 设置同步锁文件
+    ///
+    /// 设置后同步开始前先获取锁，防止定时任务重叠时两个进程同时修改工作副本
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 锁文件路径，`None` 表示不加锁
+    pub fn with_lock_file(mut self, path: Option<PathBuf>) -> Self {
+        self.files.lock_file = path;
+        self
+    }
+
+    /// 设置进度文件
+    ///
+    /// 同步期间把当前版本、完成数量、预计剩余时间和最近的错误写入该 JSON 文件，供外部程序轮询
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 进度文件路径，`None` 表示不写入
+    pub fn with_progress_file(mut self, path: Option<PathBuf>) -> Self {
+        self.files.progress = ProgressFile::new(path);
+        self.files.progress.set_clock(self.clock.clone());
+        self
+    }
+
+    /// 设置配置文件等工具自身的状态文件
+    ///
+    /// 其中位于 Git 目录中的文件（包括锁文件和进度文件）会加入 `.git/info/exclude`，不会被提交到仓库
+    ///
+    /// # 参数
+    ///
+    /// * `files` - 状态文件路径
+    pub fn with_state_files(mut self, files: Vec<PathBuf>) -> Self {
+        self.files.state_files = files;
+        self
+    }
+
+    /// 设置时钟
+    ///
+    /// 历史记录、检查点、同步计划和进度文件中的时间都从该时钟获取，
+    /// 测试中注入 [`FixedClock`](crate::FixedClock) 使时间相关的行为可重复
+    ///
+    /// # 参数
+    ///
+    /// * `clock` - 时钟
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.history.set_clock(clock.clone());
+        self.files.progress.set_clock(clock.clone());
+        self.clock = clock;
+        self
+    }
+
+    /// 设置同步失败时是否回滚
+    ///
+    /// 开启后同步失败时把 Git 分支重置到同步开始前的 HEAD，把 SVN 工作副本更新回同步开始前的版本，
+    /// 并恢复同步进度，不留下导入了一半的状态
+    ///
+    /// # 参数
+    ///
+    /// * `enabled` - 是否回滚
+    pub fn with_rollback_on_failure(mut self, enabled: bool) -> Self {
+        self.rollback_on_failure = enabled;
+        self
+    }
+
+    /// 设置 Git 仓库已有与 SVN 无关的提交历史时是否仍然同步
+    ///
+    /// # 参数
+    ///
+    /// * `enabled` - 是否允许
+    pub fn with_allow_unrelated_history(mut self, enabled: bool) -> Self {
+        self.allow_unrelated_history = enabled;
+        self
+    }
+
+    /// 注册同步通知
+    ///
+    /// 可以注册多个，同步开始、完成和失败时按注册顺序通知（dry-run 不通知）
+    ///
+    /// # 参数
+    ///
+    /// * `notifier` - 通知实现
+    pub fn with_notifier(mut self, notifier: Box<dyn Notifier>) -> Self {
+        self.hooks.notifiers.push(notifier);
+        self
+    }
+
+    /// 设置连续失败时的退避策略，用于持续运行的监视模式
+    ///
+    /// 设置后每次失败都记录到同步对的健康状态，退避期间的同步直接跳过；
+    /// 失败通知只在连续失败达到告警阈值时发送一次，避免每轮失败都发送告警
+    ///
+    /// # 参数
+    ///
+    /// * `policy` - 退避策略，`None` 表示每次都同步并通知
+    pub fn with_backoff(mut self, policy: Option<BackoffPolicy>) -> Self {
+        self.backoff = policy;
+        self
+    }
+
+    /// 设置是否为监视模式的一轮同步
+    ///
+    /// 监视模式下没有新版本时直接结束本轮：不加锁、不发送通知，也不推送或重写镜像标记
+    ///
+    /// # 参数
+    ///
+    /// * `enabled` - 是否为监视模式
+    pub fn with_watch(mut self, enabled: bool) -> Self {
+        self.watch = enabled;
+        self
+    }
+
+    /// 设置是否输出同步过程中的细节
+    ///
+    /// 简要模式下每个版本只输出一行结果，另外保留警告、错误和最终汇总
+    ///
+    /// # 参数
+    ///
+    /// * `verbose` - 是否输出细节
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.hooks.reporter = Reporter::new(verbose);
+        self
+    }
+
+    /// 设置锁被占用时是否等待
+    ///
+    /// # 参数
+    ///
+    /// * `enabled` - 是否等待另一个同步结束，否则直接返回错误
+    pub fn with_lock_wait(mut self, enabled: bool) -> Self {
+        self.files.wait_for_lock = enabled;
+        self
+    }
+
+    /// 设置工作副本格式过旧时是否自动执行 `svn upgrade`
+    ///
+    /// # 参数
+    ///
+    /// * `enabled` - 是否自动升级
+    pub fn with_auto_upgrade(mut self, enabled: bool) -> Self {
+        self.auto_upgrade = enabled;
+        self
+    }
+
+    /// 按命令行的提交参数设置同步工具
+    ///
+    /// # 参数
+    ///
+    /// * `args` - 同步和恢复共用的提交参数
+    pub fn with_commit_args(self, args: CommitArgs) -> Self {
+        let notifiers: Vec<_> = args
+            .notify
+            .iter()
+            .map(NotifyTarget::create_notifier)
+            .collect();
+        let mut tool = self
+            .with_revision_policy(
+                RevisionPolicy {
+                    changelist: args.changelist,
+                    skip_authors: args.skip_authors,
+                    merge_authors: args.merge_authors,
+                    layout: args.layout,
+                    confirm_threshold: ConfirmThreshold {
+                        revisions: args.confirm_above_revs,
+                        changed_paths: args.confirm_above_paths,
+                    },
+                    ..Default::default()
+                }
+                .with_skip_paths(args.skip_paths),
+            )
+            .with_commit_policy(CommitPolicy {
+                manifest_dir: args.manifest_dir,
+                git_notes: args.git_notes,
+                export: args.export,
+                list_changed_files: args.list_changed_files,
+                git_svn_id: args.git_svn_id,
+                git_tags: args.git_tags,
+                message_limits: MessageLimits {
+                    subject_width: args.subject_width,
+                    body_width: args.body_width,
+                    max_message_chars: Some(
+                        args.max_message_chars.unwrap_or(DEFAULT_MAX_MESSAGE_CHARS),
+                    ),
+                    max_author_chars: Some(
+                        args.max_author_chars.unwrap_or(DEFAULT_MAX_AUTHOR_CHARS),
+                    ),
+                },
+                strip_keywords: args.strip_keywords,
+                preserve_dates: args.preserve_dates,
+                date_timezone: args.date_timezone,
+                preserve_authors: args.preserve_authors,
+                service_accounts: args.service_accounts,
+                author_rules: args.author_fallbacks,
+            })
+            .with_auto_upgrade(args.auto_upgrade)
+            .with_lock_wait(args.wait);
+        tool.hooks.notifiers.extend(notifiers);
+        tool
+    }
+
     /// 创建使用默认真实Git实现的同步工具
     ///
     /// 这是一个便捷方法，创建使用RealGitOperations的SyncTool
@@ -104,475 +477,5029 @@ impl<S: FileStorage> SyncTool<S> {
     }
 
     /// 执行同步
-    pub fn run(&self) -> Result<()> {
+    pub fn run(&mut self) -> Result<()> {
         self.run_with_options(&SyncRunOptions::default())
     }
 
     /// 按选项执行同步
-    pub fn run_with_options(&self, options: &SyncRunOptions) -> Result<()> {
-        let mut svn_logs = self.svn_operations.get_logs(&self.config.svn_dir)?;
-        svn_logs = limit_logs(svn_logs, options.limit);
+    ///
+    /// 如果历史记录中保存了上次同步到的版本，则从该版本之后继续同步，
+    /// 否则从 SVN 工作副本的 BASE 之后开始。
+    pub fn run_with_options(&mut self, options: &SyncRunOptions) -> Result<()> {
+        if let (Some(since), Some(until)) = (options.since, options.until)
+            && since >= until
+        {
+            return Err(SyncError::App(format!(
+                "--since ({since}) 必须早于 --until ({until})"
+            )));
+        }
+        if options.dry_run {
+            return self.sync_pending(options).map(|_| ());
+        }
+        if self.skip_for_backoff() {
+            return Ok(());
+        }
+        if self.is_idle() {
+            self.hooks.reporter.detail("没有新的 SVN 版本");
+            return Ok(());
+        }
 
-        if svn_logs.is_empty() {
+        let _lock = self.acquire_lock()?;
+        let span = self.pair_span("svn2git.sync");
+        let started = Instant::now();
+        self.notify(|notifier, config| notifier.notify_started(config));
+        let result = self.sync_pending(options);
+        let result = self.rollback_failed_sync(result);
+        let result = self.restore_stash(result);
+        record_span_result(&span, &result);
+        let alert = self.record_run_outcome(&result, started);
+        self.notify_result(result, started, alert)
+    }
+
+    /// 监视模式下本轮是否无事可做
+    ///
+    /// 上次同步成功、没有未完成的同步且 SVN 没有新版本时为 `true`；
+    /// 上次失败（如推送失败）时仍执行完整流程，以便重试推送等收尾步骤
+    fn is_idle(&self) -> bool {
+        if !self.watch {
+            return false;
+        }
+        let Some(record) = self
+            .history
+            .find_record(&self.config.svn_dir, &self.config.git_dir)
+        else {
+            return false;
+        };
+        if record.has_checkpoint() || record.last_run().is_some_and(|run| !run.success) {
+            return false;
+        }
+        if !matches!(self.checkout_url(), Ok(None)) {
+            return false;
+        }
+        // 获取失败时交给完整流程报告错误
+        self.fetch_pending_logs(SvnLogQuery {
+            limit: Some(1),
+            ..Default::default()
+        })
+        .next()
+        .is_none()
+    }
+
+    /// 设置了退避策略且同步对仍在退避期内时跳过本次同步
+    fn skip_for_backoff(&self) -> bool {
+        if self.backoff.is_none() {
+            return false;
+        }
+        let Some(record) = self
+            .history
+            .find_record(&self.config.svn_dir, &self.config.git_dir)
+        else {
+            return false;
+        };
+        let Some(until) = record.health().backing_off_until(self.history.now()) else {
+            return false;
+        };
+        println!(
+            "{} 已连续失败 {} 次，{} 之前不再重试，本轮跳过",
+            record.label(),
+            record.health().consecutive_failures(),
+            until.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S")
+        );
+        true
+    }
+
+    /// 计算待同步的版本并同步
+    ///
+    /// # 返回值
+    ///
+    /// 同步的版本数
+    fn sync_pending(&mut self, options: &SyncRunOptions) -> Result<usize> {
+        self.check_git_capabilities()?;
+        if let Some(url) = self.checkout_url()? {
+            if options.dry_run {
+                println!(
+                    "dry-run 模式：SVN 工作副本 {} 不存在，实际同步时会先从 {url} 检出",
+                    self.config.svn_dir.display()
+                );
+                return Ok(0);
+            }
+            self.svn_operations
+                .checkout(url, &self.config.svn_dir, "0")?;
+        }
+        if !options.dry_run {
+            self.validate_directories()?;
+        }
+        self.detect_sparse_scope()?;
+
+        // `--only-revs` 和交互选择需要看到全部待同步的版本，否则只获取 `--limit` 条，
+        // 并且只在内存中保留一个窗口的日志
+        let query = SvnLogQuery {
+            since: options.since,
+            until: options.until,
+            limit: options.limit.filter(|_| options.only_revs.is_none()),
+            ..Default::default()
+        };
+        let mut plan = Vec::new();
+        let mut pending = if options.only_revs.is_some() || options.select {
+            let mut svn_logs = self.fetch_pending_logs(query).collect::<Result<Vec<_>>>()?;
+            if let Some(only_revs) = &options.only_revs {
+                svn_logs = self.select_only_revs(svn_logs, only_revs)?;
+            }
+            svn_logs = limit_logs(svn_logs, options.limit);
+            plan.extend(svn_logs.iter().map(|log| self.planned_revision(log)));
+            PendingLogs::from_logs(svn_logs)
+        } else {
+            let query = self.pending_query(query);
+            PendingLogs::scan(
+                self.fetch_logs(query.clone()),
+                query,
+                self.log_window,
+                |log| plan.push(self.planned_revision(log)),
+            )?
+        };
+        let mut revisions: Vec<String> = plan.iter().map(|p| p.rev.clone()).collect();
+        self.update_plan(plan, options.dry_run)?;
+
+        if pending.is_empty() {
             println!("没有可同步的 SVN 日志");
-            return Ok(());
+            if !options.dry_run {
+                self.publish_history()?;
+            }
+            return Ok(0);
         }
 
         if options.dry_run {
             println!(
                 "dry-run 模式：共 {} 条日志，仅预览，不会执行 svn update 或 git commit",
-                svn_logs.len()
+                pending.len()
             );
-            for (idx, log) in svn_logs.iter().enumerate() {
+            let total = pending.len();
+            let mut idx = 0;
+            while let Some(log) = self.next_pending(&mut pending)? {
+                idx += 1;
                 println!(
-                    "[预览 {}/{}] r{} | {} | Git提交: {}",
-                    idx + 1,
-                    svn_logs.len(),
+                    "[预览 {idx}/{total}] r{} | {} | Git提交: {}",
                     log.version,
                     summarize_message(&log.message),
-                    build_git_commit_message(&log.message)
+                    build_git_commit_message(
+                        &self.rewritten_message(&log),
+                        &self.config.message_prefix
+                    )
                 );
             }
-            return Ok(());
+            return Ok(0);
         }
 
-        if !confirm_sync_with_interactor(&svn_logs, self.interactor.as_ref()) {
-            println!("同步已取消");
-            return Ok(());
+        if options.select {
+            let svn_logs = self.select_interactively(pending.into_logs())?;
+            if svn_logs.is_empty() {
+                println!("没有选择任何版本，同步已取消");
+                return Ok(0);
+            }
+            revisions = svn_logs.iter().map(|log| log.version.clone()).collect();
+            pending = PendingLogs::from_logs(svn_logs);
         }
-
-        for (idx, log) in svn_logs.iter().enumerate() {
+        if !self
+            .revisions
+            .confirm_threshold
+            .requires_confirmation_for(pending.len(), pending.changed_paths())
+        {
             println!(
-                "[{}/{}] 准备同步 SVN r{}：{}",
-                idx + 1,
-                svn_logs.len(),
-                log.version,
-                summarize_message(&log.message)
+                "待同步的 {} 个版本未超过确认阈值，自动开始同步",
+                pending.len()
             );
+        } else {
+            if !pending.is_loaded() {
+                println!(
+                    "待同步的版本共 {} 个，以下只列出前 {} 个",
+                    pending.len(),
+                    pending.loaded().len()
+                );
+            }
+            let svn_logs = pending.loaded();
+            let diffs = if options.preview_diff {
+                self.revision_diffs(svn_logs)
+            } else {
+                Vec::new()
+            };
+            if !confirm_sync_with_interactor(svn_logs, &diffs, self.interactor.as_ref()) {
+                println!("同步已取消");
+                return Ok(0);
+            }
+        }
 
-            self.svn_operations
-                .update_to_rev(&self.config.svn_dir, &log.version)
-                .map_err(|e| {
-                    SyncError::App(format!(
-                        "同步第 {} 条日志失败（SVN r{}）：{}",
-                        idx + 1,
-                        log.version,
-                        e
-                    ))
-                })?;
-            println!("[{}/{}] SVN 更新完成", idx + 1, svn_logs.len());
-
-            self.ensure_git_conflict_free().map_err(|e| {
-                SyncError::App(format!(
-                    "同步第 {} 条日志失败（SVN r{}）：{}",
-                    idx + 1,
-                    log.version,
-                    e
-                ))
-            })?;
+        let total = pending.len();
+        self.begin_checkpoint(revisions)?;
+        self.sync_logs(pending)?;
+        self.publish_history()?;
+        Ok(total)
+    }
 
-            git_commit_with_ops(
-                self.git_operations.as_ref(),
-                &self.config.git_dir,
-                &build_git_commit_message(&log.message),
-            )
-            .map_err(|e| {
-                SyncError::App(format!(
-                    "同步第 {} 条日志失败（SVN r{}）：{}",
-                    idx + 1,
-                    log.version,
-                    e
-                ))
-            })?;
-            println!(
-                "[{}/{}] Git 提交完成：{}",
-                idx + 1,
-                svn_logs.len(),
-                build_git_commit_message(&log.message)
-            );
+    /// 恢复上次中断的同步
+    ///
+    /// 只同步检查点中尚未完成的版本，用户已在上次同步时确认过，因此不再询问。
+    ///
+    /// # 返回值
+    ///
+    /// * `Err(SyncError)` - 该同步对没有未完成的同步，或同步失败
+    pub fn resume(&mut self) -> Result<()> {
+        let _lock = self.acquire_lock()?;
+        let span = self.pair_span("svn2git.resume");
+        let started = Instant::now();
+        self.notify(|notifier, config| notifier.notify_started(config));
+        let result = self.resume_pending();
+        let result = self.rollback_failed_sync(result);
+        let result = self.restore_stash(result);
+        record_span_result(&span, &result);
+        let alert = self.record_run_outcome(&result, started);
+        self.notify_result(result, started, alert)
+    }
+
+    /// 回滚上次未完成的同步
+    ///
+    /// 把 Git 分支重置到同步开始前的 HEAD，把 SVN 工作副本更新回同步开始前的版本，
+    /// 同步进度恢复到同步开始前并清除检查点
+    ///
+    /// # 返回值
+    ///
+    /// * `Err(SyncError)` - 该同步对没有可回滚的同步，或回滚失败
+    pub fn rollback(&mut self) -> Result<()> {
+        let _lock = self.acquire_lock()?;
+        let start = self
+            .history
+            .find_record(&self.config.svn_dir, &self.config.git_dir)
+            .and_then(|r| r.checkpoint())
+            .and_then(|c| c.start())
+            .cloned()
+            .ok_or_else(|| SyncError::App("没有可回滚的同步".into()))?;
+        self.rollback_to(&start)
+    }
+
+    /// 重试一个提交失败的版本
+    ///
+    /// 只同步这一个版本，不再询问确认。该版本必须有失败记录，并且是下一个待同步的版本。
+    /// 继续策略下失败版本之后的版本同步成功时，失败版本的更改已随该版本提交，
+    /// 同步时会清除其失败记录，这里提示无需重试。
+    /// 同步前先把 SVN 工作副本更新到上次同步的版本。
+    ///
+    /// # 参数
+    ///
+    /// * `rev` - 失败的 SVN 版本
+    pub fn retry(&mut self, rev: &str) -> Result<()> {
+        let _lock = self.acquire_lock()?;
+        let record = self
+            .history
+            .find_record(&self.config.svn_dir, &self.config.git_dir)
+            .ok_or_else(|| SyncError::App("没有找到该同步对的历史记录".into()))?;
+        if !record.failed_revisions().iter().any(|failed| failed == rev) {
+            return Err(SyncError::App(match record.folded_into(rev) {
+                Some(into_rev) => format!("r{rev} 的更改已随后续版本 r{into_rev} 提交，无需重试"),
+                None => format!("r{rev} 没有同步失败的记录"),
+            }));
         }
+        let last_synced = record.last_synced_rev().map(str::to_string);
+        self.check_git_capabilities()?;
+        self.validate_directories()?;
+        self.detect_sparse_scope()?;
 
-        self.history.save()
+        self.hooks.reporter.detail("正在获取 SVN 日志");
+        let logs = self.svn_operations.get_logs(
+            &self.config.svn_dir,
+            &SvnLogQuery {
+                after_rev: last_synced.clone(),
+                limit: Some(1),
+                ..Default::default()
+            },
+        )?;
+        let synced_after = |synced: &str| match (rev.parse::<u64>(), synced.parse::<u64>()) {
+            (Ok(rev), Ok(synced)) => rev <= synced,
+            _ => false,
+        };
+        match (logs.first(), last_synced.as_deref()) {
+            (Some(next), _) if next.version == rev => {}
+            (_, Some(synced)) if synced_after(synced) => {
+                return Err(SyncError::App(format!(
+                    "已经同步到 r{synced}，r{rev} 的更改已随后续版本提交，无法单独重试"
+                )));
+            }
+            (Some(next), _) => {
+                return Err(SyncError::App(format!(
+                    "r{rev} 之前还有未同步的版本 r{}，请先同步该版本",
+                    next.version
+                )));
+            }
+            (None, _) => return Err(SyncError::App(format!("SVN 中没有待同步的 r{rev}"))),
+        }
+
+        if let Some(synced) = &last_synced {
+            let current = self.svn_operations.info(&self.config.svn_dir)?.revision;
+            if &current != synced {
+                println!("SVN 工作副本位于 r{current}，先更新到上次同步的 r{synced}");
+                self.update_working_copy(synced)?;
+            }
+        }
+        println!("重试 SVN r{rev}");
+        self.sync_logs(PendingLogs::from_logs(logs))?;
+        println!("r{rev} 重试成功");
+        Ok(())
     }
 
-    fn ensure_git_conflict_free(&self) -> Result<()> {
-        let status = self.git_operations.status(&self.config.git_dir)?;
-        if has_conflict_entries(&status) {
-            return Err(SyncError::App(
-                "检测到 Git 冲突状态（如 UU/AA/DU），已停止后续同步".into(),
-            ));
+    /// 检查 Git 后端是否支持本次同步请求的功能，在获取日志之前报错
+    fn check_git_capabilities(&self) -> Result<()> {
+        let capabilities = self.git_operations.capabilities();
+        let allow_empty = self.config.policies.empty_revision == EmptyRevisionPolicy::AllowEmpty;
+        let required = [
+            (
+                self.commits.git_notes,
+                capabilities.notes,
+                "Git notes",
+                "--git-notes",
+            ),
+            (
+                self.commits.preserve_authors,
+                capabilities.author_override,
+                "指定提交作者",
+                "--preserve-authors",
+            ),
+            (
+                allow_empty,
+                capabilities.empty_commits,
+                "空提交",
+                "empty-revision 策略 allow-empty",
+            ),
+            (
+                self.publish.bundle.is_some(),
+                capabilities.bundle,
+                "生成 git bundle",
+                "--bundle",
+            ),
+            (
+                !self.config.env.is_empty(),
+                capabilities.command_env,
+                "附带环境变量",
+                "history env 设置的环境变量",
+            ),
+        ];
+        for (requested, supported, feature, option) in required {
+            if requested && !supported {
+                return Err(SyncError::App(format!(
+                    "当前 Git 后端不支持{feature}，无法使用 {option}"
+                )));
+            }
         }
         Ok(())
     }
-}
 
-fn summarize_message(message: &str) -> String {
-    let trimmed = message.trim();
-    if trimmed.is_empty() {
-        return "(空提交说明)".to_string();
+    /// 记录提交失败的版本，保存失败只输出警告
+    fn record_failed_revision(&mut self, rev: &str) {
+        let (svn_dir, git_dir) = (&self.config.svn_dir, &self.config.git_dir);
+        if self.history.find_record(svn_dir, git_dir).is_none() {
+            return;
+        }
+        self.history
+            .record_failed_revision(&self.config.svn_dir, &self.config.git_dir, rev);
+        if let Err(e) = self.history.save() {
+            eprintln!("警告：保存失败版本记录失败：{e}");
+        }
     }
 
-    let first_line = trimmed.lines().next().unwrap_or_default().trim();
-    const MAX_CHARS: usize = 80;
-    if first_line.chars().count() <= MAX_CHARS {
-        return first_line.to_string();
+    /// 失败版本的更改已随后续版本提交：清除失败记录，并把它们映射到该版本的提交
+    ///
+    /// # 参数
+    ///
+    /// * `revs` - 之前失败的版本
+    /// * `into_rev` - 同步成功的后续版本
+    fn fold_failed_revisions(&mut self, revs: &[String], into_rev: &str) {
+        if revs.is_empty() {
+            return;
+        }
+        let (svn_dir, git_dir) = (&self.config.svn_dir, &self.config.git_dir);
+        if self.history.find_record(svn_dir, git_dir).is_none() {
+            return;
+        }
+        for rev in revs {
+            self.history
+                .fold_failed_revision(svn_dir, git_dir, rev, into_rev);
+        }
+        println!(
+            "r{} 的更改已随 r{into_rev} 提交，不再需要重试",
+            revs.join("、r")
+        );
+        if let Err(e) = self.history.save() {
+            eprintln!("警告：保存失败版本记录失败：{e}");
+        }
     }
 
-    let mut shortened: String = first_line.chars().take(MAX_CHARS).collect();
+    /// 开启了失败回滚时，同步失败后回滚到同步开始前的状态
+    ///
+    /// 回滚失败只输出警告，返回原始错误
+    fn rollback_failed_sync(&mut self, result: Result<usize>) -> Result<usize> {
+        let start = match (&result, self.rollback_on_failure, self.sync_start.take()) {
+            (Err(_), true, Some(start)) => start,
+            _ => return result,
+        };
+        println!("同步失败，回滚到同步开始前的状态");
+        if let Err(e) = self.rollback_to(&start) {
+            eprintln!("警告：回滚失败：{e}，可稍后执行 rollback 命令重试");
+        }
+        result
+    }
+
+    /// 回滚到同步开始前的状态
+    fn rollback_to(&mut self, start: &SyncStartState) -> Result<()> {
+        self.git_operations
+            .reset_to(&self.config.git_dir, start.git_head.as_deref())?;
+        self.update_working_copy(&start.svn_rev)?;
+        self.history
+            .restore_sync_state(&self.config.svn_dir, &self.config.git_dir, start);
+        self.history.save()?;
+        self.saved_revisions = 0;
+        println!(
+            "已回滚：Git 分支重置到 {}，SVN 工作副本更新到 r{}",
+            start.git_head.as_deref().unwrap_or("（无提交）"),
+            start.svn_rev
+        );
+        Ok(())
+    }
+
+    /// 同步检查点中尚未完成的版本
+    ///
+    /// # 返回值
+    ///
+    /// 同步的版本数
+    fn resume_pending(&mut self) -> Result<usize> {
+        let checkpoint = self
+            .history
+            .find_record(&self.config.svn_dir, &self.config.git_dir)
+            .and_then(|r| r.checkpoint())
+            .ok_or_else(|| SyncError::App("没有需要恢复的同步".into()))?;
+        let pending = checkpoint.pending().to_vec();
+        self.sync_start = checkpoint.start().cloned();
+        self.check_git_capabilities()?;
+        self.validate_directories()?;
+        self.detect_sparse_scope()?;
+
+        let mut svn_logs = Vec::new();
+        for log in self.fetch_pending_logs(SvnLogQuery::default()) {
+            let log = log?;
+            if pending.contains(&log.version) {
+                svn_logs.push(log);
+            }
+            // 检查点中的版本都找到后不再获取之后的日志
+            if svn_logs.len() == pending.len() {
+                break;
+            }
+        }
+
+        if svn_logs.is_empty() {
+            println!("检查点中的版本均已同步，清除检查点");
+            self.history
+                .set_checkpoint(&self.config.svn_dir, &self.config.git_dir, None);
+            self.history.save()?;
+            self.publish_history()?;
+            return Ok(0);
+        }
+
+        println!(
+            "恢复中断的同步：剩余 {} / {} 条日志",
+            svn_logs.len(),
+            pending.len()
+        );
+        let total = svn_logs.len();
+        self.sync_logs(PendingLogs::from_logs(svn_logs))?;
+        self.publish_history()?;
+        Ok(total)
+    }
+
+    /// 同步对的链路追踪 span
+    fn pair_span(&self, name: &str) -> Span {
+        trace_span(name)
+            .with_attribute("svn.dir", self.config.svn_dir.display())
+            .with_attribute("git.dir", self.config.git_dir.display())
+    }
+
+    /// 依次调用已注册的通知，通知失败只输出警告
+    fn notify(&self, send: impl Fn(&dyn Notifier, &SyncConfig) -> Result<()>) {
+        for notifier in &self.hooks.notifiers {
+            if let Err(e) = send(notifier.as_ref(), &self.config) {
+                eprintln!("警告：发送同步通知失败：{e}");
+            }
+        }
+    }
+
+    /// 把本次运行的结果保存到同步对的历史记录，`history list` 据此显示状态
+    ///
+    /// 保存失败只输出警告，不影响同步结果
+    ///
+    /// 设置了退避策略时同时更新同步对的健康状态
+    ///
+    /// # 返回值
+    ///
+    /// 失败时是否需要发送失败通知
+    fn record_run_outcome(&mut self, result: &Result<usize>, started: Instant) -> bool {
+        let (svn_dir, git_dir) = (&self.config.svn_dir, &self.config.git_dir);
+        let recorded = self.history.record_run(
+            svn_dir,
+            git_dir,
+            result,
+            self.saved_revisions,
+            started.elapsed().as_secs(),
+        );
+        let mut alert = true;
+        if let Some(policy) = &self.backoff {
+            match result {
+                Ok(_) => {
+                    if self.history.record_success(svn_dir, git_dir) {
+                        println!("同步已恢复正常");
+                    }
+                }
+                Err(e) => {
+                    if let Some(outcome) =
+                        self.history
+                            .record_failure(svn_dir, git_dir, &e.to_string(), policy)
+                    {
+                        println!("{} 秒后再重试该同步对", outcome.backoff.as_secs());
+                        alert = outcome.should_alert;
+                    }
+                }
+            }
+        }
+        if recorded && let Err(e) = self.history.save() {
+            eprintln!("警告：保存同步结果失败：{e}");
+        }
+        alert
+    }
+
+    /// 按同步结果发送完成或失败通知
+    ///
+    /// `alert` 为 `false` 时失败不发送通知（连续失败尚未达到告警阈值或已告警过）
+    fn notify_result(&self, result: Result<usize>, started: Instant, alert: bool) -> Result<()> {
+        match result {
+            Ok(revisions) => {
+                let report = SyncReport {
+                    svn_dir: self.config.svn_dir.clone(),
+                    git_dir: self.config.git_dir.clone(),
+                    revisions,
+                    duration: started.elapsed(),
+                };
+                self.notify(|notifier, _| notifier.notify_finished(&report));
+                Ok(())
+            }
+            Err(e) => {
+                if alert {
+                    self.notify(|notifier, config| notifier.notify_failed(config, &e));
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// 同步完成后按设置推送到远程仓库并生成 git bundle
+    fn publish_history(&self) -> Result<()> {
+        self.write_state_marker()?;
+        self.push_to_remote()?;
+        self.write_bundle()
+    }
+
+    /// 把同步进度写入 Git 仓库的镜像标记，还没有同步过任何版本时跳过
+    fn write_state_marker(&self) -> Result<()> {
+        let (svn_dir, git_dir) = (&self.config.svn_dir, &self.config.git_dir);
+        let Some(record) = self.history.find_record(svn_dir, git_dir) else {
+            return Ok(());
+        };
+        let Some(rev) = record.last_synced_rev() else {
+            return Ok(());
+        };
+        let svn_uuid = record.identity().map(|identity| identity.svn_uuid.clone());
+        let marker = MirrorMarker::new(record.id(), svn_uuid, rev);
+        self.git_operations
+            .write_blob_ref(git_dir, STATE_REF, &marker.render())
+    }
+
+    /// 同步对没有同步进度时，从 Git 仓库的镜像标记恢复
+    ///
+    /// 本地配置文件丢失后重新添加同步对时，从标记中的版本之后继续同步，
+    /// 而不是从工作副本的 BASE 开始重复提交。标记属于其他 SVN 仓库时忽略。
+    fn recover_sync_state(&mut self, svn_uuid: &str) -> Result<()> {
+        let (svn_dir, git_dir) = (&self.config.svn_dir, &self.config.git_dir);
+        let needs_recovery = self
+            .history
+            .find_record(svn_dir, git_dir)
+            .is_some_and(|record| record.last_synced_rev().is_none() && !record.has_checkpoint());
+        if !needs_recovery {
+            return Ok(());
+        }
+        let Some(text) = self.git_operations.read_blob_ref(git_dir, STATE_REF)? else {
+            return Ok(());
+        };
+        let marker = MirrorMarker::parse(&text)?;
+        if marker
+            .svn_uuid
+            .as_deref()
+            .is_some_and(|uuid| uuid != svn_uuid)
+        {
+            println!(
+                "Git 仓库中的镜像标记属于 SVN 仓库 {}，不恢复同步进度",
+                marker.svn_uuid.unwrap_or_default()
+            );
+            return Ok(());
+        }
+        let head = self.git_operations.rev_parse_head(git_dir)?;
+        self.history
+            .update_sync_state(svn_dir, git_dir, &marker.last_synced_rev, &head);
+        self.history.save()?;
+        println!(
+            "已从 Git 仓库的 {STATE_REF} 恢复同步进度：r{}（由 svn2git {} 写入）",
+            marker.last_synced_rev, marker.tool_version
+        );
+        Ok(())
+    }
+
+    /// 设置了 bundle 文件时把所有分支和标签打包，仓库还没有提交时跳过
+    fn write_bundle(&self) -> Result<()> {
+        let (Some(file), git_dir) = (&self.publish.bundle, &self.config.git_dir) else {
+            return Ok(());
+        };
+        if self.git_operations.rev_parse_head(git_dir).is_err() {
+            println!("Git 仓库还没有提交，跳过生成 git bundle");
+            return Ok(());
+        }
+        self.git_operations.bundle(git_dir, file)?;
+        println!(
+            "已生成 git bundle {}，可在其他机器上 git clone 该文件",
+            file.display()
+        );
+        Ok(())
+    }
+
+    /// 设置远程仓库，并按参数或策略推送所有分支和标签
+    ///
+    /// 每个版本的提交和进度在同步过程中已经保存，推送失败不回滚，
+    /// 下次同步（即使没有新版本）会再次推送
+    fn push_to_remote(&self) -> Result<()> {
+        let git_dir = &self.config.git_dir;
+        if let Some(url) = &self.publish.git_remote {
+            self.git_operations.add_remote(git_dir, GIT_REMOTE, url)?;
+        }
+        if !self.publish.push && self.config.policies.push != PushPolicy::Always {
+            return Ok(());
+        }
+
+        println!("推送分支和标签到 {GIT_REMOTE}");
+        self.git_operations.push(git_dir, GIT_REMOTE).map_err(|e| {
+            SyncError::App(format!(
+                "{e}\n本地提交和同步进度已保留，可稍后重新执行同步或手动 git push 重试"
+            ))
+        })?;
+        println!("推送完成");
+        Ok(())
+    }
+
+    /// 设置了锁文件时获取同步锁，返回的锁在同步结束后释放
+    fn acquire_lock(&self) -> Result<Option<SyncLock>> {
+        self.files
+            .lock_file
+            .as_deref()
+            .map(|path| SyncLock::acquire(path, self.files.wait_for_lock))
+            .transpose()
+    }
+
+    /// 需要先检出工作副本时返回 SVN 仓库 URL
+    ///
+    /// 只有设置了 URL 且工作副本目录不存在或为空时才需要检出
+    fn checkout_url(&self) -> Result<Option<&str>> {
+        let Some(url) = &self.svn_url else {
+            return Ok(None);
+        };
+        let svn_dir = &self.config.svn_dir;
+        if svn_dir.exists() && svn_dir.read_dir()?.next().is_some() {
+            return Ok(None);
+        }
+        Ok(Some(url))
+    }
+
+    /// 获取上次同步版本之后的日志
+    ///
+    /// 如果历史记录中保存了上次同步到的版本，则从该版本之后开始，
+    /// 否则从 SVN 工作副本的 BASE 之后开始。日志按段惰性获取，见 [`SvnLogStream`]。
+    fn fetch_pending_logs(&self, query: SvnLogQuery) -> SvnLogStream<'_> {
+        self.fetch_logs(self.pending_query(query))
+    }
+
+    /// 把查询范围的起点设为上次同步到的版本，见 [`Self::fetch_pending_logs`]
+    fn pending_query(&self, query: SvnLogQuery) -> SvnLogQuery {
+        SvnLogQuery {
+            after_rev: self
+                .history
+                .find_record(&self.config.svn_dir, &self.config.git_dir)
+                .and_then(|r| r.last_synced_rev())
+                .map(str::to_string),
+            ..query
+        }
+    }
+
+    /// 按查询范围分段获取日志
+    fn fetch_logs(&self, query: SvnLogQuery) -> SvnLogStream<'_> {
+        self.hooks.reporter.detail("正在获取 SVN 日志");
+        let svn_dir = &self.config.svn_dir;
+        let remote = self
+            .svn_operations
+            .info(svn_dir)
+            .is_ok_and(|info| is_remote_url(&info.url));
+        SvnLogStream::new(
+            self.svn_operations.as_ref(),
+            svn_dir,
+            query,
+            self.log_batch,
+            remote,
+        )
+    }
+
+    /// 从待同步的版本中选出 `--only-revs` 指定的版本
+    ///
+    /// SVN 工作副本按版本整体更新，未选择的版本不会单独生成提交，
+    /// 其更改随之后第一个选择的版本一起提交，之后的未选择版本仍然待同步
+    fn select_only_revs(
+        &self,
+        pending: Vec<SvnLog>,
+        only_revs: &RevisionSet,
+    ) -> Result<Vec<SvnLog>> {
+        let mut folded = Vec::new();
+        let mut notes = Vec::new();
+        for log in &pending {
+            if !only_revs.contains(&log.version) {
+                folded.push(format!("r{}", log.version));
+            } else if !folded.is_empty() {
+                notes.push(format!(
+                    "注意：未选择的 {} 的更改会随 r{} 一起提交",
+                    folded.join("、"),
+                    log.version
+                ));
+                folded.clear();
+            }
+        }
+        let map = self
+            .history
+            .find_record(&self.config.svn_dir, &self.config.git_dir)
+            .map(|r| r.revision_map().clone())
+            .unwrap_or_default();
+        let selected = only_revs.select(pending, &map)?;
+        for note in notes {
+            println!("{note}");
+        }
+        Ok(selected)
+    }
+
+    /// 让用户从待同步的版本中取消选择个别版本
+    ///
+    /// 未选择的版本按 `--only-revs` 的规则处理：不单独生成提交，其更改随之后第一个选择的版本提交
+    fn select_interactively(&self, pending: Vec<SvnLog>) -> Result<Vec<SvnLog>> {
+        let selected = self.interactor.select_revisions(&pending)?;
+        if selected.is_empty() {
+            return Ok(Vec::new());
+        }
+        let revs: RevisionSet = selected
+            .join(",")
+            .parse()
+            .map_err(|e: String| SyncError::App(format!("选择的版本无效：{e}")))?;
+        self.select_only_revs(pending, &revs)
+    }
+
+    /// 获取待同步版本的变更摘要，供确认同步时预览
+    ///
+    /// 摘要只用于展示，获取失败的版本给出警告后跳过，不影响同步
+    fn revision_diffs(&self, svn_logs: &[SvnLog]) -> Vec<RevisionDiff> {
+        svn_logs
+            .iter()
+            .filter_map(|log| {
+                match self
+                    .svn_operations
+                    .diff_summary(&self.config.svn_dir, &log.version)
+                {
+                    Ok(entries) => Some(RevisionDiff {
+                        version: log.version.clone(),
+                        entries,
+                    }),
+                    Err(e) => {
+                        eprintln!("警告：无法获取 r{} 的变更摘要：{e}", log.version);
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// 输出与上次同步计划的差异，并记录本次计划
+    ///
+    /// 开始同步时计划随检查点一起保存；dry-run 不创建检查点，需要单独保存，
+    /// 这样修改配置后用 dry-run 反复预览也能看到每次修改的效果
+    fn update_plan(&mut self, revisions: Vec<PlannedRevision>, save: bool) -> Result<()> {
+        let (svn_dir, git_dir) = (&self.config.svn_dir, &self.config.git_dir);
+        let Some(record) = self.history.find_record(svn_dir, git_dir) else {
+            return Ok(());
+        };
+
+        let plan = SyncPlan::new_at(revisions, self.clock.now());
+        if let Some(previous) = record.last_plan() {
+            let diff = plan.diff(previous, record.last_synced_rev());
+            let created_at = previous.created_at().with_timezone(&Local);
+            if diff.is_empty() {
+                println!(
+                    "同步计划与上次（{}）相同",
+                    created_at.format("%Y-%m-%d %H:%M:%S")
+                );
+            } else {
+                println!(
+                    "同步计划与上次（{}）相比：\n{diff}",
+                    created_at.format("%Y-%m-%d %H:%M:%S")
+                );
+            }
+        }
+
+        self.history.set_last_plan(svn_dir, git_dir, plan);
+        if save { self.history.save() } else { Ok(()) }
+    }
+
+    /// 同步计划中的一个版本
+    fn planned_revision(&self, log: &SvnLog) -> PlannedRevision {
+        PlannedRevision {
+            rev: log.version.clone(),
+            skipped: self.skips_revision(log),
+        }
+    }
+
+    /// 记录本次计划同步的版本和同步开始前的状态，用于中断后恢复或回滚
+    fn begin_checkpoint(&mut self, revisions: Vec<String>) -> Result<()> {
+        let record = self
+            .history
+            .find_record(&self.config.svn_dir, &self.config.git_dir);
+        let start = SyncStartState {
+            git_head: self
+                .git_operations
+                .rev_parse_head(&self.config.git_dir)
+                .ok(),
+            svn_rev: self.svn_operations.info(&self.config.svn_dir)?.revision,
+            last_synced_rev: record.and_then(|r| r.last_synced_rev()).map(str::to_string),
+            last_git_hash: record.and_then(|r| r.last_git_hash()).map(str::to_string),
+            mapped_revisions: record.map_or(0, |r| r.revision_map().len()),
+        };
+        self.sync_start = Some(start.clone());
+        if record.is_none() {
+            return Ok(());
+        }
+
+        self.history.set_checkpoint(
+            &self.config.svn_dir,
+            &self.config.git_dir,
+            Some(SyncCheckpoint::new_at(revisions, self.clock.now()).with_start(start)),
+        );
+        self.history.save()
+    }
+
+    /// 逐条同步日志，每完成一条就保存进度
+    fn sync_logs(&mut self, pending: PendingLogs) -> Result<()> {
+        self.files.progress.start(pending.len());
+        let result = self.sync_revisions(pending);
+        self.files.progress.finish(&result);
+        result
+    }
+
+    /// 下一条待同步的日志，当前窗口取完时获取下一个窗口
+    fn next_pending(&self, pending: &mut PendingLogs) -> Result<Option<SvnLog>> {
+        pending.next(self.log_window, |query| self.fetch_logs(query).collect())
+    }
+
+    /// 查看下一条待同步的日志，见 [`Self::next_pending`]
+    fn peek_pending<'a>(&self, pending: &'a mut PendingLogs) -> Result<Option<&'a SvnLog>> {
+        pending.peek(self.log_window, |query| self.fetch_logs(query).collect())
+    }
+
+    /// 逐个同步版本，由 [`Self::sync_logs`] 记录进度
+    fn sync_revisions(&mut self, mut pending: PendingLogs) -> Result<()> {
+        self.prepare_layout()?;
+        let total = pending.len();
+        let mut failed = Vec::new();
+        // 失败后工作副本回到失败前的版本，这些版本的更改会随下一个成功同步的版本一起提交
+        let mut unfolded: Vec<String> = Vec::new();
+        let mut skipped = Vec::new();
+        let mut merging: Vec<SvnLog> = Vec::new();
+        let mut commits = 0;
+        for idx in 0.. {
+            let Some(current) = self.next_pending(&mut pending)? else {
+                break;
+            };
+            let version = current.version.clone();
+            let span = trace_span("svn2git.revision").with_attribute("svn.revision", &version);
+            let fail = |e: SyncError| {
+                let e = revision_error(idx + 1, &version, e, None);
+                span.record_error(&e);
+                e
+            };
+            let log = &current;
+            self.hooks.reporter.detail(format_args!(
+                "[{}/{}] 准备同步 SVN r{}：{}",
+                idx + 1,
+                total,
+                log.version,
+                summarize_message(&log.message)
+            ));
+            self.files.progress.revision(idx, &log.version);
+
+            if let Some(reason) = self.skip_reason(log) {
+                println!(
+                    "[{}/{}] r{} 按跳过规则（{reason}）不更新也不提交",
+                    idx + 1,
+                    total,
+                    log.version
+                );
+                self.record_progress(log, false).map_err(fail)?;
+                skipped.push(format!("r{}（{reason}）", log.version));
+                continue;
+            }
+
+            // 连续的机器人版本只在最后一个版本提交，之前的版本不更新，更改随最后一个版本进入 Git
+            if self.merges_revision(log)
+                && self
+                    .peek_pending(&mut pending)
+                    .map_err(fail)?
+                    .is_some_and(|next| self.merges_revision(next))
+            {
+                println!(
+                    "[{}/{}] r{} 与后续连续的机器人版本合并提交",
+                    idx + 1,
+                    total,
+                    log.version
+                );
+                self.record_progress(log, false).map_err(fail)?;
+                merging.push(current);
+                continue;
+            }
+            let merged;
+            let log = match self.merges_revision(log) && !merging.is_empty() {
+                true => {
+                    merging.push(current);
+                    let logs = std::mem::take(&mut merging);
+                    merged = merge_logs(&logs.iter().collect::<Vec<_>>());
+                    &merged
+                }
+                false => {
+                    merging.clear();
+                    log
+                }
+            };
+
+            let limited = self.limit_fields(log);
+            let log = &limited;
+
+            let svn_before = self
+                .svn_operations
+                .info(&self.config.svn_dir)
+                .map_err(fail)?
+                .revision;
+            let changelist_paths = self.update_working_copy(&log.version).map_err(fail)?;
+            self.hooks
+                .reporter
+                .detail(format_args!("[{}/{}] SVN 更新完成", idx + 1, total));
+
+            if let Some(tag) = self.create_svn_tag(log).map_err(fail)? {
+                println!(
+                    "[{}/{}] r{} 已创建 Git 附注标签 {tag}",
+                    idx + 1,
+                    total,
+                    log.version
+                );
+                self.record_progress(log, false).map_err(fail)?;
+                continue;
+            }
+
+            if !self.switch_layout_branch(log).map_err(fail)? {
+                println!(
+                    "[{}/{}] r{} 没有修改 trunk、分支或标签的内容，不提交",
+                    idx + 1,
+                    total,
+                    log.version
+                );
+                self.record_progress(log, false).map_err(fail)?;
+                continue;
+            }
+
+            let head_before = self
+                .git_operations
+                .rev_parse_head(&self.config.git_dir)
+                .ok();
+            let committed = match self.apply_revision(log, changelist_paths.as_deref()) {
+                Ok(committed) => {
+                    self.fold_failed_revisions(&std::mem::take(&mut unfolded), &log.version);
+                    committed
+                }
+                Err(e) => {
+                    let rollback = self.rollback_revision(head_before.as_deref(), &svn_before);
+                    let e = revision_error(idx + 1, &log.version, e, Some(rollback));
+                    span.record_error(&e);
+                    self.record_failed_revision(&log.version);
+                    if self.config.policies.error == ErrorPolicy::Stop {
+                        return Err(e);
+                    }
+                    eprintln!("{e}，按策略继续同步后续版本");
+                    self.files.progress.error(&e.to_string());
+                    failed.push(format!("r{}", log.version));
+                    unfolded.push(log.version.clone());
+                    continue;
+                }
+            };
+            if committed {
+                commits += 1;
+                println!(
+                    "[{}/{}] r{} Git 提交完成：{}",
+                    idx + 1,
+                    total,
+                    log.version,
+                    build_git_commit_message(
+                        &self.rewritten_message(log),
+                        &self.config.message_prefix
+                    )
+                );
+            } else {
+                println!(
+                    "[{}/{}] r{} 没有需要提交的更改，跳过提交",
+                    idx + 1,
+                    total,
+                    log.version
+                );
+            }
+        }
+
+        if !skipped.is_empty() {
+            println!(
+                "跳过了 {} 个版本，其更改随后续版本提交：{}",
+                skipped.len(),
+                skipped.join("、")
+            );
+        }
+        if !failed.is_empty() {
+            return Err(SyncError::App(format!(
+                "以下 SVN 版本同步失败：{}",
+                failed.join("、")
+            )));
+        }
+        println!("同步完成：{} 个版本，生成 {commits} 个 Git 提交", total);
+        Ok(())
+    }
+
+    /// 按未提交更改策略检查 Git 工作目录
+    ///
+    /// 策略为暂存时执行 `git stash`，同步结束后由 [`Self::restore_stash`] 恢复
+    fn ensure_dirty_tree_allowed(&mut self) -> Result<()> {
+        let git_dir = &self.config.git_dir;
+        let mut policy = self.config.policies.dirty_tree;
+        if policy == DirtyTreePolicy::Ignore || self.git_operations.is_clean(git_dir)? {
+            return Ok(());
+        }
+        if policy == DirtyTreePolicy::Ask {
+            policy = self.interactor.choose_dirty_tree_policy(git_dir);
+        }
+        match policy {
+            DirtyTreePolicy::Ignore => Ok(()),
+            DirtyTreePolicy::Fail | DirtyTreePolicy::Ask => {
+                Err(SyncError::DirtyWorkingTree(git_dir.clone()))
+            }
+            DirtyTreePolicy::Stash => {
+                self.stashed = self.git_operations.stash(git_dir, DIRTY_TREE_STASH)?;
+                if self.stashed {
+                    println!("已暂存 Git 工作目录中未提交的更改，同步结束后恢复");
+                } else {
+                    println!("Git 工作目录中只有未跟踪的文件，不暂存，将随第一个版本一起提交");
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// 恢复同步前暂存的更改
+    ///
+    /// 恢复失败时更改保留在 stash 中，错误附加到同步结果上
+    fn restore_stash(&mut self, result: Result<usize>) -> Result<usize> {
+        if !std::mem::take(&mut self.stashed) {
+            return result;
+        }
+        let hint = match self.git_operations.stash_pop(&self.config.git_dir) {
+            Ok(()) => {
+                println!("已恢复同步前暂存的更改");
+                return result;
+            }
+            Err(e) => format!("恢复同步前暂存的更改失败，更改保留在 git stash 中，请手动处理：{e}"),
+        };
+        match result {
+            Ok(_) => Err(SyncError::App(hint)),
+            Err(e) => Err(SyncError::App(format!("{e}（{hint}）"))),
+        }
+    }
+
+    /// 开始同步之前检查目录，避免在版本循环中途才因目录问题失败
+    ///
+    /// SVN 目录必须是工作副本，Git 目录必须是已初始化的仓库，
+    /// 并按策略检查 Git 工作目录是否干净
+    fn validate_directories(&mut self) -> Result<()> {
+        let (svn_dir, git_dir) = (&self.config.svn_dir, &self.config.git_dir);
+        let info = match self.svn_operations.info(svn_dir) {
+            Ok(info) => info,
+            Err(e) if e.is_upgrade_required() => self.upgrade_working_copy()?,
+            Err(e) => {
+                return Err(SyncError::NotSvnWorkingCopy {
+                    path: svn_dir.clone(),
+                    reason: e.to_string(),
+                });
+            }
+        };
+        if !self.git_operations.is_repository(git_dir)? {
+            if !self.interactor.confirm_git_init(git_dir) {
+                return Err(SyncError::NotGitRepository(git_dir.clone()));
+            }
+            self.init_git_repository()?;
+        } else {
+            self.ensure_related_git_history()?;
+            self.recover_sync_state(&info.repository_uuid)?;
+        }
+        self.svn_location = Some((info.url.clone(), info.repository_uuid.clone()));
+        self.verify_repository_identity(info.repository_uuid)?;
+        self.ensure_dirty_tree_allowed()
+    }
+
+    /// 校验同步对绑定的仓库，还没有绑定时绑定当前的仓库
+    ///
+    /// 绑定只记录在内存中，随同步进度一起保存
+    fn verify_repository_identity(&mut self, svn_uuid: String) -> Result<()> {
+        let (svn_dir, git_dir) = (&self.config.svn_dir, &self.config.git_dir);
+        let Some(record) = self.history.find_record(svn_dir, git_dir) else {
+            return Ok(());
+        };
+        // 还没有提交的仓库无法解析 HEAD
+        let git_root = match self.git_operations.rev_parse_head(git_dir) {
+            Ok(_) => Some(self.git_operations.root_commit(git_dir)?),
+            Err(_) => None,
+        };
+        let current = RepoIdentity { svn_uuid, git_root };
+        let update = match record.identity() {
+            Some(bound) => bound.verify(&current, svn_dir, git_dir)?,
+            None => Some(current),
+        };
+        if let Some(identity) = update {
+            self.history.set_identity(svn_dir, git_dir, identity);
+        }
+        Ok(())
+    }
+
+    /// 检查已有的 Git 仓库是否为本工具生成的镜像
+    ///
+    /// 同步对还没有同步记录，而 Git 仓库已有提交且没有一个是本工具生成的，
+    /// 说明很可能选错了目录，继续同步会把 SVN 版本混进无关项目的历史中，
+    /// 除非用 `--force` 明确允许。
+    fn ensure_related_git_history(&self) -> Result<()> {
+        let (svn_dir, git_dir) = (&self.config.svn_dir, &self.config.git_dir);
+        let has_baseline = self
+            .history
+            .find_record(svn_dir, git_dir)
+            .is_some_and(|record| record.last_synced_rev().is_some() || record.has_checkpoint());
+        if self.allow_unrelated_history || has_baseline {
+            return Ok(());
+        }
+        // 还没有提交的仓库无法解析 HEAD
+        if self.git_operations.rev_parse_head(git_dir).is_err() {
+            return Ok(());
+        }
+        let log = self.git_operations.log(git_dir, None)?;
+        let mut summaries = log
+            .lines()
+            .filter_map(|line| line.split_once(' ').map(|(_, summary)| summary));
+        if log.trim().is_empty()
+            || summaries.any(|summary| is_svn2git_commit(summary, &self.config.message_prefix))
+        {
+            return Ok(());
+        }
+        Err(SyncError::App(format!(
+            "Git 仓库 {} 已有 {} 个与 SVN 同步无关的提交，为避免把 SVN 版本混入其他项目的历史，已拒绝同步（确认无误时加上 --force）",
+            git_dir.display(),
+            log.lines().count()
+        )))
+    }
+
+    /// 升级格式过旧的 SVN 工作副本
+    ///
+    /// 没有开启自动升级时返回说明如何处理的错误，而不是 svn 的原始错误
+    fn upgrade_working_copy(&self) -> Result<SvnInfo> {
+        let svn_dir = &self.config.svn_dir;
+        if !self.auto_upgrade {
+            return Err(SyncError::App(format!(
+                "SVN 工作副本 {} 的格式过旧，需要先执行 svn upgrade（加上 --auto-upgrade 可自动升级）",
+                svn_dir.display()
+            )));
+        }
+        println!(
+            "SVN 工作副本 {} 的格式过旧，正在执行 svn upgrade",
+            svn_dir.display()
+        );
+        self.svn_operations.upgrade(svn_dir)?;
+        let info = self.svn_operations.info(svn_dir)?;
+        println!("SVN 工作副本升级完成");
+        Ok(info)
+    }
+
+    /// 初始化 Git 仓库，并把当前 SVN 工作副本提交为初始状态
+    ///
+    /// 之后同步的每个版本都以这个提交为基础，第一次 `git add` 不会因为仓库不存在而失败
+    fn init_git_repository(&self) -> Result<()> {
+        let git_dir = &self.config.git_dir;
+        fs::create_dir_all(git_dir)?;
+        self.git_operations.init(git_dir)?;
+        self.git_operations
+            .config_user(git_dir, INIT_USER_NAME, INIT_USER_EMAIL)?;
+        println!("已初始化 Git 仓库 {}", git_dir.display());
+
+        self.ignore_svn_metadata()?;
+        if self.commits.export || self.layout.is_some() {
+            self.mirror_to_git_dir()?;
+        }
+        self.git_operations.add_all(git_dir)?;
+        if self.git_operations.is_clean(git_dir)? {
+            println!("SVN 工作副本没有文件，跳过初始提交");
+            return Ok(());
+        }
+        let revision = self.svn_operations.info(&self.config.svn_dir)?.revision;
+        self.git_operations
+            .commit(git_dir, &format!("{INIT_COMMIT_PREFIX}{revision})"))?;
+        println!("已提交 SVN 工作副本 r{revision} 的初始状态");
+        Ok(())
+    }
+
+    /// 把已更新的工作副本同步为一个 Git 提交，并记录进度
+    ///
+    /// 包含复制到 Git 目录、属性规则更新、暂存、提交、删除检查、Git note 和校验清单，
+    /// 任一步失败都由调用方回滚本版本的 Git 操作。
+    ///
+    /// # 返回值
+    ///
+    /// 是否生成了 Git 提交
+    fn apply_revision(
+        &mut self,
+        log: &SvnLog,
+        changelist_paths: Option<&[String]>,
+    ) -> Result<bool> {
+        if self.commits.export || self.layout.is_some() {
+            let stats = self.mirror_to_git_dir()?;
+            self.hooks.reporter.detail(format_args!(
+                "已复制到 Git 目录：更新 {} 个文件，删除 {} 个文件",
+                stats.copied, stats.removed
+            ));
+        }
+        let props = self.apply_svn_properties()?;
+        self.ensure_git_conflict_free()?;
+
+        let metadata = self.commit_metadata(log)?;
+        let committed = self.commit_revision(log, changelist_paths, &metadata, &props)?;
+        // 变更列表模式不会同步列表以外的删除
+        if committed && self.revisions.changelist.is_none() {
+            self.verify_deletions(log)?;
+        }
+        self.record_progress(log, committed)?;
+        Ok(committed)
+    }
+
+    /// 开启分支映射时检测 SVN 仓库布局
+    fn prepare_layout(&mut self) -> Result<()> {
+        if self.commits.git_tags && !self.revisions.layout {
+            return Err(SyncError::App(
+                "创建 Git 附注标签需要同时开启分支映射".into(),
+            ));
+        }
+        if !self.revisions.layout || self.layout.is_some() {
+            return Ok(());
+        }
+        let (svn_dir, git_dir) = (&self.config.svn_dir, &self.config.git_dir);
+        if self.revisions.changelist.is_some() {
+            return Err(SyncError::App("分支映射不能与变更列表同时使用".into()));
+        }
+        if svn_dir.starts_with(git_dir) || git_dir.starts_with(svn_dir) {
+            return Err(SyncError::App(format!(
+                "分支映射要求 SVN 目录与 Git 目录互不包含：{} / {}",
+                svn_dir.display(),
+                git_dir.display()
+            )));
+        }
+
+        let layout = detect_layout(self.svn_operations.as_ref(), svn_dir)?.ok_or_else(|| {
+            SyncError::App(format!(
+                "{} 对应的 SVN 目录下没有 trunk/branches/tags 标准布局",
+                svn_dir.display()
+            ))
+        })?;
+        println!(
+            "检测到 SVN 标准布局：{} / {} / {}",
+            layout.trunk, layout.branches, layout.tags
+        );
+        self.layout = Some(layout);
+        Ok(())
+    }
+
+    /// 开启分支映射时，切换到本版本修改的开发线对应的 Git 分支
+    ///
+    /// 分支不存在时创建：通过 `svn copy` 创建的开发线从来源分支开始，否则从当前 HEAD 开始。
+    ///
+    /// # 返回值
+    ///
+    /// 是否需要提交本版本；没有修改任何开发线或删除了整条开发线时不提交（Git 分支保留）
+    fn switch_layout_branch(&mut self, log: &SvnLog) -> Result<bool> {
+        let Some(layout) = &self.layout else {
+            return Ok(true);
+        };
+        let info = self.svn_operations.info(&self.config.svn_dir)?;
+        let root = info
+            .url
+            .strip_prefix(&info.repository_root)
+            .unwrap_or_default();
+        let Some(revision) = layout.revision(log, root)? else {
+            return Ok(false);
+        };
+        if revision.deleted {
+            return Ok(false);
+        }
+
+        let git_dir = &self.config.git_dir;
+        let branch = revision.target.git_branch();
+        if self
+            .git_operations
+            .checkout_branch(git_dir, &branch)
+            .is_err()
+        {
+            let start = revision.copied_from.as_ref().map(LayoutTarget::git_branch);
+            self.git_operations
+                .create_branch(git_dir, &branch, start.as_deref())?;
+            self.git_operations.checkout_branch(git_dir, &branch)?;
+            println!(
+                "已创建 Git 分支 {branch}（起点：{}）",
+                start.as_deref().unwrap_or("HEAD")
+            );
+        }
+        self.layout_target = Some(revision.target);
+        Ok(true)
+    }
+
+    /// 开启附注标签时，把只复制到 `tags/<名称>` 的版本转换为来源分支上的附注标签
+    ///
+    /// 标签创建者为 SVN 作者（邮箱为 `作者@仓库UUID`），时间为 SVN 提交时间。
+    /// 复制的同时还修改了其他路径的版本仍按普通版本提交。
+    ///
+    /// # 返回值
+    ///
+    /// 创建的标签名；本版本不是标签复制时返回 `None`
+    fn create_svn_tag(&self, log: &SvnLog) -> Result<Option<String>> {
+        let (true, Some(layout)) = (self.commits.git_tags, &self.layout) else {
+            return Ok(None);
+        };
+        if log.paths.len() != 1 {
+            return Ok(None);
+        }
+        let info = self.svn_operations.info(&self.config.svn_dir)?;
+        let root = info
+            .url
+            .strip_prefix(&info.repository_root)
+            .unwrap_or_default();
+        let Some(LayoutRevision {
+            target: LayoutTarget::Tag(name),
+            copied_from: Some(source),
+            deleted: false,
+        }) = layout.revision(log, root)?
+        else {
+            return Ok(None);
+        };
+
+        let author = match log.author.as_str() {
+            "" => SVN_NO_AUTHOR,
+            author => author,
+        };
+        let tagger = GitSignature {
+            name: author.to_string(),
+            email: format!("{author}@{}", info.repository_uuid),
+            date: DateTime::parse_from_rfc3339(&log.date)
+                .ok()
+                .map(|date| date.with_timezone(&Utc)),
+        };
+        let message = match log.message.trim() {
+            "" => format!("SVN r{} 标签 {name}", log.version),
+            message => format_commit_message(message, &self.commits.message_limits),
+        };
+        self.git_operations.tag(
+            &self.config.git_dir,
+            &name,
+            &source.git_branch(),
+            &message,
+            Some(&tagger),
+        )?;
+        Ok(Some(name))
+    }
+
+    /// 复制到 Git 目录的 SVN 目录：开启分支映射时为当前开发线目录，否则为工作副本根目录
+    fn source_dir(&self) -> PathBuf {
+        match (&self.layout, &self.layout_target) {
+            (Some(layout), Some(target)) => self.config.svn_dir.join(layout.dir(target)),
+            _ => self.config.svn_dir.clone(),
+        }
+    }
+
+    /// 同步某个版本失败后，把 HEAD 和暂存区恢复到同步该版本之前的提交，
+    /// 并把 SVN 工作副本更新回该版本之前的版本，使两边重新保持一致
+    ///
+    /// 已写入的 Git note 指向被撤销的提交，不会出现在历史中。
+    ///
+    /// # 参数
+    ///
+    /// * `head_before` - 同步该版本之前的 HEAD
+    /// * `svn_before` - 同步该版本之前工作副本的版本
+    ///
+    /// # 返回值
+    ///
+    /// 回滚结果的说明
+    fn rollback_revision(&self, head_before: Option<&str>, svn_before: &str) -> String {
+        let git = match self
+            .git_operations
+            .reset_to(&self.config.git_dir, head_before)
+        {
+            Ok(()) => "Git 仓库已恢复到同步该版本之前的状态".to_string(),
+            Err(reset_error) => format!("恢复 Git 仓库失败，请手动检查：{reset_error}"),
+        };
+        let svn = match self.update_working_copy(svn_before) {
+            Ok(_) => format!("SVN 工作副本已恢复到 r{svn_before}"),
+            Err(update_error) => format!(
+                "恢复 SVN 工作副本失败，请手动执行 svn update -r {svn_before}：{update_error}"
+            ),
+        };
+        format!("{git}，{svn}")
+    }
+
+    /// 把工作副本更新到指定版本；设置了变更列表时只更新其中的文件
+    ///
+    /// 更新产生冲突时先清除工作副本中的冲突，再返回 [`SyncError::SvnConflict`]
+    ///
+    /// # 返回值
+    ///
+    /// 设置了变更列表时返回其中的文件（相对 Git 仓库根目录）
+    fn update_working_copy(&self, rev: &str) -> Result<Option<Vec<String>>> {
+        self.update_paths(rev).inspect_err(|e| {
+            if matches!(e, SyncError::SvnConflict { .. }) {
+                self.recover_from_conflict();
+            }
+        })
+    }
+
+    /// 更新产生冲突后清除工作副本中的冲突，避免影响下一次同步
+    fn recover_from_conflict(&self) {
+        match self.svn_operations.revert_all(&self.config.svn_dir) {
+            Ok(()) => println!("已执行 svn cleanup 和 svn revert -R 清除冲突"),
+            Err(e) => eprintln!(
+                "警告：清除冲突失败：{e}，请手动在 {} 执行 svn cleanup 和 svn revert -R .",
+                self.config.svn_dir.display()
+            ),
+        }
+    }
+
+    fn update_paths(&self, rev: &str) -> Result<Option<Vec<String>>> {
+        let started = Instant::now();
+        let Some(changelist) = &self.revisions.changelist else {
+            self.hooks
+                .reporter
+                .detail(format_args!("正在拉取 SVN 版本 {rev} 到本地"));
+            self.svn_operations
+                .update_to_rev(&self.config.svn_dir, rev)?;
+            self.hooks
+                .reporter
+                .detail(format_args!("SVN 更新到 {rev} 成功"));
+            report_slow_update(rev, started.elapsed());
+            return Ok(None);
+        };
+
+        let paths = self
+            .svn_operations
+            .changelist_paths(&self.config.svn_dir, changelist)?;
+        if paths.is_empty() {
+            return Err(SyncError::App(format!("变更列表 {changelist} 中没有文件")));
+        }
+        self.hooks.reporter.detail(format_args!(
+            "正在拉取 {} 个路径到 SVN 版本 {rev}",
+            paths.len()
+        ));
+        self.svn_operations
+            .update_paths_to_rev(&self.config.svn_dir, rev, &paths)?;
+        self.hooks
+            .reporter
+            .detail(format_args!("SVN 更新到 {rev} 成功"));
+        report_slow_update(rev, started.elapsed());
+
+        let prefix = git_relative_prefix(&self.config.svn_dir, &self.config.git_dir);
+        Ok(Some(
+            paths.iter().map(|path| format!("{prefix}{path}")).collect(),
+        ))
+    }
+
+    /// 提交当前版本
+    ///
+    /// 设置了变更列表时只暂存其中的文件（以及工具维护的 `.gitattributes`），
+    /// 这些文件都没有修改时不提交。其他情况下暂存所有更改后没有任何修改时按空版本策略处理：
+    /// 跳过时不提交，允许空提交时生成空提交，默认提交失败。
+    ///
+    /// # 返回值
+    ///
+    /// 是否生成了 Git 提交
+    fn commit_revision(
+        &self,
+        log: &SvnLog,
+        changelist_paths: Option<&[String]>,
+        metadata: &CommitMetadata,
+        props: &SvnProperties,
+    ) -> Result<bool> {
+        let git_ops = self.git_operations.as_ref();
+        let git_dir = &self.config.git_dir;
+        let message = self.commit_message(log);
+
+        self.ignore_svn_metadata()?;
+
+        let Some(paths) = changelist_paths else {
+            let policy = self.config.policies.empty_revision;
+            self.stage_removed_paths(log)?;
+            git_ops.add_all(git_dir)?;
+            self.hooks.reporter.detail("已添加所有更改到暂存区");
+            self.stage_executables(props, None)?;
+            let mut metadata = metadata.clone();
+            if policy != EmptyRevisionPolicy::Fail && git_ops.is_clean(git_dir)? {
+                if policy == EmptyRevisionPolicy::Skip {
+                    self.hooks.reporter.detail(format_args!(
+                        "SVN r{} 没有产生 Git 更改，已跳过",
+                        log.version
+                    ));
+                    return Ok(false);
+                }
+                self.hooks.reporter.detail(format_args!(
+                    "SVN r{} 没有产生 Git 更改，生成空提交",
+                    log.version
+                ));
+                metadata.allow_empty = true;
+            }
+            if let Err(e) = self.commit_staged(&message, &metadata) {
+                if policy == EmptyRevisionPolicy::Fail && git_ops.is_clean(git_dir).unwrap_or(false)
+                {
+                    return Err(SyncError::App(format!(
+                        "SVN r{} 没有产生任何 Git 更改，无法提交（可将 empty-revision 策略设为 skip 或 allow-empty）：{e}",
+                        log.version
+                    )));
+                }
+                return Err(e);
+            }
+            return Ok(true);
+        };
+
+        let mut paths = paths.to_vec();
+        for managed in [".gitattributes", ".gitignore"] {
+            if git_dir.join(managed).exists() {
+                paths.push(managed.to_string());
+            }
+        }
+        if !status_touches_paths(&git_ops.status(git_dir)?, &paths) {
+            return Ok(false);
+        }
+        git_ops.add_paths(git_dir, &paths)?;
+        self.hooks
+            .reporter
+            .detail(format_args!("已添加 {} 个路径的更改到暂存区", paths.len()));
+        self.stage_executables(props, Some(&paths))?;
+        self.commit_staged(&message, metadata)?;
+        Ok(true)
+    }
+
+    /// 按 `svn:executable` 在暂存区中标记可执行文件
+    ///
+    /// # 参数
+    ///
+    /// * `props` - 本版本工作副本的版本属性
+    /// * `only` - 只处理其中的路径（变更列表模式），`None` 表示全部
+    fn stage_executables(&self, props: &SvnProperties, only: Option<&[String]>) -> Result<()> {
+        let mut files = svn_executables(props, &self.source_dir(), &self.config.git_dir);
+        if let Some(only) = only {
+            files.retain(|file| only.contains(file));
+        }
+        if files.is_empty() {
+            return Ok(());
+        }
+        self.git_operations
+            .set_executable(&self.config.git_dir, &files)?;
+        self.hooks.reporter.detail(format_args!(
+            "{} 个 {SVN_EXECUTABLE} 文件已标记为可执行",
+            files.len()
+        ));
+        Ok(())
+    }
+
+    /// 提交暂存区；保留作者或提交时间时使用指定的作者和时间
+    fn commit_staged(&self, message: &str, metadata: &CommitMetadata) -> Result<()> {
+        let git_dir = &self.config.git_dir;
+        if *metadata == CommitMetadata::default() {
+            self.git_operations.commit(git_dir, message)?;
+        } else {
+            self.git_operations
+                .commit_with(git_dir, message, metadata)?;
+        }
+        self.hooks
+            .reporter
+            .detail(format_args!("Git 提交成功：{message}"));
+        Ok(())
+    }
+
+    /// 按保留作者和提交时间的设置生成本版本的提交元数据
+    fn commit_metadata(&mut self, log: &SvnLog) -> Result<CommitMetadata> {
+        let mut metadata = CommitMetadata {
+            date: self.commit_date(log),
+            ..Default::default()
+        };
+        if self.commits.preserve_authors {
+            let uuid = match &self.repository_uuid {
+                Some(uuid) => uuid.clone(),
+                None => {
+                    let uuid = self
+                        .svn_operations
+                        .info(&self.config.svn_dir)?
+                        .repository_uuid;
+                    self.repository_uuid.insert(uuid).clone()
+                }
+            };
+            let (name, email) = resolve_author(
+                log,
+                &self.commits.author_rules,
+                &self.commits.service_accounts,
+                &uuid,
+            );
+            metadata.author_name = Some(name);
+            metadata.author_email = Some(email);
+        }
+        Ok(metadata)
+    }
+
+    /// 保留提交时间时，计算本版本的 Git 提交时间
+    ///
+    /// 版本时间早于之前已提交的版本时按 `date-order` 策略处理；
+    /// 没有有效的 svn:date 时使用当前时间。设置了时区偏移时换算到该时区。
+    ///
+    /// # 返回值
+    ///
+    /// 未开启保留提交时间时返回 `None`
+    fn commit_date(&mut self, log: &SvnLog) -> Option<DateTime<FixedOffset>> {
+        if !self.commits.preserve_dates {
+            return None;
+        }
+        let date = DateTime::parse_from_rfc3339(&log.date).unwrap_or_else(|_| {
+            eprintln!(
+                "警告：SVN r{} 没有有效的 svn:date，使用当前时间提交",
+                log.version
+            );
+            self.clock.now().fixed_offset()
+        });
+
+        let date = match self.last_commit_date {
+            Some(previous) if date < previous => match self.config.policies.date_order {
+                DateOrderPolicy::Keep => date,
+                DateOrderPolicy::Warn => {
+                    eprintln!(
+                        "警告：SVN r{} 的时间 {} 早于之前的版本（{}），Git 提交时间将不按顺序递增",
+                        log.version,
+                        date.to_rfc3339(),
+                        previous.to_rfc3339()
+                    );
+                    date
+                }
+                DateOrderPolicy::Clamp => {
+                    let clamped = previous + chrono::Duration::seconds(1);
+                    println!(
+                        "SVN r{} 的时间 {} 早于之前的版本，提交时间调整为 {}",
+                        log.version,
+                        date.to_rfc3339(),
+                        clamped.to_rfc3339()
+                    );
+                    clamped
+                }
+            },
+            _ => date,
+        };
+        self.last_commit_date = Some(self.last_commit_date.map_or(date, |prev| prev.max(date)));
+        Some(match self.commits.date_timezone {
+            Some(offset) => date.with_timezone(&offset),
+            None => date,
+        })
+    }
+
+    /// SVN 工作副本位于 Git 仓库内时，在 `.gitignore` 中忽略 `.svn` 管理目录
+    ///
+    /// 不在 Git 仓库内时移除该规则
+    fn ignore_svn_metadata(&self) -> Result<()> {
+        let (svn_dir, git_dir) = (&self.config.svn_dir, &self.config.git_dir);
+        let lines = if svn_dir.starts_with(git_dir) {
+            vec![SVN_METADATA_IGNORE.to_string()]
+        } else {
+            Vec::new()
+        };
+        if update_gitignore_section(git_dir, "svn-metadata", &lines)? && !lines.is_empty() {
+            println!(".gitignore 已忽略 SVN 管理目录 {SVN_METADATA_IGNORE}");
+        }
+        self.exclude_state_files()
+    }
+
+    /// 位于 Git 目录中的配置文件、锁文件和进度文件不参与提交
+    ///
+    /// 例如在 Git 工作目录中运行时，默认的 `config.json` 就在 Git 目录中，`git add -A` 会把它一起提交
+    fn exclude_state_files(&self) -> Result<()> {
+        let files = self.state_files_in_git_dir();
+        let lines: Vec<String> = files.iter().map(|f| gitattributes_pattern(f)).collect();
+        if update_git_exclude_section(&self.config.git_dir, "state-files", &lines)?
+            && !files.is_empty()
+        {
+            eprintln!(
+                "警告：svn2git 的状态文件位于 Git 目录中，已加入 .git/info/exclude，不会被提交：{}",
+                files.join("、")
+            );
+        }
+        Ok(())
+    }
+
+    /// 位于 Git 目录中的配置文件、锁文件和进度文件，`/` 分隔的相对路径
+    fn state_files_in_git_dir(&self) -> Vec<String> {
+        let git_dir = resolve_path(&self.config.git_dir);
+        let progress_files = self
+            .files
+            .progress
+            .path()
+            .map(|path| [path.to_path_buf(), path.with_extension("tmp")]);
+        let mut files: Vec<String> = self
+            .files
+            .state_files
+            .iter()
+            .cloned()
+            .chain(self.files.lock_file.clone())
+            .chain(progress_files.into_iter().flatten())
+            .filter_map(|file| {
+                let file = resolve_path(&file);
+                let relative = file.strip_prefix(&git_dir).ok()?;
+                let parts: Vec<_> = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect();
+                (!parts.is_empty()).then(|| parts.join("/"))
+            })
+            .collect();
+        files.dedup();
+        files
+    }
+
+    /// 把 SVN 工作副本复制到 Git 目录
+    ///
+    /// 只删除 Git 已跟踪的文件，未跟踪、被忽略的文件和工具的状态文件保持不变
+    fn mirror_to_git_dir(&self) -> Result<MirrorStats> {
+        let git_dir = &self.config.git_dir;
+        let tracked = match self.git_operations.rev_parse_head(git_dir) {
+            Ok(head) => self
+                .git_operations
+                .tree_blobs(git_dir, &head)?
+                .into_keys()
+                .map(PathBuf::from)
+                .collect(),
+            // 还没有提交时没有已跟踪的文件
+            Err(_) => BTreeSet::new(),
+        };
+        let keep = self
+            .state_files_in_git_dir()
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+        mirror_tree(&self.source_dir(), git_dir, &tracked, &keep)
+    }
+
+    /// 版本作者是否为需要合并连续版本的机器人账号
+    fn merges_revision(&self, log: &SvnLog) -> bool {
+        self.revisions.merge_authors.contains(&log.author)
+    }
+
+    /// 读取 SVN 工作副本的检出范围并记录到同步对
+    ///
+    /// 稀疏工作副本只镜像已检出的目录，只修改了未检出路径的版本按跳过处理
+    fn detect_sparse_scope(&mut self) -> Result<()> {
+        let svn_dir = &self.config.svn_dir;
+        let scope = self.svn_operations.depth_scope(svn_dir)?;
+        self.history
+            .set_sparse_scope(svn_dir, &self.config.git_dir, scope.clone());
+        if scope.is_full() {
+            self.sparse_scope = None;
+            return Ok(());
+        }
+        println!("SVN 工作副本为稀疏检出（{scope}），只同步已检出的目录");
+        let info = self.svn_operations.info(svn_dir)?;
+        let wc_path = info
+            .url
+            .strip_prefix(&info.repository_root)
+            .unwrap_or_default()
+            .trim_matches('/')
+            .to_string();
+        self.sparse_scope = Some((scope, wc_path));
+        Ok(())
+    }
+
+    /// 版本是否按作者或路径规则跳过
+    fn skips_revision(&self, log: &SvnLog) -> bool {
+        self.skip_reason(log).is_some()
+    }
+
+    /// 跳过版本的原因
+    ///
+    /// 作者在跳过列表中，或只修改了跳过规则匹配的路径、稀疏工作副本未检出的路径时跳过；
+    /// 没有变更路径信息的版本不按路径跳过
+    fn skip_reason(&self, log: &SvnLog) -> Option<String> {
+        if self.revisions.skip_authors.contains(&log.author) {
+            return Some(format!("作者 {}", log.author));
+        }
+        let paths_skipped = !self.revisions.skip_paths.is_empty()
+            && !log.paths.is_empty()
+            && log.paths.iter().all(|changed| {
+                !self
+                    .revisions
+                    .skip_paths
+                    .matches(changed.path.trim_start_matches('/'))
+            });
+        if paths_skipped {
+            return Some("只修改了跳过的路径".to_string());
+        }
+        let outside_scope = self.sparse_scope.as_ref().is_some_and(|(scope, wc_path)| {
+            !log.paths.is_empty()
+                && log.paths.iter().all(|changed| {
+                    let path = changed.path.trim_start_matches('/');
+                    let relative = match wc_path.is_empty() {
+                        true => Some(path),
+                        false => path
+                            .strip_prefix(wc_path.as_str())
+                            .and_then(|rest| rest.strip_prefix('/')),
+                    };
+                    !relative.is_some_and(|relative| scope.contains(relative))
+                })
+        });
+        outside_scope.then(|| "只修改了未检出的路径".to_string())
+    }
+
+    /// 按长度限制截断版本的提交说明和作者，截断时输出警告
+    fn limit_fields(&self, log: &SvnLog) -> SvnLog {
+        let mut log = log.clone();
+        let limits = &self.commits.message_limits;
+        for (name, field, max) in [
+            ("提交说明", &mut log.message, limits.max_message_chars),
+            ("作者", &mut log.author, limits.max_author_chars),
+        ] {
+            if let Some(truncated) = max.and_then(|max| truncate_field(field, max)) {
+                println!(
+                    "警告：SVN r{} 的{name}有 {} 个字符，超过上限 {}，已截断",
+                    log.version,
+                    field.chars().count(),
+                    max.unwrap_or_default()
+                );
+                *field = truncated;
+            }
+        }
+        log
+    }
+
+    /// 生成版本的 Git 提交说明，按设置在末尾列出变更路径和 git-svn-id
+    ///
+    /// SVN 提交说明先按改写规则改写，再移除控制字符，并按设置限制标题行和正文的宽度
+    fn commit_message(&self, log: &SvnLog) -> String {
+        let mut message = format_commit_message(
+            &build_git_commit_message(&self.rewritten_message(log), &self.config.message_prefix),
+            &self.commits.message_limits,
+        );
+        if self.commits.list_changed_files && !log.paths.is_empty() {
+            message.push_str("\n\n变更文件:");
+            for changed in &log.paths {
+                message.push_str(&strip_control_chars(&format!(
+                    "\n  {} {}",
+                    changed.action, changed.path
+                )));
+            }
+        }
+        // git-svn 只识别最后一行的 git-svn-id
+        if self.commits.git_svn_id
+            && let Some((url, uuid)) = &self.svn_location
+        {
+            message.push_str(&format!(
+                "\n\n{}",
+                build_git_svn_id(url, &log.version, uuid)
+            ));
+        }
+        message
+    }
+
+    /// 按同步对的改写规则改写后的 SVN 提交说明
+    fn rewritten_message(&self, log: &SvnLog) -> String {
+        rewrite_message(&log.message, &self.config.message_rewrites)
+    }
+
+    /// 显式暂存本版本中被删除（D）或替换（R）的路径
+    ///
+    /// 整体 `git add` 前先按路径执行一次“删除再添加”，
+    /// 避免被替换的文件或目录里残留的旧文件没有从 Git 中删除。
+    /// 只处理 Git 状态中确实有更改的路径。
+    fn stage_removed_paths(&self, log: &SvnLog) -> Result<()> {
+        let paths = self.changed_git_paths(log, &["D", "R"])?;
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        let status = self.git_operations.status(&self.config.git_dir)?;
+        let paths: Vec<String> = paths
+            .into_iter()
+            .filter(|path| status_touches_paths(&status, std::slice::from_ref(path)))
+            .collect();
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        self.hooks.reporter.detail(format_args!(
+            "{} 个被删除或替换的路径已单独暂存",
+            paths.len()
+        ));
+        self.git_operations.add_paths(&self.config.git_dir, &paths)
+    }
+
+    /// 检查本版本在 SVN 中删除的路径已从 Git 提交中移除
+    ///
+    /// 被替换（R）的路径会重新添加，不在检查范围内
+    ///
+    /// # 返回值
+    ///
+    /// * `Err(SyncError)` - 列出仍残留在 Git 中的路径
+    fn verify_deletions(&self, log: &SvnLog) -> Result<()> {
+        let deleted = self.changed_git_paths(log, &["D"])?;
+        if deleted.is_empty() {
+            return Ok(());
+        }
+
+        let head = self.git_operations.rev_parse_head(&self.config.git_dir)?;
+        let files = self
+            .git_operations
+            .tree_blobs(&self.config.git_dir, &head)?;
+        let leftovers: Vec<&str> = deleted
+            .iter()
+            .filter(|path| {
+                files.keys().any(|file| {
+                    file == *path
+                        || file
+                            .strip_prefix(path.as_str())
+                            .is_some_and(|rest| rest.starts_with('/'))
+                })
+            })
+            .map(String::as_str)
+            .collect();
+        if leftovers.is_empty() {
+            return Ok(());
+        }
+
+        Err(SyncError::App(format!(
+            "SVN r{} 中删除的路径仍在 Git 提交中：{}",
+            log.version,
+            leftovers.join(", ")
+        )))
+    }
+
+    /// 日志中指定操作的变更路径，转换为相对 Git 仓库根目录的路径
+    ///
+    /// 日志中没有这些操作时不读取工作副本信息
+    fn changed_git_paths(&self, log: &SvnLog, actions: &[&str]) -> Result<Vec<String>> {
+        if !log
+            .paths
+            .iter()
+            .any(|changed| actions.contains(&changed.action.as_str()))
+        {
+            return Ok(Vec::new());
+        }
+
+        let info = self.svn_operations.info(&self.config.svn_dir)?;
+        let mut wc_path = info
+            .url
+            .strip_prefix(&info.repository_root)
+            .unwrap_or_default()
+            .to_string();
+        if let (Some(layout), Some(target)) = (&self.layout, &self.layout_target) {
+            wc_path = format!("{wc_path}/{}", layout.dir(target));
+        }
+        let prefix = git_relative_prefix(&self.config.svn_dir, &self.config.git_dir);
+        Ok(changed_paths_in_git(log, actions, &wc_path, &prefix))
+    }
+
+    /// 记录已同步的版本和对应的 Git HEAD，并立即保存
+    ///
+    /// 同时从检查点中移除该版本；生成了提交时按设置添加 Git note、写入该版本的校验清单
+    fn record_progress(&mut self, log: &SvnLog, committed: bool) -> Result<()> {
+        let rev = log.version.as_str();
+        let head = self.git_operations.rev_parse_head(&self.config.git_dir)?;
+        self.saved_revisions += 1;
+        if !committed {
+            self.history
+                .update_sync_state(&self.config.svn_dir, &self.config.git_dir, rev, &head);
+            return self.history.save();
+        }
+        if self.commits.git_notes {
+            self.git_operations.add_note(
+                &self.config.git_dir,
+                SVN_NOTES_REF,
+                &head,
+                &build_svn_note(log),
+            )?;
+        }
+        if let Some(dir) = &self.commits.manifest_dir {
+            let files = self
+                .git_operations
+                .tree_blobs(&self.config.git_dir, &head)?;
+            let path = write_manifest(dir, rev, &head, &self.config.git_dir, files.keys())?;
+            self.hooks
+                .reporter
+                .detail(format_args!("已写入校验清单：{}", path.display()));
+        }
+        self.history
+            .update_sync_state(&self.config.svn_dir, &self.config.git_dir, rev, &head);
+        self.history.save()
+    }
+
+    /// 把 SVN 版本属性同步为 `.gitattributes` 规则
+    fn apply_svn_properties(&self) -> Result<SvnProperties> {
+        let (svn_dir, git_dir) = (&self.source_dir(), &self.config.git_dir);
+        let props = self.svn_operations.proplist(svn_dir)?;
+
+        // 只在属性有变化时输出
+        let locked = sync_needs_lock(&props, svn_dir, git_dir)?;
+        if locked > 0 {
+            self.hooks.reporter.detail(format_args!(
+                "{locked} 个 {SVN_NEEDS_LOCK} 文件已标记为 lockable"
+            ));
+        }
+        let binary = sync_mime_types(&props, svn_dir, git_dir)?;
+        if binary > 0 {
+            self.hooks.reporter.detail(format_args!(
+                "{binary} 个二进制 {SVN_MIME_TYPE} 文件已标记为 binary"
+            ));
+        }
+        if self.commits.strip_keywords {
+            let stripped = strip_keywords(&props, svn_dir, git_dir)?;
+            if stripped > 0 {
+                self.hooks.reporter.detail(format_args!(
+                    "{stripped} 个 {SVN_KEYWORDS} 文件已还原展开的关键字"
+                ));
+            }
+        }
+        let eol = sync_eol_styles(&props, svn_dir, git_dir)?;
+        if eol > 0 {
+            self.hooks.reporter.detail(format_args!(
+                "{eol} 个 {SVN_EOL_STYLE} 文件已写入换行符属性"
+            ));
+        }
+        let ignored = sync_ignores(&props, svn_dir, git_dir)?;
+        if ignored > 0 {
+            self.hooks.reporter.detail(format_args!(
+                "{ignored} 个目录的 {SVN_IGNORE} 规则已更新到 .gitignore"
+            ));
+        }
+        Ok(props)
+    }
+
+    fn ensure_git_conflict_free(&self) -> Result<()> {
+        let status = self.git_operations.status(&self.config.git_dir)?;
+        if has_conflict_entries(&status) {
+            if self.config.policies.conflict == ConflictPolicy::Reset {
+                let head = self
+                    .git_operations
+                    .rev_parse_head(&self.config.git_dir)
+                    .ok();
+                self.git_operations
+                    .reset_to(&self.config.git_dir, head.as_deref())?;
+                eprintln!("检测到 Git 冲突状态，按策略已把暂存区重置到 HEAD");
+                return Ok(());
+            }
+            return Err(SyncError::App(
+                "检测到 Git 冲突状态（如 UU/AA/DU），已停止后续同步".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 在 span 上记录同步结果
+fn record_span_result(span: &Span, result: &Result<usize>) {
+    match result {
+        Ok(revisions) => span.set_attribute("svn2git.revisions", revisions),
+        Err(e) => span.record_error(e),
+    }
+}
+
+/// 为同步某个版本时的错误附加版本和回滚信息，保留原始错误的类型
+///
+/// 冲突错误本身带有版本号，没有回滚信息时原样返回
+fn revision_error(
+    index: usize,
+    rev: &str,
+    error: SyncError,
+    rollback: Option<String>,
+) -> SyncError {
+    match error {
+        e @ SyncError::SvnConflict { .. } if rollback.is_none() => e,
+        e => SyncError::Revision {
+            index,
+            rev: rev.to_string(),
+            source: Box::new(e),
+            rollback,
+        },
+    }
+}
+
+/// 解析为绝对路径，文件还不存在时只解析所在的目录
+fn resolve_path(path: &Path) -> PathBuf {
+    if let Ok(path) = path.canonicalize() {
+        return path;
+    }
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    match (
+        absolute.parent().and_then(|dir| dir.canonicalize().ok()),
+        absolute.file_name(),
+    ) {
+        (Some(dir), Some(name)) => dir.join(name),
+        _ => absolute,
+    }
+}
+
+/// 单个版本更新用时过长时提示网络较慢
+fn report_slow_update(rev: &str, elapsed: Duration) {
+    if elapsed > SLOW_UPDATE {
+        println!(
+            "svn update 到 r{rev} 用时 {} 秒，网络较慢，请耐心等待",
+            elapsed.as_secs()
+        );
+    }
+}
+
+/// 合并连续的版本
+///
+/// 使用最后一个版本的版本号、作者和时间，提交说明列出每个版本的摘要；
+/// 变更路径取并集，同一路径保留最后一次的变更类型
+fn merge_logs(logs: &[&SvnLog]) -> SvnLog {
+    let (first, last) = (logs[0], logs[logs.len() - 1]);
+    let mut message = format!(
+        "合并 {} 个连续版本 r{}-r{}\n",
+        logs.len(),
+        first.version,
+        last.version
+    );
+    let mut paths: Vec<SvnChangedPath> = Vec::new();
+    for log in logs {
+        message.push_str(&format!(
+            "\nr{}: {}",
+            log.version,
+            summarize_message(&log.message)
+        ));
+        for changed in &log.paths {
+            match paths.iter_mut().find(|p| p.path == changed.path) {
+                Some(existing) => *existing = changed.clone(),
+                None => paths.push(changed.clone()),
+            }
+        }
+    }
+    SvnLog {
+        message,
+        paths,
+        ..last.clone()
+    }
+}
+
+fn summarize_message(message: &str) -> String {
+    let trimmed = message.trim();
+    if trimmed.is_empty() {
+        return "(空提交说明)".to_string();
+    }
+
+    let first_line = trimmed.lines().next().unwrap_or_default().trim();
+    const MAX_CHARS: usize = 80;
+    if first_line.chars().count() <= MAX_CHARS {
+        return first_line.to_string();
+    }
+
+    let mut shortened: String = first_line.chars().take(MAX_CHARS).collect();
     shortened.push_str("...");
     shortened
 }
 
-fn build_git_commit_message(svn_message: &str) -> String {
-    let trimmed = svn_message.trim();
-    if trimmed.is_empty() {
-        "SVN: <空>".to_string()
-    } else {
-        format!("SVN: {trimmed}")
+/// 提交标题是否由本工具生成（同步的版本或自动初始化的初始提交）
+///
+/// 除当前前缀外也认默认的 `SVN: `，中途修改过前缀的仓库仍能识别之前同步的提交
+fn is_svn2git_commit(summary: &str, prefix: &str) -> bool {
+    (!prefix.is_empty() && summary.starts_with(prefix))
+        || summary.starts_with(DEFAULT_MESSAGE_PREFIX)
+        || summary.starts_with(INIT_COMMIT_PREFIX)
+}
+
+/// 生成 Git 提交说明：前缀加上去掉首尾空白的 SVN 提交说明
+///
+/// # 参数
+///
+/// * `svn_message`: SVN 提交说明
+/// * `prefix`: 提交标题的前缀，可以为空
+pub(crate) fn build_git_commit_message(svn_message: &str, prefix: &str) -> String {
+    match svn_message.trim() {
+        "" => format!("{prefix}<空>"),
+        trimmed => format!("{prefix}{trimmed}"),
+    }
+}
+
+/// 生成 git-svn 格式的版本记录 `git-svn-id: <URL>@<版本> <仓库UUID>`
+///
+/// # 参数
+///
+/// * `url`: SVN 工作副本的 URL
+/// * `rev`: SVN 版本
+/// * `uuid`: SVN 仓库 UUID
+pub fn build_git_svn_id(url: &str, rev: &str, uuid: &str) -> String {
+    format!("git-svn-id: {url}@{rev} {uuid}")
+}
+
+/// 解析提交说明中的 git-svn-id 记录
+///
+/// 与 git-svn 相同，只识别以 `git-svn-id: ` 开头的最后一个这样的行
+///
+/// # 返回
+///
+/// `(URL, 版本, 仓库UUID)`，没有记录或格式不对时为 `None`
+pub fn parse_git_svn_id(message: &str) -> Option<(&str, &str, &str)> {
+    let line = message
+        .lines()
+        .rev()
+        .find_map(|line| line.trim().strip_prefix("git-svn-id: "))?;
+    let (location, uuid) = line.trim().rsplit_once(' ')?;
+    let (url, rev) = location.rsplit_once('@')?;
+    rev.parse::<u64>().ok()?;
+    Some((url, rev, uuid))
+}
+
+/// 生成记录 SVN 元数据的 Git note 内容
+fn build_svn_note(log: &SvnLog) -> String {
+    let mut note = format!("SVN-Revision: {}\n", log.version);
+    if !log.author.is_empty() {
+        note.push_str(&format!("SVN-Author: {}\n", log.author));
+    }
+    if !log.paths.is_empty() {
+        note.push_str("Changed-Paths:\n");
+        for path in &log.paths {
+            note.push_str(&format!("  {} {}\n", path.action, path.path));
+        }
+    }
+    note
+}
+
+fn limit_logs(logs: Vec<crate::ops::SvnLog>, limit: Option<usize>) -> Vec<crate::ops::SvnLog> {
+    match limit {
+        Some(n) => logs.into_iter().take(n).collect(),
+        None => logs,
+    }
+}
+
+/// 日志中指定操作（如删除 `D`、替换 `R`）的路径，转换为相对 Git 仓库根目录的路径
+///
+/// # 参数
+///
+/// * `log` - SVN 日志
+/// * `actions` - 需要的变更操作
+/// * `wc_path` - 工作副本在仓库中的路径（如 `/trunk`，仓库根目录为空）
+/// * `git_prefix` - 工作副本在 Git 仓库中的相对目录
+///
+/// # 返回值
+///
+/// 去重后的路径；工作副本以外的路径和工作副本根目录本身会被忽略
+fn changed_paths_in_git(
+    log: &SvnLog,
+    actions: &[&str],
+    wc_path: &str,
+    git_prefix: &str,
+) -> Vec<String> {
+    let mut paths = Vec::new();
+    for changed in &log.paths {
+        if !actions.contains(&changed.action.as_str()) {
+            continue;
+        }
+        let Some(relative) = changed
+            .path
+            .strip_prefix(wc_path)
+            .and_then(|rest| rest.strip_prefix('/'))
+            .filter(|rest| !rest.is_empty())
+        else {
+            continue;
+        };
+        let path = format!("{git_prefix}{relative}");
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// `git status --porcelain` 输出中是否有指定路径（或其下级路径）的更改
+fn status_touches_paths(status: &str, paths: &[String]) -> bool {
+    status
+        .lines()
+        .filter(|line| line.len() > 3)
+        .flat_map(|line| line[3..].split(" -> "))
+        .map(|file| file.trim_matches('"'))
+        .any(|file| {
+            paths.iter().any(|path| {
+                file == path
+                    || file
+                        .strip_prefix(path.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+        })
+}
+
+fn has_conflict_entries(status: &str) -> bool {
+    status.lines().any(|line| {
+        if line.len() < 2 {
+            return false;
+        }
+        matches!(&line[..2], "DD" | "AU" | "UD" | "UA" | "DU" | "AA" | "UU")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::RefCell,
+        collections::BTreeMap,
+        path::Path,
+        path::PathBuf,
+        rc::Rc,
+        str::FromStr,
+        sync::{Arc, Mutex},
+    };
+
+    use chrono::{FixedOffset, NaiveDate};
+
+    use crate::{
+        config::{
+            DirtyTreePolicy, ErrorPolicy, HistoryManager, HistoryRecord, MirrorMarker,
+            MockFileStorage, PlannedRevision, RepoIdentity, STATE_REF, SyncCheckpoint, SyncConfig,
+            SyncPlan, SyncStartState,
+        },
+        depth::{SparseScope, SvnDepth},
+        error::SyncError,
+        interactor::{ConfirmThreshold, MockUserInteractor},
+        layout::TRUNK_BRANCH,
+        lock::SyncLock,
+        message::{DEFAULT_MAX_AUTHOR_CHARS, MessageLimits},
+        notify::{Notifier, SyncReport},
+        ops::{
+            CommitMetadata, GitCapabilities, GitCommitEntry, GitOperations, GitSignature,
+            MockSvnOperations, SvnChangedPath, SvnLog, SvnOperations,
+        },
+        progress::{ProgressState, SyncProgress},
+        sync_options::{CommitPolicy, PublishPolicy, RevisionPolicy},
+    };
+
+    use super::{
+        GIT_REMOTE, SVN_NOTES_REF, SyncRunOptions, SyncTool, build_git_commit_message,
+        build_git_svn_id, build_svn_note, changed_paths_in_git, has_conflict_entries, limit_logs,
+        merge_logs, parse_git_svn_id, status_touches_paths, summarize_message,
+    };
+
+    struct TestGitState {
+        add_all_calls: usize,
+        commit_messages: Vec<String>,
+        status_calls: usize,
+        status_output: String,
+        notes: Vec<(String, String, String)>,
+        added_paths: Vec<Vec<String>>,
+        tree: BTreeMap<String, String>,
+        resets: Vec<Option<String>>,
+        fail_notes: bool,
+        /// 写入 note 时返回认证失败的 git 命令错误
+        deny_notes: bool,
+        commit_metadata: Vec<CommitMetadata>,
+        executables: Vec<String>,
+        branch_ops: Vec<String>,
+        tags: Vec<(String, String, String, Option<GitSignature>)>,
+        remotes: Vec<(String, String)>,
+        pushes: Vec<String>,
+        fail_push: bool,
+        not_repository: bool,
+        log_output: String,
+        root_commit: String,
+        bundles: Vec<PathBuf>,
+        blob_refs: BTreeMap<String, String>,
+        stash_ops: Vec<&'static str>,
+        capabilities: GitCapabilities,
+    }
+
+    struct TestGitOperations {
+        state: Rc<RefCell<TestGitState>>,
+    }
+
+    impl TestGitOperations {
+        fn new(status_output: &str) -> (Self, Rc<RefCell<TestGitState>>) {
+            let state = Rc::new(RefCell::new(TestGitState {
+                add_all_calls: 0,
+                commit_messages: Vec::new(),
+                status_calls: 0,
+                status_output: status_output.to_string(),
+                notes: Vec::new(),
+                added_paths: Vec::new(),
+                tree: BTreeMap::new(),
+                resets: Vec::new(),
+                fail_notes: false,
+                deny_notes: false,
+                commit_metadata: Vec::new(),
+                executables: Vec::new(),
+                branch_ops: Vec::new(),
+                tags: Vec::new(),
+                remotes: Vec::new(),
+                pushes: Vec::new(),
+                fail_push: false,
+                not_repository: false,
+                log_output: String::new(),
+                root_commit: "hash0".to_string(),
+                bundles: Vec::new(),
+                blob_refs: BTreeMap::new(),
+                stash_ops: Vec::new(),
+                capabilities: GitCapabilities::ALL,
+            }));
+            (
+                Self {
+                    state: state.clone(),
+                },
+                state,
+            )
+        }
+    }
+
+    impl GitOperations for TestGitOperations {
+        fn init(&self, _path: &Path) -> crate::error::Result<()> {
+            self.state.borrow_mut().not_repository = false;
+            Ok(())
+        }
+
+        fn config_user(&self, _path: &Path, _name: &str, _email: &str) -> crate::error::Result<()> {
+            Ok(())
+        }
+
+        fn add_all(&self, path: &Path) -> crate::error::Result<()> {
+            let _ = path;
+            self.state.borrow_mut().add_all_calls += 1;
+            Ok(())
+        }
+
+        fn add_paths(&self, _path: &Path, files: &[String]) -> crate::error::Result<()> {
+            self.state.borrow_mut().added_paths.push(files.to_vec());
+            Ok(())
+        }
+
+        fn set_executable(&self, _path: &Path, files: &[String]) -> crate::error::Result<()> {
+            self.state
+                .borrow_mut()
+                .executables
+                .extend(files.iter().cloned());
+            Ok(())
+        }
+
+        fn commit(&self, _path: &Path, message: &str) -> crate::error::Result<()> {
+            let mut state = self.state.borrow_mut();
+            state.commit_messages.push(message.to_string());
+            state.commit_metadata.push(CommitMetadata::default());
+            Ok(())
+        }
+
+        fn commit_with(
+            &self,
+            _path: &Path,
+            message: &str,
+            metadata: &CommitMetadata,
+        ) -> crate::error::Result<()> {
+            let mut state = self.state.borrow_mut();
+            state.commit_messages.push(message.to_string());
+            state.commit_metadata.push(metadata.clone());
+            Ok(())
+        }
+
+        fn status(&self, _path: &Path) -> crate::error::Result<String> {
+            let mut state = self.state.borrow_mut();
+            state.status_calls += 1;
+            Ok(state.status_output.clone())
+        }
+
+        fn log(&self, _path: &Path, _count: Option<usize>) -> crate::error::Result<String> {
+            Ok(self.state.borrow().log_output.clone())
+        }
+
+        fn is_repository(&self, _path: &Path) -> crate::error::Result<bool> {
+            Ok(!self.state.borrow().not_repository)
+        }
+
+        fn is_clean(&self, _path: &Path) -> crate::error::Result<bool> {
+            Ok(self.state.borrow().status_output.trim().is_empty())
+        }
+
+        fn rev_parse_head(&self, _path: &Path) -> crate::error::Result<String> {
+            Ok(format!("hash{}", self.state.borrow().commit_messages.len()))
+        }
+
+        fn root_commit(&self, _path: &Path) -> crate::error::Result<String> {
+            Ok(self.state.borrow().root_commit.clone())
+        }
+
+        fn commit_history(
+            &self,
+            _path: &Path,
+            _notes_ref: &str,
+        ) -> crate::error::Result<Vec<GitCommitEntry>> {
+            Ok(self
+                .state
+                .borrow()
+                .commit_messages
+                .iter()
+                .enumerate()
+                .map(|(idx, message)| GitCommitEntry {
+                    hash: format!("hash{}", idx + 1),
+                    message: message.clone(),
+                    note: None,
+                })
+                .collect())
+        }
+
+        fn tree_blobs(
+            &self,
+            _path: &Path,
+            _rev: &str,
+        ) -> crate::error::Result<BTreeMap<String, String>> {
+            Ok(self.state.borrow().tree.clone())
+        }
+
+        fn hash_files(&self, _path: &Path, files: &[PathBuf]) -> crate::error::Result<Vec<String>> {
+            Ok(vec![String::new(); files.len()])
+        }
+
+        fn add_note(
+            &self,
+            _path: &Path,
+            notes_ref: &str,
+            commit: &str,
+            note: &str,
+        ) -> crate::error::Result<()> {
+            if self.state.borrow().fail_notes {
+                return Err(crate::error::SyncError::App("note 写入失败".into()));
+            }
+            if self.state.borrow().deny_notes {
+                return Err(crate::error::SyncError::GitCommand {
+                    action: "推送 note".into(),
+                    program: "git".into(),
+                    args: "notes add".into(),
+                    exit_code: Some(128),
+                    stderr: "fatal: Authentication failed".into(),
+                });
+            }
+            self.state.borrow_mut().notes.push((
+                notes_ref.to_string(),
+                commit.to_string(),
+                note.to_string(),
+            ));
+            Ok(())
+        }
+
+        fn reset_to(&self, _path: &Path, commit: Option<&str>) -> crate::error::Result<()> {
+            self.state
+                .borrow_mut()
+                .resets
+                .push(commit.map(str::to_string));
+            Ok(())
+        }
+
+        fn stash(&self, _path: &Path, _message: &str) -> crate::error::Result<bool> {
+            let mut state = self.state.borrow_mut();
+            state.stash_ops.push("stash");
+            Ok(!state.status_output.is_empty())
+        }
+
+        fn stash_pop(&self, _path: &Path) -> crate::error::Result<()> {
+            self.state.borrow_mut().stash_ops.push("pop");
+            Ok(())
+        }
+
+        fn create_branch(
+            &self,
+            _path: &Path,
+            name: &str,
+            start: Option<&str>,
+        ) -> crate::error::Result<()> {
+            self.state
+                .borrow_mut()
+                .branch_ops
+                .push(format!("create {name} {}", start.unwrap_or("HEAD")));
+            Ok(())
+        }
+
+        fn checkout_branch(&self, _path: &Path, name: &str) -> crate::error::Result<()> {
+            let mut state = self.state.borrow_mut();
+            let created = format!("create {name} ");
+            if name != TRUNK_BRANCH && !state.branch_ops.iter().any(|op| op.starts_with(&created)) {
+                return Err(SyncError::App(format!("Git分支 {name} 不存在")));
+            }
+            state.branch_ops.push(format!("checkout {name}"));
+            Ok(())
+        }
+
+        fn tag(
+            &self,
+            _path: &Path,
+            name: &str,
+            target: &str,
+            message: &str,
+            tagger: Option<&GitSignature>,
+        ) -> crate::error::Result<()> {
+            self.state.borrow_mut().tags.push((
+                name.to_string(),
+                target.to_string(),
+                message.to_string(),
+                tagger.cloned(),
+            ));
+            Ok(())
+        }
+
+        fn add_remote(&self, _path: &Path, name: &str, url: &str) -> crate::error::Result<()> {
+            self.state
+                .borrow_mut()
+                .remotes
+                .push((name.to_string(), url.to_string()));
+            Ok(())
+        }
+
+        fn push(&self, _path: &Path, remote: &str) -> crate::error::Result<()> {
+            let mut state = self.state.borrow_mut();
+            if state.fail_push {
+                return Err(SyncError::App("推送被拒绝".into()));
+            }
+            state.pushes.push(remote.to_string());
+            Ok(())
+        }
+
+        fn bundle(&self, _path: &Path, file: &Path) -> crate::error::Result<()> {
+            self.state.borrow_mut().bundles.push(file.to_path_buf());
+            Ok(())
+        }
+
+        fn write_blob_ref(
+            &self,
+            _path: &Path,
+            reference: &str,
+            content: &str,
+        ) -> crate::error::Result<()> {
+            self.state
+                .borrow_mut()
+                .blob_refs
+                .insert(reference.to_string(), content.to_string());
+            Ok(())
+        }
+
+        fn read_blob_ref(
+            &self,
+            _path: &Path,
+            reference: &str,
+        ) -> crate::error::Result<Option<String>> {
+            Ok(self.state.borrow().blob_refs.get(reference).cloned())
+        }
+
+        fn capabilities(&self) -> GitCapabilities {
+            self.state.borrow().capabilities
+        }
+    }
+
+    fn create_history_manager(expect_save_count: usize) -> HistoryManager<MockFileStorage> {
+        let mut storage = MockFileStorage::new();
+        storage.expect_load().returning(|| Ok(vec![]));
+        if expect_save_count > 0 {
+            storage
+                .expect_save()
+                .times(expect_save_count)
+                .returning(|_| Ok(()));
+        }
+        HistoryManager::new(storage).unwrap()
+    }
+
+    fn create_svn_ops(logs: &[(&str, &str)]) -> MockSvnOperations {
+        let svn_ops = MockSvnOperations::new();
+        for (version, message) in logs {
+            svn_ops.add_log_to_mock(&PathBuf::from("svn_dir"), version, message);
+        }
+        svn_ops
+    }
+
+    fn create_config() -> SyncConfig {
+        SyncConfig::new(
+            PathBuf::from_str("svn_dir").unwrap(),
+            PathBuf::from_str("git_dir").unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_run_success_with_mock_svn_and_git() {
+        let config = create_config();
+        let history = create_history_manager(2);
+
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+
+        let svn_ops = create_svn_ops(&[("1", "初始提交"), ("2", "修复问题")]);
+
+        let (git_ops_impl, git_state) = TestGitOperations::new("");
+        let git_ops = Box::new(git_ops_impl);
+        let mut tool = SyncTool::with_svn_operations(
+            config,
+            history,
+            Box::new(interactor),
+            git_ops,
+            Box::new(svn_ops.clone()),
+        );
+
+        let result = tool.run();
+        assert!(result.is_ok());
+        assert_eq!(svn_ops.updated_revisions(), vec!["1", "2"]);
+        assert_eq!(git_state.borrow().add_all_calls, 2);
+        assert_eq!(git_state.borrow().commit_messages.len(), 2);
+        // 每个版本只读取一次版本属性，各属性映射共用
+        assert_eq!(svn_ops.proplist_calls(), 2);
+    }
+
+    #[test]
+    fn test_run_should_keep_only_one_log_window_in_memory() {
+        let mut interactor = MockUserInteractor::new();
+        interactor
+            .expect_confirm_sync()
+            .withf(|logs, _| logs.iter().map(|log| log.version.as_str()).eq(["1", "2"]))
+            .returning(|_, _| true);
+        let svn_ops = create_svn_ops(&[
+            ("1", "m1"),
+            ("2", "m2"),
+            ("3", "m3"),
+            ("4", "m4"),
+            ("5", "m5"),
+        ]);
+        let (git_ops_impl, git_state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(5),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops.clone()),
+        )
+        .with_log_window(2);
+
+        tool.run().unwrap();
+        assert_eq!(svn_ops.updated_revisions(), ["1", "2", "3", "4", "5"]);
+        assert_eq!(git_state.borrow().commit_messages.len(), 5);
+        // 计算待同步版本之后，每个窗口从上一个窗口的最后一个版本继续获取
+        let windows: Vec<_> = svn_ops
+            .log_queries()
+            .into_iter()
+            .filter(|query| query.limit.is_some_and(|limit| limit <= 2))
+            .map(|query| query.after_rev)
+            .collect();
+        assert_eq!(windows, [Some("2".to_string()), Some("4".to_string())]);
+    }
+
+    #[test]
+    fn test_run_cancel_should_not_update_or_save() {
+        let config = create_config();
+        let history = create_history_manager(0);
+
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| false);
+
+        let svn_ops = create_svn_ops(&[("10", "测试")]);
+
+        let (git_ops_impl, git_state) = TestGitOperations::new("");
+        let git_ops = Box::new(git_ops_impl);
+        let mut tool = SyncTool::with_svn_operations(
+            config,
+            history,
+            Box::new(interactor),
+            git_ops,
+            Box::new(svn_ops.clone()),
+        );
+
+        let result = tool.run();
+        assert!(result.is_ok());
+        assert!(svn_ops.updated_revisions().is_empty());
+        assert_eq!(git_state.borrow().add_all_calls, 0);
+    }
+
+    #[test]
+    fn test_run_should_return_error_when_svn_update_fails() {
+        let config = create_config();
+        let history = create_history_manager(0);
+
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+
+        let svn_ops = create_svn_ops(&[("3", "触发失败")]);
+        svn_ops.fail_update_at("3");
+
+        let (git_ops_impl, git_state) = TestGitOperations::new("");
+        let git_ops = Box::new(git_ops_impl);
+        let mut tool = SyncTool::with_svn_operations(
+            config,
+            history,
+            Box::new(interactor),
+            git_ops,
+            Box::new(svn_ops),
+        );
+
+        let result = tool.run();
+        assert!(result.is_err());
+        assert_eq!(git_state.borrow().add_all_calls, 0);
+    }
+
+    #[test]
+    fn test_run_should_revert_conflicts_and_return_conflict_error() {
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+
+        let svn_ops = create_svn_ops(&[("1", "m1"), ("2", "冲突")]);
+        svn_ops.inject_conflict("2", &["src/a.rs"]);
+
+        let (git_ops_impl, git_state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(1),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops.clone()),
+        );
+
+        let result = tool.run();
+        assert!(
+            matches!(&result, Err(SyncError::SvnConflict { rev, paths }) if rev == "2" && paths == &["src/a.rs"]),
+            "{result:?}"
+        );
+        assert_eq!(git_state.borrow().commit_messages, vec!["SVN: m1"]);
+        assert!(
+            svn_ops
+                .modified_paths(&PathBuf::from("svn_dir"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_run_dry_run_should_not_update_or_commit_or_save() {
+        let config = create_config();
+        let history = create_history_manager(0);
+
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().times(0);
+
+        let svn_ops = create_svn_ops(&[("11", "dry run")]);
+
+        let (git_ops_impl, git_state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            config,
+            history,
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops.clone()),
+        );
+
+        let result = tool.run_with_options(&SyncRunOptions {
+            dry_run: true,
+            ..Default::default()
+        });
+        assert!(result.is_ok());
+        assert!(svn_ops.updated_revisions().is_empty());
+        assert_eq!(git_state.borrow().add_all_calls, 0);
+        assert_eq!(git_state.borrow().commit_messages.len(), 0);
+        assert_eq!(git_state.borrow().status_calls, 0);
+    }
+
+    #[test]
+    fn test_run_limit_should_only_process_first_n_logs() {
+        let config = create_config();
+        let history = create_history_manager(1);
+
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+
+        let svn_ops = create_svn_ops(&[("1", "m1"), ("2", "m2")]);
+
+        let (git_ops_impl, git_state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            config,
+            history,
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops.clone()),
+        );
+
+        let result = tool.run_with_options(&SyncRunOptions {
+            limit: Some(1),
+            ..Default::default()
+        });
+        assert!(result.is_ok());
+        assert_eq!(svn_ops.updated_revisions(), vec!["1"]);
+        assert_eq!(git_state.borrow().add_all_calls, 1);
+        assert_eq!(git_state.borrow().commit_messages, vec!["SVN: m1"]);
+    }
+
+    #[test]
+    fn test_run_only_revs_should_sync_selected_revisions() {
+        let config = create_config();
+        let history = create_history_manager(2);
+
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+
+        let svn_ops = create_svn_ops(&[("1", "m1"), ("2", "m2"), ("3", "m3"), ("4", "m4")]);
+        let (git_ops_impl, git_state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            config,
+            history,
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops.clone()),
+        );
+
+        let err = tool
+            .run_with_options(&SyncRunOptions {
+                only_revs: Some("2,7".parse().unwrap()),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("r7 不在待同步的版本中"));
+        assert!(svn_ops.updated_revisions().is_empty());
+
+        tool.run_with_options(&SyncRunOptions {
+            only_revs: Some("2-3".parse().unwrap()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(svn_ops.updated_revisions(), vec!["2", "3"]);
+        assert_eq!(
+            git_state.borrow().commit_messages,
+            vec!["SVN: m2", "SVN: m3"]
+        );
+    }
+
+    #[test]
+    fn test_run_select_should_sync_only_revisions_chosen_by_user() {
+        let mut interactor = MockUserInteractor::new();
+        let mut seq = mockall::Sequence::new();
+        interactor
+            .expect_select_revisions()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_| Ok(Vec::new()));
+        interactor
+            .expect_select_revisions()
+            .times(1)
+            .in_sequence(&mut seq)
+            .withf(|logs| logs.len() == 4)
+            .returning(|_| Ok(vec!["1".into(), "3".into()]));
+        interactor
+            .expect_confirm_sync()
+            .times(1)
+            .withf(|logs, _| logs.iter().map(|log| log.version.as_str()).eq(["1", "3"]))
+            .returning(|_, _| true);
+
+        let svn_ops = create_svn_ops(&[("1", "m1"), ("2", "m2"), ("3", "m3"), ("4", "m4")]);
+        let (git_ops_impl, git_state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(2),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops.clone()),
+        );
+        let options = SyncRunOptions {
+            select: true,
+            ..Default::default()
+        };
+
+        // 什么都不选时取消同步
+        tool.run_with_options(&options).unwrap();
+        assert!(svn_ops.updated_revisions().is_empty());
+
+        tool.run_with_options(&options).unwrap();
+        assert_eq!(svn_ops.updated_revisions(), vec!["1", "3"]);
+        assert_eq!(
+            git_state.borrow().commit_messages,
+            vec!["SVN: m1", "SVN: m3"]
+        );
+    }
+
+    #[test]
+    fn test_run_should_apply_message_rewrites() {
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let mut config = create_config();
+        config.message_rewrites = vec![
+            r"^\[T-\d+\]\s*=>".parse().unwrap(),
+            r"#(\d+)=>https://issues.example.com/$1".parse().unwrap(),
+        ];
+        let (git_ops_impl, git_state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            config,
+            create_history_manager(2),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(create_svn_ops(&[("1", "[T-9] fix #12"), ("2", "plain")])),
+        );
+
+        tool.run().unwrap();
+        assert_eq!(
+            git_state.borrow().commit_messages,
+            vec!["SVN: fix https://issues.example.com/12", "SVN: plain"]
+        );
+    }
+
+    #[test]
+    fn test_run_should_use_configured_message_prefix() {
+        let run = |prefix: &str| {
+            let mut interactor = MockUserInteractor::new();
+            interactor.expect_confirm_sync().returning(|_, _| true);
+            let mut config = create_config();
+            config.message_prefix = prefix.to_string();
+            let (git_ops_impl, git_state) = TestGitOperations::new("");
+            let mut tool = SyncTool::with_svn_operations(
+                config,
+                create_history_manager(2),
+                Box::new(interactor),
+                Box::new(git_ops_impl),
+                Box::new(create_svn_ops(&[("1", "fix"), ("2", " ")])),
+            );
+            tool.run().unwrap();
+            git_state.borrow().commit_messages.clone()
+        };
+        assert_eq!(run("[svn] "), vec!["[svn] fix", "[svn] <空>"]);
+        assert_eq!(run(""), vec!["fix", "<空>"]);
+    }
+
+    #[test]
+    fn test_run_should_confirm_only_above_threshold() {
+        let run = |logs: &[(&str, &str)], confirmations: usize, saves: usize| {
+            let mut interactor = MockUserInteractor::new();
+            interactor
+                .expect_confirm_sync()
+                .times(confirmations)
+                .returning(|_, _| false);
+            let svn_ops = create_svn_ops(logs);
+            let (git_ops_impl, git_state) = TestGitOperations::new("");
+            let mut tool = SyncTool::with_svn_operations(
+                create_config(),
+                create_history_manager(saves),
+                Box::new(interactor),
+                Box::new(git_ops_impl),
+                Box::new(svn_ops),
+            )
+            .with_revision_policy(RevisionPolicy {
+                confirm_threshold: ConfirmThreshold {
+                    revisions: Some(2),
+                    changed_paths: None,
+                },
+                ..Default::default()
+            });
+            tool.run().unwrap();
+            git_state.borrow().commit_messages.len()
+        };
+
+        // 未超过阈值时自动同步，超过时询问（这里拒绝）
+        assert_eq!(run(&[("1", "m1"), ("2", "m2")], 0, 2), 2);
+        assert_eq!(run(&[("1", "m1"), ("2", "m2"), ("3", "m3")], 1, 0), 0);
+    }
+
+    #[test]
+    fn test_run_preview_diff_should_pass_revision_diffs_to_confirmation() {
+        let mut interactor = MockUserInteractor::new();
+        interactor
+            .expect_confirm_sync()
+            .times(1)
+            .withf(|_, diffs| {
+                diffs.len() == 1
+                    && diffs[0].version == "1"
+                    && diffs[0]
+                        .entries
+                        .iter()
+                        .map(|entry| (entry.status(), entry.path.as_str()))
+                        .eq([("A ".to_string(), "trunk/a.txt")])
+            })
+            .returning(|_, _| false);
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_svn_log_to_mock(
+            &PathBuf::from("svn_dir"),
+            SvnLog {
+                version: "1".into(),
+                message: "m1".into(),
+                paths: vec![SvnChangedPath {
+                    action: "A".into(),
+                    path: "/trunk/a.txt".into(),
+                    kind: "file".into(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        );
+        let (git_ops_impl, _git_state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(0),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops.clone()),
+        );
+
+        tool.run_with_options(&SyncRunOptions {
+            preview_diff: true,
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(svn_ops.updated_revisions().is_empty());
+    }
+
+    #[test]
+    fn test_run_should_reject_features_unsupported_by_git_backend() {
+        let svn_ops = create_svn_ops(&[("1", "m1")]);
+        let (git_ops_impl, git_state) = TestGitOperations::new("");
+        git_state.borrow_mut().capabilities = GitCapabilities {
+            notes: false,
+            ..GitCapabilities::ALL
+        };
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(0),
+            Box::new(MockUserInteractor::new()),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops.clone()),
+        )
+        .with_commit_policy(CommitPolicy {
+            git_notes: true,
+            ..Default::default()
+        });
+
+        let err = tool
+            .run_with_options(&SyncRunOptions {
+                dry_run: true,
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Application error: 当前 Git 后端不支持Git notes，无法使用 --git-notes"
+        );
+        assert!(tool.run().is_err());
+        assert!(svn_ops.updated_revisions().is_empty());
+        assert!(git_state.borrow().commit_messages.is_empty());
+    }
+
+    #[test]
+    fn test_run_should_reject_env_when_git_backend_cannot_use_it() {
+        let svn_ops = create_svn_ops(&[("1", "m1")]);
+        let (git_ops_impl, git_state) = TestGitOperations::new("");
+        git_state.borrow_mut().capabilities = GitCapabilities {
+            command_env: false,
+            ..GitCapabilities::ALL
+        };
+        let mut config = create_config();
+        config
+            .env
+            .insert("GIT_SSH_COMMAND".to_string(), "ssh -i key".to_string());
+        let mut tool = SyncTool::with_svn_operations(
+            config,
+            create_history_manager(0),
+            Box::new(MockUserInteractor::new()),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops.clone()),
+        );
+
+        let err = tool.run().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Application error: 当前 Git 后端不支持附带环境变量，无法使用 history env 设置的环境变量"
+        );
+        assert!(svn_ops.updated_revisions().is_empty());
+    }
+
+    #[test]
+    fn test_run_should_stop_when_git_conflict_detected() {
+        let config = create_config();
+        let history = create_history_manager(0);
+
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+
+        let svn_ops = create_svn_ops(&[("5", "conflict")]);
+
+        let (git_ops_impl, git_state) = TestGitOperations::new("UU conflict.txt");
+        let mut tool = SyncTool::with_svn_operations(
+            config,
+            history,
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        );
+
+        let result = tool.run();
+        assert!(result.is_err());
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("检测到 Git 冲突状态"));
+        assert_eq!(git_state.borrow().status_calls, 1);
+        assert_eq!(git_state.borrow().add_all_calls, 0);
+    }
+
+    #[test]
+    fn test_run_should_continue_after_last_synced_rev_and_record_progress() {
+        let config = create_config();
+
+        let saved: Arc<Mutex<Vec<HistoryRecord>>> = Arc::new(Mutex::new(Vec::new()));
+        let saved_clone = saved.clone();
+        let mut storage = MockFileStorage::new();
+        storage.expect_load().returning(|| {
+            let mut record =
+                HistoryRecord::new(1, PathBuf::from("svn_dir"), PathBuf::from("git_dir"));
+            record.set_sync_state("1", "hash-old");
+            Ok(vec![record])
+        });
+        // 开始时保存检查点，之后每个版本保存一次，结束时保存同步结果
+        storage.expect_save().times(4).returning(move |records| {
+            *saved_clone.lock().unwrap() = records.to_vec();
+            Ok(())
+        });
+        let history = HistoryManager::new(storage).unwrap();
+
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+
+        let svn_ops = create_svn_ops(&[("1", "m1"), ("2", "m2"), ("3", "m3")]);
+        let (git_ops_impl, git_state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            config,
+            history,
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops.clone()),
+        );
+
+        tool.run().unwrap();
+        assert_eq!(svn_ops.updated_revisions(), vec!["2", "3"]);
+        assert_eq!(
+            git_state.borrow().commit_messages,
+            vec!["SVN: m2", "SVN: m3"]
+        );
+
+        let saved = saved.lock().unwrap();
+        assert_eq!(saved[0].last_synced_rev(), Some("3"));
+        assert_eq!(saved[0].last_git_hash(), Some("hash2"));
+        assert!(!saved[0].has_checkpoint());
+        let last_run = saved[0].last_run().unwrap();
+        assert!(last_run.success);
+        assert_eq!(last_run.revisions, 2);
+    }
+
+    fn create_recording_history(
+        record: HistoryRecord,
+        saved: Arc<Mutex<Vec<HistoryRecord>>>,
+    ) -> HistoryManager<MockFileStorage> {
+        let mut storage = MockFileStorage::new();
+        storage
+            .expect_load()
+            .returning(move || Ok(vec![record.clone()]));
+        storage.expect_save().returning(move |records| {
+            *saved.lock().unwrap() = records.to_vec();
+            Ok(())
+        });
+        HistoryManager::new(storage).unwrap()
+    }
+
+    #[test]
+    fn test_run_should_write_state_marker_and_recover_progress_from_it() {
+        let run = |marker: Option<String>| {
+            let saved = Arc::new(Mutex::new(Vec::new()));
+            let history = create_recording_history(
+                HistoryRecord::new(1, PathBuf::from("svn_dir"), PathBuf::from("git_dir")),
+                saved.clone(),
+            );
+            let mut interactor = MockUserInteractor::new();
+            interactor.expect_confirm_sync().returning(|_, _| true);
+            let (git_ops_impl, git_state) = TestGitOperations::new("");
+            if let Some(marker) = marker {
+                git_state
+                    .borrow_mut()
+                    .blob_refs
+                    .insert(STATE_REF.to_string(), marker);
+            }
+            let svn_ops = create_svn_ops(&[("1", "m1"), ("2", "m2"), ("3", "m3")]);
+            let mut tool = SyncTool::with_svn_operations(
+                create_config(),
+                history,
+                Box::new(interactor),
+                Box::new(git_ops_impl),
+                Box::new(svn_ops.clone()),
+            );
+            tool.run().unwrap();
+            let marker = git_state.borrow().blob_refs.get(STATE_REF).cloned();
+            (svn_ops.updated_revisions(), marker.unwrap())
+        };
+
+        let (updated, marker) = run(None);
+        assert_eq!(updated, vec!["1", "2", "3"]);
+        let parsed = MirrorMarker::parse(&marker).unwrap();
+        assert_eq!(parsed.pair_id, 1);
+        assert_eq!(parsed.last_synced_rev, "3");
+        assert!(parsed.svn_uuid.is_some());
+
+        // 配置丢失后从标记中的版本之后继续
+        let recovered = MirrorMarker {
+            last_synced_rev: "2".into(),
+            ..parsed.clone()
+        };
+        let (updated, _) = run(Some(recovered.render()));
+        assert_eq!(updated, vec!["3"]);
+
+        // 属于其他 SVN 仓库的标记不恢复
+        let other = MirrorMarker {
+            svn_uuid: Some("other-uuid".into()),
+            ..recovered
+        };
+        let (updated, _) = run(Some(other.render()));
+        assert_eq!(updated, vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_run_should_bind_and_verify_repository_identity() {
+        const UUID: &str = "00000000-0000-0000-0000-000000000000";
+        let run = |identity: Option<RepoIdentity>| {
+            let mut record =
+                HistoryRecord::new(1, PathBuf::from("svn_dir"), PathBuf::from("git_dir"));
+            record.set_identity(identity);
+            let saved = Arc::new(Mutex::new(Vec::new()));
+            let mut interactor = MockUserInteractor::new();
+            interactor.expect_confirm_sync().returning(|_, _| true);
+            let (git_ops_impl, git_state) = TestGitOperations::new("");
+            let mut tool = SyncTool::with_svn_operations(
+                create_config(),
+                create_recording_history(record, saved.clone()),
+                Box::new(interactor),
+                Box::new(git_ops_impl),
+                Box::new(create_svn_ops(&[("1", "m1")])),
+            );
+            let result = tool.run();
+            let commits = git_state.borrow().commit_messages.len();
+            let saved = saved.lock().unwrap().first().cloned();
+            (result, commits, saved)
+        };
+        let identity = |svn_uuid: &str, git_root: &str| RepoIdentity {
+            svn_uuid: svn_uuid.into(),
+            git_root: Some(git_root.into()),
+        };
+
+        // 第一次同步时绑定
+        let (result, commits, saved) = run(None);
+        result.unwrap();
+        assert_eq!(commits, 1);
+        assert_eq!(saved.unwrap().identity(), Some(&identity(UUID, "hash0")));
+
+        run(Some(identity(UUID, "hash0"))).0.unwrap();
+
+        for swapped in [
+            identity("other-uuid", "hash0"),
+            identity(UUID, "other-root"),
+        ] {
+            let (result, commits, _) = run(Some(swapped));
+            let err = result.unwrap_err();
+            assert!(err.to_string().contains("history rebind"), "{err}");
+            assert_eq!(commits, 0);
+        }
+    }
+
+    #[test]
+    fn test_run_interrupted_should_keep_checkpoint() {
+        let saved: Arc<Mutex<Vec<HistoryRecord>>> = Arc::new(Mutex::new(Vec::new()));
+        let record = HistoryRecord::new(1, PathBuf::from("svn_dir"), PathBuf::from("git_dir"));
+        let history = create_recording_history(record, saved.clone());
+
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+
+        let svn_ops = create_svn_ops(&[("1", "m1"), ("2", "m2"), ("3", "m3")]);
+        svn_ops.fail_update_at("2");
+        let (git_ops_impl, _) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            history,
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        );
+
+        assert!(tool.run().is_err());
+
+        let saved = saved.lock().unwrap();
+        assert_eq!(saved[0].last_synced_rev(), Some("1"));
+        assert_eq!(
+            saved[0].checkpoint().unwrap().pending(),
+            ["2".to_string(), "3".to_string()]
+        );
+        let last_run = saved[0].last_run().unwrap();
+        assert!(!last_run.success);
+        assert_eq!(last_run.revisions, 1);
+        assert!(last_run.error.as_deref().unwrap().contains("r2"));
+    }
+
+    #[test]
+    fn test_run_with_rollback_on_failure_should_restore_pre_sync_state() {
+        let saved: Arc<Mutex<Vec<HistoryRecord>>> = Arc::new(Mutex::new(Vec::new()));
+        let record = HistoryRecord::new(1, PathBuf::from("svn_dir"), PathBuf::from("git_dir"));
+        let history = create_recording_history(record, saved.clone());
+
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+
+        let svn_ops = create_svn_ops(&[("1", "m1"), ("2", "m2"), ("3", "m3")]);
+        svn_ops.fail_update_at("2");
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            history,
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops.clone()),
+        )
+        .with_rollback_on_failure(true);
+
+        assert!(tool.run().is_err());
+
+        assert_eq!(state.borrow().commit_messages, vec!["SVN: m1"]);
+        assert_eq!(
+            state.borrow().resets.last(),
+            Some(&Some("hash0".to_string()))
+        );
+        assert_eq!(
+            svn_ops.info(&PathBuf::from("svn_dir")).unwrap().revision,
+            "0"
+        );
+        let saved = saved.lock().unwrap();
+        assert_eq!(saved[0].last_synced_rev(), None);
+        assert!(saved[0].revision_map().is_empty());
+        assert!(!saved[0].has_checkpoint());
+    }
+
+    #[test]
+    fn test_rollback_should_restore_state_recorded_in_checkpoint() {
+        let saved: Arc<Mutex<Vec<HistoryRecord>>> = Arc::new(Mutex::new(Vec::new()));
+        let mut record = HistoryRecord::new(1, PathBuf::from("svn_dir"), PathBuf::from("git_dir"));
+        record.set_sync_state("1", "hash1");
+        record.set_sync_state("2", "hash2");
+        let start = SyncStartState {
+            git_head: Some("hash1".into()),
+            svn_rev: "1".into(),
+            last_synced_rev: Some("1".into()),
+            last_git_hash: Some("hash1".into()),
+            mapped_revisions: 1,
+        };
+        record.set_checkpoint(Some(
+            SyncCheckpoint::new(vec!["3".into()]).with_start(start),
+        ));
+        let history = create_recording_history(record, saved.clone());
+
+        let svn_ops = create_svn_ops(&[("1", "m1"), ("2", "m2"), ("3", "m3")]);
+        svn_ops
+            .update_to_rev(&PathBuf::from("svn_dir"), "2")
+            .unwrap();
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            history,
+            Box::new(MockUserInteractor::new()),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops.clone()),
+        );
+
+        tool.rollback().unwrap();
+
+        assert_eq!(state.borrow().resets, vec![Some("hash1".to_string())]);
+        assert_eq!(
+            svn_ops.info(&PathBuf::from("svn_dir")).unwrap().revision,
+            "1"
+        );
+        let saved = saved.lock().unwrap();
+        assert_eq!(saved[0].last_synced_rev(), Some("1"));
+        assert_eq!(saved[0].revision_map().len(), 1);
+        assert!(!saved[0].has_checkpoint());
+        drop(saved);
+        assert!(tool.rollback().is_err());
+    }
+
+    #[test]
+    fn test_resume_should_sync_remaining_revisions_without_confirm() {
+        let saved: Arc<Mutex<Vec<HistoryRecord>>> = Arc::new(Mutex::new(Vec::new()));
+        let mut record = HistoryRecord::new(1, PathBuf::from("svn_dir"), PathBuf::from("git_dir"));
+        record.set_checkpoint(Some(SyncCheckpoint::new(vec![
+            "1".into(),
+            "2".into(),
+            "3".into(),
+        ])));
+        record.set_sync_state("1", "hash-old");
+        let history = create_recording_history(record, saved.clone());
+
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().times(0);
+
+        // 第 4 版不在检查点内，恢复时不应同步
+        let svn_ops = create_svn_ops(&[("1", "m1"), ("2", "m2"), ("3", "m3"), ("4", "m4")]);
+        let (git_ops_impl, git_state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            history,
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops.clone()),
+        );
+
+        tool.resume().unwrap();
+        assert_eq!(svn_ops.updated_revisions(), vec!["2", "3"]);
+        assert_eq!(
+            git_state.borrow().commit_messages,
+            vec!["SVN: m2", "SVN: m3"]
+        );
+
+        let saved = saved.lock().unwrap();
+        assert_eq!(saved[0].last_synced_rev(), Some("3"));
+        assert!(!saved[0].has_checkpoint());
+    }
+
+    #[test]
+    fn test_resume_without_checkpoint_should_fail() {
+        let config = create_config();
+        let history = create_history_manager(0);
+        let (git_ops_impl, _) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            config,
+            history,
+            Box::new(MockUserInteractor::new()),
+            Box::new(git_ops_impl),
+            Box::new(create_svn_ops(&[("1", "m1")])),
+        );
+
+        let err = tool.resume().unwrap_err().to_string();
+        assert!(err.contains("没有需要恢复的同步"));
+    }
+
+    #[test]
+    fn test_run_should_pass_date_window_to_svn_query() {
+        let history = create_history_manager(0);
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| false);
+
+        let svn_ops = create_svn_ops(&[("1", "m1")]);
+        let (git_ops_impl, _) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            history,
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops.clone()),
+        );
+
+        let since = NaiveDate::from_ymd_opt(2024, 1, 1);
+        let until = NaiveDate::from_ymd_opt(2024, 2, 1);
+        tool.run_with_options(&SyncRunOptions {
+            since,
+            until,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let queries = svn_ops.log_queries();
+        assert_eq!(queries.len(), 1);
+        assert_eq!(queries[0].since, since);
+        assert_eq!(queries[0].until, until);
+
+        // 起始日期不早于结束日期时直接报错
+        let result = tool.run_with_options(&SyncRunOptions {
+            since: until,
+            until: since,
+            ..Default::default()
+        });
+        assert!(result.is_err());
+        assert_eq!(svn_ops.log_queries().len(), 1);
+    }
+
+    #[test]
+    fn test_run_should_write_manifest_per_revision() {
+        let manifest_dir = tempfile::tempdir().unwrap();
+        let history = create_history_manager(2);
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+
+        let svn_ops = create_svn_ops(&[("1", "m1"), ("2", "m2")]);
+        let (git_ops_impl, _) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            history,
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        )
+        .with_commit_policy(CommitPolicy {
+            manifest_dir: Some(manifest_dir.path().to_path_buf()),
+            ..Default::default()
+        });
+
+        tool.run().unwrap();
+        assert!(manifest_dir.path().join("r1-hash1.sha256").exists());
+        assert!(manifest_dir.path().join("r2-hash2.sha256").exists());
+    }
+
+    #[test]
+    fn test_run_should_add_svn_notes_when_enabled() {
+        let history = create_history_manager(1);
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_svn_log_to_mock(
+            &PathBuf::from("svn_dir"),
+            SvnLog {
+                version: "7".into(),
+                message: "m7".into(),
+                author: "alice".into(),
+                paths: vec![SvnChangedPath {
+                    action: "M".into(),
+                    path: "/trunk/a.txt".into(),
+                    copy_from: None,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        );
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            history,
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        )
+        .with_commit_policy(CommitPolicy {
+            git_notes: true,
+            ..Default::default()
+        });
+
+        tool.run().unwrap();
+        let state = state.borrow();
+        assert_eq!(
+            state.notes,
+            vec![(
+                SVN_NOTES_REF.to_string(),
+                "hash1".to_string(),
+                "SVN-Revision: 7\nSVN-Author: alice\nChanged-Paths:\n  M /trunk/a.txt\n"
+                    .to_string()
+            )]
+        );
+        // 提交说明不包含元数据
+        assert_eq!(state.commit_messages, vec!["SVN: m7"]);
+    }
+
+    #[test]
+    fn test_build_svn_note_without_author_or_paths() {
+        let log = SvnLog {
+            version: "3".into(),
+            ..Default::default()
+        };
+        assert_eq!(build_svn_note(&log), "SVN-Revision: 3\n");
+    }
+
+    #[test]
+    fn test_run_with_changelist_should_only_update_and_commit_listed_files() {
+        let history = create_history_manager(2);
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+
+        let svn_ops = create_svn_ops(&[("1", "m1"), ("2", "m2")]);
+        svn_ops.add_to_changelist("ui", "src/ui.rs");
+        let svn_probe = svn_ops.clone();
+        let (git_ops_impl, git_state) = TestGitOperations::new(" M src/ui.rs\n");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            history,
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        )
+        .with_revision_policy(RevisionPolicy {
+            changelist: Some("ui".into()),
+            ..Default::default()
+        });
+
+        tool.run().unwrap();
+        assert_eq!(
+            svn_probe.path_updates(),
+            vec![
+                ("1".to_string(), vec!["src/ui.rs".to_string()]),
+                ("2".to_string(), vec!["src/ui.rs".to_string()]),
+            ]
+        );
+        let state = git_state.borrow();
+        assert_eq!(state.add_all_calls, 0);
+        assert_eq!(state.added_paths, vec![vec!["src/ui.rs".to_string()]; 2]);
+        assert_eq!(state.commit_messages.len(), 2);
+    }
+
+    #[test]
+    fn test_run_with_changelist_should_skip_commit_when_files_unchanged() {
+        let history = create_history_manager(1);
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+
+        let svn_ops = create_svn_ops(&[("1", "m1")]);
+        svn_ops.add_to_changelist("ui", "src/ui.rs");
+        let (git_ops_impl, git_state) = TestGitOperations::new(" M src/other.rs\n");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            history,
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        )
+        .with_revision_policy(RevisionPolicy {
+            changelist: Some("ui".into()),
+            ..Default::default()
+        });
+
+        tool.run().unwrap();
+        assert!(git_state.borrow().commit_messages.is_empty());
+    }
+
+    #[test]
+    fn test_run_with_empty_changelist_should_fail() {
+        let history = create_history_manager(0);
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+
+        let (git_ops_impl, _) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            history,
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(create_svn_ops(&[("1", "m1")])),
+        )
+        .with_revision_policy(RevisionPolicy {
+            changelist: Some("missing".into()),
+            ..Default::default()
+        });
+
+        let err = tool.run().unwrap_err().to_string();
+        assert!(err.contains("变更列表 missing 中没有文件"));
+    }
+
+    #[test]
+    fn test_run_should_stage_replaced_and_deleted_paths_before_add_all() {
+        let history = create_history_manager(1);
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+
+        let changed = |action: &str, path: &str| SvnChangedPath {
+            action: action.into(),
+            path: path.into(),
+            copy_from: None,
+            ..Default::default()
+        };
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_svn_log_to_mock(
+            &PathBuf::from("svn_dir"),
+            SvnLog {
+                version: "3".into(),
+                message: "m3".into(),
+                paths: vec![
+                    changed("R", "/lib"),
+                    changed("D", "/old.txt"),
+                    changed("D", "/untracked.txt"),
+                    changed("A", "/new.txt"),
+                ],
+                ..Default::default()
+            },
+        );
+        let (git_ops_impl, state) =
+            TestGitOperations::new(" D lib/a.rs\n D old.txt\n?? lib/b.rs\n?? new.txt\n");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            history,
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        );
+
+        tool.run().unwrap();
+        let state = state.borrow();
+        assert_eq!(
+            state.added_paths,
+            vec![vec!["lib".to_string(), "old.txt".to_string()]]
+        );
+        assert_eq!(state.add_all_calls, 1);
+        assert_eq!(state.commit_messages, vec!["SVN: m3"]);
+    }
+
+    #[test]
+    fn test_run_should_fail_when_deleted_paths_remain_in_git() {
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_svn_log_to_mock(
+            &PathBuf::from("svn_dir"),
+            SvnLog {
+                version: "4".into(),
+                message: "删除旧文件".into(),
+                paths: vec![
+                    SvnChangedPath {
+                        action: "D".into(),
+                        path: "/old".into(),
+                        copy_from: None,
+                        ..Default::default()
+                    },
+                    SvnChangedPath {
+                        action: "D".into(),
+                        path: "/gone.txt".into(),
+                        copy_from: None,
+                        ..Default::default()
+                    },
+                    SvnChangedPath {
+                        action: "R".into(),
+                        path: "/replaced.txt".into(),
+                        copy_from: None,
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+        );
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        state.borrow_mut().tree = ["old/a.txt", "older.txt", "replaced.txt"]
+            .into_iter()
+            .map(|file| (file.to_string(), String::new()))
+            .collect();
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(0),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        );
+
+        let err = tool.run().unwrap_err().to_string();
+        assert!(
+            err.contains("SVN r4 中删除的路径仍在 Git 提交中：old"),
+            "{err}"
+        );
+        assert!(!err.contains("gone.txt") && !err.contains("replaced.txt"));
+        // 提交被撤销，进度没有保存
+        assert!(err.contains("Git 仓库已恢复到同步该版本之前的状态"));
+        assert_eq!(state.borrow().resets, vec![Some("hash0".to_string())]);
+    }
+
+    #[test]
+    fn test_run_should_ignore_svn_metadata_when_svn_dir_is_git_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_log_to_mock(dir.path(), "1", "m1");
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            SyncConfig::new(dir.path().into(), dir.path().into()),
+            create_history_manager(1),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        );
+
+        tool.run().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(".gitignore")).unwrap(),
+            "# BEGIN svn2git svn-metadata\n.svn/\n# END svn2git svn-metadata\n"
+        );
+        assert_eq!(state.borrow().commit_messages, vec!["SVN: m1"]);
+    }
+
+    #[test]
+    fn test_run_should_exclude_state_files_inside_git_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_log_to_mock(dir.path(), "1", "m1");
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, _) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            SyncConfig::new(dir.path().into(), dir.path().into()),
+            create_history_manager(1),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        )
+        .with_lock_file(Some(dir.path().join("svn2git-a.lock")))
+        .with_state_files(vec![
+            dir.path().join("config.json"),
+            outside.path().join("other.json"),
+        ]);
+
+        tool.run().unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join(".git/info/exclude")).unwrap(),
+            "# BEGIN svn2git state-files\n/config.json\n/svn2git-a.lock\n# END svn2git state-files\n"
+        );
+        // 只写入本地的排除规则，不改动会被提交的 .gitignore 中用户的规则
+        assert!(
+            !std::fs::read_to_string(dir.path().join(".gitignore"))
+                .unwrap()
+                .contains("config.json")
+        );
+    }
+
+    #[test]
+    fn test_run_should_mark_svn_executables_in_git_index() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("build.sh"), "#!/bin/sh").unwrap();
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_log_to_mock(dir.path(), "1", "m1");
+        svn_ops.set_property("build.sh", crate::SVN_EXECUTABLE, "*");
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            SyncConfig::new(dir.path().into(), dir.path().into()),
+            create_history_manager(1),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        );
+
+        tool.run().unwrap();
+        let state = state.borrow();
+        assert_eq!(state.executables, vec!["build.sh"]);
+        assert_eq!(state.commit_messages, vec!["SVN: m1"]);
+    }
+
+    #[test]
+    fn test_run_should_rollback_revision_when_note_fails() {
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        state.borrow_mut().fail_notes = true;
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(0),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(create_svn_ops(&[("1", "m1"), ("2", "m2")])),
+        )
+        .with_commit_policy(CommitPolicy {
+            git_notes: true,
+            ..Default::default()
+        });
+
+        let err = tool.run().unwrap_err().to_string();
+        assert!(err.contains("同步第 1 条日志失败（SVN r1）"), "{err}");
+        assert!(
+            err.contains(
+                "note 写入失败（Git 仓库已恢复到同步该版本之前的状态，SVN 工作副本已恢复到 r0）"
+            ),
+            "{err}"
+        );
+        let state = state.borrow();
+        assert_eq!(state.commit_messages, vec!["SVN: m1"]);
+        assert_eq!(state.resets, vec![Some("hash0".to_string())]);
+    }
+
+    #[test]
+    fn test_run_should_keep_command_error_type_when_revision_fails() {
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        state.borrow_mut().deny_notes = true;
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(0),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(create_svn_ops(&[("1", "m1")])),
+        )
+        .with_commit_policy(CommitPolicy {
+            git_notes: true,
+            ..Default::default()
+        });
+
+        let err = tool.run().unwrap_err();
+        assert!(err.is_authentication_failure(), "{err}");
+        assert_eq!(err.exit_code(), Some(128));
+        assert!(
+            matches!(&err, SyncError::Revision { index: 1, rev, rollback: Some(_), .. } if rev == "1"),
+            "{err:?}"
+        );
+        assert!(
+            matches!(err.root_cause(), SyncError::GitCommand { .. }),
+            "{err:?}"
+        );
+        let message = err.to_string();
+        assert!(
+            message.starts_with("同步第 1 条日志失败（SVN r1）：推送 note失败"),
+            "{message}"
+        );
+        assert!(message.ends_with("SVN 工作副本已恢复到 r0）"), "{message}");
     }
-}
 
-fn limit_logs(logs: Vec<crate::ops::SvnLog>, limit: Option<usize>) -> Vec<crate::ops::SvnLog> {
-    match limit {
-        Some(n) => logs.into_iter().take(n).collect(),
-        None => logs,
+    #[test]
+    fn test_run_with_error_continue_policy_should_sync_remaining_revisions() {
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        state.borrow_mut().fail_notes = true;
+        let mut config = create_config();
+        config.policies.error = ErrorPolicy::Continue;
+        let mut tool = SyncTool::with_svn_operations(
+            config,
+            create_history_manager(0),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(create_svn_ops(&[("1", "m1"), ("2", "m2")])),
+        )
+        .with_commit_policy(CommitPolicy {
+            git_notes: true,
+            ..Default::default()
+        });
+
+        let err = tool.run().unwrap_err().to_string();
+        assert!(err.contains("以下 SVN 版本同步失败：r1、r2"), "{err}");
+        let state = state.borrow();
+        assert_eq!(state.commit_messages, vec!["SVN: m1", "SVN: m2"]);
+        assert_eq!(state.resets.len(), 2);
     }
-}
 
-fn has_conflict_entries(status: &str) -> bool {
-    status.lines().any(|line| {
-        if line.len() < 2 {
-            return false;
-        }
-        matches!(&line[..2], "DD" | "AU" | "UD" | "UA" | "DU" | "AA" | "UU")
-    })
-}
+    #[test]
+    fn test_run_should_validate_directories_before_confirming() {
+        // 只设置初始化确认预期：校验失败时不应询问是否同步
+        let run = |svn_ops: MockSvnOperations, not_repository: bool| {
+            let (git_ops_impl, state) = TestGitOperations::new("");
+            state.borrow_mut().not_repository = not_repository;
+            let mut interactor = MockUserInteractor::new();
+            interactor.expect_confirm_git_init().returning(|_| false);
+            let mut tool = SyncTool::with_svn_operations(
+                create_config(),
+                create_history_manager(0),
+                Box::new(interactor),
+                Box::new(git_ops_impl),
+                Box::new(svn_ops),
+            );
+            (tool.run(), state)
+        };
 
-#[cfg(test)]
-mod tests {
-    use std::{cell::RefCell, path::Path, path::PathBuf, rc::Rc, str::FromStr};
+        let (result, _) = run(MockSvnOperations::new(), false);
+        assert!(matches!(
+            result,
+            Err(SyncError::NotSvnWorkingCopy { path, .. }) if path == Path::new("svn_dir")
+        ));
 
-    use crate::{
-        config::{HistoryManager, MockFileStorage, SyncConfig},
-        error::SyncError,
-        interactor::MockUserInteractor,
-        ops::{GitOperations, SvnLog},
-    };
+        let (result, state) = run(create_svn_ops(&[("1", "m1")]), true);
+        assert!(matches!(result, Err(SyncError::NotGitRepository(_))));
+        assert!(state.borrow().commit_messages.is_empty());
+    }
 
-    use super::{
-        MockSvnOperations, SyncRunOptions, SyncTool, build_git_commit_message,
-        has_conflict_entries, limit_logs, summarize_message,
-    };
+    #[test]
+    fn test_run_should_refuse_unrelated_git_history_without_force() {
+        let run = |log: &str, force: bool, saves: usize| {
+            let (git_ops_impl, state) = TestGitOperations::new("");
+            state.borrow_mut().log_output = log.to_string();
+            let mut interactor = MockUserInteractor::new();
+            interactor.expect_confirm_sync().returning(|_, _| true);
+            let mut tool = SyncTool::with_svn_operations(
+                create_config(),
+                create_history_manager(saves),
+                Box::new(interactor),
+                Box::new(git_ops_impl),
+                Box::new(create_svn_ops(&[("1", "m1")])),
+            )
+            .with_allow_unrelated_history(force);
+            (tool.run(), state)
+        };
 
-    struct TestGitState {
-        add_all_calls: usize,
-        commit_messages: Vec<String>,
-        status_calls: usize,
-        status_output: String,
-    }
+        let unrelated = "a1b2c3d Fix login page\n9f8e7d6 Initial commit\n";
+        let (result, state) = run(unrelated, false, 0);
+        let err = result.unwrap_err();
+        assert!(
+            err.to_string().contains("2 个与 SVN 同步无关的提交"),
+            "{err}"
+        );
+        assert!(state.borrow().commit_messages.is_empty());
 
-    struct TestGitOperations {
-        state: Rc<RefCell<TestGitState>>,
+        let (result, state) = run(unrelated, true, 1);
+        result.unwrap();
+        assert_eq!(state.borrow().commit_messages.len(), 1);
+
+        // 有本工具生成的提交时视为已有的镜像
+        let (result, _) = run("a1b2c3d Local fix\n9f8e7d6 SVN: m0\n", false, 1);
+        result.unwrap();
+        let (result, _) = run("9f8e7d6 Initial state (SVN r12)\n", false, 1);
+        result.unwrap();
     }
 
-    impl TestGitOperations {
-        fn new(status_output: &str) -> (Self, Rc<RefCell<TestGitState>>) {
-            let state = Rc::new(RefCell::new(TestGitState {
-                add_all_calls: 0,
-                commit_messages: Vec::new(),
-                status_calls: 0,
-                status_output: status_output.to_string(),
-            }));
-            (
-                Self {
-                    state: state.clone(),
-                },
-                state,
+    #[test]
+    fn test_run_should_upgrade_outdated_working_copy_only_when_enabled() {
+        let run = |auto_upgrade: bool| {
+            let svn_ops = create_svn_ops(&[("1", "m1")]);
+            svn_ops.set_needs_upgrade();
+            let (git_ops_impl, state) = TestGitOperations::new("");
+            let mut interactor = MockUserInteractor::new();
+            interactor.expect_confirm_sync().returning(|_, _| true);
+            let mut tool = SyncTool::with_svn_operations(
+                create_config(),
+                create_history_manager(if auto_upgrade { 1 } else { 0 }),
+                Box::new(interactor),
+                Box::new(git_ops_impl),
+                Box::new(svn_ops),
             )
-        }
+            .with_auto_upgrade(auto_upgrade);
+            (tool.run(), state)
+        };
+
+        let (result, state) = run(false);
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("--auto-upgrade"), "{err}");
+        assert!(state.borrow().commit_messages.is_empty());
+
+        let (result, state) = run(true);
+        result.unwrap();
+        assert_eq!(state.borrow().commit_messages.len(), 1);
     }
 
-    impl GitOperations for TestGitOperations {
-        fn init(&self, _path: &Path) -> crate::error::Result<()> {
-            Ok(())
-        }
+    #[test]
+    fn test_run_should_init_missing_git_repository_with_initial_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut interactor = MockUserInteractor::new();
+        interactor
+            .expect_confirm_git_init()
+            .times(1)
+            .returning(|_| true);
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new(" A a.txt");
+        state.borrow_mut().not_repository = true;
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_log_to_mock(dir.path(), "1", "m1");
+        let config = SyncConfig::new(dir.path().to_path_buf(), dir.path().join("git"));
+        let mut tool = SyncTool::with_svn_operations(
+            config,
+            create_history_manager(1),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        );
 
-        fn config_user(&self, _path: &Path, _name: &str, _email: &str) -> crate::error::Result<()> {
-            Ok(())
-        }
+        tool.run().unwrap();
+        assert!(dir.path().join("git").is_dir());
+        let state = state.borrow();
+        assert!(!state.not_repository);
+        assert_eq!(state.commit_messages[0], "Initial state (SVN r0)");
+        assert_eq!(state.commit_messages.len(), 2);
+    }
 
-        fn add_all(&self, path: &Path) -> crate::error::Result<()> {
-            let _ = path;
-            self.state.borrow_mut().add_all_calls += 1;
-            Ok(())
-        }
+    #[test]
+    fn test_run_with_policies_for_empty_revision_dirty_tree_and_conflict() {
+        let run = |status: &str, policies: &[(&str, &str)], saves: usize| {
+            let mut interactor = MockUserInteractor::new();
+            interactor.expect_confirm_sync().returning(|_, _| true);
+            let (git_ops_impl, state) = TestGitOperations::new(status);
+            let mut config = create_config();
+            for (name, value) in policies {
+                config.policies.set(name, value).unwrap();
+            }
+            let mut tool = SyncTool::with_svn_operations(
+                config,
+                create_history_manager(saves),
+                Box::new(interactor),
+                Box::new(git_ops_impl),
+                Box::new(create_svn_ops(&[("1", "m1")])),
+            );
+            (tool.run(), state)
+        };
 
-        fn commit(&self, _path: &Path, message: &str) -> crate::error::Result<()> {
-            self.state
-                .borrow_mut()
-                .commit_messages
-                .push(message.to_string());
-            Ok(())
-        }
+        let (result, state) = run("", &[("empty-revision", "skip")], 1);
+        assert!(result.is_ok());
+        assert!(state.borrow().commit_messages.is_empty());
+        assert_eq!(state.borrow().add_all_calls, 1);
 
-        fn status(&self, _path: &Path) -> crate::error::Result<String> {
-            let mut state = self.state.borrow_mut();
-            state.status_calls += 1;
-            Ok(state.status_output.clone())
-        }
+        let (result, state) = run("", &[("empty-revision", "allow-empty")], 1);
+        assert!(result.is_ok());
+        assert_eq!(state.borrow().commit_messages, vec!["SVN: m1"]);
+        assert!(state.borrow().commit_metadata[0].allow_empty);
 
-        fn log(&self, _path: &Path, _count: Option<usize>) -> crate::error::Result<String> {
-            Ok(String::new())
-        }
+        let (result, state) = run(" M a.txt", &[("dirty-tree", "fail")], 0);
+        let err = result.unwrap_err();
+        assert!(matches!(err, SyncError::DirtyWorkingTree(_)));
+        assert!(err.to_string().contains("按策略拒绝同步"), "{err}");
+        assert_eq!(state.borrow().add_all_calls, 0);
 
-        fn is_clean(&self, _path: &Path) -> crate::error::Result<bool> {
-            Ok(true)
-        }
+        let (result, state) = run("UU conflict.txt", &[("conflict", "reset")], 1);
+        assert!(result.is_ok());
+        assert_eq!(state.borrow().resets, vec![Some("hash0".to_string())]);
+        assert_eq!(state.borrow().commit_messages, vec!["SVN: m1"]);
     }
 
-    fn create_history_manager(expect_save_count: usize) -> HistoryManager<MockFileStorage> {
-        let mut storage = MockFileStorage::new();
-        storage.expect_load().returning(|| Ok(vec![]));
-        if expect_save_count > 0 {
-            storage
-                .expect_save()
-                .times(expect_save_count)
-                .returning(|_| Ok(()));
+    #[test]
+    fn test_run_should_stash_dirty_tree_and_restore_after_sync() {
+        let run = |policy: &str, choice: Option<DirtyTreePolicy>, saves: usize| {
+            let mut interactor = MockUserInteractor::new();
+            interactor.expect_confirm_sync().returning(|_, _| true);
+            if let Some(choice) = choice {
+                interactor
+                    .expect_choose_dirty_tree_policy()
+                    .times(1)
+                    .returning(move |_| choice);
+            }
+            let (git_ops_impl, state) = TestGitOperations::new(" M a.txt");
+            let mut config = create_config();
+            config.policies.set("dirty-tree", policy).unwrap();
+            let mut tool = SyncTool::with_svn_operations(
+                config,
+                create_history_manager(saves),
+                Box::new(interactor),
+                Box::new(git_ops_impl),
+                Box::new(create_svn_ops(&[("1", "m1")])),
+            );
+            (tool.run(), state)
+        };
+
+        let (result, state) = run("stash", None, 1);
+        assert!(result.is_ok());
+        assert_eq!(state.borrow().stash_ops, ["stash", "pop"]);
+        assert_eq!(state.borrow().commit_messages, vec!["SVN: m1"]);
+
+        let (result, state) = run("ask", Some(DirtyTreePolicy::Fail), 0);
+        assert!(matches!(result, Err(SyncError::DirtyWorkingTree(_))));
+        assert!(state.borrow().stash_ops.is_empty());
+
+        let (result, state) = run("ask", Some(DirtyTreePolicy::Stash), 1);
+        assert!(result.is_ok());
+        assert_eq!(state.borrow().stash_ops, ["stash", "pop"]);
+
+        // 同步失败时同样恢复暂存的更改
+        let (result, state) = {
+            let (git_ops_impl, state) = TestGitOperations::new(" M a.txt");
+            state.borrow_mut().fail_push = true;
+            let mut config = create_config();
+            config.policies.set("dirty-tree", "stash").unwrap();
+            config.policies.set("push", "always").unwrap();
+            let mut interactor = MockUserInteractor::new();
+            interactor.expect_confirm_sync().returning(|_, _| true);
+            let mut tool = SyncTool::with_svn_operations(
+                config,
+                create_history_manager(1),
+                Box::new(interactor),
+                Box::new(git_ops_impl),
+                Box::new(create_svn_ops(&[("1", "m1")])),
+            );
+            (tool.run(), state)
+        };
+        assert!(result.is_err());
+        assert_eq!(state.borrow().stash_ops, ["stash", "pop"]);
+    }
+
+    #[test]
+    fn test_run_with_preserve_dates_should_apply_date_order_policy() {
+        let run = |date_order: &str, timezone: Option<FixedOffset>| {
+            let svn_ops = MockSvnOperations::new();
+            for (version, date) in [
+                ("1", "2024-01-02T03:04:05.000000Z"),
+                ("2", "2024-01-02T03:00:00.000000Z"),
+                ("3", "2024-01-02T04:00:00.000000Z"),
+            ] {
+                svn_ops.add_svn_log_to_mock(
+                    &PathBuf::from("svn_dir"),
+                    SvnLog {
+                        version: version.into(),
+                        message: format!("m{version}"),
+                        date: date.into(),
+                        ..Default::default()
+                    },
+                );
+            }
+            let mut interactor = MockUserInteractor::new();
+            interactor.expect_confirm_sync().returning(|_, _| true);
+            let (git_ops_impl, state) = TestGitOperations::new("");
+            let mut config = create_config();
+            config.policies.set("date-order", date_order).unwrap();
+            let mut tool = SyncTool::with_svn_operations(
+                config,
+                create_history_manager(3),
+                Box::new(interactor),
+                Box::new(git_ops_impl),
+                Box::new(svn_ops),
+            )
+            .with_commit_policy(CommitPolicy {
+                preserve_dates: true,
+                date_timezone: timezone,
+                ..Default::default()
+            });
+            tool.run().unwrap();
+            let state = state.borrow();
+            state
+                .commit_metadata
+                .iter()
+                .map(|metadata| metadata.date.unwrap().to_rfc3339())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            run("warn", None),
+            vec![
+                "2024-01-02T03:04:05+00:00",
+                "2024-01-02T03:00:00+00:00",
+                "2024-01-02T04:00:00+00:00"
+            ]
+        );
+        assert_eq!(
+            run("clamp", None),
+            vec![
+                "2024-01-02T03:04:05+00:00",
+                "2024-01-02T03:04:06+00:00",
+                "2024-01-02T04:00:00+00:00"
+            ]
+        );
+        assert_eq!(
+            run("keep", FixedOffset::east_opt(8 * 3600))[0],
+            "2024-01-02T11:04:05+08:00"
+        );
+    }
+
+    #[test]
+    fn test_run_with_preserve_authors_should_apply_fallback_rules() {
+        let svn_ops = MockSvnOperations::new();
+        for (version, author, path) in [
+            ("1", "alice", "/trunk/core/a.rs"),
+            ("2", "buildbot", "/trunk/ui/b.css"),
+            ("3", "", "/trunk/core/c.rs"),
+        ] {
+            svn_ops.add_svn_log_to_mock(
+                &PathBuf::from("svn_dir"),
+                SvnLog {
+                    version: version.into(),
+                    author: author.into(),
+                    message: format!("m{version}"),
+                    paths: vec![SvnChangedPath {
+                        action: "M".into(),
+                        path: path.into(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+            );
         }
-        HistoryManager::new(storage).unwrap()
+        let uuid = svn_ops
+            .info(&PathBuf::from("svn_dir"))
+            .unwrap()
+            .repository_uuid;
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(3),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        )
+        .with_commit_policy(CommitPolicy {
+            preserve_authors: true,
+            service_accounts: vec!["buildbot".into()],
+            author_rules: vec!["path:trunk/ui=Bob <bob@example.com>".parse().unwrap()],
+            ..Default::default()
+        });
+
+        tool.run().unwrap();
+        let state = state.borrow();
+        let authors: Vec<_> = state
+            .commit_metadata
+            .iter()
+            .map(|m| {
+                (
+                    m.author_name.clone().unwrap(),
+                    m.author_email.clone().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            authors,
+            vec![
+                ("alice".to_string(), format!("alice@{uuid}")),
+                ("Bob".to_string(), "bob@example.com".to_string()),
+                ("(no author)".to_string(), format!("(no author)@{uuid}")),
+            ]
+        );
+        assert!(state.commit_metadata.iter().all(|m| m.date.is_none()));
     }
 
-    fn create_config() -> SyncConfig {
-        SyncConfig::new(
-            PathBuf::from_str("svn_dir").unwrap(),
-            PathBuf::from_str("git_dir").unwrap(),
+    #[test]
+    fn test_run_with_export_should_copy_working_copy_to_git_dir() {
+        let (svn_dir, git_dir) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        std::fs::create_dir_all(svn_dir.path().join(".svn")).unwrap();
+        std::fs::write(svn_dir.path().join("a.txt"), "a").unwrap();
+        std::fs::write(git_dir.path().join("deleted.txt"), "old").unwrap();
+        std::fs::write(git_dir.path().join("untracked.log"), "local").unwrap();
+
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_log_to_mock(svn_dir.path(), "1", "m1");
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        state
+            .borrow_mut()
+            .tree
+            .insert("deleted.txt".into(), "blob".into());
+        let mut tool = SyncTool::with_svn_operations(
+            SyncConfig::new(svn_dir.path().into(), git_dir.path().into()),
+            create_history_manager(1),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
         )
+        .with_commit_policy(CommitPolicy {
+            export: true,
+            ..Default::default()
+        });
+
+        tool.run().unwrap();
+        assert!(git_dir.path().join("a.txt").exists());
+        assert!(!git_dir.path().join("deleted.txt").exists());
+        assert!(git_dir.path().join("untracked.log").exists());
+        assert!(!git_dir.path().join(".svn").exists());
+        assert!(!git_dir.path().join(".gitignore").exists());
+        assert_eq!(state.borrow().commit_messages, vec!["SVN: m1"]);
     }
 
     #[test]
-    fn test_run_success_with_mock_svn_and_git() {
-        let config = create_config();
-        let history = create_history_manager(1);
+    fn test_run_with_export_should_keep_state_files_in_git_dir() {
+        let (svn_dir, git_dir) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        std::fs::write(svn_dir.path().join("a.txt"), "a").unwrap();
+        let config_file = git_dir.path().join("config.json");
+        std::fs::write(&config_file, "{}").unwrap();
 
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_log_to_mock(svn_dir.path(), "1", "m1");
         let mut interactor = MockUserInteractor::new();
-        interactor.expect_confirm_sync().returning(|_| true);
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        // 即使状态文件被误提交过，复制时也不会删除
+        state
+            .borrow_mut()
+            .tree
+            .insert("config.json".into(), "blob".into());
+        let lock_file = git_dir.path().join("svn2git-a.lock");
+        let mut tool = SyncTool::with_svn_operations(
+            SyncConfig::new(svn_dir.path().into(), git_dir.path().into()),
+            create_history_manager(1),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        )
+        .with_commit_policy(CommitPolicy {
+            export: true,
+            ..Default::default()
+        })
+        .with_lock_file(Some(lock_file.clone()))
+        .with_state_files(vec![config_file.clone()]);
 
-        let mut svn_ops = MockSvnOperations::new();
-        svn_ops.expect_get_logs().returning(|_| {
-            Ok(vec![
-                SvnLog {
-                    version: "1".into(),
-                    message: "初始提交".into(),
-                },
+        tool.run().unwrap();
+        assert_eq!(std::fs::read_to_string(&config_file).unwrap(), "{}");
+        assert!(git_dir.path().join("a.txt").exists());
+        assert_eq!(state.borrow().commit_messages, vec!["SVN: m1"]);
+    }
+
+    #[test]
+    fn test_run_with_layout_should_commit_each_branch_separately() {
+        let (svn_dir, git_dir) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        for (file, content) in [("trunk/a.txt", "trunk"), ("branches/dev/a.txt", "dev")] {
+            let path = svn_dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let changed = |action: &str, path: &str, copy_from: Option<&str>| SvnChangedPath {
+            action: action.into(),
+            path: path.into(),
+            copy_from: copy_from.map(str::to_string),
+            ..Default::default()
+        };
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.set_list_entries(&["branches/", "tags/", "trunk/"]);
+        for (version, paths) in [
+            ("1", vec![changed("A", "/trunk/a.txt", None)]),
+            ("2", vec![changed("A", "/branches/dev", Some("/trunk"))]),
+            ("3", vec![changed("M", "/branches/dev/a.txt", None)]),
+            ("4", vec![changed("D", "/branches/dev", None)]),
+        ] {
+            svn_ops.add_svn_log_to_mock(
+                svn_dir.path(),
                 SvnLog {
-                    version: "2".into(),
-                    message: "修复问题".into(),
+                    version: version.into(),
+                    message: format!("m{version}"),
+                    paths,
+                    ..Default::default()
                 },
-            ])
+            );
+        }
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            SyncConfig::new(svn_dir.path().into(), git_dir.path().into()),
+            create_history_manager(4),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        )
+        .with_revision_policy(RevisionPolicy {
+            layout: true,
+            ..Default::default()
         });
-        svn_ops
-            .expect_update_to_rev()
-            .times(2)
-            .returning(|_, _| Ok(()));
 
-        let (git_ops_impl, git_state) = TestGitOperations::new("");
-        let git_ops = Box::new(git_ops_impl);
-        let tool = SyncTool::with_svn_operations(
-            config,
-            history,
+        tool.run().unwrap();
+        let state = state.borrow();
+        assert_eq!(state.commit_messages, vec!["SVN: m1", "SVN: m2", "SVN: m3"]);
+        assert_eq!(
+            state.branch_ops,
+            vec![
+                "checkout master",
+                "create dev master",
+                "checkout dev",
+                "checkout dev"
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(git_dir.path().join("a.txt")).unwrap(),
+            "dev"
+        );
+        assert!(!git_dir.path().join("trunk").exists());
+    }
+
+    #[test]
+    fn test_run_with_git_tags_should_tag_copies_into_tags() {
+        let (svn_dir, git_dir) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        std::fs::create_dir_all(svn_dir.path().join("trunk")).unwrap();
+        std::fs::write(svn_dir.path().join("trunk/a.txt"), "a").unwrap();
+
+        let changed = |action: &str, path: &str, copy_from: Option<&str>| SvnChangedPath {
+            action: action.into(),
+            path: path.into(),
+            copy_from: copy_from.map(str::to_string),
+            ..Default::default()
+        };
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.set_list_entries(&["branches/", "tags/", "trunk/"]);
+        svn_ops.add_svn_log_to_mock(
+            svn_dir.path(),
+            SvnLog {
+                version: "1".into(),
+                message: "m1".into(),
+                paths: vec![changed("A", "/trunk/a.txt", None)],
+                ..Default::default()
+            },
+        );
+        svn_ops.add_svn_log_to_mock(
+            svn_dir.path(),
+            SvnLog {
+                version: "2".into(),
+                message: "发布 1.0\n".into(),
+                author: "alice".into(),
+                date: "2024-01-02T03:04:05.000000Z".into(),
+                paths: vec![changed("A", "/tags/v1.0", Some("/trunk"))],
+            },
+        );
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            SyncConfig::new(svn_dir.path().into(), git_dir.path().into()),
+            create_history_manager(2),
             Box::new(interactor),
-            git_ops,
+            Box::new(git_ops_impl),
             Box::new(svn_ops),
+        )
+        .with_revision_policy(RevisionPolicy {
+            layout: true,
+            ..Default::default()
+        })
+        .with_commit_policy(CommitPolicy {
+            git_tags: true,
+            ..Default::default()
+        });
+
+        tool.run().unwrap();
+        let state = state.borrow();
+        assert_eq!(state.commit_messages, vec!["SVN: m1"]);
+        let (name, target, message, tagger) = &state.tags[0];
+        assert_eq!(
+            (name.as_str(), target.as_str(), message.as_str()),
+            ("v1.0", "master", "发布 1.0")
         );
+        let tagger = tagger.as_ref().unwrap();
+        assert_eq!(tagger.name, "alice");
+        assert_eq!(tagger.email, "alice@00000000-0000-0000-0000-000000000000");
+        assert_eq!(tagger.date.unwrap().timestamp(), 1_704_164_645);
+    }
 
-        let result = tool.run();
-        assert!(result.is_ok());
-        assert_eq!(git_state.borrow().add_all_calls, 2);
-        assert_eq!(git_state.borrow().commit_messages.len(), 2);
+    #[test]
+    fn test_run_with_layout_should_require_standard_layout() {
+        let (svn_dir, git_dir) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.set_list_entries(&["src/"]);
+        svn_ops.add_log_to_mock(svn_dir.path(), "1", "m1");
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            SyncConfig::new(svn_dir.path().into(), git_dir.path().into()),
+            create_history_manager(0),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        )
+        .with_revision_policy(RevisionPolicy {
+            layout: true,
+            ..Default::default()
+        });
+
+        let err = tool.run().unwrap_err().to_string();
+        assert!(err.contains("标准布局"));
+        assert!(state.borrow().commit_messages.is_empty());
     }
 
     #[test]
-    fn test_run_cancel_should_not_update_or_save() {
-        let config = create_config();
-        let history = create_history_manager(0);
+    fn test_run_should_sanitize_and_limit_commit_message() {
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(1),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(create_svn_ops(&[("1", "fix\u{1b}[0m parser crash\r\n")])),
+        )
+        .with_commit_policy(CommitPolicy {
+            message_limits: MessageLimits {
+                subject_width: Some(14),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        tool.run().unwrap();
+        assert_eq!(
+            state.borrow().commit_messages,
+            vec!["SVN: fix[0m\n\nparser crash"]
+        );
+    }
+
+    #[test]
+    fn test_run_should_truncate_oversized_message_and_author() {
+        let svn_ops = MockSvnOperations::new();
+        svn_ops.add_svn_log_to_mock(
+            &PathBuf::from("svn_dir"),
+            SvnLog {
+                version: "1".into(),
+                author: "a".repeat(300),
+                message: "日志".repeat(100_000),
+                ..Default::default()
+            },
+        );
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(1),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        )
+        .with_commit_policy(CommitPolicy {
+            preserve_authors: true,
+            message_limits: MessageLimits {
+                max_message_chars: Some(6),
+                max_author_chars: Some(DEFAULT_MAX_AUTHOR_CHARS),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
 
+        tool.run().unwrap();
+        let state = state.borrow();
+        assert_eq!(state.commit_messages, vec!["SVN: 日志日志日志…"]);
+        let author = state.commit_metadata[0].author_name.clone().unwrap();
+        assert_eq!(author, format!("{}…", "a".repeat(DEFAULT_MAX_AUTHOR_CHARS)));
+    }
+
+    #[test]
+    fn test_run_should_skip_revisions_touching_only_skipped_paths() {
+        let changed = |path: &str| SvnChangedPath {
+            action: "M".into(),
+            path: path.into(),
+            copy_from: None,
+            ..Default::default()
+        };
+        let svn_ops = MockSvnOperations::new();
+        for (version, paths) in [
+            ("1", vec![changed("/trunk/docs/a.md")]),
+            (
+                "2",
+                vec![changed("/trunk/docs/b.md"), changed("/trunk/src/main.rs")],
+            ),
+        ] {
+            svn_ops.add_svn_log_to_mock(
+                &PathBuf::from("svn_dir"),
+                SvnLog {
+                    version: version.into(),
+                    message: format!("m{version}"),
+                    paths,
+                    ..Default::default()
+                },
+            );
+        }
+        let svn_probe = svn_ops.clone();
         let mut interactor = MockUserInteractor::new();
-        interactor.expect_confirm_sync().returning(|_| false);
-
-        let mut svn_ops = MockSvnOperations::new();
-        svn_ops.expect_get_logs().returning(|_| {
-            Ok(vec![SvnLog {
-                version: "10".into(),
-                message: "测试".into(),
-            }])
-        });
-        svn_ops.expect_update_to_rev().times(0);
-
-        let (git_ops_impl, git_state) = TestGitOperations::new("");
-        let git_ops = Box::new(git_ops_impl);
-        let tool = SyncTool::with_svn_operations(
-            config,
-            history,
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(2),
             Box::new(interactor),
-            git_ops,
+            Box::new(git_ops_impl),
             Box::new(svn_ops),
-        );
+        )
+        .with_revision_policy(RevisionPolicy::default().with_skip_paths(vec!["trunk/docs".into()]))
+        .with_commit_policy(CommitPolicy {
+            list_changed_files: true,
+            ..Default::default()
+        });
 
-        let result = tool.run();
-        assert!(result.is_ok());
-        assert_eq!(git_state.borrow().add_all_calls, 0);
+        tool.run().unwrap();
+        assert_eq!(svn_probe.updated_revisions(), vec!["2"]);
+        assert_eq!(
+            state.borrow().commit_messages,
+            vec!["SVN: m2\n\n变更文件:\n  M /trunk/docs/b.md\n  M /trunk/src/main.rs"]
+        );
     }
 
     #[test]
-    fn test_run_should_return_error_when_svn_update_fails() {
-        let config = create_config();
-        let history = create_history_manager(0);
-
+    fn test_run_with_git_svn_id_should_end_messages_with_git_svn_id() {
+        let svn_ops = create_svn_ops(&[("1", "m1"), ("2", "m2")]);
+        let info = svn_ops.info(Path::new("svn_dir")).unwrap();
         let mut interactor = MockUserInteractor::new();
-        interactor.expect_confirm_sync().returning(|_| true);
-
-        let mut svn_ops = MockSvnOperations::new();
-        svn_ops.expect_get_logs().returning(|_| {
-            Ok(vec![SvnLog {
-                version: "3".into(),
-                message: "触发失败".into(),
-            }])
-        });
-        svn_ops
-            .expect_update_to_rev()
-            .times(1)
-            .returning(|_, _| Err(SyncError::App("svn update failed".into())));
-
-        let (git_ops_impl, git_state) = TestGitOperations::new("");
-        let git_ops = Box::new(git_ops_impl);
-        let tool = SyncTool::with_svn_operations(
-            config,
-            history,
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(2),
             Box::new(interactor),
-            git_ops,
+            Box::new(git_ops_impl),
             Box::new(svn_ops),
-        );
+        )
+        .with_commit_policy(CommitPolicy {
+            git_svn_id: true,
+            ..Default::default()
+        });
 
-        let result = tool.run();
-        assert!(result.is_err());
-        assert_eq!(git_state.borrow().add_all_calls, 0);
+        tool.run().unwrap();
+        let messages = state.borrow().commit_messages.clone();
+        assert_eq!(
+            messages[1],
+            format!(
+                "SVN: m2\n\ngit-svn-id: {}@2 {}",
+                info.url, info.repository_uuid
+            )
+        );
+        assert_eq!(
+            parse_git_svn_id(&messages[0]),
+            Some((info.url.as_str(), "1", info.repository_uuid.as_str()))
+        );
     }
 
     #[test]
-    fn test_run_dry_run_should_not_update_or_commit_or_save() {
-        let config = create_config();
-        let history = create_history_manager(0);
+    fn test_parse_git_svn_id() {
+        let id = build_git_svn_id("svn://host/repo/trunk", "42", "uuid-a");
+        assert_eq!(id, "git-svn-id: svn://host/repo/trunk@42 uuid-a");
+        assert_eq!(
+            parse_git_svn_id(&format!("subject\n\nbody\n\n{id}\n")),
+            Some(("svn://host/repo/trunk", "42", "uuid-a"))
+        );
+        assert_eq!(parse_git_svn_id("git-svn-id: svn://host@HEAD uuid"), None);
+        assert_eq!(parse_git_svn_id("subject"), None);
+    }
 
+    #[test]
+    fn test_run_should_skip_revisions_outside_sparse_working_copy() {
+        let changed = |path: &str| SvnChangedPath {
+            action: "M".into(),
+            path: path.into(),
+            copy_from: None,
+            ..Default::default()
+        };
+        let svn_ops = MockSvnOperations::new();
+        for (version, paths) in [
+            ("1", vec![changed("/trunk/docs/a.md")]),
+            ("2", vec![changed("/trunk/src/main.rs")]),
+        ] {
+            svn_ops.add_svn_log_to_mock(
+                &PathBuf::from("svn_dir"),
+                SvnLog {
+                    version: version.into(),
+                    message: format!("m{version}"),
+                    paths,
+                    ..Default::default()
+                },
+            );
+        }
+        let scope = SparseScope::new([
+            (String::new(), SvnDepth::Immediates),
+            ("trunk".into(), SvnDepth::Immediates),
+            ("trunk/src".into(), SvnDepth::Infinity),
+        ]);
+        svn_ops.set_depth_scope(scope);
+        let svn_probe = svn_ops.clone();
         let mut interactor = MockUserInteractor::new();
-        interactor.expect_confirm_sync().times(0);
-
-        let mut svn_ops = MockSvnOperations::new();
-        svn_ops.expect_get_logs().returning(|_| {
-            Ok(vec![SvnLog {
-                version: "11".into(),
-                message: "dry run".into(),
-            }])
-        });
-        svn_ops.expect_update_to_rev().times(0);
-
-        let (git_ops_impl, git_state) = TestGitOperations::new("");
-        let tool = SyncTool::with_svn_operations(
-            config,
-            history,
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(2),
             Box::new(interactor),
             Box::new(git_ops_impl),
             Box::new(svn_ops),
         );
 
-        let result = tool.run_with_options(&SyncRunOptions {
-            dry_run: true,
-            limit: None,
-        });
-        assert!(result.is_ok());
-        assert_eq!(git_state.borrow().add_all_calls, 0);
-        assert_eq!(git_state.borrow().commit_messages.len(), 0);
-        assert_eq!(git_state.borrow().status_calls, 0);
+        tool.run().unwrap();
+        assert_eq!(svn_probe.updated_revisions(), vec!["2"]);
+        assert_eq!(state.borrow().commit_messages, vec!["SVN: m2"]);
     }
 
     #[test]
-    fn test_run_limit_should_only_process_first_n_logs() {
-        let config = create_config();
-        let history = create_history_manager(1);
-
-        let mut interactor = MockUserInteractor::new();
-        interactor.expect_confirm_sync().returning(|_| true);
-
-        let mut svn_ops = MockSvnOperations::new();
-        svn_ops.expect_get_logs().returning(|_| {
-            Ok(vec![
-                SvnLog {
-                    version: "1".into(),
-                    message: "m1".into(),
+    fn test_changed_paths_in_git() {
+        let log = SvnLog {
+            paths: vec![
+                SvnChangedPath {
+                    action: "R".into(),
+                    path: "/trunk/src/main.rs".into(),
+                    copy_from: None,
+                    ..Default::default()
                 },
-                SvnLog {
-                    version: "2".into(),
-                    message: "m2".into(),
+                SvnChangedPath {
+                    action: "D".into(),
+                    path: "/trunk".into(),
+                    copy_from: None,
+                    ..Default::default()
                 },
-            ])
-        });
-        svn_ops
-            .expect_update_to_rev()
-            .times(1)
-            .returning(|_, _| Ok(()));
+                SvnChangedPath {
+                    action: "D".into(),
+                    path: "/branches/dev/a.txt".into(),
+                    copy_from: None,
+                    ..Default::default()
+                },
+                SvnChangedPath {
+                    action: "D".into(),
+                    path: "/trunk-old/a.txt".into(),
+                    copy_from: None,
+                    ..Default::default()
+                },
+                SvnChangedPath {
+                    action: "M".into(),
+                    path: "/trunk/b.txt".into(),
+                    copy_from: None,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            changed_paths_in_git(&log, &["D", "R"], "/trunk", "wc/"),
+            vec!["wc/src/main.rs"]
+        );
+        assert_eq!(
+            changed_paths_in_git(&log, &["D", "R"], "", ""),
+            vec![
+                "trunk/src/main.rs",
+                "trunk",
+                "branches/dev/a.txt",
+                "trunk-old/a.txt"
+            ]
+        );
+    }
 
-        let (git_ops_impl, git_state) = TestGitOperations::new("");
-        let tool = SyncTool::with_svn_operations(
-            config,
+    #[test]
+    fn test_run_with_svn_url_should_checkout_missing_working_copy() {
+        let history = create_history_manager(0);
+        let interactor = MockUserInteractor::new();
+        let svn_ops = MockSvnOperations::new();
+        let svn_probe = svn_ops.clone();
+        let (git_ops_impl, _) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
             history,
             Box::new(interactor),
             Box::new(git_ops_impl),
             Box::new(svn_ops),
-        );
+        )
+        .with_svn_url(Some("https://svn.example.com/repo".into()));
 
-        let result = tool.run_with_options(&SyncRunOptions {
-            dry_run: false,
-            limit: Some(1),
-        });
-        assert!(result.is_ok());
-        assert_eq!(git_state.borrow().add_all_calls, 1);
-        assert_eq!(git_state.borrow().commit_messages, vec!["SVN: m1"]);
+        tool.run().unwrap();
+        let info = svn_probe.info(&PathBuf::from("svn_dir")).unwrap();
+        assert_eq!(info.url, "https://svn.example.com/repo");
+        assert_eq!(info.revision, "0");
     }
 
     #[test]
-    fn test_run_should_stop_when_git_conflict_detected() {
-        let config = create_config();
-        let history = create_history_manager(0);
-
-        let mut interactor = MockUserInteractor::new();
-        interactor.expect_confirm_sync().returning(|_| true);
-
-        let mut svn_ops = MockSvnOperations::new();
-        svn_ops.expect_get_logs().returning(|_| {
-            Ok(vec![SvnLog {
-                version: "5".into(),
-                message: "conflict".into(),
-            }])
-        });
+    fn test_fetch_logs_from_remote_repository_in_batches() {
+        let svn_dir = PathBuf::from("svn_dir");
+        let svn_ops = MockSvnOperations::new();
         svn_ops
-            .expect_update_to_rev()
-            .times(1)
-            .returning(|_, _| Ok(()));
-
-        let (git_ops_impl, git_state) = TestGitOperations::new("UU conflict.txt");
-        let tool = SyncTool::with_svn_operations(
-            config,
-            history,
-            Box::new(interactor),
+            .checkout("https://svn.example.com/repo", &svn_dir, "0")
+            .unwrap();
+        for rev in ["1", "2", "3", "4", "5"] {
+            svn_ops.add_log_to_mock(&svn_dir, rev, "m");
+        }
+        let svn_probe = svn_ops.clone();
+        let (git_ops_impl, _) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(0),
+            Box::new(MockUserInteractor::new()),
             Box::new(git_ops_impl),
             Box::new(svn_ops),
+        )
+        .with_log_batch(std::num::NonZeroUsize::new(2));
+
+        tool.run_with_options(&SyncRunOptions {
+            dry_run: true,
+            ..Default::default()
+        })
+        .unwrap();
+        let queries: Vec<_> = svn_probe
+            .log_queries()
+            .into_iter()
+            .map(|query| (query.after_rev, query.limit))
+            .collect();
+        assert_eq!(
+            queries,
+            [
+                (None, Some(2)),
+                (Some("2".to_string()), Some(2)),
+                (Some("4".to_string()), Some(2)),
+            ]
         );
+    }
 
-        let result = tool.run();
-        assert!(result.is_err());
-        let err = result.unwrap_err().to_string();
-        assert!(err.contains("检测到 Git 冲突状态"));
-        assert_eq!(git_state.borrow().status_calls, 1);
-        assert_eq!(git_state.borrow().add_all_calls, 0);
+    #[test]
+    fn test_run_with_svn_url_should_not_checkout_in_dry_run_or_non_empty_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+
+        for (svn_dir, dry_run) in [(PathBuf::from("svn_dir"), true), (dir.path().into(), false)] {
+            let svn_ops = MockSvnOperations::new();
+            let svn_probe = svn_ops.clone();
+            let (git_ops_impl, _) = TestGitOperations::new("");
+            let mut tool = SyncTool::with_svn_operations(
+                SyncConfig::new(svn_dir.clone(), PathBuf::from("git_dir")),
+                create_history_manager(0),
+                Box::new(MockUserInteractor::new()),
+                Box::new(git_ops_impl),
+                Box::new(svn_ops),
+            )
+            .with_svn_url(Some("https://svn.example.com/repo".into()));
+
+            let result = tool.run_with_options(&SyncRunOptions {
+                dry_run,
+                ..Default::default()
+            });
+            assert_eq!(result.is_ok(), dry_run);
+            assert!(svn_probe.info(&svn_dir).is_err());
+        }
+    }
+
+    #[test]
+    fn test_status_touches_paths() {
+        let paths = vec!["src".to_string(), "a.txt".to_string()];
+        assert!(status_touches_paths(" M src/main.rs\n", &paths));
+        assert!(status_touches_paths("R  old.txt -> a.txt\n", &paths));
+        assert!(!status_touches_paths("?? srcx/main.rs\n M b.txt\n", &paths));
     }
 
     #[test]
@@ -588,10 +5515,12 @@ mod tests {
             SvnLog {
                 version: "1".into(),
                 message: "a".into(),
+                ..Default::default()
             },
             SvnLog {
                 version: "2".into(),
                 message: "b".into(),
+                ..Default::default()
             },
         ];
         let limited = limit_logs(logs, Some(1));
@@ -607,9 +5536,526 @@ mod tests {
 
     #[test]
     fn test_build_git_commit_message() {
-        assert_eq!(build_git_commit_message("修复bug"), "SVN: 修复bug");
-        assert_eq!(build_git_commit_message("  修复bug  "), "SVN: 修复bug");
-        assert_eq!(build_git_commit_message(""), "SVN: <空>");
-        assert_eq!(build_git_commit_message("   "), "SVN: <空>");
+        assert_eq!(build_git_commit_message("修复bug", "SVN: "), "SVN: 修复bug");
+        assert_eq!(
+            build_git_commit_message("  修复bug  ", "SVN: "),
+            "SVN: 修复bug"
+        );
+        assert_eq!(build_git_commit_message("", "SVN: "), "SVN: <空>");
+        assert_eq!(build_git_commit_message("   ", "SVN: "), "SVN: <空>");
+        assert_eq!(
+            build_git_commit_message("修复bug", "[svn] "),
+            "[svn] 修复bug"
+        );
+        assert_eq!(build_git_commit_message(" 修复bug", ""), "修复bug");
+        assert_eq!(build_git_commit_message("", ""), "<空>");
+    }
+
+    #[test]
+    fn test_run_with_push_should_push_after_sync_and_keep_commits_on_failure() {
+        let run = |config: SyncConfig, logs: &[(&str, &str)], saves: usize, fail_push: bool| {
+            let mut interactor = MockUserInteractor::new();
+            interactor.expect_confirm_sync().returning(|_, _| true);
+            let (git_ops, git_state) = TestGitOperations::new("");
+            git_state.borrow_mut().fail_push = fail_push;
+            let mut tool = SyncTool::with_svn_operations(
+                config,
+                create_history_manager(saves),
+                Box::new(interactor),
+                Box::new(git_ops),
+                Box::new(create_svn_ops(logs)),
+            )
+            .with_publish_policy(PublishPolicy {
+                git_remote: Some("https://example.com/repo.git".into()),
+                push: true,
+                ..Default::default()
+            });
+            (tool.run(), git_state, tool)
+        };
+
+        let (result, state, _) = run(create_config(), &[("1", "a"), ("2", "b")], 2, false);
+        result.unwrap();
+        assert_eq!(
+            state.borrow().remotes,
+            vec![(
+                GIT_REMOTE.to_string(),
+                "https://example.com/repo.git".into()
+            )]
+        );
+        assert_eq!(state.borrow().pushes, vec![GIT_REMOTE]);
+
+        // 推送失败不回滚：两个版本都已提交并保存进度
+        let (result, state, mut tool) = run(create_config(), &[("1", "a"), ("2", "b")], 2, true);
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("本地提交和同步进度已保留")
+        );
+        assert_eq!(state.borrow().commit_messages.len(), 2);
+        assert!(state.borrow().resets.is_empty());
+
+        // 没有新版本时也推送，用于重试
+        state.borrow_mut().fail_push = false;
+        tool.run().unwrap();
+        assert_eq!(state.borrow().commit_messages.len(), 2);
+        assert_eq!(state.borrow().pushes, vec![GIT_REMOTE]);
+    }
+
+    #[test]
+    fn test_run_with_bundle_should_bundle_after_sync() {
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops, git_state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(1),
+            Box::new(interactor),
+            Box::new(git_ops),
+            Box::new(create_svn_ops(&[("1", "a")])),
+        )
+        .with_publish_policy(PublishPolicy {
+            bundle: Some("out/repo.bundle".into()),
+            ..Default::default()
+        });
+
+        tool.run().unwrap();
+        assert_eq!(git_state.borrow().commit_messages.len(), 1);
+        assert_eq!(
+            git_state.borrow().bundles,
+            vec![PathBuf::from("out/repo.bundle")]
+        );
+
+        // 后端不支持时在同步开始前报错
+        git_state.borrow_mut().capabilities.bundle = false;
+        let err = tool.run().unwrap_err();
+        assert!(err.to_string().contains("--bundle"), "{err}");
+        assert_eq!(git_state.borrow().bundles.len(), 1);
+    }
+
+    #[test]
+    fn test_run_with_push_policy_always_should_push_without_flag() {
+        let mut config = create_config();
+        config.policies.set("push", "always").unwrap();
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops, git_state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            config,
+            create_history_manager(1),
+            Box::new(interactor),
+            Box::new(git_ops),
+            Box::new(create_svn_ops(&[("1", "a")])),
+        );
+
+        tool.run().unwrap();
+        assert!(git_state.borrow().remotes.is_empty());
+        assert_eq!(git_state.borrow().pushes, vec![GIT_REMOTE]);
+    }
+
+    #[test]
+    fn test_run_dry_run_should_save_plan_for_next_comparison() {
+        let mut record = HistoryRecord::new(1, PathBuf::from("svn_dir"), PathBuf::from("git_dir"));
+        record.set_sync_state("1", "hash1");
+        record.set_last_plan(SyncPlan::new(
+            ["2", "3", "5"]
+                .into_iter()
+                .map(|rev| PlannedRevision {
+                    rev: rev.into(),
+                    skipped: false,
+                })
+                .collect(),
+        ));
+        let saved = Arc::new(Mutex::new(Vec::new()));
+        let history = create_recording_history(record, saved.clone());
+
+        let svn_ops = MockSvnOperations::new();
+        for (version, path) in [
+            ("1", "/trunk/src/a.rs"),
+            ("2", "/trunk/src/b.rs"),
+            ("3", "/trunk/docs/a.md"),
+            ("4", "/trunk/src/c.rs"),
+        ] {
+            svn_ops.add_svn_log_to_mock(
+                &PathBuf::from("svn_dir"),
+                SvnLog {
+                    version: version.into(),
+                    message: format!("m{version}"),
+                    paths: vec![SvnChangedPath {
+                        action: "M".into(),
+                        path: path.into(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+            );
+        }
+        let (git_ops_impl, _) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            history,
+            Box::new(MockUserInteractor::new()),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        )
+        .with_revision_policy(RevisionPolicy::default().with_skip_paths(vec!["trunk/docs".into()]));
+
+        tool.run_with_options(&SyncRunOptions {
+            dry_run: true,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let saved = saved.lock().unwrap();
+        let plan = saved[0].last_plan().unwrap();
+        let revisions: Vec<(&str, bool)> = plan
+            .revisions()
+            .iter()
+            .map(|r| (r.rev.as_str(), r.skipped))
+            .collect();
+        assert_eq!(revisions, [("2", false), ("3", true), ("4", false)]);
+    }
+
+    #[test]
+    fn test_run_should_fail_when_lock_is_held() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock_path = dir.path().join("sync.lock");
+        let held = SyncLock::acquire(&lock_path, false).unwrap();
+
+        let build = |saves: usize| {
+            let mut interactor = MockUserInteractor::new();
+            interactor.expect_confirm_sync().returning(|_, _| true);
+            let (git_ops, git_state) = TestGitOperations::new("");
+            let tool = SyncTool::with_svn_operations(
+                create_config(),
+                create_history_manager(saves),
+                Box::new(interactor),
+                Box::new(git_ops),
+                Box::new(create_svn_ops(&[("1", "a")])),
+            )
+            .with_lock_file(Some(lock_path.clone()));
+            (tool, git_state)
+        };
+
+        let (mut tool, git_state) = build(0);
+        let err = tool.run().unwrap_err().to_string();
+        assert!(err.contains("另一个同步正在进行"), "{err}");
+        assert!(git_state.borrow().commit_messages.is_empty());
+        // dry-run 不加锁
+        tool.run_with_options(&SyncRunOptions {
+            dry_run: true,
+            ..Default::default()
+        })
+        .unwrap();
+
+        drop(held);
+        let (mut tool, git_state) = build(1);
+        tool.run().unwrap();
+        assert_eq!(git_state.borrow().commit_messages.len(), 1);
+        SyncLock::acquire(&lock_path, false).unwrap();
+    }
+
+    #[test]
+    fn test_run_with_skip_authors_should_not_update_or_commit_bot_revisions() {
+        let svn_ops = MockSvnOperations::new();
+        for (version, author) in [("1", "alice"), ("2", "buildbot"), ("3", "bob")] {
+            svn_ops.add_svn_log_to_mock(
+                &PathBuf::from("svn_dir"),
+                SvnLog {
+                    version: version.into(),
+                    author: author.into(),
+                    message: format!("m{version}"),
+                    ..Default::default()
+                },
+            );
+        }
+        let svn_probe = svn_ops.clone();
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(3),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        )
+        .with_revision_policy(RevisionPolicy {
+            skip_authors: vec!["buildbot".into()],
+            ..Default::default()
+        });
+
+        tool.run().unwrap();
+        assert_eq!(svn_probe.updated_revisions(), vec!["1", "3"]);
+        assert_eq!(state.borrow().commit_messages, vec!["SVN: m1", "SVN: m3"]);
+    }
+
+    #[test]
+    fn test_run_with_merge_authors_should_collapse_consecutive_bot_revisions() {
+        let svn_ops = MockSvnOperations::new();
+        for (version, author) in [
+            ("1", "alice"),
+            ("2", "buildbot"),
+            ("3", "buildbot"),
+            ("4", "buildbot"),
+            ("5", "alice"),
+            ("6", "buildbot"),
+        ] {
+            svn_ops.add_svn_log_to_mock(
+                &PathBuf::from("svn_dir"),
+                SvnLog {
+                    version: version.into(),
+                    author: author.into(),
+                    message: format!("m{version}"),
+                    ..Default::default()
+                },
+            );
+        }
+        let svn_probe = svn_ops.clone();
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops_impl, state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(6),
+            Box::new(interactor),
+            Box::new(git_ops_impl),
+            Box::new(svn_ops),
+        )
+        .with_revision_policy(RevisionPolicy {
+            merge_authors: vec!["buildbot".into()],
+            ..Default::default()
+        });
+
+        tool.run().unwrap();
+        assert_eq!(svn_probe.updated_revisions(), vec!["1", "4", "5", "6"]);
+        assert_eq!(
+            state.borrow().commit_messages,
+            vec![
+                "SVN: m1",
+                "SVN: 合并 3 个连续版本 r2-r4\n\nr2: m2\nr3: m3\nr4: m4",
+                "SVN: m5",
+                "SVN: m6",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_logs_should_keep_last_action_per_path() {
+        let log = |version: &str, changes: &[(&str, &str)]| SvnLog {
+            version: version.into(),
+            author: "buildbot".into(),
+            message: format!("m{version}"),
+            paths: changes
+                .iter()
+                .map(|(action, path)| SvnChangedPath {
+                    action: action.to_string(),
+                    path: path.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let first = log("2", &[("A", "/trunk/out.bin"), ("M", "/trunk/a.txt")]);
+        let second = log("3", &[("D", "/trunk/out.bin")]);
+
+        let merged = merge_logs(&[&first, &second]);
+        assert_eq!(merged.version, "3");
+        let actions: Vec<(&str, &str)> = merged
+            .paths
+            .iter()
+            .map(|p| (p.action.as_str(), p.path.as_str()))
+            .collect();
+        assert_eq!(actions, [("D", "/trunk/out.bin"), ("M", "/trunk/a.txt")]);
+    }
+
+    #[test]
+    fn test_run_should_write_progress_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let progress_path = dir.path().join("sync.progress.json");
+        let mut interactor = MockUserInteractor::new();
+        interactor.expect_confirm_sync().returning(|_, _| true);
+        let (git_ops, git_state) = TestGitOperations::new("");
+        let mut tool = SyncTool::with_svn_operations(
+            create_config(),
+            create_history_manager(2),
+            Box::new(interactor),
+            Box::new(git_ops),
+            Box::new(create_svn_ops(&[("1", "a"), ("2", "b")])),
+        )
+        .with_progress_file(Some(progress_path.clone()));
+
+        tool.run().unwrap();
+        assert_eq!(git_state.borrow().commit_messages.len(), 2);
+        let progress: SyncProgress =
+            serde_json::from_slice(&std::fs::read(&progress_path).unwrap()).unwrap();
+        assert_eq!(progress.state, ProgressState::Completed);
+        assert_eq!((progress.done, progress.total), (2, 2));
+        assert_eq!(progress.last_error, None);
+    }
+
+    struct RecordingNotifier(Rc<RefCell<Vec<String>>>);
+
+    impl Notifier for RecordingNotifier {
+        fn notify_started(&self, config: &SyncConfig) -> crate::error::Result<()> {
+            self.0
+                .borrow_mut()
+                .push(format!("started {}", config.git_dir.display()));
+            Ok(())
+        }
+
+        fn notify_finished(&self, report: &SyncReport) -> crate::error::Result<()> {
+            self.0
+                .borrow_mut()
+                .push(format!("finished {}", report.revisions));
+            Ok(())
+        }
+
+        fn notify_failed(
+            &self,
+            _config: &SyncConfig,
+            error: &SyncError,
+        ) -> crate::error::Result<()> {
+            self.0.borrow_mut().push(format!("failed {error}"));
+            // 通知失败不影响同步结果
+            Err(SyncError::App("webhook 不可用".into()))
+        }
+    }
+
+    #[test]
+    fn test_run_should_notify_started_and_finished_or_failed() {
+        let build = |fail_push: bool, saves: usize| {
+            let events = Rc::new(RefCell::new(Vec::new()));
+            let mut interactor = MockUserInteractor::new();
+            interactor.expect_confirm_sync().returning(|_, _| true);
+            let (git_ops, git_state) = TestGitOperations::new("");
+            git_state.borrow_mut().fail_push = fail_push;
+            let tool = SyncTool::with_svn_operations(
+                create_config(),
+                create_history_manager(saves),
+                Box::new(interactor),
+                Box::new(git_ops),
+                Box::new(create_svn_ops(&[("1", "a"), ("2", "b")])),
+            )
+            .with_publish_policy(PublishPolicy {
+                push: fail_push,
+                ..Default::default()
+            })
+            .with_notifier(Box::new(RecordingNotifier(events.clone())));
+            (tool, events)
+        };
+
+        let (mut tool, events) = build(false, 2);
+        tool.run().unwrap();
+        assert_eq!(
+            *events.borrow(),
+            [
+                format!("started {}", create_config().git_dir.display()),
+                "finished 2".into()
+            ]
+        );
+
+        let (mut tool, events) = build(true, 2);
+        let err = tool.run().unwrap_err().to_string();
+        assert_eq!(events.borrow().len(), 2);
+        assert_eq!(events.borrow()[1], format!("failed {err}"));
+
+        // dry-run 不通知
+        let (mut tool, events) = build(false, 0);
+        tool.run_with_options(&SyncRunOptions {
+            dry_run: true,
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(events.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_run_in_watch_mode_should_skip_idle_round() {
+        let run = |last_synced: &str| {
+            let events = Rc::new(RefCell::new(Vec::new()));
+            let mut interactor = MockUserInteractor::new();
+            interactor.expect_confirm_sync().returning(|_, _| true);
+            let (git_ops, git_state) = TestGitOperations::new("");
+            let mut record =
+                HistoryRecord::new(1, PathBuf::from("svn_dir"), PathBuf::from("git_dir"));
+            record.set_sync_state(last_synced, "hash-old");
+            let saved = Arc::new(Mutex::new(Vec::new()));
+            let mut tool = SyncTool::with_svn_operations(
+                create_config(),
+                create_recording_history(record, saved.clone()),
+                Box::new(interactor),
+                Box::new(git_ops),
+                Box::new(create_svn_ops(&[("1", "a"), ("2", "b")])),
+            )
+            .with_publish_policy(PublishPolicy {
+                push: true,
+                ..Default::default()
+            })
+            .with_watch(true)
+            .with_notifier(Box::new(RecordingNotifier(events.clone())));
+            tool.run().unwrap();
+            let pushes = git_state.borrow().pushes.len();
+            let saves = saved.lock().unwrap().len();
+            (events.borrow().len(), pushes, saves)
+        };
+
+        // 没有新版本：不通知、不推送、不保存
+        assert_eq!(run("2"), (0, 0, 0));
+        // 有新版本时执行完整流程
+        let (events, pushes, _) = run("1");
+        assert_eq!((events, pushes), (2, 1));
+    }
+
+    #[test]
+    fn test_run_with_backoff_should_alert_once_and_skip_while_backing_off() {
+        use crate::config::BackoffPolicy;
+        use std::time::Duration;
+
+        let build = |policy: BackoffPolicy| {
+            let events = Rc::new(RefCell::new(Vec::new()));
+            let mut interactor = MockUserInteractor::new();
+            interactor.expect_confirm_sync().returning(|_, _| true);
+            let svn_ops = create_svn_ops(&[("1", "a")]);
+            svn_ops.fail_update_at("1");
+            let (git_ops, _) = TestGitOperations::new("");
+            let history = create_recording_history(
+                HistoryRecord::new(1, PathBuf::from("svn_dir"), PathBuf::from("git_dir")),
+                Arc::new(Mutex::new(Vec::new())),
+            );
+            let tool = SyncTool::with_svn_operations(
+                create_config(),
+                history,
+                Box::new(interactor),
+                Box::new(git_ops),
+                Box::new(svn_ops),
+            )
+            .with_backoff(Some(policy))
+            .with_notifier(Box::new(RecordingNotifier(events.clone())));
+            (tool, events)
+        };
+
+        // 不退避时每轮都同步，但只在连续失败达到阈值时发送一次失败通知
+        let (mut tool, events) = build(BackoffPolicy {
+            base_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+            unhealthy_threshold: 3,
+        });
+        for _ in 0..4 {
+            assert!(tool.run().is_err());
+        }
+        let events = events.borrow();
+        assert_eq!(
+            events.iter().filter(|e| e.starts_with("started")).count(),
+            4
+        );
+        assert_eq!(events.iter().filter(|e| e.starts_with("failed")).count(), 1);
+        assert!(events[3].starts_with("failed"));
+
+        // 退避期内跳过，不加锁也不通知
+        let (mut tool, events) = build(BackoffPolicy::default());
+        assert!(tool.run().is_err());
+        tool.run().unwrap();
+        assert_eq!(events.borrow().len(), 1);
     }
 }
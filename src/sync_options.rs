@@ -0,0 +1,113 @@
+//! 同步工具的选项
+//!
+//! 按用途分组：选取哪些版本、如何生成提交、同步完成后发布到哪里、
+//! 同步工具自身的状态文件，以及通知和输出。
+
+use std::path::PathBuf;
+
+use chrono::FixedOffset;
+
+use crate::{
+    authors::AuthorRule, filter::PathFilter, interactor::ConfirmThreshold, message::MessageLimits,
+    notify::Notifier, output::Reporter, progress::ProgressFile,
+};
+
+/// 版本的选取方式
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RevisionPolicy {
+    /// 只同步该 SVN 变更列表中的文件，`None` 表示同步整个工作副本
+    ///
+    /// 每个版本只把变更列表中的文件更新到该版本，Git 中也只提交这些文件
+    pub changelist: Option<String>,
+    /// 跳过版本的路径规则：版本的所有变更路径（仓库路径，不含开头的 `/`）都被排除时跳过该版本
+    pub skip_paths: PathFilter,
+    /// 跳过版本的 SVN 作者，更改随下一个同步的版本进入 Git
+    pub skip_authors: Vec<String>,
+    /// 连续版本合并为一个 Git 提交的 SVN 作者，提交说明列出每个版本
+    pub merge_authors: Vec<String>,
+    /// 是否按 trunk/branches/tags 布局把 SVN 分支映射为 Git 分支
+    ///
+    /// 开启后 SVN 工作副本应检出布局根目录，每个版本提交到对应的 Git 分支
+    pub layout: bool,
+    /// 同步前询问确认的阈值，未设置任何阈值时每次都询问
+    pub confirm_threshold: ConfirmThreshold,
+}
+
+impl RevisionPolicy {
+    /// 按通配规则设置跳过版本的路径
+    ///
+    /// # 参数
+    ///
+    /// * `patterns` - [`PathFilter`] 通配规则，为空表示不跳过
+    pub fn with_skip_paths(mut self, patterns: Vec<String>) -> Self {
+        self.skip_paths = PathFilter::new(Vec::new(), patterns);
+        self
+    }
+}
+
+/// Git 提交的生成方式
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommitPolicy {
+    /// 校验清单输出目录，设置后每个提交中所有文件的 SHA-256 写入该目录
+    pub manifest_dir: Option<PathBuf>,
+    /// 是否为每个提交添加记录 SVN 元数据的 Git note
+    ///
+    /// note 写入 [`SVN_NOTES_REF`](crate::SVN_NOTES_REF)，包含 SVN 版本、作者和变更路径，提交说明保持不变
+    pub git_notes: bool,
+    /// 是否使用目录分离的复制策略：每次更新工作副本后把其中除 `.svn` 以外的文件镜像到 Git 目录
+    pub export: bool,
+    /// 是否在提交说明末尾列出 SVN 变更路径
+    pub list_changed_files: bool,
+    /// 是否在提交说明末尾添加 git-svn 格式的 `git-svn-id` 记录
+    pub git_svn_id: bool,
+    /// 是否把复制到 `tags/<名称>` 的版本转换为 Git 附注标签，需要同时开启分支映射
+    pub git_tags: bool,
+    /// 提交说明标题行和正文的宽度限制
+    pub message_limits: MessageLimits,
+    /// 是否在提交前还原 `svn:keywords` 文件中展开的关键字
+    pub strip_keywords: bool,
+    /// 是否使用 SVN 版本时间作为 Git 提交时间，版本时间乱序时按同步策略中的 `date-order` 处理
+    pub preserve_dates: bool,
+    /// 保留的提交时间使用的时区偏移，`None` 表示保留 SVN 记录的 UTC 时间
+    pub date_timezone: Option<FixedOffset>,
+    /// 是否使用 SVN 作者作为 Git 提交的作者
+    pub preserve_authors: bool,
+    /// 共享的 SVN 服务账号，其提交按作者回退规则归属
+    pub service_accounts: Vec<String>,
+    /// SVN 作者缺失或为服务账号时的作者回退规则，按顺序匹配
+    pub author_rules: Vec<AuthorRule>,
+}
+
+/// 同步完成后的发布方式
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PublishPolicy {
+    /// Git 远程仓库地址，同步结束时设置为 [`GIT_REMOTE`](crate::GIT_REMOTE)，
+    /// `None` 表示使用仓库已有的设置
+    pub git_remote: Option<String>,
+    /// 同步完成后生成的 git bundle 文件，`None` 表示不生成
+    pub bundle: Option<PathBuf>,
+    /// 是否推送所有分支和标签到 [`GIT_REMOTE`](crate::GIT_REMOTE)，关闭时仍按 push 策略决定
+    pub push: bool,
+}
+
+/// 同步工具自身的状态文件
+#[derive(Debug, Default)]
+pub(crate) struct SyncFiles {
+    /// 同步锁文件，`None` 表示不加锁
+    pub lock_file: Option<PathBuf>,
+    /// 锁被占用时是否等待另一个同步结束，否则直接返回错误
+    pub wait_for_lock: bool,
+    /// 进度文件
+    pub progress: ProgressFile,
+    /// 配置文件等状态文件，位于 Git 目录中的会加入 `.git/info/exclude`
+    pub state_files: Vec<PathBuf>,
+}
+
+/// 同步过程的通知和输出
+#[derive(Default)]
+pub(crate) struct SyncHooks {
+    /// 同步开始、完成和失败时按顺序通知
+    pub notifiers: Vec<Box<dyn Notifier>>,
+    /// 同步过程的输出
+    pub reporter: Reporter,
+}
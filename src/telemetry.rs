@@ -0,0 +1,470 @@
+//! 链路追踪：按同步对、SVN 版本和外部命令记录 span
+//!
+//! 启用 `otel` 特性编译，并设置 `OTEL_EXPORTER_OTLP_ENDPOINT`（或
+//! `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`）环境变量后，[`flush_traces`] 通过 OTLP/HTTP（JSON 编码）
+//! 导出已结束的 span；否则所有操作都不记录任何内容。
+//! 设置了 W3C `TRACEPARENT` 环境变量时，本进程的 span 挂在该 span 之下，
+//! 因此 sync-all 子进程的 span 与主进程属于同一条链路。
+
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    hash::{BuildHasher, Hasher, RandomState},
+    process::{Command, ExitStatus, Output},
+    sync::{Mutex, OnceLock},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::json;
+
+use crate::{
+    notify::post_json,
+    ops::{command_error, output_with_timeout},
+};
+
+/// 是否编译了 OTLP 导出
+const ENABLED: bool = cfg!(feature = "otel");
+
+/// OTLP 导出地址，后面追加 `/v1/traces`
+const ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+/// 完整的 traces 导出地址，优先于 [`ENDPOINT_ENV`]
+const TRACES_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT";
+/// 上报的服务名
+const SERVICE_NAME_ENV: &str = "OTEL_SERVICE_NAME";
+/// 父进程传入的链路上下文
+const TRACEPARENT_ENV: &str = "TRACEPARENT";
+
+/// 已结束、等待导出的 span
+static FINISHED: Mutex<Vec<SpanData>> = Mutex::new(Vec::new());
+
+thread_local! {
+    /// 本线程正在进行的 span：(trace_id, span_id)
+    static ACTIVE: RefCell<Vec<(String, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// span 类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SpanKind {
+    /// 程序内部的操作
+    Internal,
+    /// 调用外部命令
+    Client,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct SpanData {
+    trace_id: String,
+    span_id: String,
+    parent_span_id: Option<String>,
+    name: String,
+    kind: SpanKind,
+    start_unix_nano: u128,
+    end_unix_nano: u128,
+    attributes: Vec<(String, String)>,
+    error: Option<String>,
+}
+
+/// 正在进行的 span，离开作用域时结束
+///
+/// 没有启用导出时不记录任何内容
+#[derive(Debug)]
+#[must_use = "span 离开作用域时结束，应绑定到变量"]
+pub struct Span {
+    data: Option<RefCell<SpanData>>,
+}
+
+impl Span {
+    /// 开始记录 span，父 span 为本线程当前的 span
+    fn start(name: &str, kind: SpanKind) -> Self {
+        let parent = ACTIVE
+            .with(|active| active.borrow().last().cloned())
+            .or_else(remote_parent);
+        let (trace_id, parent_span_id) = match parent {
+            Some((trace_id, span_id)) => (trace_id, Some(span_id)),
+            None => (random_hex(16), None),
+        };
+        let span_id = random_hex(8);
+        ACTIVE.with(|active| {
+            active
+                .borrow_mut()
+                .push((trace_id.clone(), span_id.clone()))
+        });
+        Self {
+            data: Some(RefCell::new(SpanData {
+                trace_id,
+                span_id,
+                parent_span_id,
+                name: name.to_string(),
+                kind,
+                start_unix_nano: unix_nanos(),
+                end_unix_nano: 0,
+                attributes: Vec::new(),
+                error: None,
+            })),
+        }
+    }
+
+    /// 添加属性
+    ///
+    /// # 参数
+    ///
+    /// * `key`: 属性名
+    /// * `value`: 属性值
+    pub fn with_attribute(self, key: &str, value: impl Display) -> Self {
+        self.set_attribute(key, value);
+        self
+    }
+
+    /// 设置属性
+    ///
+    /// # 参数
+    ///
+    /// * `key`: 属性名
+    /// * `value`: 属性值
+    pub fn set_attribute(&self, key: &str, value: impl Display) {
+        if let Some(data) = &self.data {
+            data.borrow_mut()
+                .attributes
+                .push((key.to_string(), value.to_string()));
+        }
+    }
+
+    /// 把 span 标记为失败
+    ///
+    /// # 参数
+    ///
+    /// * `error`: 失败原因
+    pub fn record_error(&self, error: &impl Display) {
+        if let Some(data) = &self.data {
+            data.borrow_mut().error = Some(error.to_string());
+        }
+    }
+
+    /// 记录外部命令的退出状态，非零退出时标记为失败
+    pub(crate) fn record_exit(&self, status: &ExitStatus) {
+        if let Some(code) = status.code() {
+            self.set_attribute("process.exit.code", code);
+        }
+        if !status.success() {
+            self.record_error(&format!("命令异常退出（{status}）"));
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some(data) = self.data.take() else {
+            return;
+        };
+        let mut data = data.into_inner();
+        data.end_unix_nano = unix_nanos();
+        ACTIVE.with(|active| {
+            active
+                .borrow_mut()
+                .retain(|(_, span_id)| *span_id != data.span_id)
+        });
+        if let Ok(mut finished) = FINISHED.lock() {
+            finished.push(data);
+        }
+    }
+}
+
+/// 开始一个 span
+///
+/// # 参数
+///
+/// * `name`: span 名称，如 `svn2git.sync`
+pub fn trace_span(name: &str) -> Span {
+    match enabled() {
+        true => Span::start(name, SpanKind::Internal),
+        false => Span { data: None },
+    }
+}
+
+/// 为外部命令开始一个 span，名称为程序名和子命令，如 `svn update`
+///
+/// 只记录程序名和子命令，不记录完整参数，避免泄露命令行中的凭据
+///
+/// # 参数
+///
+/// * `command`: 外部命令
+pub(crate) fn command_span(command: &Command) -> Span {
+    if !enabled() {
+        return Span { data: None };
+    }
+    let program = command.get_program().to_string_lossy().into_owned();
+    let name = match command.get_args().next() {
+        Some(subcommand) => format!("{program} {}", subcommand.to_string_lossy()),
+        None => program.clone(),
+    };
+    Span::start(&name, SpanKind::Client).with_attribute("process.executable.name", program)
+}
+
+/// 执行外部命令并记录 span
+pub(crate) trait TracedCommand {
+    /// 与 [`Command::output`] 相同，同时记录命令的耗时和退出状态
+    ///
+    /// 设置了超时时间时，超时后终止子进程并返回 [`SyncError::CommandTimeout`](crate::SyncError::CommandTimeout)
+    ///
+    /// # 参数
+    ///
+    /// * `timeout`: 超时时间，`None` 表示不限制
+    fn traced_output(&mut self, timeout: Option<Duration>) -> crate::error::Result<Output>;
+
+    /// 与 [`TracedCommand::traced_output`] 相同，命令以非零状态退出时返回
+    /// [`SyncError::GitCommand`](crate::SyncError::GitCommand) 或
+    /// [`SyncError::SvnCommand`](crate::SyncError::SvnCommand)
+    ///
+    /// # 参数
+    ///
+    /// * `action`: 失败的操作，用于错误信息
+    /// * `timeout`: 超时时间，`None` 表示不限制
+    fn checked_output(
+        &mut self,
+        action: &str,
+        timeout: Option<Duration>,
+    ) -> crate::error::Result<Output>;
+}
+
+impl TracedCommand for Command {
+    fn traced_output(&mut self, timeout: Option<Duration>) -> crate::error::Result<Output> {
+        let span = command_span(self);
+        let output = output_with_timeout(self, timeout);
+        match &output {
+            Ok(output) => span.record_exit(&output.status),
+            Err(e) => span.record_error(e),
+        }
+        output
+    }
+
+    fn checked_output(
+        &mut self,
+        action: &str,
+        timeout: Option<Duration>,
+    ) -> crate::error::Result<Output> {
+        let output = self.traced_output(timeout)?;
+        if !output.status.success() {
+            return Err(command_error(action, self, &output));
+        }
+        Ok(output)
+    }
+}
+
+/// 本线程当前 span 的 W3C `traceparent`，用于把链路上下文传给子进程
+///
+/// 没有启用导出或当前没有 span 时返回 `None`
+pub fn traceparent() -> Option<String> {
+    ACTIVE.with(|active| {
+        active
+            .borrow()
+            .last()
+            .map(|(trace_id, span_id)| format!("00-{trace_id}-{span_id}-01"))
+    })
+}
+
+/// 导出已结束的 span
+///
+/// 导出失败只输出警告，不影响同步结果
+pub fn flush_traces() {
+    let Some(url) = traces_endpoint() else {
+        return;
+    };
+    let spans = match FINISHED.lock() {
+        Ok(mut finished) => std::mem::take(&mut *finished),
+        Err(_) => return,
+    };
+    if spans.is_empty() {
+        return;
+    }
+    let service_name = std::env::var(SERVICE_NAME_ENV).unwrap_or_else(|_| "svn2git".into());
+    if let Err(e) = post_json(url, &otlp_payload(&service_name, &spans)) {
+        eprintln!("警告：导出链路追踪数据失败：{e}");
+    }
+}
+
+/// 是否记录 span
+fn enabled() -> bool {
+    traces_endpoint().is_some()
+}
+
+/// traces 导出地址，没有编译 `otel` 特性或没有配置时为 `None`
+fn traces_endpoint() -> Option<&'static str> {
+    static ENDPOINT: OnceLock<Option<String>> = OnceLock::new();
+    ENDPOINT
+        .get_or_init(|| {
+            if !ENABLED {
+                return None;
+            }
+            let non_empty = |name| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+            non_empty(TRACES_ENDPOINT_ENV).or_else(|| {
+                non_empty(ENDPOINT_ENV)
+                    .map(|endpoint| format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+            })
+        })
+        .as_deref()
+}
+
+/// 父进程通过 `TRACEPARENT` 传入的链路上下文：(trace_id, span_id)
+fn remote_parent() -> Option<(String, String)> {
+    static PARENT: OnceLock<Option<(String, String)>> = OnceLock::new();
+    PARENT
+        .get_or_init(|| parse_traceparent(&std::env::var(TRACEPARENT_ENV).ok()?))
+        .clone()
+}
+
+/// 解析 W3C `traceparent`：`00-<32 位十六进制>-<16 位十六进制>-<标志>`
+fn parse_traceparent(value: &str) -> Option<(String, String)> {
+    let mut parts = value.trim().split('-');
+    let (_version, trace_id, span_id, _flags) =
+        (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+    let is_id = |id: &str, len: usize| {
+        id.len() == len && id.chars().all(|c| c.is_ascii_hexdigit()) && id.chars().any(|c| c != '0')
+    };
+    (is_id(trace_id, 32) && is_id(span_id, 16))
+        .then(|| (trace_id.to_ascii_lowercase(), span_id.to_ascii_lowercase()))
+}
+
+/// OTLP/HTTP JSON 格式的请求体
+fn otlp_payload(service_name: &str, spans: &[SpanData]) -> serde_json::Value {
+    let attribute =
+        |key: &str, value: &str| json!({ "key": key, "value": { "stringValue": value } });
+    let spans: Vec<_> = spans
+        .iter()
+        .map(|span| {
+            let mut value = json!({
+                "traceId": span.trace_id,
+                "spanId": span.span_id,
+                "name": span.name,
+                "kind": match span.kind {
+                    SpanKind::Internal => 1,
+                    SpanKind::Client => 3,
+                },
+                "startTimeUnixNano": span.start_unix_nano.to_string(),
+                "endTimeUnixNano": span.end_unix_nano.to_string(),
+                "attributes": span
+                    .attributes
+                    .iter()
+                    .map(|(key, value)| attribute(key, value))
+                    .collect::<Vec<_>>(),
+                "status": match &span.error {
+                    Some(message) => json!({ "code": 2, "message": message }),
+                    None => json!({ "code": 1 }),
+                },
+            });
+            if let Some(parent) = &span.parent_span_id {
+                value["parentSpanId"] = json!(parent);
+            }
+            value
+        })
+        .collect();
+    json!({
+        "resourceSpans": [{
+            "resource": { "attributes": [attribute("service.name", service_name)] },
+            "scopeSpans": [{
+                "scope": { "name": "svn2git", "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans,
+            }],
+        }],
+    })
+}
+
+/// 随机的十六进制 ID
+///
+/// # 参数
+///
+/// * `bytes`: 字节数，trace ID 为 16，span ID 为 8
+fn random_hex(bytes: usize) -> String {
+    let mut id = String::with_capacity(bytes * 2);
+    while id.len() < bytes * 2 {
+        // 每个 RandomState 使用不同的随机密钥
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u128(unix_nanos());
+        hasher.write_u32(std::process::id());
+        id.push_str(&format!("{:016x}", hasher.finish()));
+    }
+    id.truncate(bytes * 2);
+    id
+}
+
+fn unix_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 取出指定链路中已结束的 span
+    fn take_trace(trace_id: &str) -> Vec<SpanData> {
+        let mut finished = FINISHED.lock().unwrap();
+        let (trace, rest) = finished.drain(..).partition(|s| s.trace_id == trace_id);
+        *finished = rest;
+        trace
+    }
+
+    #[test]
+    fn test_spans_should_nest_and_export_as_otlp() {
+        let trace_id = {
+            let pair =
+                Span::start("svn2git.sync", SpanKind::Internal).with_attribute("git.dir", "d:/git");
+            let (trace_id, pair_id) = parse_traceparent(&traceparent().unwrap()).unwrap();
+            {
+                let revision = Span::start("svn2git.revision", SpanKind::Internal);
+                revision.record_error(&"svn update 失败");
+                let child = traceparent().unwrap();
+                assert!(child.starts_with(&format!("00-{trace_id}-")));
+                assert!(!child.contains(&pair_id));
+            }
+            assert!(traceparent().unwrap().contains(&pair_id));
+            drop(pair);
+            trace_id
+        };
+        assert_eq!(traceparent(), None);
+
+        let spans = take_trace(&trace_id);
+        assert_eq!(spans.len(), 2);
+        let (revision, pair) = (&spans[0], &spans[1]);
+        assert_eq!(revision.parent_span_id.as_ref(), Some(&pair.span_id));
+        assert!(pair.end_unix_nano >= pair.start_unix_nano);
+
+        let payload = otlp_payload("svn2git", &spans);
+        let exported = &payload["resourceSpans"][0]["scopeSpans"][0]["spans"];
+        assert_eq!(exported[0]["name"], "svn2git.revision");
+        assert_eq!(exported[0]["parentSpanId"], json!(pair.span_id));
+        assert_eq!(
+            exported[0]["status"],
+            json!({ "code": 2, "message": "svn update 失败" })
+        );
+        assert_eq!(exported[1].get("parentSpanId"), None);
+        assert_eq!(
+            exported[1]["attributes"],
+            json!([{ "key": "git.dir", "value": { "stringValue": "d:/git" } }])
+        );
+        assert_eq!(
+            payload["resourceSpans"][0]["resource"]["attributes"][0]["value"]["stringValue"],
+            "svn2git"
+        );
+    }
+
+    #[test]
+    fn test_parse_traceparent() {
+        assert_eq!(
+            parse_traceparent("00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01"),
+            Some((
+                "4bf92f3577b34da6a3ce929d0e0e4736".into(),
+                "00f067aa0ba902b7".into()
+            ))
+        );
+        assert_eq!(
+            parse_traceparent("00-00000000000000000000000000000000-00f067aa0ba902b7-01"),
+            None
+        );
+        assert_eq!(parse_traceparent("00-abc-00f067aa0ba902b7-01"), None);
+        assert_eq!(parse_traceparent("garbage"), None);
+        assert_eq!(random_hex(16).len(), 32);
+        assert_ne!(random_hex(8), random_hex(8));
+    }
+}
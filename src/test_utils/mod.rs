@@ -2,15 +2,20 @@
 //!
 //! 提供用于单元测试的Mock工具和辅助函数，避免测试依赖外部的SVN和Git命令
 
-pub mod mock_svn;
+pub mod scripted;
 pub mod test_factories;
 
 // 重新导出常用的测试工具
-pub use mock_svn::*;
+pub use scripted::{ScriptedAnswer, ScriptedUserInteractor};
 pub use test_factories::{GitTestHelper, TestData, TestFactory};
 
+// 重新导出内存存储，构造 HistoryManager 时无需 mock 期望
+pub use crate::config::MemoryStorage;
+
 // 重新导出统一Mock实现
-pub use crate::ops::{GitProvider, MockGitOperations, ProviderType};
+pub use crate::ops::{
+    GitProvider, MockGitOperations, MockSvnOperations, MockSvnRepo, ProviderType, SvnProvider,
+};
 
 use std::path::PathBuf;
 
@@ -0,0 +1,143 @@
+//! 按脚本回答的用户交互器
+
+use std::{
+    collections::VecDeque,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    config::{DirtyTreePolicy, HistoryRecord},
+    error::Result,
+    interactor::UserInteractor,
+    ops::{RevisionDiff, SvnLog},
+};
+
+/// 脚本中的一个回答
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptedAnswer {
+    /// 选择历史记录的索引
+    SelectHistoryRecord(usize),
+    /// 输入的 SVN 目录
+    SvnDir(String),
+    /// 输入的 Git 目录
+    GitDir(String),
+    /// 是否确认同步
+    ConfirmSync(bool),
+    /// 选择的版本
+    SelectRevisions(Vec<String>),
+    /// 是否确认危险操作
+    ConfirmDestructive(bool),
+    /// 是否初始化 Git 仓库
+    ConfirmGitInit(bool),
+    /// 未提交更改的处理方式
+    DirtyTreePolicy(DirtyTreePolicy),
+}
+
+#[derive(Debug, Default)]
+struct Script {
+    answers: VecDeque<ScriptedAnswer>,
+    asked: Vec<String>,
+}
+
+/// 按预设脚本依次回答的用户交互器
+///
+/// 每次询问取出脚本中的下一个回答，回答的类型与询问不符或脚本已用完时 panic，
+/// 能发现意料之外的询问。克隆的交互器共享同一个脚本，交给 [`SyncTool`](crate::SyncTool)
+/// 后保留一个克隆即可在测试结束时检查询问记录和剩余的回答。
+///
+/// # 示例
+///
+/// ```
+/// use svn2git::{UserInteractor, test_utils::{ScriptedAnswer, ScriptedUserInteractor}};
+///
+/// let interactor = ScriptedUserInteractor::new([ScriptedAnswer::ConfirmSync(true)]);
+/// assert!(interactor.confirm_sync(&[], &[]));
+/// assert_eq!(interactor.asked(), ["confirm_sync"]);
+/// assert!(interactor.is_finished());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedUserInteractor {
+    script: Arc<Mutex<Script>>,
+}
+
+impl ScriptedUserInteractor {
+    /// 创建交互器
+    ///
+    /// # 参数
+    ///
+    /// * `answers` - 按询问顺序排列的回答
+    pub fn new(answers: impl IntoIterator<Item = ScriptedAnswer>) -> Self {
+        Self {
+            script: Arc::new(Mutex::new(Script {
+                answers: answers.into_iter().collect(),
+                asked: Vec::new(),
+            })),
+        }
+    }
+
+    /// 已经询问过的问题（方法名，按询问顺序）
+    pub fn asked(&self) -> Vec<String> {
+        self.script.lock().unwrap().asked.clone()
+    }
+
+    /// 脚本中的回答是否都已用完
+    pub fn is_finished(&self) -> bool {
+        self.script.lock().unwrap().answers.is_empty()
+    }
+
+    fn next(&self, question: &str) -> ScriptedAnswer {
+        let mut script = self.script.lock().unwrap();
+        script.asked.push(question.to_string());
+        script
+            .answers
+            .pop_front()
+            .unwrap_or_else(|| panic!("脚本已用完，意外的询问：{question}"))
+    }
+}
+
+/// 取出下一个回答，类型与询问不符时 panic
+macro_rules! answer {
+    ($self:ident, $question:literal, $pattern:pat => $value:expr) => {
+        match $self.next($question) {
+            $pattern => $value,
+            other => panic!("询问 {} 时脚本中的回答为 {other:?}", $question),
+        }
+    };
+}
+
+impl UserInteractor for ScriptedUserInteractor {
+    fn select_history_record(&self, _records: &[HistoryRecord]) -> Result<usize> {
+        Ok(
+            answer!(self, "select_history_record", ScriptedAnswer::SelectHistoryRecord(index) => index),
+        )
+    }
+
+    fn input_svn_dir(&self) -> Result<String> {
+        Ok(answer!(self, "input_svn_dir", ScriptedAnswer::SvnDir(dir) => dir))
+    }
+
+    fn input_git_dir(&self) -> Result<String> {
+        Ok(answer!(self, "input_git_dir", ScriptedAnswer::GitDir(dir) => dir))
+    }
+
+    fn confirm_sync(&self, _svn_logs: &[SvnLog], _diffs: &[RevisionDiff]) -> bool {
+        answer!(self, "confirm_sync", ScriptedAnswer::ConfirmSync(confirmed) => confirmed)
+    }
+
+    fn select_revisions(&self, _svn_logs: &[SvnLog]) -> Result<Vec<String>> {
+        Ok(answer!(self, "select_revisions", ScriptedAnswer::SelectRevisions(revs) => revs))
+    }
+
+    fn confirm_destructive(&self, _action: &str, _label: &str) -> bool {
+        answer!(self, "confirm_destructive", ScriptedAnswer::ConfirmDestructive(confirmed) => confirmed)
+    }
+
+    fn confirm_git_init(&self, _git_dir: &Path) -> bool {
+        answer!(self, "confirm_git_init", ScriptedAnswer::ConfirmGitInit(confirmed) => confirmed)
+    }
+
+    fn choose_dirty_tree_policy(&self, _git_dir: &Path) -> DirtyTreePolicy {
+        answer!(self, "choose_dirty_tree_policy", ScriptedAnswer::DirtyTreePolicy(policy) => policy)
+    }
+}
@@ -27,12 +27,12 @@ impl TestFactory {
     pub fn create_sync_config(use_real_git: bool) -> SyncConfig {
         let svn_dir = TestFactory::test_path(&["svn"]);
         let git_dir = TestFactory::test_path(&["git"]);
-        let git_provider = if use_real_git {
+        let provider = if use_real_git {
             ProviderType::Real
         } else {
             ProviderType::Mock
         };
-        SyncConfig::with_git_provider(svn_dir, git_dir, git_provider)
+        SyncConfig::with_providers(svn_dir, git_dir, provider.clone(), provider)
     }
 
     /// 创建测试路径
@@ -78,16 +78,17 @@ pub struct TestData {
 }
 
 impl TestData {
-    /// 创建使用Mock Git的SyncConfig
+    /// 创建使用Mock Git和Mock SVN的SyncConfig
     ///
     /// # 返回值
     ///
     /// 返回配置好的SyncConfig
     pub fn create_mock_sync_config(&self) -> SyncConfig {
-        SyncConfig::with_git_provider(
+        SyncConfig::with_providers(
             self.svn_dir.clone(),
             self.git_dir.clone(),
             ProviderType::Mock,
+            ProviderType::Mock,
         )
     }
 
@@ -169,6 +170,7 @@ mod tests {
     fn test_create_sync_config() {
         let config = TestFactory::create_sync_config(false);
         assert_eq!(config.git_provider, ProviderType::Mock);
+        assert_eq!(config.svn_provider, ProviderType::Mock);
 
         let config = TestFactory::create_sync_config(true);
         assert_eq!(config.git_provider, ProviderType::Real);
@@ -0,0 +1,292 @@
+//! 监视模式：定期轮询 SVN 并同步新版本
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use chrono::Local;
+
+use crate::{config::HistoryRecord, error::Result};
+
+/// 监视模式的停止信号
+///
+/// 可在信号处理线程中调用 [`StopSignal::stop`] 或 [`StopSignal::request_reload`]，
+/// 正在等待下一轮的监视循环会立即醒来
+#[derive(Debug, Clone, Default)]
+pub struct StopSignal {
+    inner: Arc<(Mutex<SignalState>, Condvar)>,
+}
+
+#[derive(Debug, Default)]
+struct SignalState {
+    stopped: bool,
+    reload: bool,
+}
+
+impl StopSignal {
+    /// 创建未触发的停止信号
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 触发停止
+    pub fn stop(&self) {
+        self.update(|state| state.stopped = true);
+    }
+
+    /// 请求重新加载配置，正在等待的监视循环立即开始下一轮
+    ///
+    /// 不会打断正在进行的同步，本轮结束后才开始下一轮
+    pub fn request_reload(&self) {
+        self.update(|state| state.reload = true);
+    }
+
+    /// 是否已触发停止
+    pub fn is_stopped(&self) -> bool {
+        self.lock().stopped
+    }
+
+    /// 等待指定时长，期间触发停止或请求重新加载时提前返回
+    ///
+    /// # 返回
+    ///
+    /// 是否已触发停止
+    pub fn wait(&self, timeout: Duration) -> bool {
+        let (_, condvar) = &*self.inner;
+        let (mut guard, _) = condvar
+            .wait_timeout_while(self.lock(), timeout, |state| {
+                !state.stopped && !state.reload
+            })
+            .unwrap_or_else(|e| e.into_inner());
+        guard.reload = false;
+        guard.stopped
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SignalState> {
+        self.inner.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn update(&self, change: impl FnOnce(&mut SignalState)) {
+        change(&mut self.lock());
+        self.inner.1.notify_all();
+    }
+}
+
+/// 各同步对的监视间隔
+///
+/// 同步对可以在历史记录中设置自己的间隔，没有设置时使用 watch 的 `--interval`。
+/// 每轮从重新读取的历史记录中取间隔，修改在下一轮生效。
+#[derive(Debug, Clone)]
+pub struct WatchSchedule {
+    /// 没有设置间隔的同步对使用的间隔
+    default_interval: Duration,
+    /// 各同步对上次同步的时间
+    last_run: HashMap<(PathBuf, PathBuf), Instant>,
+}
+
+impl WatchSchedule {
+    /// 创建监视间隔
+    ///
+    /// # 参数
+    ///
+    /// * `default_interval`: 没有设置间隔的同步对使用的间隔
+    pub fn new(default_interval: Duration) -> Self {
+        Self {
+            default_interval,
+            last_run: HashMap::new(),
+        }
+    }
+
+    /// 同步对的监视间隔
+    pub fn interval(&self, record: &HistoryRecord) -> Duration {
+        record
+            .watch_interval()
+            .map_or(self.default_interval, Duration::from_secs)
+    }
+
+    /// 同步对距离下一次同步的时长，从未同步过时为零
+    ///
+    /// # 参数
+    ///
+    /// * `record`: 同步对
+    /// * `now`: 当前时间
+    pub fn remaining(&self, record: &HistoryRecord, now: Instant) -> Duration {
+        let key = (record.svn_path().clone(), record.git_path().clone());
+        self.last_run.get(&key).map_or(Duration::ZERO, |last| {
+            self.interval(record)
+                .saturating_sub(now.saturating_duration_since(*last))
+        })
+    }
+
+    /// 同步对本轮是否需要同步
+    ///
+    /// # 参数
+    ///
+    /// * `record`: 同步对
+    /// * `now`: 当前时间
+    pub fn is_due(&self, record: &HistoryRecord, now: Instant) -> bool {
+        self.remaining(record, now).is_zero()
+    }
+
+    /// 记录同步对本轮已同步
+    ///
+    /// # 参数
+    ///
+    /// * `svn_path`: SVN 路径
+    /// * `git_path`: Git 路径
+    /// * `now`: 同步开始的时间
+    pub fn mark_run(&mut self, svn_path: PathBuf, git_path: PathBuf, now: Instant) {
+        self.last_run.insert((svn_path, git_path), now);
+    }
+
+    /// 距离下一轮的等待时长，取各同步对剩余时长的最小值
+    ///
+    /// 没有同步对时使用默认间隔
+    ///
+    /// # 参数
+    ///
+    /// * `records`: 参与监视的同步对
+    /// * `now`: 当前时间
+    pub fn next_wait<'a>(
+        &self,
+        records: impl IntoIterator<Item = &'a HistoryRecord>,
+        now: Instant,
+    ) -> Duration {
+        records
+            .into_iter()
+            .map(|record| self.remaining(record, now))
+            .min()
+            .unwrap_or(self.default_interval)
+    }
+}
+
+/// 循环同步，直到触发停止信号
+///
+/// 每轮调用一次 `sync_once`，完成后按其返回的时长等待，再开始下一轮；本轮失败时等待 `interval`。
+/// 同步进度由每轮同步自身保存到历史记录，因此某一轮失败时只输出错误，下一轮从已保存的进度继续。
+/// 停止信号和重新加载请求只在两轮之间生效，不会打断正在进行的同步；
+/// 等待期间收到重新加载请求时立即开始下一轮。
+///
+/// # 参数
+///
+/// * `interval`: 本轮失败时到下一轮的等待时长
+/// * `stop`: 停止信号
+/// * `sync_once`: 执行一轮同步，返回到下一轮的等待时长
+///
+/// # 返回
+///
+/// 执行的轮数
+pub fn watch(
+    interval: Duration,
+    stop: &StopSignal,
+    mut sync_once: impl FnMut() -> Result<Duration>,
+) -> usize {
+    let mut rounds = 0;
+    while !stop.is_stopped() {
+        rounds += 1;
+        println!(
+            "[监视 第 {rounds} 轮] {}",
+            Local::now().format("%Y-%m-%d %H:%M:%S")
+        );
+        let wait = sync_once().unwrap_or_else(|e| {
+            eprintln!("本轮同步失败：{e}");
+            interval
+        });
+        println!("{} 秒后开始下一轮", wait.as_secs());
+        if stop.wait(wait) {
+            break;
+        }
+    }
+    println!("已停止监视");
+    rounds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::SyncError;
+
+    #[test]
+    fn test_watch_should_continue_after_failure_until_stopped() {
+        let stop = StopSignal::new();
+        let mut calls = 0;
+        let rounds = watch(Duration::ZERO, &stop, || {
+            calls += 1;
+            if calls == 3 {
+                stop.stop();
+            }
+            match calls {
+                1 => Err(SyncError::App("SVN 服务器不可用".into())),
+                _ => Ok(Duration::ZERO),
+            }
+        });
+        assert_eq!(rounds, 3);
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn test_stop_signal_should_wake_waiting_thread() {
+        let stop = StopSignal::new();
+        assert!(!stop.wait(Duration::ZERO));
+
+        let waiter = stop.clone();
+        let handle = std::thread::spawn(move || waiter.wait(Duration::from_secs(60)));
+        stop.stop();
+        assert!(handle.join().unwrap());
+        assert!(stop.is_stopped());
+    }
+
+    #[test]
+    fn test_reload_request_should_wake_waiting_thread_without_stopping() {
+        let stop = StopSignal::new();
+        let waiter = stop.clone();
+        let handle = std::thread::spawn(move || waiter.wait(Duration::from_secs(60)));
+        stop.request_reload();
+        assert!(!handle.join().unwrap());
+        assert!(!stop.is_stopped());
+
+        // 请求只生效一次
+        assert!(!stop.wait(Duration::ZERO));
+    }
+
+    fn record(svn: &str, interval: Option<u64>) -> HistoryRecord {
+        let mut record = HistoryRecord::new(0, svn.into(), "git".into());
+        record.set_watch_interval(interval);
+        record
+    }
+
+    #[test]
+    fn test_watch_schedule_should_use_pair_interval() {
+        let mut schedule = WatchSchedule::new(Duration::from_secs(300));
+        let fast = record("fast", Some(60));
+        let slow = record("slow", None);
+        let start = Instant::now();
+        assert!(schedule.is_due(&fast, start));
+        assert!(schedule.is_due(&slow, start));
+
+        schedule.mark_run("fast".into(), "git".into(), start);
+        schedule.mark_run("slow".into(), "git".into(), start);
+        let later = start + Duration::from_secs(60);
+        assert!(schedule.is_due(&fast, later));
+        assert!(!schedule.is_due(&slow, later));
+        assert_eq!(
+            schedule.next_wait([&fast, &slow], start),
+            Duration::from_secs(60)
+        );
+    }
+
+    #[test]
+    fn test_watch_schedule_should_follow_changed_interval() {
+        let mut schedule = WatchSchedule::new(Duration::from_secs(300));
+        let start = Instant::now();
+        schedule.mark_run("svn".into(), "git".into(), start);
+        let later = start + Duration::from_secs(120);
+        assert!(!schedule.is_due(&record("svn", None), later));
+        // 重新读取配置后间隔缩短，下一轮立即同步
+        assert!(schedule.is_due(&record("svn", Some(60)), later));
+        assert_eq!(schedule.next_wait([], later), Duration::from_secs(300));
+    }
+}
@@ -6,7 +6,8 @@ mod common;
 
 use common::*;
 use svn2git::{
-    GitOperations, MockGitOperations, ProviderType, git_commit_with_ops, test_utils::TestFactory,
+    GitOperations, MockGitOperations, ProviderType, SvnLogQuery, SvnOperations, SvnProvider,
+    git_commit_with_ops, parse_svn_log_xml, test_utils::TestFactory,
 };
 
 /// 测试：Mock Git状态查询功能应该返回仓库信息
@@ -165,6 +166,39 @@ fn test_sync_config_with_test_factory() {
     println!("✅ SyncConfig使用TestFactory创建成功");
 }
 
+/// 测试：SyncConfig应该能创建Mock SVN操作实例并模拟更新流程
+#[test]
+fn test_mock_svn_operations_from_sync_config() {
+    let config = TestFactory::create_sync_config(false);
+    assert_eq!(config.svn_provider, ProviderType::Mock);
+
+    let svn_ops = config.create_svn_operations();
+    match &svn_ops {
+        SvnProvider::Mock(mock) => {
+            mock.add_log_to_mock(&config.svn_dir, "1", "初始提交");
+            mock.add_log_to_mock(&config.svn_dir, "2", "添加功能");
+        }
+        SvnProvider::Real(_) => panic!("期望创建Mock SVN提供者"),
+    }
+
+    let logs = svn_ops
+        .get_logs(&config.svn_dir, &SvnLogQuery::default())
+        .expect("获取SVN日志失败");
+    assert_eq!(logs.len(), 2);
+
+    svn_ops
+        .update_to_rev(&config.svn_dir, "1")
+        .expect("更新SVN工作副本失败");
+    let info = svn_ops.info(&config.svn_dir).expect("获取SVN信息失败");
+    assert_eq!(info.revision, "1");
+
+    let pending = svn_ops
+        .get_logs(&config.svn_dir, &SvnLogQuery::default())
+        .expect("获取SVN日志失败");
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].version, "2");
+}
+
 /// 测试：SVN日志XML数据格式验证
 #[test]
 fn test_svn_log_xml_format() {
@@ -187,3 +221,29 @@ fn test_svn_log_xml_format() {
 
     println!("✅ SVN日志XML格式验证通过");
 }
+
+/// 测试：不调用 svn 也能解析保存下来的日志
+#[test]
+fn test_parse_saved_svn_log_xml() {
+    let logs = parse_svn_log_xml(MOCK_SVN_LOG_XML.as_bytes(), None).unwrap();
+
+    let summary: Vec<_> = logs
+        .iter()
+        .map(|log| {
+            (
+                log.version.as_str(),
+                log.author.as_str(),
+                log.message.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("123", "张三", "修复了重要bug"),
+            ("124", "李四", "添加了新功能")
+        ]
+    );
+    assert_eq!(logs[1].date, "2024-07-27T14:20:00.000Z");
+    assert!(logs.iter().all(|log| log.paths.is_empty()));
+}
@@ -5,10 +5,12 @@
 mod common;
 
 use common::*;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::{cell::RefCell, path::Path};
-use svn2git::{GitOperations, SyncError, git_commit_with_ops};
+use svn2git::{
+    CommitMetadata, GitCommitEntry, GitOperations, GitSignature, SyncError, git_commit_with_ops,
+};
 
 /// 简化的Mock Git操作实现，用于集成测试
 struct TestMockGitOperations {
@@ -156,6 +158,10 @@ impl GitOperations for TestMockGitOperations {
         }
     }
 
+    fn is_repository(&self, path: &Path) -> std::result::Result<bool, SyncError> {
+        Ok(self.get_repo(path).is_some_and(|repo| repo.initialized))
+    }
+
     fn is_clean(&self, path: &Path) -> std::result::Result<bool, SyncError> {
         println!("模拟检查工作目录是否干净: {:?}", path);
         if let Some(repo) = self.get_repo(path) {
@@ -167,6 +173,157 @@ impl GitOperations for TestMockGitOperations {
             Err(SyncError::App("Git仓库未初始化".to_string()))
         }
     }
+
+    fn rev_parse_head(&self, path: &Path) -> std::result::Result<String, SyncError> {
+        match self.get_repo(path) {
+            Some(repo) if !repo.commits.is_empty() => Ok(format!("commit{}", repo.commits.len())),
+            _ => Err(SyncError::App("Git仓库还没有任何提交".to_string())),
+        }
+    }
+
+    fn commit_history(
+        &self,
+        path: &Path,
+        _notes_ref: &str,
+    ) -> std::result::Result<Vec<GitCommitEntry>, SyncError> {
+        let repo = self
+            .get_repo(path)
+            .ok_or_else(|| SyncError::App("Git仓库还没有任何提交".to_string()))?;
+        Ok(repo
+            .commits
+            .iter()
+            .enumerate()
+            .map(|(idx, commit)| GitCommitEntry {
+                hash: format!("commit{}", idx + 1),
+                message: commit.message.clone(),
+                note: None,
+            })
+            .collect())
+    }
+
+    fn root_commit(&self, path: &Path) -> std::result::Result<String, SyncError> {
+        match self.get_repo(path) {
+            Some(repo) if !repo.commits.is_empty() => Ok("commit1".to_string()),
+            _ => Err(SyncError::App("Git仓库还没有任何提交".to_string())),
+        }
+    }
+
+    fn tree_blobs(
+        &self,
+        _path: &Path,
+        _rev: &str,
+    ) -> std::result::Result<BTreeMap<String, String>, SyncError> {
+        Err(SyncError::App("测试实现不支持读取提交文件列表".to_string()))
+    }
+
+    fn hash_files(
+        &self,
+        _path: &Path,
+        _files: &[PathBuf],
+    ) -> std::result::Result<Vec<String>, SyncError> {
+        Err(SyncError::App("测试实现不支持计算文件哈希".to_string()))
+    }
+
+    fn add_paths(&self, _path: &Path, _files: &[String]) -> std::result::Result<(), SyncError> {
+        Err(SyncError::App("测试实现不支持按路径暂存".to_string()))
+    }
+
+    fn set_executable(
+        &self,
+        _path: &Path,
+        _files: &[String],
+    ) -> std::result::Result<(), SyncError> {
+        Err(SyncError::App("测试实现不支持设置可执行位".to_string()))
+    }
+
+    fn add_note(
+        &self,
+        _path: &Path,
+        _notes_ref: &str,
+        _commit: &str,
+        _note: &str,
+    ) -> std::result::Result<(), SyncError> {
+        Err(SyncError::App("测试实现不支持Git notes".to_string()))
+    }
+
+    fn reset_to(&self, _path: &Path, _commit: Option<&str>) -> std::result::Result<(), SyncError> {
+        Err(SyncError::App("测试实现不支持Git reset".to_string()))
+    }
+
+    fn stash(&self, _path: &Path, _message: &str) -> std::result::Result<bool, SyncError> {
+        Err(SyncError::App("测试实现不支持Git stash".to_string()))
+    }
+
+    fn stash_pop(&self, _path: &Path) -> std::result::Result<(), SyncError> {
+        Err(SyncError::App("测试实现不支持Git stash".to_string()))
+    }
+
+    fn create_branch(
+        &self,
+        _path: &Path,
+        _name: &str,
+        _start: Option<&str>,
+    ) -> std::result::Result<(), SyncError> {
+        Err(SyncError::App("测试实现不支持创建分支".to_string()))
+    }
+
+    fn checkout_branch(&self, _path: &Path, _name: &str) -> std::result::Result<(), SyncError> {
+        Err(SyncError::App("测试实现不支持切换分支".to_string()))
+    }
+
+    fn commit_with(
+        &self,
+        _path: &Path,
+        _message: &str,
+        _metadata: &CommitMetadata,
+    ) -> std::result::Result<(), SyncError> {
+        Err(SyncError::App("测试实现不支持指定作者和时间".to_string()))
+    }
+
+    fn tag(
+        &self,
+        _path: &Path,
+        _name: &str,
+        _target: &str,
+        _message: &str,
+        _tagger: Option<&GitSignature>,
+    ) -> std::result::Result<(), SyncError> {
+        Err(SyncError::App("测试实现不支持创建标签".to_string()))
+    }
+
+    fn add_remote(
+        &self,
+        _path: &Path,
+        _name: &str,
+        _url: &str,
+    ) -> std::result::Result<(), SyncError> {
+        Err(SyncError::App("测试实现不支持远程仓库".to_string()))
+    }
+
+    fn push(&self, _path: &Path, _remote: &str) -> std::result::Result<(), SyncError> {
+        Err(SyncError::App("测试实现不支持推送".to_string()))
+    }
+
+    fn bundle(&self, _path: &Path, _file: &Path) -> std::result::Result<(), SyncError> {
+        Err(SyncError::App("测试实现不支持 git bundle".to_string()))
+    }
+
+    fn write_blob_ref(
+        &self,
+        _path: &Path,
+        _reference: &str,
+        _content: &str,
+    ) -> std::result::Result<(), SyncError> {
+        Ok(())
+    }
+
+    fn read_blob_ref(
+        &self,
+        _path: &Path,
+        _reference: &str,
+    ) -> std::result::Result<Option<String>, SyncError> {
+        Ok(None)
+    }
 }
 
 /// 测试：Mock Git状态查询功能应该返回仓库信息
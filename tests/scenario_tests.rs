@@ -0,0 +1,378 @@
+//! 端到端同步场景
+//!
+//! 每个场景都完整运行 [`SyncTool`]：SVN 和 Git 使用内存 Mock（SVN 更新的文件通过
+//! [`MockSvnOperations::link_git`] 出现在 Git 工作目录中），用户的回答由脚本预先给出，
+//! 同步进度保存在临时目录的配置文件中。场景既是回归测试，也是各种情况下同步行为的说明。
+
+use std::path::PathBuf;
+
+use svn2git::{
+    DiskStorage, GitOperations, HistoryManager, HistoryRecord, MockGitOperations,
+    MockSvnOperations, SvnChangedPath, SvnLog, SvnOperations, SyncConfig, SyncError,
+    SyncRunOptions, SyncTool,
+    test_utils::{ScriptedAnswer, ScriptedUserInteractor},
+};
+use tempfile::TempDir;
+
+/// 一个同步对的测试环境
+struct Scenario {
+    dir: TempDir,
+    svn: MockSvnOperations,
+    git: MockGitOperations,
+}
+
+impl Scenario {
+    /// 创建同步对：SVN 仓库有 r1 - r3 三个修改了文件的版本，历史记录中已登记该同步对
+    ///
+    /// # 参数
+    ///
+    /// * `git_initialized` - Git 仓库是否已经初始化
+    fn new(git_initialized: bool) -> Self {
+        let scenario = Self {
+            dir: tempfile::tempdir().unwrap(),
+            svn: MockSvnOperations::new(),
+            git: MockGitOperations::new(),
+        };
+        for (rev, message, file) in [
+            ("1", "添加 README", "README.md"),
+            ("2", "实现登录", "src/login.c"),
+            ("3", "修复登录超时", "src/login.c"),
+        ] {
+            scenario.add_log(rev, message, &[file]);
+        }
+        if git_initialized {
+            std::fs::create_dir_all(scenario.git_dir()).unwrap();
+            scenario.git.init(&scenario.git_dir()).unwrap();
+        }
+        scenario
+            .svn
+            .link_git(scenario.git.clone(), &scenario.git_dir());
+
+        let mut history = scenario.history();
+        history.add_record(scenario.svn_dir(), scenario.git_dir());
+        history.save().unwrap();
+        scenario
+    }
+
+    /// 向 SVN 仓库添加一个版本，`files` 为空时该版本在 Git 中没有更改
+    fn add_log(&self, rev: &str, message: &str, files: &[&str]) {
+        self.svn.add_svn_log_to_mock(
+            &self.svn_dir(),
+            SvnLog {
+                version: rev.into(),
+                message: message.into(),
+                paths: files
+                    .iter()
+                    .map(|file| SvnChangedPath {
+                        action: "M".into(),
+                        path: format!("/{file}"),
+                        copy_from: None,
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+        );
+    }
+
+    fn svn_dir(&self) -> PathBuf {
+        self.dir.path().join("svn")
+    }
+
+    fn git_dir(&self) -> PathBuf {
+        self.dir.path().join("git")
+    }
+
+    fn history(&self) -> HistoryManager<DiskStorage> {
+        HistoryManager::new(DiskStorage::new(self.dir.path().join("config.json"))).unwrap()
+    }
+
+    /// 重新从配置文件读取的同步对记录
+    fn record(&self) -> HistoryRecord {
+        self.history()
+            .find_record(&self.svn_dir(), &self.git_dir())
+            .unwrap()
+            .clone()
+    }
+
+    /// 按脚本回答创建同步工具
+    fn tool(
+        &self,
+        config: SyncConfig,
+        interactor: &ScriptedUserInteractor,
+    ) -> SyncTool<DiskStorage> {
+        SyncTool::with_svn_operations(
+            config,
+            self.history(),
+            Box::new(interactor.clone()),
+            Box::new(self.git.clone()),
+            Box::new(self.svn.clone()),
+        )
+    }
+
+    fn config(&self) -> SyncConfig {
+        SyncConfig::new(self.svn_dir(), self.git_dir())
+    }
+
+    /// Git 仓库中的提交说明（按提交顺序）
+    fn commit_messages(&self) -> Vec<String> {
+        self.git
+            .get_repo_state(&self.git_dir())
+            .map(|repo| {
+                repo.get_commits()
+                    .iter()
+                    .map(|c| c.message.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// 新的同步对：Git 目录还不是仓库时询问是否初始化，确认后依次同步所有版本
+#[test]
+fn test_scenario_fresh_pair() {
+    let scenario = Scenario::new(false);
+    let interactor = ScriptedUserInteractor::new([
+        ScriptedAnswer::ConfirmGitInit(true),
+        ScriptedAnswer::ConfirmSync(true),
+    ]);
+
+    scenario.tool(scenario.config(), &interactor).run().unwrap();
+
+    assert_eq!(interactor.asked(), ["confirm_git_init", "confirm_sync"]);
+    assert_eq!(
+        scenario.commit_messages(),
+        ["SVN: 添加 README", "SVN: 实现登录", "SVN: 修复登录超时"]
+    );
+    let record = scenario.record();
+    assert_eq!(record.last_synced_rev(), Some("3"));
+    assert_eq!(record.revision_map().len(), 3);
+    assert!(!record.has_checkpoint());
+}
+
+/// 用户拒绝同步时不更新工作副本，也不提交
+#[test]
+fn test_scenario_user_declines_sync() {
+    let scenario = Scenario::new(true);
+    let interactor = ScriptedUserInteractor::new([ScriptedAnswer::ConfirmSync(false)]);
+
+    scenario.tool(scenario.config(), &interactor).run().unwrap();
+
+    assert!(interactor.is_finished());
+    assert!(scenario.commit_messages().is_empty());
+    assert!(scenario.svn.updated_revisions().is_empty());
+    assert_eq!(scenario.record().last_synced_rev(), None);
+}
+
+/// 同步中途失败后保留检查点，问题修复后 resume 只同步剩余的版本，不再询问
+#[test]
+fn test_scenario_resume_after_failure() {
+    let scenario = Scenario::new(true);
+    scenario.svn.fail_update_at("2");
+    let interactor = ScriptedUserInteractor::new([ScriptedAnswer::ConfirmSync(true)]);
+
+    let result = scenario.tool(scenario.config(), &interactor).run();
+
+    assert!(result.unwrap_err().to_string().contains("SVN r2"));
+    assert_eq!(scenario.commit_messages(), ["SVN: 添加 README"]);
+    let record = scenario.record();
+    assert_eq!(record.last_synced_rev(), Some("1"));
+    assert_eq!(
+        record.checkpoint().unwrap().pending(),
+        ["2".to_string(), "3".to_string()]
+    );
+
+    scenario.svn.clear_update_failure("2");
+    let interactor = ScriptedUserInteractor::new([]);
+    scenario
+        .tool(record.to_sync_config(), &interactor)
+        .resume()
+        .unwrap();
+
+    assert!(interactor.asked().is_empty());
+    assert_eq!(
+        scenario.commit_messages(),
+        ["SVN: 添加 README", "SVN: 实现登录", "SVN: 修复登录超时"]
+    );
+    let record = scenario.record();
+    assert_eq!(record.last_synced_rev(), Some("3"));
+    assert!(!record.has_checkpoint());
+}
+
+/// 更新产生冲突时清除工作副本中的冲突并停止同步，冲突之前的版本保留
+#[test]
+fn test_scenario_conflict_aborts_sync() {
+    let scenario = Scenario::new(true);
+    scenario.svn.inject_conflict("2", &["src/login.c"]);
+    let interactor = ScriptedUserInteractor::new([ScriptedAnswer::ConfirmSync(true)]);
+
+    let result = scenario.tool(scenario.config(), &interactor).run();
+
+    match result {
+        Err(SyncError::SvnConflict { rev, paths }) => {
+            assert_eq!(rev, "2");
+            assert_eq!(paths, ["src/login.c"]);
+        }
+        other => panic!("应返回冲突错误：{other:?}"),
+    }
+    assert_eq!(scenario.commit_messages(), ["SVN: 添加 README"]);
+    assert!(
+        scenario
+            .svn
+            .modified_paths(&scenario.svn_dir())
+            .unwrap()
+            .is_empty()
+    );
+    let record = scenario.record();
+    assert_eq!(record.last_synced_rev(), Some("1"));
+    assert!(record.has_checkpoint());
+}
+
+/// 某个版本更新成功但提交失败时，Git 和 SVN 工作副本都回到上一个已同步的版本
+#[test]
+fn test_scenario_failed_commit_reverts_working_copy() {
+    let scenario = Scenario::new(true);
+    scenario.add_log("4", "只修改了属性", &[]);
+    let interactor = ScriptedUserInteractor::new([ScriptedAnswer::ConfirmSync(true)]);
+
+    let result = scenario.tool(scenario.config(), &interactor).run();
+
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("SVN r4 没有产生任何 Git 更改"), "{error}");
+    assert!(error.contains("SVN 工作副本已恢复到 r3"), "{error}");
+    assert_eq!(
+        scenario.svn.info(&scenario.svn_dir()).unwrap().revision,
+        "3"
+    );
+    assert_eq!(scenario.commit_messages().len(), 3);
+    assert_eq!(scenario.record().last_synced_rev(), Some("3"));
+}
+
+/// 错误策略为 continue 时，某个版本提交失败后回滚该版本并继续，结束时汇总失败的版本
+#[test]
+fn test_scenario_keep_going_after_failed_revision() {
+    let scenario = Scenario::new(true);
+    // r4 没有修改任何文件，默认的空版本策略按提交失败处理
+    scenario.add_log("4", "只修改了属性", &[]);
+    scenario.add_log("5", "更新文档", &["README.md"]);
+    let mut config = scenario.config();
+    config.policies.set("error", "continue").unwrap();
+    let interactor = ScriptedUserInteractor::new([ScriptedAnswer::ConfirmSync(true)]);
+
+    let result = scenario.tool(config, &interactor).run();
+
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("以下 SVN 版本同步失败：r4"), "{error}");
+    assert_eq!(
+        scenario.commit_messages(),
+        [
+            "SVN: 添加 README",
+            "SVN: 实现登录",
+            "SVN: 修复登录超时",
+            "SVN: 更新文档"
+        ]
+    );
+    assert_eq!(scenario.record().last_synced_rev(), Some("5"));
+}
+
+/// 空版本策略为 allow-empty 时，没有产生更改的版本也生成提交，Git 历史与 SVN 版本一一对应
+#[test]
+fn test_scenario_allow_empty_revision() {
+    let scenario = Scenario::new(true);
+    scenario.add_log("4", "只修改了属性", &[]);
+    let mut config = scenario.config();
+    config
+        .policies
+        .set("empty-revision", "allow-empty")
+        .unwrap();
+    let interactor = ScriptedUserInteractor::new([ScriptedAnswer::ConfirmSync(true)]);
+
+    scenario.tool(config, &interactor).run().unwrap();
+
+    assert_eq!(scenario.commit_messages().len(), 4);
+    assert_eq!(scenario.commit_messages()[3], "SVN: 只修改了属性");
+    let record = scenario.record();
+    assert_eq!(record.last_synced_rev(), Some("4"));
+    assert_eq!(record.revision_map().len(), 4);
+}
+
+/// 继续策略下最后一个版本提交失败后，修改策略用 retry 只重试该版本；
+/// 失败版本的更改随后续版本提交后清除失败记录，不再需要重试
+#[test]
+fn test_scenario_retry_failed_revision() {
+    let scenario = Scenario::new(true);
+    scenario.add_log("4", "只修改了属性", &[]);
+    let config = |empty_revision: &str| {
+        let mut config = scenario.config();
+        config.policies.set("error", "continue").unwrap();
+        config
+            .policies
+            .set("empty-revision", empty_revision)
+            .unwrap();
+        config
+    };
+    let interactor = ScriptedUserInteractor::new([ScriptedAnswer::ConfirmSync(true)]);
+    assert!(scenario.tool(config("fail"), &interactor).run().is_err());
+    assert_eq!(scenario.record().failed_revisions(), ["4"]);
+
+    let interactor = ScriptedUserInteractor::new([]);
+    let err = scenario.tool(config("fail"), &interactor).retry("2");
+    assert!(
+        err.unwrap_err()
+            .to_string()
+            .contains("r2 没有同步失败的记录")
+    );
+
+    scenario
+        .tool(config("allow-empty"), &interactor)
+        .retry("4")
+        .unwrap();
+
+    assert!(interactor.asked().is_empty());
+    assert_eq!(scenario.commit_messages().len(), 4);
+    let record = scenario.record();
+    assert_eq!(record.last_synced_rev(), Some("4"));
+    assert!(record.failed_revisions().is_empty());
+
+    // r5 失败后 r6 同步成功，r5 的更改已随 r6 提交
+    scenario.add_log("5", "只修改了属性", &[]);
+    scenario.add_log("6", "更新文档", &["README.md"]);
+    let interactor = ScriptedUserInteractor::new([ScriptedAnswer::ConfirmSync(true)]);
+    assert!(scenario.tool(config("fail"), &interactor).run().is_err());
+    let record = scenario.record();
+    assert!(record.failed_revisions().is_empty());
+    assert_eq!(
+        record.revision_map().git_hash("5"),
+        record.revision_map().git_hash("6")
+    );
+    let err = scenario
+        .tool(config("fail"), &interactor)
+        .retry("5")
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("r5 的更改已随后续版本 r6 提交"),
+        "{err}"
+    );
+}
+
+/// dry-run 只预览：不询问、不更新工作副本、不提交，也不记录进度
+#[test]
+fn test_scenario_dry_run() {
+    let scenario = Scenario::new(true);
+    let interactor = ScriptedUserInteractor::new([]);
+
+    scenario
+        .tool(scenario.config(), &interactor)
+        .run_with_options(&SyncRunOptions {
+            dry_run: true,
+            ..Default::default()
+        })
+        .unwrap();
+
+    assert!(interactor.asked().is_empty());
+    assert!(scenario.svn.updated_revisions().is_empty());
+    assert!(scenario.commit_messages().is_empty());
+    let record = scenario.record();
+    assert_eq!(record.last_synced_rev(), None);
+    assert!(!record.has_checkpoint());
+}
@@ -0,0 +1,200 @@
+//! 命令行输出的快照测试
+//!
+//! 下游脚本会解析 `history list`、同步汇总、同步计划和错误信息，
+//! 输出格式的任何变化都会使快照不一致，需要用 `cargo insta review` 确认后再提交
+
+use std::{path::PathBuf, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use svn2git::{
+    BackoffPolicy, FixedClock, HistoryManager, MemoryStorage, PairStatus, PlannedRevision,
+    ProgressFile, SparseScope, SvnDepth, SyncCheckpoint, SyncConfig, SyncError, SyncPlan,
+    SyncProgress, sync_pairs,
+};
+
+/// 列表和状态中的时间按本地时区显示，替换后快照与运行环境的时区无关
+const LOCAL_TIME: (&str, &str) = (r"\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}", "[本地时间]");
+
+fn fixed_time() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2024-05-01T08:00:00Z")
+        .unwrap()
+        .to_utc()
+}
+
+fn create_history() -> HistoryManager<MemoryStorage> {
+    let mut history = HistoryManager::new(MemoryStorage::new()).unwrap();
+    history.set_clock(Arc::new(FixedClock::new(fixed_time())));
+    history
+}
+
+fn plan(revisions: &[(&str, bool)]) -> SyncPlan {
+    SyncPlan::new_at(
+        revisions
+            .iter()
+            .map(|(rev, skipped)| PlannedRevision {
+                rev: rev.to_string(),
+                skipped: *skipped,
+            })
+            .collect(),
+        fixed_time(),
+    )
+}
+
+#[test]
+fn test_history_list_snapshot() {
+    let mut history = create_history();
+    insta::assert_snapshot!("history_list_empty", history.render_list());
+
+    let (svn1, git1) = (PathBuf::from("d:/svn/app"), PathBuf::from("d:/git/app"));
+    let (svn2, git2) = (PathBuf::from("d:/svn/lib"), PathBuf::from("d:/git/lib"));
+    history.add_record(svn1.clone(), git1.clone());
+    history.add_record(svn2.clone(), git2.clone());
+    history.set_checkpoint(
+        &svn1,
+        &git1,
+        Some(SyncCheckpoint::new_at(
+            vec!["8".into(), "9".into()],
+            fixed_time(),
+        )),
+    );
+    let policy = BackoffPolicy {
+        unhealthy_threshold: 1,
+        ..Default::default()
+    };
+    history.record_failure(&svn2, &git2, "网络错误", &policy);
+    history.record_run(&svn1, &git1, &Ok(3), 3, 12);
+    history.record_run(
+        &svn2,
+        &git2,
+        &Err(SyncError::GitCommand {
+            action: "git push".into(),
+            program: "git".into(),
+            args: "push origin".into(),
+            exit_code: Some(128),
+            stderr: "fatal: unable to access".into(),
+        }),
+        0,
+        4,
+    );
+
+    insta::with_settings!({ filters => vec![LOCAL_TIME] }, {
+        insta::assert_snapshot!("history_list", history.render_list());
+    });
+}
+
+#[test]
+fn test_history_records_json_snapshot() {
+    let mut history = create_history();
+    let (svn, git) = (PathBuf::from("d:/svn/app"), PathBuf::from("d:/git/app"));
+    history.add_record(svn.clone(), git.clone());
+    history.update_sync_state(&svn, &git, "7", "0123456789abcdef");
+    history.set_last_plan(&svn, &git, plan(&[("8", false), ("9", true)]));
+
+    insta::assert_json_snapshot!("history_records_json", history.records());
+}
+
+#[test]
+fn test_plan_diff_snapshot() {
+    let previous = plan(&[("5", false), ("6", false), ("7", true), ("8", false)]);
+    let current = plan(&[("6", true), ("7", false), ("9", false), ("10", false)]);
+
+    insta::assert_snapshot!("plan_diff", current.diff(&previous, Some("5")).to_string());
+    insta::assert_json_snapshot!("plan_json", current);
+}
+
+#[test]
+fn test_batch_summary_snapshot() {
+    let configs = ["app", "第三方库"]
+        .iter()
+        .map(|name| {
+            SyncConfig::new(
+                PathBuf::from(format!("d:/svn/{name}")),
+                PathBuf::from(format!("d:/git/{name}")),
+            )
+        })
+        .collect();
+    let summary = sync_pairs(configs, |config| {
+        if config.git_dir.ends_with("app") {
+            Ok(())
+        } else {
+            Err(SyncError::App("svn update 失败\n详细输出".into()))
+        }
+    });
+
+    insta::assert_snapshot!("batch_summary", summary.to_string());
+}
+
+#[test]
+fn test_pair_status_snapshot() {
+    let status = PairStatus {
+        svn_dir: PathBuf::from("d:/svn/app"),
+        git_dir: PathBuf::from("d:/git/app"),
+        svn_revision: "7".into(),
+        pending_revisions: vec!["8".into(), "9".into(), "12".into()],
+        svn_modified: vec!["src/main.c".into()],
+        git_clean: false,
+        git_last_commit: Some("0123456 SVN: 修复登录".into()),
+        git_head_moved: true,
+        last_synced_rev: Some("7".into()),
+        last_synced_at: Some(fixed_time()),
+        sparse_scope: Some(SparseScope::new([
+            (String::new(), SvnDepth::Files),
+            ("src".into(), SvnDepth::Infinity),
+        ])),
+    };
+
+    insta::with_settings!({ filters => vec![LOCAL_TIME] }, {
+        insta::assert_snapshot!("pair_status", status.to_string());
+    });
+}
+
+#[test]
+fn test_error_rendering_snapshot() {
+    let errors = [
+        SyncError::App("同步第 2 条日志失败（SVN r8）：svn update 失败".into()),
+        SyncError::NotSvnWorkingCopy {
+            path: PathBuf::from("d:/svn/app"),
+            reason: "缺少 .svn 目录".into(),
+        },
+        SyncError::NotGitRepository(PathBuf::from("d:/git/app")),
+        SyncError::DirtyWorkingTree(PathBuf::from("d:/git/app")),
+        SyncError::SvnConflict {
+            rev: "9".into(),
+            paths: vec!["src/a.c".into(), "src/b.c".into()],
+        },
+        SyncError::SvnCommand {
+            action: "svn log".into(),
+            args: "log --xml -r 8:HEAD d:/svn/app".into(),
+            exit_code: Some(1),
+            stderr: "svn: E170001: Authentication failed".into(),
+        },
+        SyncError::GitCommand {
+            action: "Git推送".into(),
+            program: "git".into(),
+            args: "push origin".into(),
+            exit_code: Some(128),
+            stderr: String::new(),
+        },
+        SyncError::CommandNotFound("svn".into()),
+    ];
+    let rendered: Vec<String> = errors.iter().map(ToString::to_string).collect();
+    insta::assert_snapshot!("errors", rendered.join("\n"));
+
+    // 进度文件中的错误是外部监控程序读取的 JSON 形式
+    let clock = FixedClock::new(fixed_time());
+    let mut progress = ProgressFile::new(None);
+    progress.set_clock(Arc::new(clock.clone()));
+    progress.start(3);
+    progress.revision(0, "8");
+    clock.advance(chrono::Duration::seconds(30));
+    progress.revision(1, "9");
+    progress.finish(&Err(SyncError::SvnConflict {
+        rev: "9".into(),
+        paths: vec!["src/a.c".into()],
+    }));
+    let json = SyncProgress {
+        pid: 0,
+        ..progress.progress().clone()
+    };
+    insta::assert_json_snapshot!("error_progress_json", json);
+}